@@ -0,0 +1,104 @@
+//! Compiled units written by `rune build`, which can be run without the
+//! scripts they were compiled from.
+//!
+//! An artifact starts with a magic header and a format version, followed by
+//! the unit and any embedded sources encoded as MessagePack.
+
+use anyhow::{bail, Context as _, Result};
+use runestick::{Source, Unit};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// The file extension used for artifacts.
+pub(crate) const EXTENSION: &str = "rnc";
+
+/// The magic header of an artifact.
+const MAGIC: &[u8; 8] = b"RUNEUNIT";
+
+/// The version of the artifact format, which is bumped whenever the unit
+/// format changes.
+const VERSION: u32 = 10;
+
+#[derive(Serialize)]
+struct ArtifactRef<'a> {
+    unit: &'a Unit,
+    sources: Vec<EmbeddedSource>,
+}
+
+#[derive(Deserialize)]
+struct Artifact {
+    unit: Unit,
+    sources: Vec<EmbeddedSource>,
+}
+
+/// A source embedded in an artifact, used to provide diagnostics.
+#[derive(Serialize, Deserialize)]
+struct EmbeddedSource {
+    name: String,
+    source: String,
+}
+
+/// Test if the given path refers to an artifact.
+pub(crate) fn is_artifact(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| ext == EXTENSION)
+}
+
+/// Write the given unit as an artifact to the given path, embedding the
+/// given sources if specified.
+pub(crate) fn write(path: &Path, unit: &Unit, sources: Option<&rune::Sources>) -> Result<()> {
+    let sources = match sources {
+        Some(sources) => sources
+            .iter()
+            .map(|source| EmbeddedSource {
+                name: source.name().to_owned(),
+                source: source.as_str().to_owned(),
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&VERSION.to_le_bytes());
+    rmp_serde::encode::write(&mut out, &ArtifactRef { unit, sources })?;
+
+    fs::write(path, out).with_context(|| format!("failed to write `{}`", path.display()))?;
+    Ok(())
+}
+
+/// Read the artifact at the given path, returning its unit and the sources
+/// embedded in it.
+pub(crate) fn read(path: &Path) -> Result<(Unit, rune::Sources)> {
+    let data = fs::read(path).with_context(|| format!("failed to read `{}`", path.display()))?;
+
+    let header = MAGIC.len() + 4;
+
+    if data.len() < header || &data[..MAGIC.len()] != MAGIC {
+        bail!("`{}` is not a compiled unit", path.display());
+    }
+
+    let mut version = [0u8; 4];
+    version.copy_from_slice(&data[MAGIC.len()..header]);
+    let version = u32::from_le_bytes(version);
+
+    if version != VERSION {
+        bail!(
+            "`{}` was built with an unsupported format version {} (expected {}), rebuild it",
+            path.display(),
+            version,
+            VERSION
+        );
+    }
+
+    let artifact: Artifact = rmp_serde::from_slice(&data[header..])
+        .with_context(|| format!("failed to decode `{}`", path.display()))?;
+
+    let mut sources = rune::Sources::new();
+
+    for source in artifact.sources {
+        sources.insert_default(Source::new(source.name, source.source));
+    }
+
+    Ok((artifact.unit, sources))
+}
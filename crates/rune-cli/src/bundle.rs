@@ -0,0 +1,143 @@
+//! Bundles written by `rune bundle`, which are Rust projects embedding a
+//! compiled unit together with a small runner for it.
+//!
+//! Building a bundle with `cargo build --release` produces a single binary
+//! which runs the script, without needing the cli or the script itself.
+
+use anyhow::{Context as _, Result};
+use runestick::Unit;
+use std::fs;
+use std::path::Path;
+
+/// The version of runestick and rune-modules the bundle depends on, which
+/// must be the ones the unit was compiled with.
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The name of the file the unit is embedded from.
+const UNIT_FILE: &str = "main.unit";
+
+/// Write a bundle for the given unit into the given directory.
+///
+/// The bundle installs the given native modules, or all of them if `None`,
+/// which must be the same ones the unit was compiled against.
+pub(crate) fn write(dir: &Path, name: &str, unit: &Unit, modules: Option<&[String]>) -> Result<()> {
+    let src = dir.join("src");
+
+    fs::create_dir_all(&src)
+        .with_context(|| format!("failed to create directory `{}`", src.display()))?;
+
+    let modules = match modules {
+        Some(modules) => modules.iter().map(String::as_str).collect::<Vec<_>>(),
+        None => crate::manifest::MODULES.to_vec(),
+    };
+
+    let unit = rmp_serde::to_vec(unit)?;
+
+    write_file(&dir.join("Cargo.toml"), &cargo_toml(name, &modules))?;
+    write_file(&src.join("main.rs"), &main_rs(name, &modules))?;
+    write_file(&src.join(UNIT_FILE), &unit)?;
+    Ok(())
+}
+
+fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    fs::write(path, contents).with_context(|| format!("failed to write `{}`", path.display()))
+}
+
+/// Generate the manifest of the bundle.
+fn cargo_toml(name: &str, modules: &[&str]) -> String {
+    let mut out = String::new();
+
+    out.push_str("[package]\n");
+    out.push_str(&format!("name = {:?}\n", package_name(name)));
+    out.push_str("version = \"0.0.0\"\n");
+    out.push_str("edition = \"2018\"\n");
+    out.push_str("publish = false\n");
+    out.push('\n');
+    out.push_str("[dependencies]\n");
+    out.push_str("tokio = {version = \"0.2.22\", features = [\"rt-core\", \"macros\"]}\n");
+    out.push_str("rmp-serde = \"1.1.1\"\n");
+    out.push_str(&format!("runestick = \"{}\"\n", VERSION));
+
+    if !modules.is_empty() {
+        let features = modules
+            .iter()
+            .map(|module| format!("{:?}", module))
+            .collect::<Vec<_>>();
+
+        out.push_str(&format!(
+            "rune-modules = {{version = \"{}\", features = [{}]}}\n",
+            VERSION,
+            features.join(", ")
+        ));
+    }
+
+    // NB: keeps the bundle from being picked up by a workspace it's written
+    // into.
+    out.push('\n');
+    out.push_str("[workspace]\n");
+    out
+}
+
+/// Generate the runner of the bundle.
+fn main_rs(name: &str, modules: &[&str]) -> String {
+    let mut installs = String::new();
+
+    for module in modules {
+        installs.push_str(&format!(
+            "    context.install(&rune_modules::{}::module()?)?;\n",
+            module
+        ));
+    }
+
+    format!(
+        r#"//! Runs the compiled unit of `{name}`.
+//!
+//! This file was generated by `rune bundle`.
+
+use runestick::{{Hash, Item, Unit, UnitFn, Vm}};
+use std::sync::Arc;
+
+static UNIT: &[u8] = include_bytes!("{unit_file}");
+
+#[tokio::main]
+async fn main() -> runestick::Result<()> {{
+    let args = std::env::args().collect::<Vec<_>>();
+
+    let mut context = runestick::Context::with_default_modules()?;
+    context.install(&runestick::modules::env::module(args.clone())?)?;
+{installs}
+    let unit: Unit = rmp_serde::from_slice(UNIT)?;
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let main = Item::of(&["main"]);
+
+    let mut execution = match vm.unit().lookup(Hash::type_hash(&main)) {{
+        Some(UnitFn::Offset {{ args: 1, .. }}) => vm.call(main, (args,))?,
+        _ => vm.call(main, ())?,
+    }};
+
+    if let Err(error) = execution.async_complete().await {{
+        eprintln!("error: {{}}", error);
+        std::process::exit(1);
+    }}
+
+    Ok(())
+}}
+"#,
+        name = name,
+        unit_file = UNIT_FILE,
+        installs = installs,
+    )
+}
+
+/// Turn the given name into a valid package name.
+fn package_name(name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect::<String>();
+
+    match name.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() => name,
+        _ => format!("rune-{}", name),
+    }
+}
@@ -36,6 +36,36 @@
 //! cargo run -- scripts/hello_world.rn
 //! ```
 //!
+//! Scripts can also be checked for errors without running them, which is
+//! suitable for editor save-hooks and CI:
+//!
+//! ```text
+//! cargo run -- check scripts
+//! ```
+//!
+//! Scripts can be compiled ahead of time into a unit, which can be run without
+//! its sources:
+//!
+//! ```text
+//! cargo run -- build scripts/hello_world.rn -o hello_world.rnc
+//! cargo run -- hello_world.rnc
+//! ```
+//!
+//! Items declared in a script can be renamed, which updates every reference to
+//! them in the script and the modules it loads:
+//!
+//! ```text
+//! cargo run -- rename scripts/hello_world.rn main entry
+//! ```
+//!
+//! Which restricted capabilities, like filesystem or network access, a script
+//! can reach is reported without running it. Denied capabilities make the
+//! command fail:
+//!
+//! ```text
+//! cargo run -- capabilities --deny net scripts/hello_world.rn
+//! ```
+//!
 //! [Rune Language]: https://github.com/rune-rs/rune
 //! [runestick]: https://github.com/rune-rs/rune
 
@@ -43,45 +73,98 @@ use anyhow::{bail, Result};
 use rune::termcolor::{ColorChoice, StandardStream};
 use rune::EmitDiagnostics as _;
 use std::env;
-use std::path::PathBuf;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
-use runestick::{Item, Value, VmExecution};
+use runestick::{Hash, Item, Value, VmExecution};
+
+mod artifact;
+mod bundle;
+mod manifest;
+mod trace;
+
+use self::manifest::Manifest;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
 
-    let mut args = env::args();
+    let mut args = env::args().peekable();
     args.next();
 
+    match args.peek().map(String::as_str) {
+        Some("check") => {
+            args.next();
+            return check(args);
+        }
+        Some("build") => {
+            args.next();
+            return build(args);
+        }
+        Some("bundle") => {
+            args.next();
+            return bundle(args);
+        }
+        Some("rename") => {
+            args.next();
+            return rename(args);
+        }
+        Some("capabilities") => {
+            args.next();
+            return capabilities(args);
+        }
+        Some("run") => {
+            args.next();
+        }
+        _ => (),
+    }
+
     let mut path = None;
-    let mut trace = false;
-    let mut dump_unit = false;
-    let mut dump_stack = false;
+    let mut flags = Flags::default();
+    let mut watch = false;
     let mut dump_functions = false;
     let mut dump_types = false;
     let mut help = false;
+    let mut cli_options = Vec::new();
 
-    let mut options = rune::Options::default();
+    let mut script_args = Vec::new();
 
     while let Some(arg) = args.next() {
         match arg.as_str() {
-            "--" => continue,
+            "--" => {
+                script_args.extend(args.by_ref());
+                break;
+            }
             "--trace" => {
-                trace = true;
+                flags.trace = true;
+            }
+            "--trace-json" => match args.next() {
+                Some(path) => {
+                    flags.trace_json = Some(PathBuf::from(path));
+                }
+                None => {
+                    println!("expected output path to `--trace-json`");
+                    return Ok(());
+                }
+            },
+            "--watch" => {
+                watch = true;
             }
             "--dump" => {
-                dump_unit = true;
-                dump_stack = true;
+                flags.dump_unit = true;
+                flags.dump_stack = true;
                 dump_functions = true;
                 dump_types = true;
             }
             "--dump-unit" => {
-                dump_unit = true;
+                flags.dump_unit = true;
             }
             "--dump-stack" => {
-                dump_stack = true;
+                flags.dump_stack = true;
             }
             "--dump-functions" => {
                 dump_functions = true;
@@ -89,6 +172,38 @@ async fn main() -> Result<()> {
             "--dump-types" => {
                 dump_types = true;
             }
+            "--coverage" => match args.next() {
+                Some(path) => {
+                    flags.coverage = Some(PathBuf::from(path));
+                    cli_options.push(String::from("coverage"));
+                }
+                None => {
+                    println!("expected output path to `--coverage`");
+                    return Ok(());
+                }
+            },
+            "--stack-limit" => match args.next().map(|limit| limit.parse()) {
+                Some(Ok(limit)) => {
+                    flags.stack_limit = Some(limit);
+                }
+                _ => {
+                    println!("expected number of values to `--stack-limit`");
+                    return Ok(());
+                }
+            },
+            "--integer-overflow" => match args.next().map(|mode| mode.parse()) {
+                Some(Ok(mode)) => {
+                    flags.integer_overflow = mode;
+                }
+                Some(Err(error)) => {
+                    println!("bad mode to `--integer-overflow`: {}", error);
+                    return Ok(());
+                }
+                None => {
+                    println!("expected mode to `--integer-overflow`");
+                    return Ok(());
+                }
+            },
             "-O" => {
                 let opt = match args.next() {
                     Some(opt) => opt,
@@ -98,7 +213,7 @@ async fn main() -> Result<()> {
                     }
                 };
 
-                options.parse_option(&opt)?;
+                cli_options.push(opt);
             }
             "--help" | "-h" => {
                 help = true;
@@ -113,19 +228,38 @@ async fn main() -> Result<()> {
         }
     }
 
-    const USAGE: &str = "rune-cli [--trace] <file>";
+    const USAGE: &str = "rune-cli [run] [--trace] [--watch] <file> [-- <args>...]";
 
     if help {
         println!("Usage: {}", USAGE);
+        println!("       {}", CHECK_USAGE);
+        println!("       {}", BUILD_USAGE);
+        println!("       {}", BUNDLE_USAGE);
+        println!("       {}", RENAME_USAGE);
+        println!("       {}", CAPABILITIES_USAGE);
+        println!();
+        println!("Arguments after `--` are passed to the script, through `std::env::args()` and");
+        println!("to its `main` function if it takes a parameter.");
         println!();
         println!("  --help, -h         - Show this help.");
         println!("  --trace           - Provide detailed tracing for each instruction executed.");
+        println!("  --trace-json <file> - Write a structured trace of each instruction executed to the given file, as JSON lines.");
+        println!(
+            "  --watch           - Watch the script for changes, and re-run it when it changes."
+        );
         println!("  --dump            - Dump all forms of diagnostic.");
         println!("  --dump-unit       - Dump diagnostics on the unit generated from the file.");
         println!("  --dump-stack      - Dump the state of the stack after completion. If compiled with `--trace` will dump it after each instruction.");
         println!("  --dump-functions  - Dump available functions.");
         println!("  --dump-types      - Dump available types.");
         println!("  --no-linking      - Disable link time checks.");
+        println!(
+            "  --coverage <file> - Write lcov coverage of the executed script to the given file."
+        );
+        println!(
+            "  --stack-limit <n> - Limit the stack of the script to the given number of values."
+        );
+        println!("  --integer-overflow <mode> - How integer overflow is handled, one of `error` (default), `wrapping`, or `saturating`.");
         println!();
         println!("Compiler options:");
         println!("  -O <option>       - Update the given compiler option.");
@@ -133,38 +267,37 @@ async fn main() -> Result<()> {
         println!("Available <option> arguments:");
         println!("  memoize-instance-fn[=<true/false>] - Inline the lookup of an instance function where appropriate.");
         println!("  link-checks[=<true/false>] - Perform linker checks which makes sure that called functions exist.");
+        println!("  optimize[=<true/false>] - Fold constant expressions and reuse dead stack slots in compiled functions.");
+        println!("  lint-infinite-loops[=<true/false>] - Warn about loops which never exit.");
+        println!("  lint-unguarded-recursion[=<true/false>] - Warn about functions which unconditionally call themselves.");
+        println!("  coverage[=<true/false>] - Emit coverage counters for every statement.");
+        println!("  api-version=<major.minor> - Check that the script is compatible with the given version of the host API.");
+        println!("  strict[=<true/false>] - Forbid shadowing variables and builtins, and require explicit returns.");
         return Ok(());
     }
 
-    let path = match path {
-        Some(path) => path,
-        None => {
-            bail!("Invalid usage: {}", USAGE);
-        }
+    // NB: a script given explicitly is run as a loose file, outside of any
+    // project it might be part of.
+    let manifest = match &path {
+        Some(..) => None,
+        None => Manifest::find(&env::current_dir()?)?,
     };
 
-    let mut context = rune::default_context()?;
-    context.install(&rune_macros::module()?)?;
-    let context = Arc::new(context);
-
-    let mut warnings = rune::Warnings::new();
-    let mut sources = rune::Sources::new();
-
-    let unit = match rune::load_path(&*context, &options, &mut sources, &path, &mut warnings) {
-        Ok(unit) => Arc::new(unit),
-        Err(error) => {
-            let mut writer = StandardStream::stderr(ColorChoice::Always);
-            error.emit_diagnostics(&mut writer, &sources)?;
-            return Ok(());
+    let path = match (path, &manifest) {
+        (Some(path), _) => path,
+        (None, Some(manifest)) => manifest.entry(),
+        (None, None) => {
+            bail!("Invalid usage: {}", USAGE);
         }
     };
 
-    let vm = runestick::Vm::new(context.clone(), unit.clone());
+    // NB: like the arguments of a process, the first argument is the script
+    // being run.
+    flags.args.push(path.display().to_string());
+    flags.args.extend(script_args);
 
-    if !warnings.is_empty() {
-        let mut writer = StandardStream::stderr(ColorChoice::Always);
-        warnings.emit_diagnostics(&mut writer, &sources)?;
-    }
+    let (context, options) = setup(manifest.as_ref(), &cli_options, &flags.args)?;
+    let context = Arc::new(context);
 
     if dump_functions {
         println!("# functions");
@@ -182,7 +315,144 @@ async fn main() -> Result<()> {
         }
     }
 
-    if dump_unit {
+    if !watch {
+        let (sources, unit) = load(&context, &options, &path)?;
+
+        if let Some(unit) = unit {
+            execute(&context, &unit, &sources, &flags).await?;
+        }
+
+        return Ok(());
+    }
+
+    loop {
+        let (sources, unit) = load(&context, &options, &path)?;
+        let watched = match &manifest {
+            Some(manifest) => Watched::new(manifest.scripts()?, &sources),
+            None => Watched::new(vec![path.clone()], &sources),
+        };
+
+        if let Some(unit) = unit {
+            tokio::select! {
+                result = execute(&context, &unit, &sources, &flags) => result?,
+                result = watched.changed() => {
+                    result?;
+                    println!("== change detected, restarting");
+                    continue;
+                }
+            }
+        }
+
+        watched.changed().await?;
+        println!("== change detected, re-running");
+    }
+}
+
+/// Set up the context and compiler options to use, taking the project
+/// manifest into account if there is one.
+///
+/// Options passed on the command line take precedence over the ones in the
+/// manifest.
+///
+/// The given arguments are the ones returned by `std::env::args()`, which are
+/// left empty when the script isn't being run.
+fn setup(
+    manifest: Option<&Manifest>,
+    cli_options: &[String],
+    args: &[String],
+) -> Result<(runestick::Context, rune::Options)> {
+    let mut options = rune::Options::default();
+
+    let mut context = match manifest {
+        Some(manifest) => {
+            manifest.apply_options(&mut options)?;
+            manifest.context()?
+        }
+        None => rune::default_context()?,
+    };
+
+    context.install(&rune_macros::module()?)?;
+    context.install(&runestick::modules::env::module(args.to_vec())?)?;
+
+    for option in cli_options {
+        options.parse_option(option)?;
+    }
+
+    Ok((context, options))
+}
+
+/// Flags controlling how a script is executed.
+#[derive(Default)]
+struct Flags {
+    /// Trace every instruction executed.
+    trace: bool,
+    /// Write a structured trace of every instruction executed to the given
+    /// path.
+    trace_json: Option<PathBuf>,
+    /// Dump the instructions of the unit before running it.
+    dump_unit: bool,
+    /// Dump the stack after running the script.
+    dump_stack: bool,
+    /// Write the coverage of the executed script in the lcov format to the
+    /// given path.
+    coverage: Option<PathBuf>,
+    /// The maximum number of values on the stack.
+    stack_limit: Option<usize>,
+    /// How integer arithmetic which overflows is handled.
+    integer_overflow: runestick::IntegerOverflow,
+    /// The arguments passed to the script, starting with the script itself.
+    args: Vec<String>,
+}
+
+/// Load and compile the script at the given path, emitting any diagnostics.
+///
+/// The returned sources contain every file which was loaded, and the unit is
+/// `None` if the script failed to compile.
+///
+/// If the path refers to a unit compiled with `rune build`, it's loaded as-is
+/// together with any sources embedded in it.
+fn load(
+    context: &runestick::Context,
+    options: &rune::Options,
+    path: &Path,
+) -> Result<(rune::Sources, Option<Arc<runestick::Unit>>)> {
+    if artifact::is_artifact(path) {
+        let (unit, sources) = artifact::read(path)?;
+        return Ok((sources, Some(Arc::new(unit))));
+    }
+
+    let mut warnings = rune::Warnings::new();
+    let mut sources = rune::Sources::new();
+
+    let unit = match rune::load_path(context, options, &mut sources, path, &mut warnings) {
+        Ok(unit) => Arc::new(unit),
+        Err(error) => {
+            let mut writer = StandardStream::stderr(ColorChoice::Always);
+            error.emit_diagnostics(&mut writer, &sources)?;
+            return Ok((sources, None));
+        }
+    };
+
+    if !warnings.is_empty() {
+        let mut writer = StandardStream::stderr(ColorChoice::Always);
+        warnings.emit_diagnostics(&mut writer, &sources)?;
+    }
+
+    Ok((sources, Some(unit)))
+}
+
+/// Execute the `main` function of the given unit.
+async fn execute(
+    context: &Arc<runestick::Context>,
+    unit: &Arc<runestick::Unit>,
+    sources: &rune::Sources,
+    flags: &Flags,
+) -> Result<()> {
+    let mut vm = runestick::Vm::new(context.clone(), unit.clone());
+    vm.stack_mut().set_limit(flags.stack_limit);
+    vm.set_integer_overflow(flags.integer_overflow);
+
+    if flags.dump_unit {
         use std::io::Write as _;
 
         println!("# instructions:");
@@ -240,14 +510,35 @@ async fn main() -> Result<()> {
             println!("{} = {:?}", hash, keys);
         }
 
+        println!("# stats:");
+        println!("{}", vm.unit().stats());
         println!("---");
     }
 
-    let mut execution: runestick::VmExecution = vm.call(Item::of(&["main"]), ())?;
+    let main = Item::of(&["main"]);
+
+    // NB: the arguments are only passed to `main` if it takes a parameter, so
+    // that scripts which don't care about them can leave it out.
+    let takes_args = match vm.unit().lookup(Hash::type_hash(&main)) {
+        Some(runestick::UnitFn::Offset { args, .. }) => args == 1,
+        _ => false,
+    };
+
+    let mut execution: runestick::VmExecution = if takes_args {
+        vm.call(main, (flags.args.clone(),))?
+    } else {
+        vm.call(main, ())?
+    };
     let last = std::time::Instant::now();
 
-    let result = if trace {
-        match do_trace(&mut execution, dump_stack).await {
+    let result = if let Some(path) = &flags.trace_json {
+        match trace::trace_json(&mut execution, sources, path).await {
+            Ok(value) => Ok(value),
+            Err(TraceError::Io(io)) => return Err(io.into()),
+            Err(TraceError::VmError(vm)) => Err(vm),
+        }
+    } else if flags.trace {
+        match do_trace(&mut execution, flags.dump_stack).await {
             Ok(value) => Ok(value),
             Err(TraceError::Io(io)) => return Err(io.into()),
             Err(TraceError::VmError(vm)) => Err(vm),
@@ -271,7 +562,7 @@ async fn main() -> Result<()> {
         }
     };
 
-    if dump_stack {
+    if flags.dump_stack {
         println!("# full stack dump after halting");
 
         let vm = execution.vm()?;
@@ -317,12 +608,696 @@ async fn main() -> Result<()> {
 
     if let Some(error) = errored {
         let mut writer = StandardStream::stderr(ColorChoice::Always);
-        error.emit_diagnostics(&mut writer, &sources)?;
+        error.emit_diagnostics(&mut writer, sources)?;
+    }
+
+    if let Some(path) = &flags.coverage {
+        fs::write(path, lcov(unit, sources))?;
+        println!("wrote coverage to `{}`", path.display());
+    }
+
+    if let Some(path) = &flags.trace_json {
+        println!("wrote trace to `{}`", path.display());
     }
 
     Ok(())
 }
 
+/// Format the coverage collected in the given unit in the lcov format.
+///
+/// The hits of a line is the highest number of hits of any counter starting
+/// on it.
+fn lcov(unit: &runestick::Unit, sources: &rune::Sources) -> String {
+    use std::collections::BTreeMap;
+    use std::fmt::Write as _;
+
+    let mut files = BTreeMap::<usize, BTreeMap<usize, u64>>::new();
+
+    for counter in unit.coverage().iter() {
+        let source = match sources.get(counter.source_id) {
+            Some(source) => source,
+            None => continue,
+        };
+
+        let (line, _) = source.position(counter.span);
+        let hits = files
+            .entry(counter.source_id)
+            .or_default()
+            .entry(line + 1)
+            .or_default();
+        *hits = u64::max(*hits, counter.hits());
+    }
+
+    let mut out = String::new();
+
+    for (source_id, lines) in files {
+        let source = match sources.get(source_id) {
+            Some(source) => source,
+            None => continue,
+        };
+
+        let name = match source.path() {
+            Some(path) => path.display().to_string(),
+            None => source.name().to_owned(),
+        };
+
+        writeln!(out, "TN:").unwrap();
+        writeln!(out, "SF:{}", name).unwrap();
+
+        for (line, hits) in &lines {
+            writeln!(out, "DA:{},{}", line, hits).unwrap();
+        }
+
+        let hit = lines.values().filter(|hits| **hits > 0).count();
+        writeln!(out, "LH:{}", hit).unwrap();
+        writeln!(out, "LF:{}", lines.len()).unwrap();
+        writeln!(out, "end_of_record").unwrap();
+    }
+
+    out
+}
+
+const CHECK_USAGE: &str = "rune-cli check [-O <option>] [<path>...]";
+
+/// Check the given files, or all scripts in the given directories, for errors
+/// without running them.
+///
+/// Without any paths, all scripts of the project in the current directory are
+/// checked.
+///
+/// Diagnostics are emitted for every script, and the process exits with a
+/// non-zero status if any of them failed to compile.
+fn check(args: impl Iterator<Item = String>) -> Result<()> {
+    let mut args = args;
+    let mut paths = Vec::new();
+    let mut cli_options = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--" => continue,
+            "-O" => {
+                let opt = match args.next() {
+                    Some(opt) => opt,
+                    None => {
+                        println!("expected optimization option to `-O`");
+                        return Ok(());
+                    }
+                };
+
+                cli_options.push(opt);
+            }
+            "--help" | "-h" => {
+                println!("Usage: {}", CHECK_USAGE);
+                println!();
+                println!("Parse, index, and compile the given scripts without running them.");
+                println!("Directories are searched recursively for `.rn` files.");
+                return Ok(());
+            }
+            other if !other.starts_with('-') => {
+                paths.push(PathBuf::from(other));
+            }
+            other => {
+                bail!("Unrecognized option: {}\nUsage: {}", other, CHECK_USAGE);
+            }
+        }
+    }
+
+    let manifest = if paths.is_empty() {
+        match Manifest::find(&env::current_dir()?)? {
+            Some(manifest) => Some(manifest),
+            None => bail!("Invalid usage: {}", CHECK_USAGE),
+        }
+    } else {
+        None
+    };
+
+    let files = match &manifest {
+        Some(manifest) => {
+            println!("checking project `{}`", manifest.name());
+            manifest.scripts()?
+        }
+        None => {
+            let mut files = Vec::new();
+
+            for path in &paths {
+                collect_scripts(path, &mut files)?;
+            }
+
+            files
+        }
+    };
+
+    let (context, options) = setup(manifest.as_ref(), &cli_options, &[])?;
+
+    let mut writer = StandardStream::stderr(ColorChoice::Auto);
+    let mut failed = 0;
+    let mut warned = 0;
+
+    for file in &files {
+        let mut warnings = rune::Warnings::new();
+        let mut sources = rune::Sources::new();
+
+        if let Err(error) = rune::load_path(&context, &options, &mut sources, file, &mut warnings) {
+            error.emit_diagnostics(&mut writer, &sources)?;
+            failed += 1;
+        }
+
+        if !warnings.is_empty() {
+            warnings.emit_diagnostics(&mut writer, &sources)?;
+            warned += 1;
+        }
+    }
+
+    println!(
+        "checked {} file(s): {} failed, {} with warnings",
+        files.len(),
+        failed,
+        warned
+    );
+
+    if failed > 0 {
+        process::exit(1);
+    }
+
+    Ok(())
+}
+
+const BUILD_USAGE: &str =
+    "rune-cli build [-o <output>] [--no-debug-info] [--embed-source] [-O <option>] [<file>]";
+
+/// Compile the given script, or the entry point of the project in the current
+/// directory, and write the compiled unit to a file which can be run without
+/// its sources.
+fn build(args: impl Iterator<Item = String>) -> Result<()> {
+    let mut args = args;
+    let mut path = None;
+    let mut output = None;
+    let mut debug_info = true;
+    let mut embed_source = false;
+    let mut cli_options = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--" => continue,
+            "-o" => match args.next() {
+                Some(out) => output = Some(PathBuf::from(out)),
+                None => bail!("expected output path to `-o`"),
+            },
+            "--no-debug-info" => {
+                debug_info = false;
+            }
+            "--embed-source" => {
+                embed_source = true;
+            }
+            "-O" => {
+                let opt = match args.next() {
+                    Some(opt) => opt,
+                    None => {
+                        println!("expected optimization option to `-O`");
+                        return Ok(());
+                    }
+                };
+
+                cli_options.push(opt);
+            }
+            "--help" | "-h" => {
+                println!("Usage: {}", BUILD_USAGE);
+                println!();
+                println!(
+                    "Compile the given script into a unit which can be run without its sources."
+                );
+                println!();
+                println!(
+                    "  -o <output>       - Where to write the unit (default: `<name>.{}`).",
+                    artifact::EXTENSION
+                );
+                println!(
+                    "  --no-debug-info   - Strip debug info, like function names, from the unit."
+                );
+                println!("  --embed-source    - Embed the sources in the unit, so that errors can be reported with them.");
+                return Ok(());
+            }
+            other if !other.starts_with('-') => {
+                path = Some(PathBuf::from(other));
+            }
+            other => {
+                bail!("Unrecognized option: {}\nUsage: {}", other, BUILD_USAGE);
+            }
+        }
+    }
+
+    if embed_source && !debug_info {
+        bail!("`--embed-source` requires debug info, which was disabled with `--no-debug-info`");
+    }
+
+    let manifest = match &path {
+        Some(..) => None,
+        None => Manifest::find(&env::current_dir()?)?,
+    };
+
+    let (path, default_output) = match (path, &manifest) {
+        (Some(path), _) => {
+            let output = path.with_extension(artifact::EXTENSION);
+            (path, output)
+        }
+        (None, Some(manifest)) => {
+            let output = manifest
+                .root()
+                .join(manifest.name())
+                .with_extension(artifact::EXTENSION);
+            (manifest.entry(), output)
+        }
+        (None, None) => bail!("Invalid usage: {}", BUILD_USAGE),
+    };
+
+    let output = output.unwrap_or(default_output);
+    let (sources, mut unit) = compile(manifest.as_ref(), &path, &cli_options)?;
+
+    if !debug_info {
+        unit.strip_debug_info();
+    }
+
+    let sources = if embed_source { Some(&sources) } else { None };
+    artifact::write(&output, &unit, sources)?;
+    println!("wrote `{}`", output.display());
+    Ok(())
+}
+
+const BUNDLE_USAGE: &str =
+    "rune-cli bundle [-o <directory>] [--no-debug-info] [-O <option>] [<file>]";
+
+/// Compile the given script, or the entry point of the project in the current
+/// directory, and write a Rust project which builds it into a standalone
+/// binary.
+fn bundle(args: impl Iterator<Item = String>) -> Result<()> {
+    let mut args = args;
+    let mut path = None;
+    let mut output = None;
+    let mut debug_info = true;
+    let mut cli_options = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--" => continue,
+            "-o" => match args.next() {
+                Some(out) => output = Some(PathBuf::from(out)),
+                None => bail!("expected output directory to `-o`"),
+            },
+            "--no-debug-info" => {
+                debug_info = false;
+            }
+            "-O" => {
+                let opt = match args.next() {
+                    Some(opt) => opt,
+                    None => {
+                        println!("expected optimization option to `-O`");
+                        return Ok(());
+                    }
+                };
+
+                cli_options.push(opt);
+            }
+            "--help" | "-h" => {
+                println!("Usage: {}", BUNDLE_USAGE);
+                println!();
+                println!(
+                    "Compile the given script and write a Rust project embedding it, which can be"
+                );
+                println!("built into a standalone binary with `cargo build --release`.");
+                println!();
+                println!(
+                    "  -o <directory>    - Where to write the project (default: `<name>-bundle`)."
+                );
+                println!(
+                    "  --no-debug-info   - Strip debug info, like function names, from the unit."
+                );
+                return Ok(());
+            }
+            other if !other.starts_with('-') => {
+                path = Some(PathBuf::from(other));
+            }
+            other => {
+                bail!("Unrecognized option: {}\nUsage: {}", other, BUNDLE_USAGE);
+            }
+        }
+    }
+
+    let manifest = match &path {
+        Some(..) => None,
+        None => Manifest::find(&env::current_dir()?)?,
+    };
+
+    let (path, name, root) = match (path, &manifest) {
+        (Some(path), _) => {
+            let name = match path.file_stem() {
+                Some(stem) => stem.to_string_lossy().into_owned(),
+                None => bail!("`{}` is not a script", path.display()),
+            };
+
+            let root = path.parent().map(Path::to_owned).unwrap_or_default();
+            (path, name, root)
+        }
+        (None, Some(manifest)) => (
+            manifest.entry(),
+            manifest.name().to_owned(),
+            manifest.root().to_owned(),
+        ),
+        (None, None) => bail!("Invalid usage: {}", BUNDLE_USAGE),
+    };
+
+    let output = output.unwrap_or_else(|| root.join(format!("{}-bundle", name)));
+    let (_, mut unit) = compile(manifest.as_ref(), &path, &cli_options)?;
+
+    if !debug_info {
+        unit.strip_debug_info();
+    }
+
+    let modules = manifest.as_ref().and_then(Manifest::modules);
+    bundle::write(&output, &name, &unit, modules)?;
+    println!(
+        "wrote `{}`, build it with `cargo build --release`",
+        output.display()
+    );
+    Ok(())
+}
+
+const RENAME_USAGE: &str = "rune-cli rename [--dry-run] <file> <item> <name>";
+
+/// Rename the item at the given path, like `foo::bar`, in the script and any
+/// modules it loads.
+fn rename(args: impl Iterator<Item = String>) -> Result<()> {
+    let mut dry_run = false;
+    let mut positional = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "--" => continue,
+            "--dry-run" => {
+                dry_run = true;
+            }
+            "--help" | "-h" => {
+                println!("Usage: {}", RENAME_USAGE);
+                println!();
+                println!("Rename an item and update every reference to it.");
+                println!("With `--dry-run` the edits are printed instead of written.");
+                return Ok(());
+            }
+            other if !other.starts_with('-') => {
+                positional.push(arg);
+            }
+            other => {
+                bail!("Unrecognized option: {}\nUsage: {}", other, RENAME_USAGE);
+            }
+        }
+    }
+
+    let (path, item, name) = match positional.as_slice() {
+        [path, item, name] => (Path::new(path), Item::of(item.split("::")), name),
+        _ => bail!("Invalid usage: {}", RENAME_USAGE),
+    };
+
+    let (context, _) = setup(None, &[], &[])?;
+    let mut sources = rune::Sources::new();
+    sources.insert_default(runestick::Source::from_path(path)?);
+
+    let edits = match rune::refactor::rename(&context, &mut sources, &item, name) {
+        Ok(edits) => edits,
+        Err(rune::refactor::RenameError::LoadError { error }) => {
+            let mut writer = StandardStream::stderr(ColorChoice::Always);
+            error.emit_diagnostics(&mut writer, &sources)?;
+            process::exit(1);
+        }
+        Err(error) => bail!(error),
+    };
+
+    let mut files = 0;
+
+    for (source_id, source) in sources.iter().enumerate() {
+        let edits = edits
+            .iter()
+            .filter(|e| e.source_id == source_id)
+            .collect::<Vec<_>>();
+
+        if edits.is_empty() {
+            continue;
+        }
+
+        files += 1;
+
+        if dry_run {
+            for edit in &edits {
+                let (line, column) = source.position(edit.span);
+                let old = source.source(edit.span).unwrap_or_default();
+
+                println!(
+                    "{}:{}:{}: `{}` -> `{}`",
+                    source.name(),
+                    line + 1,
+                    column + 1,
+                    old,
+                    edit.replacement
+                );
+            }
+
+            continue;
+        }
+
+        let path = match source.path() {
+            Some(path) => path,
+            None => bail!("source `{}` has no path to write to", source.name()),
+        };
+
+        fs::write(path, rune::refactor::apply_edits(source.as_str(), edits))?;
+    }
+
+    println!(
+        "renamed `{}` to `{}`: {} edit(s) in {} file(s)",
+        item,
+        name,
+        edits.len(),
+        files
+    );
+    Ok(())
+}
+
+const CAPABILITIES_USAGE: &str =
+    "rune-cli capabilities [--deny <capability>]... [-O <option>] [<file>]";
+
+/// Report which restricted capabilities the given script, or the entry point
+/// of the project in the current directory, can reach.
+fn capabilities(args: impl Iterator<Item = String>) -> Result<()> {
+    use rune::capabilities::{self, Capability, Restrictions};
+
+    let mut args = args;
+    let mut path = None;
+    let mut deny = Vec::new();
+    let mut cli_options = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--" => continue,
+            "--deny" => match args.next() {
+                Some(name) => match Capability::from_name(&name) {
+                    Some(capability) => deny.push(capability),
+                    None => bail!("unknown capability `{}`", name),
+                },
+                None => bail!("expected capability to `--deny`"),
+            },
+            "-O" => {
+                let opt = match args.next() {
+                    Some(opt) => opt,
+                    None => {
+                        println!("expected optimization option to `-O`");
+                        return Ok(());
+                    }
+                };
+
+                cli_options.push(opt);
+            }
+            "--help" | "-h" => {
+                println!("Usage: {}", CAPABILITIES_USAGE);
+                println!();
+                println!("Report which restricted capabilities the script can reach, without");
+                println!("running it.");
+                println!();
+                println!(
+                    "  --deny <capability> - Fail if the capability is reachable, one of `fs`, `net`, or `process`."
+                );
+                return Ok(());
+            }
+            other if !other.starts_with('-') => {
+                path = Some(PathBuf::from(other));
+            }
+            other => {
+                bail!(
+                    "Unrecognized option: {}\nUsage: {}",
+                    other,
+                    CAPABILITIES_USAGE
+                );
+            }
+        }
+    }
+
+    let manifest = match &path {
+        Some(..) => None,
+        None => Manifest::find(&env::current_dir()?)?,
+    };
+
+    let path = match (path, &manifest) {
+        (Some(path), _) => path,
+        (None, Some(manifest)) => manifest.entry(),
+        (None, None) => bail!("Invalid usage: {}", CAPABILITIES_USAGE),
+    };
+
+    let (context, options) = setup(manifest.as_ref(), &cli_options, &[])?;
+
+    let (sources, unit) = match load(&context, &options, &path)? {
+        (sources, Some(unit)) => (sources, unit),
+        (_, None) => process::exit(1),
+    };
+
+    let report = capabilities::analyze(&context, &unit, &Restrictions::default());
+    let debug = unit.debug_info();
+
+    for u in report.uses() {
+        let caller = debug
+            .and_then(|debug| debug.functions.get(&u.caller))
+            .map(|signature| signature.path.to_string())
+            .unwrap_or_else(|| u.caller.to_string());
+
+        let location = debug
+            .and_then(|debug| debug.instruction_at(u.ip))
+            .and_then(|inst| {
+                let source = sources.get(inst.source_id)?;
+                let (line, column) = source.position(inst.span);
+                Some(format!(" at {}:{}:{}", source.name(), line + 1, column + 1))
+            })
+            .unwrap_or_default();
+
+        println!(
+            "{}: `{}` called from `{}`{}",
+            u.capability, u.function, caller, location
+        );
+    }
+
+    let reached = report.capabilities();
+    let names = reached.iter().map(|c| c.name()).collect::<Vec<_>>();
+
+    if names.is_empty() {
+        println!("capabilities: none");
+    } else {
+        println!("capabilities: {}", names.join(", "));
+    }
+
+    let denied = deny
+        .iter()
+        .filter(|c| reached.contains(c))
+        .map(|c| c.name())
+        .collect::<Vec<_>>();
+
+    if !denied.is_empty() {
+        bail!("script reaches denied capabilities: {}", denied.join(", "));
+    }
+
+    Ok(())
+}
+
+/// Compile the script at the given path into a unit, exiting with a non-zero
+/// status if it fails to compile.
+fn compile(
+    manifest: Option<&Manifest>,
+    path: &Path,
+    cli_options: &[String],
+) -> Result<(rune::Sources, runestick::Unit)> {
+    let (context, options) = setup(manifest, cli_options, &[])?;
+
+    let (sources, unit) = match load(&context, &options, path)? {
+        (sources, Some(unit)) => (sources, unit),
+        (_, None) => process::exit(1),
+    };
+
+    match Arc::try_unwrap(unit) {
+        Ok(unit) => Ok((sources, unit)),
+        Err(..) => bail!("compiled unit is unexpectedly shared"),
+    }
+}
+
+/// Collect the script at the given path, or all scripts in it recursively if
+/// it's a directory.
+fn collect_scripts(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_owned());
+        return Ok(());
+    }
+
+    let mut entries = fs::read_dir(path)?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    entries.sort();
+
+    for entry in entries {
+        if entry.is_dir() {
+            collect_scripts(&entry, files)?;
+        } else if entry.extension().map_or(false, |ext| ext == "rn") {
+            files.push(entry);
+        }
+    }
+
+    Ok(())
+}
+
+/// Files watched for changes, together with when they were last modified.
+struct Watched {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl Watched {
+    /// How often watched files are checked for changes.
+    const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+    /// Watch the given scripts, and every other file loaded with them.
+    fn new(mut paths: Vec<PathBuf>, sources: &rune::Sources) -> Self {
+        for source in sources.iter() {
+            if let Some(path) = source.path() {
+                if !paths.iter().any(|p| p == path) {
+                    paths.push(path.to_owned());
+                }
+            }
+        }
+
+        let files = paths
+            .into_iter()
+            .map(|path| {
+                let modified = Self::modified(&path);
+                (path, modified)
+            })
+            .collect();
+
+        Self { files }
+    }
+
+    /// Wait until any of the watched files have changed.
+    async fn changed(&self) -> Result<()> {
+        loop {
+            tokio::time::delay_for(Self::POLL_INTERVAL).await;
+
+            if self
+                .files
+                .iter()
+                .any(|(path, modified)| Self::modified(path) != *modified)
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Get the last time the given file was modified, if it exists.
+    fn modified(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+}
+
 enum TraceError {
     Io(std::io::Error),
     VmError(runestick::VmError),
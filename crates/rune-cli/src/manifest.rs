@@ -0,0 +1,211 @@
+//! Project manifests, which are read from a `Rune.toml` file.
+//!
+//! ```toml
+//! [package]
+//! name = "hello"
+//! # The script whose `main` function is run (default: "src/main.rn").
+//! entry = "src/main.rn"
+//!
+//! [build]
+//! # Directories containing the scripts of the project (default: ["src"]).
+//! source-dirs = ["src"]
+//! # Native modules to install, besides the default ones (default: all).
+//! modules = ["json", "time"]
+//! # Compiler options, like the ones passed with `-O` (default: none).
+//! options = ["optimize"]
+//! ```
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The name of the manifest file.
+pub(crate) const MANIFEST_FILE: &str = "Rune.toml";
+
+/// The names of all native modules which can be enabled in a manifest.
+pub(crate) const MODULES: &[&str] = &[
+    "http", "json", "toml", "time", "process", "fs", "signal", "csv", "hash", "uuid", "template",
+    "db", "msgpack", "cbor",
+];
+
+/// A loaded project manifest.
+pub(crate) struct Manifest {
+    /// The directory the manifest was loaded from, which all paths in it are
+    /// relative to.
+    root: PathBuf,
+    /// The deserialized manifest.
+    file: ManifestFile,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestFile {
+    package: Package,
+    #[serde(default)]
+    build: Build,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Package {
+    name: String,
+    #[serde(default = "Package::default_entry")]
+    entry: PathBuf,
+}
+
+impl Package {
+    fn default_entry() -> PathBuf {
+        PathBuf::from("src/main.rn")
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct Build {
+    #[serde(default = "Build::default_source_dirs")]
+    source_dirs: Vec<PathBuf>,
+    #[serde(default)]
+    modules: Option<Vec<String>>,
+    #[serde(default)]
+    options: Vec<String>,
+}
+
+impl Build {
+    fn default_source_dirs() -> Vec<PathBuf> {
+        vec![PathBuf::from("src")]
+    }
+}
+
+impl Default for Build {
+    fn default() -> Self {
+        Self {
+            source_dirs: Self::default_source_dirs(),
+            modules: None,
+            options: Vec::new(),
+        }
+    }
+}
+
+impl Manifest {
+    /// Find the manifest of the project containing the given directory, by
+    /// looking for a `Rune.toml` in it and all of its parents.
+    pub(crate) fn find(dir: &Path) -> Result<Option<Self>> {
+        for dir in dir.ancestors() {
+            let path = dir.join(MANIFEST_FILE);
+
+            if path.is_file() {
+                return Ok(Some(Self::load(&path)?));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Load the manifest at the given path.
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("failed to read manifest `{}`", path.display()))?;
+
+        let file: ManifestFile = toml::from_str(&content)
+            .with_context(|| format!("failed to parse manifest `{}`", path.display()))?;
+
+        let root = match path.parent() {
+            Some(root) => root.to_owned(),
+            None => PathBuf::new(),
+        };
+
+        Ok(Self { root, file })
+    }
+
+    /// The directory containing the manifest.
+    pub(crate) fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The name of the project.
+    pub(crate) fn name(&self) -> &str {
+        &self.file.package.name
+    }
+
+    /// The path to the entry point of the project.
+    pub(crate) fn entry(&self) -> PathBuf {
+        self.root.join(&self.file.package.entry)
+    }
+
+    /// All scripts of the project, including the entry point.
+    pub(crate) fn scripts(&self) -> Result<Vec<PathBuf>> {
+        let entry = self.entry();
+        let mut scripts = vec![entry.clone()];
+
+        for dir in &self.file.build.source_dirs {
+            let dir = self.root.join(dir);
+
+            crate::collect_scripts(&dir, &mut scripts)
+                .with_context(|| format!("failed to read source directory `{}`", dir.display()))?;
+        }
+
+        let mut seen = Vec::<PathBuf>::new();
+        scripts.retain(|path| {
+            if seen.contains(path) {
+                return false;
+            }
+
+            seen.push(path.clone());
+            true
+        });
+
+        Ok(scripts)
+    }
+
+    /// The native modules enabled by the manifest, or `None` if all of them
+    /// are.
+    pub(crate) fn modules(&self) -> Option<&[String]> {
+        self.file.build.modules.as_deref()
+    }
+
+    /// Apply the compiler options of the manifest.
+    pub(crate) fn apply_options(&self, options: &mut rune::Options) -> Result<()> {
+        for option in &self.file.build.options {
+            options
+                .parse_option(option)
+                .map_err(|error| anyhow!("bad option `{}` in manifest: {}", option, error))?;
+        }
+
+        Ok(())
+    }
+
+    /// Construct the context with the native modules enabled by the manifest.
+    pub(crate) fn context(&self) -> Result<runestick::Context> {
+        let names = match &self.file.build.modules {
+            Some(names) => names,
+            None => return Ok(rune::default_context()?),
+        };
+
+        let mut context = runestick::Context::with_default_modules()?;
+
+        for name in names {
+            let module = match name.as_str() {
+                "http" => rune_modules::http::module()?,
+                "json" => rune_modules::json::module()?,
+                "toml" => rune_modules::toml::module()?,
+                "time" => rune_modules::time::module()?,
+                "process" => rune_modules::process::module()?,
+                "fs" => rune_modules::fs::module()?,
+                "signal" => rune_modules::signal::module()?,
+                "csv" => rune_modules::csv::module()?,
+                "hash" => rune_modules::hash::module()?,
+                "uuid" => rune_modules::uuid::module()?,
+                "template" => rune_modules::template::module()?,
+                "db" => rune_modules::db::module()?,
+                "msgpack" => rune_modules::msgpack::module()?,
+                "cbor" => rune_modules::cbor::module()?,
+                other => bail!("unknown module `{}` in manifest", other),
+            };
+
+            context.install(&module)?;
+        }
+
+        Ok(context)
+    }
+}
@@ -0,0 +1,227 @@
+//! Structured traces written by `--trace-json`, which external tools can use
+//! to visualize an execution as a timeline, or to step back and forth through
+//! it.
+//!
+//! The trace is written as JSON lines, with one object per line which is
+//! tagged by its `type`:
+//! * `header` - the format version and the sources of the script, which the
+//!   spans of steps refer to by index.
+//! * `step` - an executed instruction, with its location and the changes it
+//!   made to the stack.
+//! * `result` - the value returned by the script, or the error it raised.
+
+use crate::TraceError;
+use runestick::{Span, Value, Vm, VmExecution};
+use serde::Serialize;
+use std::fs;
+use std::io::{self, Write as _};
+use std::path::Path;
+
+/// The version of the trace format, which is bumped whenever it changes.
+const VERSION: u32 = 1;
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Event<'a> {
+    Header {
+        version: u32,
+        sources: Vec<TraceSource<'a>>,
+    },
+    Step(Step),
+    Result {
+        ok: bool,
+        value: String,
+    },
+}
+
+#[derive(Serialize)]
+struct TraceSource<'a> {
+    name: &'a str,
+    source: &'a str,
+}
+
+#[derive(Serialize)]
+struct Step {
+    /// The index of the step, starting at zero.
+    step: usize,
+    /// The instruction pointer before the step.
+    ip: usize,
+    /// The number of call frames before the step.
+    depth: usize,
+    /// The signature of the function, if the instruction is the first one in
+    /// a function.
+    function: Option<String>,
+    /// The executed instruction.
+    inst: String,
+    /// The comment associated with the instruction.
+    comment: Option<String>,
+    /// The location in the source the instruction was compiled from.
+    span: Option<TraceSpan>,
+    /// The changes to the stack made by the instruction.
+    stack: StackDelta,
+}
+
+#[derive(Serialize)]
+struct TraceSpan {
+    source_id: usize,
+    start: usize,
+    end: usize,
+    /// The zero-based line of the start of the span.
+    line: Option<usize>,
+    /// The zero-based column of the start of the span.
+    column: Option<usize>,
+}
+
+/// The changes to the stack made by a single instruction.
+///
+/// The stack after the instruction is the stack before it with `popped`
+/// values removed from the top, and then the values in `pushed` added.
+#[derive(Serialize)]
+struct StackDelta {
+    before: usize,
+    after: usize,
+    popped: usize,
+    pushed: Vec<String>,
+}
+
+/// Run the execution to completion, writing a structured trace of it to the
+/// given path.
+pub(crate) async fn trace_json(
+    execution: &mut VmExecution,
+    sources: &rune::Sources,
+    path: &Path,
+) -> Result<Value, TraceError> {
+    let mut out = io::BufWriter::new(fs::File::create(path)?);
+
+    write_event(
+        &mut out,
+        &Event::Header {
+            version: VERSION,
+            sources: sources
+                .iter()
+                .map(|source| TraceSource {
+                    name: source.name(),
+                    source: source.as_str(),
+                })
+                .collect(),
+        },
+    )?;
+
+    let mut step = 0;
+
+    let result = loop {
+        let (mut event, before) = {
+            let vm = execution.vm().map_err(TraceError::VmError)?;
+            (begin_step(vm, sources, step), stack_values(vm))
+        };
+
+        let result = execution.async_step().await;
+
+        // NB: the stack is inspected even if the step failed, since it's
+        // useful to see where the failing instruction left it.
+        if let Ok(vm) = execution.vm() {
+            event.stack = stack_delta(&before, &stack_values(vm));
+        }
+
+        write_event(&mut out, &Event::Step(event))?;
+        step += 1;
+
+        match result {
+            Ok(Some(value)) => break Ok(value),
+            Ok(None) => (),
+            Err(error) => break Err(error),
+        }
+    };
+
+    let event = match &result {
+        Ok(value) => Event::Result {
+            ok: true,
+            value: format!("{:?}", value),
+        },
+        Err(error) => Event::Result {
+            ok: false,
+            value: error.to_string(),
+        },
+    };
+
+    write_event(&mut out, &event)?;
+    out.flush()?;
+    result.map_err(TraceError::VmError)
+}
+
+/// Describe the instruction the virtual machine is about to execute.
+fn begin_step(vm: &Vm, sources: &rune::Sources, step: usize) -> Step {
+    let ip = vm.ip();
+    let debug = vm.unit().debug_info();
+    let debug_inst = debug.and_then(|debug| debug.instruction_at(ip));
+
+    let inst = match vm.unit().instruction_at(ip) {
+        Some(inst) => inst.to_string(),
+        None => String::from("*out of bounds*"),
+    };
+
+    Step {
+        step,
+        ip,
+        depth: vm.call_frames().len(),
+        function: debug
+            .and_then(|debug| debug.function_at(ip))
+            .map(|(_, signature)| signature.to_string()),
+        inst,
+        comment: debug_inst.and_then(|inst| inst.comment.clone()),
+        span: debug_inst.map(|inst| trace_span(sources, inst.source_id, inst.span)),
+        stack: StackDelta {
+            before: 0,
+            after: 0,
+            popped: 0,
+            pushed: Vec::new(),
+        },
+    }
+}
+
+fn trace_span(sources: &rune::Sources, source_id: usize, span: Span) -> TraceSpan {
+    let position = sources
+        .source_at(source_id)
+        .map(|source| source.position(span));
+
+    TraceSpan {
+        source_id,
+        start: span.start,
+        end: span.end,
+        line: position.map(|(line, _)| line),
+        column: position.map(|(_, column)| column),
+    }
+}
+
+/// Format every value on the stack, from the bottom of the stack.
+fn stack_values(vm: &Vm) -> Vec<String> {
+    vm.stack()
+        .iter()
+        .map(|value| format!("{:?}", value))
+        .collect()
+}
+
+/// Compute the changes between two stacks, assuming that values which format
+/// the same at the same position are unchanged.
+fn stack_delta(before: &[String], after: &[String]) -> StackDelta {
+    let common = before
+        .iter()
+        .zip(after.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    StackDelta {
+        before: before.len(),
+        after: after.len(),
+        popped: before.len() - common,
+        pushed: after[common..].to_vec(),
+    }
+}
+
+fn write_event<W>(out: &mut W, event: &Event<'_>) -> io::Result<()>
+where
+    W: io::Write,
+{
+    serde_json::to_writer(&mut *out, event)?;
+    writeln!(out)
+}
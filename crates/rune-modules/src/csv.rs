@@ -0,0 +1,274 @@
+//! The native `csv` module for the [Rune Language].
+//!
+//! [Rune Language]: https://github.com/rune-rs/rune
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = {version = "0.6.16", features = ["csv"]}
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> runestick::Result<()> {
+//! let mut context = runestick::Context::with_default_modules()?;
+//! context.install(&rune_modules::csv::module()?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! use csv;
+//!
+//! fn main() {
+//!     let rows = csv::from_string("name,age\nJohn,42\n")?;
+//!     dbg(rows);
+//!
+//!     for row in csv::reader("name,age\nJohn,42\n")? {
+//!         dbg(row.name);
+//!     }
+//!
+//!     dbg(csv::to_string([#{"name": "John", "age": 42}])?);
+//! }
+//! ```
+
+use runestick::{ContextError, Module, Object, Value};
+use std::collections::BTreeSet;
+use std::io;
+
+/// Construct the `csv` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["csv"]);
+    module.ty(&["Reader"]).build::<Reader>()?;
+    module.function(&["from_string"], from_string)?;
+    module.function(&["records_from_string"], records_from_string)?;
+    module.function(&["reader"], reader)?;
+    module.function(&["to_string"], to_string)?;
+    module.inst_fn(runestick::INTO_ITER, Reader::into_iter)?;
+    module.inst_fn(runestick::NEXT, Reader::next)?;
+    Ok(module)
+}
+
+/// Parse a csv string with a header into a vector of objects, one for each
+/// row.
+fn from_string(string: &str) -> runestick::Result<Vec<Object<String>>> {
+    let mut reader = reader(string)?;
+    let mut rows = Vec::new();
+
+    while let Some(row) = reader.next()? {
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+/// Parse a csv string without a header into a vector of records.
+fn records_from_string(string: &str) -> runestick::Result<Vec<Vec<String>>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(string.as_bytes());
+
+    let mut records = Vec::new();
+
+    for record in reader.records() {
+        records.push(record?.iter().map(String::from).collect());
+    }
+
+    Ok(records)
+}
+
+/// Construct a reader which lazily parses rows from a csv string with a
+/// header.
+fn reader(string: &str) -> runestick::Result<Reader> {
+    let mut reader = csv::Reader::from_reader(io::Cursor::new(string.as_bytes().to_vec()));
+    let headers = reader.headers()?.clone();
+    Ok(Reader { reader, headers })
+}
+
+/// Write a vector of rows as a csv string.
+///
+/// If the rows are objects, a header is written with the sorted keys of all
+/// rows, and fields missing from a row are left empty. Otherwise each row is
+/// expected to be a vector or a tuple, and is written as a plain record.
+fn to_string(rows: Vec<Value>) -> runestick::Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut keys = BTreeSet::new();
+
+    for row in &rows {
+        if let Value::Object(object) = row {
+            keys.extend(object.borrow_ref()?.keys().cloned());
+        }
+    }
+
+    let headers = keys.into_iter().collect::<Vec<_>>();
+    let mut has_header = false;
+
+    for row in rows {
+        match row {
+            Value::Object(object) => {
+                let object = object.borrow_ref()?;
+
+                if !has_header {
+                    writer.write_record(&headers)?;
+                    has_header = true;
+                }
+
+                let mut record = Vec::with_capacity(headers.len());
+
+                for key in headers.iter() {
+                    record.push(match object.get(key) {
+                        Some(value) => field(value)?,
+                        None => String::new(),
+                    });
+                }
+
+                writer.write_record(&record)?;
+            }
+            Value::Vec(vec) => {
+                let record = vec
+                    .borrow_ref()?
+                    .iter()
+                    .map(field)
+                    .collect::<Result<Vec<_>, _>>()?;
+                writer.write_record(&record)?;
+            }
+            Value::Tuple(tuple) => {
+                let record = tuple
+                    .borrow_ref()?
+                    .iter()
+                    .map(field)
+                    .collect::<Result<Vec<_>, _>>()?;
+                writer.write_record(&record)?;
+            }
+            actual => {
+                return Err(runestick::VmError::expected_any(actual.type_info()?).into());
+            }
+        }
+    }
+
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// Convert a single value into a csv field.
+fn field(value: &Value) -> Result<String, runestick::VmError> {
+    Ok(match value {
+        Value::Unit => String::new(),
+        Value::Bool(b) => b.to_string(),
+        Value::Char(c) => c.to_string(),
+        Value::Integer(n) => n.to_string(),
+        Value::Float(n) => n.to_string(),
+        Value::StaticString(s) => String::clone(s.as_ref()),
+        Value::String(s) => s.borrow_ref()?.clone(),
+        actual => return Err(runestick::VmError::expected::<String>(actual.type_info()?)),
+    })
+}
+
+/// A reader which parses one row at a time through the iterator protocol.
+struct Reader {
+    reader: csv::Reader<io::Cursor<Vec<u8>>>,
+    headers: csv::StringRecord,
+}
+
+impl Reader {
+    fn into_iter(self) -> Self {
+        self
+    }
+
+    /// Parse the next row, if there is one.
+    ///
+    /// Parse errors are raised as panics, since the iterator protocol has no
+    /// other way of reporting them.
+    fn next(&mut self) -> Result<Option<Object<String>>, runestick::VmError> {
+        let mut record = csv::StringRecord::new();
+
+        let more = self
+            .reader
+            .read_record(&mut record)
+            .map_err(runestick::VmError::panic)?;
+
+        if !more {
+            return Ok(None);
+        }
+
+        let mut row = Object::with_capacity(self.headers.len());
+
+        for (key, value) in self.headers.iter().zip(record.iter()) {
+            row.insert(key.to_owned(), value.to_owned());
+        }
+
+        Ok(Some(row))
+    }
+}
+
+runestick::impl_external!(Reader);
+
+#[cfg(test)]
+mod tests {
+    use super::{from_string, reader, records_from_string, to_string};
+    use runestick::{Object, Shared, Tuple, Value};
+
+    fn string(string: &str) -> Value {
+        Value::from(String::from(string))
+    }
+
+    fn object(fields: &[(&str, Value)]) -> Value {
+        let mut object = Object::new();
+
+        for (key, value) in fields {
+            object.insert(String::from(*key), value.clone());
+        }
+
+        Value::Object(Shared::new(object))
+    }
+
+    #[test]
+    fn test_from_string() {
+        let rows = from_string("name,age\nJohn,42\n\"Doe, Jane\",7\n").unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["name"], "John");
+        assert_eq!(rows[0]["age"], "42");
+        assert_eq!(rows[1]["name"], "Doe, Jane");
+
+        let records = records_from_string("a,b\n1,2\n").unwrap();
+        assert_eq!(records, vec![vec!["a", "b"], vec!["1", "2"]]);
+
+        assert!(from_string("a,b\n1,2,3\n").is_err());
+    }
+
+    #[test]
+    fn test_reader() {
+        let mut rows = reader("a,b\n1,2\n3,4\n").unwrap();
+        assert_eq!(rows.next().unwrap().unwrap()["b"], "2");
+        assert_eq!(rows.next().unwrap().unwrap()["a"], "3");
+        assert!(rows.next().unwrap().is_none());
+
+        let mut rows = reader("a,b\n1\n").unwrap();
+        assert!(rows.next().is_err());
+    }
+
+    #[test]
+    fn test_to_string() {
+        let rows = vec![
+            object(&[("name", string("John")), ("age", Value::Integer(42))]),
+            object(&[("name", string("Jane")), ("email", string("j@x"))]),
+        ];
+
+        assert_eq!(
+            to_string(rows).unwrap(),
+            "age,email,name\n42,,John\n,j@x,Jane\n"
+        );
+
+        let rows = vec![
+            Value::vec(vec![Value::Integer(1), string("a,b")]),
+            Value::from(Tuple::from(vec![Value::Bool(true), Value::Unit])),
+        ];
+
+        assert_eq!(to_string(rows).unwrap(), "1,\"a,b\"\ntrue,\n");
+        assert!(to_string(vec![Value::Integer(1)]).is_err());
+    }
+}
@@ -0,0 +1,408 @@
+//! The native `db` module for the [Rune Language].
+//!
+//! [Rune Language]: https://github.com/rune-rs/rune
+//!
+//! Provides access to SQLite databases through [rusqlite].
+//!
+//! [rusqlite]: https://docs.rs/rusqlite
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = {version = "0.6.16", features = ["db"]}
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> runestick::Result<()> {
+//! let mut context = runestick::Context::with_default_modules()?;
+//! context.install(&rune_modules::db::module()?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Scripts which shouldn't be able to touch the filesystem can instead be
+//! given [module_in_memory], which only permits opening in-memory databases.
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! use db;
+//!
+//! async fn main() {
+//!     let conn = db::open_in_memory()?;
+//!     conn.execute("CREATE TABLE users (name TEXT, age INTEGER)", [])?;
+//!     conn.execute("INSERT INTO users VALUES (?, ?)", ["John", 42])?;
+//!
+//!     for row in conn.query_async("SELECT * FROM users WHERE age > ?", [18]).await? {
+//!         dbg(row.name);
+//!     }
+//! }
+//! ```
+//!
+//! Parameters are bound positionally from a vector. Rows are returned as
+//! objects keyed by column name, where `NULL` is represented as `()`.
+
+use runestick::{Bytes, ContextError, Module, Object, Shared, Value};
+use std::fmt;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+
+/// Construct the `db` module, which permits opening database files.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = module_in_memory()?;
+    module.function(&["open"], open)?;
+    Ok(module)
+}
+
+/// Construct the `db` module, which only permits opening in-memory databases.
+pub fn module_in_memory() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["db"]);
+    module.ty(&["Connection"]).build::<Connection>()?;
+    module.ty(&["Error"]).build::<Error>()?;
+    module.function(&["open_in_memory"], open_in_memory)?;
+    module.inst_fn("execute", Connection::execute)?;
+    module.inst_fn("query", Connection::query)?;
+    module.async_inst_fn("execute_async", Connection::execute_async)?;
+    module.async_inst_fn("query_async", Connection::query_async)?;
+    module.inst_fn(runestick::STRING_DISPLAY, Error::display)?;
+    Ok(module)
+}
+
+/// Open the database at the given path, creating it if it doesn't exist.
+fn open(path: &str) -> Result<Connection, Error> {
+    Ok(Connection::new(rusqlite::Connection::open(path)?))
+}
+
+/// Open a new in-memory database.
+fn open_in_memory() -> Result<Connection, Error> {
+    Ok(Connection::new(rusqlite::Connection::open_in_memory()?))
+}
+
+/// A connection to a database.
+#[derive(Debug)]
+struct Connection {
+    inner: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl Connection {
+    fn new(connection: rusqlite::Connection) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(connection)),
+        }
+    }
+
+    /// Execute a statement, returning the number of rows changed.
+    fn execute(&self, sql: &str, params: Vec<Value>) -> Result<usize, Error> {
+        let params = to_params(params)?;
+        execute(&self.inner, sql, params)
+    }
+
+    /// Run a query, returning the rows it produced.
+    fn query(&self, sql: &str, params: Vec<Value>) -> Result<Vec<Object<Value>>, Error> {
+        let params = to_params(params)?;
+        let rows = query(&self.inner, sql, params)?;
+        Ok(rows.into_iter().map(to_object).collect())
+    }
+
+    /// Execute a statement on a blocking thread, returning the number of rows
+    /// changed.
+    async fn execute_async(&self, sql: &str, params: Vec<Value>) -> Result<usize, Error> {
+        let params = to_params(params)?;
+        let inner = self.inner.clone();
+        let sql = sql.to_owned();
+        tokio::task::spawn_blocking(move || execute(&inner, &sql, params)).await?
+    }
+
+    /// Run a query on a blocking thread, returning the rows it produced.
+    async fn query_async(
+        &self,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<Vec<Object<Value>>, Error> {
+        let params = to_params(params)?;
+        let inner = self.inner.clone();
+        let sql = sql.to_owned();
+        let rows = tokio::task::spawn_blocking(move || query(&inner, &sql, params)).await??;
+        Ok(rows.into_iter().map(to_object).collect())
+    }
+}
+
+/// A row as read from the database, before it's converted into an object.
+///
+/// Rune values can't be sent across threads, so this is what's produced by
+/// blocking tasks.
+type Row = Vec<(String, rusqlite::types::Value)>;
+
+fn execute(
+    inner: &Mutex<rusqlite::Connection>,
+    sql: &str,
+    params: Vec<rusqlite::types::Value>,
+) -> Result<usize, Error> {
+    let connection = inner.lock().map_err(|_| Error::Poisoned)?;
+    Ok(connection.execute(sql, rusqlite::params_from_iter(params))?)
+}
+
+fn query(
+    inner: &Mutex<rusqlite::Connection>,
+    sql: &str,
+    params: Vec<rusqlite::types::Value>,
+) -> Result<Vec<Row>, Error> {
+    let connection = inner.lock().map_err(|_| Error::Poisoned)?;
+    let mut statement = connection.prepare(sql)?;
+
+    let columns = statement
+        .column_names()
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    let mut rows = statement.query(rusqlite::params_from_iter(params))?;
+    let mut output = Vec::new();
+
+    while let Some(row) = rows.next()? {
+        let mut values = Vec::with_capacity(columns.len());
+
+        for (n, column) in columns.iter().enumerate() {
+            values.push((column.clone(), row.get::<_, rusqlite::types::Value>(n)?));
+        }
+
+        output.push(values);
+    }
+
+    Ok(output)
+}
+
+/// Convert script values into parameters which can be bound to a statement.
+fn to_params(values: Vec<Value>) -> Result<Vec<rusqlite::types::Value>, Error> {
+    use rusqlite::types::Value as Sql;
+
+    let mut params = Vec::with_capacity(values.len());
+
+    for (index, value) in values.into_iter().enumerate() {
+        let error = |value: &Value| Error::UnsupportedParameter {
+            index,
+            actual: value
+                .type_info()
+                .map(|info| info.to_string())
+                .unwrap_or_else(|e| e.to_string()),
+        };
+
+        params.push(match &value {
+            Value::Unit => Sql::Null,
+            Value::Bool(b) => Sql::Integer(*b as i64),
+            Value::Byte(b) => Sql::Integer(*b as i64),
+            Value::Integer(n) => Sql::Integer(*n),
+            Value::Float(n) => Sql::Real(*n),
+            Value::Char(c) => Sql::Text(c.to_string()),
+            Value::StaticString(s) => Sql::Text(String::clone(s.as_ref())),
+            Value::String(s) => Sql::Text(s.borrow_ref().map_err(|_| error(&value))?.clone()),
+            Value::Bytes(b) => Sql::Blob(b.borrow_ref().map_err(|_| error(&value))?.to_vec()),
+            Value::Option(option) => {
+                let option = option.borrow_ref().map_err(|_| error(&value))?.clone();
+
+                match option {
+                    Some(inner) => to_params(vec![inner]).map_err(|_| error(&value))?.remove(0),
+                    None => Sql::Null,
+                }
+            }
+            _ => return Err(error(&value)),
+        });
+    }
+
+    Ok(params)
+}
+
+/// Convert a row into an object.
+fn to_object(row: Row) -> Object<Value> {
+    use rusqlite::types::Value as Sql;
+
+    let mut object = Object::with_capacity(row.len());
+
+    for (column, value) in row {
+        let value = match value {
+            Sql::Null => Value::Unit,
+            Sql::Integer(n) => Value::Integer(n),
+            Sql::Real(n) => Value::Float(n),
+            Sql::Text(s) => Value::String(Shared::new(s)),
+            Sql::Blob(b) => Value::Bytes(Shared::new(Bytes::from_vec(b))),
+        };
+
+        object.insert(column, value);
+    }
+
+    object
+}
+
+/// Errors raised by the database module.
+#[derive(Debug)]
+enum Error {
+    /// An error raised by the database.
+    Sqlite(rusqlite::Error),
+    /// A parameter of an unsupported type was bound.
+    UnsupportedParameter { index: usize, actual: String },
+    /// The connection was poisoned by a panic in another thread.
+    Poisoned,
+    /// A blocking task failed.
+    Task(tokio::task::JoinError),
+}
+
+impl Error {
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        write!(buf, "{}", self)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sqlite(error) => write!(fmt, "{}", error),
+            Self::UnsupportedParameter { index, actual } => {
+                write!(fmt, "unsupported parameter `{}` at index {}", actual, index)
+            }
+            Self::Poisoned => write!(fmt, "connection is poisoned"),
+            Self::Task(error) => write!(fmt, "{}", error),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(error: rusqlite::Error) -> Self {
+        Self::Sqlite(error)
+    }
+}
+
+impl From<tokio::task::JoinError> for Error {
+    fn from(error: tokio::task::JoinError) -> Self {
+        Self::Task(error)
+    }
+}
+
+runestick::impl_external!(Connection);
+runestick::impl_external!(Error);
+
+#[cfg(test)]
+mod tests {
+    use super::{open, open_in_memory, Connection, Error};
+    use runestick::{Bytes, Object, Value};
+
+    fn string(string: &str) -> Value {
+        Value::from(String::from(string))
+    }
+
+    fn round_trip(conn: &Connection) -> Result<Vec<Object<Value>>, Error> {
+        conn.execute(
+            "CREATE TABLE users (name TEXT, age INTEGER, score REAL, avatar BLOB)",
+            vec![],
+        )?;
+
+        let inserted = conn.execute(
+            "INSERT INTO users VALUES (?, ?, ?, ?)",
+            vec![
+                string("John"),
+                Value::Integer(42),
+                Value::Float(0.5),
+                Value::from(Bytes::from_vec(vec![1, 2, 3])),
+            ],
+        )?;
+        assert_eq!(inserted, 1);
+
+        let inserted = conn.execute(
+            "INSERT INTO users VALUES (?, ?, ?, ?)",
+            vec![
+                string("Jane"),
+                Value::Integer(17),
+                Value::Option(runestick::Shared::new(None)),
+                Value::Unit,
+            ],
+        )?;
+        assert_eq!(inserted, 1);
+
+        conn.query(
+            "SELECT * FROM users WHERE age > ? ORDER BY name",
+            vec![Value::Integer(0)],
+        )
+    }
+
+    fn assert_rows(rows: Vec<Object<Value>>) {
+        assert_eq!(rows.len(), 2);
+
+        let jane = &rows[0];
+        assert_eq!(
+            &*jane["name"]
+                .clone()
+                .into_string()
+                .unwrap()
+                .borrow_ref()
+                .unwrap(),
+            "Jane"
+        );
+        assert!(matches!(jane["age"], Value::Integer(17)));
+        assert!(matches!(jane["score"], Value::Unit));
+        assert!(matches!(jane["avatar"], Value::Unit));
+
+        let john = &rows[1];
+        assert_eq!(
+            &*john["name"]
+                .clone()
+                .into_string()
+                .unwrap()
+                .borrow_ref()
+                .unwrap(),
+            "John"
+        );
+        assert!(matches!(john["age"], Value::Integer(42)));
+        assert!(matches!(john["score"], Value::Float(n) if n == 0.5));
+        assert_eq!(
+            &**john["avatar"]
+                .clone()
+                .into_bytes()
+                .unwrap()
+                .borrow_ref()
+                .unwrap(),
+            &[1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_in_memory_round_trip() {
+        let conn = open_in_memory().unwrap();
+        assert_rows(round_trip(&conn).unwrap());
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("rune-db-test-{}.sqlite", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let path_str = path.to_str().unwrap();
+
+        let result = round_trip(&open(path_str).unwrap());
+
+        // NB: reopen the database to check that the rows were persisted.
+        let rows = result.and_then(|_| {
+            open(path_str)
+                .unwrap()
+                .query("SELECT * FROM users ORDER BY name", vec![])
+        });
+
+        let _ = std::fs::remove_file(&path);
+        assert_rows(rows.unwrap());
+    }
+
+    #[test]
+    fn test_unsupported_parameter() {
+        let conn = open_in_memory().unwrap();
+
+        let error = conn
+            .execute("SELECT ?, ?", vec![Value::Integer(1), Value::vec(vec![])])
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            Error::UnsupportedParameter { index: 1, .. }
+        ));
+    }
+}
@@ -0,0 +1,161 @@
+//! The native `hash` module for the [Rune Language].
+//!
+//! [Rune Language]: https://github.com/rune-rs/rune
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = {version = "0.6.16", features = ["hash"]}
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> runestick::Result<()> {
+//! let mut context = runestick::Context::with_default_modules()?;
+//! context.install(&rune_modules::hash::module()?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! use hash;
+//!
+//! fn verify(secret, body, signature) {
+//!     let expected = `sha256={hash::hmac_sha256(secret, body)}`;
+//!     hash::constant_time_eq(expected, signature)
+//! }
+//!
+//! fn main() {
+//!     dbg(hash::sha256("hello world"));
+//! }
+//! ```
+//!
+//! All functions accept either strings or bytes as input, and digests are
+//! returned as lowercase hex strings.
+
+use hmac::Mac as _;
+use runestick::modules::encoding::{encode_hex, with_input};
+use runestick::{ContextError, Module, Value, VmError};
+use sha2::Digest as _;
+use subtle::ConstantTimeEq as _;
+
+/// Construct the `hash` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["hash"]);
+    module.function(&["sha256"], sha256)?;
+    module.function(&["sha1"], sha1)?;
+    module.function(&["md5"], md5)?;
+    module.function(&["hmac_sha256"], hmac_sha256)?;
+    module.function(&["constant_time_eq"], constant_time_eq)?;
+    Ok(module)
+}
+
+/// Calculate the SHA-256 digest of the input.
+fn sha256(input: Value) -> Result<String, VmError> {
+    with_input(&input, |input| encode_hex(&sha2::Sha256::digest(input)))
+}
+
+/// Calculate the SHA-1 digest of the input.
+fn sha1(input: Value) -> Result<String, VmError> {
+    with_input(&input, |input| encode_hex(&sha1::Sha1::digest(input)))
+}
+
+/// Calculate the MD5 digest of the input.
+fn md5(input: Value) -> Result<String, VmError> {
+    with_input(&input, |input| encode_hex(&md5::Md5::digest(input)))
+}
+
+/// Calculate the HMAC-SHA256 of the input using the given key.
+fn hmac_sha256(key: Value, input: Value) -> Result<String, VmError> {
+    let mut mac = with_input(&key, |key| {
+        hmac::Hmac::<sha2::Sha256>::new_from_slice(key).expect("hmac accepts keys of any size")
+    })?;
+
+    with_input(&input, |input| mac.update(input))?;
+    Ok(encode_hex(&mac.finalize().into_bytes()))
+}
+
+/// Compare two inputs for equality in constant time, to avoid leaking how much
+/// of a secret matched through timing.
+///
+/// Only the contents are compared in constant time, inputs of different
+/// lengths are never equal.
+fn constant_time_eq(a: Value, b: Value) -> Result<bool, VmError> {
+    with_input(&a, |a| with_input(&b, |b| bool::from(a.ct_eq(b))))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{constant_time_eq, hmac_sha256, md5, sha1, sha256};
+    use runestick::{Bytes, Value};
+
+    fn string(string: &str) -> Value {
+        Value::from(String::from(string))
+    }
+
+    fn bytes(bytes: &[u8]) -> Value {
+        Value::from(Bytes::from_vec(bytes.to_vec()))
+    }
+
+    #[test]
+    fn test_sha256() {
+        // FIPS 180-2, appendix B.
+        assert_eq!(
+            sha256(string("abc")).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            sha256(bytes(b"")).unwrap(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_sha1() {
+        // FIPS 180-2, appendix A.
+        assert_eq!(
+            sha1(string("abc")).unwrap(),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        assert_eq!(
+            sha1(bytes(b"")).unwrap(),
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709"
+        );
+    }
+
+    #[test]
+    fn test_md5() {
+        // RFC 1321, appendix A.5.
+        assert_eq!(md5(string("")).unwrap(), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(
+            md5(bytes(b"abc")).unwrap(),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+    }
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231, test cases 1 and 2.
+        assert_eq!(
+            hmac_sha256(bytes(&[0x0b; 20]), string("Hi There")).unwrap(),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+        assert_eq!(
+            hmac_sha256(string("Jefe"), string("what do ya want for nothing?")).unwrap(),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(string("secret"), bytes(b"secret")).unwrap());
+        assert!(!constant_time_eq(string("secret"), string("secreT")).unwrap());
+        assert!(!constant_time_eq(string("secret"), string("secrets")).unwrap());
+        assert!(constant_time_eq(Value::Integer(1), string("1")).is_err());
+    }
+}
@@ -42,6 +42,13 @@
 //! * [fs]
 //! * [process]
 //! * [signal]
+//! * [csv]
+//! * [hash]
+//! * [uuid]
+//! * [template]
+//! * [db]
+//! * [msgpack]
+//! * [cbor]
 //!
 //! ## Features
 //!
@@ -53,6 +60,13 @@
 //! * `fs` for the [fs module]][fs]
 //! * `process` for the [process module]][process]
 //! * `signal` for the [process module]][signal]
+//! * `csv` for the [csv module][csv]
+//! * `hash` for the [hash module][hash]
+//! * `uuid` for the [uuid module][uuid]
+//! * `template` for the [template module][template]
+//! * `db` for the [db module][db]
+//! * `msgpack` for the [msgpack module][msgpack]
+//! * `cbor` for the [cbor module][cbor]
 //!
 //! [http]: https://docs.rs/rune-modules/0/rune_modules/http/
 //! [json]: https://docs.rs/rune-modules/0/rune_modules/json/
@@ -61,6 +75,13 @@
 //! [fs]: https://docs.rs/rune-modules/0/rune_modules/fs/
 //! [process]: https://docs.rs/rune-modules/0/rune_modules/process/
 //! [signal]: https://docs.rs/rune-modules/0/rune_modules/signal/
+//! [csv]: https://docs.rs/rune-modules/0/rune_modules/csv/
+//! [hash]: https://docs.rs/rune-modules/0/rune_modules/hash/
+//! [uuid]: https://docs.rs/rune-modules/0/rune_modules/uuid/
+//! [template]: https://docs.rs/rune-modules/0/rune_modules/template/
+//! [db]: https://docs.rs/rune-modules/0/rune_modules/db/
+//! [msgpack]: https://docs.rs/rune-modules/0/rune_modules/msgpack/
+//! [cbor]: https://docs.rs/rune-modules/0/rune_modules/cbor/
 
 #[cfg(feature = "http")]
 pub mod http;
@@ -82,3 +103,24 @@ pub mod process;
 
 #[cfg(feature = "signal")]
 pub mod signal;
+
+#[cfg(feature = "csv")]
+pub mod csv;
+
+#[cfg(feature = "hash")]
+pub mod hash;
+
+#[cfg(feature = "uuid")]
+pub mod uuid;
+
+#[cfg(feature = "template")]
+pub mod template;
+
+#[cfg(feature = "db")]
+pub mod db;
+
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+
+#[cfg(feature = "cbor")]
+pub mod cbor;
@@ -0,0 +1,158 @@
+//! The native `msgpack` module for the [Rune Language].
+//!
+//! [Rune Language]: https://github.com/rune-rs/rune
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = {version = "0.6.16", features = ["msgpack"]}
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> runestick::Result<()> {
+//! let mut context = runestick::Context::with_default_modules()?;
+//! context.install(&rune_modules::msgpack::module()?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! use msgpack;
+//!
+//! fn main() {
+//!     let bytes = msgpack::to_bytes(#{"key": 42})?;
+//!     dbg(msgpack::from_bytes(bytes)?);
+//! }
+//! ```
+//!
+//! The same encoding is available to Rust through [to_vec] and [from_slice].
+
+use runestick::{Bytes, ContextError, Module, Value};
+
+/// Construct the `msgpack` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["msgpack"]);
+    module.function(&["from_bytes"], from_bytes)?;
+    module.function(&["to_bytes"], to_bytes)?;
+    Ok(module)
+}
+
+/// Serialize a value as MessagePack.
+///
+/// Objects are encoded as maps keyed by strings.
+pub fn to_vec(value: &Value) -> runestick::Result<Vec<u8>> {
+    Ok(rmp_serde::to_vec_named(value)?)
+}
+
+/// Deserialize a value from MessagePack.
+pub fn from_slice(bytes: &[u8]) -> runestick::Result<Value> {
+    Ok(rmp_serde::from_slice(bytes)?)
+}
+
+/// Get value from msgpack bytes.
+fn from_bytes(bytes: &[u8]) -> runestick::Result<Value> {
+    from_slice(bytes)
+}
+
+/// Convert any value to msgpack bytes.
+fn to_bytes(value: Value) -> runestick::Result<Bytes> {
+    Ok(Bytes::from_vec(to_vec(&value)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_slice, to_vec};
+    use runestick::{Bytes, Object, Shared, Tuple, Value};
+
+    fn string(string: &str) -> Value {
+        Value::from(String::from(string))
+    }
+
+    fn round_trip(value: Value) -> Value {
+        from_slice(&to_vec(&value).unwrap()).unwrap()
+    }
+
+    fn into_string(value: Value) -> String {
+        value.into_string().unwrap().take().unwrap()
+    }
+
+    #[test]
+    fn test_round_trip_scalars() {
+        assert!(matches!(round_trip(Value::Unit), Value::Unit));
+        assert!(matches!(round_trip(Value::Bool(true)), Value::Bool(true)));
+        assert!(matches!(
+            round_trip(Value::Integer(-42)),
+            Value::Integer(-42)
+        ));
+        assert!(matches!(
+            round_trip(Value::Integer(i64::MAX)),
+            Value::Integer(i64::MAX)
+        ));
+        assert!(matches!(round_trip(Value::Float(0.5)), Value::Float(n) if n == 0.5));
+        assert_eq!(into_string(round_trip(string("hello"))), "hello");
+
+        let bytes = round_trip(Value::from(Bytes::from_vec(vec![0, 1, 255])));
+        assert_eq!(
+            bytes.into_bytes().unwrap().take().unwrap().into_vec(),
+            vec![0, 1, 255]
+        );
+    }
+
+    #[test]
+    fn test_round_trip_collections() {
+        let mut inner = Object::new();
+        inner.insert(String::from("name"), string("John"));
+        inner.insert(String::from("none"), Value::Option(Shared::new(None)));
+
+        let mut object = Object::new();
+        object.insert(
+            String::from("vec"),
+            Value::vec(vec![Value::Integer(1), string("two")]),
+        );
+        object.insert(
+            String::from("tuple"),
+            Value::from(Tuple::from(vec![Value::Bool(false), Value::Float(1.5)])),
+        );
+        object.insert(String::from("inner"), Value::Object(Shared::new(inner)));
+
+        let object = round_trip(Value::Object(Shared::new(object)))
+            .into_object()
+            .unwrap()
+            .take()
+            .unwrap();
+        assert_eq!(object.len(), 3);
+
+        let vec = object["vec"].clone().into_vec().unwrap().take().unwrap();
+        assert_eq!(vec.len(), 2);
+        assert!(matches!(vec[0], Value::Integer(1)));
+        assert_eq!(into_string(vec[1].clone()), "two");
+
+        // NB: tuples are encoded as sequences, so they're decoded as vectors.
+        let tuple = object["tuple"].clone().into_vec().unwrap().take().unwrap();
+        assert_eq!(tuple.len(), 2);
+        assert!(matches!(tuple[0], Value::Bool(false)));
+        assert!(matches!(tuple[1], Value::Float(n) if n == 1.5));
+
+        let inner = object["inner"]
+            .clone()
+            .into_object()
+            .unwrap()
+            .take()
+            .unwrap();
+        assert_eq!(inner.len(), 2);
+        assert_eq!(into_string(inner["name"].clone()), "John");
+        assert!(matches!(inner["none"], Value::Unit));
+    }
+
+    #[test]
+    fn test_unsupported_values() {
+        assert!(to_vec(&Value::Result(Shared::new(Ok(Value::Unit)))).is_err());
+        assert!(from_slice(&[]).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! The native `template` module for the [Rune Language].
+//!
+//! [Rune Language]: https://github.com/rune-rs/rune
+//!
+//! Renders text templates with loops and conditionals, which is useful for
+//! generating configuration files or emails from scripts. Templates use the
+//! [Jinja2] syntax as implemented by [minijinja].
+//!
+//! [Jinja2]: https://jinja.palletsprojects.com/
+//! [minijinja]: https://docs.rs/minijinja
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = {version = "0.6.16", features = ["template"]}
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> runestick::Result<()> {
+//! let mut context = runestick::Context::with_default_modules()?;
+//! context.install(&rune_modules::template::module()?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! use template;
+//!
+//! fn main() {
+//!     let output = template::render("
+//! {% for user in users %}
+//! {{ user.name }}{% if user.admin %} (admin){% endif %}
+//! {% endfor %}
+//! ", #{"users": [#{"name": "John", "admin": true}]})?;
+//!
+//!     print(output);
+//! }
+//! ```
+//!
+//! Variables which are not defined in the provided values are reported as
+//! errors, rather than being rendered as empty strings. Block tags don't leave
+//! behind the newline that follows them, so multi-line templates render the
+//! way they read.
+//!
+//! Note that templates should be written as regular strings, since braces in
+//! template literals are interpreted as Rune expressions.
+
+use runestick::{ContextError, Module, Value};
+use std::fmt;
+use std::fmt::Write as _;
+
+/// Construct the `template` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["template"]);
+    module.ty(&["Error"]).build::<Error>()?;
+    module.function(&["render"], render)?;
+    module.inst_fn(runestick::STRING_DISPLAY, Error::display)?;
+    Ok(module)
+}
+
+/// Render the given template source, with values pulled from the given
+/// object.
+fn render(source: &str, values: Value) -> Result<String, Error> {
+    let mut env = minijinja::Environment::new();
+    env.set_undefined_behavior(minijinja::UndefinedBehavior::Strict);
+    env.set_trim_blocks(true);
+    env.set_keep_trailing_newline(true);
+    env.render_str(source, &values).map_err(Error)
+}
+
+/// Error raised when a template fails to render.
+#[derive(Debug)]
+struct Error(minijinja::Error);
+
+impl Error {
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        write!(buf, "{}", self.0)
+    }
+}
+
+runestick::impl_external!(Error);
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+    use runestick::{Object, Shared, Value};
+
+    fn values(fields: &[(&str, Value)]) -> Value {
+        let mut object = Object::new();
+
+        for (key, value) in fields {
+            object.insert(String::from(*key), value.clone());
+        }
+
+        Value::Object(Shared::new(object))
+    }
+
+    fn string(string: &str) -> Value {
+        Value::from(String::from(string))
+    }
+
+    #[test]
+    fn test_substitution() {
+        let users = Value::vec(vec![
+            values(&[("name", string("John")), ("admin", Value::Bool(true))]),
+            values(&[("name", string("Jane")), ("admin", Value::Bool(false))]),
+        ]);
+
+        let output = render(
+            "{% for user in users %}\n{% if user.admin %}* {% endif %}{{ user.name }}\n{% endfor %}\n",
+            values(&[("users", users)]),
+        )
+        .unwrap();
+
+        assert_eq!(output, "* John\nJane\n");
+
+        let output = render(
+            "{{ a + b }} {{ c }}",
+            values(&[
+                ("a", Value::Integer(1)),
+                ("b", Value::Integer(2)),
+                ("c", Value::Float(0.5)),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(output, "3 0.5");
+    }
+
+    #[test]
+    fn test_missing_keys() {
+        let error = render("Hello {{ name }}", values(&[])).unwrap_err();
+        assert!(error.0.to_string().contains("undefined"), "{}", error.0);
+
+        let error = render("{{ user.name }}", values(&[("user", values(&[]))])).unwrap_err();
+        assert!(error.0.to_string().contains("undefined"), "{}", error.0);
+
+        let mut buf = String::new();
+        error.display(&mut buf).unwrap();
+        assert!(!buf.is_empty());
+    }
+
+    #[test]
+    fn test_escaping() {
+        let values = values(&[("html", string("<a href=\"x\">&</a>"))]);
+
+        // Strings are rendered as-is unless explicitly escaped.
+        assert_eq!(
+            render("{{ html }}", values.clone()).unwrap(),
+            "<a href=\"x\">&</a>"
+        );
+        assert_eq!(
+            render("{{ html|e }}", values.clone()).unwrap(),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;&#x2f;a&gt;"
+        );
+        assert_eq!(
+            render("{% raw %}{{ html }}{% endraw %}", values).unwrap(),
+            "{{ html }}"
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! The native `uuid` module for the [Rune Language].
+//!
+//! [Rune Language]: https://github.com/rune-rs/rune
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = {version = "0.6.16", features = ["uuid"]}
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> runestick::Result<()> {
+//! let mut context = runestick::Context::with_default_modules()?;
+//! context.install(&rune_modules::uuid::module()?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! If scripts need to be reproducible, for example in tests, the module can
+//! instead be constructed with [module_with_seed], which generates the same
+//! sequence of UUIDs for a given seed.
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! use uuid;
+//!
+//! fn main() {
+//!     let id = uuid::v4();
+//!     let parsed = uuid::parse(`{id}`)?;
+//!     dbg(id == parsed);
+//! }
+//! ```
+
+use runestick::{Bytes, ContextError, Module};
+use std::fmt;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Construct the `uuid` module, which generates random UUIDs using the
+/// randomness of the operating system.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = base_module()?;
+    module.function(&["v4"], || Uuid(uuid_::Uuid::new_v4()))?;
+    Ok(module)
+}
+
+/// Construct the `uuid` module, which deterministically generates UUIDs from
+/// the given seed.
+///
+/// Note that the generator is shared by every context the module is installed
+/// into.
+pub fn module_with_seed(seed: u64) -> Result<Module, ContextError> {
+    let state = Arc::new(AtomicU64::new(seed));
+
+    let mut module = base_module()?;
+    module.function(&["v4"], move || {
+        let mut bytes = [0u8; 16];
+        bytes[..8].copy_from_slice(&splitmix64(&state).to_le_bytes());
+        bytes[8..].copy_from_slice(&splitmix64(&state).to_le_bytes());
+        Uuid(uuid_::Builder::from_random_bytes(bytes).into_uuid())
+    })?;
+    Ok(module)
+}
+
+/// Functions shared by all variants of the module.
+fn base_module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["uuid"]);
+    module.ty(&["Uuid"]).build::<Uuid>()?;
+    module.ty(&["Error"]).build::<Error>()?;
+    module.function(&["parse"], parse)?;
+    module.function(&["nil"], || Uuid(uuid_::Uuid::nil()))?;
+    module.inst_fn("to_string", Uuid::to_string)?;
+    module.inst_fn("to_bytes", Uuid::to_bytes)?;
+    module.inst_fn("version", Uuid::version)?;
+    module.inst_fn("is_nil", Uuid::is_nil)?;
+    module.inst_fn(runestick::EQ, Uuid::eq)?;
+    module.inst_fn(runestick::STRING_DISPLAY, Uuid::display)?;
+    module.inst_fn(runestick::STRING_DEBUG, Uuid::display)?;
+    module.inst_fn(runestick::STRING_DISPLAY, Error::display)?;
+    Ok(module)
+}
+
+/// Advance the given splitmix64 generator state, returning the next number.
+fn splitmix64(state: &AtomicU64) -> u64 {
+    let mut z = state
+        .fetch_add(0x9e37_79b9_7f4a_7c15, Ordering::Relaxed)
+        .wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Parse a UUID from its string representation, in any of the hyphenated,
+/// simple, braced or urn formats.
+fn parse(string: &str) -> Result<Uuid, Error> {
+    Ok(Uuid(uuid_::Uuid::parse_str(string).map_err(Error)?))
+}
+
+/// A universally unique identifier.
+#[derive(Debug, Clone, Copy)]
+struct Uuid(uuid_::Uuid);
+
+impl Uuid {
+    /// Get the 16 bytes of the UUID.
+    fn to_bytes(&self) -> Bytes {
+        Bytes::from_vec(self.0.as_bytes().to_vec())
+    }
+
+    /// Get the version number of the UUID.
+    fn version(&self) -> usize {
+        self.0.get_version_num()
+    }
+
+    /// Test if this is the nil UUID.
+    fn is_nil(&self) -> bool {
+        self.0.is_nil()
+    }
+
+    fn eq(&self, other: &Uuid) -> bool {
+        self.0 == other.0
+    }
+
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        write!(buf, "{}", self)
+    }
+}
+
+impl fmt::Display for Uuid {
+    /// Format the UUID in its lowercase hyphenated form.
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}", self.0)
+    }
+}
+
+/// Error raised when parsing an invalid UUID.
+#[derive(Debug)]
+struct Error(uuid_::Error);
+
+impl Error {
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        write!(buf, "{}", self.0)
+    }
+}
+
+runestick::impl_external!(Uuid);
+runestick::impl_external!(Error);
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, splitmix64, Uuid};
+    use std::sync::atomic::AtomicU64;
+
+    const ID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+    #[test]
+    fn test_parse_formats() {
+        let id = parse(ID).unwrap();
+
+        for string in &[
+            "67e5504410b1426f9247bb680e5fe0c8",
+            "{67e55044-10b1-426f-9247-bb680e5fe0c8}",
+            "urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "67E55044-10B1-426F-9247-BB680E5FE0C8",
+        ] {
+            assert!(id.eq(&parse(string).unwrap()), "{}", string);
+        }
+
+        assert!(parse("67e55044-10b1-426f-9247").is_err());
+        assert!(parse("not a uuid").is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        let id = parse("{67E55044-10B1-426F-9247-BB680E5FE0C8}").unwrap();
+        assert_eq!(id.to_string(), ID);
+
+        let mut buf = String::new();
+        id.display(&mut buf).unwrap();
+        assert_eq!(buf, ID);
+    }
+
+    #[test]
+    fn test_properties() {
+        let id = parse(ID).unwrap();
+        assert_eq!(id.version(), 4);
+        assert!(!id.is_nil());
+        assert_eq!(id.to_bytes().len(), 16);
+        assert_eq!(id.to_bytes()[..2], [0x67, 0xe5]);
+
+        let nil = Uuid(uuid_::Uuid::nil());
+        assert!(nil.is_nil());
+        assert_eq!(nil.version(), 0);
+        assert!(!nil.eq(&id));
+    }
+
+    #[test]
+    fn test_splitmix64() {
+        // Reference outputs of splitmix64 seeded with zero.
+        let state = AtomicU64::new(0);
+        assert_eq!(splitmix64(&state), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(splitmix64(&state), 0x6e78_9e6a_a1b9_65f4);
+        assert_eq!(splitmix64(&state), 0x06c4_5d18_8009_454f);
+    }
+}
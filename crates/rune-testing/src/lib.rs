@@ -36,6 +36,9 @@
 //!
 //! [Rune Language]: https://github.com/rune-rs/rune
 
+mod listing;
+
+pub use self::listing::{check_golden_file, diff_listings, instruction_listing};
 pub use futures_executor::block_on;
 pub use rune::CompileError::*;
 pub use rune::ParseError::*;
@@ -81,6 +84,22 @@ where
     T: runestick::FromValue,
 {
     let context = runestick::Context::with_default_modules()?;
+    run_with_context_async(context, function, args, source).await
+}
+
+/// Call the specified function in the given script, using a custom context.
+pub async fn run_with_context_async<N, A, T>(
+    context: runestick::Context,
+    function: N,
+    args: A,
+    source: &str,
+) -> Result<T>
+where
+    N: IntoIterator,
+    N::Item: Into<Component>,
+    A: runestick::Args,
+    T: runestick::FromValue,
+{
     let (unit, _) = compile_source(&context, &source)?;
 
     let vm = runestick::Vm::new(Arc::new(context), Arc::new(unit));
@@ -89,6 +108,22 @@ where
     Ok(T::from_value(output)?)
 }
 
+/// Call the specified function in the given script, using a custom context.
+pub fn run_with_context<N, A, T>(
+    context: runestick::Context,
+    function: N,
+    args: A,
+    source: &str,
+) -> Result<T>
+where
+    N: IntoIterator,
+    N::Item: Into<Component>,
+    A: runestick::Args,
+    T: runestick::FromValue,
+{
+    block_on(run_with_context_async(context, function, args, source))
+}
+
 /// Call the specified function in the given script.
 pub fn run<N, A, T>(function: N, args: A, source: &str) -> Result<T>
 where
@@ -276,7 +311,7 @@ macro_rules! assert_warnings {
 
             match warning.kind {
                 $pat => ($cond),
-                warning => {
+                ref warning => {
                     panic!("expected warning `{}` but was `{:?}`", stringify!($pat), warning);
                 }
             }
@@ -285,3 +320,63 @@ macro_rules! assert_warnings {
         assert!(it.next().is_none(), "there should be no more warnings");
     }};
 }
+
+/// Assert that the given rune program compiles into the expected listing of
+/// instructions, as produced by [instruction_listing].
+///
+/// Indentation and empty lines are ignored when comparing listings.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune_testing::*;
+///
+/// # fn main() {
+/// assert_instructions! {
+///     r#"fn main() { let a = 1; a + 2 }"#,
+///     r#"
+///     fn main():
+///       0000 = integer 1
+///       0001 = copy 0 // var `a`
+///       0002 = integer 2
+///       0003 = add
+///       0004 = clean 1
+///       0005 = return
+///     "#
+/// };
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_instructions {
+    ($source:expr, $expected:expr) => {{
+        let context = runestick::Context::with_default_modules().unwrap();
+        let (unit, _) = $crate::compile_source(&context, $source).expect("source should compile");
+        let actual = $crate::instruction_listing(&context, &unit);
+
+        if let Some(diff) = $crate::diff_listings($expected, &actual) {
+            panic!("instructions don't match:\n{}", diff);
+        }
+    }};
+}
+
+/// Assert that the given rune program compiles into the listing of
+/// instructions stored in the golden file `tests/golden/<name>.txt` of the
+/// crate being tested.
+///
+/// Run the tests with the `RUNE_BLESS` environment variable set to create or
+/// update the golden files. See [check_golden_file].
+#[macro_export]
+macro_rules! assert_golden {
+    ($name:expr, $source:expr) => {{
+        let context = runestick::Context::with_default_modules().unwrap();
+        let (unit, _) = $crate::compile_source(&context, $source).expect("source should compile");
+        let actual = $crate::instruction_listing(&context, &unit);
+
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("golden")
+            .join(format!("{}.txt", $name));
+
+        $crate::check_golden_file(path, &actual);
+    }};
+}
@@ -0,0 +1,207 @@
+//! Listings of the instructions in a unit, used to test the code generated by
+//! the compiler.
+
+use runestick::{Context, ContextSignature, Hash, Inst, Unit};
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Produce a listing of all the instructions in the given unit.
+///
+/// Every function starts with a header containing its signature, and every
+/// instruction is printed on its own line with its offset. Hashes are resolved
+/// to the names of the functions and types they refer to, and the static
+/// strings and object keys used by an instruction are shown in a trailing
+/// comment, so that the listing is readable and stable across changes which
+/// don't affect the generated code.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::Context;
+///
+/// let context = Context::with_default_modules().unwrap();
+/// let (unit, _) = rune_testing::compile_source(&context, "fn main() { 1 + 2 }").unwrap();
+///
+/// assert_eq! {
+///     rune_testing::instruction_listing(&context, &unit),
+///     "fn main():\n  0000 = integer 1\n  0001 = integer 2\n  0002 = add\n  0003 = return\n",
+/// };
+/// ```
+pub fn instruction_listing(context: &Context, unit: &Unit) -> String {
+    let mut out = String::new();
+    let debug = unit.debug_info();
+
+    for (n, inst) in unit.iter_instructions().enumerate() {
+        if let Some((_, signature)) = debug.and_then(|d| d.function_at(n)) {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+
+            writeln!(out, "fn {}:", signature).unwrap();
+        }
+
+        let info = debug.and_then(|d| d.instruction_at(n));
+
+        if let Some(label) = info.and_then(|d| d.label.as_ref()) {
+            writeln!(out, "{}:", label).unwrap();
+        }
+
+        write!(out, "  {:04} = {}", n, format_inst(context, unit, inst)).unwrap();
+
+        let comments = static_comment(unit, inst)
+            .into_iter()
+            .chain(info.and_then(|d| d.comment.clone()))
+            .collect::<Vec<_>>();
+
+        if !comments.is_empty() {
+            write!(out, " // {}", comments.join("; ")).unwrap();
+        }
+
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Compare two listings, ignoring indentation and empty lines.
+///
+/// Returns a description of the lines which differ if they don't match.
+pub fn diff_listings(expected: &str, actual: &str) -> Option<String> {
+    let expected = normalize(expected);
+    let actual = normalize(actual);
+
+    if expected == actual {
+        return None;
+    }
+
+    let mut out = String::new();
+
+    for n in 0..usize::max(expected.len(), actual.len()) {
+        match (expected.get(n), actual.get(n)) {
+            (Some(e), Some(a)) if e == a => {
+                writeln!(out, "  {}", e).unwrap();
+            }
+            (e, a) => {
+                if let Some(e) = e {
+                    writeln!(out, "- {}", e).unwrap();
+                }
+
+                if let Some(a) = a {
+                    writeln!(out, "+ {}", a).unwrap();
+                }
+            }
+        }
+    }
+
+    Some(out)
+}
+
+/// Check the listing against the golden file at the given path.
+///
+/// If the `RUNE_BLESS` environment variable is set, the golden file is
+/// written with the listing instead, which is how golden files are created
+/// and updated after an intended change to the generated code.
+///
+/// # Panics
+///
+/// Panics if the golden file is missing, or if it doesn't match the listing.
+pub fn check_golden_file<P>(path: P, actual: &str)
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+
+    if std::env::var_os("RUNE_BLESS").is_some() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+
+        std::fs::write(path, actual).unwrap();
+        return;
+    }
+
+    let expected = match std::fs::read_to_string(path) {
+        Ok(expected) => expected,
+        Err(e) => panic!(
+            "failed to read golden file `{}`: {} (run with RUNE_BLESS=1 to create it)",
+            path.display(),
+            e
+        ),
+    };
+
+    if let Some(diff) = diff_listings(&expected, actual) {
+        panic!(
+            "instructions don't match golden file `{}` (run with RUNE_BLESS=1 to update it):\n{}",
+            path.display(),
+            diff
+        );
+    }
+}
+
+/// Split a listing into lines, ignoring indentation and empty lines.
+fn normalize(listing: &str) -> Vec<&str> {
+    listing
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Format a single instruction, with the hashes it contains resolved to names.
+fn format_inst(context: &Context, unit: &Unit, inst: Inst) -> String {
+    let name = |hash| resolve_hash(context, unit, hash);
+
+    match inst {
+        Inst::Call { hash, args } => format!("call {}, {}", name(hash), args),
+        Inst::Fn { hash } => format!("fn {}", name(hash)),
+        Inst::Closure { hash, count } => format!("closure {}, {}", name(hash), count),
+        Inst::Type { hash } => format!("type {}", name(hash)),
+        Inst::TypedObject { hash, slot } => format!("typed-object {}, {}", name(hash), slot),
+        Inst::VariantObject {
+            enum_hash,
+            hash,
+            slot,
+        } => format!(
+            "variant-object {}, {}, {}",
+            name(enum_hash),
+            name(hash),
+            slot
+        ),
+        inst => inst.to_string(),
+    }
+}
+
+/// Resolve the given hash to the name of the function or type it refers to,
+/// falling back to the hash itself.
+fn resolve_hash(context: &Context, unit: &Unit, hash: Hash) -> String {
+    if let Some(signature) = unit.debug_info().and_then(|d| d.functions.get(&hash)) {
+        return signature.path.to_string();
+    }
+
+    if let Some(name) = unit.lookup_type_name(hash) {
+        return name.to_string();
+    }
+
+    match context.lookup_signature(hash) {
+        Some(ContextSignature::Function { path, .. }) => path.to_string(),
+        Some(ContextSignature::Instance { path, name, .. }) => format!("{}::{}", path, name),
+        None => hash.to_string(),
+    }
+}
+
+/// Describe the static strings or object keys used by an instruction.
+fn static_comment(unit: &Unit, inst: Inst) -> Option<String> {
+    match inst {
+        Inst::String { slot }
+        | Inst::EqStaticString { slot }
+        | Inst::ObjectSlotIndexGet { slot }
+        | Inst::ObjectSlotIndexGetAt { slot, .. } => {
+            Some(format!("{:?}", unit.lookup_string(slot).ok()?))
+        }
+        Inst::Object { slot }
+        | Inst::TypedObject { slot, .. }
+        | Inst::VariantObject { slot, .. }
+        | Inst::MatchObject { slot, .. } => Some(format!("{:?}", unit.lookup_object_keys(slot)?)),
+        _ => None,
+    }
+}
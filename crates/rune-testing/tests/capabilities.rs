@@ -0,0 +1,158 @@
+use rune::capabilities::{self, Capability, CapabilityReport, Restrictions};
+use runestick::{Context, Hash, Item, Module, Source};
+use std::collections::BTreeSet;
+
+#[derive(Debug)]
+struct Socket;
+
+runestick::impl_external!(Socket);
+
+impl Socket {
+    fn send(&self, data: i64) -> i64 {
+        data
+    }
+}
+
+fn analyze(source: &str, restrictions: &Restrictions) -> CapabilityReport {
+    let mut fs = Module::new(&["fs"]);
+    fs.function(&["remove"], |path: String| drop(path)).unwrap();
+
+    let mut net = Module::new(&["http"]);
+    net.ty(&["Socket"]).build::<Socket>().unwrap();
+    net.function(&["Socket", "new"], || Socket).unwrap();
+    net.inst_fn("send", Socket::send).unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&fs).unwrap();
+    context.install(&net).unwrap();
+
+    let mut sources = rune::Sources::new();
+    sources.insert_default(Source::new("main", source.to_owned()));
+
+    let unit = rune::load_sources(
+        &context,
+        &Default::default(),
+        &mut sources,
+        &mut Default::default(),
+    )
+    .unwrap();
+
+    capabilities::analyze(&context, &unit, restrictions)
+}
+
+fn reachable(report: &CapabilityReport, name: &str) -> BTreeSet<Capability> {
+    report.reachable_from(Hash::type_hash(&Item::of(&[name])))
+}
+
+#[test]
+fn test_transitive_capabilities() {
+    let report = analyze(
+        r#"
+        fn remove() { fs::remove("target") }
+        fn send() { let socket = http::Socket::new(); socket.send(42) }
+        fn both() { remove(); send() }
+        fn pure() { 1 + 2 }
+        fn main() { both() }
+        "#,
+        &Restrictions::default(),
+    );
+
+    assert_eq!(
+        report.capabilities(),
+        vec![Capability::Fs, Capability::Net].into_iter().collect()
+    );
+
+    assert_eq!(
+        reachable(&report, "main"),
+        vec![Capability::Fs, Capability::Net].into_iter().collect()
+    );
+
+    assert_eq!(
+        reachable(&report, "remove"),
+        vec![Capability::Fs].into_iter().collect()
+    );
+
+    assert!(reachable(&report, "pure").is_empty());
+
+    let functions = report
+        .uses()
+        .iter()
+        .map(|u| u.function.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        functions,
+        vec!["fs::remove", "http::Socket::new", "http::Socket::send"]
+    );
+}
+
+#[test]
+fn test_function_pointers_and_closures() {
+    let report = analyze(
+        r#"
+        fn remove() { fs::remove("target") }
+        fn pointer() { let f = remove; }
+        fn closure() { let f = || http::Socket::new(); }
+        fn main() { pointer(); closure() }
+        "#,
+        &Restrictions::default(),
+    );
+
+    assert_eq!(
+        reachable(&report, "pointer"),
+        vec![Capability::Fs].into_iter().collect()
+    );
+
+    assert_eq!(
+        reachable(&report, "closure"),
+        vec![Capability::Net].into_iter().collect()
+    );
+
+    assert_eq!(
+        reachable(&report, "main"),
+        vec![Capability::Fs, Capability::Net].into_iter().collect()
+    );
+}
+
+#[test]
+fn test_recursive_calls() {
+    let report = analyze(
+        r#"
+        fn a(n) { if n > 0 { b(n - 1) } }
+        fn b(n) { if n > 0 { a(n - 1) } else { fs::remove("target") } }
+        fn main() { a(10) }
+        "#,
+        &Restrictions::default(),
+    );
+
+    assert_eq!(
+        reachable(&report, "a"),
+        vec![Capability::Fs].into_iter().collect()
+    );
+
+    assert_eq!(
+        reachable(&report, "main"),
+        vec![Capability::Fs].into_iter().collect()
+    );
+}
+
+#[test]
+fn test_custom_restrictions() {
+    let source = r#"
+    fn main() { fs::remove("target"); http::Socket::new() }
+    "#;
+
+    let report = analyze(source, &Restrictions::new());
+    assert!(report.is_empty());
+
+    let mut restrictions = Restrictions::new();
+    restrictions.restrict(Item::of(&["http"]), Capability::Net);
+    restrictions.restrict(Item::of(&["http", "Socket"]), Capability::Process);
+
+    let report = analyze(source, &restrictions);
+
+    assert_eq!(
+        report.capabilities(),
+        vec![Capability::Process].into_iter().collect()
+    );
+}
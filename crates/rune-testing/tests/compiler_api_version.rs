@@ -0,0 +1,91 @@
+use rune::{CompatibilityReport, LoadErrorKind, Options, Sources, Warnings};
+use rune_testing::*;
+use runestick::{ApiVersion, Context, Module, Source};
+
+fn host_context(version: Option<ApiVersion>) -> Result<Context> {
+    let mut context = Context::with_default_modules()?;
+
+    let mut module = Module::new(&["host"]);
+    module.function(&["add"], |a: i64, b: i64| a + b)?;
+    context.install(&module)?;
+
+    if let Some(version) = version {
+        context.set_api_version(version);
+    }
+
+    Ok(context)
+}
+
+fn check(context: &Context, target: &str, source: &str) -> Result<Option<CompatibilityReport>> {
+    let mut options = Options::default();
+    options.parse_option(&format!("api-version={}", target))?;
+
+    let mut sources = Sources::new();
+    sources.insert_default(Source::new("main", source.to_owned()));
+
+    match rune::load_sources(context, &options, &mut sources, &mut Warnings::new()) {
+        Ok(..) => Ok(None),
+        Err(error) => match error.into_kind() {
+            LoadErrorKind::Incompatible { report } => Ok(Some(report)),
+            kind => panic!("unexpected error: {:?}", kind),
+        },
+    }
+}
+
+#[test]
+fn test_compatible() -> Result<()> {
+    let context = host_context(Some(ApiVersion::new(1, 3)))?;
+    let report = check(&context, "1.2", "fn main() { host::add(1, 2) }")?;
+    assert!(report.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_version_mismatch() -> Result<()> {
+    let context = host_context(Some(ApiVersion::new(1, 3)))?;
+
+    let report = check(&context, "1.4", "fn main() { host::add(1, 2) }")?.unwrap();
+    assert!(!report.version_supported());
+    assert_eq!(report.host(), Some(ApiVersion::new(1, 3)));
+    assert_eq!(report.target(), ApiVersion::new(1, 4));
+
+    let report = check(&context, "2", "fn main() { host::add(1, 2) }")?.unwrap();
+    assert!(!report.version_supported());
+
+    let context = host_context(None)?;
+    let report = check(&context, "1.0", "fn main() { host::add(1, 2) }")?.unwrap();
+    assert!(!report.version_supported());
+    Ok(())
+}
+
+#[test]
+fn test_arity_mismatch() -> Result<()> {
+    let context = host_context(Some(ApiVersion::new(1, 0)))?;
+
+    let source = r#"
+    fn main() {
+        host::add(1, 2) + host::add(1) + host::add(1, 2, 3)
+    }
+    "#;
+
+    let report = check(&context, "1.0", source)?.unwrap();
+    assert!(report.version_supported());
+    assert!(report.missing().is_empty());
+
+    let mismatches = report
+        .arity_mismatches()
+        .iter()
+        .map(|m| (m.expected, m.actual))
+        .collect::<Vec<_>>();
+
+    assert_eq!(mismatches, vec![(2, 1), (2, 3)]);
+    assert_eq!(report.arity_mismatches()[0].function, "host::add");
+    Ok(())
+}
+
+#[test]
+fn test_invalid_api_version() {
+    let mut options = Options::default();
+    assert!(options.parse_option("api-version=1.x").is_err());
+    assert!(options.parse_option("api-version").is_err());
+}
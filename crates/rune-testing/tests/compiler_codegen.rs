@@ -0,0 +1,62 @@
+use rune_testing::*;
+use runestick::Context;
+
+/// Compile the given source and produce a listing of its instructions.
+fn compile_listing(source: &str) -> String {
+    let context = Context::with_default_modules().unwrap();
+    let (unit, _) = compile_source(&context, source).unwrap();
+    instruction_listing(&context, &unit)
+}
+
+#[test]
+fn test_instructions() {
+    assert_instructions! {
+        r#"fn main() { 1 + 2 }"#,
+        r#"
+        fn main():
+          0000 = integer 1
+          0001 = integer 2
+          0002 = add
+          0003 = return
+        "#
+    };
+
+    assert_instructions! {
+        r#"fn main() {}"#,
+        r#"
+        fn main():
+          0000 = return-unit
+        "#
+    };
+}
+
+#[test]
+fn test_resolved_names() {
+    let listing = compile_listing(r#"fn foo() { 1 } fn main() { foo() }"#);
+    assert!(listing.contains("fn foo():"), "{}", listing);
+    assert!(listing.contains("call foo, 0"), "{}", listing);
+
+    let listing = compile_listing(r#"fn main() { "hello" }"#);
+    assert!(listing.contains(r#"// "hello""#), "{}", listing);
+
+    let listing = compile_listing(r#"fn main() { let o = #{a: 1}; o.a }"#);
+    assert!(listing.contains(r#"// "a""#), "{}", listing);
+}
+
+#[test]
+fn test_diff_listings() {
+    assert_eq!(diff_listings("  a\n\n  b\n", "a\nb"), None);
+
+    assert_eq! {
+        diff_listings("a\nb\n", "a\nc\n"),
+        Some(String::from("  a\n- b\n+ c\n")),
+    };
+}
+
+#[test]
+fn test_golden() {
+    assert_golden! {
+        "if_else",
+        r#"fn main() { let n = 1; if n < 2 { 10 } else { 20 } }"#
+    };
+}
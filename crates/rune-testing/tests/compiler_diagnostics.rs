@@ -0,0 +1,110 @@
+use rune::termcolor::NoColor;
+use rune::{Diagnostics, EmitDiagnostics as _, LoadError, Severity, Sources, Warnings};
+use runestick::{Context, Source};
+
+fn load(source: &str) -> (Sources, Result<(), LoadError>, Warnings) {
+    let context = Context::with_default_modules().unwrap();
+    let mut sources = Sources::new();
+    sources.insert_default(Source::new("main", source.to_owned()));
+
+    let mut warnings = Warnings::new();
+    let result = rune::load_sources(&context, &Default::default(), &mut sources, &mut warnings);
+    (sources, result.map(drop), warnings)
+}
+
+fn codes(diagnostics: &Diagnostics) -> Vec<&'static str> {
+    diagnostics.iter().map(|d| d.code()).collect()
+}
+
+#[test]
+fn test_warning_diagnostics() {
+    let source = r#"
+    fn main() {
+        let (a, b) = (1, 2);
+        1;
+        a + b
+    }
+    "#;
+
+    let (sources, result, warnings) = load(source);
+    result.unwrap();
+
+    let diagnostics = warnings.diagnostics(&sources);
+    assert!(!diagnostics.has_errors());
+    assert_eq!(codes(&diagnostics), vec!["W0002", "W0001"]);
+
+    let panic = diagnostics.with_code("W0002").next().unwrap();
+    assert_eq!(panic.severity(), Severity::Warning);
+
+    let primary = panic.primary().unwrap();
+    assert_eq!(
+        &source[primary.span().start..primary.span().end],
+        "let (a, b) = (1, 2)"
+    );
+
+    assert!(panic.notes()[0].contains("if let (a, b) = (1, 2) {"));
+
+    // NB: the context of the warning is a secondary label.
+    let context = &panic.labels()[1];
+    assert!(!context.is_primary());
+    assert_eq!(context.message(), "in this context");
+}
+
+#[test]
+fn test_compile_error_diagnostics() {
+    let source = r#"
+    fn main() {
+        #{a: 1, a: 2}
+    }
+    "#;
+
+    let (_, result, _) = load(source);
+    let diagnostics = result.unwrap_err().diagnostics();
+
+    assert!(diagnostics.has_errors());
+    assert_eq!(codes(&diagnostics), vec!["E0045"]);
+
+    let diagnostic = diagnostics.iter().next().unwrap();
+    assert_eq!(diagnostic.severity(), Severity::Error);
+
+    let messages = diagnostic
+        .labels()
+        .iter()
+        .map(|l| (l.is_primary(), l.message()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        messages,
+        vec![
+            (false, "previously defined here"),
+            (false, "object being defined here"),
+            (true, ""),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_and_load_error_diagnostics() {
+    let (_, result, _) = load("fn main() { 1 + }");
+    assert_eq!(codes(&result.unwrap_err().diagnostics()), vec!["E0115"]);
+
+    let error = LoadError::internal("broken");
+    let diagnostics = error.diagnostics();
+    assert_eq!(codes(&diagnostics), vec!["E0203"]);
+    assert!(diagnostics.iter().next().unwrap().primary().is_none());
+}
+
+#[test]
+fn test_emit_diagnostics_with_codes() {
+    let (sources, result, warnings) = load("fn main() { let a = 1; missing }");
+
+    let mut out = NoColor::new(Vec::new());
+    result
+        .unwrap_err()
+        .emit_diagnostics(&mut out, &sources)
+        .unwrap();
+    warnings.emit_diagnostics(&mut out, &sources).unwrap();
+
+    let out = String::from_utf8(out.into_inner()).unwrap();
+    assert!(out.contains("error[E0012]: missing variable `missing`"));
+}
@@ -0,0 +1,91 @@
+use rune::{CompileError, LoadErrorKind, ParseError};
+use runestick::{Context, FromValue, Item, Span, Vm};
+use std::sync::Arc;
+
+fn eval<T>(vm: &Vm, name: &str) -> runestick::Result<T>
+where
+    T: FromValue,
+{
+    let output = vm.clone().call(Item::of(&[name]), ())?.complete()?;
+    Ok(T::from_value(output)?)
+}
+
+#[test]
+fn test_compile_expressions() -> runestick::Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+
+    let unit = rune::compile_expressions(
+        &*context,
+        &[
+            ("sum", "1 + 2 + 3"),
+            ("is_long", r#""hello world".len() > 5"#),
+            ("items", "[2, 4, 6] // doubled"),
+        ],
+    )?;
+
+    let vm = Vm::new(context, Arc::new(unit));
+    assert_eq!(eval::<i64>(&vm, "sum")?, 6);
+    assert!(eval::<bool>(&vm, "is_long")?);
+    assert_eq!(eval::<Vec<i64>>(&vm, "items")?, vec![2, 4, 6]);
+    Ok(())
+}
+
+#[test]
+fn test_compile_expressions_error() -> runestick::Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let error = rune::compile_expressions(
+        &context,
+        &[("ok", "1 + 2"), ("broken", "1 + missing"), ("also_ok", "3")],
+    )
+    .unwrap_err();
+
+    match error.into_kind() {
+        LoadErrorKind::CompileError {
+            source_id,
+            error: CompileError::MissingLocal { span, .. },
+        } => {
+            assert_eq!(source_id, 1);
+            assert_eq!(span, Span::new(4, 11));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_compile_expressions_single_expression() -> runestick::Result<()> {
+    let context = Context::with_default_modules()?;
+
+    for expr in &["1 } fn other() { 2", "let a = 1; a"] {
+        let error =
+            rune::compile_expressions(&context, &[("ok", "1"), ("injected", expr)]).unwrap_err();
+
+        match error.into_kind() {
+            LoadErrorKind::ParseError {
+                source_id,
+                error: ParseError::ExpectedEof { .. },
+            } => assert_eq!(source_id, 1),
+            kind => panic!("unexpected error for `{}`: {:?}", expr, kind),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_compile_expressions_invalid_name() -> runestick::Result<()> {
+    let context = Context::with_default_modules()?;
+
+    for name in &["not valid", "f() {} fn g", ""] {
+        let error = rune::compile_expressions(&context, &[(name, "1")]).unwrap_err();
+
+        match error.into_kind() {
+            LoadErrorKind::InvalidName { name: invalid } => assert_eq!(&invalid, name),
+            kind => panic!("unexpected error for `{}`: {:?}", name, kind),
+        }
+    }
+
+    Ok(())
+}
@@ -40,6 +40,20 @@ fn test_template_strings() {
             assert_eq!(span, Span::new(13, 20));
         }
     };
+
+    assert_compile_error! {
+        r#"fn main() { let n = 0; `{foo(&n)}` }"#,
+        UnsupportedRef { span } => {
+            assert_eq!(span, Span::new(29, 31));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { `{a b}` }"#,
+        ParseError { error: ExpectedEof { span, .. } } => {
+            assert_eq!(span, Span::new(16, 17));
+        }
+    };
 }
 
 #[test]
@@ -90,3 +104,125 @@ fn test_bad_struct_declaration() {
         }
     };
 }
+
+#[test]
+fn test_bad_pattern_ranges_and_alternatives() {
+    assert_compile_error! {
+        r#"fn main() { match 1 { 1..='a' => 1 } }"#,
+        UnsupportedRangePattern { span } => {
+            assert_eq!(span, Span::new(22, 29));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { match 1 { 5..=1 => 1 } }"#,
+        EmptyRangePattern { span } => {
+            assert_eq!(span, Span::new(22, 27));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { match 1 { 1 | n => n } }"#,
+        UnsupportedPatternAlternative { span } => {
+            assert_eq!(span, Span::new(26, 27));
+        }
+    };
+}
+
+#[test]
+fn test_trailing_commas() {
+    assert_parse!(r#"struct A { a, b, }"#);
+    assert_parse!(r#"struct B(a, b,);"#);
+    assert_parse!(r#"enum E { X(a,), Y { a, }, Z, }"#);
+    assert_parse!(r#"fn f(a, b,) { a + b }"#);
+    assert_parse!(r#"struct A { a } impl A { fn f(self, n,) { n } }"#);
+    assert_parse!(r#"fn main() { |a, b,| a + b }"#);
+    assert_parse!(r#"fn main() { let (a, b,) = (1, 2,); }"#);
+    assert_parse!(r#"fn main() { let [a, b,] = [1, 2,]; }"#);
+    assert_parse!(r#"fn main() { let #{a, b,} = #{a: 1, b: 2,}; }"#);
+    assert_parse!(r#"fn main() { match Some(1) { Some(n,) => n, None => 0, } }"#);
+    assert_parse!(r#"fn main() { map!{1: 2,}; set!{1, 2,}; }"#);
+
+    assert_parse_error! {
+        r#"fn main() { (,) }"#,
+        ExpectedExpr { .. } => {}
+    };
+
+    assert_parse_error! {
+        r#"fn main() { f(1 2) }"#,
+        TokenMismatch { .. } => {}
+    };
+
+    assert_parse_error! {
+        r#"struct A { a b }"#,
+        TokenMismatch { .. } => {}
+    };
+}
+
+#[test]
+fn test_continue_outside_of_loop() {
+    assert_compile_error! {
+        r#"fn main() { continue; }"#,
+        ContinueOutsideOfLoop { span } => {
+            assert_eq!(span, Span::new(12, 20));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { loop { continue 'nope; } }"#,
+        MissingLabel { span } => {
+            assert_eq!(span, Span::new(28, 33));
+        }
+    };
+}
+
+#[test]
+fn test_unsupported_break_value() {
+    assert_compile_error! {
+        r#"fn main() { while true { break 1; } 1 }"#,
+        UnsupportedBreakValue { span, kind, loop_span } => {
+            assert_eq!(span, Span::new(25, 32));
+            assert_eq!(kind, "while");
+            assert_eq!(loop_span, Span::new(12, 35));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { 'a: for x in [1] { loop { break 'a 1; } } 2 }"#,
+        UnsupportedBreakValue { kind, .. } => {
+            assert_eq!(kind, "for");
+        }
+    };
+}
+
+#[test]
+fn test_branch_values() {
+    assert_compile_error! {
+        r#"fn main() { let a = if true { 1 } else { }; a }"#,
+        BranchProducesNothing { span, value } => {
+            assert_eq!(span, Span::new(39, 42));
+            assert_eq!(value, Span::new(28, 33));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { let a = if true { 1 }; a }"#,
+        IfWithoutElse { span, value } => {
+            assert_eq!(span, Span::new(20, 33));
+            assert_eq!(value, Span::new(28, 33));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { let a = match 1 { 1 => 2, _ => { } }; a }"#,
+        BranchProducesNothing { span, value } => {
+            assert_eq!(span, Span::new(43, 46));
+            assert_eq!(value, Span::new(35, 36));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { if true { 1 } }"#,
+        IfWithoutElse { .. } => {}
+    };
+}
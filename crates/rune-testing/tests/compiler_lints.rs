@@ -0,0 +1,125 @@
+use rune::{Options, Sources, Warning, WarningKind, Warnings};
+use runestick::{Context, Source, Span};
+
+fn lint(source: &str) -> Vec<Warning> {
+    let context = Context::with_default_modules().unwrap();
+    let mut options = Options::default();
+    options.parse_option("lint-infinite-loops").unwrap();
+    options.parse_option("lint-unguarded-recursion").unwrap();
+
+    let mut sources = Sources::new();
+    sources.insert_default(Source::new("main", source.to_owned()));
+    let mut warnings = Warnings::new();
+
+    rune::load_sources(&context, &options, &mut sources, &mut warnings).unwrap();
+    warnings.iter().cloned().collect()
+}
+
+fn infinite_loops(source: &str) -> Vec<Span> {
+    lint(source)
+        .into_iter()
+        .filter_map(|w| match w.kind {
+            WarningKind::InfiniteLoop { span, .. } => Some(span),
+            _ => None,
+        })
+        .collect()
+}
+
+fn unguarded_recursion(source: &str) -> Vec<Span> {
+    lint(source)
+        .into_iter()
+        .filter_map(|w| match w.kind {
+            WarningKind::UnguardedRecursion { span, .. } => Some(span),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_lints_are_opt_in() {
+    let context = Context::with_default_modules().unwrap();
+
+    let (_, warnings) = rune_testing::compile_source(
+        &context,
+        r#"
+        fn f(n) { f(n + 1) }
+        fn main() { loop { f(1); } }
+        "#,
+    )
+    .unwrap();
+
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_infinite_loop() {
+    assert_eq!(
+        infinite_loops(r#"fn main() { let n = 0; loop { n = n + 1; } }"#),
+        vec![Span::new(23, 42)]
+    );
+
+    assert_eq!(
+        infinite_loops(r#"fn main() { while true { dbg(1); } }"#),
+        vec![Span::new(12, 34)]
+    );
+}
+
+#[test]
+fn test_infinite_loop_with_exit() {
+    let source = r#"
+    fn main() {
+        loop { break; }
+        while true { return; }
+        'outer: loop { loop { break 'outer; } }
+        loop { let n = Some(1)?; }
+    }
+
+    fn generator() {
+        loop { yield 1; }
+    }
+    "#;
+
+    assert!(infinite_loops(source).is_empty());
+}
+
+#[test]
+fn test_inner_break_doesnt_exit_outer_loop() {
+    assert_eq!(
+        infinite_loops(r#"fn main() { loop { loop { break; } } }"#),
+        vec![Span::new(12, 36)]
+    );
+}
+
+#[test]
+fn test_continue_doesnt_exit_loop() {
+    assert_eq!(
+        infinite_loops(r#"fn main() { 'a: loop { loop { continue 'a; } } }"#),
+        vec![Span::new(16, 46)]
+    );
+}
+
+#[test]
+fn test_unguarded_recursion() {
+    assert_eq!(
+        unguarded_recursion(r#"fn f(n) { dbg(n); f(n - 1) }"#),
+        vec![Span::new(18, 26)]
+    );
+
+    assert_eq!(
+        unguarded_recursion(r#"fn f(n) { let a = 1 + f(n); a }"#),
+        vec![Span::new(22, 26)]
+    );
+}
+
+#[test]
+fn test_guarded_recursion() {
+    let source = r#"
+    fn a(n) { if n == 0 { return 0; } a(n - 1) }
+    fn b(n) { n > 0 && b(n - 1) }
+    fn c(c) { c(1) }
+    fn d(n) { let d = |n| n; d(n) }
+    fn main() { a(1); b(1); c(|n| n); d(1) }
+    "#;
+
+    assert!(unguarded_recursion(source).is_empty());
+}
@@ -0,0 +1,57 @@
+use rune::{Sources, UnitBuilder, Warnings};
+use rune_testing::*;
+use runestick::{Context, FromValue, Item, Module, Source, Vm};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+fn tenant_context() -> Result<Context> {
+    let mut context = Context::with_default_modules()?;
+
+    for tenant in &["acme", "test"] {
+        let mut module = Module::new(&["tenants", tenant, "api"]);
+        module.function(&["greet"], move || format!("hello from {}", tenant))?;
+        context.install(&module)?;
+    }
+
+    Ok(context)
+}
+
+fn run_mapped(tenant: &str, source: &str) -> Result<String> {
+    let context = tenant_context()?;
+
+    let mut unit = UnitBuilder::with_default_prelude();
+    unit.map_module(&["app", "api"], &["tenants", tenant, "api"]);
+    let unit = Rc::new(RefCell::new(unit));
+
+    let mut sources = Sources::new();
+    sources.insert_default(Source::new("main", source.to_owned()));
+    rune::compile(&context, &mut sources, &unit, &mut Warnings::new())?;
+
+    let unit = Rc::try_unwrap(unit).unwrap().into_inner().into_unit();
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let output = vm.call(Item::of(&["main"]), ())?.complete()?;
+    Ok(String::from_value(output)?)
+}
+
+#[test]
+fn test_module_map() -> Result<()> {
+    let source = r#"
+    use app::api;
+    fn main() { api::greet() }
+    "#;
+
+    assert_eq!(run_mapped("acme", source)?, "hello from acme");
+    assert_eq!(run_mapped("test", source)?, "hello from test");
+
+    let source = r#"fn main() { app::api::greet() }"#;
+    assert_eq!(run_mapped("acme", source)?, "hello from acme");
+
+    let source = r#"
+    use app::api::*;
+    fn main() { greet() }
+    "#;
+
+    assert_eq!(run_mapped("test", source)?, "hello from test");
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use rune::{Options, Sources, Warnings};
+use rune_testing::*;
+use runestick::{Context, Inst, Item, Source, Unit, Vm, VmError};
+use std::sync::Arc;
+
+fn compile(source: &str, optimize: bool) -> Unit {
+    let context = Context::with_default_modules().unwrap();
+    let mut options = Options::default();
+
+    if optimize {
+        options.parse_option("optimize").unwrap();
+    }
+
+    let mut sources = Sources::new();
+    sources.insert_default(Source::new("main", source.to_owned()));
+    let mut warnings = Warnings::new();
+
+    rune::load_sources(&context, &options, &mut sources, &mut warnings).unwrap()
+}
+
+fn run(unit: Unit) -> Result<Value, VmError> {
+    let context = Context::with_default_modules().unwrap();
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    block_on(vm.call(Item::of(&["main"]), ())?.async_complete())
+}
+
+/// Compile the source with and without optimizations, and check that both
+/// produce the same integer.
+///
+/// Returns the number of instructions in the unoptimized and optimized units.
+fn check(source: &str, expected: i64) -> (usize, usize) {
+    let unoptimized = compile(source, false);
+    let optimized = compile(source, true);
+    let counts = (
+        unoptimized.iter_instructions().count(),
+        optimized.iter_instructions().count(),
+    );
+
+    assert_eq!(run(unoptimized).unwrap().into_integer().unwrap(), expected);
+    assert_eq!(run(optimized).unwrap().into_integer().unwrap(), expected);
+    counts
+}
+
+#[test]
+fn test_fold_constants() {
+    let unit = compile(r#"fn main() { 2 * 3 + 1 }"#, true);
+
+    assert! {
+        unit.iter_instructions().any(|inst| matches!(inst, Inst::Integer { number: 7 })),
+    };
+
+    assert! {
+        !unit.iter_instructions().any(|inst| matches!(inst, Inst::Mul | Inst::Add)),
+    };
+
+    let (before, after) = check(
+        r#"fn main() { if 1 < 2 && !false { (10 >> 1) % 3 } else { 0 } }"#,
+        2,
+    );
+    assert!(after < before);
+}
+
+#[test]
+fn test_fold_short_circuit() {
+    let unit = compile(r#"fn main() { true && false }"#, true);
+
+    assert! {
+        !unit.iter_instructions().any(|inst| matches!(inst, Inst::PeekAndJumpIfNot { .. } | Inst::And)),
+    };
+
+    assert!(!run(unit).unwrap().into_bool().unwrap());
+
+    check(
+        r#"fn main() { let n = 0; if false && { n = 1; true } || true { n + 2 } else { n } }"#,
+        2,
+    );
+}
+
+#[test]
+fn test_fold_constants_keeps_errors() {
+    let unit = compile(r#"fn main() { 9223372036854775807 + 1 }"#, true);
+    let error = run(unit).unwrap_err();
+    assert!(matches!(error.kind().into_unwound_ref(), (Overflow, _)));
+
+    let unit = compile(r#"fn main() { 1 / 0 }"#, true);
+    let error = run(unit).unwrap_err();
+    assert!(matches!(error.kind().into_unwound_ref(), (DivideByZero, _)));
+}
+
+#[test]
+fn test_fold_loop_constants() {
+    let (before, after) = check(
+        r#"
+        fn main() {
+            let total = 0;
+
+            for i in 0..10 {
+                let step = 2 * 3 + 1;
+                total += i * step;
+            }
+
+            total
+        }
+        "#,
+        315,
+    );
+
+    assert!(after < before);
+}
+
+#[test]
+fn test_reuse_dead_slots() {
+    let (before, after) = check(
+        r#"
+        fn main() {
+            let total = 0;
+
+            for i in 0..10 {
+                let x = i * 2;
+                total += x;
+            }
+
+            let result = total + 1;
+            result
+        }
+        "#,
+        91,
+    );
+
+    assert!(after < before);
+
+    check(
+        r#"
+        fn add(a, b) {
+            let c = a + b;
+            c
+        }
+
+        fn main() {
+            let n = 0;
+
+            while n < 10 {
+                let next = add(n, 1);
+                n = next;
+            }
+
+            let closure = |a| { let b = a + n; b };
+            closure(5)
+        }
+        "#,
+        15,
+    );
+}
@@ -0,0 +1,113 @@
+use rune::{CompileError, LoadErrorKind, Options, Sources, Warnings};
+use runestick::{Context, Source, Span};
+
+fn compile(strict: bool, source: &str) -> Result<(), CompileError> {
+    let context = Context::with_default_modules().unwrap();
+    let mut options = Options::default();
+
+    if strict {
+        options.parse_option("strict").unwrap();
+    }
+
+    let mut sources = Sources::new();
+    sources.insert_default(Source::new("main", source.to_owned()));
+
+    match rune::load_sources(&context, &options, &mut sources, &mut Warnings::new()) {
+        Ok(..) => Ok(()),
+        Err(error) => match error.into_kind() {
+            LoadErrorKind::CompileError { error, .. } => Err(error),
+            kind => panic!("unexpected error: {:?}", kind),
+        },
+    }
+}
+
+#[test]
+fn test_strict_is_opt_in() {
+    let source = r#"
+    fn main() {
+        let total = 0;
+        for n in 0..3 { let total = total + n; }
+        let println = 1;
+        println
+    }
+    "#;
+
+    assert!(compile(false, source).is_ok());
+    assert!(compile(true, source).is_err());
+}
+
+#[test]
+fn test_strict_shadowed_variable() {
+    let source = r#"fn main() { let total = 0; if true { let total = 1; } return total; }"#;
+
+    match compile(true, source) {
+        Err(CompileError::StrictShadowedVariable {
+            name,
+            span,
+            existing_span,
+        }) => {
+            assert_eq!(name, "total");
+            assert_eq!(span, Span::new(41, 46));
+            assert_eq!(existing_span, Span::new(16, 21));
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    assert!(compile(
+        true,
+        r#"fn main() { let total = 0; if true { total = 1; } return total; }"#
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_strict_shadowed_builtin() {
+    for source in &[
+        r#"fn main() { let println = 1; }"#,
+        r#"fn main() { for Vec in 0..3 {} }"#,
+        r#"fn print(value) {}"#,
+        r#"fn f(dbg) {}"#,
+        r#"fn main() { let f = |Some| 1; }"#,
+    ] {
+        match compile(true, source) {
+            Err(CompileError::StrictShadowedBuiltin { .. }) => (),
+            result => panic!("unexpected result for `{}`: {:?}", source, result),
+        }
+    }
+}
+
+#[test]
+fn test_strict_implicit_return() {
+    match compile(true, r#"fn main() { 1 + 2 }"#) {
+        Err(CompileError::StrictImplicitReturn { span }) => {
+            assert_eq!(span, Span::new(12, 17));
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    assert!(compile(true, r#"fn main() { return 1 + 2; }"#).is_ok());
+    assert!(compile(true, r#"fn main() { for n in 0..3 {} }"#).is_ok());
+
+    for source in &[
+        r#"fn f(x) { if x { return 1; } else { return 2; } }"#,
+        r#"fn f(x) { match x { 1 => return 1, _ => { return 2; } } }"#,
+        r#"fn f(x) { if x { return 1; } }"#,
+        r#"fn main() { let f = |x| { return x; }; }"#,
+    ] {
+        if let Err(error) = compile(true, source) {
+            panic!("unexpected error for `{}`: {:?}", source, error);
+        }
+    }
+
+    for source in &[
+        r#"fn f(x) { if x { return 1; } else { 2 } }"#,
+        r#"fn f(x) { match x { 1 => return 1, _ => 2 } }"#,
+        r#"fn main() { let f = |x| x + 1; }"#,
+        r#"fn main() { let f = |x| { x }; }"#,
+    ] {
+        match compile(true, source) {
+            Err(CompileError::StrictImplicitReturn { .. }) => (),
+            result => panic!("unexpected result for `{}`: {:?}", source, result),
+        }
+    }
+}
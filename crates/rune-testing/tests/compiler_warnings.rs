@@ -29,3 +29,158 @@ fn test_remove_variant_parens() {
         }
     };
 }
+
+#[test]
+fn test_match_missing_variants() {
+    assert_warnings! {
+        r#"
+        enum Shape { Circle(r), Rect { w, h }, Empty }
+
+        fn main() {
+            match Shape::Empty {
+                Shape::Circle(r) => r,
+                Shape::Rect { w: 1, h } => h,
+            }
+        }
+        "#,
+        MatchMissingVariants { span, ref missing, .. } => {
+            assert_eq!(span, Span::new(89, 107));
+
+            let missing = missing.iter().map(ToString::to_string).collect::<Vec<_>>();
+            assert_eq!(missing, vec!["Shape::Rect", "Shape::Empty"]);
+        }
+    };
+}
+
+#[test]
+fn test_unreachable_match_arm() {
+    assert_warnings! {
+        r#"
+        enum Shape { Circle(r), Empty }
+
+        fn main() {
+            match Shape::Empty {
+                Shape::Circle(r) => r,
+                Shape::Circle(_) => 0,
+                _ => 0,
+                Shape::Empty => 0,
+            }
+        }
+        "#,
+        UnreachableMatchArm { span, .. } => {
+            assert_eq!(span, Span::new(150, 166));
+        },
+        UnreachableMatchArm { span, .. } => {
+            assert_eq!(span, Span::new(213, 225));
+        }
+    };
+}
+
+#[test]
+fn test_unreachable_code_after_return() {
+    assert_warnings! {
+        r#"fn main() { return 1; let a = 2; a }"#,
+        UnreachableCode { span, cause, .. } => {
+            assert_eq!(span, Span::new(22, 34));
+            assert_eq!(cause, Span::new(12, 20));
+        }
+    };
+}
+
+#[test]
+fn test_unreachable_code_after_break() {
+    assert_warnings! {
+        r#"fn main() { loop { break; dbg(1); } }"#,
+        UnreachableCode { span, cause, .. } => {
+            assert_eq!(span, Span::new(26, 32));
+            assert_eq!(cause, Span::new(19, 24));
+        }
+    };
+}
+
+#[test]
+fn test_unreachable_code_after_diverging_block() {
+    assert_warnings! {
+        r#"fn main() { { return; } 1 }"#,
+        UnreachableCode { span, cause, .. } => {
+            assert_eq!(span, Span::new(24, 25));
+            assert_eq!(cause, Span::new(12, 23));
+        }
+    };
+}
+
+#[test]
+fn test_unit_function_used_as_value() {
+    assert_warnings! {
+        r#"
+        fn log(message) { dbg(message); }
+
+        fn main() {
+            let a = log("a");
+            dbg(log("b"));
+            log("c") == ()
+        }
+        "#,
+        UnitFunctionUsedAsValue { span, ref item, .. } => {
+            assert_eq!(span, Span::new(84, 92));
+            assert_eq!(item.to_string(), "log");
+        },
+        UnitFunctionUsedAsValue { span, .. } => {
+            assert_eq!(span, Span::new(110, 118));
+        },
+        UnitFunctionUsedAsValue { span, .. } => {
+            assert_eq!(span, Span::new(133, 141));
+        }
+    };
+}
+
+#[test]
+fn test_unit_function_returning_value() {
+    let context = runestick::Context::with_default_modules().unwrap();
+
+    let (_, warnings) = compile_source(
+        &context,
+        r#"
+        fn first(values) {
+            for v in values {
+                return v;
+            }
+        }
+
+        fn nothing() { () }
+
+        fn main() {
+            let a = first([1, 2]);
+            let f = |x| { return x; };
+            let b = f(1);
+            nothing();
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert!(warnings.is_empty(), "{:?}", warnings);
+}
+
+#[test]
+fn test_unit_function_with_unit_branches() {
+    assert_warnings! {
+        r#"
+        fn log(n) { if n > 0 { dbg(n); } else { dbg(0); } }
+        fn main() { let a = log(1); }
+        "#,
+        UnitFunctionUsedAsValue { span, .. } => {
+            assert_eq!(span, Span::new(89, 95));
+        }
+    };
+}
+
+#[test]
+fn test_break_value_not_used() {
+    assert_warnings! {
+        r#"fn main() { loop { break 1; } 2 }"#,
+        BreakValueNotUsed { span, .. } => {
+            assert_eq!(span, Span::new(25, 26));
+        }
+    };
+}
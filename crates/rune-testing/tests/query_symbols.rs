@@ -0,0 +1,171 @@
+use rune::query::{SymbolKind, Symbols};
+use rune::{Options, Sources, Warnings};
+use runestick::{Call, Context, Item, Source, Span};
+
+fn symbols(source: &str) -> Symbols {
+    let context = Context::with_default_modules().unwrap();
+    let mut sources = Sources::new();
+    sources.insert_default(Source::new("main", source.to_owned()));
+    let mut warnings = Warnings::new();
+    let mut symbols = Symbols::new();
+
+    rune::load_sources_with_symbols(
+        &context,
+        &Options::default(),
+        &mut sources,
+        &mut warnings,
+        &mut symbols,
+    )
+    .unwrap();
+
+    symbols
+}
+
+#[test]
+fn test_symbol_kinds_and_signatures() {
+    let symbols = symbols(
+        r#"
+        struct Point { x, y }
+
+        enum Shape {
+            Circle(radius),
+            Empty,
+        }
+
+        impl Point {
+            fn len(self) { self.x + self.y }
+        }
+
+        mod geometry {
+            async fn area(shape,
+                          scale) { 0 }
+        }
+
+        fn main() {}
+        "#,
+    );
+
+    let expected = vec![
+        (&["Point"][..], SymbolKind::Struct, "struct Point { x, y }"),
+        (&["Shape"][..], SymbolKind::Enum, "enum Shape"),
+        (
+            &["Shape", "Circle"][..],
+            SymbolKind::Variant,
+            "Circle(radius)",
+        ),
+        (&["Shape", "Empty"][..], SymbolKind::Variant, "Empty"),
+        (
+            &["Point", "len"][..],
+            SymbolKind::InstanceFunction {
+                call: Call::Immediate,
+            },
+            "fn len(self)",
+        ),
+        (&["geometry"][..], SymbolKind::Module, "mod geometry"),
+        (
+            &["geometry", "area"][..],
+            SymbolKind::Function { call: Call::Async },
+            "async fn area(shape, scale)",
+        ),
+        (
+            &["main"][..],
+            SymbolKind::Function {
+                call: Call::Immediate,
+            },
+            "fn main()",
+        ),
+    ];
+
+    for (path, kind, signature) in expected {
+        let symbol = symbols.lookup(&Item::of(path)).expect("missing symbol");
+        assert_eq!(symbol.kind, kind);
+        assert_eq!(symbol.signature, signature);
+    }
+
+    assert_eq!(symbols.iter().count(), 8);
+}
+
+#[test]
+fn test_symbol_references() {
+    let source = r#"
+    fn add(a, b) { a + b }
+
+    fn main() {
+        let f = add;
+        add(1, add(2, 3)) + f(4, 5)
+    }
+    "#;
+
+    let symbols = symbols(source);
+    let add = Item::of(&["add"]);
+
+    let symbol = symbols.lookup(&add).unwrap();
+    assert_eq!(&source[symbol.span.start..symbol.span.end], "add");
+
+    let references = symbols.references(&add).collect::<Vec<_>>();
+    assert_eq!(references.len(), 3);
+
+    for reference in references {
+        assert_eq!(reference.source_id, 0);
+        assert_eq!(&source[reference.span.start..reference.span.end], "add");
+    }
+}
+
+#[test]
+fn test_symbol_at_offset() {
+    let source = r#"fn helper() { 1 } fn main() { helper() }"#;
+    let symbols = symbols(source);
+    let helper = Item::of(&["helper"]);
+
+    let offset = source.rfind("helper").unwrap() + 2;
+    assert_eq!(symbols.item_at(0, offset), Some(&helper));
+    assert_eq!(symbols.item_at(0, 4), Some(&helper));
+    assert_eq!(symbols.item_at(0, 0), None);
+    assert_eq!(symbols.item_at(1, offset), None);
+
+    let reference = symbols.references(&helper).next().unwrap();
+    assert_eq!(reference.span, Span::new(30, 36));
+}
+
+#[test]
+fn test_symbols_disabled() {
+    let context = Context::with_default_modules().unwrap();
+    let mut sources = Sources::new();
+    sources.insert_default(Source::new("main", "fn main() {}"));
+    let mut symbols = Symbols::disabled();
+
+    rune::load_sources_with_symbols(
+        &context,
+        &Options::default(),
+        &mut sources,
+        &mut Warnings::new(),
+        &mut symbols,
+    )
+    .unwrap();
+
+    assert!(symbols.is_empty());
+    assert!(symbols.lookup(&Item::of(&["main"])).is_none());
+}
+
+#[test]
+fn test_impl_references() {
+    let source = r#"
+    struct Point { x, y }
+
+    impl Point {
+        fn len(self) { self.x + self.y }
+    }
+
+    fn main() { Point { x: 1, y: 2 }.len() }
+    "#;
+
+    let symbols = symbols(source);
+    let point = Item::of(&["Point"]);
+
+    let references = symbols
+        .references(&point)
+        .map(|r| &source[r.span.start..r.span.end])
+        .collect::<Vec<_>>();
+
+    assert_eq!(references, vec!["Point", "Point"]);
+}
@@ -0,0 +1,157 @@
+use rune::refactor::{self, RenameError};
+use rune::Sources;
+use runestick::{Context, Item, Source};
+
+fn rename(source: &str, path: &[&str], name: &str) -> Result<String, RenameError> {
+    let context = Context::with_default_modules().unwrap();
+    let mut sources = Sources::new();
+    let id = sources.insert_default(Source::new("main", source.to_owned()));
+
+    let edits = refactor::rename(&context, &mut sources, &Item::of(path), name)?;
+    assert!(edits.iter().all(|e| e.source_id == id));
+    Ok(refactor::apply_edits(source, &edits))
+}
+
+#[test]
+fn test_rename_function() {
+    let source = r#"
+    fn add(a, b) { a + b }
+    fn main() { let f = add; add(1, add(2, 3)) + f(4, 5) }
+    "#;
+
+    let expected = r#"
+    fn sum(a, b) { a + b }
+    fn main() { let f = sum; sum(1, sum(2, 3)) + f(4, 5) }
+    "#;
+
+    assert_eq!(rename(source, &["add"], "sum").unwrap(), expected);
+}
+
+#[test]
+fn test_rename_module() {
+    let source = r#"
+    mod geometry {
+        fn area(w, h) { w * h }
+        fn square(s) { area(s, s) }
+    }
+
+    use geometry::area;
+
+    fn main() { geometry::square(2) + area(1, 2) + geometry :: area(3, 4) }
+    "#;
+
+    let expected = r#"
+    mod shapes {
+        fn area(w, h) { w * h }
+        fn square(s) { area(s, s) }
+    }
+
+    use shapes::area;
+
+    fn main() { shapes::square(2) + area(1, 2) + shapes :: area(3, 4) }
+    "#;
+
+    assert_eq!(rename(source, &["geometry"], "shapes").unwrap(), expected);
+}
+
+#[test]
+fn test_rename_imported_function() {
+    let source = r#"
+    mod geometry {
+        fn area(w, h) { w * h }
+        fn square(s) { area(s, s) }
+    }
+
+    use geometry::area;
+
+    fn main() { area(1, 2) + geometry::area(3, 4) }
+    "#;
+
+    let expected = r#"
+    mod geometry {
+        fn surface(w, h) { w * h }
+        fn square(s) { surface(s, s) }
+    }
+
+    use geometry::surface;
+
+    fn main() { surface(1, 2) + geometry::surface(3, 4) }
+    "#;
+
+    assert_eq!(
+        rename(source, &["geometry", "area"], "surface").unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_rename_enum_and_struct() {
+    let source = r#"
+    enum Shape { Circle(r), Empty }
+    struct Point { x, y }
+
+    impl Point {
+        fn len(self) { self.x + self.y }
+    }
+
+    fn main() {
+        let p = Point { x: 1, y: 2 };
+
+        match Shape::Circle(p.len()) {
+            Shape::Circle(r) => r,
+            Shape::Empty => 0,
+        }
+    }
+    "#;
+
+    let renamed = rename(source, &["Shape"], "Form").unwrap();
+    assert_eq!(renamed.matches("Form").count(), 4);
+    assert!(!renamed.contains("Shape"));
+
+    let renamed = rename(source, &["Shape", "Circle"], "Round").unwrap();
+    assert_eq!(renamed.matches("Round").count(), 3);
+    assert!(renamed.contains("Shape::Round(r) => r"));
+
+    let renamed = rename(source, &["Point"], "Vec2").unwrap();
+    assert_eq!(renamed.matches("Vec2").count(), 3);
+}
+
+#[test]
+fn test_rename_errors() {
+    let source = r#"
+    struct Point { x, y }
+    impl Point { fn len(self) { 0 } }
+    fn add(a, b) { a + b }
+    fn main() { add(1, 2) }
+    "#;
+
+    assert!(matches!(
+        rename(source, &["missing"], "other"),
+        Err(RenameError::MissingItem { .. })
+    ));
+
+    assert!(matches!(
+        rename(source, &["add"], "fn"),
+        Err(RenameError::InvalidName { .. })
+    ));
+
+    assert!(matches!(
+        rename(source, &["add"], "a b"),
+        Err(RenameError::InvalidName { .. })
+    ));
+
+    assert!(matches!(
+        rename(source, &["add"], "main"),
+        Err(RenameError::Conflict { .. })
+    ));
+
+    assert!(matches!(
+        rename(source, &["Point", "len"], "length"),
+        Err(RenameError::InstanceFunction { .. })
+    ));
+
+    assert!(matches!(
+        rename("fn main() { missing() }", &["main"], "entry"),
+        Err(RenameError::LoadError { .. })
+    ));
+}
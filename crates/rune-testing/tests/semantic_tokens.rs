@@ -0,0 +1,137 @@
+use rune::SemanticTokenKind::{self, *};
+
+fn classify(source: &str) -> Vec<(&str, SemanticTokenKind)> {
+    rune::semantic_tokens(source)
+        .unwrap()
+        .into_iter()
+        .map(|token| (&source[token.span.start..token.span.end], token.kind))
+        .collect()
+}
+
+#[test]
+fn test_semantic_tokens_declarations() {
+    let tokens = classify(
+        r#"
+        use std::iter::range;
+        struct Point { x, y }
+        enum Shape { Circle(radius), Empty }
+        "#,
+    );
+
+    assert_eq!(
+        tokens,
+        vec![
+            ("use", Keyword),
+            ("std", Module),
+            ("iter", Module),
+            ("range", Function),
+            ("struct", Keyword),
+            ("Point", Type),
+            ("x", Field),
+            ("y", Field),
+            ("enum", Keyword),
+            ("Shape", Type),
+            ("Circle", Variant),
+            ("radius", Field),
+            ("Empty", Variant),
+        ]
+    );
+}
+
+#[test]
+fn test_semantic_tokens_locals() {
+    let tokens = classify(
+        r#"
+        fn area(shape, scale) {
+            let total = 0;
+
+            for n in range(0, 3) {
+                total = total + n * scale;
+            }
+
+            let f = |v| v + 1;
+            f(total)
+        }
+        "#,
+    );
+
+    assert_eq!(
+        tokens,
+        vec![
+            ("fn", Keyword),
+            ("area", Function),
+            ("shape", Parameter),
+            ("scale", Parameter),
+            ("let", Keyword),
+            ("total", Variable),
+            ("0", Number),
+            ("for", Keyword),
+            ("n", Variable),
+            ("in", Keyword),
+            ("range", Function),
+            ("0", Number),
+            ("3", Number),
+            ("total", Variable),
+            ("total", Variable),
+            ("n", Variable),
+            ("scale", Parameter),
+            ("let", Keyword),
+            ("f", Variable),
+            ("v", Parameter),
+            ("v", Parameter),
+            ("1", Number),
+            ("f", Variable),
+            ("total", Variable),
+        ]
+    );
+}
+
+#[test]
+fn test_semantic_tokens_expressions() {
+    let tokens = classify(
+        r#"
+        fn main() {
+            let p = Point { x: 1, y: true };
+            assert!(p.x == 1);
+            Shape::Circle(2.0).len();
+            #{ name: "a", tag: b"b", c: 'c', t: `{p}` }
+        }
+        "#,
+    );
+
+    assert_eq!(
+        tokens,
+        vec![
+            ("fn", Keyword),
+            ("main", Function),
+            ("let", Keyword),
+            ("p", Variable),
+            ("Point", Type),
+            ("x", Field),
+            ("1", Number),
+            ("y", Field),
+            ("true", Bool),
+            ("assert", Macro),
+            ("p", Variable),
+            ("x", Field),
+            ("1", Number),
+            ("Shape", Type),
+            ("Circle", Variant),
+            ("2.0", Number),
+            ("len", Method),
+            ("name", Field),
+            ("\"a\"", String),
+            ("tag", Field),
+            ("b\"b\"", ByteString),
+            ("c", Field),
+            ("'c'", Char),
+            ("t", Field),
+            ("`{p}`", Template),
+        ]
+    );
+}
+
+#[test]
+fn test_semantic_tokens_parse_error() {
+    assert!(rune::semantic_tokens("fn main() {").is_err());
+}
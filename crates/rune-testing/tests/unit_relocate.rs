@@ -0,0 +1,83 @@
+use rune_testing::*;
+use runestick::{Context, FromValue as _, Hash, Item, Unit, UnitFn, Vm};
+use std::sync::Arc;
+
+fn function_id(unit: &Unit, name: &str) -> usize {
+    match unit.lookup(Hash::type_hash(&Item::of(&[name]))) {
+        Some(UnitFn::Offset { id, .. }) => id,
+        _ => panic!("missing function `{}`", name),
+    }
+}
+
+fn run(unit: Unit) -> i64 {
+    let context = Arc::new(Context::with_default_modules().unwrap());
+    let vm = Vm::new(context, Arc::new(unit));
+    let output = block_on(vm.call(Item::of(&["main"]), ()).unwrap().async_complete()).unwrap();
+    i64::from_value(output).unwrap()
+}
+
+#[test]
+fn test_relocated_functions_are_called() {
+    let context = Context::with_default_modules().unwrap();
+
+    let (mut unit, _) = compile_source(
+        &context,
+        r#"
+        fn a() { 1 }
+        fn b() { 2 }
+        fn main() { let f = a; a() * 10 + f() }
+        "#,
+    )
+    .unwrap();
+
+    let a = function_id(&unit, "a");
+    let b = function_id(&unit, "b");
+    let b_offset = unit.function_offset(b).unwrap();
+
+    let a_offset = unit.relocate_function(a, b_offset).unwrap();
+    assert_ne!(a_offset, b_offset);
+    assert_eq!(unit.function_offset(a).unwrap(), b_offset);
+
+    assert_eq!(run(unit), 22);
+}
+
+#[test]
+fn test_relocated_function_spans() {
+    let source = r#"
+    fn a() { 1 }
+    fn b() { panic("in b") }
+    fn main() { a() }
+    "#;
+
+    let context = Context::with_default_modules().unwrap();
+    let (mut unit, _) = compile_source(&context, source).unwrap();
+
+    let a = function_id(&unit, "a");
+    let b = function_id(&unit, "b");
+    let b_offset = unit.function_offset(b).unwrap();
+    unit.relocate_function(a, b_offset).unwrap();
+
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let error = block_on(vm.call(Item::of(&["main"]), ()).unwrap().async_complete()).unwrap_err();
+    let (unit, ip) = error.into_unwound().1.expect("expected an unwound error");
+
+    let debug = unit.debug_info().expect("missing debug info");
+    let span = debug
+        .instruction_at(ip)
+        .expect("missing debug instruction")
+        .span;
+    assert!(source[span.start..span.end].starts_with("panic"));
+
+    let (hash, _) = debug.function_enclosing(ip).expect("missing function");
+    assert_eq!(hash, Hash::type_hash(&Item::of(&["a"])));
+}
+
+#[test]
+fn test_relocate_missing_function() {
+    let context = Context::with_default_modules().unwrap();
+    let (mut unit, _) = compile_source(&context, "fn main() { 0 }").unwrap();
+
+    let count = unit.iter_function_offsets().count();
+    assert_eq!(unit.relocate_function(count, 0), None);
+    assert!(unit.function_offset(count).is_err());
+}
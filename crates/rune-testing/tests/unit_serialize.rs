@@ -0,0 +1,75 @@
+use rune_testing::*;
+use runestick::{Context, Item, Unit, Vm};
+use std::sync::Arc;
+
+fn roundtrip(unit: &Unit) -> Unit {
+    let bytes = rmp_serde::to_vec(unit).unwrap();
+    rmp_serde::from_slice(&bytes).unwrap()
+}
+
+fn run(unit: Unit) -> Result<Value, runestick::VmError> {
+    let context = Arc::new(Context::with_default_modules().unwrap());
+    let vm = Vm::new(context, Arc::new(unit));
+    block_on(vm.call(Item::of(&["main"]), ()).unwrap().async_complete())
+}
+
+#[test]
+fn test_serialized_unit_runs() {
+    let context = Context::with_default_modules().unwrap();
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        struct Point { x, y }
+        enum Shape { Circle(r), Square { side } }
+
+        fn area(shape) {
+            match shape {
+                Shape::Circle(r) => r * r * 3,
+                Shape::Square { side } => side * side,
+            }
+        }
+
+        fn main() {
+            let p = Point { x: 1, y: 2 };
+            let shapes = [Shape::Circle(2), Shape::Square { side: 3 }];
+            let total = p.x + p.y;
+
+            for shape in shapes {
+                total += area(shape);
+            }
+
+            `total: {total}`
+        }
+        "#,
+    )
+    .unwrap();
+
+    let unit = roundtrip(&unit);
+    assert!(unit.debug_info().is_some());
+
+    let output = run(unit).unwrap().into_string().unwrap();
+    assert_eq!(&*output.borrow_ref().unwrap(), "total: 24");
+}
+
+#[test]
+fn test_serialized_unit_without_debug_info() {
+    let context = Context::with_default_modules().unwrap();
+
+    let (mut unit, _) = compile_source(
+        &context,
+        r#"
+        fn fail() { 1 / 0 }
+        fn main() { fail() }
+        "#,
+    )
+    .unwrap();
+
+    unit.strip_debug_info();
+
+    let unit = roundtrip(&unit);
+    assert!(unit.debug_info().is_none());
+
+    let error = run(unit).unwrap_err();
+    assert!(matches!(error.kind().into_unwound_ref(), (DivideByZero, _)));
+}
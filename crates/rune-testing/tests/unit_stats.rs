@@ -0,0 +1,56 @@
+use rune_testing::*;
+use runestick::{Context, StaticValue};
+use std::sync::Arc;
+
+#[test]
+fn test_static_data_is_deduplicated() {
+    let context = Context::with_default_modules().unwrap();
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        fn a() {
+            let s = "hello";
+            let o = #{a: s, b: s};
+            let b = b"bytes";
+            (o, b)
+        }
+
+        fn b() {
+            let s = "hello";
+            let o = #{a: s, b: "world"};
+            let b = b"bytes";
+            (o, b)
+        }
+
+        fn main() {
+            ["hello", "world"]
+        }
+        "#,
+    )
+    .unwrap();
+
+    let stats = unit.stats();
+
+    assert_eq!(stats.functions, 3);
+    assert_eq!(stats.static_strings, 2);
+    assert_eq!(stats.static_string_bytes, 10);
+    assert_eq!(stats.static_bytes, 1);
+    assert_eq!(stats.static_bytes_bytes, 5);
+    assert_eq!(stats.static_object_keys, 1);
+    assert_eq!(stats.static_values, 1);
+    assert_eq!(stats.instructions, unit.iter_instructions().count());
+
+    let hello = unit.iter_static_strings().next().unwrap();
+
+    // Strings in the static data section share the interned static strings.
+    let interned = unit.iter_static_values().any(|value| match value {
+        StaticValue::Vec(values) => match values.first() {
+            Some(StaticValue::String(s)) => Arc::ptr_eq(s, hello),
+            _ => false,
+        },
+        _ => false,
+    });
+
+    assert!(interned);
+}
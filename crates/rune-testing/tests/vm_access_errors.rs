@@ -0,0 +1,63 @@
+use rune::termcolor::NoColor;
+use rune::{EmitDiagnostics as _, Sources};
+use rune_testing::*;
+use runestick::{Context, Item, Source, Vm, VmError};
+use std::sync::Arc;
+
+/// A generator which resumes itself, through a vector it's stored in.
+const SOURCE: &str = r#"
+fn counter(cell) {
+    let g = cell[0];
+    yield g.next();
+}
+
+fn main() {
+    let cell = [];
+    let g = counter(cell);
+    cell.push(g);
+    g.next()
+}
+"#;
+
+fn run_error(source: &str) -> (Sources, VmError) {
+    let context = Context::with_default_modules().unwrap();
+    let (unit, _) = compile_source(&context, source).unwrap();
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let error = vm
+        .call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap_err();
+
+    let mut sources = Sources::new();
+    sources.insert_default(Source::new("main", source.to_owned()));
+    (sources, error)
+}
+
+#[test]
+fn test_access_error_snapshot() {
+    let (_, error) = run_error(SOURCE);
+
+    assert!(error
+        .to_string()
+        .contains("cannot write `Generator`, value is exclusively accessed"));
+
+    let access = error.as_access_error().expect("expected an access error");
+    let snapshot = access.snapshot().unwrap();
+    assert_eq!(snapshot.type_name(), "runestick::generator::Generator");
+    assert!(!snapshot.is_moved());
+    assert!(snapshot.borrowed_at().is_some());
+    assert!(access.hint().is_some());
+}
+
+#[test]
+fn test_access_error_diagnostics() {
+    let (sources, error) = run_error(SOURCE);
+
+    let mut out = NoColor::new(Vec::new());
+    error.emit_diagnostics(&mut out, &sources).unwrap();
+    let out = String::from_utf8(out.into_inner()).unwrap();
+
+    assert!(out.contains("g.next()\n   │     -------- value borrowed here"));
+    assert!(out.contains("= hint: the value is being modified"));
+}
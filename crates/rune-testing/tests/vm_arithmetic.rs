@@ -104,6 +104,53 @@ fn test_sub() {
     );
 }
 
+#[test]
+fn test_neg() {
+    assert_eq! {
+        rune! {
+            (i64, f64, i64, i64, i64) => r#"
+            fn main() {
+                let a = 10;
+                let b = 2.5;
+                (-a, -b, a-1, - -1, -a.pow(2))
+            }
+            "#
+        },
+        (-10, -2.5, 9, 1, -100),
+    };
+
+    assert_eq! {
+        rune! {
+            bool => r#"
+            fn main() {
+                match -1 { -1 => true, _ => false }
+            }
+            "#
+        },
+        true,
+    };
+
+    assert_vm_error!(
+        r#"
+        fn main() {
+            let a = -9223372036854775808;
+            -a
+        }
+        "#,
+        Overflow => {}
+    );
+
+    assert_vm_error!(
+        r#"
+        fn main() {
+            let a = "hello";
+            -a
+        }
+        "#,
+        UnsupportedUnaryOperation { op: "-", .. } => {}
+    );
+}
+
 #[test]
 fn test_mul() {
     assert_eq! {
@@ -260,6 +307,107 @@ fn test_rem() {
     );
 }
 
+#[test]
+fn test_signed_div_rem() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, i64, i64) => r#"
+            fn main() {
+                let a = -9223372036854775808;
+                let b = a;
+                b %= -1;
+                (-7 / 2, 7 / -2, -7 % 2, 7 % -2, a % -1 + b)
+            }
+            "#
+        },
+        (-3, -3, -1, 1, 0),
+    };
+
+    assert_eq! {
+        rune! {
+            (f64, f64, f64) => r#"
+            fn main() {
+                let a = 7.5;
+                a %= 2;
+                (7.5 % 2.0, -7.5 % 2, a)
+            }
+            "#
+        },
+        (1.5, -1.5, 1.5),
+    };
+
+    assert_vm_error!(
+        r#"
+        fn main() {
+            let a = -9223372036854775808;
+            a / -1
+        }
+        "#,
+        Overflow => {}
+    );
+
+    assert_vm_error!(
+        r#"
+        fn main() {
+            let a = -9223372036854775808;
+            a /= -1;
+        }
+        "#,
+        Overflow => {}
+    );
+}
+
+#[test]
+fn test_euclid() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, i64, Option<i64>, Option<i64>) => r#"
+            fn main() {
+                (
+                    (-7).div_euclid(2),
+                    (-7).rem_euclid(2),
+                    7.div_euclid(-2),
+                    7.rem_euclid(-2),
+                    (-7).checked_rem_euclid(2),
+                    7.checked_div_euclid(0),
+                )
+            }
+            "#
+        },
+        (-4, 1, -3, 1, Some(1), None),
+    };
+
+    assert_eq! {
+        rune! {
+            (f64, f64) => r#"
+            fn main() {
+                ((-7.0).div_euclid(2.0), (-7.5).rem_euclid(2.0))
+            }
+            "#
+        },
+        (-4.0, 0.5),
+    };
+
+    assert_vm_error!(
+        r#"fn main() { 7.rem_euclid(0) }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.kind(), DivideByZero));
+        }
+    );
+
+    assert_vm_error!(
+        r#"
+        fn main() {
+            let a = -9223372036854775808;
+            a.div_euclid(-1)
+        }
+        "#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.kind(), Overflow));
+        }
+    );
+}
+
 #[test]
 fn test_bit_ops() {
     assert_eq! {
@@ -354,6 +502,103 @@ fn test_shift_ops() {
         },
         0b1010000,
     };
+
+    assert_eq!(
+        rune!(i64 => r#"fn main() { let a = 1; a << 63 }"#),
+        i64::MIN
+    );
+    assert_eq!(rune!(i64 => r#"fn main() { let a = -8; a >> 1 }"#), -4);
+    assert_eq!(
+        rune!(u8 => r#"fn main() { let a = b'\x81'; a << 1 }"#),
+        0x02
+    );
+    assert_eq!(
+        rune!(u8 => r#"fn main() { let a = b'\x81'; a >>= 7; a }"#),
+        0x01
+    );
+
+    assert_vm_error!(
+        r#"fn main() { let a = 1; a << 64 }"#,
+        ShiftOutOfRange { op, amount, bits } => {
+            assert_eq!(*op, "<<");
+            assert_eq!(*amount, 64);
+            assert_eq!(*bits, 64);
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { let a = 1; a >>= -1; }"#,
+        ShiftOutOfRange { op, amount, bits } => {
+            assert_eq!(*op, ">>=");
+            assert_eq!(*amount, -1);
+            assert_eq!(*bits, 64);
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { let a = b'\x01'; a << 8 }"#,
+        ShiftOutOfRange { amount, bits, .. } => {
+            assert_eq!(*amount, 8);
+            assert_eq!(*bits, 8);
+        }
+    );
+}
+
+#[test]
+fn test_byte_bit_ops() {
+    assert_eq!(rune!(u8 => r#"fn main() { b'\xf0' & b'\x3c' }"#), 0x30);
+    assert_eq!(rune!(u8 => r#"fn main() { b'\xf0' | b'\x0f' }"#), 0xff);
+    assert_eq!(
+        rune!(u8 => r#"fn main() { let a = b'\xff'; a ^= b'\x0f'; a }"#),
+        0xf0
+    );
+
+    assert_eq! {
+        rune!(Vec<u8> => r#"fn main() { (b"\xff\x0f" ^ b"\x0f\x0f").into_vec() }"#),
+        vec![0xf0, 0x00],
+    };
+
+    assert_eq! {
+        rune!(Vec<u8> => r#"fn main() { let a = b"\x01\x02"; a |= b"\x10\x20"; a.into_vec() }"#),
+        vec![0x11, 0x22],
+    };
+
+    assert_vm_error!(
+        r#"fn main() { b"\x01" & b"\x01\x02" }"#,
+        BytesLengthMismatch { op, lhs, rhs } => {
+            assert_eq!(*op, "&");
+            assert_eq!(*lhs, 1);
+            assert_eq!(*rhs, 2);
+        }
+    );
+}
+
+#[test]
+fn test_int_bit_intrinsics() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, i64, i64, i64) => r#"
+            fn main() {
+                let a = 0b1011;
+                let b = 1;
+                (
+                    a.count_ones(),
+                    a.count_zeros(),
+                    a.trailing_zeros(),
+                    b.leading_zeros(),
+                    b.rotate_right(1),
+                    b.rotate_left(65),
+                )
+            }
+            "#
+        },
+        (3, 61, 0, 63, i64::MIN, 2),
+    };
+
+    assert_eq! {
+        rune!((i64, i64) => r#"fn main() { let a = 1; (a.swap_bytes(), a.reverse_bits()) }"#),
+        (1 << 56, i64::MIN),
+    };
 }
 
 #[test]
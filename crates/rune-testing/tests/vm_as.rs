@@ -0,0 +1,47 @@
+use rune_testing::*;
+
+#[test]
+fn test_mixed_arithmetic() {
+    assert_eq!(rune!(f64 => r#"fn main() { 1 + 2.5 }"#), 3.5);
+    assert_eq!(rune!(f64 => r#"fn main() { 2.5 * 2 }"#), 5.0);
+    assert_eq!(rune!(f64 => r#"fn main() { 7 / 2.0 }"#), 3.5);
+    assert_eq!(rune!(f64 => r#"fn main() { let a = 1; a += 0.5; a }"#), 1.5);
+    assert_eq!(rune!(f64 => r#"fn main() { let a = 1.5; a -= 1; a }"#), 0.5);
+
+    assert_eq! {
+        rune!((bool, bool, bool) => r#"fn main() { (1 < 1.5, 2.0 >= 2, 3 > 3.5) }"#),
+        (true, true, false),
+    };
+
+    assert_eq! {
+        rune!((bool, bool, bool, bool) => r#"fn main() { (1 == 1.0, 2.0 == 2, 1 == 1.5, 0.5 == 0) }"#),
+        (true, true, false, false),
+    };
+
+    assert_eq! {
+        rune!((bool, bool, bool) => r#"fn main() { let a = 1; (a != 1.0, 1.5 != a, a != 0.0 / 0.0) }"#),
+        (false, true, true),
+    };
+}
+
+#[test]
+fn test_as() {
+    assert_eq!(rune!(i64 => r#"fn main() { 3.7 as int }"#), 3);
+    assert_eq!(rune!(i64 => r#"fn main() { -3.7 as int }"#), -3);
+    assert_eq!(
+        rune!(f64 => r#"fn main() { 7 as float / 2 as float }"#),
+        3.5
+    );
+    assert_eq!(rune!(u8 => r#"fn main() { 300 as byte }"#), 44);
+    assert_eq!(rune!(char => r#"fn main() { b'a' as char }"#), 'a');
+    assert_eq!(rune!(i64 => r#"fn main() { 'a' as int + 1 }"#), 98);
+    assert_eq!(rune!(i64 => r#"fn main() { true as int }"#), 1);
+
+    assert_vm_error!(
+        r#"fn main() { "42" as int }"#,
+        UnsupportedAs { value, target } => {
+            assert_eq!(value.to_string(), "String");
+            assert_eq!(target.to_string(), "integer");
+        }
+    );
+}
@@ -15,3 +15,20 @@ fn test_anonymous_type_precedence() {
         }
     };
 }
+
+#[test]
+fn test_block_declaring_locals_as_instance_call_argument() {
+    assert_eq! {
+        vec![1, 3],
+        rune! {
+            Vec<i64> => r#"
+            fn main() {
+                let out = [];
+                out.push({ let a = 1; a });
+                out.push({ let a = 1; let b = 2; a + b });
+                out
+            }
+            "#
+        }
+    };
+}
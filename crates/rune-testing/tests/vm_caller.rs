@@ -0,0 +1,38 @@
+use rune_testing::*;
+use runestick::{Caller, Context, FromValue, Item, Module, Vm};
+use std::sync::Arc;
+
+#[test]
+fn test_caller_information() -> Result<()> {
+    let mut module = Module::new(&["host"]);
+
+    module.function(&["caller"], || {
+        let caller = Caller::current().expect("called from a vm");
+        let item = caller.item().map(|item| item.to_string());
+        (item, caller.span().map(|span| (span.start, span.end)))
+    })?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+
+    let source = r#"
+    fn inner() {
+        host::caller()
+    }
+
+    fn main() {
+        inner()
+    }
+    "#;
+
+    let (unit, _) = compile_source(&context, source)?;
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let output = vm.call(Item::of(&["main"]), ())?.complete()?;
+    let (item, span) = <(Option<String>, Option<(usize, usize)>)>::from_value(output)?;
+
+    assert_eq!(item.as_deref(), Some("inner"));
+    let (start, end) = span.expect("span of call site");
+    assert_eq!(&source[start..end], "host::caller()");
+    assert!(Caller::current().is_none());
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use rune_testing::*;
+use runestick::{Context, FromValue as _, Generator, GeneratorState, Item, Shared, Value, Vm};
+use std::sync::Arc;
+
+#[test]
+fn test_census() -> Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let source = r#"
+    struct Entry { values }
+
+    fn leak() {
+        let cache = [];
+
+        loop {
+            cache.push(Entry { values: [cache.len()] });
+            yield cache.len();
+        }
+    }
+
+    fn main() {
+        leak()
+    }
+    "#;
+
+    let (unit, _) = compile_source(&context, source)?;
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let output = vm.call(Item::of(&["main"]), ())?.complete()?;
+    let generator = Shared::<Generator>::from_value(output)?;
+    let mut generator = generator.borrow_mut()?;
+
+    for _ in 0..3 {
+        assert!(matches!(
+            generator.resume(Value::Unit)?,
+            GeneratorState::Yielded(..)
+        ));
+    }
+
+    let census = generator.vm()?.census();
+    assert_eq!(census.count("Entry"), 3);
+    assert_eq!(census.count("Vec"), 4);
+    assert_eq!(census.count("Generator"), 1);
+    assert_eq!(census.total(), 8);
+    assert_eq!(census.to_string(), "Entry: 3\nGenerator: 1\nVec: 4\n");
+
+    let (_, entry) = census.iter().find(|(name, _)| *name == "Entry").unwrap();
+    let sites = entry.sites().collect::<Vec<_>>();
+    assert_eq!(sites.len(), 1);
+    assert_eq!(sites[0].1, 3);
+    Ok(())
+}
+
+#[test]
+fn test_census_cycle() -> Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let source = r#"
+    fn main() {
+        let object = #{};
+        let closure = || object;
+        object.closure = closure;
+    }
+    "#;
+
+    let (unit, _) = compile_source(&context, source)?;
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    vm.clone().call(Item::of(&["main"]), ())?.complete()?;
+
+    // NB: the object and the closure, together with its environment, keep
+    // each other alive even though none of them are reachable anymore.
+    let census = vm.census();
+    assert_eq!(census.to_string(), "Function: 1\nObject: 1\nTuple: 1\n");
+    Ok(())
+}
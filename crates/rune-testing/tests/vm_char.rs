@@ -0,0 +1,90 @@
+use rune_testing::*;
+
+#[test]
+fn test_char_classification() {
+    assert_eq! {
+        rune!((bool, bool, bool, bool, bool, bool) => r#"
+        fn main() {
+            (
+                'a'.is_alphabetic(),
+                '1'.is_alphabetic(),
+                ' '.is_whitespace(),
+                'F'.is_digit(16),
+                'F'.is_digit(10),
+                'Q'.is_uppercase(),
+            )
+        }
+        "#),
+        (true, false, true, true, false, true)
+    };
+}
+
+#[test]
+fn test_char_conversions() {
+    assert_eq! {
+        rune!((i64, Option<i64>, char, char, char, String) => r#"
+        fn main() {
+            (
+                'A'.to_int(),
+                'f'.to_digit(16),
+                'a'.to_uppercase(),
+                'ß'.to_uppercase(),
+                'Ä'.to_lowercase(),
+                'x'.to_string(),
+            )
+        }
+        "#),
+        (65, Some(15), 'A', 'ß', 'ä', String::from("x"))
+    };
+
+    assert_eq! {
+        rune!((Option<char>, Option<char>, Option<char>, Option<char>, Option<char>) => r#"
+        fn main() {
+            (
+                char::from_int(97),
+                char::from_int(-1),
+                char::from_digit(11, 16),
+                char::from_string("z"),
+                char::from_string("zz"),
+            )
+        }
+        "#),
+        (Some('a'), None, Some('b'), Some('z'), None)
+    };
+}
+
+#[test]
+fn test_char_scanner() {
+    assert_eq! {
+        rune!((i64, i64, i64) => r#"
+        fn main() {
+            let letters = 0;
+            let digits = 0;
+            let other = 0;
+
+            for c in "ab1 2c!".chars() {
+                if c.is_alphabetic() {
+                    letters = letters + 1;
+                } else if c.is_digit(10) {
+                    digits = digits + 1;
+                } else {
+                    other = other + 1;
+                }
+            }
+
+            (letters, digits, other)
+        }
+        "#),
+        (3, 2, 2)
+    };
+}
+
+#[test]
+fn test_char_invalid_radix() {
+    assert_vm_error!(
+        r#"fn main() { '1'.is_digit(37) }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "radix must be in the range 2 to 36, but was 37");
+        }
+    );
+}
@@ -0,0 +1,91 @@
+use rune_testing::*;
+use runestick::{Context, Module, ToValue as _, Value, VmError};
+
+fn coercion_context(coerce: bool) -> Result<Context> {
+    let mut module = Module::new(&["math"]);
+    module.function(&["half"], |n: f64| n / 2.0)?;
+    module.function(&["shout"], |s: String| s.to_uppercase())?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+
+    if coerce {
+        context.coerce::<f64, _>(|value| match value {
+            Value::Integer(n) => Ok(Value::Float(n as f64)),
+            value => Ok(value),
+        });
+
+        context.coerce::<String, _>(|value| match value {
+            Value::Char(c) => c.to_string().to_value(),
+            value => Ok(value),
+        });
+    }
+
+    Ok(context)
+}
+
+#[test]
+fn test_coercion_hooks() -> Result<()> {
+    let output: f64 = run_with_context(
+        coercion_context(true)?,
+        &["main"],
+        (),
+        r#"fn main() { math::half(3) + math::half(1.0) }"#,
+    )?;
+
+    assert_eq!(output, 2.0);
+
+    let output: (String, String) = run_with_context(
+        coercion_context(true)?,
+        &["main"],
+        (),
+        r#"fn main() { (math::shout('a'), math::shout("bc")) }"#,
+    )?;
+
+    assert_eq!(output, (String::from("A"), String::from("BC")));
+    Ok(())
+}
+
+#[test]
+fn test_coercion_without_hooks() -> Result<()> {
+    let error = run_with_context::<_, _, f64>(
+        coercion_context(false)?,
+        &["main"],
+        (),
+        r#"fn main() { math::half(3) }"#,
+    )
+    .unwrap_err();
+
+    let error = error.downcast_ref::<VmError>().expect("vm error");
+
+    match error.kind().into_unwound_ref().0 {
+        BadArgument { arg: 0, .. } => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_coercion_reference() -> Result<()> {
+    let mut module = Module::new(&["math"]);
+    module.function(&["len"], |s: &str| s.len() as i64)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+
+    context.coerce::<&str, _>(|value| match value {
+        Value::Char(c) => c.to_string().to_value(),
+        value => Ok(value),
+    });
+
+    let output: i64 = run_with_context(
+        context,
+        &["main"],
+        (),
+        r#"fn main() { math::len('a') + math::len("bc") }"#,
+    )?;
+
+    assert_eq!(output, 3);
+    Ok(())
+}
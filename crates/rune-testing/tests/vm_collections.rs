@@ -0,0 +1,248 @@
+use rune_testing::*;
+
+#[test]
+fn test_vec_sort_by() {
+    assert_eq! {
+        rune! {
+            Vec<i64> => r#"
+            fn main() {
+                let v = [3, 1, 4, 1, 5];
+                v.sort_by(|a, b| b - a);
+                v
+            }
+            "#
+        },
+        vec![5, 4, 3, 1, 1],
+    };
+}
+
+#[test]
+fn test_vec_map_filter() {
+    assert_eq! {
+        rune! {
+            Vec<i64> => r#"
+            fn main() {
+                let offset = 10;
+                [1, 2, 3, 4].filter(|v| v % 2 == 0).map(|v| v + offset)
+            }
+            "#
+        },
+        vec![12, 14],
+    };
+}
+
+#[test]
+fn test_object_retain() {
+    assert_eq! {
+        rune! {
+            (bool, bool, i64) => r#"
+            fn main() {
+                let o = #{"a": 1, "b": 2, "c": 3};
+                o.retain(|k, v| k != "a" && v < 3);
+                (o.contains_key("a"), o.contains_key("b"), o.len())
+            }
+            "#
+        },
+        (false, true, 1),
+    };
+}
+
+#[test]
+fn test_callback_errors() {
+    assert_vm_error!(
+        r#"
+        fn main() {
+            [1, 2].map(|v| v / 0)
+        }
+        "#,
+        DivideByZero => {}
+    );
+
+    assert_vm_error!(
+        r#"
+        fn main() {
+            [1, 2].sort_by(|a, b| a / 0)
+        }
+        "#,
+        DivideByZero => {}
+    );
+}
+
+#[test]
+fn test_callbacks_iterate_snapshot() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<i64>, i64) => r#"
+            fn main() {
+                let v = [1, 2, 3];
+                let a = v.map(|n| { v.push(n); n * 2 });
+                let b = v.filter(|n| { v.push(n); n > 1 });
+                (a, b, v.len())
+            }
+            "#
+        },
+        (vec![2, 4, 6], vec![2, 3, 2, 3], 12),
+    };
+
+    assert_eq! {
+        rune! {
+            Vec<i64> => r#"
+            fn main() {
+                let v = [3, 1, 2];
+                v.sort_by(|a, b| a - b + v.len() - 3);
+                v
+            }
+            "#
+        },
+        vec![1, 2, 3],
+    };
+}
+
+#[test]
+fn test_modified_during_iteration() {
+    assert_vm_error!(
+        r#"
+        fn main() {
+            let v = [3, 1, 2];
+            v.sort_by(|a, b| { v.push(a); a - b });
+        }
+        "#,
+        ModifiedDuringIteration { target } => {
+            assert_eq!(target.to_string(), "Vec");
+        }
+    );
+
+    assert_vm_error!(
+        r#"
+        fn main() {
+            let o = #{"a": 1, "b": 2};
+            o.retain(|k, v| { o.insert(k, v + 1); true });
+        }
+        "#,
+        ModifiedDuringIteration { target } => {
+            assert_eq!(target.to_string(), "Object");
+        }
+    );
+}
+
+#[test]
+fn test_checked_get() {
+    assert_eq! {
+        rune! {
+            (Option<i64>, Option<i64>, Option<i64>, Option<i64>) => r#"
+            fn main() {
+                let v = [1, 2, 3];
+                (v.get(0), v.get(3), v.get(-1), v.get(-4))
+            }
+            "#
+        },
+        (Some(1), None, Some(3), None),
+    };
+
+    assert_eq! {
+        rune! {
+            (Option<i64>, Option<i64>, usize) => r#"
+            fn main() {
+                let t = (1, 2);
+                (t.get(1), t.get(2), t.len())
+            }
+            "#
+        },
+        (Some(2), None, 2),
+    };
+
+    assert_eq! {
+        rune! {
+            (Option<i64>, Option<i64>, Option<i64>, i64) => r#"
+            fn main() {
+                let t = (1, 2);
+                (t.get(-1), t.get(-2), t.get(-3), t[-2])
+            }
+            "#
+        },
+        (Some(2), Some(1), None, 1),
+    };
+
+    assert_eq! {
+        rune! {
+            (Option<i64>, Option<i64>) => r#"
+            fn main() {
+                let o = #{"a": 1};
+                (o.get("a"), o.get("b"))
+            }
+            "#
+        },
+        (Some(1), None),
+    };
+}
+
+#[test]
+fn test_negative_index_and_slice_assignment() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64) => r#"
+            fn main() {
+                let v = [1, 2, 3];
+                let t = (4, 5);
+                (v[-1], v[-3], t[-1])
+            }
+            "#
+        },
+        (3, 1, 5),
+    };
+
+    assert_eq! {
+        rune! {
+            Vec<i64> => r#"
+            fn main() {
+                let v = [1, 2, 3];
+                v[0] = 10;
+                v[-1] = 30;
+                v
+            }
+            "#
+        },
+        vec![10, 2, 30],
+    };
+
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<i64>) => r#"
+            use std::iter::range;
+
+            fn main() {
+                let v = [1, 2, 3, 4];
+                let head = v[range(0, 2)];
+                v[range(1, 3)] = [7, 8, 9];
+                v[range(-1, 5)] = (10,);
+                (head, v)
+            }
+            "#
+        },
+        (vec![1, 2], vec![1, 7, 8, 9, 10]),
+    };
+
+    assert_vm_error!(
+        r#"
+        fn main() {
+            let v = [1, 2, 3];
+            v[-4]
+        }
+        "#,
+        MissingIndex { .. } => {}
+    );
+
+    assert_vm_error!(
+        r#"
+        fn main() {
+            let v = [1, 2, 3];
+            v[std::iter::range(2, 4)] = [];
+        }
+        "#,
+        SliceOutOfBounds { start, end, len, .. } => {
+            assert_eq!(*start, 2);
+            assert_eq!(*end, 4);
+            assert_eq!(*len, 3);
+        }
+    );
+}
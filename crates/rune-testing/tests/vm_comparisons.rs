@@ -0,0 +1,76 @@
+use rune_testing::*;
+
+#[test]
+fn test_compare_strings() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool, bool) => r#"
+            fn main() {
+                let a = "apple";
+                let b = String::from_str("banana");
+                (a < b, b > a, a <= "apple", "apples" > a, "Zebra" < a)
+            }
+            "#
+        },
+        (true, true, true, true, true),
+    };
+}
+
+#[test]
+fn test_compare_chars_and_bytes() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool) => r#"
+            fn main() {
+                ('a' < 'b', 'z' >= 'z', b'a' < b'b', b"abc" < b"abd")
+            }
+            "#
+        },
+        (true, true, true, true),
+    };
+}
+
+#[test]
+fn test_compare_tuples() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool, bool) => r#"
+            fn main() {
+                (
+                    (1, 2) < (1, 3),
+                    (2, "a") > (1, "b"),
+                    (1, 2) <= (1, 2),
+                    (1, 2) < (1, 2, 0),
+                    (1, 2.5) > (1, 2),
+                )
+            }
+            "#
+        },
+        (true, true, true, true, true),
+    };
+
+    assert_vm_error!(
+        r#"fn main() { (1, "a") < (1, 2) }"#,
+        UnsupportedBinaryOperation { op: "<", .. } => {}
+    );
+}
+
+#[test]
+fn test_compare_unordered() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool) => r#"
+            fn main() {
+                let nan = 0.0 / 0.0;
+                (nan < 1.0, nan >= 1.0, (1, nan) < (1, 2.0))
+            }
+            "#
+        },
+        (false, false, false),
+    };
+
+    assert_vm_error!(
+        r#"fn main() { "a" < 'a' }"#,
+        UnsupportedBinaryOperation { op: "<", .. } => {}
+    );
+}
@@ -0,0 +1,115 @@
+use rune_testing::*;
+
+#[test]
+fn test_content_hash_equal_values() -> Result<()> {
+    let output: (bool, bool, bool, bool) = run(
+        &["main"],
+        (),
+        r#"
+        struct Rule { name, weight }
+
+        fn main() {
+            use std::mem::content_hash;
+
+            let a = #{};
+            a.x = [1, 2.5, "three", Some(b"four")];
+            a.y = Rule { name: "rule", weight: 1 };
+
+            let b = #{};
+            b.y = Rule { weight: 1, name: String::from_str("rule") };
+            b.x = [1, 2.5, "three", Some(b"four")];
+
+            (
+                content_hash(a) == content_hash(b),
+                content_hash((1, 2)) == content_hash((1, 2)),
+                content_hash(0.0) == content_hash(-0.0),
+                content_hash(()) == content_hash(()),
+            )
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, (true, true, true, true));
+    Ok(())
+}
+
+#[test]
+fn test_content_hash_different_values() -> Result<()> {
+    let output: (bool, bool, bool, bool) = run(
+        &["main"],
+        (),
+        r#"
+        struct A { value }
+        struct B { value }
+
+        fn main() {
+            use std::mem::content_hash;
+
+            (
+                content_hash([1, 2]) != content_hash([2, 1]),
+                content_hash([1, 2]) != content_hash((1, 2)),
+                content_hash(A { value: 1 }) != content_hash(B { value: 1 }),
+                content_hash(#{a: 1}) != content_hash(#{a: 2}),
+            )
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, (true, true, true, true));
+    Ok(())
+}
+
+#[test]
+fn test_content_hash_is_stable() -> Result<()> {
+    let source = r#"
+    fn main() {
+        std::mem::content_hash(#{name: "rune", tags: ["vm", "script"], version: (0, 7)})
+    }
+    "#;
+
+    let a: i64 = run(&["main"], (), source)?;
+    let b: i64 = run(&["main"], (), source)?;
+    assert_eq!(a, b);
+
+    // NB: the hash doesn't depend on the endianness of the platform.
+    let source = r#"
+    fn main() {
+        std::mem::content_hash([1, -2, 2.5, 'x', "rune", b"vm", (), Some(true)])
+    }
+    "#;
+
+    let c: i64 = run(&["main"], (), source)?;
+    assert_eq!(c, -1906230981779844760);
+    Ok(())
+}
+
+#[test]
+fn test_content_hash_errors() {
+    let result = run::<_, _, ()>(
+        &["main"],
+        (),
+        r#"
+        fn main() {
+            let a = [];
+            a.push(a);
+            std::mem::content_hash(a);
+        }
+        "#,
+    );
+
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("contains itself"), "{}", error);
+
+    let result = run::<_, _, ()>(
+        &["main"],
+        (),
+        r#"
+        fn main() {
+            std::mem::content_hash([|| 1]);
+        }
+        "#,
+    );
+
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("can't be content hashed"), "{}", error);
+}
@@ -0,0 +1,133 @@
+use rune::{Options, Sources, Warnings};
+use runestick::{Context, Item, Source, Unit, Vm};
+use std::sync::Arc;
+
+fn compile(source: &str, coverage: bool) -> Arc<Unit> {
+    let context = Context::with_default_modules().unwrap();
+    let mut options = Options::default();
+
+    if coverage {
+        options.parse_option("coverage").unwrap();
+    }
+
+    let mut sources = Sources::new();
+    sources.insert_default(Source::new("main", source.to_owned()));
+
+    let unit = rune::load_sources(&context, &options, &mut sources, &mut Warnings::new()).unwrap();
+    Arc::new(unit)
+}
+
+fn run(unit: &Arc<Unit>) -> i64 {
+    let context = Arc::new(Context::with_default_modules().unwrap());
+    let vm = Vm::new(context, unit.clone());
+    let output =
+        rune_testing::block_on(vm.call(Item::of(&["main"]), ()).unwrap().async_complete()).unwrap();
+
+    runestick::FromValue::from_value(output).unwrap()
+}
+
+/// Collect the hits of every counter, by the source text it covers.
+fn hits<'a>(unit: &Unit, source: &'a str) -> Vec<(&'a str, u64)> {
+    unit.coverage()
+        .iter()
+        .map(|c| (&source[c.span.start..c.span.end], c.hits()))
+        .collect()
+}
+
+#[test]
+fn test_coverage_counts_statements() {
+    let source = r#"
+    fn sign(n) {
+        if n < 0 {
+            return -1;
+        }
+
+        1
+    }
+
+    fn main() {
+        let a = sign(-5);
+        let b = sign(5) + sign(6);
+        a + b
+    }
+    "#;
+
+    let unit = compile(source, true);
+    assert_eq!(run(&unit), 1);
+
+    let mut hits = hits(&unit, source);
+    hits.sort();
+
+    let mut expected = vec![
+        ("if n < 0 {\n            return -1;\n        }", 3),
+        ("return -1", 1),
+        ("1", 2),
+        ("let a = sign(-5)", 1),
+        ("let b = sign(5) + sign(6)", 1),
+        ("a + b", 1),
+    ];
+
+    expected.sort();
+    assert_eq!(hits, expected);
+}
+
+#[test]
+fn test_coverage_match_arms_and_closures() {
+    let source = r#"
+    fn main() {
+        let f = |n| n * 2;
+
+        match f(2) {
+            4 => 1,
+            _ => 0,
+        }
+    }
+    "#;
+
+    let unit = compile(source, true);
+    assert_eq!(run(&unit), 1);
+
+    let hits = hits(&unit, source)
+        .into_iter()
+        .filter(|(text, _)| text.len() < 6)
+        .collect::<Vec<_>>();
+
+    // NB: closures are compiled after the function they're declared in.
+    assert_eq!(hits, vec![("1", 1), ("0", 0), ("n * 2", 1)]);
+}
+
+#[test]
+fn test_coverage_across_async_calls() {
+    let source = r#"
+    async fn double(n) {
+        n * 2
+    }
+
+    async fn main() {
+        double(1).await + double(2).await
+    }
+    "#;
+
+    let unit = compile(source, true);
+    assert_eq!(run(&unit), 6);
+
+    assert!(hits(&unit, source).contains(&("n * 2", 2)));
+
+    unit.coverage().reset();
+    assert!(unit.coverage().iter().all(|c| c.hits() == 0));
+
+    assert_eq!(run(&unit), 6);
+    assert!(hits(&unit, source).contains(&("n * 2", 2)));
+}
+
+#[test]
+fn test_coverage_disabled_by_default() {
+    let source = r#"fn main() { let a = 1; a + 1 }"#;
+
+    let unit = compile(source, false);
+    assert_eq!(run(&unit), 2);
+    assert!(unit.coverage().is_empty());
+    assert!(unit
+        .iter_instructions()
+        .all(|inst| !matches!(inst, runestick::Inst::Coverage { .. })));
+}
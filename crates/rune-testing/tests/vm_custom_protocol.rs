@@ -0,0 +1,185 @@
+use rune_testing::*;
+use runestick::{Context, ContextError, Module, Protocol};
+
+#[derive(Debug, Clone)]
+struct Pattern {
+    prefix: String,
+}
+
+runestick::impl_external!(Pattern);
+
+fn context() -> Result<Context> {
+    let matches = Protocol::custom("matches");
+
+    let mut module = Module::new(&["dsl"]);
+    module.protocol(matches)?;
+    module.interface(&["Matcher"], &[matches])?;
+    module.ty(&["Pattern"]).build::<Pattern>()?;
+    module.function(&["Pattern", "new"], |prefix: &str| Pattern {
+        prefix: prefix.to_owned(),
+    })?;
+    module.inst_fn(matches, |pattern: &Pattern, s: &str| {
+        s.starts_with(&pattern.prefix)
+    })?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+    Ok(context)
+}
+
+#[test]
+fn test_custom_protocol() -> Result<()> {
+    let output: (bool, bool, bool, bool) = run_with_context(
+        context()?,
+        &["main"],
+        (),
+        r#"
+        use dsl::Pattern;
+
+        struct Exact { value }
+
+        impl Exact {
+            fn matches(self, s) {
+                self.value == s
+            }
+        }
+
+        fn check(pattern, s) {
+            pattern.matches(s)
+        }
+
+        fn main() {
+            let a = Pattern::new("foo");
+            let b = Exact { value: "foo" };
+            (check(a, "foobar"), check(a, "bar"), check(b, "foo"), check(b, "foobar"))
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, (true, false, true, false));
+    Ok(())
+}
+
+#[test]
+fn test_custom_protocol_registry() -> Result<()> {
+    let context = context()?;
+    let matches = context
+        .lookup_protocol("matches")
+        .expect("missing protocol");
+    assert_eq!(matches.hash, Protocol::custom("matches").hash);
+    assert_ne!(matches.hash, runestick::Hash::of("matches"));
+    assert!(context.lookup_protocol("other").is_none());
+
+    let mut other = Module::new(&["other"]);
+    other.protocol(Protocol {
+        name: "matches",
+        hash: runestick::Hash::of("matches"),
+    })?;
+
+    let mut context = context;
+
+    match context.install(&other) {
+        Err(ContextError::ConflictingProtocol { name: "matches" }) => (),
+        result => panic!("unexpected result: {:?}", result.map(|_| ())),
+    }
+
+    // NB: the rejected protocol doesn't replace the original one.
+    let matches = context
+        .lookup_protocol("matches")
+        .expect("missing protocol");
+    assert_eq!(matches.hash, Protocol::custom("matches").hash);
+
+    let mut module = Module::new(&["dsl"]);
+    module.protocol(Protocol::custom("matches"))?;
+
+    match module.protocol(Protocol {
+        name: "matches",
+        hash: runestick::Hash::of("matches"),
+    }) {
+        Err(ContextError::ConflictingProtocol { name: "matches" }) => (),
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+
+    let matches = context
+        .lookup_protocol("matches")
+        .expect("missing protocol");
+    assert_eq!(matches.hash, Protocol::custom("matches").hash);
+    Ok(())
+}
+
+#[test]
+fn test_custom_interface() -> Result<()> {
+    let context = context()?;
+
+    let interfaces = context
+        .iter_interfaces()
+        .map(|(_, interface)| interface.to_string())
+        .collect::<Vec<_>>();
+
+    assert!(interfaces.contains(&String::from("dsl::Matcher => matches")));
+
+    let output: (bool, bool, bool, bool) = run_with_context(
+        context,
+        &["main"],
+        (),
+        r#"
+        use dsl::Matcher;
+        use dsl::Pattern;
+
+        struct Text;
+
+        impl Text {
+            fn matches(self, s) {
+                true
+            }
+        }
+
+        struct Other;
+
+        fn main() {
+            (
+                Pattern::new("a") is Matcher,
+                Text is Matcher,
+                Other is not Matcher,
+                1 is not Matcher,
+            )
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, (true, true, true, true));
+    Ok(())
+}
+
+#[test]
+fn test_custom_protocol_shadowing_instance_fn() -> Result<()> {
+    let mut module = Module::new(&["dsl"]);
+    module.protocol(Protocol::custom("len"))?;
+
+    let mut context = Context::with_default_modules()?;
+
+    match context.install(&module) {
+        Err(ContextError::ConflictingProtocolFunction { name: "len" }) => (),
+        result => panic!("unexpected result: {:?}", result.map(|_| ())),
+    }
+
+    let mut module = Module::new(&["dsl"]);
+    module.protocol(Protocol::custom("size"))?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+
+    let mut other = Module::new(&["other"]);
+    other.ty(&["Pattern"]).build::<Pattern>()?;
+    other.inst_fn("size", |_: &Pattern| 0i64)?;
+
+    match context.install(&other) {
+        Err(ContextError::ConflictingProtocolFunction { name: "size" }) => (),
+        result => panic!("unexpected result: {:?}", result.map(|_| ())),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use rune_testing::*;
+use runestick::{Context, ContextError, FromValue as _, Module, ToValue as _, VmError};
+use std::sync::{Arc, Mutex};
+
+fn math_context() -> Result<Context> {
+    let mut module = Module::new(&["math"]);
+    module.function(&["add"], |a: i64, b: i64| a + b)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+    Ok(context)
+}
+
+#[test]
+fn test_decorate() -> Result<()> {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let mut context = math_context()?;
+
+    let c = calls.clone();
+    context.decorate(&["math", "add"], move |original, args| {
+        let a = i64::from_value(args[0].clone())?;
+        let b = i64::from_value(args[1].clone())?;
+        c.lock().unwrap().push((a, b));
+        let output = i64::from_value(original.call_values(args)?)?;
+        (output * 10).to_value()
+    })?;
+
+    let output: i64 = run_with_context(
+        context,
+        &["main"],
+        (),
+        r#"fn main() { math::add(1, 2) + math::add(3, 4) }"#,
+    )?;
+
+    assert_eq!(output, 100);
+    assert_eq!(*calls.lock().unwrap(), vec![(1, 2), (3, 4)]);
+    Ok(())
+}
+
+#[test]
+fn test_decorate_twice() -> Result<()> {
+    let mut context = math_context()?;
+
+    context.decorate(&["math", "add"], |original, args| {
+        let output = i64::from_value(original.call_values(args)?)?;
+        (output + 1).to_value()
+    })?;
+
+    context.decorate(&["math", "add"], |original, args| {
+        let output = i64::from_value(original.call_values(args)?)?;
+        (output * 2).to_value()
+    })?;
+
+    let output: i64 = run_with_context(
+        context,
+        &["main"],
+        (),
+        r#"fn main() { let add = math::add; add(1, 2) }"#,
+    )?;
+
+    assert_eq!(output, 8);
+    Ok(())
+}
+
+#[test]
+fn test_decorate_refuse() -> Result<()> {
+    let mut context = math_context()?;
+
+    context.decorate(&["math", "add"], |original, args| {
+        if i64::from_value(args[0].clone())? < 0 {
+            return Err(VmError::panic("negative numbers are not allowed"));
+        }
+
+        original.call_values(args)
+    })?;
+
+    let error =
+        run_with_context::<_, _, i64>(context, &["main"], (), r#"fn main() { math::add(-1, 2) }"#)
+            .unwrap_err();
+
+    assert!(error.downcast_ref::<VmError>().is_some());
+    Ok(())
+}
+
+#[test]
+fn test_decorate_missing() -> Result<()> {
+    let mut context = math_context()?;
+
+    match context.decorate(&["math", "sub"], |original, args| {
+        original.call_values(args)
+    }) {
+        Err(ContextError::MissingFunction { name }) => {
+            assert_eq!(name.to_string(), "math::sub");
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use rune_testing::*;
+
+const DESCRIBE: &str = r#"
+fn describe(patch) {
+    let out = [];
+
+    for operation in patch {
+        let path = String::new();
+
+        for segment in operation.path {
+            path.push_str(`/{segment}`);
+        }
+
+        out.push(`{operation.op} {path}`);
+    }
+
+    out
+}
+"#;
+
+fn diff(a: &str, b: &str) -> Result<Vec<String>> {
+    let source = format!(
+        r#"
+        {}
+
+        fn main() {{
+            describe(std::diff::diff({}, {}))
+        }}
+        "#,
+        DESCRIBE, a, b
+    );
+
+    run(&["main"], (), &source)
+}
+
+#[test]
+fn test_diff_objects_and_vectors() -> Result<()> {
+    let patch = diff(
+        r#"#{name: "rune", tags: ["a", "b", "c"], nested: #{x: 1, y: 2}, old: true}"#,
+        r#"#{name: "rune", tags: ["a", "d"], nested: #{x: 1, y: 3}, new: 1}"#,
+    )?;
+
+    assert_eq!(
+        patch,
+        vec![
+            "replace /nested/y",
+            "add /new",
+            "remove /old",
+            "replace /tags/1",
+            "remove /tags/2",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_diff_equal_values() -> Result<()> {
+    let patch = diff(
+        r#"#{a: [1, (2, 3)], b: Some("x"), c: b"bytes"}"#,
+        r#"#{a: [1, (2, 3)], b: Some("x"), c: b"bytes"}"#,
+    )?;
+
+    assert!(patch.is_empty(), "{:?}", patch);
+    Ok(())
+}
+
+#[test]
+fn test_diff_structs() -> Result<()> {
+    let output: (Vec<String>, Vec<String>, Vec<String>) = run(
+        &["main"],
+        (),
+        &format!(
+            r#"
+            {}
+
+            struct Point {{ x, y }}
+            enum Shape {{ Circle(r), Square(side) }}
+
+            fn main() {{
+                let a = describe(std::diff::diff(Point {{ x: 1, y: 2 }}, Point {{ x: 1, y: 5 }}));
+                let b = describe(std::diff::diff(Shape::Circle(1), Shape::Circle(2)));
+                let c = describe(std::diff::diff(Shape::Circle(1), Shape::Square(1)));
+                (a, b, c)
+            }}
+            "#,
+            DESCRIBE
+        ),
+    )?;
+
+    assert_eq!(
+        output,
+        (
+            vec![String::from("replace /y")],
+            vec![String::from("replace /0")],
+            vec![String::from("replace ")],
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn test_apply_round_trip() -> Result<()> {
+    let output: (bool, bool) = run(
+        &["main"],
+        (),
+        r#"
+        fn main() {
+            let a = #{name: "rune", tags: ["a", "b", "c"], nested: #{x: 1, y: 2}, old: true};
+            let b = #{name: "rune", tags: ["a", "d"], nested: #{x: 1, y: 3}, new: 1};
+
+            let patched = std::diff::apply(a, std::diff::diff(a, b));
+            (patched == b, a.tags.len() == 3)
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, (true, true));
+    Ok(())
+}
+
+#[test]
+fn test_apply_invalid_patch() {
+    let result = run::<_, _, ()>(
+        &["main"],
+        (),
+        r#"
+        fn main() {
+            std::diff::apply(#{a: [1]}, [#{op: "replace", path: ["a", 3], value: 2}]);
+        }
+        "#,
+    );
+
+    let error = result.unwrap_err().to_string();
+    assert!(
+        error.contains("can't apply patch at `$.a[3]`: index out of bounds"),
+        "{}",
+        error
+    );
+}
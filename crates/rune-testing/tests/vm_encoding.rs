@@ -0,0 +1,109 @@
+use rune_testing::*;
+
+#[test]
+fn test_base64() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            use std::encoding::base64;
+            use std::encoding::base64_url;
+
+            fn main() {
+                let decoded = base64::decode("aGVsbG8/Pz4+")?;
+
+                [
+                    base64::encode(""),
+                    base64::encode("f"),
+                    base64::encode("fo"),
+                    base64::encode("foo"),
+                    base64::encode("hello??>>".into_bytes()),
+                    base64_url::encode("hello??>>"),
+                    String::from_utf8(decoded)?,
+                    String::from_utf8(base64::decode("Zm8=")?)?,
+                    String::from_utf8(base64::decode("Zm8")?)?,
+                    String::from_utf8(base64_url::decode("aGVsbG8_Pz4-")?)?,
+                ]
+            }
+            "#
+        },
+        vec![
+            String::from(""),
+            String::from("Zg=="),
+            String::from("Zm8="),
+            String::from("Zm9v"),
+            String::from("aGVsbG8/Pz4+"),
+            String::from("aGVsbG8_Pz4-"),
+            String::from("hello??>>"),
+            String::from("fo"),
+            String::from("fo"),
+            String::from("hello??>>"),
+        ],
+    };
+
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool, bool, bool, bool) => r#"
+            use std::encoding::base64;
+
+            fn main() {
+                (
+                    base64::decode("Zm9v!").is_err(),
+                    base64::decode("Z").is_err(),
+                    base64::decode("Zm=v").is_err(),
+                    base64::decode("QUJD====").is_err(),
+                    base64::decode("Zm8==").is_err(),
+                    base64::decode("Zm9=").is_err(),
+                    base64::decode("Zh==").is_err(),
+                )
+            }
+            "#
+        },
+        (true, true, true, true, true, true, true),
+    };
+}
+
+#[test]
+fn test_hex_and_percent() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            use std::encoding::hex;
+            use std::encoding::percent;
+
+            fn main() {
+                [
+                    hex::encode("hi!"),
+                    String::from_utf8(hex::decode("68692A")?)?,
+                    percent::encode("a b&c=ö~"),
+                    percent::decode("a%20b%26c%3D%C3%B6~")?,
+                ]
+            }
+            "#
+        },
+        vec![
+            String::from("686921"),
+            String::from("hi*"),
+            String::from("a%20b%26c%3D%C3%B6~"),
+            String::from("a b&c=ö~"),
+        ],
+    };
+
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool) => r#"
+            use std::encoding::hex;
+            use std::encoding::percent;
+
+            fn main() {
+                (
+                    hex::decode("abc").is_err(),
+                    hex::decode("zz").is_err(),
+                    percent::decode("%4").is_err(),
+                    percent::decode("%FF").is_err(),
+                )
+            }
+            "#
+        },
+        (true, true, true, true),
+    };
+}
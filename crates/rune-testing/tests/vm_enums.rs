@@ -0,0 +1,100 @@
+use rune_testing::*;
+
+#[test]
+fn test_variant_name() -> Result<()> {
+    let output: Vec<Option<String>> = run(
+        &["main"],
+        (),
+        r#"
+        enum Shape { Circle(r), Square { side }, Empty }
+
+        fn main() {
+            [
+                std::enums::variant_name(Shape::Circle(1)),
+                std::enums::variant_name(Shape::Square { side: 2 }),
+                std::enums::variant_name(Shape::Empty),
+                std::enums::variant_name(Some(1)),
+                std::enums::variant_name(Err(1)),
+                std::enums::variant_name(42),
+            ]
+        }
+        "#,
+    )?;
+
+    assert_eq!(
+        output,
+        vec![
+            Some(String::from("Circle")),
+            Some(String::from("Square")),
+            Some(String::from("Empty")),
+            Some(String::from("Some")),
+            Some(String::from("Err")),
+            None,
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_from_name() -> Result<()> {
+    let output: (bool, bool, bool) = run(
+        &["main"],
+        (),
+        r#"
+        enum Color { Red, Green, Blue }
+
+        fn main() {
+            let green = match std::enums::from_name(Color, "Green").unwrap() {
+                Color::Green => true,
+                _ => false,
+            };
+
+            let missing = std::enums::from_name(Color, "Purple").is_none();
+
+            let blue = std::enums::from_name(Color, "Blue").unwrap();
+            let roundtrip = std::enums::variant_name(blue).unwrap() == "Blue";
+            (green, missing, roundtrip)
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, (true, true, true));
+    Ok(())
+}
+
+#[test]
+fn test_from_name_with_fields() {
+    let result = run::<_, _, ()>(
+        &["main"],
+        (),
+        r#"
+        enum Shape { Circle(r), Empty }
+
+        fn main() {
+            std::enums::from_name(Shape, "Circle");
+        }
+        "#,
+    );
+
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("has fields"), "{}", error);
+}
+
+#[test]
+fn test_variants() -> Result<()> {
+    let output: Vec<String> = run(
+        &["main"],
+        (),
+        r#"
+        enum Level { Debug, Info, Warning(message), Error { code } }
+
+        fn main() {
+            std::enums::variants(Level)
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, vec!["Debug", "Info", "Warning", "Error"]);
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use rune_testing::*;
+use runestick::modules::env;
+use runestick::Context;
+
+#[test]
+fn test_env_args() -> Result<()> {
+    let mut context = Context::with_default_modules()?;
+    context.install(&env::module(vec![
+        String::from("script.rn"),
+        String::from("--flag"),
+        String::from("value"),
+    ])?)?;
+
+    let output: (Vec<String>, usize) = run_with_context(
+        context,
+        &["main"],
+        (),
+        r#"
+        fn main() {
+            let args = std::env::args();
+            // NB: every call returns a fresh copy of the arguments.
+            args.push("extra");
+            (args, std::env::args().len())
+        }
+        "#,
+    )?;
+
+    assert_eq!(
+        output,
+        (
+            vec![
+                String::from("script.rn"),
+                String::from("--flag"),
+                String::from("value"),
+                String::from("extra"),
+            ],
+            3
+        )
+    );
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use rune_testing::*;
+use runestick::{Context, Module};
+
+#[derive(Debug, Clone, Copy)]
+struct Point {
+    x: i64,
+    y: i64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Meters(i64);
+
+runestick::impl_external!(Point);
+runestick::impl_external!(Meters);
+
+fn context() -> Result<Context> {
+    let mut module = Module::new(&["geometry"]);
+    module.ty(&["Point"]).build::<Point>()?;
+    module.function(&["Point", "new"], |x: i64, y: i64| Point { x, y })?;
+    module.inst_fn(runestick::EQ, |a: &Point, b: &Point| {
+        a.x == b.x && a.y == b.y
+    })?;
+    module.ty(&["Meters"]).build::<Meters>()?;
+    module.function(&["Meters", "new"], Meters)?;
+    module.inst_fn(runestick::EQ, |a: &Meters, b: i64| a.0 == b)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+    Ok(context)
+}
+
+#[test]
+fn test_eq_protocol() -> Result<()> {
+    let output: (bool, bool, bool) = run_with_context(
+        context()?,
+        &["main"],
+        (),
+        r#"
+        use geometry::Point;
+
+        fn main() {
+            let a = Point::new(1, 2);
+            (a == Point::new(1, 2), a != Point::new(1, 2), a == Point::new(2, 1))
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, (true, false, false));
+    Ok(())
+}
+
+#[test]
+fn test_eq_protocol_unrelated_types() -> Result<()> {
+    let output: (bool, bool, bool, bool) = run_with_context(
+        context()?,
+        &["main"],
+        (),
+        r#"
+        use geometry::Meters;
+        use geometry::Point;
+
+        fn main() {
+            let a = Point::new(1, 2);
+            (a == 1, 1 == a, a != "point", a == Meters::new(1))
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, (false, false, true, false));
+    Ok(())
+}
+
+#[test]
+fn test_eq_protocol_either_side() -> Result<()> {
+    let output: (bool, bool, bool, bool) = run_with_context(
+        context()?,
+        &["main"],
+        (),
+        r#"
+        use geometry::Meters;
+
+        fn main() {
+            let m = Meters::new(3);
+            (m == 3, 3 == m, 4 == m, m != 4)
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, (true, true, false, true));
+    Ok(())
+}
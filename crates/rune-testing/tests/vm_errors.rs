@@ -0,0 +1,66 @@
+use rune_testing::*;
+use runestick::{Context, Item, Vm};
+use std::sync::Arc;
+
+/// Run the given source and return the error message, together with the
+/// source that the failing instruction points to.
+fn error_at(source: &str) -> (String, String) {
+    let context = Context::with_default_modules().unwrap();
+    let (unit, _) = compile_source(&context, source).unwrap();
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+
+    let error = block_on(vm.call(Item::of(&["main"]), ()).unwrap().async_complete()).unwrap_err();
+
+    let (error, unwound) = error.into_unwound();
+    let (unit, ip) = unwound.expect("error should be unwound");
+
+    let span = unit
+        .debug_info()
+        .and_then(|debug| debug.instruction_at(ip))
+        .expect("instruction should have debug info")
+        .span;
+
+    (error.to_string(), source[span.start..span.end].to_owned())
+}
+
+#[test]
+fn test_missing_field_span() {
+    assert_eq! {
+        error_at(r#"fn main() { let o = #{a: #{b: 1}}; o.a.c }"#),
+        (String::from("missing field `c` on `Object`"), String::from(".c")),
+    };
+
+    assert_eq! {
+        error_at(r#"fn main() { let o = #{a: #{b: 1}}; o.b.a }"#),
+        (String::from("missing field `b` on `Object`"), String::from(".b")),
+    };
+}
+
+#[test]
+fn test_missing_index_span() {
+    assert_eq! {
+        error_at(r#"fn main() { let v = [[1, 2], [3]]; v[1][1] }"#),
+        (String::from("missing index `1` on `Vec`"), String::from("[1]")),
+    };
+
+    assert_eq! {
+        error_at(r#"fn main() { let t = (1, 2); t.5 }"#),
+        (String::from("missing index `5` on `Tuple`"), String::from(".5")),
+    };
+
+    assert_eq! {
+        error_at(r#"fn main() { let v = [1]; v[2] = 3; }"#),
+        (String::from("missing index `2` on `Vec`"), String::from("v[2]")),
+    };
+}
+
+#[test]
+fn test_binary_operation_span() {
+    assert_eq! {
+        error_at(r#"fn main() { let a = 1; (a + 2) * (a + "b") }"#),
+        (
+            String::from("unsupported vm operation `integer + String`"),
+            String::from(r#"a + "b""#),
+        ),
+    };
+}
@@ -0,0 +1,88 @@
+use rune::{CompileError, EvalError, LoadErrorKind, ParseError};
+use runestick::{Context, Span, Value};
+use std::sync::Arc;
+
+#[test]
+fn test_eval() -> runestick::Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+
+    let output: i64 = rune::eval(&context, "a + b * 2", vec![("a", 1), ("b", 2)])?;
+    assert_eq!(output, 5);
+
+    // NB: bindings are matched by name, regardless of order.
+    let output: i64 = rune::eval(&context, "a + b * 2", vec![("b", 3), ("a", 4)])?;
+    assert_eq!(output, 10);
+
+    let output: String = rune::eval(
+        &context,
+        r#"`{name} is {age}`"#,
+        vec![
+            ("name", Value::from(String::from("Alice"))),
+            ("age", Value::Integer(42)),
+        ],
+    )?;
+
+    assert_eq!(output, "Alice is 42");
+
+    let output: i64 = rune::eval(&context, "1 + 2", Vec::<(&str, i64)>::new())?;
+    assert_eq!(output, 3);
+    Ok(())
+}
+
+#[test]
+fn test_eval_errors() -> runestick::Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+
+    let error = rune::eval::<_, _, _, i64>(&context, "a +", vec![("a", 1)]).unwrap_err();
+    assert!(matches!(error, EvalError::LoadError { .. }));
+
+    let error =
+        rune::eval::<_, _, _, i64>(&context, "a / b", vec![("a", 1), ("b", 0)]).unwrap_err();
+    assert!(matches!(error, EvalError::VmError { .. }));
+
+    let error = rune::eval::<_, _, _, i64>(&context, "1", vec![("not valid", 1)]).unwrap_err();
+    assert!(matches!(error, EvalError::InvalidName { .. }));
+    Ok(())
+}
+
+#[test]
+fn test_eval_single_expression() -> runestick::Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+
+    for expr in &["1 } fn other() { 2", "let a = 1; a"] {
+        match rune::eval::<_, _, _, i64>(&context, expr, vec![("a", 1)]) {
+            Err(EvalError::LoadError { error }) => match error.into_kind() {
+                LoadErrorKind::ParseError {
+                    error: ParseError::ExpectedEof { .. },
+                    ..
+                } => (),
+                kind => panic!("unexpected error for `{}`: {:?}", expr, kind),
+            },
+            result => panic!("unexpected result for `{}`: {:?}", expr, result.is_ok()),
+        }
+    }
+
+    // NB: the expression is followed by a newline, so it can end with a
+    // comment.
+    let output: i64 = rune::eval(&context, "a // comment", vec![("a", 1)])?;
+    assert_eq!(output, 1);
+    Ok(())
+}
+
+#[test]
+fn test_eval_spans() -> runestick::Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+
+    match rune::eval::<_, _, _, i64>(&context, "a + missing", vec![("a", 1)]) {
+        Err(EvalError::LoadError { error }) => match error.into_kind() {
+            LoadErrorKind::CompileError {
+                error: CompileError::MissingLocal { span, .. },
+                ..
+            } => assert_eq!(span, Span::new(4, 11)),
+            kind => panic!("unexpected error: {:?}", kind),
+        },
+        result => panic!("unexpected result: {:?}", result.is_ok()),
+    }
+
+    Ok(())
+}
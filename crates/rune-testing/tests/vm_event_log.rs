@@ -0,0 +1,82 @@
+use rune_testing::*;
+use runestick::{Context, Event, Item, Module, Vm};
+use std::sync::Arc;
+
+#[test]
+fn test_event_log() -> Result<()> {
+    let mut module = Module::new(&["audit"]);
+    module.function(&["send"], |_: &str, amount: i64| amount > 0)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+
+    let source = r#"
+    fn transfer(to, amount) {
+        if !audit::send(to, amount) {
+            panic("refused");
+        }
+    }
+
+    fn main() {
+        transfer("alice", 10);
+        transfer("bob", 0);
+    }
+    "#;
+
+    let (unit, _) = compile_source(&context, source)?;
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let mut execution = vm.call(Item::of(&["main"]), ())?.with_event_log(16);
+    assert!(execution.complete().is_err());
+
+    let mut events = execution.event_log().expect("event log").events();
+    assert!(matches!(events.pop(), Some(Event::Error { .. })));
+
+    let events = events.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(
+        events,
+        vec![
+            r#"call transfer("alice", 10)"#,
+            r#"native audit::send("alice", 10)"#,
+            r#"call transfer("bob", 0)"#,
+            r#"native audit::send("bob", 0)"#,
+            r#"native std::panic("refused")"#,
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_event_log_ring_buffer() -> Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let source = r#"
+    fn id(n) { n }
+
+    fn main() {
+        id(0);
+        id(1);
+        id("two");
+        id(#{});
+        id([4]);
+    }
+    "#;
+
+    let (unit, _) = compile_source(&context, source)?;
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let mut execution = vm.call(Item::of(&["main"]), ())?.with_event_log(2);
+    execution.complete()?;
+
+    let event_log = execution.event_log().expect("event log");
+    assert_eq!(event_log.dropped(), 3);
+
+    let events = event_log
+        .events()
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(events, vec!["call id(<Object>)", "call id(<Vec>)"]);
+    Ok(())
+}
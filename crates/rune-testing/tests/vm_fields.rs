@@ -0,0 +1,84 @@
+use rune_testing::*;
+
+#[test]
+fn test_struct_fields_in_declaration_order() -> Result<()> {
+    let output: Vec<(String, i64)> = run(
+        &["main"],
+        (),
+        r#"
+        struct User { name, age, email }
+
+        fn main() {
+            std::object::fields(User { email: 3, name: 1, age: 2 })
+        }
+        "#,
+    )?;
+
+    assert_eq!(
+        output,
+        vec![
+            (String::from("name"), 1),
+            (String::from("age"), 2),
+            (String::from("email"), 3),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_variant_fields_in_declaration_order() -> Result<()> {
+    let output: Vec<(String, i64)> = run(
+        &["main"],
+        (),
+        r#"
+        enum Shape { Rect { width, height } }
+
+        fn main() {
+            std::object::fields(Shape::Rect { height: 2, width: 1 })
+        }
+        "#,
+    )?;
+
+    assert_eq!(
+        output,
+        vec![(String::from("width"), 1), (String::from("height"), 2)]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_object_fields_sorted() -> Result<()> {
+    let output: Vec<(String, i64)> = run(
+        &["main"],
+        (),
+        r#"
+        fn main() {
+            std::object::fields(#{ c: 3, a: 1, b: 2 })
+        }
+        "#,
+    )?;
+
+    assert_eq!(
+        output,
+        vec![
+            (String::from("a"), 1),
+            (String::from("b"), 2),
+            (String::from("c"), 3),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_fields_of_non_object() {
+    assert_vm_error!(
+        r#"fn main() { std::object::fields(42) }"#,
+        Expected { expected, actual } => {
+            assert_eq!(expected.to_string(), "Object");
+            assert_eq!(actual.to_string(), "integer");
+        }
+    );
+}
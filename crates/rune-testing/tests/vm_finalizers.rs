@@ -0,0 +1,111 @@
+use rune_testing::*;
+use runestick::{Context, Module, Value};
+use std::sync::{Arc, Mutex};
+
+const SOURCE: &str = r#"
+fn main() {
+    let a = host::open(1);
+    host::open(2);
+    let b = host::open(3);
+    host::open(4)
+}
+"#;
+
+#[derive(Debug)]
+struct Handle {
+    id: i64,
+    closed: Arc<Mutex<Vec<i64>>>,
+}
+
+impl Handle {
+    fn close(self) {
+        self.closed.lock().unwrap().push(self.id);
+    }
+}
+
+runestick::impl_external!(Handle);
+
+fn open_module(closed: &Arc<Mutex<Vec<i64>>>) -> Result<Module> {
+    let closed = closed.clone();
+    let mut module = Module::new(&["host"]);
+    module.function(&["open"], move |id: i64| Handle {
+        id,
+        closed: closed.clone(),
+    })?;
+    Ok(module)
+}
+
+fn sorted(closed: &Arc<Mutex<Vec<i64>>>) -> Vec<i64> {
+    let mut closed = closed.lock().unwrap().clone();
+    closed.sort();
+    closed
+}
+
+#[test]
+fn test_finalizer() -> Result<()> {
+    let closed = Arc::new(Mutex::new(Vec::new()));
+
+    let mut module = open_module(&closed)?;
+    module.ty(&["Handle"]).build_with_finalizer(Handle::close)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+
+    let output: Handle = run_with_context(context, &["main"], (), SOURCE)?;
+
+    // NB: the returned value is owned by the host, so it's not finalized.
+    assert_eq!(output.id, 4);
+    drop(output);
+    assert_eq!(sorted(&closed), vec![1, 2, 3]);
+    Ok(())
+}
+
+#[test]
+fn test_finalizer_outside_vm() -> Result<()> {
+    let closed = Arc::new(Mutex::new(Vec::new()));
+
+    let mut module = open_module(&closed)?;
+    module.ty(&["Handle"]).build_with_finalizer(Handle::close)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+
+    let output: Value = run_with_context(context, &["main"], (), SOURCE)?;
+    assert_eq!(sorted(&closed), vec![1, 2, 3]);
+
+    // NB: the value is still finalized after the virtual machine is gone.
+    drop(output);
+    assert_eq!(sorted(&closed), vec![1, 2, 3, 4]);
+    Ok(())
+}
+
+#[test]
+fn test_async_finalizer() -> Result<()> {
+    let closed = Arc::new(Mutex::new(Vec::new()));
+    let queue = Arc::new(Mutex::new(Vec::new()));
+
+    let mut module = open_module(&closed)?;
+    module
+        .ty(&["Handle"])
+        .build_with_async_finalizer(|handle: Handle| async move { handle.close() })?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+
+    let q = queue.clone();
+    context.set_finalizer_executor(move |future| q.lock().unwrap().push(future));
+
+    let output: Handle = run_with_context(context, &["main"], (), SOURCE)?;
+    assert_eq!(output.id, 4);
+
+    let futures = std::mem::take(&mut *queue.lock().unwrap());
+    assert_eq!(futures.len(), 3);
+    assert!(closed.lock().unwrap().is_empty());
+
+    for future in futures {
+        block_on(future);
+    }
+
+    assert_eq!(sorted(&closed), vec![1, 2, 3]);
+    Ok(())
+}
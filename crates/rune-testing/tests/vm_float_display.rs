@@ -0,0 +1,51 @@
+use rune_testing::*;
+use runestick::{Context, FloatDisplay};
+
+const SOURCE: &str = r#"
+fn main() {
+    let a = 1.0;
+    let b = 0.25;
+    let c = 2;
+    `{a} {b} {c}`
+}
+"#;
+
+fn display(float_display: FloatDisplay) -> Result<String> {
+    let mut context = Context::with_default_modules()?;
+    context.set_float_display(float_display);
+    run_with_context(context, &["main"], (), SOURCE)
+}
+
+#[test]
+fn test_float_display() -> Result<()> {
+    assert_eq!(display(FloatDisplay::default())?, "1.0 0.25 2");
+    assert_eq!(display(FloatDisplay::Shortest)?, "1.0 0.25 2");
+    assert_eq!(display(FloatDisplay::Plain)?, "1 0.25 2");
+    assert_eq!(display(FloatDisplay::Fixed(1))?, "1.0 0.2 2");
+    assert_eq!(display(FloatDisplay::Fixed(3))?, "1.000 0.250 2");
+    Ok(())
+}
+
+#[test]
+fn test_float_round() {
+    assert_eq! {
+        rune!((f64, f64, f64) => r#"
+        fn main() {
+            (3.14159.round(2), 2.5.round(0), 1234.5678.round(1))
+        }
+        "#),
+        (3.14, 3.0, 1234.6)
+    };
+}
+
+#[test]
+fn test_float_to_fixed() {
+    assert_eq! {
+        rune!((String, String, String) => r#"
+        fn main() {
+            (3.14159.to_fixed(2), 1.0.to_fixed(0), 0.5.to_fixed(3))
+        }
+        "#),
+        ("3.14".to_owned(), "1".to_owned(), "0.500".to_owned())
+    };
+}
@@ -0,0 +1,94 @@
+use rune_testing::*;
+
+#[test]
+fn test_closure_tuple_arg() {
+    assert_eq! {
+        rune!(i64 => r#"
+        fn main() {
+            let add = |(a, b)| a + b;
+            add((1, 2))
+        }
+        "#),
+        3
+    };
+}
+
+#[test]
+fn test_fn_tuple_args() {
+    assert_eq! {
+        rune!(i64 => r#"
+        fn dist((x1, y1), (x2, y2)) {
+            let dx = x2 - x1;
+            let dy = y2 - y1;
+            dx * dx + dy * dy
+        }
+
+        fn main() {
+            dist((1, 2), (4, 6))
+        }
+        "#),
+        25
+    };
+}
+
+#[test]
+fn test_mixed_args() {
+    assert_eq! {
+        rune!(i64 => r#"
+        fn f(a, [b, c, ..], _, #{d}) {
+            a + b + c + d
+        }
+
+        fn main() {
+            f(1, [2, 3, 100], 1000, #{d: 4})
+        }
+        "#),
+        10
+    };
+}
+
+#[test]
+fn test_closure_captures_with_pattern_args() {
+    assert_eq! {
+        rune!(i64 => r#"
+        fn main() {
+            let offset = 10;
+            let f = |n, (a, b)| offset + n * (a + b);
+            let sum = 0;
+
+            for pair in [(1, 2), (3, 4)] {
+                sum = sum + f(2, pair);
+            }
+
+            sum
+        }
+        "#),
+        40
+    };
+}
+
+#[test]
+fn test_nested_pattern_arg() {
+    assert_eq! {
+        rune!(i64 => r#"
+        fn main() {
+            let f = |((a, b), [c])| a * b * c;
+            f(((2, 3), [4]))
+        }
+        "#),
+        24
+    };
+}
+
+#[test]
+fn test_pattern_arg_mismatch() {
+    assert_vm_error!(
+        r#"
+        fn f((a, b)) { a + b }
+        fn main() { f((1, 2, 3)) }
+        "#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "pattern did not match");
+        }
+    );
+}
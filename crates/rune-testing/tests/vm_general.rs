@@ -65,6 +65,52 @@ fn test_boolean_ops() {
     };
 }
 
+#[test]
+fn test_boolean_short_circuit() {
+    assert_eq! {
+        rune! {
+            (bool, bool, i64) => r#"
+            fn main() {
+                let calls = [];
+                let a = false && calls.push(1);
+                let b = true || calls.push(2);
+                let c = true && { calls.push(3); true };
+                let d = false || { calls.push(4); false };
+                (a || b, c && !d, calls.len())
+            }
+            "#
+        },
+        (true, true, 2),
+    };
+
+    assert_eq! {
+        rune! {
+            bool => r#"
+            fn main() {
+                let v = [];
+                v.len() > 0 && v[0] == 1
+            }
+            "#
+        },
+        false,
+    };
+
+    assert_vm_error!(
+        r#"fn main() { 1 && true }"#,
+        UnsupportedBinaryOperation { op: "&&", .. } => {}
+    );
+
+    assert_vm_error!(
+        r#"fn main() { true && 1 }"#,
+        UnsupportedBinaryOperation { op: "&&", .. } => {}
+    );
+
+    assert_vm_error!(
+        r#"fn main() { false || 1 }"#,
+        UnsupportedBinaryOperation { op: "||", .. } => {}
+    );
+}
+
 #[test]
 fn test_if() {
     assert_eq! {
@@ -100,6 +146,44 @@ fn test_if() {
         },
         10,
     };
+
+    assert_eq! {
+        rune!{
+            i64 => r#"
+            fn main() {
+                let n = 3;
+
+                if n == 1 {
+                    10
+                } else if n == 2 {
+                    20
+                } else if n == 3 {
+                    30
+                } else {
+                    0
+                }
+            }
+            "#
+        },
+        30,
+    };
+
+    assert_eq! {
+        rune!{
+            i64 => r#"
+            fn main() {
+                let n = 0;
+
+                if n > 5 {
+                    n = 10;
+                }
+
+                n
+            }
+            "#
+        },
+        0,
+    };
 }
 
 #[test]
@@ -233,6 +317,124 @@ fn test_loop() {
     };
 }
 
+#[test]
+fn test_continue() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, i64, i64, i64) => r#"
+            fn main() {
+                let odd = [];
+
+                for i in 0..6 {
+                    if i % 2 == 0 {
+                        continue;
+                    }
+
+                    odd.push(i);
+                }
+
+                let n = 0;
+                let sum = 0;
+
+                while n < 10 {
+                    n += 1;
+                    let double = n * 2;
+
+                    if n % 3 != 0 {
+                        continue;
+                    }
+
+                    sum += double;
+                }
+
+                let total = 0;
+
+                for v in [1, 2, 3, 4] {
+                    let skip = v == 2;
+
+                    if skip {
+                        continue;
+                    }
+
+                    total += v;
+                }
+
+                let count = 0;
+
+                let value = loop {
+                    count += 1;
+
+                    if count < 5 {
+                        continue;
+                    }
+
+                    break count * 2;
+                };
+
+                (odd, sum, total, value)
+            }
+            "#
+        },
+        (vec![1, 3, 5], 36, 8, 10),
+    };
+}
+
+#[test]
+fn test_labeled_continue_and_break() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, i64, i64) => r#"
+            fn main() {
+                let pairs = [];
+
+                'outer: for a in [1, 2, 3] {
+                    for b in [1, 2, 3].iter() {
+                        let product = a * b;
+
+                        if b > a {
+                            continue 'outer;
+                        }
+
+                        pairs.push(product);
+                    }
+                }
+
+                let found = 'outer: loop {
+                    let k = 0;
+
+                    loop {
+                        k += 1;
+
+                        if k == 3 {
+                            break 'outer k * 10;
+                        }
+                    }
+                };
+
+                let n = 0;
+
+                let first = 'search: while n < 10 {
+                    n += 1;
+
+                    for m in 0..n {
+                        if m * n == 12 {
+                            break 'search m;
+                        }
+
+                        if m > 2 {
+                            continue 'search;
+                        }
+                    }
+                };
+
+                (pairs, found, first)
+            }
+            "#
+        },
+        (vec![1, 2, 4, 3, 6, 9], 30, 3),
+    };
+}
+
 #[test]
 fn test_for() {
     assert_eq! {
@@ -710,6 +912,64 @@ fn test_template_string() {
     };
 }
 
+#[test]
+fn test_template_field_paths_and_method_calls() {
+    assert_eq! {
+        rune! {
+            String => r#"
+            struct User { name, points }
+
+            impl User {
+                fn score(self) {
+                    self.points + 1
+                }
+
+                fn describe(self) {
+                    `{self.name} has {self.score() * 2} points`
+                }
+            }
+
+            fn main() {
+                let user = User { name: "John", points: 20 };
+                `{user.describe()}, {user.name.len()} {"{}"} {`{user.points}`}`
+            }
+            "#
+        },
+        "John has 42 points, 4 {} 20",
+    };
+}
+
+#[test]
+fn test_template_arbitrary_expressions() {
+    assert_eq! {
+        rune! {
+            String => r#"
+            async fn get(n) {
+                Some(n)
+            }
+
+            async fn main() {
+                let a = 1;
+                let b = 2;
+                `{a + b} {if a < b && !false { "lt" } else { "ge" }} {if a > 0 { "pos" } else { "neg" }} {match a { 1 => "one", _ => "other" }} {get(a).await.unwrap()} {#{a: 4}.a} {['{', '}'].len()} {(5, 6).1}`
+            }
+            "#
+        },
+        "3 lt pos one 1 4 2 6",
+    };
+
+    assert_eq! {
+        rune! {
+            String => r#"
+            fn main() {
+                `{['}', '\'', '{'].len()} {`it's {['}'].len()}`} {"'{"}`
+            }
+            "#
+        },
+        "3 it's 1 '{",
+    };
+}
+
 #[test]
 fn test_variants_as_functions() {
     assert_eq! {
@@ -880,3 +1140,44 @@ fn test_index_get() {
         32,
     };
 }
+
+#[test]
+fn test_trailing_commas() {
+    assert_eq! {
+        rune! {
+            (i64, (i64, i64), (i64,), Vec<i64>, i64, i64, i64) => r#"
+            struct Point { x, y, }
+
+            enum Shape { Circle(r,), Rect { w, h, }, }
+
+            fn add(a, b,) {
+                a + b
+            }
+
+            fn area(shape,) {
+                match shape {
+                    Shape::Circle(r,) => r * r * 3,
+                    Shape::Rect { w, h, } => w * h,
+                }
+            }
+
+            fn main() {
+                let point = Point { x: 1, y: 2, };
+                let object = #{a: 3, b: 4,};
+                let sub = |a, b,| a - b;
+
+                (
+                    add(point.x, point.y,),
+                    (1, 2,),
+                    (1,),
+                    [1, 2, 3,],
+                    object.a.pow(2,) + sub(object.b, 1,),
+                    area(Shape::Circle(2,),) + area(Shape::Rect { w: 2, h: 3, },),
+                    match point { Point { x, .. } => x, },
+                )
+            }
+            "#
+        },
+        (3, (1, 2), (1,), vec![1, 2, 3], 12, 18, 1),
+    };
+}
@@ -0,0 +1,94 @@
+use rune_testing::*;
+
+#[test]
+fn test_map_literal() {
+    assert_eq! {
+        rune!((i64, i64, Option<i64>, bool, i64) => r#"
+        fn main() {
+            let key = "b";
+            let map = map!{"a": 1, key: 1 + 1, (1, 'c'): 3,};
+            (map.len(), map["b"], map.get("c"), map.contains_key((1, 'c')), map[(1, 'c')])
+        }
+        "#),
+        (3, 2, None, true, 3)
+    };
+}
+
+#[test]
+fn test_map_literal_later_entries_win() {
+    assert_eq! {
+        rune!((i64, i64) => r#"
+        fn main() {
+            let map = map!{1: "one", 1: "uno", 2: "two"};
+            (map.len(), map[1].len())
+        }
+        "#),
+        (2, 3)
+    };
+}
+
+#[test]
+fn test_map_methods() {
+    assert_eq! {
+        rune!((i64, i64, bool, i64) => r#"
+        use std::collections::HashMap;
+
+        fn main() {
+            let map = HashMap::new();
+            map.insert("a", 1);
+            map["b"] = 2;
+            map.remove("a");
+
+            let sum = 0;
+
+            for entry in map!{"x": 10, "y": 20} {
+                let (k, v) = entry;
+                sum = sum + v;
+            }
+
+            (map.len(), map["b"], map!{}.is_empty(), sum)
+        }
+        "#),
+        (1, 2, true, 30)
+    };
+}
+
+#[test]
+fn test_set_literal() {
+    assert_eq! {
+        rune!((i64, bool, bool, bool) => r#"
+        fn main() {
+            let x = 2;
+            let set = set!{1, x, 2, "three"};
+            let added = set.insert(4);
+            (set.len(), set.contains(2), set.contains(3), added)
+        }
+        "#),
+        (4, true, false, true)
+    };
+}
+
+#[test]
+fn test_unsupported_key() {
+    assert_vm_error!(
+        r#"fn main() { map!{1.5: 1} }"#,
+        UnsupportedKey { actual } => {
+            assert_eq!(actual.to_string(), "float");
+        }
+    );
+}
+
+#[test]
+fn test_map_compiles_to_instruction() -> Result<()> {
+    let context = runestick::Context::with_default_modules()?;
+
+    let (unit, _) = compile_source(&context, r#"fn main() { (map!{"a": 1, "b": 2}, set!{1}) }"#)?;
+
+    assert!(unit
+        .iter_instructions()
+        .any(|inst| matches!(inst, runestick::Inst::HashMap { count: 2 })));
+    assert!(unit
+        .iter_instructions()
+        .any(|inst| matches!(inst, runestick::Inst::HashSet { count: 1 })));
+    Ok(())
+}
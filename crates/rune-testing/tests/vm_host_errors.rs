@@ -0,0 +1,88 @@
+use rune_testing::*;
+use runestick::{Context, HostErrorMode, Module, VmError};
+
+fn host_context(mode: HostErrorMode) -> Result<Context> {
+    let mut module = Module::new(&["host"]);
+    module.fallible_function(&["parse"], mode, |s: &str| s.parse::<i64>())?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+    Ok(context)
+}
+
+const SOURCE: &str = r#"
+fn main() {
+    match host::parse("nope") {
+        Ok(n) => `ok {n}`,
+        Err(e) => `err {e}`,
+    }
+}
+"#;
+
+#[test]
+fn test_host_error() -> Result<()> {
+    let output: i64 = run_with_context(
+        host_context(HostErrorMode::HostError)?,
+        &["main"],
+        (),
+        r#"fn main() { host::parse("42")? + 1 }"#,
+    )?;
+
+    assert_eq!(output, 43);
+
+    let output: String = run_with_context(
+        host_context(HostErrorMode::HostError)?,
+        &["main"],
+        (),
+        SOURCE,
+    )?;
+
+    assert_eq!(output, "err invalid digit found in string");
+
+    let (message, type_name): (String, String) = run_with_context(
+        host_context(HostErrorMode::HostError)?,
+        &["main"],
+        (),
+        r#"
+        fn main() {
+            match host::parse("") {
+                Err(e) => (e.message(), e.type_name()),
+                _ => ("unexpected", ""),
+            }
+        }
+        "#,
+    )?;
+
+    assert_eq!(message, "cannot parse integer from empty string");
+    assert!(type_name.ends_with("ParseIntError"));
+    Ok(())
+}
+
+#[test]
+fn test_host_error_message() -> Result<()> {
+    let output: String =
+        run_with_context(host_context(HostErrorMode::Message)?, &["main"], (), SOURCE)?;
+
+    assert_eq!(output, "err invalid digit found in string");
+    Ok(())
+}
+
+#[test]
+fn test_host_error_panic() -> Result<()> {
+    let error = run_with_context::<_, _, String>(
+        host_context(HostErrorMode::Panic)?,
+        &["main"],
+        (),
+        SOURCE,
+    )
+    .unwrap_err();
+
+    let error = error.downcast_ref::<VmError>().expect("vm error");
+
+    match error.kind().into_unwound_ref().0 {
+        Panic { reason } => assert_eq!(reason.to_string(), "invalid digit found in string"),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use rune_testing::*;
+use runestick::{Context, FromValue as _, Instance, IntegerOverflow, Item};
+use std::sync::Arc;
+
+const SCRIPT: &str = r#"
+fn add(a, b) {
+    a + b
+}
+
+fn push(values, value) {
+    values.push(value);
+    values.len()
+}
+"#;
+
+fn instance() -> Instance {
+    let context = Context::with_default_modules().unwrap();
+    let (unit, _) = compile_source(&context, SCRIPT).unwrap();
+    Instance::new(Arc::new(context), Arc::new(unit))
+}
+
+#[test]
+fn test_multiple_entry_points() -> Result<()> {
+    let instance = instance();
+
+    let output = instance
+        .call(Item::of(&["add"]), (1i64, 2i64))?
+        .complete()?;
+    assert_eq!(i64::from_value(output)?, 3);
+
+    let output = instance
+        .call(Item::of(&["push"]), (vec![1i64], 2i64))?
+        .complete()?;
+    assert_eq!(i64::from_value(output)?, 2);
+    Ok(())
+}
+
+#[test]
+fn test_isolated_settings() -> Result<()> {
+    let first = instance();
+    let mut second = first.clone();
+    second.set_integer_overflow(IntegerOverflow::Wrapping);
+
+    assert!(Arc::ptr_eq(first.unit(), second.unit()));
+
+    let output = second
+        .call(Item::of(&["add"]), (i64::MAX, 1i64))?
+        .complete()?;
+    assert_eq!(i64::from_value(output)?, i64::MIN);
+
+    let error = first
+        .call(Item::of(&["add"]), (i64::MAX, 1i64))?
+        .complete()
+        .unwrap_err();
+
+    assert!(matches!(error.kind().into_unwound_ref().0, Overflow));
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use rune_testing::*;
+use runestick::{Context, FromValue as _, IntegerOverflow, Item, Value, Vm, VmError};
+use std::sync::Arc;
+
+const OPERATIONS: &str = r#"
+fn main() {
+    let max = 9223372036854775807;
+    let min = -max - 1;
+
+    let a = max;
+    a += 1;
+    let b = min;
+    b -= 1;
+    let c = max;
+    c *= 2;
+    let d = min;
+    d /= -1;
+
+    [max + 1, min - 1, max * 2, min / -1, -min, min % -1, a, b, c, d]
+}
+"#;
+
+fn run(source: &str, integer_overflow: IntegerOverflow) -> Result<Value, VmError> {
+    let context = Context::with_default_modules().unwrap();
+    let (unit, _) = compile_source(&context, source).unwrap();
+    let mut vm = Vm::new(Arc::new(context), Arc::new(unit));
+    vm.set_integer_overflow(integer_overflow);
+    block_on(vm.call(Item::of(&["main"]), ())?.async_complete())
+}
+
+#[test]
+fn test_integer_overflow_error() {
+    let error = run(OPERATIONS, IntegerOverflow::Error).unwrap_err();
+    let (kind, _) = error.kind().into_unwound_ref();
+    assert!(matches!(kind, Overflow), "{:?}", kind);
+}
+
+#[test]
+fn test_integer_overflow_wrapping() {
+    let output = run(OPERATIONS, IntegerOverflow::Wrapping).unwrap();
+
+    assert_eq!(
+        Vec::<i64>::from_value(output).unwrap(),
+        vec![
+            i64::MIN,
+            i64::MAX,
+            -2,
+            i64::MIN,
+            i64::MIN,
+            0,
+            i64::MIN,
+            i64::MAX,
+            -2,
+            i64::MIN
+        ]
+    );
+}
+
+#[test]
+fn test_integer_overflow_saturating() {
+    let output = run(OPERATIONS, IntegerOverflow::Saturating).unwrap();
+
+    assert_eq!(
+        Vec::<i64>::from_value(output).unwrap(),
+        vec![
+            i64::MAX,
+            i64::MIN,
+            i64::MAX,
+            i64::MAX,
+            i64::MAX,
+            0,
+            i64::MAX,
+            i64::MIN,
+            i64::MAX,
+            i64::MAX
+        ]
+    );
+}
+
+#[test]
+fn test_integer_overflow_divide_by_zero() {
+    let error = run("fn main() { 1 / 0 }", IntegerOverflow::Wrapping).unwrap_err();
+    let (kind, _) = error.kind().into_unwound_ref();
+    assert!(matches!(kind, DivideByZero), "{:?}", kind);
+}
+
+#[test]
+fn test_integer_overflow_is_inherited() {
+    let source = r#"
+    fn generate(n) {
+        yield n + 1;
+    }
+
+    async fn add(n) {
+        n + 1
+    }
+
+    async fn main() {
+        let max = 9223372036854775807;
+        [generate(max).next().unwrap(), add(max).await]
+    }
+    "#;
+
+    let output = run(source, IntegerOverflow::Wrapping).unwrap();
+
+    assert_eq!(
+        Vec::<i64>::from_value(output).unwrap(),
+        vec![i64::MIN, i64::MIN]
+    );
+}
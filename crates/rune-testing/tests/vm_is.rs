@@ -24,3 +24,40 @@ fn test_binop_override() {
         (true, false, false, true),
     };
 }
+
+#[test]
+fn test_is_interface() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool, bool, bool) => r#"
+            use std::iter::IntoIterator;
+            use std::iter::Iterator;
+
+            struct Counter { n }
+
+            impl Counter {
+                #[protocol(NEXT)]
+                fn next(self) {
+                    self.n = self.n + 1;
+                    Some(self.n)
+                }
+            }
+
+            fn main() {
+                let counter = Counter { n: 0 };
+                let range = std::iter::range(0, 10);
+
+                (
+                    counter is Iterator,
+                    counter is not IntoIterator,
+                    range is Iterator,
+                    [] is IntoIterator,
+                    [] is not Iterator,
+                    42 is Iterator,
+                )
+            }
+            "#
+        },
+        (true, true, true, true, true, false),
+    };
+}
@@ -64,3 +64,96 @@ fn test_path_type_match() {
         true,
     };
 }
+
+#[test]
+fn test_range_patterns() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool) => r#"
+            fn test(n) { match n { -5..=-1 => true, 1..=5 => true, _ => false } }
+            fn main() { (test(-5), test(0), test(5), test(6)) }
+            "#
+        },
+        (true, false, true, false),
+    };
+
+    assert_eq! {
+        rune! {
+            (bool, bool, bool) => r#"
+            fn test(c) { match c { 'a'..='z' => true, _ => false } }
+            fn main() { (test('a'), test('z'), test('A')) }
+            "#
+        },
+        (true, true, false),
+    };
+
+    assert_eq! {
+        rune! {
+            (bool, bool, bool) => r#"
+            fn test(b) { match b { b'0'..=b'9' => true, _ => false } }
+            fn main() { (test(b'0'), test(b'9'), test('5')) }
+            "#
+        },
+        (true, true, false),
+    };
+
+    assert_eq! {
+        rune!(bool => r#"fn main() { if let 1..=3 = 2 { true } else { false } }"#),
+        true,
+    };
+}
+
+#[test]
+fn test_alternative_patterns() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, i64, i64) => r#"
+            fn test(n) {
+                match n {
+                    1 | 2 | 3 => 1,
+                    4..=9 | 100 => 2,
+                    'a' | "a" => 3,
+                    _ => 0,
+                }
+            }
+
+            fn main() { (test(2), test(7), test(100), test("a"), test(10)) }
+            "#
+        },
+        (1, 2, 2, 3, 0),
+    };
+
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            fn main() {
+                match [1, 5] {
+                    [1 | 2, 4..=6] => 1,
+                    _ => 0,
+                }
+            }
+            "#
+        },
+        1,
+    };
+}
+
+#[test]
+fn test_match_inside_collection_literals() {
+    assert_eq! {
+        rune! {
+            (i64, Vec<i64>, i64, i64, i64) => r#"
+            fn main() {
+                let x = 1;
+                let t = (0, match x { 1 => 2, _ => 0 });
+                let v = [0, match x { 1 => 3, _ => 0 }];
+                let o = #{a: 0, b: match x { 1 => 4, _ => 0 }};
+                let m = map!{0: 0, 1: match x { 1 => 5, _ => 0 }};
+                v[match x { 1 => 0, _ => 1 }] = match x { 1 => 6, _ => 0 };
+                (t.1, v, o.b, m[1], #{[`b`]: 0, c: match x { 1 => 7, _ => 0 }}.c)
+            }
+            "#
+        },
+        (2, vec![6, 3], 4, 5, 7),
+    };
+}
@@ -0,0 +1,171 @@
+use rune_testing::*;
+use runestick::{Context, Module, VmError};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[derive(Debug)]
+struct Point {
+    x: i64,
+}
+
+runestick::impl_external!(Point);
+
+fn counting_context(calls: &Arc<AtomicUsize>) -> Result<Context> {
+    let mut module = Module::new(&["host"]);
+
+    let c = calls.clone();
+    module.function(&["double"], move |n: i64| {
+        c.fetch_add(1, Ordering::SeqCst);
+        n * 2
+    })?;
+
+    module.ty(&["Point"]).build::<Point>()?;
+    module.function(&["Point", "new"], |x: i64| Point { x })?;
+
+    let c = calls.clone();
+    module.function(&["norm"], move |p: &Point| {
+        c.fetch_add(1, Ordering::SeqCst);
+        p.x.abs()
+    })?;
+
+    module.inst_fn(runestick::HASH, |p: &Point| p.x)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+    Ok(context)
+}
+
+#[test]
+fn test_memoize() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let output: i64 = run_with_context(
+        counting_context(&calls)?,
+        &["main"],
+        (),
+        r#"
+        fn main() {
+            let double = std::cache::memoize(host::double);
+            double(1) + double(1) + double(2) + double(2)
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, 12);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    Ok(())
+}
+
+#[test]
+fn test_memoize_script_function() -> Result<()> {
+    let output: i64 = run(
+        &["main"],
+        (),
+        r#"
+        fn fib(n) {
+            if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+        }
+
+        fn main() {
+            let fib = std::cache::memoize(fib);
+            fib(20) + fib(20)
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, 13530);
+    Ok(())
+}
+
+#[test]
+fn test_memoize_capacity() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let _: i64 = run_with_context(
+        counting_context(&calls)?,
+        &["main"],
+        (),
+        r#"
+        fn main() {
+            let double = std::cache::memoize_with(host::double, #{capacity: 1});
+            double(1) + double(2) + double(1)
+        }
+        "#,
+    )?;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    Ok(())
+}
+
+#[test]
+fn test_memoize_hash_protocol() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let output: i64 = run_with_context(
+        counting_context(&calls)?,
+        &["main"],
+        (),
+        r#"
+        fn main() {
+            let norm = std::cache::memoize(host::norm);
+            norm(host::Point::new(-3)) + norm(host::Point::new(-3))
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, 6);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+fn test_memoize_unsupported_key() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let error = run_with_context::<_, _, i64>(
+        counting_context(&calls)?,
+        &["main"],
+        (),
+        r#"
+        fn main() {
+            let double = std::cache::memoize(host::double);
+            double([1])
+        }
+        "#,
+    )
+    .unwrap_err();
+
+    let error = error.downcast_ref::<VmError>().expect("vm error");
+
+    match error.kind().into_unwound_ref().0 {
+        UnsupportedKey { .. } => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+    Ok(())
+}
+
+#[test]
+fn test_memoize_invalid_ttl() -> Result<()> {
+    for ttl in &["1000000000000000000000.0", "-1.0", "0.0 / 0.0"] {
+        let source = format!(
+            r#"
+            fn main() {{
+                std::cache::memoize_with(|n| n, #{{ttl: {}}});
+            }}
+            "#,
+            ttl
+        );
+
+        let error = run::<_, _, ()>(&["main"], (), &source).unwrap_err();
+        let error = error.downcast_ref::<VmError>().expect("vm error");
+
+        match error.kind().into_unwound_ref().0 {
+            Panic { reason } => assert!(reason.to_string().starts_with("invalid cache ttl")),
+            kind => panic!("unexpected error: {:?}", kind),
+        }
+    }
+
+    Ok(())
+}
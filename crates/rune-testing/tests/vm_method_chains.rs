@@ -0,0 +1,164 @@
+use rune_testing::*;
+
+#[test]
+fn test_chains_on_literals() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, i64, i64, i64) => r#"
+            fn main() {
+                (
+                    [1, 2, 3].len(),
+                    "x".len(),
+                    #{a: 1}.len(),
+                    (1, 2).1.pow(2),
+                    2.pow(3) * 3.pow(2),
+                    -[1].len(),
+                )
+            }
+            "#
+        },
+        (3, 1, 1, 4, 72, -1),
+    };
+}
+
+#[test]
+fn test_chains_on_call_results() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64) => r#"
+            struct Foo { values }
+
+            impl Foo {
+                fn bar(self) {
+                    self
+                }
+            }
+
+            fn foo() {
+                Foo { values: [1, 2, 3] }
+            }
+
+            fn main() {
+                (foo().bar().values.len(), foo().bar().values[1], (|v| v)([4, 5]).len())
+            }
+            "#
+        },
+        (3, 2, 2),
+    };
+}
+
+#[test]
+fn test_chains_after_await_and_try() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            struct Foo { values }
+
+            impl Foo {
+                fn bar(self) {
+                    self
+                }
+            }
+
+            async fn foo() {
+                Ok(Some(Foo { values: [1, 2] }))
+            }
+
+            async fn run() {
+                Ok(foo().await??.bar().values.len())
+            }
+
+            async fn main() {
+                run().await.unwrap()
+            }
+            "#
+        },
+        2,
+    };
+}
+
+#[test]
+fn test_chains_in_expression_positions() {
+    assert_eq! {
+        rune! {
+            (i64, bool, i64, i64, i64) => r#"
+            fn main() {
+                let t = ((1, (2, 3)), 4);
+                let n = 0;
+
+                for v in [1, 2].iter() {
+                    n += v;
+                }
+
+                let a = if "x".len() > 0 { n } else { 0 };
+                let b = match [1].len() { 1 => true, _ => false };
+                let c = if true { [1, 2] } else { [] }.len();
+                let d = { [1, 2, 3] }.len();
+                (a, b, c, d, t.0.1.1)
+            }
+            "#
+        },
+        (3, true, 2, 3, 3),
+    };
+}
+
+#[test]
+fn test_unary_operands_in_conditions() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64) => r#"
+            async fn get(n) {
+                Ok(n)
+            }
+
+            async fn run() {
+                let done = false;
+                let a = if !done { 1 } else { 2 };
+                let n = get(-1).await?;
+                let b = if -n < 0 { 1 } else { 2 };
+                let c = 0;
+
+                while !(c > 2) {
+                    c += 1;
+                }
+
+                Ok((a, b, c))
+            }
+
+            async fn main() {
+                run().await.unwrap()
+            }
+            "#
+        },
+        (1, 2, 3),
+    };
+}
+
+#[test]
+fn test_match_scrutinee_expressions() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, i64, i64, i64) => r#"
+            struct Foo { a }
+
+            async fn get(n) {
+                Some(n)
+            }
+
+            async fn main() {
+                let x = 1;
+
+                (
+                    match get(x).await { Some(n) => n, None => 0 },
+                    match -x { -1 => 2, _ => 0 },
+                    match x.pow(2) + 2 { 3 => 3, _ => 0 },
+                    match [1, 2].len() > 1 && !false { _ => 4 },
+                    match #{a: 5}.a { n => n },
+                    match (Foo { a: 6 }).a { n => n },
+                )
+            }
+            "#
+        },
+        (1, 2, 3, 4, 5, 6),
+    };
+}
@@ -0,0 +1,86 @@
+use rune_testing::*;
+use runestick::{Context, Item, Recorder, Vm, VmErrorKind};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct Counters {
+    executions: AtomicU64,
+    instructions: AtomicU64,
+    awaits: AtomicU64,
+    errors: Mutex<Vec<String>>,
+}
+
+impl Recorder for Counters {
+    fn execution_started(&self) {
+        self.executions.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn instructions_executed(&self, count: u64) {
+        self.instructions.fetch_add(count, Ordering::SeqCst);
+    }
+
+    fn error(&self, kind: &VmErrorKind) {
+        self.errors.lock().unwrap().push(kind.to_string());
+    }
+
+    fn awaited(&self) {
+        self.awaits.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn run_recorded(source: &str) -> (Arc<Counters>, Result<Value, runestick::VmError>) {
+    let counters = Arc::new(Counters::default());
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.set_recorder(counters.clone());
+
+    let (unit, _) = compile_source(&context, source).unwrap();
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let result = block_on(vm.call(Item::of(&["main"]), ()).unwrap().async_complete());
+    (counters, result)
+}
+
+#[test]
+fn test_metrics_counters() {
+    let (counters, result) = run_recorded(
+        r#"
+        async fn double(n) { n * 2 }
+
+        async fn main() {
+            let total = 0;
+
+            for n in [1, 2, 3] {
+                total += double(n).await;
+            }
+
+            total
+        }
+        "#,
+    );
+
+    assert_eq!(result.unwrap().into_integer().unwrap(), 12);
+    assert_eq!(counters.executions.load(Ordering::SeqCst), 4);
+    assert_eq!(counters.awaits.load(Ordering::SeqCst), 3);
+    assert!(counters.instructions.load(Ordering::SeqCst) > 10);
+    assert!(counters.errors.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_metrics_errors() {
+    let (counters, result) = run_recorded(
+        r#"
+        fn fail() { 1 / 0 }
+        fn main() { Some(1).map(|_| fail()) }
+        "#,
+    );
+
+    assert!(result.is_err());
+
+    // The error is only recorded once, even though it propagates through
+    // several executions.
+    assert_eq!(
+        *counters.errors.lock().unwrap(),
+        vec![String::from("division by zero")]
+    );
+}
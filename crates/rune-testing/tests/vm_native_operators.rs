@@ -0,0 +1,42 @@
+use rune_testing::*;
+use runestick::{Context, Module};
+
+#[derive(Debug, Clone, Copy)]
+struct Number(i64);
+
+runestick::impl_external!(Number);
+
+fn context() -> Result<Context> {
+    let mut module = Module::new(&["number"]);
+    module.ty(&["Number"]).build::<Number>()?;
+    module.function(&["Number", "new"], Number)?;
+    module.inst_fn("get", |n: &Number| n.0)?;
+    module.inst_fn(runestick::ADD, |a: &Number, b: i64| Number(a.0 + b))?;
+    module.inst_fn(runestick::SUB, |a: &Number, b: i64| Number(a.0 - b))?;
+    module.inst_fn(runestick::MUL, |a: &Number, b: i64| Number(a.0 * b))?;
+    module.inst_fn(runestick::DIV, |a: &Number, b: i64| Number(a.0 / b))?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+    Ok(context)
+}
+
+#[test]
+fn test_native_operators() -> Result<()> {
+    let output: (i64, i64, i64, i64) = run_with_context(
+        context()?,
+        &["main"],
+        (),
+        r#"
+        use number::Number;
+
+        fn main() {
+            let n = Number::new(12);
+            ((n + 3).get(), (n - 3).get(), (n * 3).get(), (n / 3).get())
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, (15, 9, 36, 4));
+    Ok(())
+}
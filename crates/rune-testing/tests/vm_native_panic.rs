@@ -0,0 +1,97 @@
+use rune_testing::*;
+use runestick::{Context, Item, Module, Vm, VmError, VmExecution};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+#[derive(Debug)]
+struct Counter;
+
+runestick::impl_external!(Counter);
+
+fn context(propagate_panics: bool) -> Result<Context> {
+    let mut module = Module::new(&["native"]);
+    module.ty(&["Counter"]).build::<Counter>()?;
+    module.function(&["Counter", "new"], || Counter)?;
+    module.function(&["explode"], |n: i64| -> i64 {
+        panic!("exploded at {}", n)
+    })?;
+    module.inst_fn("increment", |_: &Counter| -> i64 { panic!("overflow") })?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+    context.set_propagate_panics(propagate_panics);
+    Ok(context)
+}
+
+fn start(context: Context, source: &str) -> Result<VmExecution> {
+    let (unit, _) = compile_source(&context, source)?;
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    Ok(vm.call(Item::of(&["main"]), ())?)
+}
+
+fn native_panic(error: &VmError) -> Option<(&str, &str)> {
+    match error.kind().into_unwound_ref() {
+        (NativePanic { function, message }, _) => Some((function.as_str(), message.as_str())),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_native_panic_is_error() -> Result<()> {
+    let mut execution = start(context(false)?, r#"fn main() { native::explode(42) }"#)?;
+
+    let error = execution.complete().unwrap_err();
+    assert_eq!(
+        native_panic(&error),
+        Some(("native::explode", "exploded at 42"))
+    );
+
+    // The execution is poisoned, and refuses to continue.
+    assert!(execution.vm()?.is_poisoned());
+    let error = execution.complete().unwrap_err();
+    assert!(matches!(error.kind().into_unwound_ref(), (Poisoned, _)));
+
+    // Clearing the virtual machine makes it usable again.
+    execution.vm_mut()?.clear();
+    assert!(!execution.vm()?.is_poisoned());
+    Ok(())
+}
+
+#[test]
+fn test_native_panic_names() -> Result<()> {
+    let mut execution = start(
+        context(false)?,
+        r#"fn main() { native::Counter::new().increment() }"#,
+    )?;
+
+    let error = execution.complete().unwrap_err();
+    assert_eq!(
+        native_panic(&error),
+        Some(("native::Counter::increment", "overflow"))
+    );
+
+    // Native functions called through function pointers.
+    let mut execution = start(
+        context(false)?,
+        r#"
+        fn apply(f, n) { f(n) }
+        fn main() { apply(native::explode, 1) }
+        "#,
+    )?;
+
+    let error = execution.complete().unwrap_err();
+    assert_eq!(
+        native_panic(&error),
+        Some(("native::explode", "exploded at 1"))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_native_panic_propagate() -> Result<()> {
+    let mut execution = start(context(true)?, r#"fn main() { native::explode(1) }"#)?;
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| execution.complete()));
+    assert!(result.is_err());
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use rune_testing::*;
+use runestick::Span;
+
+#[test]
+fn test_object_shorthand() {
+    assert_eq! {
+        rune!((String, i64) => r#"
+        fn main() {
+            let name = "Alice";
+            let age = 31;
+            let person = #{ name, age };
+            (person.name, person.age)
+        }
+        "#),
+        (String::from("Alice"), 31)
+    };
+}
+
+#[test]
+fn test_object_computed_keys() {
+    assert_eq! {
+        rune!((i64, i64, i64, i64) => r#"
+        fn main() {
+            let prefix = "key";
+            let n = 0;
+            let object = #{ [`{prefix}-1`]: 1, static: 2, [`{prefix}-2`]: 3, n };
+            (object["key-1"], object.static, object["key-2"], object.len())
+        }
+        "#),
+        (1, 2, 3, 4)
+    };
+}
+
+#[test]
+fn test_object_computed_keys_later_wins() {
+    assert_eq! {
+        rune!((i64, i64) => r#"
+        fn main() {
+            let object = #{ a: 1, ["a"]: 2 };
+            (object.a, object.len())
+        }
+        "#),
+        (2, 1)
+    };
+}
+
+#[test]
+fn test_object_computed_key_side_effects() {
+    assert_eq! {
+        rune!(i64 => r#"
+        fn main() {
+            let calls = [];
+            let key = |k| { calls.push(k); k };
+            #{ [key("a")]: key("b") };
+            calls.len()
+        }
+        "#),
+        2
+    };
+}
+
+#[test]
+fn test_object_computed_key_must_be_string() {
+    assert_vm_error!(
+        r#"fn main() { #{ [1]: 2 } }"#,
+        Expected { expected, actual } => {
+            assert_eq!(expected.to_string(), "String");
+            assert_eq!(actual.to_string(), "integer");
+        }
+    );
+}
+
+#[test]
+fn test_object_computed_key_errors() {
+    assert_compile_error! {
+        r#"struct Foo { a } fn main() { Foo { ["a"]: 1 } }"#,
+        UnsupportedComputedKey { span } => {
+            assert_eq!(span, Span::new(35, 40));
+        }
+    };
+
+    assert_parse_error! {
+        r#"fn main() { #{ ["a"] } }"#,
+        TokenMismatch { expected, .. } => {
+            assert_eq!(expected, rune::ast::Kind::Colon);
+        }
+    };
+}
+
+#[test]
+fn test_field_access_on_objects_from_anywhere() {
+    // NB: objects constructed by the host don't share a hasher with the ones
+    // constructed by the virtual machine.
+    let mut host = runestick::Object::new();
+    host.insert(String::from("a"), runestick::Value::from(1i64));
+
+    let context = runestick::Context::with_default_modules().unwrap();
+
+    let output: (i64, i64, i64, i64, i64) = run_with_context(
+        context,
+        &["main"],
+        (host,),
+        r#"
+        struct Point { x, y }
+        enum Shape { Circle { radius } }
+
+        fn main(host) {
+            let point = Point { x: 1, y: 2 };
+            point.y = point.x + 2;
+
+            let circle = Shape::Circle { radius: 4 };
+            let object = #{a: 5};
+
+            let radius = match circle { Shape::Circle { radius } => radius };
+            (host.a, point.x, point.y, radius, object.a)
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(output, (1, 1, 3, 4, 5));
+}
@@ -0,0 +1,129 @@
+use rune_testing::*;
+
+#[test]
+fn test_operator_overloading() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, bool, bool, bool) => r#"
+            struct Vec2 { x, y }
+
+            impl Vec2 {
+                #[protocol(ADD)]
+                fn add(self, other) {
+                    Vec2 { x: self.x + other.x, y: self.y + other.y }
+                }
+
+                #[protocol(MUL)]
+                fn scale(self, n) {
+                    Vec2 { x: self.x * n, y: self.y * n }
+                }
+
+                #[protocol(ADD_ASSIGN)]
+                fn add_assign(self, other) {
+                    self.x = self.x + other.x;
+                    self.y = self.y + other.y;
+                }
+
+                #[protocol(EQ)]
+                fn eq(self, other) {
+                    self.x == other.x && self.y == other.y
+                }
+
+                #[protocol(NEG)]
+                fn neg(self) {
+                    Vec2 { x: -self.x, y: -self.y }
+                }
+            }
+
+            fn main() {
+                let a = Vec2 { x: 1, y: 2 } + Vec2 { x: 3, y: 4 } * 2;
+                a += Vec2 { x: 10, y: 10 };
+                let b = 100;
+                (
+                    a.x,
+                    a.y,
+                    b,
+                    a == Vec2 { x: 17, y: 20 },
+                    a != Vec2 { x: 17, y: 20 },
+                    -a == Vec2 { x: -17, y: -20 },
+                )
+            }
+            "#
+        },
+        (17, 20, 100, true, false, true)
+    };
+}
+
+#[test]
+fn test_protocol_attribute_errors() {
+    assert_compile_error! {
+        r#"struct Foo; impl Foo { #[protocol(PLUS)] fn add(self, other) { } }"#,
+        UnknownProtocol { name, span } => {
+            assert_eq!(name, "PLUS");
+            assert_eq!(span, Span::new(34, 38));
+        }
+    };
+
+    assert_compile_error! {
+        r#"struct Foo; impl Foo { #[protocol(ADD)] fn add(self) { } }"#,
+        ProtocolArgumentCount { expected: 2, actual: 1, .. } => {}
+    };
+
+    assert_compile_error! {
+        r#"struct Foo; impl Foo { #[protocol(ADD)] async fn add(self, other) { } }"#,
+        UnsupportedProtocolCall { .. } => {}
+    };
+
+    assert_compile_error! {
+        r#"#[protocol(ADD)] fn add(a, b) { } fn main() { }"#,
+        UnsupportedAttribute { span } => {
+            assert_eq!(span, Span::new(0, 16));
+        }
+    };
+}
+
+#[test]
+fn test_protocols_run_in_calling_vm() {
+    use runestick::{Context, FromValue as _, IntegerOverflow, Item, Vm};
+    use std::sync::Arc;
+
+    let context = Context::with_default_modules().unwrap();
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        struct Counter { n }
+
+        impl Counter {
+            #[protocol(ADD_ASSIGN)]
+            fn add_assign(self, n) {
+                self.n = self.n + n;
+            }
+
+            #[protocol(EQ)]
+            fn eq(self, n) {
+                self.n + 1 == n + 1
+            }
+        }
+
+        fn main() {
+            let max = 9223372036854775807;
+            let c = Counter { n: max };
+            c += 1;
+            (c.n, c == -max - 1, c != -max - 1, -max - 1 == c)
+        }
+        "#,
+    )
+    .unwrap();
+
+    // NB: the protocol functions only observe the integer overflow behavior of
+    // the virtual machine if they're called in it.
+    let mut vm = Vm::new(Arc::new(context), Arc::new(unit));
+    vm.set_integer_overflow(IntegerOverflow::Wrapping);
+    let output = block_on(vm.call(Item::of(&["main"]), ()).unwrap().async_complete()).unwrap();
+
+    assert_eq!(
+        <(i64, bool, bool, bool)>::from_value(output).unwrap(),
+        (i64::MIN, true, false, true)
+    );
+}
@@ -29,3 +29,47 @@ fn test_option() {
         1,
     };
 }
+
+#[test]
+fn test_option_combinators() {
+    assert_eq! {
+        rune! {
+            (Option<i64>, Option<i64>, Option<i64>) => r#"
+            fn main() {
+                let n = 2;
+                (Some(1).map(|v| v + n), None.map(|v| v + n), Some(1).and_then(|v| None))
+            }
+            "#
+        },
+        (Some(3), None, None),
+    };
+
+    assert_eq! {
+        rune! {
+            (Result<i64, String>, Result<i64, String>, i64) => r#"
+            fn main() {
+                (Some(1).ok_or("missing"), None.ok_or("missing"), Some(42).expect("a value"))
+            }
+            "#
+        },
+        (Ok(1), Err(String::from("missing")), 42),
+    };
+
+    let source = r#"fn main() { None.expect("a value") }"#;
+
+    assert_vm_error!(
+        source,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "a value");
+            let span = reason.span().expect("panic to have a span");
+            assert_eq!(&source[span.start..span.end], "None.expect(\"a value\")");
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { None.unwrap() }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "called `Option::unwrap()` on a `None` value");
+        }
+    );
+}
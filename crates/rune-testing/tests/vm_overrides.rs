@@ -0,0 +1,78 @@
+use rune_testing::*;
+use runestick::{Context, FromValue as _, Item, Module, Overrides, Vm};
+use std::sync::Arc;
+
+fn net_context() -> Result<Arc<Context>> {
+    let mut module = Module::new(&["net"]);
+    module.function(&["fetch"], |url: &str| -> String {
+        panic!("tried to fetch {}", url);
+    })?;
+    module.function(&["online"], || false)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+    Ok(Arc::new(context))
+}
+
+const SOURCE: &str = r#"
+fn fetch_all(urls) {
+    let out = [];
+
+    for url in urls {
+        out.push(net::fetch(url));
+    }
+
+    out
+}
+
+fn main() {
+    let fetch = net::fetch;
+    (net::online(), fetch_all(["a", "b"]), fetch("c"))
+}
+"#;
+
+#[test]
+fn test_overrides() -> Result<()> {
+    let context = net_context()?;
+    let (unit, _) = compile_source(&context, SOURCE)?;
+    let unit = Arc::new(unit);
+
+    let mut overrides = Overrides::new();
+    overrides.insert(&["net", "fetch"], |url: &str| format!("mocked {}", url));
+    overrides.insert(&["net", "online"], || true);
+    assert_eq!(overrides.len(), 2);
+
+    let vm = Vm::new(context.clone(), unit.clone());
+    let output = vm
+        .call(Item::of(&["main"]), ())?
+        .with_overrides(overrides)
+        .complete()?;
+
+    let (online, all, single) = <(bool, Vec<String>, String)>::from_value(output)?;
+    assert!(online);
+    assert_eq!(all, vec!["mocked a", "mocked b"]);
+    assert_eq!(single, "mocked c");
+
+    // Overrides only apply to the execution they were installed in.
+    let vm = Vm::new(context, unit);
+    assert!(vm.call(Item::of(&["main"]), ())?.complete().is_err());
+    Ok(())
+}
+
+#[test]
+fn test_overrides_unknown_function() -> Result<()> {
+    let context = net_context()?;
+    let (unit, _) = compile_source(&context, r#"fn main() { net::online() }"#)?;
+
+    let mut overrides = Overrides::new();
+    overrides.insert(&["net", "offline"], || true);
+
+    let vm = Vm::new(context, Arc::new(unit));
+    let output = vm
+        .call(Item::of(&["main"]), ())?
+        .with_overrides(overrides)
+        .complete()?;
+
+    assert!(!output.into_bool()?);
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use rune_testing::*;
+use runestick::{Context, Item, Vm, VmError};
+use std::sync::Arc;
+use std::thread;
+
+fn run_main(source: &str) -> VmError {
+    let context = Context::with_default_modules().unwrap();
+    let (unit, _) = compile_source(&context, source).unwrap();
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    block_on(vm.call(Item::of(&["main"]), ()).unwrap().async_complete()).unwrap_err()
+}
+
+#[test]
+fn test_panic_with_payload() -> Result<()> {
+    let error = run_main(r#"fn main() { panic(#{code: 404, path: "/index.html"}) }"#);
+    let panic = error.as_panic().expect("expected a panic");
+
+    // Accessing the payload leaves it in place.
+    assert!(panic.payload().is_some());
+
+    let object = error
+        .into_panic()
+        .expect("expected a panic")
+        .into_payload()?
+        .expect("missing payload")
+        .into_object()?;
+    let mut object = object.take()?;
+    let code: i64 = runestick::FromValue::from_value(object.remove("code").unwrap())?;
+    let path: String = runestick::FromValue::from_value(object.remove("path").unwrap())?;
+    assert_eq!((code, path.as_str()), (404, "/index.html"));
+    Ok(())
+}
+
+#[test]
+fn test_panic_downcast_payload() -> Result<()> {
+    let error = run_main(r#"fn main() { panic((1, "two")) }"#);
+
+    // Errors are sent across threads, together with their payloads.
+    let payload = thread::spawn(move || {
+        let panic = error.as_panic().expect("expected a panic");
+        panic.downcast_payload::<(i64, String)>()
+    })
+    .join()
+    .expect("thread panicked")?;
+
+    assert_eq!(payload, Some((1, String::from("two"))));
+
+    // The payload can be downcast any number of times.
+    let error = run_main(r#"fn main() { panic([1, 2]) }"#);
+    let panic = error.as_panic().expect("expected a panic");
+    assert_eq!(panic.downcast_payload::<Vec<i64>>()?, Some(vec![1, 2]));
+    assert_eq!(panic.downcast_payload::<Vec<i64>>()?, Some(vec![1, 2]));
+
+    let error = run_main(r#"fn main() { panic(42) }"#);
+    let panic = error.as_panic().expect("expected a panic");
+    assert!(panic.downcast_payload::<String>().is_err());
+    Ok(())
+}
+
+#[test]
+fn test_panic_message() -> Result<()> {
+    let error = run_main(r#"fn main() { panic("oh no") }"#);
+    let panic = error.as_panic().expect("expected a panic");
+    assert_eq!(panic.to_string(), "oh no");
+    assert_eq!(
+        panic.downcast_payload::<String>()?.as_deref(),
+        Some("oh no")
+    );
+
+    // Functions can't be detached from the virtual machine, so only the
+    // message is kept.
+    let error = run_main(
+        r#"
+        fn foo() {}
+        fn main() { panic(foo) }
+        "#,
+    );
+
+    let panic = error.as_panic().expect("expected a panic");
+    assert!(panic.payload().is_none());
+    assert!(panic.payload_error().is_some());
+    assert!(error.into_panic().unwrap().into_payload().is_err());
+    Ok(())
+}
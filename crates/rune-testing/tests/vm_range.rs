@@ -0,0 +1,89 @@
+use rune_testing::*;
+
+#[test]
+fn test_for_range() {
+    assert_eq! {
+        rune!(i64 => r#"fn main() { let n = 0; for i in 0..10 { n += i; } n }"#),
+        45,
+    };
+
+    assert_eq! {
+        rune!(i64 => r#"fn main() { let n = 1; for i in 1..=5 { n *= i; } n }"#),
+        120,
+    };
+
+    assert_eq! {
+        rune!(i64 => r#"fn main() { let n = 0; for i in 5..0 { n += 1; } n }"#),
+        0,
+    };
+
+    assert_eq! {
+        rune!(i64 => r#"
+        fn main() {
+            let n = 0;
+
+            for i in 0..3 {
+                for j in i..3 {
+                    n += 1;
+                }
+            }
+
+            n
+        }
+        "#),
+        6,
+    };
+}
+
+#[test]
+fn test_for_range_break() {
+    assert_eq! {
+        rune!(i64 => r#"fn main() { for i in 0..100 { if i * i > 50 { break i; } } }"#),
+        8,
+    };
+
+    assert_eq! {
+        rune!(i64 => r#"
+        fn main() {
+            let n = 0;
+
+            'outer: for i in 0..10 {
+                for j in 0..10 {
+                    if i == 5 {
+                        break 'outer;
+                    }
+
+                    n += 1;
+                }
+            }
+
+            n
+        }
+        "#),
+        50,
+    };
+}
+
+#[test]
+fn test_range_expr() {
+    assert_eq! {
+        rune!(Vec<i64> => r#"fn main() { let r = 2..=4; let v = []; for x in r { v.push(x); } v }"#),
+        vec![2, 3, 4],
+    };
+
+    assert_eq! {
+        rune!(i64 => r#"fn main() { let n = 0; for i in 1 + 1..2 * 3 { n += i; } n }"#),
+        14,
+    };
+}
+
+#[test]
+fn test_range_bounds_error() {
+    assert_vm_error!(
+        r#"fn main() { for i in "a"..1 {} }"#,
+        UnsupportedRangeBounds { start, end } => {
+            assert_eq!(start.to_string(), "String");
+            assert_eq!(end.to_string(), "integer");
+        }
+    );
+}
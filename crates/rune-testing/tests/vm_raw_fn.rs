@@ -0,0 +1,107 @@
+use rune_testing::*;
+use runestick::{Context, Module, Stack, VmError};
+
+/// Sums all arguments passed to it, using a scoped frame over the arguments.
+fn sum(stack: &mut Stack, args: usize) -> Result<(), VmError> {
+    let total = {
+        let mut frame = stack.frame(args)?;
+        let mut total = 0;
+
+        for _ in 0..args {
+            total += frame.pop_as::<i64>()?;
+        }
+
+        // Values below the frame can't be popped.
+        assert!(frame.pop().is_err());
+
+        total
+    };
+
+    stack.push_value(total)?;
+    Ok(())
+}
+
+#[test]
+fn test_raw_fn_stack_helpers() -> Result<()> {
+    let mut module = Module::new(&["math"]);
+    module.raw_fn(&["sum"], sum)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+
+    let output: (i64, i64) = run_with_context(
+        context,
+        &["main"],
+        (),
+        r#"
+        fn main() {
+            let a = 10;
+            (math::sum(1, 2, 3), a + math::sum())
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, (6, 10));
+    Ok(())
+}
+
+fn repeat(stack: &mut Stack, args: usize) -> Result<(), VmError> {
+    let (string, n): (String, usize) = stack.take_args(args)?;
+    stack.push_value(string.repeat(n))
+}
+
+fn repeat_context() -> Result<Context> {
+    let mut module = Module::new(&["text"]);
+    module.raw_fn(&["repeat"], repeat)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+    Ok(context)
+}
+
+#[test]
+fn test_raw_fn_take_args() -> Result<()> {
+    let output: String = run_with_context(
+        repeat_context()?,
+        &["main"],
+        (),
+        r#"fn main() { text::repeat("ab", 3) }"#,
+    )?;
+
+    assert_eq!(output, "ababab");
+
+    let error = run_with_context::<_, _, ()>(
+        repeat_context()?,
+        &["main"],
+        (),
+        r#"fn main() { text::repeat("ab", "3") }"#,
+    )
+    .unwrap_err();
+
+    let error = error.downcast_ref::<VmError>().expect("vm error");
+
+    match error.kind().into_unwound_ref().0 {
+        BadArgumentType { arg: 1, .. } => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    let error = run_with_context::<_, _, ()>(
+        repeat_context()?,
+        &["main"],
+        (),
+        r#"fn main() { text::repeat("ab") }"#,
+    )
+    .unwrap_err();
+
+    let error = error.downcast_ref::<VmError>().expect("vm error");
+
+    match error.kind().into_unwound_ref().0 {
+        BadArgumentCount {
+            actual: 1,
+            expected: 2,
+        } => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    Ok(())
+}
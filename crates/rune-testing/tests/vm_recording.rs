@@ -0,0 +1,127 @@
+use rune_testing::*;
+use runestick::{Context, Hash, Item, Module, Recording, Unit, Value, Vm};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+
+const SOURCE: &str = r#"
+fn main() {
+    let a = host::next();
+    let b = host::next();
+    host::label(a + b)
+}
+"#;
+
+fn host_context(counter: &Arc<AtomicI64>) -> Result<Arc<Context>> {
+    let mut module = Module::new(&["host"]);
+
+    let c = counter.clone();
+    module.function(&["next"], move || c.fetch_add(1, Ordering::SeqCst))?;
+    module.function(&["label"], |n: i64| format!("#{}", n))?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+    Ok(Arc::new(context))
+}
+
+fn record(context: &Arc<Context>, unit: &Arc<Unit>) -> Result<(String, Recording)> {
+    let vm = Vm::new(context.clone(), unit.clone());
+    let mut execution = vm.call(Item::of(&["main"]), ())?.with_recording();
+    let output = execution.complete()?.into_string()?.take()?;
+    Ok((output, execution.recording().expect("recording")))
+}
+
+#[test]
+fn test_record_replay() -> Result<()> {
+    let counter = Arc::new(AtomicI64::new(10));
+    let context = host_context(&counter)?;
+    let (unit, _) = compile_source(&context, SOURCE)?;
+    let unit = Arc::new(unit);
+
+    let (output, recording) = record(&context, &unit)?;
+    assert_eq!(output, "#21");
+
+    let hashes = recording
+        .calls()
+        .iter()
+        .map(|call| call.hash)
+        .collect::<Vec<_>>();
+
+    let next = Hash::type_hash(&["host", "next"]);
+    let label = Hash::type_hash(&["host", "label"]);
+    assert_eq!(hashes, vec![next, next, label]);
+
+    let bytes = rmp_serde::to_vec(&recording)?;
+    let recording: Recording = rmp_serde::from_slice(&bytes)?;
+
+    // Replaying doesn't call the host, so the counter doesn't advance and
+    // the output is the same as when it was recorded.
+    let vm = Vm::new(context.clone(), unit.clone());
+    let output = vm
+        .call(Item::of(&["main"]), ())?
+        .with_replay(recording)
+        .complete()?
+        .into_string()?
+        .take()?;
+
+    assert_eq!(output, "#21");
+    assert_eq!(counter.load(Ordering::SeqCst), 12);
+    Ok(())
+}
+
+#[test]
+fn test_replay_errors() -> Result<()> {
+    let counter = Arc::new(AtomicI64::new(0));
+    let context = host_context(&counter)?;
+    let (unit, _) = compile_source(&context, SOURCE)?;
+    let unit = Arc::new(unit);
+
+    let (_, recording) = record(&context, &unit)?;
+
+    let mut calls = recording.into_calls();
+    calls.pop();
+
+    let vm = Vm::new(context.clone(), unit.clone());
+    let error = vm
+        .call(Item::of(&["main"]), ())?
+        .with_replay(Recording::new(calls.clone()))
+        .complete()
+        .unwrap_err();
+
+    match error.kind().into_unwound_ref().0 {
+        ReplayExhausted { .. } => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    let mut mismatched = calls.clone();
+    mismatched[0].hash = Hash::type_hash(&["host", "label"]);
+
+    let vm = Vm::new(context.clone(), unit.clone());
+    let error = vm
+        .call(Item::of(&["main"]), ())?
+        .with_replay(Recording::new(mismatched))
+        .complete()
+        .unwrap_err();
+
+    match error.kind().into_unwound_ref().0 {
+        ReplayMismatch { .. } => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    let (_, recording) = record(&context, &unit)?;
+    let mut calls = recording.into_calls();
+    calls[2].args = vec![Value::Integer(0)];
+
+    let vm = Vm::new(context, unit);
+    let error = vm
+        .call(Item::of(&["main"]), ())?
+        .with_replay(Recording::new(calls))
+        .complete()
+        .unwrap_err();
+
+    match error.kind().into_unwound_ref().0 {
+        ReplayArgumentsMismatch { .. } => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    Ok(())
+}
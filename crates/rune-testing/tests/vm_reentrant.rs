@@ -0,0 +1,79 @@
+use rune_testing::*;
+use runestick::{Context, Module, VmError};
+
+fn context() -> Result<Context> {
+    let mut module = Module::new(&["host"]);
+
+    module.function(&["apply"], |f: Function, value: i64| {
+        f.call::<_, i64>((value,))
+    })?;
+
+    module.async_function(&["apply_async"], |f: Function, value: i64| async move {
+        f.async_call::<_, i64>((value,)).await
+    })?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+    Ok(context)
+}
+
+#[test]
+fn test_reentrant_call() -> Result<()> {
+    let output: i64 = run_with_context(
+        context()?,
+        &["main"],
+        (),
+        r#"
+        fn add(v) { v + 2 }
+
+        fn main() {
+            let a = 1;
+            host::apply(|v| v + a, 41) + host::apply(add, 0)
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, 44);
+    Ok(())
+}
+
+#[test]
+fn test_reentrant_async_call() -> Result<()> {
+    let output: i64 = run_with_context(
+        context()?,
+        &["main"],
+        (),
+        r#"
+        async fn double(v) { v * 2 }
+
+        async fn main() {
+            let a = host::apply_async(double, 10).await;
+            let b = host::apply_async(|v| v + 1, 1).await;
+            a + b
+        }
+        "#,
+    )?;
+
+    assert_eq!(output, 22);
+    Ok(())
+}
+
+#[test]
+fn test_reentrant_call_error() -> Result<()> {
+    let error = run_with_context::<_, _, i64>(
+        context()?,
+        &["main"],
+        (),
+        r#"
+        fn main() {
+            host::apply(|v| v / 0, 1)
+        }
+        "#,
+    )
+    .unwrap_err();
+
+    let error = error.downcast_ref::<VmError>().expect("a vm error");
+    let (kind, _) = error.kind().into_unwound_ref();
+    assert!(matches!(kind, DivideByZero));
+    Ok(())
+}
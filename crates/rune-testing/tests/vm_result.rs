@@ -1,4 +1,12 @@
 use rune_testing::*;
+use runestick::{Context, Module, ToValue as _};
+use std::fmt;
+use std::fmt::Write as _;
+
+#[derive(Debug)]
+struct Broken;
+
+runestick::impl_external!(Broken);
 
 #[test]
 fn test_result() {
@@ -29,3 +37,77 @@ fn test_result() {
         1,
     };
 }
+
+#[test]
+fn test_result_combinators() {
+    assert_eq! {
+        rune! {
+            (Result<i64, i64>, Result<i64, i64>, Result<i64, i64>) => r#"
+            fn main() {
+                (Ok(1).map(|v| v + 1), Err(1).map(|v| v + 1), Ok(1).and_then(|v| Err(v * 10)))
+            }
+            "#
+        },
+        (Ok(2), Err(1), Err(10)),
+    };
+
+    assert_eq! {
+        rune! {
+            (i64, i64, i64) => r#"
+            fn main() {
+                (Err(1).unwrap_or(2), Err(3).unwrap_or_else(|e| e * 2), Ok(4).expect("a value"))
+            }
+            "#
+        },
+        (2, 6, 4),
+    };
+
+    assert_vm_error!(
+        r#"fn main() { Err(1).expect("a value") }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "a value: 1");
+            assert!(reason.span().is_some());
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { Err([1, "two", Some(3)]).unwrap() }"#,
+        Panic { reason } => {
+            assert_eq!(
+                reason.to_string(),
+                "called `Result::unwrap()` on an `Err` value: [1, \"two\", Some(3)]"
+            );
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { let v = []; v.push(v); v.push(Some(v)); Err(v).unwrap() }"#,
+        Panic { reason } => {
+            assert_eq!(
+                reason.to_string(),
+                "called `Result::unwrap()` on an `Err` value: [[...], Some([...])]"
+            );
+        }
+    );
+}
+
+#[test]
+fn test_string_debug_restores_buffer() -> Result<()> {
+    let mut module = Module::new(&["broken"]);
+    module.ty(&["Broken"]).build::<Broken>()?;
+    module.inst_fn(runestick::STRING_DEBUG, |_: &Broken, buf: &mut String| {
+        buf.push_str("partial");
+        Err::<(), _>(fmt::Error)
+    })?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+
+    let mut buf = String::from("prefix: ");
+    assert!(Broken.to_value()?.string_debug(&context, &mut buf).is_err());
+    assert_eq!(buf, "prefix: partial");
+
+    write!(buf, "!")?;
+    assert_eq!(buf, "prefix: partial!");
+    Ok(())
+}
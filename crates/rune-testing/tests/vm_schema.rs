@@ -0,0 +1,89 @@
+use rune_testing::*;
+
+const SCHEMA: &str = r#"
+fn schema() {
+    std::schema::Schema::new(#{
+        type: "object",
+        extra: false,
+        fields: #{
+            name: #{type: "string", min_len: 1},
+            age: #{type: "int", min: 0, max: 150, optional: true},
+            score: #{type: "number", max: 1.5},
+            tags: #{type: "vec", items: "string", max_len: 2},
+        },
+    })
+}
+"#;
+
+fn validate(payload: &str) -> Result<Vec<String>> {
+    let source = format!(
+        r#"
+        {}
+
+        fn main() {{
+            match schema().validate({}) {{
+                Ok(_) => [],
+                Err(errors) => {{
+                    let out = [];
+
+                    for error in errors {{
+                        out.push(`{{error.path()}} {{error.message()}}`);
+                    }}
+
+                    out
+                }}
+            }}
+        }}
+        "#,
+        SCHEMA, payload
+    );
+
+    run(&["main"], (), &source)
+}
+
+#[test]
+fn test_schema_valid() -> Result<()> {
+    let errors = validate(r#"#{name: "ada", age: 36, score: 1, tags: ["a"]}"#)?;
+    assert!(errors.is_empty());
+
+    let errors = validate(r#"#{name: "ada", score: 1.5, tags: []}"#)?;
+    assert!(errors.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_schema_errors() -> Result<()> {
+    let errors = validate(r#"#{name: "", age: -1, tags: ["a", 2, "c"], admin: true}"#)?;
+
+    assert_eq!(
+        errors,
+        vec![
+            "$.age must be at least 0",
+            "$.name length must be at least 1",
+            "$.score missing field",
+            "$.tags[1] expected string, found int",
+            "$.tags length must be at most 2",
+            "$.admin unexpected field",
+        ]
+    );
+
+    let errors = validate(r#"[1, 2]"#)?;
+    assert_eq!(errors, vec!["$ expected object, found vec"]);
+    Ok(())
+}
+
+#[test]
+fn test_schema_invalid_declaration() {
+    let result = run::<_, _, ()>(
+        &["main"],
+        (),
+        r#"fn main() { std::schema::Schema::new(#{type: "string", min: 1}); }"#,
+    );
+
+    let error = result.unwrap_err().to_string();
+    assert!(
+        error.contains("`min` isn't supported for `string`"),
+        "{}",
+        error
+    );
+}
@@ -0,0 +1,125 @@
+use rune_testing::*;
+
+#[test]
+fn test_select_default_when_pending() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            use std::channel::channel;
+
+            async fn main() {
+                let (tx, rx) = channel();
+
+                let first = select {
+                    value = rx => value,
+                    default => Some(0),
+                };
+
+                tx.send(42);
+
+                let second = select {
+                    value = rx => value,
+                    default => Some(0),
+                };
+
+                first.unwrap() + second.unwrap()
+            }
+            "#
+        },
+        42,
+    };
+}
+
+#[test]
+fn test_select_default_prefers_ready_futures() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            async fn ready() { 7 }
+
+            async fn main() {
+                select {
+                    value = ready() => value,
+                    default => 0,
+                }
+            }
+            "#
+        },
+        7,
+    };
+}
+
+#[test]
+fn test_select_over_channels_and_streams() {
+    let mut out: Vec<(String, Option<i64>)> = rune! {
+            Vec<(String, Option<i64>)> => r#"
+            use std::channel::channel;
+
+            async fn numbers() {
+                yield 1;
+                yield 2;
+            }
+
+            async fn main() {
+                let (tx, rx) = channel();
+                tx.send(10);
+                tx.send(20);
+
+                let s = numbers();
+                let out = [];
+
+                // NB: the channel is left open, so once it's empty only the
+                // stream is ready.
+                for n in 0..5 {
+                    out.push(select {
+                        value = rx => ("rx", value),
+                        value = s => ("s", value),
+                    });
+                }
+
+                out.push(("rx", rx.try_recv()));
+                out
+            }
+            "#
+    };
+
+    assert_eq!(out.pop(), Some(("rx".to_owned(), None)));
+    out.sort();
+
+    assert_eq!(
+        out,
+        vec![
+            ("rx".to_owned(), Some(10)),
+            ("rx".to_owned(), Some(20)),
+            ("s".to_owned(), None),
+            ("s".to_owned(), Some(1)),
+            ("s".to_owned(), Some(2)),
+        ]
+    );
+}
+
+#[test]
+fn test_channel_recv_and_send() {
+    assert_eq! {
+        rune! {
+            (i64, bool, bool) => r#"
+            use std::channel::channel;
+
+            async fn main() {
+                let (tx, rx) = channel();
+                let other = tx.clone();
+
+                tx.send(1);
+                other.send(2);
+
+                let sum = rx.recv().await.unwrap() + rx.await.unwrap();
+                let empty = rx.try_recv().is_none();
+                drop(rx);
+
+                (sum, empty, tx.send(3).is_err())
+            }
+            "#
+        },
+        (3, true, true),
+    };
+}
@@ -0,0 +1,97 @@
+use rune_testing::*;
+use runestick::{Context, FromValue as _, Hash, Item, Unit, UnitFn, Value, Vm, VmError};
+use std::sync::Arc;
+
+const RECURSION: &str = r#"
+fn depth(n) {
+    if n == 0 {
+        return 0;
+    }
+
+    depth(n - 1) + 1
+}
+
+fn main() {
+    depth(100)
+}
+"#;
+
+fn function_id(unit: &Unit, name: &str) -> usize {
+    match unit.lookup(Hash::type_hash(&Item::of(&[name]))) {
+        Some(UnitFn::Offset { id, .. }) => id,
+        _ => panic!("missing function `{}`", name),
+    }
+}
+
+fn run_with_limit(source: &str, limit: usize) -> Result<Value, VmError> {
+    let context = Context::with_default_modules().unwrap();
+    let (unit, _) = compile_source(&context, source).unwrap();
+    let mut vm = Vm::new(Arc::new(context), Arc::new(unit));
+    vm.stack_mut().set_limit(Some(limit));
+    block_on(vm.call(Item::of(&["main"]), ())?.async_complete())
+}
+
+#[test]
+fn test_function_stack() {
+    let context = Context::with_default_modules().unwrap();
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        fn add(a, b) { a + b }
+        fn nested(a) { [a, [a, [a]]] }
+        fn main() { add(1, 2) }
+        "#,
+    )
+    .unwrap();
+
+    // The arguments, and a copy of each of them.
+    assert_eq!(unit.function_stack(function_id(&unit, "add")).unwrap(), 4);
+    // The argument, and a copy of it for every level of nesting.
+    assert_eq!(
+        unit.function_stack(function_id(&unit, "nested")).unwrap(),
+        4
+    );
+    // The arguments of the call.
+    assert_eq!(unit.function_stack(function_id(&unit, "main")).unwrap(), 2);
+}
+
+#[test]
+fn test_stack_limit() {
+    let output = run_with_limit(RECURSION, 1000).unwrap();
+    assert_eq!(i64::from_value(output).unwrap(), 100);
+
+    let error = run_with_limit(RECURSION, 100).unwrap_err();
+    let (kind, _) = error.kind().into_unwound_ref();
+
+    match kind {
+        StackOverflow { limit } => assert_eq!(*limit, 100),
+        kind => panic!("expected stack overflow but got `{:?}`", kind),
+    }
+}
+
+#[test]
+fn test_stack_limit_of_generator() {
+    let source = r#"
+    fn depth(n) {
+        if n == 0 {
+            return 0;
+        }
+
+        depth(n - 1) + 1
+    }
+
+    fn generate(n) {
+        yield depth(n);
+    }
+
+    fn main() {
+        generate(100).next()
+    }
+    "#;
+
+    assert!(run_with_limit(source, 1000).is_ok());
+
+    let error = run_with_limit(source, 100).unwrap_err();
+    assert!(error.to_string().contains("stack overflow"));
+}
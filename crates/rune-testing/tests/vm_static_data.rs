@@ -0,0 +1,142 @@
+use rune_testing::*;
+use runestick::{Context, Inst, StaticValue};
+
+#[test]
+fn test_constant_literals_are_static() {
+    let context = Context::with_default_modules().unwrap();
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        fn main() {
+            let a = [1, 2.5, "three", [b'4', '5']];
+            let b = (true, (), #{a: 1, b: b"two"});
+            let c = #{values: [1, 2, 3]};
+            [a, b, c]
+        }
+        "#,
+    )
+    .unwrap();
+
+    let statics = unit
+        .iter_instructions()
+        .filter(|inst| matches!(inst, Inst::Static { .. }))
+        .count();
+
+    assert_eq!(statics, 3);
+    assert_eq!(unit.iter_static_values().count(), 3);
+
+    assert!(!unit
+        .iter_instructions()
+        .any(|inst| matches!(inst, Inst::Vec { count: 4 } | Inst::Tuple { .. })));
+
+    assert!(matches!(
+        unit.iter_static_values().next(),
+        Some(StaticValue::Vec(values)) if values.len() == 4
+    ));
+}
+
+#[test]
+fn test_non_constant_literals_are_not_static() {
+    let context = Context::with_default_modules().unwrap();
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        fn main() {
+            let n = 1;
+            let a = [n, 2];
+            let b = (1 + 2, 3);
+            let c = #{n};
+            let d = [];
+            (a, b, c, d)
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(unit.iter_static_values().count(), 0);
+}
+
+#[test]
+fn test_static_values_are_copied_on_load() {
+    assert_eq!(
+        rune!(i64 => r#"
+        fn values() {
+            [1, 2, [3, 4]]
+        }
+
+        fn main() {
+            let a = values();
+            a.push(5);
+            a[2].push(6);
+
+            let b = values();
+            a.len() * 100 + a[2].len() * 10 + b.len() + b[2].len()
+        }
+        "#),
+        435
+    );
+
+    assert_eq!(
+        rune!(i64 => r#"
+        fn main() {
+            let sum = 0;
+
+            for n in 0..3 {
+                let o = #{a: 1, b: (2, 3)};
+                o.a = o.a + n;
+                sum += o.a + o.b.1;
+            }
+
+            sum
+        }
+        "#),
+        15
+    );
+}
+
+#[test]
+fn test_static_values_are_reused_when_unmodified() {
+    assert_eq!(
+        rune!(i64 => r#"
+        fn values() {
+            [1, 2, [3, 4]]
+        }
+
+        fn main() {
+            let a = values();
+            let b = values();
+            b[2].push(5);
+            let c = values();
+            c.push(6);
+            a.len() * 1000 + b[2].len() * 100 + c.len() * 10 + c[2].len()
+        }
+        "#),
+        3342
+    );
+
+    assert_eq!(
+        rune!(i64 => r#"
+        fn main() {
+            let inner = None;
+            let sum = 0;
+
+            for n in 0..3 {
+                let o = #{a: [1, 2]};
+
+                if n == 0 {
+                    inner = Some(o.a);
+                } else {
+                    o.a.push(n);
+                }
+
+                sum += o.a.len();
+            }
+
+            sum * 10 + inner.unwrap().len()
+        }
+        "#),
+        82
+    );
+}
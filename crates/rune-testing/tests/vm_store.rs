@@ -0,0 +1,47 @@
+use rune_testing::*;
+use runestick::modules::store::{self, MemoryStore, Store as _};
+use runestick::Context;
+use std::sync::Arc;
+
+#[test]
+fn test_store() -> Result<()> {
+    let shared = Arc::new(MemoryStore::new());
+    shared.set("config", b"existing".to_vec())?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&store::module(shared.clone())?)?;
+
+    let output: (Option<String>, Vec<String>, bool, bool, Option<String>) = run_with_context(
+        context,
+        &["main"],
+        (),
+        r#"
+        use std::store;
+
+        fn main() {
+            let existing = store::get_string("config")?;
+            store::set("user/1", "John")?;
+            store::set("user/2", "Jane".into_bytes())?;
+            store::set("other", "value")?;
+            let users = store::list("user/")?;
+            let deleted = store::delete("other")?;
+            let deleted_again = store::delete("other")?;
+            (existing, users, deleted, deleted_again, store::get_string("other")?)
+        }
+        "#,
+    )?;
+
+    assert_eq!(
+        output,
+        (
+            Some(String::from("existing")),
+            vec![String::from("user/1"), String::from("user/2")],
+            true,
+            false,
+            None
+        )
+    );
+
+    assert_eq!(shared.get("user/2")?, Some(b"Jane".to_vec()));
+    Ok(())
+}
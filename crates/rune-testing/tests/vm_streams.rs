@@ -71,3 +71,118 @@ fn test_resume() {
         6,
     };
 }
+
+/// A future which is pending the first time it's polled.
+struct YieldNow(bool);
+
+impl std::future::Future for YieldNow {
+    type Output = ();
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<()> {
+        if std::mem::replace(&mut self.0, true) {
+            return std::task::Poll::Ready(());
+        }
+
+        cx.waker().wake_by_ref();
+        std::task::Poll::Pending
+    }
+}
+
+fn yield_context() -> runestick::Context {
+    let mut module = runestick::Module::new(&["native"]);
+    module
+        .async_function(&["yield_now"], || YieldNow(false))
+        .unwrap();
+
+    let mut context = runestick::Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+    context
+}
+
+#[test]
+fn test_stream_completion() {
+    assert_eq! {
+        rune! {
+            (i64, bool, i64) => r#"
+            async fn foo() {
+                yield 1;
+                yield 2;
+                10
+            }
+
+            async fn main() {
+                let gen = foo();
+                let result = 0;
+
+                while let Some(value) = gen.next().await {
+                    result += value;
+                }
+
+                (result, gen.is_complete(), gen.completion().unwrap())
+            }
+            "#
+        },
+        (3, true, 10),
+    };
+}
+
+#[test]
+fn test_select_over_streams() {
+    let (result, a, b): (Vec<(String, Option<i64>)>, Option<String>, Option<String>) =
+        rune_testing::run_with_context(
+            yield_context(),
+            &["main"],
+            (),
+            r#"
+        async fn numbers(from, to) {
+            for n in from..to {
+                native::yield_now().await;
+                yield n;
+            }
+
+            "done"
+        }
+
+        async fn main() {
+            let a = numbers(0, 3);
+            let b = numbers(10, 12);
+            let out = [];
+
+            loop {
+                let value = select {
+                    value = a => ("a", value),
+                    value = b => ("b", value),
+                };
+
+                if value == () {
+                    break;
+                }
+
+                out.push(value);
+            }
+
+            (out, a.completion(), b.completion())
+        }
+        "#,
+        )
+        .unwrap();
+
+    let from = |name: &str| {
+        result
+            .iter()
+            .filter(|(n, _)| n == name)
+            .map(|(_, value)| *value)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(a.as_deref(), Some("done"));
+    assert_eq!(b.as_deref(), Some("done"));
+
+    // NB: every value is produced exactly once, even though the streams
+    // lose selects while they're waiting.
+    assert_eq!(from("a"), vec![Some(0), Some(1), Some(2), None]);
+    assert_eq!(from("b"), vec![Some(10), Some(11), None]);
+}
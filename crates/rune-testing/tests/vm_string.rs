@@ -0,0 +1,136 @@
+use rune_testing::*;
+
+#[test]
+fn test_unicode_lengths() {
+    assert_eq! {
+        rune! {
+            (usize, usize, usize) => r#"
+            fn main() {
+                let s = "héllo wörld";
+                (s.len(), s.len_bytes(), s.len_chars())
+            }
+            "#
+        },
+        (13, 13, 11),
+    };
+}
+
+#[test]
+fn test_chars_and_graphemes() {
+    assert_eq! {
+        rune! {
+            (Vec<char>, Vec<String>) => r#"
+            fn main() {
+                let s = "e\u{301}a🇸🇪";
+                (s.chars(), s.graphemes())
+            }
+            "#
+        },
+        (
+            vec!['e', '\u{301}', 'a', '🇸', '🇪'],
+            vec![String::from("e\u{301}"), String::from("a"), String::from("🇸🇪")],
+        ),
+    };
+}
+
+#[test]
+fn test_case_folding() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, String, String) => r#"
+            fn main() {
+                (
+                    "Straße".eq_ignore_case("STRAßE"),
+                    "ΣΑΣ".eq_ignore_case("σας"),
+                    "abc".eq_ignore_case("abd"),
+                    "ÅÄÖ".to_lowercase(),
+                    "åäö".to_uppercase(),
+                )
+            }
+            "#
+        },
+        (true, true, false, String::from("åäö"), String::from("ÅÄÖ")),
+    };
+}
+
+#[test]
+fn test_number_formatting() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            use std::fmt::format_int;
+            use std::fmt::format_float;
+
+            fn main() {
+                [
+                    format_int(1234567, #{separator: "_", width: 12}),
+                    format_int(-1234, #{separator: ",", fill: '0', width: 8}),
+                    format_int(42, #{sign: true}),
+                    format_int(123456, #{separator: " ", group: 2}),
+                    format_float(-1234.5678, #{separator: ".", decimal: ",", precision: 2}),
+                    format_float(0.5, #{precision: 3, width: 8, fill: '*'}),
+                    format_float(1.0 / 0.0, #{sign: true}),
+                    format_float(-1.0 / 0.0, #{separator: ",", width: 8}),
+                    format_float(0.0 / 0.0, #{fill: '0', width: 8}),
+                ]
+            }
+            "#
+        },
+        vec![
+            String::from("   1_234_567"),
+            String::from("-001,234"),
+            String::from("+42"),
+            String::from("12 34 56"),
+            String::from("-1.234,57"),
+            String::from("***0.500"),
+            String::from("+inf"),
+            String::from("    -inf"),
+            String::from("     NaN"),
+        ],
+    };
+
+    assert_vm_error!(
+        r#"
+        fn main() {
+            std::fmt::format_int(1, #{separatr: "_"});
+        }
+        "#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "unsupported number format option `separatr`");
+        }
+    );
+}
+
+#[test]
+fn test_constant_string_folding() {
+    assert_eq! {
+        rune!(String => r#"fn main() { "Hello" + ", " + ("World" + `{"!"}`) }"#),
+        "Hello, World!",
+    };
+
+    assert_eq! {
+        rune!(String => r#"fn main() { `{1} + {-2} = {`{-1}`}` }"#),
+        "1 + -2 = -1",
+    };
+
+    // NB: folded strings are still owned, just like at runtime.
+    assert_eq! {
+        rune!(String => r#"fn main() { let s = `Hello {"World"}`; s.push_str("!"); s }"#),
+        "Hello World!",
+    };
+
+    assert_eq! {
+        rune!(String => r#"fn main() { let n = 2; `{1} {n} {"3"}` }"#),
+        "1 2 3",
+    };
+
+    let context = runestick::Context::with_default_modules().unwrap();
+    let (unit, _) = compile_source(&context, r#"fn main() { `a {"b"} {1}` + "c" }"#).unwrap();
+
+    let strings = unit
+        .iter_static_strings()
+        .map(|s| s.as_str().to_owned())
+        .collect::<Vec<_>>();
+
+    assert_eq!(strings, vec![String::from("a b 1c")]);
+}
@@ -0,0 +1,163 @@
+use rune_testing::*;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// A recorded span.
+#[derive(Debug, Clone, PartialEq)]
+struct Recorded {
+    /// Name of the span, including the item for function calls.
+    name: String,
+    /// Name of the parent span.
+    parent: Option<String>,
+    /// If the span has been closed.
+    closed: bool,
+}
+
+#[derive(Default)]
+struct State {
+    spans: Vec<Recorded>,
+    current: Vec<u64>,
+}
+
+/// A subscriber which records the names and parents of all spans.
+#[derive(Default, Clone)]
+struct Recorder(Arc<Mutex<State>>);
+
+impl Recorder {
+    fn spans(&self) -> Vec<(String, Option<String>, bool)> {
+        let state = self.0.lock().unwrap();
+
+        state
+            .spans
+            .iter()
+            .map(|span| (span.name.clone(), span.parent.clone(), span.closed))
+            .collect()
+    }
+}
+
+struct ItemVisitor(Option<String>);
+
+impl Visit for ItemVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "item" {
+            self.0 = Some(value.to_owned());
+        }
+    }
+
+    fn record_debug(&mut self, _: &Field, _: &dyn fmt::Debug) {}
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+        let mut visitor = ItemVisitor(None);
+        attrs.record(&mut visitor);
+
+        let name = match visitor.0 {
+            Some(item) => format!("{} {}", attrs.metadata().name(), item),
+            None => attrs.metadata().name().to_owned(),
+        };
+
+        let mut state = self.0.lock().unwrap();
+
+        let parent = if attrs.is_contextual() {
+            state.current.last().copied()
+        } else {
+            attrs.parent().map(|id| id.into_u64())
+        };
+
+        let parent = parent.map(|id| state.spans[id as usize - 1].name.clone());
+
+        state.spans.push(Recorded {
+            name,
+            parent,
+            closed: false,
+        });
+
+        Id::from_u64(state.spans.len() as u64)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, id: &Id) {
+        self.0.lock().unwrap().current.push(id.into_u64());
+    }
+
+    fn exit(&self, _: &Id) {
+        self.0.lock().unwrap().current.pop();
+    }
+
+    fn try_close(&self, id: Id) -> bool {
+        self.0.lock().unwrap().spans[id.into_u64() as usize - 1].closed = true;
+        true
+    }
+}
+
+fn span(name: &str, parent: Option<&str>) -> (String, Option<String>, bool) {
+    (name.to_owned(), parent.map(str::to_owned), true)
+}
+
+#[test]
+fn test_function_spans() {
+    let recorder = Recorder::default();
+
+    let output: i64 = tracing::subscriber::with_default(recorder.clone(), || {
+        let _root = tracing::trace_span!("host").entered();
+
+        rune! { i64 =>
+            r#"
+            fn bar(n) { n + 1 }
+            fn foo(n) { bar(n) * 2 }
+            fn main() { foo(1) + bar(2) }
+            "#
+        }
+    });
+
+    assert_eq!(output, 7);
+
+    assert_eq!(
+        recorder.spans(),
+        vec![
+            span("host", None),
+            span("call main", Some("host")),
+            span("call foo", Some("call main")),
+            span("call bar", Some("call foo")),
+            span("call bar", Some("call main")),
+        ]
+    );
+}
+
+#[test]
+fn test_await_spans() {
+    let recorder = Recorder::default();
+
+    let output: i64 = tracing::subscriber::with_default(recorder.clone(), || {
+        rune! { i64 =>
+            r#"
+            async fn foo(n) { n + 1 }
+            async fn main() { foo(1).await }
+            "#
+        }
+    });
+
+    assert_eq!(output, 2);
+
+    assert_eq!(
+        recorder.spans(),
+        vec![
+            span("call main", None),
+            span("await", Some("call main")),
+            span("call foo", Some("await")),
+        ]
+    );
+}
@@ -0,0 +1,77 @@
+use rune_testing::*;
+use std::thread;
+
+#[test]
+fn test_transfer_between_vms() -> Result<()> {
+    let value: Value = run(
+        &["main"],
+        (),
+        r#"
+        fn main() {
+            let shared = [1, 2];
+            #{a: shared, b: shared, c: Some(("hello", 42.0))}
+        }
+        "#,
+    )?;
+
+    let transfer = value.deep_copy_for_transfer()?;
+
+    let output: (i64, i64, String) = thread::spawn(move || {
+        run(
+            &["main"],
+            (transfer.into_value(),),
+            r#"
+            fn main(value) {
+                value.a.push(3);
+                let (s, _) = value.c.unwrap();
+                (value.a.len(), value.b.len(), s)
+            }
+            "#,
+        )
+    })
+    .join()
+    .expect("thread panicked")?;
+
+    assert_eq!(output, (3, 3, String::from("hello")));
+
+    // The original value is unaffected by modifications to the copy.
+    let a = value
+        .into_object()?
+        .take()?
+        .remove("a")
+        .expect("missing field");
+    let a: Vec<i64> = runestick::FromValue::from_value(a)?;
+    assert_eq!(a, vec![1, 2]);
+    Ok(())
+}
+
+#[test]
+fn test_transfer_errors() -> Result<()> {
+    let value: Value = run(
+        &["main"],
+        (),
+        r#"
+        fn main() {
+            let v = [];
+            v.push(v);
+            v
+        }
+        "#,
+    )?;
+
+    let error = value.deep_copy_for_transfer().unwrap_err();
+    assert!(matches!(error.kind(), TransferCycle { .. }));
+
+    let value: Value = run(
+        &["main"],
+        (),
+        r#"
+        fn foo() {}
+        fn main() { [1, foo] }
+        "#,
+    )?;
+
+    let error = value.deep_copy_for_transfer().unwrap_err();
+    assert!(matches!(error.kind(), UnsupportedTransfer { .. }));
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use rune::termcolor::Buffer;
+use rune::EmitDiagnostics as _;
+use rune_testing::*;
+use runestick::{Context, Item, Source, Vm};
+use std::sync::Arc;
+
+const SOURCE: &str = r#"
+mod shop {
+    struct Customer { name }
+}
+
+enum Status { Active, Closed }
+
+fn main() {
+    let customer = shop::Customer { name: "Ada" };
+    customer + Status::Active
+}
+"#;
+
+#[test]
+fn test_type_names_in_errors() {
+    let context = Arc::new(Context::with_default_modules().unwrap());
+    let (unit, _) = compile_source(&context, SOURCE).unwrap();
+
+    let vm = Vm::new(context, Arc::new(unit));
+    let names = vm.type_names();
+
+    assert_eq!(
+        names
+            .lookup(runestick::Hash::type_hash(&Item::of(&["shop", "Customer"])))
+            .map(ToString::to_string),
+        Some(String::from("shop::Customer"))
+    );
+
+    let error = block_on(vm.call(Item::of(&["main"]), ()).unwrap().async_complete()).unwrap_err();
+
+    assert!(error.to_string().contains("Type(0x"));
+
+    let message = names.display(&error).to_string();
+    assert!(message.contains("`shop::Customer + Status`"), "{}", message);
+
+    // Type names are only resolved while displaying through the resolver.
+    assert!(error.to_string().contains("Type(0x"));
+}
+
+#[test]
+fn test_type_names_in_diagnostics() {
+    let context = Arc::new(Context::with_default_modules().unwrap());
+
+    let mut sources = rune::Sources::new();
+    sources.insert_default(Source::new("main", SOURCE));
+
+    let unit = rune::load_sources(
+        &context,
+        &rune::Options::default(),
+        &mut sources,
+        &mut rune::Warnings::new(),
+    )
+    .unwrap();
+
+    let vm = Vm::new(context, Arc::new(unit));
+    let error = block_on(vm.call(Item::of(&["main"]), ()).unwrap().async_complete()).unwrap_err();
+
+    let mut buffer = Buffer::no_color();
+    error.emit_diagnostics(&mut buffer, &sources).unwrap();
+    let output = String::from_utf8(buffer.into_inner()).unwrap();
+
+    assert!(output.contains("shop::Customer"), "{}", output);
+    assert!(!output.contains("Type(0x"), "{}", output);
+}
@@ -10,6 +10,8 @@ pub enum AssemblyInst {
     JumpIf { label: Label },
     JumpIfNot { label: Label },
     JumpIfBranch { branch: i64, label: Label },
+    PeekAndJumpIf { label: Label },
+    PeekAndJumpIfNot { label: Label },
     PopAndJumpIfNot { count: usize, label: Label },
     Raw { raw: Inst },
 }
@@ -29,8 +31,9 @@ pub struct Assembly {
     pub(crate) comments: HashMap<usize, Vec<String>>,
     /// The number of labels.
     pub(crate) label_count: usize,
-    /// The collection of functions required by this assembly.
-    pub(crate) required_functions: HashMap<Hash, Vec<(Span, usize)>>,
+    /// The collection of functions required by this assembly, with the span,
+    /// source id, and number of arguments of every call.
+    pub(crate) required_functions: HashMap<Hash, Vec<(Span, usize, usize)>>,
 }
 
 impl Assembly {
@@ -89,6 +92,18 @@ impl Assembly {
             .push((AssemblyInst::JumpIfBranch { branch, label }, span));
     }
 
+    /// Add a conditional jump which leaves the condition on the stack.
+    pub(crate) fn peek_and_jump_if(&mut self, label: Label, span: Span) {
+        self.instructions
+            .push((AssemblyInst::PeekAndJumpIf { label }, span));
+    }
+
+    /// Add a conditional jump which leaves the condition on the stack.
+    pub(crate) fn peek_and_jump_if_not(&mut self, label: Label, span: Span) {
+        self.instructions
+            .push((AssemblyInst::PeekAndJumpIfNot { label }, span));
+    }
+
     /// Add a pop-and-jump-if-not instruction to a label.
     pub(crate) fn pop_and_jump_if_not(&mut self, count: usize, label: Label, span: Span) {
         self.instructions
@@ -97,11 +112,11 @@ impl Assembly {
 
     /// Push a raw instruction.
     pub(crate) fn push(&mut self, raw: Inst, span: Span) {
-        if let Inst::Call { hash, .. } = raw {
+        if let Inst::Call { hash, args } = raw {
             self.required_functions
                 .entry(hash)
                 .or_default()
-                .push((span, self.source_id));
+                .push((span, self.source_id, args));
         }
 
         self.instructions.push((AssemblyInst::Raw { raw }, span));
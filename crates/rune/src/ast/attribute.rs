@@ -0,0 +1,67 @@
+use crate::ast;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::traits::{Parse, Peek};
+use runestick::Span;
+
+/// An attribute, like `#[protocol(ADD)]`.
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    /// The `#` token.
+    pub hash: ast::Hash,
+    /// The open bracket.
+    pub open: ast::OpenBracket,
+    /// The name of the attribute.
+    pub name: ast::Ident,
+    /// The optional arguments of the attribute.
+    pub args: Option<ast::Parenthesized<ast::Ident, ast::Comma>>,
+    /// The close bracket.
+    pub close: ast::CloseBracket,
+}
+
+impl Attribute {
+    /// Access the span of the attribute.
+    pub fn span(&self) -> Span {
+        self.hash.span().join(self.close.span())
+    }
+}
+
+impl Peek for Attribute {
+    fn peek(t1: Option<ast::Token>, t2: Option<ast::Token>) -> bool {
+        matches!(
+            (t1.map(|t| t.kind), t2.map(|t| t.kind)),
+            (
+                Some(ast::Kind::Hash),
+                Some(ast::Kind::Open(ast::Delimiter::Bracket))
+            )
+        )
+    }
+}
+
+/// Parse implementation for an attribute.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::Attribute>("#[test]").unwrap();
+///
+/// let attribute = parse_all::<ast::Attribute>("#[protocol(ADD)]").unwrap();
+/// assert_eq!(attribute.args.unwrap().items.len(), 1);
+/// ```
+impl Parse for Attribute {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        Ok(Self {
+            hash: parser.parse()?,
+            open: parser.parse()?,
+            name: parser.parse()?,
+            args: if parser.peek::<ast::OpenParen>()? {
+                Some(parser.parse()?)
+            } else {
+                None
+            },
+            close: parser.parse()?,
+        })
+    }
+}
@@ -48,7 +48,11 @@ impl Decl {
 }
 
 impl Peek for Decl {
-    fn peek(t1: Option<ast::Token>, _: Option<ast::Token>) -> bool {
+    fn peek(t1: Option<ast::Token>, t2: Option<ast::Token>) -> bool {
+        if ast::Attribute::peek(t1, t2) {
+            return true;
+        }
+
         let t1 = match t1 {
             Some(t1) => t1,
             None => return false,
@@ -75,7 +79,7 @@ impl Parse for Decl {
             ast::Kind::Enum => Self::DeclEnum(parser.parse()?),
             ast::Kind::Struct => Self::DeclStruct(parser.parse()?),
             ast::Kind::Impl => Self::DeclImpl(parser.parse()?),
-            ast::Kind::Async | ast::Kind::Fn => Self::DeclFn(parser.parse()?),
+            ast::Kind::Async | ast::Kind::Fn | ast::Kind::Hash => Self::DeclFn(parser.parse()?),
             ast::Kind::Mod => Self::DeclMod(parser.parse()?),
             _ => {
                 return Err(ParseError::ExpectedDecl {
@@ -8,6 +8,8 @@ use runestick::Span;
 /// A function.
 #[derive(Debug, Clone)]
 pub struct DeclFn {
+    /// Attributes associated with the function.
+    pub attributes: Vec<ast::Attribute>,
     /// The optional `async` keyword.
     pub async_: Option<ast::Async>,
     /// The `fn` token.
@@ -46,13 +48,13 @@ impl DeclFn {
 }
 
 impl Peek for DeclFn {
-    fn peek(t1: Option<Token>, _: Option<Token>) -> bool {
+    fn peek(t1: Option<Token>, t2: Option<Token>) -> bool {
         let t = match t1 {
             Some(t) => t,
             None => return false,
         };
 
-        matches!(t.kind, Kind::Fn | Kind::Async)
+        matches!(t.kind, Kind::Fn | Kind::Async) || ast::Attribute::peek(t1, t2)
     }
 }
 
@@ -71,10 +73,14 @@ impl Peek for DeclFn {
 ///
 /// let item = parse_all::<ast::DeclFn>("fn hello(foo, bar) {}").unwrap();
 /// assert_eq!(item.args.items.len(), 2);
+///
+/// let item = parse_all::<ast::DeclFn>("#[protocol(ADD)] fn add(self, other) {}").unwrap();
+/// assert_eq!(item.attributes.len(), 1);
 /// ```
 impl Parse for DeclFn {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
         Ok(Self {
+            attributes: parser.parse()?,
             async_: parser.parse()?,
             fn_: parser.parse()?,
             name: parser.parse()?,
@@ -18,18 +18,6 @@ impl ops::Deref for EagerBrace {
     }
 }
 
-/// Indicates if field accesses should be parsed or not.
-#[derive(Debug, Clone, Copy)]
-pub(super) struct ExprChain(pub(super) bool);
-
-impl ops::Deref for ExprChain {
-    type Target = bool;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
 /// A rune expression.
 #[derive(Debug, Clone)]
 pub enum Expr {
@@ -69,6 +57,8 @@ pub enum Expr {
     ExprIndexGet(ast::ExprIndexGet),
     /// A break expression.
     ExprBreak(ast::ExprBreak),
+    /// A continue expression.
+    ExprContinue(ast::ExprContinue),
     /// A yield expression.
     ExprYield(ast::ExprYield),
     /// A block as an expression.
@@ -105,9 +95,29 @@ pub enum Expr {
     LitObject(ast::LitObject),
     /// A literal tuple declaration.
     LitTuple(ast::LitTuple),
+    /// A literal hash map, expanded from a `map!{}` macro call.
+    LitMap(ast::LitMap),
+    /// A literal hash set, expanded from a `set!{}` macro call.
+    LitSet(ast::LitSet),
 }
 
 impl Expr {
+    /// Parse an expression and narrow it down to one kind of expression with
+    /// the given function, which returns the expression back if it's of the
+    /// wrong kind.
+    pub(super) fn parse_variant<T>(
+        parser: &mut Parser<'_>,
+        expected: &'static str,
+        narrow: impl FnOnce(Self) -> Result<T, Self>,
+    ) -> Result<T, ParseError> {
+        let expr = parser.parse::<Self>()?;
+
+        narrow(expr).map_err(|expr| ParseError::ExpectedSyntax {
+            span: expr.span(),
+            expected,
+        })
+    }
+
     /// Test if the expression implicitly evaluates to nothing.
     pub fn produces_nothing(&self) -> bool {
         match self {
@@ -119,6 +129,7 @@ impl Expr {
             Self::ExprIf(expr_if) => expr_if.produces_nothing(),
             Self::ExprGroup(expr_group) => expr_group.produces_nothing(),
             Self::ExprBreak(..) => true,
+            Self::ExprContinue(..) => true,
             Self::ExprBinary(expr_binary) => expr_binary.produces_nothing(),
             Self::ExprBlock(expr_block) => expr_block.produces_nothing(),
             Self::ExprReturn(..) => true,
@@ -126,6 +137,69 @@ impl Expr {
         }
     }
 
+    /// Test if the expression always evaluates to `()`.
+    pub fn produces_unit(&self) -> bool {
+        match self {
+            Self::LitUnit(..) => true,
+            Self::ExprWhile(..) => true,
+            Self::ExprFor(..) => true,
+            Self::ExprLet(..) => true,
+            Self::ExprIndexSet(..) => true,
+            Self::ExprIf(expr_if) => expr_if.produces_unit(),
+            Self::ExprGroup(expr_group) => expr_group.expr.produces_unit(),
+            Self::ExprBinary(expr_binary) => expr_binary.produces_nothing(),
+            Self::ExprBlock(expr_block) => {
+                expr_block.async_.is_none() && expr_block.produces_unit()
+            }
+            _ => false,
+        }
+    }
+
+    /// Test if the expression definitely evaluates to a value other than `()`.
+    ///
+    /// Expressions like function calls might or might not produce a value, so
+    /// they are not considered to produce one.
+    pub fn produces_value(&self) -> bool {
+        match self {
+            Self::LitBool(..) => true,
+            Self::LitChar(..) => true,
+            Self::LitByte(..) => true,
+            Self::LitNumber(..) => true,
+            Self::LitStr(..) => true,
+            Self::LitByteStr(..) => true,
+            Self::LitTemplate(..) => true,
+            Self::LitVec(..) => true,
+            Self::LitObject(..) => true,
+            Self::LitTuple(..) => true,
+            Self::LitMap(..) => true,
+            Self::LitSet(..) => true,
+            Self::ExprClosure(..) => true,
+            Self::ExprUnary(..) => true,
+            Self::ExprBinary(expr_binary) => !expr_binary.produces_nothing(),
+            Self::ExprGroup(expr_group) => expr_group.expr.produces_value(),
+            Self::ExprBlock(expr_block) => {
+                expr_block.async_.is_some() || expr_block.produces_value()
+            }
+            _ => false,
+        }
+    }
+
+    /// Test if the expression unconditionally diverges, meaning that nothing
+    /// following it is reachable.
+    ///
+    /// Like `return` and `break`, or a block containing one of them.
+    pub fn diverges(&self) -> bool {
+        match self {
+            Self::ExprBreak(..) => true,
+            Self::ExprContinue(..) => true,
+            Self::ExprReturn(..) => true,
+            Self::ExprBlock(expr_block) => {
+                expr_block.async_.is_none() && expr_block.diverging_expr().is_some()
+            }
+            _ => false,
+        }
+    }
+
     /// Test if expression should be chained by default.
     pub fn is_chainable(&self) -> bool {
         match self {
@@ -157,6 +231,7 @@ impl Expr {
             Self::ExprBinary(expr) => expr.span(),
             Self::ExprIndexGet(expr) => expr.span(),
             Self::ExprBreak(b) => b.span(),
+            Self::ExprContinue(c) => c.span(),
             Self::ExprYield(b) => b.span(),
             Self::ExprBlock(b) => b.span(),
             Self::ExprReturn(ret) => ret.span(),
@@ -169,6 +244,8 @@ impl Expr {
             Self::LitVec(expr) => expr.span(),
             Self::LitObject(expr) => expr.span(),
             Self::LitTuple(expr) => expr.span(),
+            Self::LitMap(expr) => expr.span(),
+            Self::LitSet(expr) => expr.span(),
             Self::LitNumber(expr) => expr.span(),
             Self::LitByte(expr) => expr.span(),
             Self::LitChar(expr) => expr.span(),
@@ -203,16 +280,15 @@ impl Expr {
     /// are arguments to statements immediately followed by blocks. Like `if`,
     /// `while`, and `match`.
     pub(super) fn parse_without_eager_brace(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
-        Self::parse_full(parser, EagerBrace(false), ExprChain(true))
+        Self::parse_full(parser, EagerBrace(false))
     }
 
     /// Full, configurable parsing of an expression.
     pub(super) fn parse_full(
         parser: &mut Parser<'_>,
         eager_brace: EagerBrace,
-        expr_chain: ExprChain,
     ) -> Result<Self, ParseError> {
-        let lhs = Self::parse_primary(parser, eager_brace, expr_chain)?;
+        let lhs = Self::parse_primary(parser, eager_brace)?;
         Ok(Self::parse_expr_binary(parser, lhs, 0, eager_brace)?)
     }
 
@@ -247,7 +323,7 @@ impl Expr {
         }
 
         let open = parser.parse::<ast::OpenParen>()?;
-        let expr = ast::Expr::parse_full(parser, EagerBrace(true), ExprChain(true))?;
+        let expr = ast::Expr::parse_full(parser, EagerBrace(true))?;
 
         if parser.peek::<ast::CloseParen>()? {
             return Ok(Expr::ExprGroup(ast::ExprGroup {
@@ -261,18 +337,42 @@ impl Expr {
         Ok(Expr::LitTuple(lit_tuple))
     }
 
+    /// Parse a negation, folding it into a number literal if that's what
+    /// it's applied to so that literals like `-9223372036854775808` can be
+    /// represented.
+    fn parse_neg(parser: &mut Parser<'_>, eager_brace: EagerBrace) -> Result<Self, ParseError> {
+        let expr_unary = ast::ExprUnary::parse_with_eager_brace(parser, eager_brace)?;
+
+        match (expr_unary.op, *expr_unary.expr) {
+            (ast::UnaryOp::Neg, Self::LitNumber(lit_number)) => {
+                match lit_number.negate(expr_unary.token) {
+                    Ok(lit_number) => Ok(Self::LitNumber(lit_number)),
+                    Err(lit_number) => Ok(Self::ExprUnary(ast::ExprUnary {
+                        op: expr_unary.op,
+                        token: expr_unary.token,
+                        expr: Box::new(Self::LitNumber(lit_number)),
+                    })),
+                }
+            }
+            (op, expr) => Ok(Self::ExprUnary(ast::ExprUnary {
+                op,
+                token: expr_unary.token,
+                expr: Box::new(expr),
+            })),
+        }
+    }
+
     /// Parse a single expression value.
     pub(super) fn parse_primary(
         parser: &mut Parser<'_>,
         eager_brace: EagerBrace,
-        expr_chain: ExprChain,
     ) -> Result<Self, ParseError> {
         let token = parser.token_peek_eof()?;
 
         let expr = match token.kind {
             ast::Kind::Async => {
                 let async_: ast::Async = parser.parse()?;
-                let expr: Self = Self::parse_primary(parser, eager_brace, expr_chain)?;
+                let expr: Self = Self::parse_primary(parser, eager_brace)?;
 
                 match expr {
                     Self::ExprClosure(expr_closure) => Self::ExprClosure(ast::ExprClosure {
@@ -314,7 +414,10 @@ impl Expr {
                 });
             }
             ast::Kind::Hash => Self::LitObject(parser.parse()?),
-            ast::Kind::Bang | Kind::Amp | Kind::Star => Self::ExprUnary(parser.parse()?),
+            ast::Kind::Bang | Kind::Amp | Kind::Star => {
+                Self::ExprUnary(ast::ExprUnary::parse_with_eager_brace(parser, eager_brace)?)
+            }
+            ast::Kind::Dash => Self::parse_neg(parser, eager_brace)?,
             ast::Kind::While => Self::ExprWhile(parser.parse()?),
             ast::Kind::Loop => Self::ExprLoop(parser.parse()?),
             ast::Kind::For => Self::ExprFor(parser.parse()?),
@@ -333,6 +436,7 @@ impl Expr {
             ast::Kind::True | Kind::False => Self::LitBool(parser.parse()?),
             ast::Kind::Ident => Self::parse_ident_start(parser, eager_brace)?,
             ast::Kind::Break => Self::ExprBreak(parser.parse()?),
+            ast::Kind::Continue => Self::ExprContinue(parser.parse()?),
             ast::Kind::Yield => Self::ExprYield(parser.parse()?),
             ast::Kind::Return => Self::ExprReturn(parser.parse()?),
             _ => {
@@ -343,10 +447,6 @@ impl Expr {
             }
         };
 
-        if !*expr_chain {
-            return Ok(expr);
-        }
-
         Ok(Self::parse_expr_chain(parser, expr)?)
     }
 
@@ -392,53 +492,28 @@ impl Expr {
                         try_: parser.parse()?,
                     });
                 }
+                // NB: only a restricted set of tokens may follow a dot, so that
+                // it's never ambiguous what `.await`, `?`, or a call applies
+                // to.
                 ast::Kind::Dot => {
                     let dot = parser.parse()?;
-                    let token = parser.token_peek()?;
-
-                    if let Some(token) = token {
-                        if let Kind::Await = token.kind {
-                            expr = Expr::ExprAwait(ast::ExprAwait {
+                    let token = parser.token_peek_eof()?;
+
+                    expr = match token.kind {
+                        Kind::Await => Expr::ExprAwait(ast::ExprAwait {
+                            expr: Box::new(expr),
+                            dot,
+                            await_: parser.parse()?,
+                        }),
+                        Kind::Ident | Kind::LitNumber { .. } => {
+                            Expr::ExprFieldAccess(ast::ExprFieldAccess {
                                 expr: Box::new(expr),
                                 dot,
-                                await_: parser.parse()?,
-                            });
-
-                            continue;
+                                expr_field: parser.parse()?,
+                            })
                         }
-                    }
-
-                    let next = Expr::parse_primary(parser, EagerBrace(false), ExprChain(false))?;
-
-                    let span = match next {
-                        Expr::Path(path) => {
-                            let span = path.span();
-
-                            if let Some(name) = path.try_into_ident() {
-                                expr = Expr::ExprFieldAccess(ast::ExprFieldAccess {
-                                    expr: Box::new(expr),
-                                    dot,
-                                    expr_field: ast::ExprField::Ident(name),
-                                });
-
-                                continue;
-                            }
-
-                            span
-                        }
-                        Expr::LitNumber(n) => {
-                            expr = Expr::ExprFieldAccess(ast::ExprFieldAccess {
-                                expr: Box::new(expr),
-                                dot,
-                                expr_field: ast::ExprField::LitNumber(n),
-                            });
-
-                            continue;
-                        }
-                        other => other.span(),
+                        _ => return Err(ParseError::UnsupportedFieldAccess { span: token.span }),
                     };
-
-                    return Err(ParseError::UnsupportedFieldAccess { span });
                 }
                 _ => break,
             }
@@ -468,7 +543,7 @@ impl Expr {
                 parser.token_next()?;
             }
 
-            let mut rhs = Self::parse_primary(parser, eager_brace, ExprChain(true))?;
+            let mut rhs = Self::parse_primary(parser, eager_brace)?;
 
             lookahead_tok = parser.token_peek_pair()?;
 
@@ -536,7 +611,7 @@ impl Expr {
 /// ```
 impl Parse for Expr {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
-        Self::parse_full(parser, EagerBrace(true), ExprChain(true))
+        Self::parse_full(parser, EagerBrace(true))
     }
 }
 
@@ -554,6 +629,7 @@ impl Peek for Expr {
             ast::Kind::Label => matches!(t2.map(|t| t.kind), Some(Kind::Colon)),
             ast::Kind::Hash => true,
             ast::Kind::Bang | ast::Kind::Amp | ast::Kind::Star => true,
+            ast::Kind::Dash => true,
             ast::Kind::While => true,
             ast::Kind::Loop => true,
             ast::Kind::For => true,
@@ -571,6 +647,7 @@ impl Peek for Expr {
             ast::Kind::True | Kind::False => true,
             ast::Kind::Ident => true,
             ast::Kind::Break => true,
+            ast::Kind::Continue => true,
             ast::Kind::Return => true,
             _ => false,
         }
@@ -22,12 +22,21 @@ impl ExprAwait {
     }
 }
 
+/// Parse implementation for an await expression.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::ExprAwait>("foo.await").unwrap();
+/// parse_all::<ast::ExprAwait>("foo().await").unwrap();
+/// ```
 impl Parse for ExprAwait {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
-        Ok(Self {
-            expr: Box::new(parser.parse()?),
-            dot: parser.parse()?,
-            await_: parser.parse()?,
+        Expr::parse_variant(parser, "an await expression", |expr| match expr {
+            Expr::ExprAwait(expr) => Ok(expr),
+            expr => Err(expr),
         })
     }
 }
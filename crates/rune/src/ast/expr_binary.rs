@@ -1,5 +1,7 @@
 use crate::ast;
-use crate::traits::Peek;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::traits::{Parse, Peek};
 use runestick::Span;
 use std::fmt;
 
@@ -61,6 +63,8 @@ pub enum BinOp {
     Is,
     /// Negated instance of test `a is not b`.
     IsNot,
+    /// Type conversion `a as b`.
+    As,
     /// Lazy and operator `&&`.
     And,
     /// Lazy or operator `||`.
@@ -75,6 +79,10 @@ pub enum BinOp {
     BitXor,
     /// Bitwise or operator `a | b`.
     BitOr,
+    /// Range `a..b`.
+    Range,
+    /// Inclusive range `a..=b`.
+    RangeInclusive,
     /// Assign operator `a = b`.
     Assign,
     /// Add assign `a += b`.
@@ -104,16 +112,18 @@ impl BinOp {
     pub(super) fn precedence(self) -> usize {
         // NB: Rules from: https://doc.rust-lang.org/reference/expressions.html#expression-precedence
         match self {
-            Self::Is | Self::IsNot => 11,
-            Self::Mul | Self::Div | Self::Rem => 10,
-            Self::Add | Self::Sub => 9,
-            Self::Shl | Self::Shr => 8,
-            Self::BitAnd => 7,
-            Self::BitXor => 6,
-            Self::BitOr => 5,
-            Self::Eq | Self::Neq | Self::Lt | Self::Gt | Self::Lte | Self::Gte => 4,
-            Self::And => 3,
-            Self::Or => 2,
+            Self::As => 13,
+            Self::Is | Self::IsNot => 12,
+            Self::Mul | Self::Div | Self::Rem => 11,
+            Self::Add | Self::Sub => 10,
+            Self::Shl | Self::Shr => 9,
+            Self::BitAnd => 8,
+            Self::BitXor => 7,
+            Self::BitOr => 6,
+            Self::Eq | Self::Neq | Self::Lt | Self::Gt | Self::Lte | Self::Gte => 5,
+            Self::And => 4,
+            Self::Or => 3,
+            Self::Range | Self::RangeInclusive => 2,
             // assign operators
             _ => 1,
         }
@@ -155,6 +165,9 @@ impl BinOp {
 
                 Self::Is
             }
+            ast::Kind::As => Self::As,
+            ast::Kind::DotDot => Self::Range,
+            ast::Kind::DotDotEq => Self::RangeInclusive,
             ast::Kind::Eq => Self::Assign,
             ast::Kind::AmpAmp => Self::And,
             ast::Kind::PipePipe => Self::Or,
@@ -204,6 +217,9 @@ impl fmt::Display for BinOp {
             Self::Lte => write!(f, "<="),
             Self::Is => write!(f, "is"),
             Self::IsNot => write!(f, "is not"),
+            Self::As => write!(f, "as"),
+            Self::Range => write!(f, ".."),
+            Self::RangeInclusive => write!(f, "..="),
             Self::Assign => write!(f, "="),
             Self::And => write!(f, "&&"),
             Self::Or => write!(f, "||"),
@@ -234,3 +250,54 @@ impl Peek for BinOp {
         }
     }
 }
+
+/// Parse implementation for a binary operator.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::BinOp>("+=").unwrap();
+/// parse_all::<ast::BinOp>("is not").unwrap();
+/// ```
+impl Parse for BinOp {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        let token = parser.token_peek_eof()?;
+
+        let op = match parser.token_peek_pair()?.and_then(Self::from_token) {
+            Some((op, _)) => op,
+            None => {
+                return Err(ParseError::ExpectedOperator {
+                    span: token.span,
+                    actual: token.kind,
+                })
+            }
+        };
+
+        for _ in 0..op.advance() {
+            parser.token_next()?;
+        }
+
+        Ok(op)
+    }
+}
+
+/// Parse implementation for a binary expression.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::ExprBinary>("a + b").unwrap();
+/// parse_all::<ast::ExprBinary>("a = b * 2").unwrap();
+/// ```
+impl Parse for ExprBinary {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        ast::Expr::parse_variant(parser, "a binary expression", |expr| match expr {
+            ast::Expr::ExprBinary(expr) => Ok(expr),
+            expr => Err(expr),
+        })
+    }
+}
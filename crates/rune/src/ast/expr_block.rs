@@ -37,6 +37,33 @@ impl ExprBlock {
         }
     }
 
+    /// Get the first expression in the block which unconditionally diverges,
+    /// making everything following it unreachable.
+    pub fn diverging_expr(&self) -> Option<&ast::Expr> {
+        self.exprs
+            .iter()
+            .map(|(expr, _)| expr)
+            .chain(self.trailing_expr.as_deref())
+            .find(|expr| expr.diverges())
+    }
+
+    /// Test if the block always evaluates to `()`, because it either doesn't
+    /// have a trailing expression or its trailing expression is `()`.
+    pub fn produces_unit(&self) -> bool {
+        match &self.trailing_expr {
+            Some(trailing) => trailing.produces_unit(),
+            None => true,
+        }
+    }
+
+    /// Test if the block definitely evaluates to a value other than `()`.
+    pub fn produces_value(&self) -> bool {
+        match &self.trailing_expr {
+            Some(trailing) => trailing.produces_value(),
+            None => false,
+        }
+    }
+
     /// ExprBlock is constant if a trailing expression exists and is all literal.
     pub fn is_const(&self) -> bool {
         match &self.trailing_expr {
@@ -1,56 +1,18 @@
 use crate::ast;
-use crate::ast::{Kind, Token};
 use crate::error::ParseError;
 use crate::parser::Parser;
-use crate::traits::{Parse, Peek};
+use crate::traits::Parse;
 use runestick::Span;
 
-/// Things that we can break on.
-#[derive(Debug, Clone)]
-pub enum ExprBreakValue {
-    /// Breaking a value out of a loop.
-    Expr(Box<ast::Expr>),
-    /// Break and jump to the given label.
-    Label(ast::Label),
-}
-
-impl ExprBreakValue {
-    /// Access the span of the expression.
-    pub fn span(&self) -> Span {
-        match self {
-            Self::Expr(expr) => expr.span(),
-            Self::Label(label) => label.span(),
-        }
-    }
-}
-
-impl Parse for ExprBreakValue {
-    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
-        let token = parser.token_peek_eof()?;
-
-        Ok(match token.kind {
-            ast::Kind::Label => Self::Label(parser.parse()?),
-            _ => Self::Expr(Box::new(parser.parse()?)),
-        })
-    }
-}
-
-impl Peek for ExprBreakValue {
-    fn peek(t1: Option<Token>, t2: Option<Token>) -> bool {
-        match t1.map(|t| t.kind) {
-            Some(Kind::Label) => true,
-            _ => ast::Expr::peek(t1, t2),
-        }
-    }
-}
-
-/// A return statement `break [expr]`.
+/// A break statement `break ['label] [expr]`.
 #[derive(Debug, Clone)]
 pub struct ExprBreak {
-    /// The return token.
+    /// The break token.
     pub break_: ast::Break,
+    /// An optional label of the loop to break out of.
+    pub label: Option<ast::Label>,
     /// An optional expression to break with.
-    pub expr: Option<ExprBreakValue>,
+    pub expr: Option<Box<ast::Expr>>,
 }
 
 impl ExprBreak {
@@ -58,6 +20,8 @@ impl ExprBreak {
     pub fn span(&self) -> Span {
         if let Some(expr) = &self.expr {
             self.break_.span().join(expr.span())
+        } else if let Some(label) = &self.label {
+            self.break_.span().join(label.span())
         } else {
             self.break_.span()
         }
@@ -66,9 +30,39 @@ impl ExprBreak {
 
 impl Parse for ExprBreak {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        let break_ = parser.parse()?;
+        let label = parse_loop_label(parser)?;
+
+        let expr = if parser.peek::<ast::Expr>()? {
+            Some(Box::new(parser.parse()?))
+        } else {
+            None
+        };
+
         Ok(Self {
-            break_: parser.parse()?,
-            expr: parser.parse()?,
+            break_,
+            label,
+            expr,
         })
     }
 }
+
+/// Parse the optional label of a loop which is being targeted by a `break` or
+/// a `continue`.
+///
+/// NB: a label followed by a colon is the start of a labeled loop
+/// expression, like in `break 'outer: loop { .. }`.
+pub(super) fn parse_loop_label(parser: &mut Parser<'_>) -> Result<Option<ast::Label>, ParseError> {
+    let is_label = match parser.token_peek_pair()? {
+        Some((t1, t2)) => {
+            t1.kind == ast::Kind::Label && !matches!(t2.map(|t| t.kind), Some(ast::Kind::Colon))
+        }
+        None => false,
+    };
+
+    Ok(if is_label {
+        Some(parser.parse()?)
+    } else {
+        None
+    })
+}
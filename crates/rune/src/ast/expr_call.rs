@@ -1,4 +1,7 @@
 use crate::ast;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::traits::Parse;
 use runestick::Span;
 
 /// A function call `<expr>(<args>)`.
@@ -16,3 +19,22 @@ impl ExprCall {
         self.expr.span().join(self.args.span())
     }
 }
+
+/// Parse implementation for a function call.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::ExprCall>("foo()").unwrap();
+/// parse_all::<ast::ExprCall>("foo::bar(1, 2)").unwrap();
+/// ```
+impl Parse for ExprCall {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        ast::Expr::parse_variant(parser, "a function call", |expr| match expr {
+            ast::Expr::ExprCall(expr) => Ok(expr),
+            expr => Err(expr),
+        })
+    }
+}
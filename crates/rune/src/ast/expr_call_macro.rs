@@ -2,6 +2,7 @@ use crate::ast;
 use crate::error::ParseError;
 use crate::parser::Parser;
 use crate::token_stream::TokenStream;
+use crate::traits::Parse;
 use runestick::Span;
 
 /// A function call `<expr>!(<args>)`.
@@ -86,3 +87,22 @@ impl ExprCallMacro {
         self.path.span().join(self.close.span)
     }
 }
+
+/// Parse implementation for a macro call.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::ExprCallMacro>("foo!(1 + 2)").unwrap();
+/// parse_all::<ast::ExprCallMacro>("foo::bar![a, b]").unwrap();
+/// ```
+impl Parse for ExprCallMacro {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        ast::Expr::parse_variant(parser, "a macro call", |expr| match expr {
+            ast::Expr::ExprCallMacro(expr) => Ok(expr),
+            expr => Err(expr),
+        })
+    }
+}
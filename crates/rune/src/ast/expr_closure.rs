@@ -4,12 +4,15 @@ use crate::parser::Parser;
 use crate::traits::Parse;
 use runestick::Span;
 
+/// The arguments of a closure.
 #[derive(Debug, Clone)]
 pub enum ExprClosureArgs {
+    /// A closure without arguments `||`.
     Empty {
         /// The `||` token.
         token: ast::Or,
     },
+    /// A closure with a list of arguments `|<args>|`.
     List {
         /// The opening pipe for the argument group.
         open: ast::Pipe,
@@ -92,33 +95,46 @@ impl Parse for ExprClosure {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
         let async_ = parser.parse()?;
 
-        let args = if let Some(token) = parser.parse::<Option<ast::Or>>()? {
-            ExprClosureArgs::Empty { token }
-        } else {
-            let open = parser.parse()?;
-            let mut args = Vec::new();
+        Ok(Self {
+            async_,
+            args: parser.parse()?,
+            body: Box::new(parser.parse()?),
+        })
+    }
+}
 
-            while !parser.peek::<ast::Pipe>()? {
-                let arg = parser.parse()?;
+/// Parse implementation for the arguments of a closure.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::ExprClosureArgs>("||").unwrap();
+/// parse_all::<ast::ExprClosureArgs>("|a, b|").unwrap();
+/// ```
+impl Parse for ExprClosureArgs {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        if let Some(token) = parser.parse::<Option<ast::Or>>()? {
+            return Ok(Self::Empty { token });
+        }
 
-                let comma = parser.parse::<Option<ast::Comma>>()?;
-                let is_end = comma.is_none();
-                args.push((arg, comma));
+        let open = parser.parse()?;
+        let mut args = Vec::new();
 
-                if is_end {
-                    break;
-                }
-            }
+        while !parser.peek::<ast::Pipe>()? {
+            let arg = parser.parse()?;
 
-            let close = parser.parse()?;
+            let comma = parser.parse::<Option<ast::Comma>>()?;
+            let is_end = comma.is_none();
+            args.push((arg, comma));
 
-            ExprClosureArgs::List { open, args, close }
-        };
+            if is_end {
+                break;
+            }
+        }
 
-        Ok(Self {
-            async_,
-            args,
-            body: Box::new(parser.parse()?),
-        })
+        let close = parser.parse()?;
+        Ok(Self::List { open, args, close })
     }
 }
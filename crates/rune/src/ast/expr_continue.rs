@@ -0,0 +1,34 @@
+use crate::ast;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::traits::Parse;
+use runestick::Span;
+
+/// A continue statement `continue ['label]`.
+#[derive(Debug, Clone)]
+pub struct ExprContinue {
+    /// The continue token.
+    pub continue_: ast::Continue,
+    /// An optional label of the loop to continue.
+    pub label: Option<ast::Label>,
+}
+
+impl ExprContinue {
+    /// Access the span of the expression.
+    pub fn span(&self) -> Span {
+        if let Some(label) = &self.label {
+            self.continue_.span().join(label.span())
+        } else {
+            self.continue_.span()
+        }
+    }
+}
+
+impl Parse for ExprContinue {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        Ok(Self {
+            continue_: parser.parse()?,
+            label: ast::expr_break::parse_loop_label(parser)?,
+        })
+    }
+}
@@ -1,4 +1,7 @@
 use crate::ast;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::traits::Parse;
 use runestick::Span;
 
 /// The field being accessed.
@@ -20,6 +23,33 @@ impl ExprField {
     }
 }
 
+/// Parse implementation for the field being accessed.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::ExprField>("foo").unwrap();
+/// parse_all::<ast::ExprField>("1").unwrap();
+/// ```
+impl Parse for ExprField {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        let token = parser.token_peek_eof()?;
+
+        Ok(match token.kind {
+            ast::Kind::Ident => Self::Ident(parser.parse()?),
+            ast::Kind::LitNumber { .. } => Self::LitNumber(parser.parse()?),
+            _ => {
+                return Err(ParseError::ExpectedSyntax {
+                    span: token.span,
+                    expected: "a field name or number",
+                })
+            }
+        })
+    }
+}
+
 /// A field access `<expr>.<field>`.
 #[derive(Debug, Clone)]
 pub struct ExprFieldAccess {
@@ -37,3 +67,22 @@ impl ExprFieldAccess {
         self.expr.span().join(self.expr_field.span())
     }
 }
+
+/// Parse implementation for a field access.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::ExprFieldAccess>("foo.bar").unwrap();
+/// parse_all::<ast::ExprFieldAccess>("foo.0").unwrap();
+/// ```
+impl Parse for ExprFieldAccess {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        ast::Expr::parse_variant(parser, "a field access", |expr| match expr {
+            ast::Expr::ExprFieldAccess(expr) => Ok(expr),
+            expr => Err(expr),
+        })
+    }
+}
@@ -35,6 +35,19 @@ impl ExprIf {
     pub fn produces_nothing(&self) -> bool {
         self.expr_else.is_none()
     }
+
+    /// Test if all branches of the if expression evaluate to `()`.
+    pub fn produces_unit(&self) -> bool {
+        self.block.produces_unit()
+            && self
+                .expr_else_ifs
+                .iter()
+                .all(|expr_else_if| expr_else_if.block.produces_unit())
+            && self
+                .expr_else
+                .as_ref()
+                .map_or(true, |expr_else| expr_else.block.produces_unit())
+    }
 }
 
 /// Parse an if statement.
@@ -1,4 +1,7 @@
 use crate::ast::{CloseBracket, Expr, OpenBracket};
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::traits::Parse;
 use runestick::Span;
 
 /// An index get operation `<target>[<index>]`.
@@ -20,3 +23,22 @@ impl ExprIndexGet {
         self.target.span().join(self.close.span())
     }
 }
+
+/// Parse implementation for an index expression.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::ExprIndexGet>("foo[0]").unwrap();
+/// parse_all::<ast::ExprIndexGet>("foo[\"bar\"][1]").unwrap();
+/// ```
+impl Parse for ExprIndexGet {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        Expr::parse_variant(parser, "an index expression", |expr| match expr {
+            Expr::ExprIndexGet(expr) => Ok(expr),
+            expr => Err(expr),
+        })
+    }
+}
@@ -1,4 +1,7 @@
 use crate::ast::{CloseBracket, Eq, Expr, OpenBracket};
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::traits::Parse;
 use runestick::Span;
 
 /// An index set operation `<target>[<index>] = <value>`.
@@ -24,3 +27,21 @@ impl ExprIndexSet {
         self.target.span().join(self.value.span())
     }
 }
+
+/// Parse implementation for an index assignment.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::ExprIndexSet>("foo[0] = 1").unwrap();
+/// ```
+impl Parse for ExprIndexSet {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        Expr::parse_variant(parser, "an index assignment", |expr| match expr {
+            Expr::ExprIndexSet(expr) => Ok(expr),
+            expr => Err(expr),
+        })
+    }
+}
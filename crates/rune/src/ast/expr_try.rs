@@ -1,4 +1,7 @@
 use crate::ast;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::traits::Parse;
 use runestick::Span;
 
 /// A try expression `<expr>?`.
@@ -16,3 +19,21 @@ impl ExprTry {
         self.expr.span().join(self.try_.span())
     }
 }
+
+/// Parse implementation for a try expression.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::ExprTry>("foo()?").unwrap();
+/// ```
+impl Parse for ExprTry {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        ast::Expr::parse_variant(parser, "a try expression", |expr| match expr {
+            ast::Expr::ExprTry(expr) => Ok(expr),
+            expr => Err(expr),
+        })
+    }
+}
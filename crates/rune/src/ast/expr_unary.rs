@@ -1,5 +1,5 @@
 use crate::ast;
-use crate::ast::expr::{EagerBrace, ExprChain};
+use crate::ast::expr::EagerBrace;
 use crate::error::ParseError;
 use crate::parser::Parser;
 use crate::traits::Parse;
@@ -22,6 +22,22 @@ impl ExprUnary {
     pub fn span(&self) -> Span {
         self.token.span.join(self.expr.span())
     }
+
+    /// Parse a unary expression, where `eager_brace` decides if its operand
+    /// can be an object literal like `Foo {}`.
+    pub(super) fn parse_with_eager_brace(
+        parser: &mut Parser<'_>,
+        eager_brace: EagerBrace,
+    ) -> Result<Self, ParseError> {
+        let token = parser.token_next()?;
+        let op = UnaryOp::from_token(token)?;
+
+        Ok(Self {
+            op,
+            token,
+            expr: Box::new(ast::Expr::parse_primary(parser, eager_brace)?),
+        })
+    }
 }
 
 /// Parse a unary statement.
@@ -34,21 +50,11 @@ impl ExprUnary {
 /// parse_all::<ast::ExprUnary>("!0").unwrap();
 /// parse_all::<ast::ExprUnary>("*foo").unwrap();
 /// parse_all::<ast::ExprUnary>("&foo").unwrap();
+/// parse_all::<ast::ExprUnary>("-foo").unwrap();
 /// ```
 impl Parse for ExprUnary {
     fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
-        let token = parser.token_next()?;
-        let op = UnaryOp::from_token(token)?;
-
-        Ok(Self {
-            op,
-            token,
-            expr: Box::new(ast::Expr::parse_primary(
-                parser,
-                EagerBrace(true),
-                ExprChain(true),
-            )?),
-        })
+        Self::parse_with_eager_brace(parser, EagerBrace(true))
     }
 }
 
@@ -61,6 +67,8 @@ pub enum UnaryOp {
     BorrowRef,
     /// Dereference `*<thing>`.
     Deref,
+    /// Negation `-<thing>`.
+    Neg,
 }
 
 impl UnaryOp {
@@ -70,6 +78,7 @@ impl UnaryOp {
             ast::Kind::Bang => Self::Not,
             ast::Kind::Amp => Self::BorrowRef,
             ast::Kind::Star => Self::Deref,
+            ast::Kind::Dash => Self::Neg,
             actual => {
                 return Err(ParseError::ExpectedUnaryOperator {
                     span: token.span,
@@ -80,12 +89,30 @@ impl UnaryOp {
     }
 }
 
+/// Parse implementation for a unary operator.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::UnaryOp>("!").unwrap();
+/// parse_all::<ast::UnaryOp>("&").unwrap();
+/// parse_all::<ast::UnaryOp>("-").unwrap();
+/// ```
+impl Parse for UnaryOp {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        Self::from_token(parser.token_next()?)
+    }
+}
+
 impl fmt::Display for UnaryOp {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Not => write!(fmt, "!")?,
             Self::BorrowRef => write!(fmt, "&")?,
             Self::Deref => write!(fmt, "*")?,
+            Self::Neg => write!(fmt, "-")?,
         }
 
         Ok(())
@@ -13,6 +13,8 @@ pub enum FnArg {
     Ignore(ast::Underscore),
     /// Binding the argument to an ident.
     Ident(ast::Ident),
+    /// Destructuring the argument with a pattern, like `(a, b)`.
+    Pat(ast::Pat),
 }
 
 impl FnArg {
@@ -22,10 +24,25 @@ impl FnArg {
             Self::Self_(s) => s.span(),
             Self::Ignore(ignore) => ignore.span(),
             Self::Ident(ident) => ident.span(),
+            Self::Pat(pat) => pat.span(),
         }
     }
 }
 
+/// Parse a single function argument.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::FnArg>("self").unwrap();
+/// parse_all::<ast::FnArg>("_").unwrap();
+/// parse_all::<ast::FnArg>("a").unwrap();
+/// parse_all::<ast::FnArg>("(a, b)").unwrap();
+/// parse_all::<ast::FnArg>("[a, ..]").unwrap();
+/// parse_all::<ast::FnArg>("#{a, b}").unwrap();
+/// ```
 impl Parse for FnArg {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
         let token = parser.token_peek_eof()?;
@@ -34,6 +51,11 @@ impl Parse for FnArg {
             ast::Kind::Self_ => Self::Self_(parser.parse()?),
             ast::Kind::Underscore => Self::Ignore(parser.parse()?),
             ast::Kind::Ident => Self::Ident(parser.parse()?),
+            // NB: alternatives aren't supported, since `|` would be ambiguous
+            // with the end of the arguments of a closure.
+            ast::Kind::Open(ast::Delimiter::Parenthesis)
+            | ast::Kind::Open(ast::Delimiter::Bracket)
+            | ast::Kind::Hash => Self::Pat(ast::Pat::parse_single(parser)?),
             _ => return Err(ParseError::ExpectedFunctionArgument { span: token.span }),
         })
     }
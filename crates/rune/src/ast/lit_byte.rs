@@ -1,7 +1,7 @@
 use crate::ast;
 use crate::error::ParseError;
 use crate::parser::Parser;
-use crate::traits::{Parse, Resolve};
+use crate::traits::{Parse, Resolve, ResolveOwned};
 use runestick::{Source, Span};
 
 /// A byte literal.
@@ -29,7 +29,8 @@ impl LitByte {
 /// parse_all::<ast::LitByte>("b'\\0'").unwrap();
 /// parse_all::<ast::LitByte>("b'\\n'").unwrap();
 /// parse_all::<ast::LitByte>("b'\\r'").unwrap();
-/// parse_all::<ast::LitByte>("b'\\\\''").unwrap();
+/// parse_all::<ast::LitByte>("b'\\''").unwrap();
+/// parse_all::<ast::LitByte>("b'\\\\'").unwrap();
 /// ```
 impl Parse for LitByte {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
@@ -85,3 +86,11 @@ impl<'a> Resolve<'a> for LitByte {
         Ok(c)
     }
 }
+
+impl ResolveOwned for LitByte {
+    type Owned = u8;
+
+    fn resolve_owned(&self, source: &Source) -> Result<u8, ParseError> {
+        self.resolve(source)
+    }
+}
@@ -1,7 +1,7 @@
 use crate::ast;
 use crate::error::ParseError;
 use crate::parser::Parser;
-use crate::traits::{Parse, Resolve};
+use crate::traits::{Parse, Resolve, ResolveOwned};
 use runestick::{Source, Span};
 use std::borrow::Cow;
 
@@ -58,6 +58,14 @@ impl<'a> Resolve<'a> for LitByteStr {
     }
 }
 
+impl ResolveOwned for LitByteStr {
+    type Owned = Vec<u8>;
+
+    fn resolve_owned(&self, source: &Source) -> Result<Vec<u8>, ParseError> {
+        Ok(self.resolve(source)?.into_owned())
+    }
+}
+
 /// Parse a string literal.
 ///
 /// # Examples
@@ -1,7 +1,7 @@
 use crate::ast;
 use crate::error::ParseError;
 use crate::parser::Parser;
-use crate::traits::{Parse, Resolve};
+use crate::traits::{Parse, Resolve, ResolveOwned};
 use runestick::{Source, Span};
 
 /// A character literal.
@@ -84,3 +84,11 @@ impl<'a> Resolve<'a> for LitChar {
         Ok(c)
     }
 }
+
+impl ResolveOwned for LitChar {
+    type Owned = char;
+
+    fn resolve_owned(&self, source: &Source) -> Result<char, ParseError> {
+        self.resolve(source)
+    }
+}
@@ -0,0 +1,102 @@
+use crate::ast;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use runestick::Span;
+
+/// A hash map literal `map!{<key>: <value>, ...}`.
+///
+/// This is a built-in macro which is expanded by the compiler, so it can't be
+/// parsed on its own. See [LitMap::from_macro].
+#[derive(Debug, Clone)]
+pub struct LitMap {
+    /// The path of the macro call.
+    pub path: ast::Path,
+    /// The bang operator `!`.
+    pub bang: ast::Bang,
+    /// The open delimiter.
+    pub open: ast::Token,
+    /// Entries in the map.
+    pub entries: Vec<LitMapEntry>,
+    /// The close delimiter.
+    pub close: ast::Token,
+    /// If the entire map is constant.
+    is_const: bool,
+}
+
+/// A single entry `<key>: <value>` in a hash map literal.
+#[derive(Debug, Clone)]
+pub struct LitMapEntry {
+    /// The key of the entry.
+    pub key: ast::Expr,
+    /// The colon separating the key from the value.
+    pub colon: ast::Colon,
+    /// The value of the entry.
+    pub value: ast::Expr,
+}
+
+impl LitMap {
+    /// Access the span of the expression.
+    pub fn span(&self) -> Span {
+        self.path.span().join(self.close.span)
+    }
+
+    /// Test if the entire expression is constant.
+    pub fn is_const(&self) -> bool {
+        self.is_const
+    }
+
+    /// Construct a hash map literal out of the given macro call, by parsing
+    /// its entries.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rune::{parse_all, ast};
+    ///
+    /// let call = parse_all::<ast::Expr>("map!{\"a\": 1, \"b\": 2,}").unwrap();
+    ///
+    /// let call = match call {
+    ///     ast::Expr::ExprCallMacro(call) => call,
+    ///     _ => panic!("expected macro call"),
+    /// };
+    ///
+    /// let lit_map = ast::LitMap::from_macro(call).unwrap();
+    /// assert_eq!(lit_map.entries.len(), 2);
+    /// assert!(lit_map.is_const());
+    /// ```
+    pub fn from_macro(expr_call_macro: ast::ExprCallMacro) -> Result<Self, ParseError> {
+        let mut parser = Parser::from_token_stream(&expr_call_macro.stream);
+
+        let mut entries = Vec::new();
+        let mut is_const = true;
+
+        while parser.token_peek()?.is_some() {
+            let key = parser.parse::<ast::Expr>()?;
+            let colon = parser.parse()?;
+            let value = parser.parse::<ast::Expr>()?;
+
+            if !key.is_const() || !value.is_const() {
+                is_const = false;
+            }
+
+            entries.push(LitMapEntry { key, colon, value });
+
+            if parser.peek::<ast::Comma>()? {
+                parser.parse::<ast::Comma>()?;
+            } else {
+                break;
+            }
+        }
+
+        parser.parse_eof()?;
+
+        Ok(Self {
+            path: expr_call_macro.path,
+            bang: expr_call_macro.bang,
+            open: expr_call_macro.open,
+            entries,
+            close: expr_call_macro.close,
+            is_const,
+        })
+    }
+}
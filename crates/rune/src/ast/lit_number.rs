@@ -1,7 +1,7 @@
 use crate::ast;
 use crate::error::ParseError;
 use crate::parser::Parser;
-use crate::traits::{Parse, Resolve};
+use crate::traits::{Parse, Resolve, ResolveOwned};
 use runestick::{Source, Span};
 
 /// A resolved number literal.
@@ -15,8 +15,8 @@ pub enum Number {
 /// A number literal.
 #[derive(Debug, Clone)]
 pub struct LitNumber {
-    /// If the number is negative.
-    is_negative: bool,
+    /// The minus sign of a negative number.
+    negative: Option<ast::Token>,
     /// Indicates if the number is fractional.
     is_fractional: bool,
     /// The kind of the number literal.
@@ -28,7 +28,23 @@ pub struct LitNumber {
 impl LitNumber {
     /// Access the span of the expression.
     pub fn span(&self) -> Span {
-        self.token.span
+        match &self.negative {
+            Some(negative) => negative.span.join(self.token.span),
+            None => self.token.span,
+        }
+    }
+
+    /// Negate the number literal with the given minus sign, unless it's
+    /// already negative.
+    pub(crate) fn negate(self, negative: ast::Token) -> Result<Self, Self> {
+        if self.negative.is_some() {
+            return Err(self);
+        }
+
+        Ok(Self {
+            negative: Some(negative),
+            ..self
+        })
     }
 }
 
@@ -43,19 +59,25 @@ impl LitNumber {
 /// parse_all::<ast::LitNumber>("42.42").unwrap();
 /// parse_all::<ast::LitNumber>("0.42").unwrap();
 /// parse_all::<ast::LitNumber>("0.42e10").unwrap();
+/// parse_all::<ast::LitNumber>("-42").unwrap();
 /// ```
 impl Parse for LitNumber {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        let negative = if parser.peek::<ast::Dash>()? {
+            Some(parser.token_next()?)
+        } else {
+            None
+        };
+
         let token = parser.token_next()?;
 
         Ok(match token.kind {
             ast::Kind::LitNumber {
-                is_negative,
                 is_fractional,
                 number,
                 ..
             } => LitNumber {
-                is_negative,
+                negative,
                 is_fractional,
                 number,
                 token,
@@ -84,14 +106,15 @@ impl<'a> Resolve<'a> for LitNumber {
             .source(span)
             .ok_or_else(|| ParseError::BadSlice { span })?;
 
-        let string = if self.is_negative {
-            &string[1..]
-        } else {
-            string
-        };
+        let span = self.span();
 
         if self.is_fractional {
             let number = f64::from_str(string).map_err(err_span(span))?;
+            let number = if self.negative.is_some() {
+                -number
+            } else {
+                number
+            };
             return Ok(Number::Float(number));
         }
 
@@ -104,7 +127,7 @@ impl<'a> Resolve<'a> for LitNumber {
 
         let number = num::BigUint::from_str_radix(&string[s..], radix).map_err(err_span(span))?;
 
-        let number = if self.is_negative {
+        let number = if self.negative.is_some() {
             num::BigInt::from(number).neg().to_i64()
         } else {
             number.to_i64()
@@ -122,3 +145,11 @@ impl<'a> Resolve<'a> for LitNumber {
         }
     }
 }
+
+impl ResolveOwned for LitNumber {
+    type Owned = Number;
+
+    fn resolve_owned(&self, source: &Source) -> Result<Number, ParseError> {
+        self.resolve(source)
+    }
+}
@@ -1,7 +1,7 @@
 use crate::ast;
 use crate::error::ParseError;
 use crate::parser::Parser;
-use crate::traits::{Parse, Resolve};
+use crate::traits::{Parse, Resolve, ResolveOwned};
 use runestick::{Source, Span};
 use std::borrow::Cow;
 
@@ -39,7 +39,7 @@ impl Parse for LitObjectIdent {
 #[derive(Debug, Clone)]
 pub struct LitObjectFieldAssign {
     /// The key of the field.
-    pub key: LitObjectKey,
+    pub key: LitObjectFieldKey,
     /// The assigned expression of the field.
     pub assign: Option<(ast::Colon, ast::Expr)>,
 }
@@ -56,6 +56,10 @@ impl LitObjectFieldAssign {
 
     /// Check if assignment is constant or not.
     pub fn is_const(&self) -> bool {
+        if let LitObjectFieldKey::Computed(..) = &self.key {
+            return false;
+        }
+
         match &self.assign {
             Some((_, expr)) => expr.is_const(),
             None => false,
@@ -71,14 +75,20 @@ impl LitObjectFieldAssign {
 /// use rune::{parse_all, ast};
 ///
 /// parse_all::<ast::LitObjectFieldAssign>("\"foo\": 42").unwrap();
-/// parse_all::<ast::LitObjectFieldAssign>("\"foo\": 42").unwrap();
-/// parse_all::<ast::LitObjectFieldAssign>("\"foo\": 42").unwrap();
+/// parse_all::<ast::LitObjectFieldAssign>("foo").unwrap();
+/// parse_all::<ast::LitObjectFieldAssign>("[foo()]: 42").unwrap();
+/// assert!(parse_all::<ast::LitObjectFieldAssign>("[foo()]").is_err());
 /// ```
 impl Parse for LitObjectFieldAssign {
     fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
-        let key = parser.parse()?;
+        let key = parser.parse::<LitObjectFieldKey>()?;
 
-        let assign = if parser.peek::<ast::Colon>()? {
+        // NB: computed keys always need a value.
+        let assign = if let LitObjectFieldKey::Computed(..) = &key {
+            let colon = parser.parse()?;
+            let expr = parser.parse::<ast::Expr>()?;
+            Some((colon, expr))
+        } else if parser.peek::<ast::Colon>()? {
             let colon = parser.parse()?;
             let expr = parser.parse::<ast::Expr>()?;
             Some((colon, expr))
@@ -90,6 +100,74 @@ impl Parse for LitObjectFieldAssign {
     }
 }
 
+/// The key of a field in an object literal.
+#[derive(Debug, Clone)]
+pub enum LitObjectFieldKey {
+    /// A key which is known when the object is compiled.
+    Key(LitObjectKey),
+    /// A computed key `[<expr>]`, which is evaluated when the object is
+    /// constructed.
+    Computed(LitObjectComputedKey),
+}
+
+impl LitObjectFieldKey {
+    /// Get the span of the field key.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Key(key) => key.span(),
+            Self::Computed(key) => key.span(),
+        }
+    }
+}
+
+/// Parse the key of a field in an object literal.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::LitObjectFieldKey>("foo").unwrap();
+/// parse_all::<ast::LitObjectFieldKey>("[`key-{n}`]").unwrap();
+/// ```
+impl Parse for LitObjectFieldKey {
+    fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
+        if parser.peek::<ast::OpenBracket>()? {
+            return Ok(Self::Computed(parser.parse()?));
+        }
+
+        Ok(Self::Key(parser.parse()?))
+    }
+}
+
+/// A computed key `[<expr>]` in an object literal.
+#[derive(Debug, Clone)]
+pub struct LitObjectComputedKey {
+    /// The open bracket.
+    pub open: ast::OpenBracket,
+    /// The expression computing the key.
+    pub expr: Box<ast::Expr>,
+    /// The close bracket.
+    pub close: ast::CloseBracket,
+}
+
+impl LitObjectComputedKey {
+    /// Get the span of the computed key.
+    pub fn span(&self) -> Span {
+        self.open.span().join(self.close.span())
+    }
+}
+
+impl Parse for LitObjectComputedKey {
+    fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
+        Ok(Self {
+            open: parser.parse()?,
+            expr: Box::new(parser.parse()?),
+            close: parser.parse()?,
+        })
+    }
+}
+
 /// Possible literal object keys.
 #[derive(Debug, Clone)]
 pub enum LitObjectKey {
@@ -147,6 +225,14 @@ impl<'a> Resolve<'a> for LitObjectKey {
     }
 }
 
+impl ResolveOwned for LitObjectKey {
+    type Owned = String;
+
+    fn resolve_owned(&self, source: &Source) -> Result<String, ParseError> {
+        Ok(self.resolve(source)?.into_owned())
+    }
+}
+
 /// A number literal.
 #[derive(Debug, Clone)]
 pub struct LitObject {
@@ -174,6 +260,13 @@ impl LitObject {
         self.is_const
     }
 
+    /// Test if any of the keys of the object are computed.
+    pub fn has_computed_keys(&self) -> bool {
+        self.assignments
+            .iter()
+            .any(|assign| matches!(assign.key, LitObjectFieldKey::Computed(..)))
+    }
+
     /// Parse a literal object with the given path.
     pub fn parse_with_ident(
         parser: &mut Parser<'_>,
@@ -223,6 +316,7 @@ impl LitObject {
 /// parse_all::<ast::LitObject>("Foo {\"foo\": 42}").unwrap();
 /// parse_all::<ast::LitObject>("#{\"foo\": 42}").unwrap();
 /// parse_all::<ast::LitObject>("#{\"foo\": 42,}").unwrap();
+/// parse_all::<ast::LitObject>("#{foo, [bar]: 42}").unwrap();
 /// ```
 impl Parse for LitObject {
     fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
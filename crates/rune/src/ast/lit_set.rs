@@ -0,0 +1,89 @@
+use crate::ast;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use runestick::Span;
+
+/// A hash set literal `set!{<key>, ...}`.
+///
+/// This is a built-in macro which is expanded by the compiler, so it can't be
+/// parsed on its own. See [LitSet::from_macro].
+#[derive(Debug, Clone)]
+pub struct LitSet {
+    /// The path of the macro call.
+    pub path: ast::Path,
+    /// The bang operator `!`.
+    pub bang: ast::Bang,
+    /// The open delimiter.
+    pub open: ast::Token,
+    /// Items in the set.
+    pub items: Vec<ast::Expr>,
+    /// The close delimiter.
+    pub close: ast::Token,
+    /// If the entire set is constant.
+    is_const: bool,
+}
+
+impl LitSet {
+    /// Access the span of the expression.
+    pub fn span(&self) -> Span {
+        self.path.span().join(self.close.span)
+    }
+
+    /// Test if the entire expression is constant.
+    pub fn is_const(&self) -> bool {
+        self.is_const
+    }
+
+    /// Construct a hash set literal out of the given macro call, by parsing
+    /// its items.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rune::{parse_all, ast};
+    ///
+    /// let call = parse_all::<ast::Expr>("set!{1, 2, foo()}").unwrap();
+    ///
+    /// let call = match call {
+    ///     ast::Expr::ExprCallMacro(call) => call,
+    ///     _ => panic!("expected macro call"),
+    /// };
+    ///
+    /// let lit_set = ast::LitSet::from_macro(call).unwrap();
+    /// assert_eq!(lit_set.items.len(), 3);
+    /// assert!(!lit_set.is_const());
+    /// ```
+    pub fn from_macro(expr_call_macro: ast::ExprCallMacro) -> Result<Self, ParseError> {
+        let mut parser = Parser::from_token_stream(&expr_call_macro.stream);
+
+        let mut items = Vec::new();
+        let mut is_const = true;
+
+        while parser.token_peek()?.is_some() {
+            let expr = parser.parse::<ast::Expr>()?;
+
+            if !expr.is_const() {
+                is_const = false;
+            }
+
+            items.push(expr);
+
+            if parser.peek::<ast::Comma>()? {
+                parser.parse::<ast::Comma>()?;
+            } else {
+                break;
+            }
+        }
+
+        parser.parse_eof()?;
+
+        Ok(Self {
+            path: expr_call_macro.path,
+            bang: expr_call_macro.bang,
+            open: expr_call_macro.open,
+            items,
+            close: expr_call_macro.close,
+            is_const,
+        })
+    }
+}
@@ -1,7 +1,7 @@
 use crate::ast;
 use crate::error::ParseError;
 use crate::parser::Parser;
-use crate::traits::{Parse, Resolve};
+use crate::traits::{Parse, Resolve, ResolveOwned};
 use runestick::{Source, Span};
 use std::borrow::Cow;
 
@@ -61,6 +61,14 @@ impl<'a> Resolve<'a> for LitStr {
     }
 }
 
+impl ResolveOwned for LitStr {
+    type Owned = String;
+
+    fn resolve_owned(&self, source: &Source) -> Result<String, ParseError> {
+        Ok(self.resolve(source)?.into_owned())
+    }
+}
+
 /// Parse a string literal.
 ///
 /// # Examples
@@ -1,7 +1,7 @@
 use crate::ast;
 use crate::error::ParseError;
 use crate::parser::Parser;
-use crate::traits::{Parse, Resolve};
+use crate::traits::{Parse, Resolve, ResolveOwned};
 use runestick::{Source, Span};
 
 /// A string literal.
@@ -79,6 +79,7 @@ impl<'a> Resolve<'a> for LitTemplate {
 
                     let mut parser = Parser::new_with_start(source, span.start);
                     let expr = ast::Expr::parse(&mut parser)?;
+                    parser.parse_eof()?;
                     components.push(TemplateComponent::Expr(Box::new(expr)));
                     has_expansions = true;
                 }
@@ -102,6 +103,14 @@ impl<'a> Resolve<'a> for LitTemplate {
     }
 }
 
+impl ResolveOwned for LitTemplate {
+    type Owned = Template;
+
+    fn resolve_owned(&self, source: &Source) -> Result<Template, ParseError> {
+        self.resolve(source)
+    }
+}
+
 /// Parse a string literal.
 ///
 /// # Examples
@@ -2,9 +2,10 @@
 
 use crate::error::ParseError;
 use crate::parser::Parser;
-use crate::traits::{Parse, Peek, Resolve};
+use crate::traits::{Parse, Peek, Resolve, ResolveOwned};
 use runestick::{Source, Span};
 
+mod attribute;
 mod condition;
 mod decl;
 mod decl_enum;
@@ -22,6 +23,7 @@ mod expr_break;
 mod expr_call;
 mod expr_call_macro;
 mod expr_closure;
+mod expr_continue;
 mod expr_else;
 mod expr_else_if;
 mod expr_field_access;
@@ -46,8 +48,10 @@ mod lit_bool;
 mod lit_byte;
 mod lit_byte_str;
 mod lit_char;
+mod lit_map;
 mod lit_number;
 mod lit_object;
+mod lit_set;
 mod lit_str;
 mod lit_template;
 mod lit_tuple;
@@ -56,13 +60,16 @@ mod lit_vec;
 mod parenthesized;
 mod pat;
 mod pat_object;
+mod pat_or;
 mod pat_path;
+mod pat_range;
 mod pat_tuple;
 mod pat_vec;
 mod path;
 mod token;
 pub(super) mod utils;
 
+pub use self::attribute::Attribute;
 pub use self::condition::Condition;
 pub use self::decl::Decl;
 pub use self::decl_enum::DeclEnum;
@@ -76,10 +83,11 @@ pub use self::expr::Expr;
 pub use self::expr_await::ExprAwait;
 pub use self::expr_binary::{BinOp, ExprBinary};
 pub use self::expr_block::ExprBlock;
-pub use self::expr_break::{ExprBreak, ExprBreakValue};
+pub use self::expr_break::ExprBreak;
 pub use self::expr_call::ExprCall;
 pub use self::expr_call_macro::ExprCallMacro;
-pub use self::expr_closure::ExprClosure;
+pub use self::expr_closure::{ExprClosure, ExprClosureArgs};
+pub use self::expr_continue::ExprContinue;
 pub use self::expr_else::ExprElse;
 pub use self::expr_else_if::ExprElseIf;
 pub use self::expr_field_access::{ExprField, ExprFieldAccess};
@@ -104,8 +112,13 @@ pub use self::lit_bool::LitBool;
 pub use self::lit_byte::LitByte;
 pub use self::lit_byte_str::LitByteStr;
 pub use self::lit_char::LitChar;
+pub use self::lit_map::{LitMap, LitMapEntry};
 pub use self::lit_number::{LitNumber, Number};
-pub use self::lit_object::{LitObject, LitObjectFieldAssign, LitObjectIdent, LitObjectKey};
+pub use self::lit_object::{
+    LitObject, LitObjectComputedKey, LitObjectFieldAssign, LitObjectFieldKey, LitObjectIdent,
+    LitObjectKey,
+};
+pub use self::lit_set::LitSet;
 pub use self::lit_str::LitStr;
 pub use self::lit_template::{LitTemplate, Template, TemplateComponent};
 pub use self::lit_tuple::LitTuple;
@@ -114,7 +127,9 @@ pub use self::lit_vec::LitVec;
 pub use self::parenthesized::Parenthesized;
 pub use self::pat::Pat;
 pub use self::pat_object::{PatObject, PatObjectItem};
+pub use self::pat_or::PatOr;
 pub use self::pat_path::PatPath;
+pub use self::pat_range::PatRange;
 pub use self::pat_tuple::PatTuple;
 pub use self::pat_vec::PatVec;
 pub use self::path::Path;
@@ -182,6 +197,7 @@ decl_tokens! {
     (Self_, "The `self` keyword.", Kind::Self_),
     (Fn, "The `fn` keyword.", Kind::Fn),
     (Is, "The `is` keyword.", Kind::Is),
+    (As, "The `as` keyword.", Kind::As),
     (Not, "The `!` operator.", Kind::Not),
     (Enum, "The `enum` keyword.", Kind::Enum),
     (Struct, "The `struct` keyword.", Kind::Struct),
@@ -204,11 +220,13 @@ decl_tokens! {
     (For, "The `for` keyword.", Kind::For),
     (In, "The `in` keyword.", Kind::In),
     (Break, "The `break` keyword.", Kind::Break),
+    (Continue, "The `continue` keyword.", Kind::Continue),
     (Yield, "The `yield` keyword.", Kind::Yield),
     (Return, "The `return` keyword.", Kind::Return),
     (Rocket, "The rocket `=>`.", Kind::Rocket),
     (Hash, "The hash `#`.", Kind::Hash),
     (DotDot, "Two dots `..`.", Kind::DotDot),
+    (DotDotEq, "Two dots and an equals `..=`.", Kind::DotDotEq),
     (Await, "The `await` keyword.", Kind::Await),
     (Async, "The `async` keyword.", Kind::Async),
     (Select, "The `select` keyword.", Kind::Select),
@@ -221,6 +239,7 @@ decl_tokens! {
     (Mul, "Multiply `*` operator.", Kind::Star),
     (Mod, "The `mod` keyword.", Kind::Mod),
     (Bang, "The `!` operator.", Kind::Bang),
+    (Dash, "The `-` operator.", Kind::Dash),
 }
 
 impl<'a> Resolve<'a> for Ident {
@@ -235,6 +254,14 @@ impl<'a> Resolve<'a> for Ident {
     }
 }
 
+impl ResolveOwned for Ident {
+    type Owned = String;
+
+    fn resolve_owned(&self, source: &Source) -> Result<String, ParseError> {
+        Ok(self.resolve(source)?.to_owned())
+    }
+}
+
 impl<'a> Resolve<'a> for Label {
     type Output = &'a str;
 
@@ -247,9 +274,17 @@ impl<'a> Resolve<'a> for Label {
     }
 }
 
+impl ResolveOwned for Label {
+    type Owned = String;
+
+    fn resolve_owned(&self, source: &Source) -> Result<String, ParseError> {
+        Ok(self.resolve(source)?.to_owned())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{ast, parse_all};
+    use crate::{ast, parse_all, ParseError};
 
     #[test]
     fn test_expr() {
@@ -285,4 +320,156 @@ mod tests {
 
         parse_all::<ast::Expr>("42 is int::int").unwrap();
     }
+
+    /// Parse the given source both directly and through a token stream, and
+    /// check that both produce the same syntax tree.
+    fn roundtrip<T>(source: &str)
+    where
+        T: crate::Parse + std::fmt::Debug,
+    {
+        let mut lexer = crate::Lexer::new(source);
+        let mut stream = crate::TokenStream::new(Vec::new(), lexer.end());
+
+        while let Some(token) = lexer.next().unwrap() {
+            stream.push(token);
+        }
+
+        let expected = parse_all::<T>(source).unwrap();
+        let actual = stream.parse::<T>().unwrap();
+        assert_eq!(format!("{:?}", actual), format!("{:?}", expected));
+    }
+
+    /// Resolve the given owned value from a source which is dropped
+    /// immediately after.
+    fn resolve_owned<T>(source: &str) -> T::Owned
+    where
+        T: crate::Parse + crate::ResolveOwned,
+    {
+        let source = runestick::Source::new("main", source);
+        let ast = parse_all::<T>(source.as_str()).unwrap();
+        ast.resolve_owned(&source).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_owned() {
+        use crate::ast::lit_number::Number;
+
+        assert_eq!(resolve_owned::<ast::Ident>("foo"), "foo");
+        // NB: labels are only lexed as such when they're followed by something.
+        assert_eq!(resolve_owned::<ast::Label>("'outer "), "outer");
+        assert_eq!(resolve_owned::<ast::LitStr>(r#""a\nb""#), "a\nb");
+        assert_eq!(resolve_owned::<ast::LitChar>(r"'\n'"), '\n');
+        assert_eq!(resolve_owned::<ast::LitChar>("'\u{e5}'"), '\u{e5}');
+        assert_eq!(resolve_owned::<ast::LitByte>(r"b'\x7f'"), 0x7f);
+        assert_eq!(resolve_owned::<ast::LitByteStr>(r#"b"a\0""#), b"a\0");
+        assert_eq!(resolve_owned::<ast::LitObjectKey>(r#""key""#), "key");
+        assert_eq!(resolve_owned::<ast::Path>("foo::bar"), ["foo", "bar"]);
+
+        assert!(matches!(
+            resolve_owned::<ast::LitNumber>("0x2a"),
+            Number::Integer(42)
+        ));
+    }
+
+    #[test]
+    fn test_token_stream_roundtrip() {
+        roundtrip::<ast::DeclFile>("fn main() { let a = 1; a + 2 }");
+        roundtrip::<ast::DeclFn>("async fn foo(a, b) { a.await? }");
+        roundtrip::<ast::Expr>("if a { b } else { `c {d}` }");
+        roundtrip::<ast::ExprAwait>("foo.bar().await");
+        roundtrip::<ast::ExprBinary>("a + b * 2");
+        roundtrip::<ast::ExprCall>("foo::bar(1, \"two\")");
+        roundtrip::<ast::ExprCallMacro>("foo!(1 + 2)");
+        roundtrip::<ast::ExprClosure>("|a, b| a + b");
+        roundtrip::<ast::ExprClosureArgs>("|a, b|");
+        roundtrip::<ast::ExprField>("0");
+        roundtrip::<ast::ExprFieldAccess>("foo.0");
+        roundtrip::<ast::ExprIndexGet>("foo[0]");
+        roundtrip::<ast::ExprIndexSet>("foo[0] = 1");
+        roundtrip::<ast::ExprTry>("foo()?");
+        roundtrip::<ast::BinOp>("is not");
+        roundtrip::<ast::UnaryOp>("!");
+        roundtrip::<ast::UnaryOp>("-");
+        roundtrip::<ast::ExprUnary>("-foo");
+        roundtrip::<ast::LitNumber>("-42");
+        roundtrip::<ast::Pat>("[a, ..]");
+        roundtrip::<ast::PatOr>("1 | 2");
+        roundtrip::<ast::PatPath>("Foo::Bar");
+        roundtrip::<ast::PatRange>("1..=2");
+    }
+
+    /// Describe how the given expression nests, following the operand of
+    /// every chained, unary, and binary expression.
+    fn chain(expr: &ast::Expr) -> String {
+        match expr {
+            ast::Expr::ExprCall(e) => format!("call({})", chain(&e.expr)),
+            ast::Expr::ExprFieldAccess(e) => format!("field({})", chain(&e.expr)),
+            ast::Expr::ExprIndexGet(e) => format!("index({})", chain(&e.target)),
+            ast::Expr::ExprAwait(e) => format!("await({})", chain(&e.expr)),
+            ast::Expr::ExprTry(e) => format!("try({})", chain(&e.expr)),
+            ast::Expr::ExprUnary(e) => format!("unary({})", chain(&e.expr)),
+            ast::Expr::ExprBinary(e) => format!("binary({})", chain(&e.lhs)),
+            ast::Expr::Path(..) => String::from("path"),
+            ast::Expr::LitNumber(..) => String::from("number"),
+            ast::Expr::LitStr(..) => String::from("str"),
+            ast::Expr::LitVec(..) => String::from("vec"),
+            ast::Expr::LitTuple(..) => String::from("tuple"),
+            _ => String::from("_"),
+        }
+    }
+
+    #[test]
+    fn test_method_chains() {
+        let test = |source: &str, expected: &str| {
+            let expr = parse_all::<ast::Expr>(source).unwrap();
+            assert_eq!(chain(&expr), expected, "{}", source);
+        };
+
+        test("[1, 2, 3].len()", "call(field(vec))");
+        test("\"x\".len()", "call(field(str))");
+        test("(1, 2).0.pow(2)", "call(field(field(tuple)))");
+        test("foo().bar().baz", "field(call(field(call(path))))");
+        test("foo()[0].bar()", "call(field(index(call(path))))");
+        test("a.0.1", "field(field(path))");
+        test("a.await?.b", "field(try(await(path)))");
+        test(
+            "a.b().await.c()?",
+            "try(call(field(await(call(field(path))))))",
+        );
+        test("a?.b?.c()", "call(field(try(field(try(path)))))");
+        test("-1.abs()", "unary(call(field(number)))");
+        test("!a.await?", "unary(try(await(path)))");
+        test("[1].len() + 1", "binary(call(field(vec)))");
+        test(
+            "client.get(url).await?.json().await?",
+            "try(await(call(field(try(await(call(field(path))))))))",
+        );
+        test("a?.await", "await(try(path))");
+    }
+
+    #[test]
+    fn test_unsupported_field_access() {
+        for source in &["a.-1", "a.(b)", "a.[0]"] {
+            let error = parse_all::<ast::Expr>(source).unwrap_err();
+            assert!(
+                matches!(error, ParseError::UnsupportedFieldAccess { .. }),
+                "{}: {:?}",
+                source,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_unary_operand_without_eager_brace() {
+        parse_all::<ast::Expr>("if !done { 1 } else { 2 }").unwrap();
+        parse_all::<ast::Expr>("if -a < 0 { 1 }").unwrap();
+        parse_all::<ast::Expr>("while !*done { 1 }").unwrap();
+    }
+
+    #[test]
+    fn test_parse_variant() {
+        let error = parse_all::<ast::ExprCall>("foo.bar").unwrap_err();
+        assert_eq!(error.to_string(), "expected a function call");
+    }
 }
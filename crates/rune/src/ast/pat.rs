@@ -28,9 +28,29 @@ pub enum Pat {
     PatTuple(ast::PatTuple),
     /// An object pattern.
     PatObject(ast::PatObject),
+    /// An inclusive range pattern.
+    PatRange(ast::PatRange),
+    /// A pattern with alternatives.
+    PatOr(ast::PatOr),
 }
 
 impl Pat {
+    /// Parse a pattern and narrow it down to one kind of pattern with the
+    /// given function, which returns the pattern back if it's of the wrong
+    /// kind.
+    pub(super) fn parse_variant<T>(
+        parser: &mut Parser<'_>,
+        expected: &'static str,
+        narrow: impl FnOnce(Self) -> Result<T, Self>,
+    ) -> Result<T, ParseError> {
+        let pat = parser.parse::<Self>()?;
+
+        narrow(pat).map_err(|pat| ParseError::ExpectedSyntax {
+            span: pat.span(),
+            expected,
+        })
+    }
+
     /// Get the span of the pattern.
     pub fn span(&self) -> Span {
         match self {
@@ -44,7 +64,48 @@ impl Pat {
             Self::PatVec(pat) => pat.span(),
             Self::PatTuple(pat) => pat.span(),
             Self::PatObject(pat) => pat.span(),
+            Self::PatRange(pat) => pat.span(),
+            Self::PatOr(pat) => pat.span(),
+        }
+    }
+
+    /// Parse a pattern without alternatives.
+    pub(super) fn parse_single(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        let token = parser.token_peek_eof()?;
+
+        let pat = match token.kind {
+            ast::Kind::Open(Delimiter::Parenthesis) => {
+                if parser.peek::<ast::LitUnit>()? {
+                    Self::PatUnit(parser.parse()?)
+                } else {
+                    Self::PatTuple(parser.parse()?)
+                }
+            }
+            ast::Kind::Open(Delimiter::Bracket) => Self::PatVec(parser.parse()?),
+            ast::Kind::Hash => Self::PatObject(parser.parse()?),
+            ast::Kind::LitByte { .. } => Self::PatByte(parser.parse()?),
+            ast::Kind::LitChar { .. } => Self::PatChar(parser.parse()?),
+            ast::Kind::LitNumber { .. } | ast::Kind::Dash => Self::PatNumber(parser.parse()?),
+            ast::Kind::LitStr { .. } => Self::PatString(parser.parse()?),
+            ast::Kind::Underscore => Self::PatIgnore(parser.parse()?),
+            ast::Kind::Ident => Self::parse_ident(parser)?,
+            _ => {
+                return Err(ParseError::ExpectedPatError {
+                    span: token.span,
+                    actual: token.kind,
+                })
+            }
+        };
+
+        if parser.peek::<ast::DotDotEq>()? {
+            return Ok(Self::PatRange(ast::PatRange {
+                start: Box::new(pat),
+                dot_dot_eq: parser.parse()?,
+                end: Box::new(Self::parse_single(parser)?),
+            }));
         }
+
+        Ok(pat)
     }
 
     /// Parse a pattern with a starting identifier.
@@ -106,34 +167,28 @@ impl Pat {
 /// parse_all::<ast::Pat>("var").unwrap();
 /// parse_all::<ast::Pat>("_").unwrap();
 /// parse_all::<ast::Pat>("Foo(n)").unwrap();
+/// parse_all::<ast::Pat>("1..=5").unwrap();
+/// parse_all::<ast::Pat>("'a'..='z'").unwrap();
+/// parse_all::<ast::Pat>("1 | 2 | 3").unwrap();
 /// ```
 impl Parse for Pat {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
-        let token = parser.token_peek_eof()?;
+        let first = Self::parse_single(parser)?;
 
-        Ok(match token.kind {
-            ast::Kind::Open(Delimiter::Parenthesis) => {
-                if parser.peek::<ast::LitUnit>()? {
-                    Self::PatUnit(parser.parse()?)
-                } else {
-                    Self::PatTuple(parser.parse()?)
-                }
-            }
-            ast::Kind::Open(Delimiter::Bracket) => Self::PatVec(parser.parse()?),
-            ast::Kind::Hash => Self::PatObject(parser.parse()?),
-            ast::Kind::LitByte { .. } => Self::PatByte(parser.parse()?),
-            ast::Kind::LitChar { .. } => Self::PatChar(parser.parse()?),
-            ast::Kind::LitNumber { .. } => Self::PatNumber(parser.parse()?),
-            ast::Kind::LitStr { .. } => Self::PatString(parser.parse()?),
-            ast::Kind::Underscore => Self::PatIgnore(parser.parse()?),
-            ast::Kind::Ident => Self::parse_ident(parser)?,
-            _ => {
-                return Err(ParseError::ExpectedPatError {
-                    span: token.span,
-                    actual: token.kind,
-                })
-            }
-        })
+        if !parser.peek::<ast::Pipe>()? {
+            return Ok(first);
+        }
+
+        let mut rest = Vec::new();
+
+        while parser.peek::<ast::Pipe>()? {
+            rest.push((parser.parse()?, Self::parse_single(parser)?));
+        }
+
+        Ok(Self::PatOr(ast::PatOr {
+            first: Box::new(first),
+            rest,
+        }))
     }
 }
 
@@ -151,6 +206,7 @@ impl Peek for Pat {
             ast::Kind::LitByte { .. } => true,
             ast::Kind::LitChar { .. } => true,
             ast::Kind::LitNumber { .. } => true,
+            ast::Kind::Dash => true,
             ast::Kind::LitStr { .. } => true,
             ast::Kind::Underscore => true,
             ast::Kind::Ident => true,
@@ -0,0 +1,48 @@
+use crate::ast;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::traits::Parse;
+use runestick::Span;
+
+/// A pattern with alternatives, like `1 | 2 | 3`.
+#[derive(Debug, Clone)]
+pub struct PatOr {
+    /// The first alternative.
+    pub first: Box<ast::Pat>,
+    /// The rest of the alternatives.
+    pub rest: Vec<(ast::Pipe, ast::Pat)>,
+}
+
+impl PatOr {
+    /// Get the span of the pattern.
+    pub fn span(&self) -> Span {
+        match self.rest.last() {
+            Some((_, pat)) => self.first.span().join(pat.span()),
+            None => self.first.span(),
+        }
+    }
+
+    /// Iterate over all alternatives in the pattern.
+    pub fn alternatives(&self) -> impl Iterator<Item = &'_ ast::Pat> + '_ {
+        std::iter::once(&*self.first).chain(self.rest.iter().map(|(_, pat)| pat))
+    }
+}
+
+/// Parse implementation for a pattern with alternatives.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::PatOr>("1 | 2").unwrap();
+/// parse_all::<ast::PatOr>("Foo | Bar(_)").unwrap();
+/// ```
+impl Parse for PatOr {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        ast::Pat::parse_variant(parser, "a pattern with alternatives", |pat| match pat {
+            ast::Pat::PatOr(pat) => Ok(pat),
+            pat => Err(pat),
+        })
+    }
+}
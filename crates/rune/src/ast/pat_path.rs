@@ -1,4 +1,7 @@
 use crate::ast;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::traits::Parse;
 use runestick::Span;
 
 /// A tuple pattern.
@@ -14,3 +17,22 @@ impl PatPath {
         self.path.span()
     }
 }
+
+/// Parse implementation for a path pattern.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::PatPath>("foo").unwrap();
+/// parse_all::<ast::PatPath>("foo::Bar").unwrap();
+/// ```
+impl Parse for PatPath {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        ast::Pat::parse_variant(parser, "a path pattern", |pat| match pat {
+            ast::Pat::PatPath(pat) => Ok(pat),
+            pat => Err(pat),
+        })
+    }
+}
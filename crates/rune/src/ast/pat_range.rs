@@ -0,0 +1,41 @@
+use crate::ast;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::traits::Parse;
+use runestick::Span;
+
+/// An inclusive range pattern, like `1..=5` or `'a'..='z'`.
+#[derive(Debug, Clone)]
+pub struct PatRange {
+    /// The start of the range.
+    pub start: Box<ast::Pat>,
+    /// The `..=` token.
+    pub dot_dot_eq: ast::DotDotEq,
+    /// The end of the range.
+    pub end: Box<ast::Pat>,
+}
+
+impl PatRange {
+    /// Get the span of the pattern.
+    pub fn span(&self) -> Span {
+        self.start.span().join(self.end.span())
+    }
+}
+
+/// Parse implementation for a range pattern.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::PatRange>("1..=2").unwrap();
+/// ```
+impl Parse for PatRange {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        ast::Pat::parse_variant(parser, "a range pattern", |pat| match pat {
+            ast::Pat::PatRange(pat) => Ok(pat),
+            pat => Err(pat),
+        })
+    }
+}
@@ -2,7 +2,7 @@ use crate::ast;
 use crate::ast::{Kind, Token};
 use crate::error::ParseError;
 use crate::parser::Parser;
-use crate::traits::{Parse, Peek, Resolve};
+use crate::traits::{Parse, Peek, Resolve, ResolveOwned};
 use runestick::{Source, Span};
 
 /// A path, where each element is separated by a `::`.
@@ -101,3 +101,15 @@ impl<'a> Resolve<'a> for Path {
         Ok(output)
     }
 }
+
+impl ResolveOwned for Path {
+    type Owned = Vec<String>;
+
+    fn resolve_owned(&self, source: &Source) -> Result<Vec<String>, ParseError> {
+        Ok(self
+            .resolve(source)?
+            .into_iter()
+            .map(str::to_owned)
+            .collect())
+    }
+}
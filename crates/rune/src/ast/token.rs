@@ -87,6 +87,8 @@ pub enum Kind {
     Struct,
     /// An `is` token.
     Is,
+    /// An `as` token.
+    As,
     /// An `not` token.
     Not,
     /// A `let` token.
@@ -113,6 +115,8 @@ pub enum Kind {
     False,
     /// A `break` token.
     Break,
+    /// A `continue` token.
+    Continue,
     /// A `yield` token.
     Yield,
     /// A `return` token.
@@ -137,8 +141,6 @@ pub enum Kind {
     LitNumber {
         /// Indicates if it's a decimal number.
         is_fractional: bool,
-        /// Indicates if the number is negative.
-        is_negative: bool,
         /// The number literal kind.
         number: NumberKind,
     },
@@ -211,6 +213,8 @@ pub enum Kind {
     QuestionMark,
     /// `..`.
     DotDot,
+    /// `..=`.
+    DotDotEq,
     /// `&&`.
     AmpAmp,
     /// `||`.
@@ -245,6 +249,9 @@ pub enum Kind {
     LtLtEq,
     /// `>>=`.
     GtGtEq,
+    /// Input which the lexer didn't recognize, like an unexpected character or
+    /// an unterminated literal.
+    Error,
 }
 
 impl fmt::Display for Kind {
@@ -256,6 +263,7 @@ impl fmt::Display for Kind {
             Self::Enum => write!(f, "enum")?,
             Self::Struct => write!(f, "struct")?,
             Self::Is => write!(f, "is")?,
+            Self::As => write!(f, "as")?,
             Self::Not => write!(f, "not")?,
             Self::Let => write!(f, "let")?,
             Self::If => write!(f, "if")?,
@@ -269,6 +277,7 @@ impl fmt::Display for Kind {
             Self::True => write!(f, "true")?,
             Self::False => write!(f, "false")?,
             Self::Break => write!(f, "break")?,
+            Self::Continue => write!(f, "continue")?,
             Self::Yield => write!(f, "yield")?,
             Self::Return => write!(f, "return")?,
             Self::Await => write!(f, "await")?,
@@ -323,10 +332,12 @@ impl fmt::Display for Kind {
             Self::Bang => write!(f, "!")?,
             Self::QuestionMark => write!(f, "?")?,
             Self::DotDot => write!(f, "..")?,
+            Self::DotDotEq => write!(f, "..=")?,
             Self::AmpAmp => write!(f, "&&")?,
             Self::PipePipe => write!(f, "||")?,
             Self::Pipe => write!(f, "|")?,
             Self::Perc => write!(f, "%")?,
+            Self::Error => write!(f, "error")?,
         }
 
         Ok(())
@@ -187,17 +187,25 @@ where
 /// Find the span of an expression inside of a balanced collection of braces.
 ///
 /// This is expected to start parsing immediately after an opening brace `{`.
+///
+/// String literals, character literals, and nested templates inside of the
+/// expression are skipped over, so that any braces they contain don't affect
+/// the balance.
 pub(crate) fn template_expr<I>(span: Span, it: &mut I) -> Result<Span, ParseError>
 where
     I: Iterator<Item = (usize, char)>,
 {
     let mut start = None;
     let mut level = 1;
+    let mut pending = None;
 
     loop {
-        let (n, c) = it
-            .next()
-            .ok_or_else(|| ParseError::InvalidTemplateLiteral { span })?;
+        let (n, c) = match pending.take() {
+            Some(pending) => pending,
+            None => it
+                .next()
+                .ok_or_else(|| ParseError::InvalidTemplateLiteral { span })?,
+        };
 
         if start.is_none() {
             start = Some(n);
@@ -206,6 +214,9 @@ where
         match c {
             '{' => level += 1,
             '}' => level -= 1,
+            '"' => skip_quoted(span, it, '"')?,
+            '`' => skip_quoted(span, it, '`')?,
+            '\'' => pending = skip_char(span, it)?,
             _ => (),
         }
 
@@ -216,6 +227,70 @@ where
     }
 }
 
+/// Skip over a quoted literal inside of a template expression, up until and
+/// including the closing `end` character.
+///
+/// This is expected to start parsing immediately after the opening quote.
+/// Expressions in nested templates are skipped through [template_expr], so
+/// character literals in them like `'}'` are skipped over as well. Quotes in
+/// the text of a literal carry no meaning.
+fn skip_quoted<I>(span: Span, it: &mut I, end: char) -> Result<(), ParseError>
+where
+    I: Iterator<Item = (usize, char)>,
+{
+    loop {
+        let (_, c) = it
+            .next()
+            .ok_or_else(|| ParseError::InvalidTemplateLiteral { span })?;
+
+        match c {
+            '\\' => {
+                it.next()
+                    .ok_or_else(|| ParseError::InvalidTemplateLiteral { span })?;
+            }
+            '{' if end == '`' => {
+                template_expr(span, it)?;
+            }
+            c if c == end => return Ok(()),
+            _ => (),
+        }
+    }
+}
+
+/// Skip over what might be a character literal inside of a template
+/// expression.
+///
+/// This is expected to start parsing immediately after the opening quote. If
+/// the quote turns out to be the start of a label like `'outer`, the character
+/// following the label's first character is returned so that the caller can
+/// process it.
+fn skip_char<I>(span: Span, it: &mut I) -> Result<Option<(usize, char)>, ParseError>
+where
+    I: Iterator<Item = (usize, char)>,
+{
+    let mut next = || {
+        it.next()
+            .ok_or_else(|| ParseError::InvalidTemplateLiteral { span })
+    };
+
+    let (_, c) = next()?;
+
+    if c == '\\' {
+        // NB: skip the escaped character, which might be a quote itself.
+        next()?;
+        skip_quoted(span, it, '\'')?;
+        return Ok(None);
+    }
+
+    let (n, c) = next()?;
+
+    if c == '\'' {
+        return Ok(None);
+    }
+
+    Ok(Some((n, c)))
+}
+
 /// Test if the given expression qualifieis as a block end or not, as with a
 /// body in a match expression.
 ///
@@ -234,7 +309,7 @@ pub(crate) fn is_block_end(expr: &ast::Expr, comma: Option<&ast::Comma>) -> bool
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_hex_escape, parse_unicode_escape};
+    use super::{parse_hex_escape, parse_unicode_escape, template_expr};
     use runestick::Span;
 
     macro_rules! input {
@@ -261,4 +336,27 @@ mod tests {
         let c = parse_unicode_escape(Span::empty(), input!("{1f4af}")).unwrap();
         assert_eq!(c, '💯');
     }
+
+    #[test]
+    fn test_template_expr() {
+        fn test(source: &str) -> &str {
+            let span = template_expr(Span::empty(), input!(source)).unwrap();
+            &source[span.start..span.end]
+        }
+
+        assert_eq!(test("a + b} rest"), "a + b");
+        assert_eq!(test("{ a }} rest"), "{ a }");
+        assert_eq!(test("\"}\"} rest"), "\"}\"");
+        assert_eq!(test("'}'} rest"), "'}'");
+        assert_eq!(test("'\\''} rest"), "'\\''");
+        assert_eq!(test("'\\u{7d}'} rest"), "'\\u{7d}'");
+        assert_eq!(
+            test("'a: loop { break 'a; }} rest"),
+            "'a: loop { break 'a; }"
+        );
+        assert_eq!(test("`{'}'}`} rest"), "`{'}'}`");
+        assert_eq!(test("`{'\\''} '`} rest"), "`{'\\''} '`");
+        assert_eq!(test("\"'\"} rest"), "\"'\"");
+        assert!(template_expr(Span::empty(), input!("'{'")).is_err());
+    }
 }
@@ -0,0 +1,321 @@
+//! Analysis of which restricted capabilities a compiled unit can reach.
+//!
+//! Native functions are classified by the module they're declared in, like
+//! `fs::read_to_string` granting access to the filesystem. The analysis builds
+//! a call graph over the functions in the unit, and reports every restricted
+//! native function which can be reached from it without running anything.
+//!
+//! The analysis is conservative: a function pointer which is loaded counts as
+//! a call, and an instance function call like `value.get()` counts as a call
+//! to every instance function named `get`. Protocols which are invoked
+//! implicitly by operators and loops are not considered.
+
+use crate::collections::{HashMap, HashSet};
+use runestick::{Component, Context, ContextSignature, Hash, Inst, Item, Unit, UnitFn};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// A restricted capability.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Capability {
+    /// Access to the filesystem.
+    Fs,
+    /// Access to the network.
+    Net,
+    /// Spawning and signalling processes.
+    Process,
+}
+
+impl Capability {
+    /// Get the capability corresponding to the given name, like `fs`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "fs" => Some(Self::Fs),
+            "net" => Some(Self::Net),
+            "process" => Some(Self::Process),
+            _ => None,
+        }
+    }
+
+    /// Get the name of the capability.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Fs => "fs",
+            Self::Net => "net",
+            Self::Process => "process",
+        }
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(self.name())
+    }
+}
+
+/// Which native modules require which capability.
+///
+/// The default restricts the `fs`, `http`, `process`, and `signal` modules
+/// provided by `rune-modules`.
+#[derive(Debug, Clone)]
+pub struct Restrictions {
+    modules: Vec<(Item, Capability)>,
+}
+
+impl Restrictions {
+    /// Construct a new collection of restrictions without any restricted
+    /// modules.
+    pub fn new() -> Self {
+        Self {
+            modules: Vec::new(),
+        }
+    }
+
+    /// Mark every native function and type in the given module as requiring
+    /// `capability`.
+    pub fn restrict(&mut self, module: Item, capability: Capability) {
+        self.modules.push((module, capability));
+    }
+
+    /// Get the capability required by the given native item, if any.
+    ///
+    /// If multiple restricted modules contain the item, the most specific one
+    /// is used.
+    pub fn capability_of(&self, item: &Item) -> Option<Capability> {
+        self.modules
+            .iter()
+            .filter(|(module, _)| starts_with(item, module))
+            .max_by_key(|(module, _)| module.as_vec().len())
+            .map(|(_, capability)| *capability)
+    }
+}
+
+impl Default for Restrictions {
+    fn default() -> Self {
+        let mut restrictions = Self::new();
+        restrictions.restrict(Item::of(&["fs"]), Capability::Fs);
+        restrictions.restrict(Item::of(&["http"]), Capability::Net);
+        restrictions.restrict(Item::of(&["process"]), Capability::Process);
+        restrictions.restrict(Item::of(&["signal"]), Capability::Process);
+        restrictions
+    }
+}
+
+/// A call from a function in the unit to a restricted native function.
+#[derive(Debug, Clone)]
+pub struct CapabilityUse {
+    /// The capability required by the native function.
+    pub capability: Capability,
+    /// The native function being called.
+    pub function: Item,
+    /// The hash of the function in the unit making the call.
+    pub caller: Hash,
+    /// The instruction pointer of the call.
+    pub ip: usize,
+}
+
+/// The result of analyzing which capabilities a unit can reach.
+#[derive(Debug, Clone, Default)]
+pub struct CapabilityReport {
+    uses: Vec<CapabilityUse>,
+    reachable: HashMap<Hash, BTreeSet<Capability>>,
+}
+
+impl CapabilityReport {
+    /// Test if the unit can't reach any restricted capability.
+    pub fn is_empty(&self) -> bool {
+        self.uses.is_empty()
+    }
+
+    /// All direct uses of restricted native functions in the unit, in the
+    /// order they appear.
+    pub fn uses(&self) -> &[CapabilityUse] {
+        &self.uses
+    }
+
+    /// All capabilities which can be reached by calling any function in the
+    /// unit.
+    pub fn capabilities(&self) -> BTreeSet<Capability> {
+        self.uses.iter().map(|u| u.capability).collect()
+    }
+
+    /// The capabilities which can be transitively reached by calling the
+    /// function with the given hash.
+    pub fn reachable_from(&self, hash: Hash) -> BTreeSet<Capability> {
+        self.reachable.get(&hash).cloned().unwrap_or_default()
+    }
+}
+
+/// Analyze which restricted capabilities the functions in the given unit can
+/// reach.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::capabilities::{self, Capability, Restrictions};
+/// use runestick::{Context, Hash, Item, Module, Source};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut module = Module::new(&["fs"]);
+/// module.function(&["remove"], |path: String| drop(path))?;
+///
+/// let mut context = Context::with_default_modules()?;
+/// context.install(&module)?;
+///
+/// let mut sources = rune::Sources::new();
+/// sources.insert_default(Source::new("entry", r#"
+/// fn clean() { fs::remove("target") }
+/// fn main() { clean() }
+/// "#));
+///
+/// let unit = rune::load_sources(&context, &Default::default(), &mut sources, &mut Default::default())?;
+/// let report = capabilities::analyze(&context, &unit, &Restrictions::default());
+///
+/// let main = Hash::type_hash(&Item::of(&["main"]));
+/// assert!(report.reachable_from(main).contains(&Capability::Fs));
+/// assert_eq!(report.uses()[0].function, Item::of(&["fs", "remove"]));
+/// # Ok(())
+/// # }
+/// ```
+pub fn analyze(context: &Context, unit: &Unit, restrictions: &Restrictions) -> CapabilityReport {
+    let instructions = unit.iter_instructions().collect::<Vec<_>>();
+
+    let mut functions = unit
+        .iter_functions()
+        .filter_map(|(hash, f)| match f {
+            UnitFn::Offset { id, .. } => Some((unit.function_offset(*id).ok()?, hash)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    functions.sort();
+
+    // NB: restricted native instance functions, by the hash of their name.
+    let mut native_instance = HashMap::<Hash, Vec<(Item, Capability)>>::new();
+
+    for (_, signature) in context.iter_functions() {
+        if let ContextSignature::Instance { path, name, .. } = signature {
+            if let Some(capability) = restrictions.capability_of(path) {
+                native_instance
+                    .entry(context.instance_fn_hash(name))
+                    .or_default()
+                    .push((path.extended(name.as_str()), capability));
+            }
+        }
+    }
+
+    // NB: functions in the unit, by the hash of their name. Without debug info
+    // we don't know which function is which, so an instance function call
+    // could reach any function in the unit.
+    let unit_instance = unit.debug_info().map(|debug| {
+        let mut by_name = HashMap::<Hash, Vec<Hash>>::new();
+
+        for (hash, signature) in &debug.functions {
+            if let Some(Component::String(name)) = signature.path.last() {
+                by_name
+                    .entry(context.instance_fn_hash(name))
+                    .or_default()
+                    .push(*hash);
+            }
+        }
+
+        by_name
+    });
+
+    let all_functions = functions.iter().map(|(_, hash)| *hash).collect::<Vec<_>>();
+
+    let mut uses = Vec::new();
+    let mut calls = HashMap::<Hash, HashSet<Hash>>::new();
+
+    for (index, (offset, caller)) in functions.iter().enumerate() {
+        let end = match functions.get(index + 1) {
+            Some((end, _)) => *end,
+            None => instructions.len(),
+        };
+
+        let callees = calls.entry(*caller).or_default();
+
+        for (ip, inst) in instructions.iter().enumerate().take(end).skip(*offset) {
+            let mut direct = |function: &Item, capability: Capability| {
+                uses.push(CapabilityUse {
+                    capability,
+                    function: function.clone(),
+                    caller: *caller,
+                    ip,
+                });
+            };
+
+            match *inst {
+                Inst::Call { hash, .. } | Inst::Fn { hash } | Inst::Closure { hash, .. } => {
+                    if let Some(UnitFn::Offset { .. }) = unit.lookup(hash) {
+                        callees.insert(hash);
+                        continue;
+                    }
+
+                    if let Some(ContextSignature::Function { path, .. }) =
+                        context.lookup_signature(hash)
+                    {
+                        if let Some(capability) = restrictions.capability_of(path) {
+                            direct(path, capability);
+                        }
+                    }
+                }
+                Inst::CallInstance { hash, .. } | Inst::LoadInstanceFn { hash } => {
+                    for (function, capability) in native_instance.get(&hash).into_iter().flatten() {
+                        direct(function, *capability);
+                    }
+
+                    match &unit_instance {
+                        Some(by_name) => {
+                            callees.extend(by_name.get(&hash).into_iter().flatten().copied());
+                        }
+                        None => {
+                            callees.extend(all_functions.iter().copied());
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    let mut reachable = HashMap::<Hash, BTreeSet<Capability>>::new();
+
+    for u in &uses {
+        reachable.entry(u.caller).or_default().insert(u.capability);
+    }
+
+    // NB: propagate capabilities backwards through the call graph until
+    // nothing changes.
+    loop {
+        let mut changed = false;
+
+        for (caller, callees) in &calls {
+            let mut found = reachable.get(caller).cloned().unwrap_or_default();
+            let before = found.len();
+
+            for callee in callees {
+                if let Some(capabilities) = reachable.get(callee) {
+                    found.extend(capabilities.iter().copied());
+                }
+            }
+
+            if found.len() != before {
+                reachable.insert(*caller, found);
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    CapabilityReport { uses, reachable }
+}
+
+/// Test if `item` is contained in the module `prefix`.
+fn starts_with(item: &Item, prefix: &Item) -> bool {
+    let mut item = item.into_iter();
+    prefix.into_iter().all(|c| item.next() == Some(c))
+}
@@ -0,0 +1,111 @@
+//! Checking a unit against the host API it targets.
+
+use runestick::{ApiVersion, Hash, Span};
+use std::fmt;
+
+/// A report of how compatible a unit is with the host API provided by a
+/// context, produced when loading sources with the `api-version` option.
+///
+/// Instead of failing on the first function which is missing, every call to a
+/// function which doesn't exist or which is called with the wrong number of
+/// arguments is collected.
+#[derive(Debug, Clone)]
+pub struct CompatibilityReport {
+    pub(crate) target: ApiVersion,
+    pub(crate) host: Option<ApiVersion>,
+    pub(crate) missing: Vec<MissingFunction>,
+    pub(crate) arity_mismatches: Vec<ArityMismatch>,
+}
+
+impl CompatibilityReport {
+    /// The version of the host API targeted by the unit.
+    pub fn target(&self) -> ApiVersion {
+        self.target
+    }
+
+    /// The version of the host API provided by the context, if declared.
+    pub fn host(&self) -> Option<ApiVersion> {
+        self.host
+    }
+
+    /// Test if the version of the host API supports the targeted version.
+    ///
+    /// A context which doesn't declare a version never does.
+    pub fn version_supported(&self) -> bool {
+        match self.host {
+            Some(host) => host.supports(self.target),
+            None => false,
+        }
+    }
+
+    /// Functions which are called but which don't exist.
+    pub fn missing(&self) -> &[MissingFunction] {
+        &self.missing
+    }
+
+    /// Native functions which are called with the wrong number of arguments.
+    pub fn arity_mismatches(&self) -> &[ArityMismatch] {
+        &self.arity_mismatches
+    }
+
+    /// Test if the unit is compatible with the context.
+    pub fn is_compatible(&self) -> bool {
+        self.version_supported() && self.missing.is_empty() && self.arity_mismatches.is_empty()
+    }
+}
+
+impl fmt::Display for CompatibilityReport {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.host {
+            Some(host) if !host.supports(self.target) => writeln!(
+                fmt,
+                "host API version {} doesn't support target version {}",
+                host, self.target
+            )?,
+            None => writeln!(
+                fmt,
+                "host API doesn't declare a version, but version {} is targeted",
+                self.target
+            )?,
+            _ => (),
+        }
+
+        for missing in &self.missing {
+            writeln!(fmt, "missing function with hash `{}`", missing.hash)?;
+        }
+
+        for mismatch in &self.arity_mismatches {
+            writeln!(
+                fmt,
+                "`{}` expects {} arguments, but is called with {}",
+                mismatch.function, mismatch.expected, mismatch.actual
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A function which is called by a unit but doesn't exist.
+#[derive(Debug, Clone)]
+pub struct MissingFunction {
+    /// Hash of the function.
+    pub hash: Hash,
+    /// Spans and source ids where the function is called.
+    pub spans: Vec<(Span, usize)>,
+}
+
+/// A native function which is called with the wrong number of arguments.
+#[derive(Debug, Clone)]
+pub struct ArityMismatch {
+    /// The signature of the function.
+    pub function: String,
+    /// The number of arguments the function expects.
+    pub expected: usize,
+    /// The number of arguments it's called with.
+    pub actual: usize,
+    /// The span of the call.
+    pub span: Span,
+    /// The source id of the call.
+    pub source_id: usize,
+}
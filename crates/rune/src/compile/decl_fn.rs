@@ -3,7 +3,7 @@ use crate::compiler::{Compiler, Needs};
 use crate::error::CompileResult;
 use crate::traits::Compile;
 use crate::{traits::Resolve as _, CompileError};
-use runestick::Inst;
+use runestick::{Inst, Source, Span};
 
 impl Compile<(ast::DeclFn, bool)> for Compiler<'_> {
     fn compile(&mut self, (fn_decl, instance_fn): (ast::DeclFn, bool)) -> CompileResult<()> {
@@ -12,6 +12,7 @@ impl Compile<(ast::DeclFn, bool)> for Compiler<'_> {
         let _guard = self.items.push_block();
 
         let mut first = true;
+        let mut pats = Vec::new();
 
         for (arg, _) in fn_decl.args.items.iter() {
             let span = arg.span();
@@ -28,27 +29,61 @@ impl Compile<(ast::DeclFn, bool)> for Compiler<'_> {
                 ast::FnArg::Ident(ident) => {
                     let span = ident.span();
                     let name = ident.resolve(&*self.source)?;
+                    self.check_strict_binding(None, name, span)?;
                     self.scopes.last_mut(span)?.new_var(name, span)?;
                 }
                 ast::FnArg::Ignore(ignore) => {
                     let span = ignore.span();
                     self.scopes.decl_anon(span)?;
                 }
+                ast::FnArg::Pat(pat) => {
+                    let offset = self.scopes.decl_anon(pat.span())?;
+                    pats.push((offset, pat));
+                }
             }
 
             first = false;
         }
 
+        self.compile_fn_arg_pats(&pats, span)?;
+        self.warn_unreachable(&fn_decl.body);
+
+        if self.options.strict {
+            if !instance_fn {
+                let name = fn_decl.name.resolve(&*self.source)?;
+
+                if self.unit.borrow().is_prelude(name) {
+                    return Err(CompileError::StrictShadowedBuiltin {
+                        span: fn_decl.name.span(),
+                        name: name.to_owned(),
+                    });
+                }
+            }
+        }
+
+        if let Some(expr) = &fn_decl.body.trailing_expr {
+            self.check_strict_implicit_return(expr)?;
+        }
+
+        if self.options.lint_unguarded_recursion && !instance_fn {
+            if let Some(span) = unguarded_self_call(&*self.source, &fn_decl)? {
+                self.warnings
+                    .unguarded_recursion(self.source_id, span, self.context());
+            }
+        }
+
         if fn_decl.body.exprs.is_empty() && fn_decl.body.trailing_expr.is_none() {
             self.asm.push(Inst::ReturnUnit, span);
             return Ok(());
         }
 
         for (expr, _) in &fn_decl.body.exprs {
+            self.coverage(expr.span());
             self.compile((expr, Needs::None))?;
         }
 
         if let Some(expr) = &fn_decl.body.trailing_expr {
+            self.coverage(expr.span());
             self.compile((&**expr, Needs::Value))?;
 
             let total_var_count = self.scopes.last(span)?.total_var_count;
@@ -64,3 +99,115 @@ impl Compile<(ast::DeclFn, bool)> for Compiler<'_> {
         Ok(())
     }
 }
+
+/// The outcome of scanning an expression for a call to the function it's in.
+enum Scan {
+    /// The function is called at the given span.
+    Call(Span),
+    /// The function isn't called, and the expression can't branch or exit.
+    Continue,
+    /// The expression might branch or exit, so we stop looking.
+    Stop,
+}
+
+/// Find a call the function makes to itself before anything in its body
+/// which might branch or exit, in which case the recursion can never
+/// terminate.
+fn unguarded_self_call(source: &Source, fn_decl: &ast::DeclFn) -> CompileResult<Option<Span>> {
+    let name = fn_decl.name.resolve(source)?;
+
+    for (arg, _) in &fn_decl.args.items {
+        if let ast::FnArg::Ident(ident) = arg {
+            if ident.resolve(source)? == name {
+                return Ok(None);
+            }
+        }
+    }
+
+    let body = &fn_decl.body;
+    let exprs = body
+        .exprs
+        .iter()
+        .map(|(expr, _)| expr)
+        .chain(body.trailing_expr.as_deref());
+
+    for expr in exprs {
+        match scan_self_call(source, expr, name)? {
+            Scan::Call(span) => return Ok(Some(span)),
+            Scan::Continue => (),
+            Scan::Stop => break,
+        }
+    }
+
+    Ok(None)
+}
+
+/// Scan the given expression for a call to the function with the given name.
+///
+/// Only straight-line expressions are scanned, anything else stops the scan.
+fn scan_self_call(source: &Source, expr: &ast::Expr, name: &str) -> CompileResult<Scan> {
+    let scan = match expr {
+        ast::Expr::ExprCall(expr_call) => {
+            for (expr, _) in &expr_call.args.items {
+                match scan_self_call(source, expr, name)? {
+                    Scan::Continue => (),
+                    scan => return Ok(scan),
+                }
+            }
+
+            match &*expr_call.expr {
+                ast::Expr::Path(path) => match path.try_as_ident() {
+                    Some(ident) if ident.resolve(source)? == name => Scan::Call(expr_call.span()),
+                    _ => Scan::Continue,
+                },
+                // NB: instance function calls like `values.push(n)`.
+                ast::Expr::ExprFieldAccess(expr_field_access) => {
+                    scan_self_call(source, &expr_field_access.expr, name)?
+                }
+                _ => Scan::Stop,
+            }
+        }
+        ast::Expr::ExprLet(expr_let) => match scan_self_call(source, &expr_let.expr, name)? {
+            Scan::Continue => match &expr_let.pat {
+                // NB: the function is shadowed by a variable.
+                ast::Pat::PatPath(pat_path) => match pat_path.path.try_as_ident() {
+                    Some(ident) if ident.resolve(source)? == name => Scan::Stop,
+                    _ => Scan::Continue,
+                },
+                _ => Scan::Continue,
+            },
+            scan => scan,
+        },
+        ast::Expr::ExprBinary(expr_binary) => {
+            match scan_self_call(source, &expr_binary.lhs, name)? {
+                Scan::Continue => match expr_binary.op {
+                    // NB: the right-hand side is conditionally evaluated.
+                    ast::BinOp::And | ast::BinOp::Or => Scan::Stop,
+                    _ => scan_self_call(source, &expr_binary.rhs, name)?,
+                },
+                scan => scan,
+            }
+        }
+        ast::Expr::ExprGroup(expr_group) => scan_self_call(source, &expr_group.expr, name)?,
+        ast::Expr::ExprFieldAccess(expr_field_access) => {
+            scan_self_call(source, &expr_field_access.expr, name)?
+        }
+        ast::Expr::ExprReturn(expr_return) => match expr_return.expr.as_deref() {
+            Some(expr) => match scan_self_call(source, expr, name)? {
+                Scan::Continue => Scan::Stop,
+                scan => scan,
+            },
+            None => Scan::Stop,
+        },
+        ast::Expr::Path(..)
+        | ast::Expr::LitUnit(..)
+        | ast::Expr::LitBool(..)
+        | ast::Expr::LitChar(..)
+        | ast::Expr::LitByte(..)
+        | ast::Expr::LitNumber(..)
+        | ast::Expr::LitStr(..) => Scan::Continue,
+        _ => Scan::Stop,
+    };
+
+    Ok(scan)
+}
@@ -51,6 +51,9 @@ impl Compile<(&ast::Expr, Needs)> for Compiler<'_> {
             ast::Expr::ExprBreak(expr_break) => {
                 self.compile(expr_break)?;
             }
+            ast::Expr::ExprContinue(expr_continue) => {
+                self.compile(expr_continue)?;
+            }
             ast::Expr::ExprYield(expr_yield) => {
                 self.compile((expr_yield, needs))?;
             }
@@ -99,6 +102,12 @@ impl Compile<(&ast::Expr, Needs)> for Compiler<'_> {
             ast::Expr::LitObject(lit_object) => {
                 self.compile((lit_object, needs))?;
             }
+            ast::Expr::LitMap(lit_map) => {
+                self.compile((lit_map, needs))?;
+            }
+            ast::Expr::LitSet(lit_set) => {
+                self.compile((lit_set, needs))?;
+            }
             ast::Expr::LitChar(lit_char) => {
                 self.compile((lit_char, needs))?;
             }
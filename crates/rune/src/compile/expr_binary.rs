@@ -11,6 +11,9 @@ impl Compile<(&ast::ExprBinary, Needs)> for Compiler<'_> {
         let span = expr_binary.span();
         log::trace!("ExprBinary => {:?}", self.source.source(span));
 
+        self.warn_unit_function_value(&*expr_binary.lhs)?;
+        self.warn_unit_function_value(&*expr_binary.rhs)?;
+
         // Special expressions which operates on the stack in special ways.
         match expr_binary.op {
             ast::BinOp::Assign
@@ -36,11 +39,40 @@ impl Compile<(&ast::ExprBinary, Needs)> for Compiler<'_> {
             _ => (),
         }
 
+        // NB: concatenation of constant strings is performed at compile time.
+        if let Some(string) = self.constant_concat(expr_binary)? {
+            if !needs.value() {
+                self.warnings.not_used(self.source_id, span, self.context());
+                return Ok(());
+            }
+
+            self.compile_constant_string(&string, span)?;
+            return Ok(());
+        }
+
         // NB: need to declare these as anonymous local variables so that they
         // get cleaned up in case there is an early break (return, try, ...).
         self.compile((&*expr_binary.lhs, Needs::Value))?;
         self.scopes.decl_anon(span)?;
 
+        // NB: if the left-hand side of a boolean operator decides its outcome,
+        // it's left on the stack as the result and the right-hand side is
+        // skipped. Anything which isn't a boolean falls through to the
+        // operator instruction.
+        let short_circuit = match expr_binary.op {
+            ast::BinOp::And { .. } => {
+                let label = self.asm.new_label("and_short_circuit");
+                self.asm.peek_and_jump_if_not(label, span);
+                Some(label)
+            }
+            ast::BinOp::Or { .. } => {
+                let label = self.asm.new_label("or_short_circuit");
+                self.asm.peek_and_jump_if(label, span);
+                Some(label)
+            }
+            _ => None,
+        };
+
         self.compile((&*expr_binary.rhs, rhs_needs_of(expr_binary.op)))?;
         self.scopes.decl_anon(span)?;
 
@@ -84,6 +116,21 @@ impl Compile<(&ast::ExprBinary, Needs)> for Compiler<'_> {
             ast::BinOp::IsNot { .. } => {
                 self.asm.push(Inst::IsNot, span);
             }
+            ast::BinOp::As { .. } => {
+                self.asm.push(Inst::As, span);
+            }
+            ast::BinOp::Range { .. } => {
+                let hash = runestick::hash!(std::iter::range);
+                self.asm.push(Inst::Call { hash, args: 2 }, span);
+            }
+            ast::BinOp::RangeInclusive { .. } => {
+                // NB: inclusive ranges are constructed as exclusive ranges
+                // which end one past the inclusive end.
+                let hash = runestick::hash!(std::iter::range);
+                self.asm.push(Inst::Integer { number: 1 }, span);
+                self.asm.push(Inst::Add, span);
+                self.asm.push(Inst::Call { hash, args: 2 }, span);
+            }
             ast::BinOp::And { .. } => {
                 self.asm.push(Inst::And, span);
             }
@@ -110,6 +157,10 @@ impl Compile<(&ast::ExprBinary, Needs)> for Compiler<'_> {
             }
         }
 
+        if let Some(label) = short_circuit {
+            self.asm.label(label)?;
+        }
+
         // NB: we put it here to preserve the call in case it has side effects.
         // But if we don't need the value, then pop it from the stack.
         if !needs.value() {
@@ -125,7 +176,7 @@ impl Compile<(&ast::ExprBinary, Needs)> for Compiler<'_> {
 /// operator.
 fn rhs_needs_of(op: ast::BinOp) -> Needs {
     match op {
-        ast::BinOp::Is | ast::BinOp::IsNot => Needs::Type,
+        ast::BinOp::Is | ast::BinOp::IsNot | ast::BinOp::As => Needs::Type,
         _ => Needs::Value,
     }
 }
@@ -3,7 +3,7 @@ use crate::compiler::{Compiler, Needs};
 use crate::error::CompileResult;
 use crate::traits::Compile;
 use crate::CompileError;
-use runestick::{CompileMeta, CompileMetaCapture, Hash, Inst};
+use runestick::{CompileMeta, CompileMetaCapture, Inst};
 
 struct CallAsync(());
 struct BlockBody(());
@@ -75,7 +75,7 @@ impl Compile<(CallAsync, &ast::ExprBlock)> for Compiler<'_> {
         }
 
         let item = meta.item();
-        let hash = Hash::type_hash(item);
+        let hash = item.type_hash();
         self.asm.push_with_comment(
             Inst::Call {
                 hash,
@@ -101,6 +101,7 @@ impl Compile<(BlockBody, &ast::ExprBlock, Needs)> for Compiler<'_> {
         let _guard = self.items.push_block();
 
         self.contexts.push(span);
+        self.warn_unreachable(expr_block);
 
         let span = expr_block.span();
 
@@ -108,11 +109,13 @@ impl Compile<(BlockBody, &ast::ExprBlock, Needs)> for Compiler<'_> {
         let scopes_count = self.scopes.push(new_scope);
 
         for (expr, _) in &expr_block.exprs {
+            self.coverage(expr.span());
             // NB: terminated expressions do not need to produce a value.
             self.compile((expr, Needs::None))?;
         }
 
         if let Some(expr) = &expr_block.trailing_expr {
+            self.coverage(expr.span());
             self.compile((&**expr, needs))?;
         }
 
@@ -1,6 +1,7 @@
 use crate::ast;
-use crate::compiler::Compiler;
+use crate::compiler::{Compiler, Needs};
 use crate::error::CompileResult;
+use crate::loops::LoopKind;
 use crate::{traits::Compile, CompileError};
 use runestick::Inst;
 
@@ -12,29 +13,45 @@ impl Compile<&ast::ExprBreak> for Compiler<'_> {
         let span = expr_break.span();
         log::trace!("ExprBreak => {:?}", self.source.source(span));
 
-        let current_loop = match self.loops.last() {
-            Some(current_loop) => current_loop,
-            None => {
-                return Err(CompileError::BreakOutsideOfLoop { span });
-            }
+        let (last_loop, to_drop) = match &expr_break.label {
+            Some(label) => self.loops.walk_until_label(&*self.source, *label)?,
+            None => match self.loops.last() {
+                Some(current_loop) => (current_loop, current_loop.drop.into_iter().collect()),
+                None => {
+                    return Err(CompileError::BreakOutsideOfLoop { span });
+                }
+            },
         };
 
-        let (last_loop, to_drop, has_value) = if let Some(expr) = &expr_break.expr {
-            match expr {
-                ast::ExprBreakValue::Expr(expr) => {
-                    self.compile((&**expr, current_loop.needs))?;
-                    (current_loop, current_loop.drop.into_iter().collect(), true)
-                }
-                ast::ExprBreakValue::Label(label) => {
-                    let (last_loop, to_drop) =
-                        self.loops.walk_until_label(&*self.source, *label)?;
-                    (last_loop, to_drop, false)
-                }
+        let has_value = if let Some(expr) = &expr_break.expr {
+            // NB: only the value of a `loop` can be silently discarded, since
+            // it's the only way it produces anything. A `while` or a `for`
+            // loop in statement position can't produce a value at all.
+            if last_loop.kind != LoopKind::Loop && !last_loop.needs.value() {
+                return Err(CompileError::UnsupportedBreakValue {
+                    span,
+                    kind: last_loop.kind.keyword(),
+                    loop_span: last_loop.span,
+                });
             }
+
+            // NB: the value is evaluated for its side effects and popped,
+            // since the warning below covers it not being used.
+            self.compile((&**expr, Needs::Value))?;
+
+            if !last_loop.needs.value() {
+                self.warnings
+                    .break_value_not_used(self.source_id, expr.span(), self.context());
+                self.asm.push(Inst::Pop, span);
+            }
+
+            true
         } else {
-            (current_loop, current_loop.drop.into_iter().collect(), false)
+            false
         };
 
+        self.loops.mark_exit(last_loop.break_label);
+
         // Drop loop temporary. Typically an iterator.
         for offset in to_drop {
             self.asm.push(Inst::Drop { offset }, span);
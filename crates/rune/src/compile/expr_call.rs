@@ -3,7 +3,7 @@ use crate::compiler::{Compiler, Needs};
 use crate::error::CompileResult;
 use crate::traits::{Compile, Resolve as _};
 use crate::CompileError;
-use runestick::{CompileMeta, Hash, Inst};
+use runestick::{CompileMeta, Inst};
 
 /// Compile a call expression.
 impl Compile<(&ast::ExprCall, Needs)> for Compiler<'_> {
@@ -16,6 +16,10 @@ impl Compile<(&ast::ExprCall, Needs)> for Compiler<'_> {
 
         let args = expr_call.args.items.len();
 
+        for (expr, _) in expr_call.args.items.iter() {
+            self.warn_unit_function_value(expr)?;
+        }
+
         // NB: either handle a proper function call by resolving it's meta hash,
         // or expand the expression.
         #[allow(clippy::never_loop)]
@@ -36,6 +40,7 @@ impl Compile<(&ast::ExprCall, Needs)> for Compiler<'_> {
                     );
 
                     self.compile((&**expr, Needs::Value))?;
+                    self.scopes.decl_anon(span)?;
 
                     for (expr, _) in expr_call.args.items.iter() {
                         self.compile((expr, Needs::Value))?;
@@ -43,7 +48,7 @@ impl Compile<(&ast::ExprCall, Needs)> for Compiler<'_> {
                     }
 
                     let ident = ident.resolve(&*self.source)?;
-                    let hash = Hash::of(ident);
+                    let hash = self.context.instance_fn_hash(ident);
                     self.asm.push(Inst::CallInstance { hash, args }, span);
                 }
                 expr => {
@@ -124,7 +129,7 @@ impl Compile<(&ast::ExprCall, Needs)> for Compiler<'_> {
             }
         };
 
-        let hash = Hash::type_hash(&item);
+        let hash = item.type_hash();
         self.asm
             .push_with_comment(Inst::Call { hash, args }, span, format!("fn `{}`", item));
 
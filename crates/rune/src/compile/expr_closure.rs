@@ -3,7 +3,7 @@ use crate::compiler::{Compiler, Needs};
 use crate::error::CompileResult;
 use crate::traits::Compile;
 use crate::{traits::Resolve as _, CompileError};
-use runestick::{CompileMeta, CompileMetaCapture, Hash, Inst};
+use runestick::{CompileMeta, CompileMetaCapture, Inst};
 
 /// Compile the body of a closure function.
 impl Compile<(ast::ExprClosure, &[CompileMetaCapture])> for Compiler<'_> {
@@ -14,7 +14,16 @@ impl Compile<(ast::ExprClosure, &[CompileMetaCapture])> for Compiler<'_> {
         let span = expr_closure.span();
         log::trace!("ExprClosure => {:?}", self.source.source(span));
 
-        let count = {
+        let mut pats = Vec::new();
+
+        for (arg, _) in expr_closure.args.as_slice() {
+            if let ast::FnArg::Ident(ident) = arg {
+                let ident = ident.resolve(&*self.source)?;
+                self.check_strict_binding(None, ident, arg.span())?;
+            }
+        }
+
+        {
             let scope = self.scopes.last_mut(span)?;
 
             for (arg, _) in expr_closure.args.as_slice() {
@@ -32,6 +41,9 @@ impl Compile<(ast::ExprClosure, &[CompileMetaCapture])> for Compiler<'_> {
                         // Ignore incoming variable.
                         let _ = scope.decl_anon(span);
                     }
+                    ast::FnArg::Pat(pat) => {
+                        pats.push((scope.decl_anon(span), pat));
+                    }
                 }
             }
 
@@ -42,10 +54,16 @@ impl Compile<(ast::ExprClosure, &[CompileMetaCapture])> for Compiler<'_> {
                     scope.new_var(&capture.ident, span)?;
                 }
             }
+        }
 
-            scope.total_var_count
-        };
+        // NB: destructuring happens after the environment has been unpacked,
+        // since it occupies the slots following the arguments.
+        self.compile_fn_arg_pats(&pats, span)?;
+        self.check_strict_implicit_return(&expr_closure.body)?;
+
+        let count = self.scopes.last(span)?.total_var_count;
 
+        self.coverage(expr_closure.body.span());
         self.compile((&*expr_closure.body, Needs::Value))?;
 
         if count != 0 {
@@ -72,7 +90,7 @@ impl Compile<(&ast::ExprClosure, Needs)> for Compiler<'_> {
 
         let _guard = self.items.push_closure();
         let item = self.items.item();
-        let hash = Hash::type_hash(&item);
+        let hash = item.type_hash();
 
         let meta =
             self.query
@@ -0,0 +1,46 @@
+use crate::ast;
+use crate::compiler::Compiler;
+use crate::error::CompileResult;
+use crate::{traits::Compile, CompileError};
+use runestick::Inst;
+
+/// Compile a continue expression.
+impl Compile<&ast::ExprContinue> for Compiler<'_> {
+    fn compile(&mut self, expr_continue: &ast::ExprContinue) -> CompileResult<()> {
+        let span = expr_continue.span();
+        log::trace!("ExprContinue => {:?}", self.source.source(span));
+
+        let (last_loop, mut to_drop) = match &expr_continue.label {
+            Some(label) => self.loops.walk_until_label(&*self.source, *label)?,
+            None => match self.loops.last() {
+                Some(current_loop) => (current_loop, vec![]),
+                None => {
+                    return Err(CompileError::ContinueOutsideOfLoop { span });
+                }
+            },
+        };
+
+        // NB: the temporary of the loop being continued is still in use, only
+        // the temporaries of loops nested inside of it are dropped.
+        if expr_continue.label.is_some() && last_loop.drop.is_some() {
+            to_drop.pop();
+        }
+
+        self.loops.mark_exit_nested(last_loop.break_label);
+
+        for offset in to_drop {
+            self.asm.push(Inst::Drop { offset }, span);
+        }
+
+        let vars = self
+            .scopes
+            .last(span)?
+            .total_var_count
+            .checked_sub(last_loop.continue_var_count)
+            .ok_or_else(|| CompileError::internal("var count should be larger", span))?;
+
+        self.locals_pop(vars, span);
+        self.asm.jump(last_loop.continue_label, span);
+        Ok(())
+    }
+}
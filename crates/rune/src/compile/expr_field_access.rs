@@ -14,6 +14,14 @@ impl Compile<(&ast::ExprFieldAccess, Needs)> for Compiler<'_> {
     ) -> CompileResult<()> {
         let span = expr_field_access.span();
 
+        // NB: the access itself points at the field being accessed, so that
+        // errors in long chains of field accesses point out the one that
+        // failed.
+        let field_span = expr_field_access
+            .dot
+            .span()
+            .join(expr_field_access.expr_field.span());
+
         // Optimizations!
         //
         // TODO: perform deferred compilation for expressions instead, so we can
@@ -22,7 +30,8 @@ impl Compile<(&ast::ExprFieldAccess, Needs)> for Compiler<'_> {
         #[allow(clippy::single_match)]
         match (&*expr_field_access.expr, &expr_field_access.expr_field) {
             (ast::Expr::Path(path), ast::ExprField::LitNumber(n)) => {
-                if try_immediate_field_access_optimization(self, span, path, n, needs)? {
+                if try_immediate_field_access_optimization(self, span, field_span, path, n, needs)?
+                {
                     return Ok(());
                 }
             }
@@ -44,7 +53,7 @@ impl Compile<(&ast::ExprFieldAccess, Needs)> for Compiler<'_> {
                         _ => break,
                     };
 
-                    self.asm.push(Inst::TupleIndexGet { index }, span);
+                    self.asm.push(Inst::TupleIndexGet { index }, field_span);
 
                     if !needs.value() {
                         self.warnings.not_used(self.source_id, span, self.context());
@@ -57,7 +66,7 @@ impl Compile<(&ast::ExprFieldAccess, Needs)> for Compiler<'_> {
                     let field = ident.resolve(&*self.source)?;
                     let slot = self.unit.borrow_mut().new_static_string(field)?;
 
-                    self.asm.push(Inst::ObjectSlotIndexGet { slot }, span);
+                    self.asm.push(Inst::ObjectSlotIndexGet { slot }, field_span);
 
                     if !needs.value() {
                         self.warnings.not_used(self.source_id, span, self.context());
@@ -76,6 +85,7 @@ impl Compile<(&ast::ExprFieldAccess, Needs)> for Compiler<'_> {
 fn try_immediate_field_access_optimization(
     this: &mut Compiler<'_>,
     span: Span,
+    field_span: Span,
     path: &ast::Path,
     n: &ast::LitNumber,
     needs: Needs,
@@ -107,7 +117,7 @@ fn try_immediate_field_access_optimization(
             offset: var.offset,
             index,
         },
-        span,
+        field_span,
     );
 
     if !needs.value() {
@@ -1,7 +1,7 @@
 use crate::ast;
 use crate::compiler::{Compiler, Needs};
 use crate::error::CompileResult;
-use crate::loops::Loop;
+use crate::loops::{Loop, LoopKind};
 use crate::traits::{Compile, Resolve as _};
 use runestick::Inst;
 
@@ -11,6 +11,12 @@ impl Compile<(&ast::ExprFor, Needs)> for Compiler<'_> {
         let span = expr_for.span();
         log::trace!("ExprFor => {:?}", self.source.source(span));
 
+        if let ast::Expr::ExprBinary(range) = &*expr_for.iter {
+            if let ast::BinOp::Range | ast::BinOp::RangeInclusive = range.op {
+                return self.compile_for_range(expr_for, range, needs);
+            }
+        }
+
         let start_label = self.asm.new_label("for_start");
         let end_label = self.asm.new_label("for_end");
         let break_label = self.asm.new_label("for_break");
@@ -35,18 +41,11 @@ impl Compile<(&ast::ExprFor, Needs)> for Compiler<'_> {
             (iter_offset, loop_scope_expected)
         };
 
-        let _guard = self.loops.push(Loop {
-            label: expr_for.label.map(|(label, _)| label),
-            break_label,
-            total_var_count,
-            needs,
-            drop: Some(iter_offset),
-        });
-
         // Declare named loop variable.
         let binding_offset = {
             self.asm.push(Inst::Unit, expr_for.iter.span());
             let name = expr_for.var.resolve(&*self.source)?;
+            self.check_strict_binding(None, name, expr_for.var.span())?;
             self.scopes
                 .last_mut(span)?
                 .decl_var(name, expr_for.var.span())
@@ -80,6 +79,19 @@ impl Compile<(&ast::ExprFor, Needs)> for Compiler<'_> {
             None
         };
 
+        let _guard = self.loops.push(Loop {
+            kind: LoopKind::For,
+            span,
+            label: expr_for.label.map(|(label, _)| label),
+            continue_label: start_label,
+            continue_var_count: self.scopes.last(span)?.total_var_count,
+            break_label,
+            total_var_count,
+            needs,
+            drop: Some(iter_offset),
+            exits: false,
+        });
+
         self.asm.label(start_label)?;
 
         // Use the memoized loop variable.
@@ -185,3 +197,94 @@ impl Compile<(&ast::ExprFor, Needs)> for Compiler<'_> {
         Ok(())
     }
 }
+
+impl Compiler<'_> {
+    /// Compile a for loop over a range expression like `0..n`.
+    ///
+    /// Instead of constructing an iterator, the counter and the end of the
+    /// range are stored in anonymous locals and advanced with
+    /// [Inst::RangeNext].
+    fn compile_for_range(
+        &mut self,
+        expr_for: &ast::ExprFor,
+        range: &ast::ExprBinary,
+        needs: Needs,
+    ) -> CompileResult<()> {
+        let span = expr_for.span();
+        log::trace!("ExprFor (range) => {:?}", self.source.source(span));
+
+        let start_label = self.asm.new_label("for_range_start");
+        let end_label = self.asm.new_label("for_range_end");
+        let break_label = self.asm.new_label("for_range_break");
+
+        let total_var_count = self.scopes.last(span)?.total_var_count;
+
+        let loop_scope = self.scopes.child(span)?;
+        let loop_scope_expected = self.scopes.push(loop_scope);
+
+        self.compile((&*range.lhs, Needs::Value))?;
+        let counter = self.scopes.decl_anon(range.lhs.span())?;
+
+        self.compile((&*range.rhs, Needs::Value))?;
+
+        // NB: inclusive ranges are iterated over as exclusive ranges which end
+        // one past the inclusive end.
+        if let ast::BinOp::RangeInclusive = range.op {
+            self.asm.push(Inst::Integer { number: 1 }, range.span());
+            self.asm.push(Inst::Add, range.span());
+        }
+
+        let end = self.scopes.decl_anon(range.rhs.span())?;
+
+        // Declare named loop variable.
+        let binding = {
+            self.asm.push(Inst::Unit, expr_for.iter.span());
+            let name = expr_for.var.resolve(&*self.source)?;
+            self.check_strict_binding(None, name, expr_for.var.span())?;
+            self.scopes
+                .last_mut(span)?
+                .decl_var(name, expr_for.var.span())
+        };
+
+        let _guard = self.loops.push(Loop {
+            kind: LoopKind::For,
+            span,
+            label: expr_for.label.map(|(label, _)| label),
+            continue_label: start_label,
+            continue_var_count: self.scopes.last(span)?.total_var_count,
+            break_label,
+            total_var_count,
+            needs,
+            drop: None,
+            exits: false,
+        });
+
+        self.asm.label(start_label)?;
+
+        self.asm.push(
+            Inst::RangeNext {
+                counter,
+                end,
+                binding,
+            },
+            expr_for.iter.span(),
+        );
+
+        self.asm.jump_if_not(end_label, expr_for.iter.span());
+
+        self.compile((&*expr_for.body, Needs::None))?;
+        self.asm.jump(start_label, span);
+        self.asm.label(end_label)?;
+
+        self.clean_last_scope(span, loop_scope_expected, Needs::None)?;
+
+        // NB: If a value is needed from a for loop, encode it as a unit.
+        if needs.value() {
+            self.asm.push(Inst::Unit, span);
+        }
+
+        // NB: breaks produce their own value.
+        self.asm.label(break_label)?;
+        Ok(())
+    }
+}
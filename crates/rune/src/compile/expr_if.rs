@@ -1,7 +1,8 @@
 use crate::ast;
-use crate::compiler::{Compiler, Needs};
+use crate::compiler::{BranchValue, Compiler, Needs};
 use crate::error::CompileResult;
 use crate::traits::Compile;
+use crate::CompileError;
 use runestick::Inst;
 
 /// Compile an if expression.
@@ -10,6 +11,10 @@ impl Compile<(&ast::ExprIf, Needs)> for Compiler<'_> {
         let span = expr_if.span();
         log::trace!("ExprIf => {:?}", self.source.source(span));
 
+        if needs.value() {
+            self.check_if_branches(expr_if)?;
+        }
+
         let then_label = self.asm.new_label("if_then");
         let end_label = self.asm.new_label("if_end");
 
@@ -47,7 +52,7 @@ impl Compile<(&ast::ExprIf, Needs)> for Compiler<'_> {
 
         let mut it = branches.into_iter().peekable();
 
-        if let Some((branch, label, scope)) = it.next() {
+        while let Some((branch, label, scope)) = it.next() {
             let span = branch.span();
 
             self.asm.label(label)?;
@@ -65,3 +70,33 @@ impl Compile<(&ast::ExprIf, Needs)> for Compiler<'_> {
         Ok(())
     }
 }
+
+impl Compiler<'_> {
+    /// Check that the branches of an if expression whose value is used agree
+    /// on whether they produce a value or not.
+    fn check_if_branches(&self, expr_if: &ast::ExprIf) -> CompileResult<()> {
+        let mut branches = vec![(expr_if.block.span(), BranchValue::of_block(&expr_if.block))];
+
+        for branch in &expr_if.expr_else_ifs {
+            branches.push((branch.block.span(), BranchValue::of_block(&branch.block)));
+        }
+
+        if let Some(fallback) = &expr_if.expr_else {
+            branches.push((
+                fallback.block.span(),
+                BranchValue::of_block(&fallback.block),
+            ));
+        }
+
+        let value = self.check_branch_values(&branches)?;
+
+        if let (Some(value), None) = (value, &expr_if.expr_else) {
+            return Err(CompileError::IfWithoutElse {
+                span: expr_if.span(),
+                value,
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -22,7 +22,10 @@ impl Compile<(&ast::ExprIndexGet, Needs)> for Compiler<'_> {
         self.compile((&*expr_index_get.target, Needs::Value))?;
         self.scopes.decl_anon(span)?;
 
-        self.asm.push(Inst::IndexGet, span);
+        // NB: point at the index being accessed, so that errors in chains of
+        // index accesses point out the one that failed.
+        let index_span = expr_index_get.open.span().join(expr_index_get.close.span());
+        self.asm.push(Inst::IndexGet, index_span);
 
         // NB: we still need to perform the operation since it might have side
         // effects, but pop the result in case a value is not needed.
@@ -13,10 +13,24 @@ impl Compile<(&ast::ExprIndexSet, Needs)> for Compiler<'_> {
         let span = expr_index_set.span();
         log::trace!("ExprIndexSet => {:?}", self.source.source(span));
 
+        let scope = self.scopes.child(span)?;
+        let guard = self.scopes.push(scope);
+
         self.compile((&*expr_index_set.value, Needs::Value))?;
+        self.scopes.decl_anon(span)?;
         self.compile((&*expr_index_set.index, Needs::Value))?;
+        self.scopes.decl_anon(span)?;
         self.compile((&*expr_index_set.target, Needs::Value))?;
-        self.asm.push(Inst::IndexSet, span);
+
+        self.scopes.pop(guard, span)?;
+
+        // NB: point at the index being assigned to rather than the whole
+        // assignment.
+        let index_span = expr_index_set
+            .target
+            .span()
+            .join(expr_index_set.close.span());
+        self.asm.push(Inst::IndexSet, index_span);
 
         // Encode a unit in case a value is needed.
         if needs.value() {
@@ -11,6 +11,8 @@ impl Compile<(&ast::ExprLet, Needs)> for Compiler<'_> {
         let span = expr_let.span();
         log::trace!("ExprLet => {:?}", self.source.source(span));
 
+        self.warn_unit_function_value(&*expr_let.expr)?;
+
         // NB: assignments "move" the value being assigned.
         self.compile((&*expr_let.expr, Needs::Value))?;
 
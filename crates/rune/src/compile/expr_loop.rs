@@ -1,7 +1,7 @@
 use crate::ast;
 use crate::compiler::{Compiler, Needs};
 use crate::error::CompileResult;
-use crate::loops::Loop;
+use crate::loops::{Loop, LoopKind};
 use crate::traits::Compile;
 use runestick::Inst;
 
@@ -15,16 +15,24 @@ impl Compile<(&ast::ExprLoop, Needs)> for Compiler<'_> {
         let end_label = self.asm.new_label("loop_end");
         let break_label = self.asm.new_label("loop_break");
 
+        let total_var_count = self.scopes.last(span)?.total_var_count;
+
         let _guard = self.loops.push(Loop {
+            kind: LoopKind::Loop,
+            span,
             label: expr_loop.label.map(|(label, _)| label),
+            continue_label: start_label,
+            continue_var_count: total_var_count,
             break_label,
-            total_var_count: self.scopes.last(span)?.total_var_count,
+            total_var_count,
             needs,
             drop: None,
+            exits: false,
         });
 
         self.asm.label(start_label)?;
         self.compile((&*expr_loop.body, Needs::None))?;
+        self.warn_infinite_loop(span);
         self.asm.jump(start_label, span);
         self.asm.label(end_label)?;
 
@@ -1,15 +1,40 @@
 use crate::assembly::Assembly;
 use crate::ast;
-use crate::compiler::{Compiler, Needs};
+use crate::collections::HashSet;
+use crate::compiler::{BranchValue, Compiler, Needs};
 use crate::error::CompileResult;
 use crate::traits::Compile;
-use runestick::Inst;
+use runestick::{CompileMeta, Inst, Item};
+
+/// What values a single match pattern covers.
+enum Coverage {
+    /// The pattern matches any value.
+    All,
+    /// The pattern matches any value of the given enum variant.
+    Variant { enum_item: Item, item: Item },
+    /// The pattern matches some values of the given enum variant.
+    PartialVariant { enum_item: Item, item: Item },
+    /// The pattern matches some other set of values.
+    Other,
+}
 
 impl Compile<(&ast::ExprMatch, Needs)> for Compiler<'_> {
     fn compile(&mut self, (expr_match, needs): (&ast::ExprMatch, Needs)) -> CompileResult<()> {
         let span = expr_match.span();
         log::trace!("ExprMatch => {:?}", self.source.source(span));
 
+        self.check_match_coverage(expr_match)?;
+
+        if needs.value() {
+            let branches = expr_match
+                .branches
+                .iter()
+                .map(|(branch, _)| (branch.body.span(), BranchValue::of_expr(&branch.body)))
+                .collect::<Vec<_>>();
+
+            self.check_branch_values(&branches)?;
+        }
+
         let new_scope = self.scopes.child(span)?;
         let expected_scopes = self.scopes.push(new_scope);
 
@@ -76,6 +101,7 @@ impl Compile<(&ast::ExprMatch, Needs)> for Compiler<'_> {
             self.asm.label(*label)?;
 
             let expected = self.scopes.push(scope.clone());
+            self.coverage(branch.body.span());
             self.compile((&*branch.body, needs))?;
             self.clean_last_scope(span, expected, needs)?;
 
@@ -91,3 +117,145 @@ impl Compile<(&ast::ExprMatch, Needs)> for Compiler<'_> {
         Ok(())
     }
 }
+
+impl Compiler<'_> {
+    /// Warn about match arms which can never be reached, and about matches
+    /// over script enums which don't cover all of their variants.
+    fn check_match_coverage(&mut self, expr_match: &ast::ExprMatch) -> CompileResult<()> {
+        let mut catch_all = false;
+        let mut only_variants = true;
+        let mut enum_items = HashSet::new();
+        let mut covered = HashSet::new();
+
+        for (branch, _) in &expr_match.branches {
+            let span = branch.pat.span();
+
+            if catch_all {
+                self.warnings
+                    .unreachable_match_arm(self.source_id, span, self.context());
+                continue;
+            }
+
+            let guarded = branch.condition.is_some();
+
+            match self.pat_coverage(&branch.pat)? {
+                Coverage::All => {
+                    catch_all = !guarded;
+                }
+                Coverage::Variant { enum_item, item } => {
+                    if covered.contains(&item) {
+                        self.warnings
+                            .unreachable_match_arm(self.source_id, span, self.context());
+                    } else if !guarded {
+                        covered.insert(item);
+                    }
+
+                    enum_items.insert(enum_item);
+                }
+                Coverage::PartialVariant { enum_item, item } => {
+                    if covered.contains(&item) {
+                        self.warnings
+                            .unreachable_match_arm(self.source_id, span, self.context());
+                    }
+
+                    enum_items.insert(enum_item);
+                }
+                Coverage::Other => {
+                    only_variants = false;
+                }
+            }
+        }
+
+        if catch_all || !only_variants || enum_items.len() != 1 {
+            return Ok(());
+        }
+
+        let enum_item = match enum_items.into_iter().next() {
+            Some(enum_item) => enum_item,
+            None => return Ok(()),
+        };
+
+        let variants = match self.query.enum_variants(&enum_item) {
+            Some(variants) => variants,
+            None => return Ok(()),
+        };
+
+        let missing = variants
+            .iter()
+            .filter(|item| !covered.contains(*item))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if !missing.is_empty() {
+            let span = expr_match.match_.span().join(expr_match.expr.span());
+            self.warnings
+                .match_missing_variants(self.source_id, span, missing, self.context());
+        }
+
+        Ok(())
+    }
+
+    /// Calculate what values the given pattern covers.
+    fn pat_coverage(&mut self, pat: &ast::Pat) -> CompileResult<Coverage> {
+        let (path, irrefutable) = match pat {
+            ast::Pat::PatIgnore(..) => return Ok(Coverage::All),
+            ast::Pat::PatPath(pat_path) => (&pat_path.path, true),
+            ast::Pat::PatTuple(pat_tuple) => {
+                let path = match &pat_tuple.path {
+                    Some(path) => path,
+                    None => return Ok(Coverage::Other),
+                };
+
+                let mut irrefutable = true;
+
+                for (pat, _) in &pat_tuple.items {
+                    irrefutable &= self.is_irrefutable(pat)?;
+                }
+
+                (path, irrefutable)
+            }
+            ast::Pat::PatObject(pat_object) => {
+                let path = match &pat_object.ident {
+                    ast::LitObjectIdent::Named(path) => path,
+                    ast::LitObjectIdent::Anonymous(..) => return Ok(Coverage::Other),
+                };
+
+                let mut irrefutable = true;
+
+                for (field, _) in &pat_object.fields {
+                    if let Some((_, pat)) = &field.binding {
+                        irrefutable &= self.is_irrefutable(pat)?;
+                    }
+                }
+
+                (path, irrefutable)
+            }
+            _ => return Ok(Coverage::Other),
+        };
+
+        let item = self.convert_path_to_item(path)?;
+
+        let (enum_item, item) = match self.lookup_meta(&item, path.span())? {
+            Some(CompileMeta::TupleVariant {
+                enum_item, tuple, ..
+            }) => (enum_item, tuple.item),
+            Some(CompileMeta::StructVariant {
+                enum_item, object, ..
+            }) => (enum_item, object.item),
+            Some(..) => return Ok(Coverage::Other),
+            None if path.try_as_ident().is_some() => return Ok(Coverage::All),
+            None => return Ok(Coverage::Other),
+        };
+
+        Ok(if irrefutable {
+            Coverage::Variant { enum_item, item }
+        } else {
+            Coverage::PartialVariant { enum_item, item }
+        })
+    }
+
+    /// Test if the given nested pattern matches any value.
+    fn is_irrefutable(&mut self, pat: &ast::Pat) -> CompileResult<bool> {
+        Ok(matches!(self.pat_coverage(pat)?, Coverage::All))
+    }
+}
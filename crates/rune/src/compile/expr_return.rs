@@ -10,6 +10,8 @@ impl Compile<(&ast::ExprReturn, Needs)> for Compiler<'_> {
         let span = return_expr.span();
         log::trace!("ExprReturn => {:?}", self.source.source(span));
 
+        self.loops.mark_exit_all();
+
         // NB: drop any loop temporaries.
         for l in self.loops.iter() {
             if let Some(offset) = l.drop {
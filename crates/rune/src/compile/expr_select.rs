@@ -27,7 +27,8 @@ impl Compile<(&ast::ExprSelect, Needs)> for Compiler<'_> {
             self.compile((&*branch.expr, Needs::Value))?;
         }
 
-        self.asm.push(Inst::Select { len }, span);
+        let default = expr_select.default_branch.is_some();
+        self.asm.push(Inst::Select { len, default }, span);
 
         for (branch, (label, _)) in branches.iter().enumerate() {
             self.asm.jump_if_branch(branch as i64, *label, span);
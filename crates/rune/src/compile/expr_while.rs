@@ -1,7 +1,7 @@
 use crate::ast;
 use crate::compiler::{Compiler, Needs};
 use crate::error::CompileResult;
-use crate::loops::Loop;
+use crate::loops::{Loop, LoopKind};
 use crate::traits::Compile;
 use runestick::Inst;
 
@@ -16,12 +16,19 @@ impl Compile<(&ast::ExprWhile, Needs)> for Compiler<'_> {
         let end_label = self.asm.new_label("while_end");
         let break_label = self.asm.new_label("while_break");
 
+        let total_var_count = self.scopes.last(span)?.total_var_count;
+
         let _guard = self.loops.push(Loop {
+            kind: LoopKind::While,
+            span,
             label: expr_while.label.map(|(label, _)| label),
+            continue_label: start_label,
+            continue_var_count: total_var_count,
             break_label,
-            total_var_count: self.scopes.last(span)?.total_var_count,
+            total_var_count,
             needs,
             drop: None,
+            exits: false,
         });
 
         self.asm.label(start_label)?;
@@ -34,6 +41,12 @@ impl Compile<(&ast::ExprWhile, Needs)> for Compiler<'_> {
         self.compile((&*expr_while.body, Needs::None))?;
         self.clean_last_scope(span, expected, Needs::None)?;
 
+        if let ast::Condition::Expr(expr) = &expr_while.condition {
+            if let ast::Expr::LitBool(ast::LitBool { value: true, .. }) = &**expr {
+                self.warn_infinite_loop(span);
+            }
+        }
+
         self.asm.jump(start_label, span);
         self.asm.label(end_label)?;
 
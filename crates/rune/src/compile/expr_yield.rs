@@ -10,6 +10,10 @@ impl Compile<(&ast::ExprYield, Needs)> for Compiler<'_> {
         let span = expr_yield.span();
         log::trace!("ExprYield => {:?}", self.source.source(span));
 
+        // NB: a generator suspended in a loop might never be resumed, so the
+        // loop doesn't run forever on its own.
+        self.loops.mark_exit_all();
+
         if let Some(expr) = &expr_yield.expr {
             self.compile((&**expr, Needs::Value))?;
             self.asm.push(Inst::Yield, span);
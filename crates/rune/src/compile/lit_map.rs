@@ -0,0 +1,49 @@
+use crate::ast;
+use crate::compiler::{Compiler, Needs};
+use crate::error::CompileResult;
+use crate::traits::Compile;
+use runestick::Inst;
+
+/// Compile a literal hash map.
+impl Compile<(&ast::LitMap, Needs)> for Compiler<'_> {
+    fn compile(&mut self, (lit_map, needs): (&ast::LitMap, Needs)) -> CompileResult<()> {
+        let span = lit_map.span();
+        log::trace!("LitMap => {:?}", self.source.source(span));
+
+        if !needs.value() && lit_map.is_const() {
+            // Don't encode unecessary literals.
+            return Ok(());
+        }
+
+        let scope = self.scopes.child(span)?;
+        let guard = self.scopes.push(scope);
+
+        for entry in &lit_map.entries {
+            self.compile((&entry.key, Needs::Value))?;
+            self.scopes.decl_anon(span)?;
+            self.compile((&entry.value, Needs::Value))?;
+
+            // Evaluate the expressions one by one, then pop them to cause any
+            // side effects (without creating a map).
+            if needs.value() {
+                self.scopes.decl_anon(span)?;
+            } else {
+                self.asm.push(Inst::Pop, span);
+                self.asm.push(Inst::Pop, span);
+                self.scopes.last_mut(span)?.undecl_anon(1, span)?;
+            }
+        }
+
+        self.scopes.pop(guard, span)?;
+
+        // No need to create a map if it's not needed.
+        if !needs.value() {
+            self.warnings.not_used(self.source_id, span, self.context());
+            return Ok(());
+        }
+
+        let count = lit_map.entries.len();
+        self.asm.push(Inst::HashMap { count }, span);
+        Ok(())
+    }
+}
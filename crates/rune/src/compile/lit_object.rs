@@ -6,7 +6,7 @@ use crate::{
     traits::{Compile, Resolve as _},
     CompileError,
 };
-use runestick::{CompileMeta, Hash, Inst, Item, Span};
+use runestick::{CompileMeta, Inst, Item, Span};
 
 /// Compile a literal object.
 impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
@@ -19,13 +19,27 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
             return Ok(());
         }
 
+        if let ast::LitObjectIdent::Anonymous(..) = &lit_object.ident {
+            if lit_object.has_computed_keys() {
+                return compile_dynamic_object(self, lit_object, needs);
+            }
+        }
+
         let mut keys = Vec::new();
         let mut check_keys = Vec::new();
         let mut keys_dup = HashMap::new();
 
         for assign in &lit_object.assignments {
             let span = assign.span();
-            let key = assign.key.resolve(&*self.source)?.to_string();
+
+            let key = match &assign.key {
+                ast::LitObjectFieldKey::Key(key) => key,
+                ast::LitObjectFieldKey::Computed(key) => {
+                    return Err(CompileError::UnsupportedComputedKey { span: key.span() });
+                }
+            };
+
+            let key = key.resolve(&*self.source)?.to_string();
             keys.push(key.clone());
             check_keys.push((key.clone(), assign.key.span()));
 
@@ -38,6 +52,16 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
             }
         }
 
+        if needs.value() && !lit_object.assignments.is_empty() {
+            if let Some(value) = self.static_object(lit_object)? {
+                self.compile_static(value, span);
+                return Ok(());
+            }
+        }
+
+        let scope = self.scopes.child(span)?;
+        let guard = self.scopes.push(scope);
+
         for assign in lit_object.assignments.iter() {
             let span = assign.span();
 
@@ -46,19 +70,24 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
 
                 // Evaluate the expressions one by one, then pop them to cause any
                 // side effects (without creating an object).
-                if !needs.value() {
+                if needs.value() {
+                    self.scopes.decl_anon(span)?;
+                } else {
                     self.asm.push(Inst::Pop, span);
                 }
-            } else {
-                let key = assign.key.resolve(&*self.source)?;
+            } else if let ast::LitObjectFieldKey::Key(key) = &assign.key {
+                let key = key.resolve(&*self.source)?;
                 let var = self.scopes.get_var(&*key, span)?;
 
                 if needs.value() {
                     var.copy(&mut self.asm, span, format!("name `{}`", key));
+                    self.scopes.decl_anon(span)?;
                 }
             }
         }
 
+        self.scopes.pop(guard, span)?;
+
         // No need to encode an object since the value is not needed.
         if !needs.value() {
             self.warnings.not_used(self.source_id, span, self.context());
@@ -87,7 +116,7 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
                             &object.item,
                         )?;
 
-                        let hash = Hash::type_hash(&object.item);
+                        let hash = object.item.type_hash();
                         self.asm.push(Inst::TypedObject { hash, slot }, span);
                     }
                     CompileMeta::StructVariant {
@@ -100,8 +129,8 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
                             &object.item,
                         )?;
 
-                        let enum_hash = Hash::type_hash(&enum_item);
-                        let hash = Hash::type_hash(&object.item);
+                        let enum_hash = enum_item.type_hash();
+                        let hash = object.item.type_hash();
 
                         self.asm.push(
                             Inst::VariantObject {
@@ -129,6 +158,87 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
     }
 }
 
+/// Compile an anonymous object with computed keys.
+///
+/// Since the keys aren't known ahead of time, each key is pushed onto the stack
+/// followed by its value, in the order in which they're declared.
+fn compile_dynamic_object(
+    compiler: &mut Compiler<'_>,
+    lit_object: &ast::LitObject,
+    needs: Needs,
+) -> CompileResult<()> {
+    let span = lit_object.span();
+    let mut keys_dup = HashMap::new();
+
+    let scope = compiler.scopes.child(span)?;
+    let guard = compiler.scopes.push(scope);
+
+    for assign in &lit_object.assignments {
+        let span = assign.span();
+
+        match &assign.key {
+            ast::LitObjectFieldKey::Key(key) => {
+                let key = key.resolve(&*compiler.source)?;
+
+                if let Some(existing) = keys_dup.insert(key.to_string(), span) {
+                    return Err(CompileError::DuplicateObjectKey {
+                        span,
+                        existing,
+                        object: span,
+                    });
+                }
+
+                if needs.value() {
+                    let slot = compiler.unit.borrow_mut().new_static_string(&*key)?;
+                    compiler.asm.push(Inst::String { slot }, span);
+                    compiler.scopes.decl_anon(span)?;
+                }
+            }
+            ast::LitObjectFieldKey::Computed(key) => {
+                compiler.compile((&*key.expr, Needs::Value))?;
+
+                if needs.value() {
+                    compiler.scopes.decl_anon(span)?;
+                } else {
+                    compiler.asm.push(Inst::Pop, span);
+                }
+            }
+        }
+
+        if let Some((_, expr)) = &assign.assign {
+            compiler.compile((expr, Needs::Value))?;
+
+            if needs.value() {
+                compiler.scopes.decl_anon(span)?;
+            } else {
+                compiler.asm.push(Inst::Pop, span);
+            }
+        } else if let ast::LitObjectFieldKey::Key(key) = &assign.key {
+            let key = key.resolve(&*compiler.source)?;
+            let var = compiler.scopes.get_var(&*key, span)?;
+
+            if needs.value() {
+                var.copy(&mut compiler.asm, span, format!("name `{}`", key));
+                compiler.scopes.decl_anon(span)?;
+            }
+        }
+    }
+
+    compiler.scopes.pop(guard, span)?;
+
+    // No need to encode an object since the value is not needed.
+    if !needs.value() {
+        compiler
+            .warnings
+            .not_used(compiler.source_id, span, compiler.context());
+        return Ok(());
+    }
+
+    let count = lit_object.assignments.len();
+    compiler.asm.push(Inst::DynamicObject { count }, span);
+    Ok(())
+}
+
 fn check_object_fields(
     fields: Option<&HashSet<String>>,
     check_keys: Vec<(String, Span)>,
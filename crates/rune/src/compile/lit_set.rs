@@ -0,0 +1,45 @@
+use crate::ast;
+use crate::compiler::{Compiler, Needs};
+use crate::error::CompileResult;
+use crate::traits::Compile;
+use runestick::Inst;
+
+/// Compile a literal hash set.
+impl Compile<(&ast::LitSet, Needs)> for Compiler<'_> {
+    fn compile(&mut self, (lit_set, needs): (&ast::LitSet, Needs)) -> CompileResult<()> {
+        let span = lit_set.span();
+        log::trace!("LitSet => {:?}", self.source.source(span));
+
+        if !needs.value() && lit_set.is_const() {
+            // Don't encode unecessary literals.
+            return Ok(());
+        }
+
+        let scope = self.scopes.child(span)?;
+        let guard = self.scopes.push(scope);
+
+        for expr in &lit_set.items {
+            self.compile((expr, Needs::Value))?;
+
+            // Evaluate the expressions one by one, then pop them to cause any
+            // side effects (without creating a set).
+            if needs.value() {
+                self.scopes.decl_anon(span)?;
+            } else {
+                self.asm.push(Inst::Pop, span);
+            }
+        }
+
+        self.scopes.pop(guard, span)?;
+
+        // No need to create a set if it's not needed.
+        if !needs.value() {
+            self.warnings.not_used(self.source_id, span, self.context());
+            return Ok(());
+        }
+
+        let count = lit_set.items.len();
+        self.asm.push(Inst::HashSet { count }, span);
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@ use crate::ast;
 use crate::compiler::{Compiler, Needs};
 use crate::error::CompileResult;
 use crate::traits::{Compile, Resolve as _};
-use runestick::Inst;
+use runestick::{Inst, Span};
 
 /// Compile a literal template string.
 impl Compile<(&ast::LitTemplate, Needs)> for Compiler<'_> {
@@ -23,6 +23,11 @@ impl Compile<(&ast::LitTemplate, Needs)> for Compiler<'_> {
                 .template_without_expansions(self.source_id, span, self.context());
         }
 
+        if let Some(string) = self.constant_template(&template)? {
+            self.compile_constant_string(&string, span)?;
+            return Ok(());
+        }
+
         let scope = self.scopes.child(span)?;
         let expected = self.scopes.push(scope);
 
@@ -52,3 +57,96 @@ impl Compile<(&ast::LitTemplate, Needs)> for Compiler<'_> {
         Ok(())
     }
 }
+
+impl Compiler<'_> {
+    /// Push a string which has been constructed at compile time.
+    ///
+    /// The static string is converted into an owned string, since that is
+    /// what templates and string concatenation produce when they're evaluated
+    /// at runtime.
+    pub(crate) fn compile_constant_string(
+        &mut self,
+        string: &str,
+        span: Span,
+    ) -> CompileResult<()> {
+        let slot = self.unit.borrow_mut().new_static_string(string)?;
+        self.asm.push(Inst::String { slot }, span);
+
+        self.asm.push(
+            Inst::StringConcat {
+                len: 1,
+                size_hint: string.len(),
+            },
+            span,
+        );
+
+        Ok(())
+    }
+
+    /// Evaluate the given expression into a string at compile time, if all of
+    /// its parts are constant.
+    pub(crate) fn constant_string(&self, expr: &ast::Expr) -> CompileResult<Option<String>> {
+        Ok(match expr {
+            ast::Expr::LitStr(lit_str) => Some(lit_str.resolve(&*self.source)?.into_owned()),
+            ast::Expr::LitTemplate(lit_template) => {
+                let template = lit_template.resolve(&*self.source)?;
+                self.constant_template(&template)?
+            }
+            ast::Expr::ExprBinary(expr_binary) => self.constant_concat(expr_binary)?,
+            ast::Expr::ExprGroup(expr_group) => self.constant_string(&*expr_group.expr)?,
+            _ => None,
+        })
+    }
+
+    /// Evaluate a string concatenation like `"a" + "b"` at compile time, if
+    /// both sides are constant strings.
+    pub(crate) fn constant_concat(
+        &self,
+        expr_binary: &ast::ExprBinary,
+    ) -> CompileResult<Option<String>> {
+        if !matches!(expr_binary.op, ast::BinOp::Add) {
+            return Ok(None);
+        }
+
+        let mut lhs = match self.constant_string(&*expr_binary.lhs)? {
+            Some(lhs) => lhs,
+            None => return Ok(None),
+        };
+
+        let rhs = match self.constant_string(&*expr_binary.rhs)? {
+            Some(rhs) => rhs,
+            None => return Ok(None),
+        };
+
+        lhs.push_str(&rhs);
+        Ok(Some(lhs))
+    }
+
+    /// Evaluate a template at compile time, if all of its expansions are
+    /// constant strings or integers.
+    fn constant_template(&self, template: &ast::Template) -> CompileResult<Option<String>> {
+        let mut buf = String::with_capacity(template.size_hint);
+
+        for c in template.components.iter() {
+            match c {
+                ast::TemplateComponent::String(string) => {
+                    buf.push_str(string);
+                }
+                ast::TemplateComponent::Expr(expr) => match &**expr {
+                    ast::Expr::LitNumber(lit_number) => match lit_number.resolve(&*self.source)? {
+                        ast::Number::Integer(integer) => {
+                            buf.push_str(&integer.to_string());
+                        }
+                        ast::Number::Float(..) => return Ok(None),
+                    },
+                    expr => match self.constant_string(expr)? {
+                        Some(string) => buf.push_str(&string),
+                        None => return Ok(None),
+                    },
+                },
+            }
+        }
+
+        Ok(Some(buf))
+    }
+}
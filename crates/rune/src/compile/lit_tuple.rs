@@ -2,7 +2,7 @@ use crate::ast;
 use crate::compiler::{Compiler, Needs};
 use crate::error::CompileResult;
 use crate::traits::Compile;
-use runestick::Inst;
+use runestick::{Inst, StaticValue};
 
 /// Compile a literal tuple.
 impl Compile<(&ast::LitTuple, Needs)> for Compiler<'_> {
@@ -16,10 +16,25 @@ impl Compile<(&ast::LitTuple, Needs)> for Compiler<'_> {
             return Ok(());
         }
 
+        if lit_tuple.is_const() && !lit_tuple.items.is_empty() {
+            let exprs = lit_tuple.items.iter().map(|(expr, _)| expr);
+
+            if let Some(values) = self.static_values(exprs)? {
+                self.compile_static(StaticValue::Tuple(values), span);
+                return Ok(());
+            }
+        }
+
+        let scope = self.scopes.child(span)?;
+        let guard = self.scopes.push(scope);
+
         for (expr, _) in lit_tuple.items.iter() {
             self.compile((expr, Needs::Value))?;
+            self.scopes.decl_anon(span)?;
         }
 
+        self.scopes.pop(guard, span)?;
+
         self.asm.push(
             Inst::Tuple {
                 count: lit_tuple.items.len(),
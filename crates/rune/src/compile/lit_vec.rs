@@ -2,7 +2,7 @@ use crate::ast;
 use crate::compiler::{Compiler, Needs};
 use crate::error::CompileResult;
 use crate::traits::Compile;
-use runestick::Inst;
+use runestick::{Inst, StaticValue};
 
 /// Compile a literal vector.
 impl Compile<(&ast::LitVec, Needs)> for Compiler<'_> {
@@ -17,16 +17,30 @@ impl Compile<(&ast::LitVec, Needs)> for Compiler<'_> {
 
         let count = lit_vec.items.len();
 
+        if needs.value() && lit_vec.is_const() && count > 0 {
+            if let Some(values) = self.static_values(&lit_vec.items)? {
+                self.compile_static(StaticValue::Vec(values), span);
+                return Ok(());
+            }
+        }
+
+        let scope = self.scopes.child(span)?;
+        let guard = self.scopes.push(scope);
+
         for expr in lit_vec.items.iter() {
             self.compile((expr, Needs::Value))?;
 
             // Evaluate the expressions one by one, then pop them to cause any
             // side effects (without creating an object).
-            if !needs.value() {
+            if needs.value() {
+                self.scopes.decl_anon(span)?;
+            } else {
                 self.asm.push(Inst::Pop, span);
             }
         }
 
+        self.scopes.pop(guard, span)?;
+
         // No need to create a vector if it's not needed.
         if !needs.value() {
             self.warnings.not_used(self.source_id, span, self.context());
@@ -6,6 +6,7 @@ mod expr_block;
 mod expr_break;
 mod expr_call;
 mod expr_closure;
+mod expr_continue;
 mod expr_field_access;
 mod expr_for;
 mod expr_if;
@@ -26,8 +27,10 @@ mod lit_bool;
 mod lit_byte;
 mod lit_byte_str;
 mod lit_char;
+mod lit_map;
 mod lit_number;
 mod lit_object;
+mod lit_set;
 mod lit_str;
 mod lit_template;
 mod lit_tuple;
@@ -1,11 +1,13 @@
 use crate::assembly::Assembly;
 use crate::ast;
-use crate::collections::HashMap;
+use crate::collections::{HashMap, HashSet};
 use crate::error::CompileError;
 use crate::traits::{Compile as _, Resolve as _};
 use crate::unit_builder::UnitBuilder;
 use crate::{MacroContext, SourceId};
-use runestick::{CompileMeta, Context, Inst, Item, Label, Source, Span, TypeCheck};
+use runestick::{
+    Call, CompileMeta, Context, Inst, Item, Label, Protocol, Source, Span, StaticValue, TypeCheck,
+};
 use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::rc::Rc;
@@ -16,10 +18,12 @@ use crate::index_scopes::IndexScopes;
 use crate::items::Items;
 use crate::load_error::{LoadError, LoadErrorKind};
 use crate::loops::Loops;
+use crate::optimize;
 use crate::options::Options;
-use crate::query::{Build, BuildEntry, Query};
+use crate::query::{Build, BuildEntry, InstanceFunction, Query, Symbols};
 use crate::scopes::{Scope, ScopeGuard, Scopes};
 use crate::sources::Sources;
+use crate::stack_effect::{self, Frame, StackError};
 use crate::warning::Warnings;
 use std::sync::Arc;
 
@@ -40,6 +44,45 @@ impl Needs {
     }
 }
 
+/// What a single branch of an `if` or a `match` is known to evaluate to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BranchValue {
+    /// The branch definitely produces a value.
+    Value,
+    /// The branch definitely produces nothing.
+    Nothing,
+    /// The branch might produce either, or diverges.
+    Unknown,
+}
+
+impl BranchValue {
+    /// Classify a branch which is a block.
+    pub(crate) fn of_block(block: &ast::ExprBlock) -> Self {
+        if block.diverging_expr().is_some() {
+            Self::Unknown
+        } else if block.produces_nothing() {
+            Self::Nothing
+        } else if block.produces_value() {
+            Self::Value
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// Classify a branch which is an expression.
+    pub(crate) fn of_expr(expr: &ast::Expr) -> Self {
+        if expr.diverges() {
+            Self::Unknown
+        } else if expr.produces_nothing() {
+            Self::Nothing
+        } else if expr.produces_value() {
+            Self::Value
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
 /// Compile the given source with default options.
 pub fn compile(
     context: &Context,
@@ -47,24 +90,47 @@ pub fn compile(
     unit: &Rc<RefCell<UnitBuilder>>,
     warnings: &mut Warnings,
 ) -> Result<(), LoadError> {
-    compile_with_options(context, sources, &Default::default(), unit, warnings)?;
+    compile_with_options(
+        context,
+        sources,
+        &Default::default(),
+        unit,
+        warnings,
+        &mut Symbols::disabled(),
+    )?;
     Ok(())
 }
 
 /// Encode the given object into a collection of asm.
+///
+/// Information on declared and referenced items is collected into `symbols`.
 pub fn compile_with_options(
     context: &Context,
     sources: &mut Sources,
     options: &Options,
     unit: &Rc<RefCell<UnitBuilder>>,
     warnings: &mut Warnings,
+    symbols: &mut Symbols,
+) -> Result<(), LoadError> {
+    // Query system to populate.
+    let mut query = Query::new(unit.clone(), std::mem::take(symbols));
+    let result = compile_sources(context, sources, options, unit, warnings, &mut query);
+    *symbols = std::mem::take(&mut query.symbols);
+    result
+}
+
+fn compile_sources(
+    context: &Context,
+    sources: &mut Sources,
+    options: &Options,
+    unit: &Rc<RefCell<UnitBuilder>>,
+    warnings: &mut Warnings,
+    query: &mut Query,
 ) -> Result<(), LoadError> {
     // Imports to process.
     let mut imports = VecDeque::new();
     // Macros to expand.
     let mut macros = VecDeque::new();
-    // Query system to populate.
-    let mut query = Query::new(unit.clone());
     // Files loaded while loading modules.
     let mut loaded = HashMap::<Item, (SourceId, Span)>::new();
     // Expanded expressions.
@@ -76,7 +142,12 @@ pub fn compile_with_options(
             None => return Err(LoadError::internal("missing queued source by id")),
         };
 
-        let file = match crate::parse_all::<ast::DeclFile>(source.as_str()) {
+        let parsed = match sources.take_parsed(source_id) {
+            Some(file) => Ok(file),
+            None => crate::parse_all::<ast::DeclFile>(source.as_str()),
+        };
+
+        let file = match parsed {
             Ok(file) => file,
             Err(error) => {
                 return Err(LoadError::from(LoadErrorKind::ParseError {
@@ -88,7 +159,7 @@ pub fn compile_with_options(
 
         let mut indexer = Indexer {
             loaded: &mut loaded,
-            query: &mut query,
+            query: &mut *query,
             imports: &mut imports,
             macros: &mut macros,
             sources,
@@ -112,7 +183,8 @@ pub fn compile_with_options(
         while let Some(import) = imports.pop_front() {
             let source_id = import.source_id;
 
-            if let Err(error) = import.process(context, &mut *unit.borrow_mut()) {
+            if let Err(error) = import.process(context, &mut *unit.borrow_mut(), &mut query.symbols)
+            {
                 return Err(LoadError::from(LoadErrorKind::CompileError {
                     error,
                     source_id,
@@ -147,7 +219,7 @@ pub fn compile_with_options(
             // index the newly added macros.
             let mut indexer = Indexer {
                 loaded: &mut loaded,
-                query: &mut query,
+                query: &mut *query,
                 imports: &mut imports,
                 macros: &mut macros,
                 sources,
@@ -161,7 +233,7 @@ pub fn compile_with_options(
 
             match kind {
                 MacroKind::Expr => {
-                    let expr = match compiler.eval_macro::<ast::Expr>(ast) {
+                    let expr = match compiler.eval_expr_macro(ast) {
                         Ok(expr) => expr,
                         Err(error) => {
                             return Err(LoadError::from(LoadErrorKind::CompileError {
@@ -190,28 +262,155 @@ pub fn compile_with_options(
 
     verify_imports(context, &mut *unit.borrow_mut())?;
 
-    while let Some(entry) = query.queue.pop_front() {
-        let source_id = entry.source_id;
+    loop {
+        while let Some(entry) = query.queue.pop_front() {
+            let source_id = entry.source_id;
 
-        if let Err(error) = compile_entry(
-            context,
-            options,
-            unit,
-            warnings,
-            &mut query,
-            entry,
-            &expanded_expr,
-        ) {
-            return Err(LoadError::from(LoadErrorKind::CompileError {
-                source_id,
-                error,
-            }));
+            if let Err(error) = compile_entry(
+                context,
+                options,
+                unit,
+                warnings,
+                query,
+                entry,
+                &expanded_expr,
+            ) {
+                return Err(LoadError::from(LoadErrorKind::CompileError {
+                    source_id,
+                    error,
+                }));
+            }
+        }
+
+        // NB: when collecting symbols, functions which are never used are
+        // built as well so that the references inside of them are recorded.
+        if !query.symbols.is_enabled() {
+            break;
+        }
+
+        let unused = query.unused_functions();
+
+        if unused.is_empty() {
+            break;
+        }
+
+        for (item, span, source_id) in unused {
+            if let Err(error) = query.query_meta(&item, span) {
+                return Err(LoadError::from(LoadErrorKind::CompileError {
+                    source_id,
+                    error,
+                }));
+            }
         }
     }
 
     Ok(())
 }
 
+/// Built-in protocols which can be implemented by instance functions declared
+/// in scripts, with the number of arguments they take including `self`.
+const SCRIPT_PROTOCOLS: &[(&str, Protocol, usize)] = &[
+    ("ADD", runestick::ADD, 2),
+    ("ADD_ASSIGN", runestick::ADD_ASSIGN, 2),
+    ("SUB", runestick::SUB, 2),
+    ("SUB_ASSIGN", runestick::SUB_ASSIGN, 2),
+    ("MUL", runestick::MUL, 2),
+    ("MUL_ASSIGN", runestick::MUL_ASSIGN, 2),
+    ("DIV", runestick::DIV, 2),
+    ("DIV_ASSIGN", runestick::DIV_ASSIGN, 2),
+    ("REM", runestick::REM, 2),
+    ("REM_ASSIGN", runestick::REM_ASSIGN, 2),
+    ("BIT_AND", runestick::BIT_AND, 2),
+    ("BIT_AND_ASSIGN", runestick::BIT_AND_ASSIGN, 2),
+    ("BIT_XOR", runestick::BIT_XOR, 2),
+    ("BIT_XOR_ASSIGN", runestick::BIT_XOR_ASSIGN, 2),
+    ("BIT_OR", runestick::BIT_OR, 2),
+    ("BIT_OR_ASSIGN", runestick::BIT_OR_ASSIGN, 2),
+    ("SHL", runestick::SHL, 2),
+    ("SHL_ASSIGN", runestick::SHL_ASSIGN, 2),
+    ("SHR", runestick::SHR, 2),
+    ("SHR_ASSIGN", runestick::SHR_ASSIGN, 2),
+    ("NEG", runestick::NEG, 1),
+    ("EQ", runestick::EQ, 2),
+    ("INDEX_GET", runestick::INDEX_GET, 2),
+    ("INTO_ITER", runestick::INTO_ITER, 1),
+    ("NEXT", runestick::NEXT, 1),
+];
+
+/// Resolve the protocol an instance function implements through a
+/// `#[protocol(NAME)]` attribute, if it has one.
+///
+/// Built-in protocols are named by their constant, like `ADD`, while custom
+/// protocols are named as they were declared.
+fn protocol_attribute(
+    context: &Context,
+    source: &Source,
+    f: &InstanceFunction,
+) -> CompileResult<Option<Protocol>> {
+    let attribute = match f.ast.attributes.as_slice() {
+        [] => return Ok(None),
+        [attribute] => attribute,
+        [_, attribute, ..] => {
+            return Err(CompileError::UnsupportedAttribute {
+                span: attribute.span(),
+            })
+        }
+    };
+
+    let ident = match &attribute.args {
+        Some(args) if attribute.name.resolve(source)? == "protocol" => {
+            match args.items.as_slice() {
+                [(ident, _)] => ident,
+                _ => {
+                    return Err(CompileError::UnsupportedAttribute {
+                        span: attribute.span(),
+                    })
+                }
+            }
+        }
+        _ => {
+            return Err(CompileError::UnsupportedAttribute {
+                span: attribute.span(),
+            })
+        }
+    };
+
+    let name = ident.resolve(source)?;
+    let span = f.ast.span();
+
+    let (protocol, expected) = match SCRIPT_PROTOCOLS.iter().find(|(n, ..)| *n == name) {
+        Some((_, protocol, args)) => (*protocol, Some(*args)),
+        None => match context.lookup_protocol(name) {
+            Some(protocol) => (protocol, None),
+            None => {
+                return Err(CompileError::UnknownProtocol {
+                    span: ident.span(),
+                    name: name.to_owned(),
+                })
+            }
+        },
+    };
+
+    let actual = f.ast.args.items.len();
+
+    if let Some(expected) = expected {
+        if actual != expected {
+            return Err(CompileError::ProtocolArgumentCount {
+                span,
+                protocol,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    if !matches!(f.call, Call::Immediate) {
+        return Err(CompileError::UnsupportedProtocolCall { span, protocol });
+    }
+
+    Ok(Some(protocol))
+}
+
 fn compile_entry(
     context: &Context,
     options: &Options,
@@ -255,8 +454,16 @@ fn compile_entry(
             compiler.contexts.push(span);
             compiler.compile((f.ast, false))?;
 
+            let frame = Frame::new(count);
+
+            if options.optimize {
+                optimize::optimize(&mut asm, frame, &*unit.borrow());
+            }
+
+            let stack = max_stack_depth(&asm, frame, &*unit.borrow(), span)?;
+
             unit.borrow_mut()
-                .new_function(source_id, item, count, asm, f.call, args)?;
+                .new_function(source_id, item, count, stack, asm, f.call, args)?;
         }
         Build::InstanceFunction(f) => {
             let args = format_fn_args(&*source, f.ast.args.items.iter().map(|(a, _)| a))?;
@@ -268,8 +475,9 @@ fn compile_entry(
             let source = compiler.source.clone();
             let name = f.ast.name.resolve(&*source)?;
 
+            // NB: the reference to the impl item is recorded when indexing.
             let meta = compiler
-                .lookup_meta(&f.impl_item, f.instance_span)?
+                .find_meta(&f.impl_item, f.instance_span)?
                 .ok_or_else(|| CompileError::MissingType {
                     span: f.instance_span,
                     item: f.impl_item.clone(),
@@ -282,10 +490,23 @@ fn compile_entry(
                         span,
                     })?;
 
+            let name = match protocol_attribute(context, &*source, &f)? {
+                Some(protocol) => protocol.hash,
+                None => context.instance_fn_hash(name),
+            };
+
             compiler.compile((f.ast, true))?;
 
+            let frame = Frame::new(count);
+
+            if options.optimize {
+                optimize::optimize(&mut asm, frame, &*unit.borrow());
+            }
+
+            let stack = max_stack_depth(&asm, frame, &*unit.borrow(), span)?;
+
             unit.borrow_mut().new_instance_function(
-                source_id, item, value_type, name, count, asm, f.call, args,
+                source_id, item, value_type, name, count, stack, asm, f.call, args,
             )?;
         }
         Build::Closure(c) => {
@@ -296,8 +517,16 @@ fn compile_entry(
             compiler.contexts.push(span);
             compiler.compile((c.ast, &c.captures[..]))?;
 
+            let frame = Frame::closure(count, c.captures.len());
+
+            if options.optimize {
+                optimize::optimize(&mut asm, frame, &*unit.borrow());
+            }
+
+            let stack = max_stack_depth(&asm, frame, &*unit.borrow(), span)?;
+
             unit.borrow_mut()
-                .new_function(source_id, item, count, asm, c.call, args)?;
+                .new_function(source_id, item, count, stack, asm, c.call, args)?;
         }
         Build::AsyncBlock(async_block) => {
             let span = async_block.ast.span();
@@ -305,10 +534,19 @@ fn compile_entry(
             compiler.contexts.push(span);
             compiler.compile((async_block.ast, &async_block.captures[..]))?;
 
+            let frame = Frame::new(args);
+
+            if options.optimize {
+                optimize::optimize(&mut asm, frame, &*unit.borrow());
+            }
+
+            let stack = max_stack_depth(&asm, frame, &*unit.borrow(), span)?;
+
             unit.borrow_mut().new_function(
                 source_id,
                 item,
                 args,
+                stack,
                 asm,
                 async_block.call,
                 Vec::new(),
@@ -319,6 +557,57 @@ fn compile_entry(
     Ok(())
 }
 
+/// Calculate the maximum depth of the stack of the given function, which is
+/// recorded in the unit so that the stack can be reserved when it's called.
+///
+/// This also verifies the depth of the stack at every instruction, so that
+/// instructions which would corrupt the stack are reported at the expression
+/// that emitted them instead of misbehaving at runtime.
+fn max_stack_depth(
+    asm: &Assembly,
+    frame: Frame,
+    unit: &UnitBuilder,
+    span: Span,
+) -> Result<usize, CompileError> {
+    let error = match stack_effect::max_depth(asm, frame, unit) {
+        Ok(depth) => return Ok(depth),
+        Err(error) => error,
+    };
+
+    let span_of = |n: usize| {
+        asm.instructions
+            .get(n)
+            .map(|(_, span)| *span)
+            .unwrap_or(span)
+    };
+
+    Err(match error {
+        StackError::Underflow { at, depth, pops } => CompileError::StackUnderflow {
+            span: span_of(at),
+            depth,
+            pops,
+        },
+        StackError::Mismatch {
+            at,
+            from,
+            expected,
+            actual,
+        } => CompileError::StackMismatch {
+            span: span_of(at),
+            from: span_of(from),
+            expected,
+            actual,
+        },
+        StackError::MissingLabel { at } => {
+            CompileError::internal("jump to a label which was never applied", span_of(at))
+        }
+        StackError::Unknown { at } => CompileError::internal(
+            "failed to calculate the stack effect of instruction",
+            span_of(at),
+        ),
+    })
+}
+
 fn format_fn_args<'a, I>(source: &Source, arguments: I) -> Result<Vec<String>, CompileError>
 where
     I: IntoIterator<Item = &'a ast::FnArg>,
@@ -336,6 +625,9 @@ where
             ast::FnArg::Ident(ident) => {
                 args.push(ident.resolve(source)?.to_string());
             }
+            ast::FnArg::Pat(pat) => {
+                args.push(source.source(pat.span()).unwrap_or("_").to_owned());
+            }
         }
     }
 
@@ -375,7 +667,7 @@ pub(crate) struct Compiler<'a> {
     /// The source we are compiling for.
     pub(crate) source: Arc<Source>,
     /// The context we are compiling for.
-    context: &'a Context,
+    pub(crate) context: &'a Context,
     /// Expressions expanded in a macro.
     pub(crate) expanded_exprs: &'a HashMap<Item, ast::Expr>,
     /// Query system to compile required items.
@@ -401,6 +693,18 @@ pub(crate) struct Compiler<'a> {
 impl<'a> Compiler<'a> {
     /// Access the meta for the given language item.
     pub fn lookup_meta(&mut self, name: &Item, span: Span) -> CompileResult<Option<CompileMeta>> {
+        let meta = self.find_meta(name, span)?;
+
+        if let Some(meta) = &meta {
+            self.reference_meta(meta, span);
+        }
+
+        Ok(meta)
+    }
+
+    /// Find the metadata for the given item, without recording a reference to
+    /// it.
+    fn find_meta(&mut self, name: &Item, span: Span) -> CompileResult<Option<CompileMeta>> {
         log::trace!("lookup meta: {}", name);
 
         if let Some(meta) = self.context.lookup_meta(name) {
@@ -427,6 +731,20 @@ impl<'a> Compiler<'a> {
         Ok(None)
     }
 
+    /// Record a reference to the item of the given meta.
+    ///
+    /// Closures and async blocks are looked up where they're declared, so
+    /// those aren't references.
+    fn reference_meta(&mut self, meta: &CompileMeta, span: Span) {
+        if let CompileMeta::Closure { .. } | CompileMeta::AsyncBlock { .. } = meta {
+            return;
+        }
+
+        self.query
+            .symbols
+            .reference(meta.item(), self.source_id, span);
+    }
+
     /// Pop locals by simply popping them.
     pub(crate) fn locals_pop(&mut self, total_var_count: usize, span: Span) {
         match total_var_count {
@@ -510,6 +828,16 @@ impl<'a> Compiler<'a> {
                     self.asm
                         .push_with_comment(Inst::Fn { hash }, span, format!("fn `{}`", item));
                 }
+                CompileMeta::Enum {
+                    value_type, item, ..
+                } => {
+                    let hash = value_type.as_type_hash();
+                    self.asm.push_with_comment(
+                        Inst::Type { hash },
+                        span,
+                        format!("enum `{}`", item),
+                    );
+                }
                 meta => {
                     return Err(CompileError::UnsupportedValue {
                         span,
@@ -839,8 +1167,9 @@ impl<'a> Compiler<'a> {
                 _ => return Err(CompileError::UnsupportedBinding { span }),
             };
 
-            load(&mut self.asm);
             let name = ident.resolve(&*self.source)?;
+            self.check_strict_binding(Some(scope), name, span)?;
+            load(&mut self.asm);
             scope.decl_var(name, span);
         }
 
@@ -915,13 +1244,19 @@ impl<'a> Compiler<'a> {
                     }
                 }
 
-                let ident = match item.as_local() {
-                    Some(ident) => ident,
-                    None => {
-                        return Err(CompileError::UnsupportedBinding { span });
-                    }
+                // NB: a plain identifier always binds a local variable, even
+                // if it shadows an item which is in scope, like a builtin.
+                let ident = match path.path.try_as_ident() {
+                    Some(ident) => ident.resolve(&*self.source)?,
+                    None => match item.as_local() {
+                        Some(ident) => ident,
+                        None => {
+                            return Err(CompileError::UnsupportedBinding { span });
+                        }
+                    },
                 };
 
+                self.check_strict_binding(Some(scope), &ident, span)?;
                 load(&mut self.asm);
                 scope.decl_var(&ident, span);
                 return Ok(false);
@@ -929,41 +1264,8 @@ impl<'a> Compiler<'a> {
             ast::Pat::PatIgnore(..) => {
                 return Ok(false);
             }
-            ast::Pat::PatUnit(unit) => {
-                load(&mut self.asm);
-                self.asm.push(Inst::IsUnit, unit.span());
-            }
-            ast::Pat::PatByte(lit_byte) => {
-                let byte = lit_byte.resolve(&*self.source)?;
-                load(&mut self.asm);
-                self.asm.push(Inst::EqByte { byte }, lit_byte.span());
-            }
-            ast::Pat::PatChar(lit_char) => {
-                let character = lit_char.resolve(&*self.source)?;
-                load(&mut self.asm);
-                self.asm
-                    .push(Inst::EqCharacter { character }, lit_char.span());
-            }
-            ast::Pat::PatNumber(number_literal) => {
-                let span = number_literal.span();
-                let number = number_literal.resolve(&*self.source)?;
-
-                let integer = match number {
-                    ast::Number::Integer(integer) => integer,
-                    ast::Number::Float(..) => {
-                        return Err(CompileError::MatchFloatInPattern { span });
-                    }
-                };
-
-                load(&mut self.asm);
-                self.asm.push(Inst::EqInteger { integer }, span);
-            }
-            ast::Pat::PatString(pat_string) => {
-                let span = pat_string.span();
-                let string = pat_string.resolve(&*self.source)?;
-                let slot = self.unit.borrow_mut().new_static_string(&*string)?;
-                load(&mut self.asm);
-                self.asm.push(Inst::EqStaticString { slot }, span);
+            ast::Pat::PatOr(pat_or) => {
+                self.compile_pat_or(pat_or, load)?;
             }
             ast::Pat::PatVec(pat_vec) => {
                 self.compile_pat_vec(scope, pat_vec, false_label, &load)?;
@@ -977,6 +1279,11 @@ impl<'a> Compiler<'a> {
                 self.compile_pat_object(scope, object, false_label, &load)?;
                 return Ok(true);
             }
+            pat => {
+                if !self.compile_pat_lit(pat, load)? {
+                    return Err(CompileError::UnsupportedPattern { span });
+                }
+            }
         }
 
         self.asm
@@ -984,6 +1291,157 @@ impl<'a> Compiler<'a> {
         Ok(true)
     }
 
+    /// Encode the prologue of a function or closure, which destructures the
+    /// arguments at the given offsets with their patterns.
+    ///
+    /// Like with `let`, the function panics if a pattern doesn't match.
+    pub(crate) fn compile_fn_arg_pats(
+        &mut self,
+        pats: &[(usize, &ast::Pat)],
+        span: Span,
+    ) -> CompileResult<()> {
+        let mut scope = self.scopes.pop_unchecked(span)?;
+
+        for (offset, pat) in pats.iter().copied() {
+            let span = pat.span();
+
+            let load = |asm: &mut Assembly| {
+                asm.push(Inst::Copy { offset }, span);
+            };
+
+            let false_label = self.asm.new_label("arg_panic");
+
+            if self.compile_pat(&mut scope, pat, false_label, &load)? {
+                let ok_label = self.asm.new_label("arg_ok");
+                self.asm.jump(ok_label, span);
+                self.asm.label(false_label)?;
+                self.asm.push(
+                    Inst::Panic {
+                        reason: runestick::PanicReason::UnmatchedPattern,
+                    },
+                    span,
+                );
+
+                self.asm.label(ok_label)?;
+            }
+        }
+
+        let _ = self.scopes.push(scope);
+        Ok(())
+    }
+
+    /// Encode a literal or range pattern, leaving a boolean on the stack
+    /// indicating if it matched.
+    ///
+    /// Returns `false` if the pattern is not a literal pattern, in which case
+    /// nothing is encoded.
+    fn compile_pat_lit(
+        &mut self,
+        pat: &ast::Pat,
+        load: &dyn Fn(&mut Assembly),
+    ) -> CompileResult<bool> {
+        let span = pat.span();
+
+        let inst = match pat {
+            ast::Pat::PatUnit(..) => Inst::IsUnit,
+            ast::Pat::PatByte(lit_byte) => {
+                let byte = lit_byte.resolve(&*self.source)?;
+                Inst::EqByte { byte }
+            }
+            ast::Pat::PatChar(lit_char) => {
+                let character = lit_char.resolve(&*self.source)?;
+                Inst::EqCharacter { character }
+            }
+            ast::Pat::PatNumber(number_literal) => {
+                let integer = self.compile_pat_integer(number_literal)?;
+                Inst::EqInteger { integer }
+            }
+            ast::Pat::PatString(pat_string) => {
+                let string = pat_string.resolve(&*self.source)?;
+                let slot = self.unit.borrow_mut().new_static_string(&*string)?;
+                Inst::EqStaticString { slot }
+            }
+            ast::Pat::PatRange(pat_range) => self.compile_pat_range(pat_range)?,
+            _ => return Ok(false),
+        };
+
+        load(&mut self.asm);
+        self.asm.push(inst, span);
+        Ok(true)
+    }
+
+    /// Resolve the integer of a number pattern.
+    fn compile_pat_integer(&self, number_literal: &ast::LitNumber) -> CompileResult<i64> {
+        let span = number_literal.span();
+
+        match number_literal.resolve(&*self.source)? {
+            ast::Number::Integer(integer) => Ok(integer),
+            ast::Number::Float(..) => Err(CompileError::MatchFloatInPattern { span }),
+        }
+    }
+
+    /// Construct the instruction used to test an inclusive range pattern.
+    fn compile_pat_range(&self, pat_range: &ast::PatRange) -> CompileResult<Inst> {
+        let span = pat_range.span();
+        log::trace!("PatRange => {:?}", self.source.source(span));
+
+        let (inst, empty) = match (&*pat_range.start, &*pat_range.end) {
+            (ast::Pat::PatByte(start), ast::Pat::PatByte(end)) => {
+                let start = start.resolve(&*self.source)?;
+                let end = end.resolve(&*self.source)?;
+                (Inst::MatchByteRange { start, end }, start > end)
+            }
+            (ast::Pat::PatChar(start), ast::Pat::PatChar(end)) => {
+                let start = start.resolve(&*self.source)?;
+                let end = end.resolve(&*self.source)?;
+                (Inst::MatchCharacterRange { start, end }, start > end)
+            }
+            (ast::Pat::PatNumber(start), ast::Pat::PatNumber(end)) => {
+                let start = self.compile_pat_integer(start)?;
+                let end = self.compile_pat_integer(end)?;
+                (Inst::MatchIntegerRange { start, end }, start > end)
+            }
+            _ => return Err(CompileError::UnsupportedRangePattern { span }),
+        };
+
+        if empty {
+            return Err(CompileError::EmptyRangePattern { span });
+        }
+
+        Ok(inst)
+    }
+
+    /// Encode a pattern with alternatives, leaving a boolean on the stack
+    /// indicating if any of the alternatives matched.
+    fn compile_pat_or(
+        &mut self,
+        pat_or: &ast::PatOr,
+        load: &dyn Fn(&mut Assembly),
+    ) -> CompileResult<()> {
+        let span = pat_or.span();
+        log::trace!("PatOr => {:?}", self.source.source(span));
+
+        let matched_label = self.asm.new_label("pat_or_matched");
+        let end_label = self.asm.new_label("pat_or_end");
+
+        for pat in pat_or.alternatives() {
+            let span = pat.span();
+
+            if !self.compile_pat_lit(pat, load)? {
+                return Err(CompileError::UnsupportedPatternAlternative { span });
+            }
+
+            self.asm.jump_if(matched_label, span);
+        }
+
+        self.asm.push(Inst::Bool { value: false }, span);
+        self.asm.jump(end_label, span);
+        self.asm.label(matched_label)?;
+        self.asm.push(Inst::Bool { value: true }, span);
+        self.asm.label(end_label)?;
+        Ok(())
+    }
+
     /// Clean the last scope.
     pub(crate) fn clean_last_scope(
         &mut self,
@@ -1006,4 +1464,326 @@ impl<'a> Compiler<'a> {
     pub(crate) fn context(&self) -> Option<Span> {
         self.contexts.last().copied()
     }
+
+    /// Emit a coverage counter for the code at the given span, if coverage is
+    /// enabled.
+    pub(crate) fn coverage(&mut self, span: Span) {
+        if !self.options.coverage {
+            return;
+        }
+
+        let counter = self
+            .unit
+            .borrow_mut()
+            .new_coverage_counter(self.source_id, span);
+
+        self.asm.push(Inst::Coverage { counter }, span);
+    }
+
+    /// Warn about any code in the given block which follows an expression
+    /// that unconditionally diverges.
+    pub(crate) fn warn_unreachable(&mut self, expr_block: &ast::ExprBlock) {
+        let mut exprs = expr_block
+            .exprs
+            .iter()
+            .map(|(expr, _)| expr)
+            .chain(expr_block.trailing_expr.as_deref());
+
+        let cause = match exprs.by_ref().find(|expr| expr.diverges()) {
+            Some(cause) => cause.span(),
+            None => return,
+        };
+
+        let span = match exprs.next() {
+            Some(first) => first.span(),
+            None => return,
+        };
+
+        let span = match exprs.last() {
+            Some(last) => span.join(last.span()),
+            None => span,
+        };
+
+        self.warnings
+            .unreachable_code(self.source_id, span, cause, self.context());
+    }
+
+    /// Warn about the loop being compiled if nothing in its body exits it,
+    /// and the `lint-infinite-loops` option is enabled.
+    pub(crate) fn warn_infinite_loop(&mut self, span: Span) {
+        if !self.options.lint_infinite_loops {
+            return;
+        }
+
+        if let Some(l) = self.loops.last() {
+            if !l.exits {
+                self.warnings
+                    .infinite_loop(self.source_id, span, self.context());
+            }
+        }
+    }
+
+    /// Check a new binding against the `strict` option, which forbids
+    /// shadowing existing variables and builtins.
+    ///
+    /// `scope` is the scope the binding is declared in, if it has been taken
+    /// off the stack of scopes.
+    pub(crate) fn check_strict_binding(
+        &self,
+        scope: Option<&Scope>,
+        name: &str,
+        span: Span,
+    ) -> CompileResult<()> {
+        if !self.options.strict {
+            return Ok(());
+        }
+
+        let existing = match scope.and_then(|scope| scope.get(name)) {
+            Some(var) => Some(var),
+            None => self.scopes.try_get_var(name)?,
+        };
+
+        if let Some(var) = existing {
+            return Err(CompileError::StrictShadowedVariable {
+                span,
+                name: name.to_owned(),
+                existing_span: var.span(),
+            });
+        }
+
+        if self.unit.borrow().is_prelude(name) {
+            return Err(CompileError::StrictShadowedBuiltin {
+                span,
+                name: name.to_owned(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Check the expression whose value is implicitly returned from a
+    /// function or a closure against the `strict` option, which forbids
+    /// implicitly returning a value.
+    pub(crate) fn check_strict_implicit_return(&self, expr: &ast::Expr) -> CompileResult<()> {
+        if self.options.strict && implicitly_returns(expr) {
+            return Err(CompileError::StrictImplicitReturn { span: expr.span() });
+        }
+
+        Ok(())
+    }
+
+    /// Check that the branches of an `if` or a `match` whose value is used
+    /// agree on whether they produce a value or not.
+    ///
+    /// Returns the span of a branch which produces a value, if any.
+    pub(crate) fn check_branch_values(
+        &self,
+        branches: &[(Span, BranchValue)],
+    ) -> CompileResult<Option<Span>> {
+        let value = branches
+            .iter()
+            .find(|(_, value)| *value == BranchValue::Value)
+            .map(|(span, _)| *span);
+
+        let value = match value {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        if let Some((span, _)) = branches
+            .iter()
+            .find(|(_, value)| *value == BranchValue::Nothing)
+        {
+            return Err(CompileError::BranchProducesNothing { span: *span, value });
+        }
+
+        Ok(Some(value))
+    }
+
+    /// Warn if the given expression is a call to a function declared in the
+    /// unit which never produces a value, since using its value is most
+    /// likely a mistake.
+    pub(crate) fn warn_unit_function_value(&mut self, expr: &ast::Expr) -> CompileResult<()> {
+        let (span, path) = match expr {
+            ast::Expr::ExprCall(expr_call) => match &*expr_call.expr {
+                ast::Expr::Path(path) => (expr_call.span(), path),
+                _ => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
+
+        let item = self.convert_path_to_item(path)?;
+
+        if let Some(name) = item.as_local() {
+            if self.scopes.try_get_var(name)?.is_some() {
+                return Ok(());
+            }
+        }
+
+        if let Some(CompileMeta::Function { item, .. }) = self.lookup_meta(&item, path.span())? {
+            if self.query.is_unit_function(&item) {
+                self.warnings.unit_function_used_as_value(
+                    self.source_id,
+                    span,
+                    item,
+                    self.context(),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Place the given static value in the static data section of the unit,
+    /// and load it.
+    pub(crate) fn compile_static(&mut self, value: StaticValue, span: Span) {
+        let slot = self.unit.borrow_mut().new_static_value(value);
+        self.asm.push(Inst::Static { slot }, span);
+    }
+
+    /// Construct the static values for a sequence of constant expressions, or
+    /// `None` if any of them can't be represented as one.
+    pub(crate) fn static_values<'e, I>(&self, exprs: I) -> CompileResult<Option<Vec<StaticValue>>>
+    where
+        I: IntoIterator<Item = &'e ast::Expr>,
+    {
+        let mut values = Vec::new();
+
+        for expr in exprs {
+            match self.static_value(expr)? {
+                Some(value) => values.push(value),
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(values))
+    }
+
+    /// Construct the static value of a constant expression, or `None` if it
+    /// can't be represented as one.
+    ///
+    /// Only literals are supported, constant expressions which would need to
+    /// be evaluated are compiled as usual.
+    pub(crate) fn static_value(&self, expr: &ast::Expr) -> CompileResult<Option<StaticValue>> {
+        let source = &*self.source;
+
+        Ok(Some(match expr {
+            ast::Expr::LitUnit(..) => StaticValue::Unit,
+            ast::Expr::LitBool(lit_bool) => StaticValue::Bool(lit_bool.value),
+            ast::Expr::LitByte(lit_byte) => StaticValue::Byte(lit_byte.resolve(source)?),
+            ast::Expr::LitChar(lit_char) => StaticValue::Char(lit_char.resolve(source)?),
+            ast::Expr::LitNumber(lit_number) => match lit_number.resolve(source)? {
+                ast::Number::Integer(n) => StaticValue::Integer(n),
+                ast::Number::Float(n) => StaticValue::Float(n),
+            },
+            ast::Expr::LitStr(lit_str) => {
+                let string = lit_str.resolve(source)?;
+                let string = self.unit.borrow_mut().intern_static_string(&*string)?;
+                StaticValue::String(string)
+            }
+            ast::Expr::LitByteStr(lit_byte_str) => {
+                StaticValue::Bytes(lit_byte_str.resolve(source)?.into_owned())
+            }
+            ast::Expr::LitVec(lit_vec) => match self.static_values(&lit_vec.items)? {
+                Some(values) => StaticValue::Vec(values),
+                None => return Ok(None),
+            },
+            ast::Expr::LitTuple(lit_tuple) => {
+                match self.static_values(lit_tuple.items.iter().map(|(expr, _)| expr))? {
+                    Some(values) => StaticValue::Tuple(values),
+                    None => return Ok(None),
+                }
+            }
+            ast::Expr::LitObject(lit_object) => match self.static_object(lit_object)? {
+                Some(value) => value,
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        }))
+    }
+
+    /// Construct the static value of an anonymous object with constant
+    /// fields, or `None` if it can't be represented as one.
+    pub(crate) fn static_object(
+        &self,
+        lit_object: &ast::LitObject,
+    ) -> CompileResult<Option<StaticValue>> {
+        if !lit_object.is_const() {
+            return Ok(None);
+        }
+
+        if let ast::LitObjectIdent::Named(..) = &lit_object.ident {
+            return Ok(None);
+        }
+
+        let mut values = Vec::new();
+        let mut keys = HashSet::new();
+
+        for assign in &lit_object.assignments {
+            let expr = match &assign.assign {
+                Some((_, expr)) => expr,
+                None => return Ok(None),
+            };
+
+            let key = match &assign.key {
+                ast::LitObjectFieldKey::Key(key) => key.resolve(&*self.source)?.into_owned(),
+                ast::LitObjectFieldKey::Computed(..) => return Ok(None),
+            };
+
+            // NB: duplicate keys are reported when the object is compiled.
+            if !keys.insert(key.clone()) {
+                return Ok(None);
+            }
+
+            match self.static_value(expr)? {
+                Some(value) => values.push((key, value)),
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(StaticValue::Object(values)))
+    }
+}
+
+/// Test if the given expression might produce a value when it's evaluated.
+///
+/// Branches which diverge, like through a `return`, produce nothing.
+fn implicitly_returns(expr: &ast::Expr) -> bool {
+    if expr.diverges() {
+        return false;
+    }
+
+    match expr {
+        ast::Expr::ExprIf(expr_if) => {
+            block_implicitly_returns(&expr_if.block)
+                || expr_if
+                    .expr_else_ifs
+                    .iter()
+                    .any(|expr_else_if| block_implicitly_returns(&expr_else_if.block))
+                || expr_if.expr_else.as_ref().map_or(false, |expr_else| {
+                    block_implicitly_returns(&expr_else.block)
+                })
+        }
+        ast::Expr::ExprMatch(expr_match) => expr_match
+            .branches
+            .iter()
+            .any(|(branch, _)| implicitly_returns(&branch.body)),
+        ast::Expr::ExprGroup(expr_group) => implicitly_returns(&expr_group.expr),
+        ast::Expr::ExprBlock(expr_block) if expr_block.async_.is_none() => {
+            block_implicitly_returns(expr_block)
+        }
+        expr => !expr.produces_nothing(),
+    }
+}
+
+/// Test if the given block might produce a value when it's evaluated.
+fn block_implicitly_returns(block: &ast::ExprBlock) -> bool {
+    if block.diverging_expr().is_some() {
+        return false;
+    }
+
+    match &block.trailing_expr {
+        Some(expr) => implicitly_returns(expr),
+        None => false,
+    }
 }
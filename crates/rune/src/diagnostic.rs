@@ -0,0 +1,246 @@
+//! A single representation of warnings and errors, for tooling to consume.
+
+use runestick::Span;
+use std::fmt;
+use std::sync::Arc;
+
+/// The severity of a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The diagnostic is a warning, which doesn't prevent compilation.
+    Warning,
+    /// The diagnostic is an error, which prevents compilation.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Warning => fmt.write_str("warning"),
+            Self::Error => fmt.write_str("error"),
+        }
+    }
+}
+
+/// A span in a source which is related to a diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticLabel {
+    source_id: usize,
+    span: Span,
+    primary: bool,
+    message: String,
+}
+
+impl DiagnosticLabel {
+    /// Construct a label pointing to where the diagnostic happened.
+    pub fn primary(source_id: usize, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            source_id,
+            span,
+            primary: true,
+            message: message.into(),
+        }
+    }
+
+    /// Construct a label pointing to code related to the diagnostic.
+    pub fn secondary(source_id: usize, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            source_id,
+            span,
+            primary: false,
+            message: message.into(),
+        }
+    }
+
+    /// The id of the source the label is in.
+    pub fn source_id(&self) -> usize {
+        self.source_id
+    }
+
+    /// The span of the label.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Test if this label points to where the diagnostic happened.
+    pub fn is_primary(&self) -> bool {
+        self.primary
+    }
+
+    /// The message of the label, which might be empty.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// A warning or an error, with a stable code.
+///
+/// Codes are never reused, so tooling can rely on them to identify a kind of
+/// diagnostic across versions. Warnings are prefixed with `W` and errors with
+/// `E`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    code: &'static str,
+    severity: Severity,
+    message: String,
+    labels: Vec<DiagnosticLabel>,
+    notes: Vec<String>,
+}
+
+impl Diagnostic {
+    /// Construct a new warning.
+    pub fn warning(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(code, Severity::Warning, message.into())
+    }
+
+    /// Construct a new error.
+    pub fn error(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(code, Severity::Error, message.into())
+    }
+
+    fn new(code: &'static str, severity: Severity, message: String) -> Self {
+        Self {
+            code,
+            severity,
+            message,
+            labels: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    /// Add a label to the diagnostic.
+    pub fn with_label(mut self, label: DiagnosticLabel) -> Self {
+        self.labels.push(label);
+        self
+    }
+
+    /// Add a note to the diagnostic.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// The stable code of the diagnostic, like `W0001`.
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// The severity of the diagnostic.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// The message of the diagnostic.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The spans related to the diagnostic.
+    pub fn labels(&self) -> &[DiagnosticLabel] {
+        &self.labels
+    }
+
+    /// The first primary label of the diagnostic, if it has a location.
+    pub fn primary(&self) -> Option<&DiagnosticLabel> {
+        self.labels.iter().find(|l| l.primary)
+    }
+
+    /// Additional notes on the diagnostic.
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}[{}]: {}", self.severity, self.code, self.message)
+    }
+}
+
+/// An immutable snapshot of diagnostics.
+///
+/// The snapshot is cheap to clone, and never changes once it has been
+/// constructed.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{Severity, Sources, Warnings};
+/// use runestick::Span;
+///
+/// let mut warnings = Warnings::new();
+/// warnings.not_used(0, Span::new(4, 8), None);
+///
+/// let diagnostics = warnings.diagnostics(&Sources::new());
+/// warnings.not_used(0, Span::new(10, 12), None);
+///
+/// assert_eq!(diagnostics.len(), 1);
+///
+/// let diagnostic = diagnostics.iter().next().unwrap();
+/// assert_eq!(diagnostic.code(), "W0001");
+/// assert_eq!(diagnostic.severity(), Severity::Warning);
+/// assert_eq!(diagnostic.primary().unwrap().span(), Span::new(4, 8));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Diagnostics {
+    diagnostics: Arc<[Diagnostic]>,
+}
+
+impl Diagnostics {
+    /// Test if there are no diagnostics.
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Get the number of diagnostics.
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    /// Iterate over all diagnostics.
+    pub fn iter(&self) -> impl Iterator<Item = &'_ Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    /// Test if any of the diagnostics is an error.
+    pub fn has_errors(&self) -> bool {
+        self.iter().any(|d| d.severity == Severity::Error)
+    }
+
+    /// Iterate over all diagnostics with the given code.
+    pub fn with_code<'a>(&'a self, code: &'a str) -> impl Iterator<Item = &'a Diagnostic> {
+        self.iter().filter(move |d| d.code == code)
+    }
+}
+
+impl Default for Diagnostics {
+    fn default() -> Self {
+        Self::from(Vec::new())
+    }
+}
+
+impl From<Vec<Diagnostic>> for Diagnostics {
+    fn from(diagnostics: Vec<Diagnostic>) -> Self {
+        Self {
+            diagnostics: diagnostics.into(),
+        }
+    }
+}
+
+impl std::iter::FromIterator<Diagnostic> for Diagnostics {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Diagnostic>,
+    {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type IntoIter = std::slice::Iter<'a, Diagnostic>;
+    type Item = &'a Diagnostic;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.diagnostics.iter()
+    }
+}
@@ -1,11 +1,10 @@
 //! Runtime helpers for loading code and emitting diagnostics.
 
-use crate::unit_builder::LinkerError;
-use crate::{CompileError, LoadError, LoadErrorKind, Sources, WarningKind, Warnings};
-use runestick::VmError;
-use std::error::Error as _;
+use crate::{Diagnostics, LoadError, Severity, Sources, Warnings};
+use runestick::{TypeNames, VmError};
 use std::fmt;
 use std::io;
+use std::sync::Arc;
 use thiserror::Error;
 
 use codespan_reporting::diagnostic::{Diagnostic, Label};
@@ -44,8 +43,16 @@ impl EmitDiagnostics for Warnings {
     where
         O: WriteColor,
     {
-        use std::fmt::Write as _;
+        self.diagnostics(sources).emit_diagnostics(out, sources)
+    }
+}
 
+/// Emit a snapshot of diagnostics.
+impl EmitDiagnostics for Diagnostics {
+    fn emit_diagnostics<O>(self, out: &mut O, sources: &Sources) -> Result<(), DiagnosticsError>
+    where
+        O: WriteColor,
+    {
         let config = codespan_reporting::term::Config::default();
         let mut files = SimpleFiles::new();
 
@@ -53,93 +60,37 @@ impl EmitDiagnostics for Warnings {
             files.add(source.name(), source.as_str());
         }
 
-        let mut labels = Vec::new();
-        let mut notes = Vec::new();
-
-        for w in &self {
-            let context = match &w.kind {
-                WarningKind::NotUsed { span, context } => {
-                    labels.push(
-                        Label::primary(w.source_id, span.start..span.end)
-                            .with_message("value not used"),
-                    );
-
-                    *context
-                }
-                WarningKind::LetPatternMightPanic { span, context } => {
-                    labels.push(
-                        Label::primary(w.source_id, span.start..span.end)
-                            .with_message("let binding might panic"),
-                    );
-
-                    let binding = sources.source_at(w.source_id).and_then(|s| s.source(*span));
-
-                    if let Some(binding) = binding {
-                        let mut note = String::new();
-                        writeln!(note, "Consider rewriting to:")?;
-                        writeln!(note, "if {} {{", binding)?;
-                        writeln!(note, "    // ..")?;
-                        writeln!(note, "}}")?;
-                        notes.push(note);
-                    }
-
-                    *context
-                }
-                WarningKind::TemplateWithoutExpansions { span, context } => {
-                    labels.push(
-                        Label::primary(w.source_id, span.start..span.end)
-                            .with_message("template string without expansions like `{1 + 2}`"),
-                    );
-
-                    *context
-                }
-                WarningKind::RemoveTupleCallParams {
-                    span,
-                    variant,
-                    context,
-                } => {
-                    labels.push(
-                        Label::secondary(w.source_id, span.start..span.end).with_message(
-                            "constructing this variant could be done without parentheses",
-                        ),
-                    );
-
-                    let variant = sources
-                        .source_at(w.source_id)
-                        .and_then(|s| s.source(*variant));
-
-                    if let Some(variant) = variant {
-                        let mut note = String::new();
-                        writeln!(note, "Consider rewriting to `{}`", variant)?;
-                        notes.push(note);
-                    }
-
-                    *context
-                }
-                WarningKind::UnecessarySemiColon { span } => {
-                    labels.push(
-                        Label::primary(w.source_id, span.start..span.end)
-                            .with_message("unnecessary semicolon"),
-                    );
-
-                    None
-                }
+        for d in &self {
+            let severity = match d.severity() {
+                Severity::Warning => Diagnostic::warning(),
+                Severity::Error => Diagnostic::error(),
             };
 
-            if let Some(context) = context {
-                labels.push(
-                    Label::secondary(w.source_id, context.start..context.end)
-                        .with_message("in this context"),
-                );
-            }
+            let labels = d
+                .labels()
+                .iter()
+                .map(|l| {
+                    let span = l.span();
+
+                    let label = if l.is_primary() {
+                        Label::primary(l.source_id(), span.start..span.end)
+                    } else {
+                        Label::secondary(l.source_id(), span.start..span.end)
+                    };
+
+                    label.with_message(l.message())
+                })
+                .collect();
+
+            let diagnostic = severity
+                .with_code(d.code())
+                .with_message(d.message())
+                .with_labels(labels)
+                .with_notes(d.notes().to_vec());
+
+            term::emit(out, &config, &files, &diagnostic)?;
         }
 
-        let diagnostic = Diagnostic::warning()
-            .with_message("warning")
-            .with_labels(labels)
-            .with_notes(notes);
-
-        term::emit(out, &config, &files, &diagnostic)?;
         Ok(())
     }
 }
@@ -170,10 +121,15 @@ impl EmitDiagnostics for VmError {
             }
         };
 
+        // NB: resolve the names of types declared in the unit, so that they
+        // aren't displayed as hashes.
+        let names = TypeNames::new(None, Some(unit.clone()));
+        let message = names.display(&error).to_string();
+
         let debug_info = match unit.debug_info() {
             Some(debug_info) => debug_info,
             None => {
-                writeln!(out, "virtual machine error: {} (no debug info)", error)?;
+                writeln!(out, "virtual machine error: {} (no debug info)", message)?;
                 return Ok(());
             }
         };
@@ -184,7 +140,7 @@ impl EmitDiagnostics for VmError {
                 writeln!(
                     out,
                     "virtual machine error: {} (no debug instruction)",
-                    error
+                    message
                 )?;
 
                 return Ok(());
@@ -198,142 +154,63 @@ impl EmitDiagnostics for VmError {
         let source_id = debug_inst.source_id;
         let span = debug_inst.span;
 
-        labels
-            .push(Label::primary(source_id, span.start..span.end).with_message(error.to_string()));
-
-        let diagnostic = Diagnostic::error()
-            .with_message("virtual machine error")
-            .with_labels(labels);
-
-        term::emit(out, &config, &files, &diagnostic)?;
-        Ok(())
-    }
-}
-
-impl EmitDiagnostics for LoadError {
-    fn emit_diagnostics<O>(self, out: &mut O, sources: &Sources) -> Result<(), DiagnosticsError>
-    where
-        O: WriteColor,
-    {
-        let config = codespan_reporting::term::Config::default();
-
-        let mut files = SimpleFiles::new();
-
-        for source in sources.iter() {
-            files.add(source.name(), source.as_str());
+        if sources.get(source_id).is_none() {
+            writeln!(
+                out,
+                "virtual machine error: {} (no source available)",
+                error
+            )?;
+            return Ok(());
         }
 
-        let mut labels = Vec::new();
+        labels.push(Label::primary(source_id, span.start..span.end).with_message(message));
 
-        let (span, source_id) = match self.kind() {
-            LoadErrorKind::Internal { message } => {
-                writeln!(out, "internal error: {}", message)?;
-                return Ok(());
-            }
-            LoadErrorKind::ReadFile { error, path } => {
-                writeln!(out, "failed to read file: {}: {}", path.display(), error)?;
-                return Ok(());
-            }
-            LoadErrorKind::LinkError { errors } => {
-                for error in errors {
-                    match error {
-                        LinkerError::MissingFunction { hash, spans } => {
-                            let mut labels = Vec::new();
-
-                            for (span, source_id) in spans {
-                                labels.push(
-                                    Label::primary(*source_id, span.start..span.end)
-                                        .with_message("called here."),
-                                );
-                            }
-
-                            let diagnostic = Diagnostic::error()
-                                .with_message(format!("missing function with hash `{}`", hash))
-                                .with_labels(labels);
-
-                            term::emit(out, &config, &files, &diagnostic)?;
-                        }
-                    }
-                }
+        let mut notes = Vec::new();
 
-                return Ok(());
-            }
-            LoadErrorKind::ParseError { source_id, error } => (error.span(), *source_id),
-            LoadErrorKind::CompileError { source_id, error } => {
-                let source_id = *source_id;
-
-                let span = match error {
-                    CompileError::ReturnLocalReferences {
-                        block,
-                        references_at,
-                        span,
-                        ..
-                    } => {
-                        for ref_span in references_at {
-                            if span.overlaps(*ref_span) {
-                                continue;
-                            }
-
-                            labels.push(
-                                Label::secondary(source_id, ref_span.start..ref_span.end)
-                                    .with_message("reference created here"),
-                            );
-                        }
-
-                        labels.push(
-                            Label::secondary(source_id, block.start..block.end)
-                                .with_message("block returned from"),
-                        );
-
-                        *span
-                    }
-                    CompileError::DuplicateObjectKey {
-                        span,
-                        existing,
-                        object,
-                    } => {
-                        labels.push(
-                            Label::secondary(source_id, existing.start..existing.end)
-                                .with_message("previously defined here"),
-                        );
-
-                        labels.push(
-                            Label::secondary(source_id, object.start..object.end)
-                                .with_message("object being defined here"),
-                        );
-
-                        *span
-                    }
-                    CompileError::ModAlreadyLoaded { span, existing, .. } => {
-                        let (existing_source_id, existing_span) = *existing;
-
-                        labels.push(
-                            Label::secondary(
-                                existing_source_id,
-                                existing_span.start..existing_span.end,
-                            )
-                            .with_message("previously loaded here"),
-                        );
-
-                        *span
-                    }
-                    error => error.span(),
+        if let Some(error) = error.as_access_error() {
+            let borrowed_at = error
+                .snapshot()
+                .and_then(|snapshot| snapshot.borrowed_at())
+                .filter(|site| site.unit == Arc::as_ptr(&unit) as usize)
+                .and_then(|site| debug_info.instruction_at(site.ip));
+
+            // NB: if the value is borrowed by the failing instruction itself,
+            // the label wouldn't add anything.
+            if let Some(borrowed_at) = borrowed_at.filter(|inst| inst.span != span) {
+                let message = match error.snapshot() {
+                    Some(snapshot) if snapshot.is_moved() => "value moved here",
+                    _ => "value borrowed here",
                 };
 
-                (span, source_id)
+                labels.push(
+                    Label::secondary(
+                        borrowed_at.source_id,
+                        borrowed_at.span.start..borrowed_at.span.end,
+                    )
+                    .with_message(message),
+                );
             }
-        };
 
-        if let Some(e) = self.source() {
-            labels
-                .push(Label::primary(source_id, span.start..span.end).with_message(e.to_string()));
+            if let Some(hint) = error.hint() {
+                notes.push(format!("hint: {}", hint));
+            }
         }
 
         let diagnostic = Diagnostic::error()
-            .with_message(self.to_string())
-            .with_labels(labels);
+            .with_message("virtual machine error")
+            .with_labels(labels)
+            .with_notes(notes);
 
         term::emit(out, &config, &files, &diagnostic)?;
         Ok(())
     }
 }
+
+impl EmitDiagnostics for LoadError {
+    fn emit_diagnostics<O>(self, out: &mut O, sources: &Sources) -> Result<(), DiagnosticsError>
+    where
+        O: WriteColor,
+    {
+        self.diagnostics().emit_diagnostics(out, sources)
+    }
+}
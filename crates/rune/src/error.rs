@@ -1,8 +1,8 @@
 use crate::ast;
 use crate::ast::Kind;
 use crate::unit_builder::UnitBuilderError;
-use crate::SourceId;
-use runestick::{CompileMeta, Item, Span};
+use crate::{Diagnostic, DiagnosticLabel, SourceId};
+use runestick::{CompileMeta, Item, Protocol, Span};
 use std::io;
 use std::path::PathBuf;
 use thiserror::Error;
@@ -18,6 +18,12 @@ pub enum ConfigurationError {
         /// The unsupported option.
         option: String,
     },
+    /// Tried to target a malformed version of the host API.
+    #[error("invalid api version `{version}`, expected a version like `1.2`")]
+    InvalidApiVersion {
+        /// The malformed version.
+        version: String,
+    },
 }
 
 /// Error when parsing.
@@ -193,6 +199,14 @@ pub enum ParseError {
         /// The actual token kind which was not a string.
         actual: Kind,
     },
+    /// Expected a specific kind of syntax, but parsed something else.
+    #[error("expected {expected}")]
+    ExpectedSyntax {
+        /// The span of what was parsed instead.
+        span: Span,
+        /// A description of what was expected.
+        expected: &'static str,
+    },
     /// Expected an operator but got something else.
     #[error("expected operator (`+`, `-`, `/`, `*`) but got `{actual}`")]
     ExpectedOperator {
@@ -377,6 +391,7 @@ impl ParseError {
             Self::ExpectedByte { span, .. } => span,
             Self::ExpectedChar { span, .. } => span,
             Self::ExpectedString { span, .. } => span,
+            Self::ExpectedSyntax { span, .. } => span,
             Self::ExpectedOperator { span, .. } => span,
             Self::ExpectedBool { span, .. } => span,
             Self::ExpectedLitObjectKey { span, .. } => span,
@@ -403,6 +418,69 @@ impl ParseError {
             Self::ExpectedMacroCloseDelimiter { span, .. } => span,
         }
     }
+
+    /// The stable code of the parse error, like `E0101`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UnexpectedEof { .. } => "E0101",
+            Self::ExpectedEof { .. } => "E0102",
+            Self::ExpectedDecl { .. } => "E0103",
+            Self::ExpectedDeclUseImportComponent { .. } => "E0104",
+            Self::ExpectedStringEscape { .. } => "E0105",
+            Self::UnterminatedStrLit { .. } => "E0106",
+            Self::UnterminatedCharLit { .. } => "E0107",
+            Self::UnterminatedByteLit { .. } => "E0108",
+            Self::ExpectedCharClose { .. } => "E0109",
+            Self::ExpectedByteClose { .. } => "E0110",
+            Self::ExpectedTemplateClose { .. } => "E0111",
+            Self::ExpectedCharEscape { .. } => "E0112",
+            Self::TokenMismatch { .. } => "E0113",
+            Self::ExpectedPatError { .. } => "E0114",
+            Self::ExpectedExpr { .. } => "E0115",
+            Self::ExpectedEnumVariant { .. } => "E0116",
+            Self::ExpectedLoop { .. } => "E0117",
+            Self::ExpectedBlockExpr { .. } => "E0118",
+            Self::UnexpectedChar { .. } => "E0119",
+            Self::ExpectedNumber { .. } => "E0120",
+            Self::ExpectedByte { .. } => "E0121",
+            Self::ExpectedChar { .. } => "E0122",
+            Self::ExpectedString { .. } => "E0123",
+            Self::ExpectedSyntax { .. } => "E0124",
+            Self::ExpectedOperator { .. } => "E0125",
+            Self::ExpectedBool { .. } => "E0126",
+            Self::ExpectedLitObjectKey { .. } => "E0127",
+            Self::ExpectedUnaryOperator { .. } => "E0128",
+            Self::PrecedenceGroupRequired { .. } => "E0129",
+            Self::BadSlice { .. } => "E0130",
+            Self::BadEscapeSequence { .. } => "E0131",
+            Self::BadNumberLiteral { .. } => "E0132",
+            Self::BadNumberOutOfBounds { .. } => "E0133",
+            Self::BadCharLiteral { .. } => "E0134",
+            Self::BadByteLiteral { .. } => "E0135",
+            Self::UnicodeEscapeNotSupported { .. } => "E0136",
+            Self::BadUnicodeEscape { .. } => "E0137",
+            Self::UnsupportedUnicodeByteEscape { .. } => "E0138",
+            Self::UnsupportedByteEscape { .. } => "E0139",
+            Self::BadByteEscape { .. } => "E0140",
+            Self::InvalidTemplateLiteral { .. } => "E0141",
+            Self::UnexpectedCloseBrace { .. } => "E0142",
+            Self::UnsupportedFieldAccess { .. } => "E0143",
+            Self::ExpectedFunctionArgument { .. } => "E0144",
+            Self::UnsupportedAsyncExpr { .. } => "E0145",
+            Self::ExpectedMacroDelimiter { .. } => "E0146",
+            Self::ExpectedMacroCloseDelimiter { .. } => "E0147",
+        }
+    }
+
+    /// Convert the parse error into a diagnostic, located in the source with
+    /// the given id.
+    pub fn to_diagnostic(&self, source_id: usize) -> Diagnostic {
+        Diagnostic::error(self.code(), self.to_string()).with_label(DiagnosticLabel::primary(
+            source_id,
+            self.span(),
+            "",
+        ))
+    }
 }
 
 /// Error when encoding AST.
@@ -416,6 +494,32 @@ pub enum CompileError {
         /// Where the invariant was broken.
         span: Span,
     },
+    /// The compiler emitted an instruction which pops more values than there
+    /// are on the stack.
+    #[error(
+        "internal compiler error: instruction pops {pops} values, but the stack only has {depth}"
+    )]
+    StackUnderflow {
+        /// The span of the instruction.
+        span: Span,
+        /// The depth of the stack before the instruction.
+        depth: usize,
+        /// The number of values popped by the instruction.
+        pops: usize,
+    },
+    /// The compiler emitted instructions which reach the same instruction with
+    /// different depths of the stack.
+    #[error("internal compiler error: instruction is reached with a stack depth of {actual}, but expected {expected}")]
+    StackMismatch {
+        /// The span of the instruction.
+        span: Span,
+        /// The span of the instruction which reached it with `actual`.
+        from: Span,
+        /// The depth of the stack it was first reached with.
+        expected: usize,
+        /// The depth of the stack it was reached with from `from`.
+        actual: usize,
+    },
     /// Trying to use an experimental feature which was not enabled.
     #[error("experimental feature: {msg}")]
     Experimental {
@@ -600,6 +704,12 @@ pub enum CompileError {
         /// The path to the unsupported object.
         item: Item,
     },
+    /// Computed keys are only supported in anonymous objects.
+    #[error("computed keys are only supported in anonymous objects")]
+    UnsupportedComputedKey {
+        /// The span of the computed key.
+        span: Span,
+    },
     /// Key is not present in the given type literal.
     #[error("missing field `{field}` in declaration of `{item}`")]
     LitObjectMissingField {
@@ -705,6 +815,41 @@ pub enum CompileError {
         /// The span of the illegal break.
         span: Span,
     },
+    /// Error raised when a branch of an `if` or a `match` whose value is used
+    /// produces nothing, while another branch produces a value.
+    #[error("branch produces nothing, but another branch produces a value")]
+    BranchProducesNothing {
+        /// The span of the branch which produces nothing.
+        span: Span,
+        /// The span of the branch which produces a value.
+        value: Span,
+    },
+    /// Error raised when an `if` without an `else` branch is used as a value,
+    /// while one of its branches produces a value.
+    #[error("`if` without an `else` branch can't produce a value")]
+    IfWithoutElse {
+        /// The span of the if expression.
+        span: Span,
+        /// The span of the branch which produces a value.
+        value: Span,
+    },
+    /// Error raised when trying to use a continue outside of a loop.
+    #[error("continue expressions can only be used inside of a loop")]
+    ContinueOutsideOfLoop {
+        /// The span of the illegal continue.
+        span: Span,
+    },
+    /// Error raised when breaking with a value out of a `while` or a `for`
+    /// loop which can't produce one, since its value is not used.
+    #[error("cannot break with a value out of a `{kind}` loop whose value is not used")]
+    UnsupportedBreakValue {
+        /// The span of the break.
+        span: Span,
+        /// The keyword of the loop being broken out of.
+        kind: &'static str,
+        /// The span of the loop being broken out of.
+        loop_span: Span,
+    },
     /// An error raised when attempting to return locally created references
     /// from a function.
     #[error("cannot return locally created references")]
@@ -722,6 +867,24 @@ pub enum CompileError {
         /// Where the float was used.
         span: Span,
     },
+    /// Attempting to use a range pattern with unsupported bounds.
+    #[error("range patterns require integer, character, or byte bounds of the same type")]
+    UnsupportedRangePattern {
+        /// Where the range pattern was used.
+        span: Span,
+    },
+    /// Attempting to use a range pattern which can never match.
+    #[error("lower bound of range pattern is greater than its upper bound")]
+    EmptyRangePattern {
+        /// Where the range pattern was used.
+        span: Span,
+    },
+    /// Attempting to use a pattern as an alternative which is not supported.
+    #[error("only literal and range patterns can be used as alternatives")]
+    UnsupportedPatternAlternative {
+        /// Where the alternative was used.
+        span: Span,
+    },
     /// Attempting to create an object with a duplicate object key.
     #[error("duplicate key in literal object")]
     DuplicateObjectKey {
@@ -771,12 +934,73 @@ pub enum CompileError {
         /// Where the expression is.
         span: Span,
     },
+    /// Tried to use an attribute which isn't supported where it's used.
+    #[error("unsupported attribute")]
+    UnsupportedAttribute {
+        /// Where the attribute is.
+        span: Span,
+    },
+    /// Tried to implement a protocol which doesn't exist.
+    #[error("no protocol named `{name}`")]
+    UnknownProtocol {
+        /// Where the protocol is named.
+        span: Span,
+        /// The name of the missing protocol.
+        name: String,
+    },
+    /// Tried to implement a protocol with the wrong number of arguments.
+    #[error("protocol `{protocol}` is implemented with {expected} arguments including `self`, but the function takes {actual}")]
+    ProtocolArgumentCount {
+        /// Where the function is declared.
+        span: Span,
+        /// The protocol being implemented.
+        protocol: Protocol,
+        /// The number of arguments expected.
+        expected: usize,
+        /// The number of arguments of the function.
+        actual: usize,
+    },
+    /// Tried to implement a protocol with an async function or a generator.
+    #[error("protocol `{protocol}` can't be implemented by an async function or a generator")]
+    UnsupportedProtocolCall {
+        /// Where the function is declared.
+        span: Span,
+        /// The protocol being implemented.
+        protocol: Protocol,
+    },
     /// Trying to use a filesystem module from an in-memory soruce.
     #[error("cannot load external modules from in-memory sources")]
     UnsupportedFileMod {
         /// The span where the error happened.
         span: Span,
     },
+    /// A `let` shadows a variable which is already declared, which isn't
+    /// permitted with the `strict` option.
+    #[error("variable `{name}` shadows an existing variable, assign to it instead")]
+    StrictShadowedVariable {
+        /// The span of the new binding.
+        span: Span,
+        /// The name of the variable.
+        name: String,
+        /// The span of the existing variable.
+        existing_span: Span,
+    },
+    /// A binding or function shadows a name from the prelude, which isn't
+    /// permitted with the `strict` option.
+    #[error("`{name}` shadows a builtin")]
+    StrictShadowedBuiltin {
+        /// The span of the binding.
+        span: Span,
+        /// The name of the builtin.
+        name: String,
+    },
+    /// A function or a closure produces a value without an explicit `return`,
+    /// which isn't permitted with the `strict` option.
+    #[error("functions and closures must use an explicit `return` to produce a value")]
+    StrictImplicitReturn {
+        /// The span of the expression producing the value.
+        span: Span,
+    },
 }
 
 impl CompileError {
@@ -803,6 +1027,11 @@ impl CompileError {
         match *self {
             Self::UnitBuilderError { .. } => Span::default(),
             Self::Internal { span, .. } => span,
+            Self::StackUnderflow { span, .. } => span,
+            Self::StackMismatch { span, .. } => span,
+            Self::StrictShadowedVariable { span, .. } => span,
+            Self::StrictShadowedBuiltin { span, .. } => span,
+            Self::StrictImplicitReturn { span, .. } => span,
             Self::Experimental { span, .. } => span,
             Self::ModNotFound { span, .. } => span,
             Self::ModFileError { span, .. } => span,
@@ -827,6 +1056,7 @@ impl CompileError {
             Self::UnsupportedUnaryOp { span, .. } => span,
             Self::UnsupportedBinaryOp { span, .. } => span,
             Self::UnsupportedLitObject { span, .. } => span,
+            Self::UnsupportedComputedKey { span, .. } => span,
             Self::UnsupportedAssignExpr { span, .. } => span,
             Self::UnsupportedAssignBinOp { span, .. } => span,
             Self::UnsupportedSelectPattern { span, .. } => span,
@@ -837,8 +1067,15 @@ impl CompileError {
             Self::UnsupportedPattern { span, .. } => span,
             Self::UnsupportedBinding { span, .. } => span,
             Self::BreakOutsideOfLoop { span, .. } => span,
+            Self::ContinueOutsideOfLoop { span, .. } => span,
+            Self::BranchProducesNothing { span, .. } => span,
+            Self::IfWithoutElse { span, .. } => span,
+            Self::UnsupportedBreakValue { span, .. } => span,
             Self::ReturnLocalReferences { span, .. } => span,
             Self::MatchFloatInPattern { span, .. } => span,
+            Self::UnsupportedRangePattern { span, .. } => span,
+            Self::EmptyRangePattern { span, .. } => span,
+            Self::UnsupportedPatternAlternative { span, .. } => span,
             Self::DuplicateObjectKey { span, .. } => span,
             Self::LitObjectMissingField { span, .. } => span,
             Self::LitObjectNotField { span, .. } => span,
@@ -849,6 +1086,185 @@ impl CompileError {
             Self::MissingPreludeModule { .. } => Span::empty(),
             Self::UnsupportedAsyncExpr { span, .. } => span,
             Self::UnsupportedFileMod { span, .. } => span,
+            Self::UnsupportedAttribute { span, .. } => span,
+            Self::UnknownProtocol { span, .. } => span,
+            Self::ProtocolArgumentCount { span, .. } => span,
+            Self::UnsupportedProtocolCall { span, .. } => span,
         }
     }
+
+    /// The stable code of the compile error, like `E0001`.
+    ///
+    /// Wrapped parse errors use the code of the parse error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Internal { .. } => "E0001",
+            Self::Experimental { .. } => "E0002",
+            Self::ModNotFound { .. } => "E0003",
+            Self::ModFileError { .. } => "E0004",
+            Self::ModAlreadyLoaded { .. } => "E0005",
+            Self::UnitBuilderError { .. } => "E0006",
+            Self::ParseError { error } => error.code(),
+            Self::ItemConflict { .. } => "E0008",
+            Self::VariableConflict { .. } => "E0009",
+            Self::MissingMacro { .. } => "E0010",
+            Self::CallMacroError { .. } => "E0011",
+            Self::MissingLocal { .. } => "E0012",
+            Self::MissingType { .. } => "E0013",
+            Self::MissingModule { .. } => "E0014",
+            Self::MissingLabel { .. } => "E0015",
+            Self::UnsupportedWildcard { .. } => "E0016",
+            Self::UnsupportedAsyncBlock { .. } => "E0017",
+            Self::UnsupportedInstanceFunction { .. } => "E0018",
+            Self::UnsupportedValue { .. } => "E0019",
+            Self::UnsupportedType { .. } => "E0020",
+            Self::UnsupportedSelf { .. } => "E0021",
+            Self::UnsupportedUnaryOp { .. } => "E0022",
+            Self::UnsupportedBinaryOp { .. } => "E0023",
+            Self::UnsupportedLitObject { .. } => "E0024",
+            Self::UnsupportedComputedKey { .. } => "E0025",
+            Self::LitObjectMissingField { .. } => "E0026",
+            Self::LitObjectNotField { .. } => "E0027",
+            Self::UnsupportedAssignExpr { .. } => "E0028",
+            Self::UnsupportedAssignBinOp { .. } => "E0029",
+            Self::UnsupportedRef { .. } => "E0030",
+            Self::UnsupportedAwait { .. } => "E0031",
+            Self::UnsupportedSelectPattern { .. } => "E0032",
+            Self::UnsupportedFieldAccess { .. } => "E0033",
+            Self::UnsupportedArgumentCount { .. } => "E0034",
+            Self::UnsupportedMetaPattern { .. } => "E0035",
+            Self::UnsupportedMetaClosure { .. } => "E0036",
+            Self::UnsupportedPattern { .. } => "E0037",
+            Self::UnsupportedBinding { .. } => "E0038",
+            Self::BreakOutsideOfLoop { .. } => "E0039",
+            Self::ReturnLocalReferences { .. } => "E0040",
+            Self::MatchFloatInPattern { .. } => "E0041",
+            Self::UnsupportedRangePattern { .. } => "E0042",
+            Self::EmptyRangePattern { .. } => "E0043",
+            Self::UnsupportedPatternAlternative { .. } => "E0044",
+            Self::DuplicateObjectKey { .. } => "E0045",
+            Self::MissingFunction { .. } => "E0046",
+            Self::YieldOutsideFunction { .. } => "E0047",
+            Self::AwaitOutsideFunction { .. } => "E0048",
+            Self::InstanceFunctionOutsideImpl { .. } => "E0049",
+            Self::MissingPreludeModule { .. } => "E0050",
+            Self::UnsupportedAsyncExpr { .. } => "E0051",
+            Self::UnsupportedAttribute { .. } => "E0052",
+            Self::UnknownProtocol { .. } => "E0053",
+            Self::ProtocolArgumentCount { .. } => "E0054",
+            Self::UnsupportedProtocolCall { .. } => "E0055",
+            Self::UnsupportedFileMod { .. } => "E0056",
+            Self::ContinueOutsideOfLoop { .. } => "E0057",
+            Self::UnsupportedBreakValue { .. } => "E0058",
+            Self::BranchProducesNothing { .. } => "E0059",
+            Self::IfWithoutElse { .. } => "E0060",
+            Self::StackUnderflow { .. } => "E0061",
+            Self::StackMismatch { .. } => "E0062",
+            Self::StrictShadowedVariable { .. } => "E0063",
+            Self::StrictShadowedBuiltin { .. } => "E0064",
+            Self::StrictImplicitReturn { .. } => "E0065",
+        }
+    }
+
+    /// Convert the compile error into a diagnostic, located in the source
+    /// with the given id.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rune::{CompileError, Severity};
+    /// use runestick::Span;
+    ///
+    /// let error = CompileError::MissingLabel { span: Span::new(1, 4) };
+    /// let diagnostic = error.to_diagnostic(0);
+    ///
+    /// assert_eq!(diagnostic.code(), "E0015");
+    /// assert_eq!(diagnostic.severity(), Severity::Error);
+    /// assert_eq!(diagnostic.primary().unwrap().span(), Span::new(1, 4));
+    /// ```
+    pub fn to_diagnostic(&self, source_id: usize) -> Diagnostic {
+        let mut diagnostic = Diagnostic::error(self.code(), self.to_string());
+
+        match self {
+            Self::ReturnLocalReferences {
+                block,
+                references_at,
+                span,
+                ..
+            } => {
+                for reference in references_at {
+                    if span.overlaps(*reference) {
+                        continue;
+                    }
+
+                    diagnostic = diagnostic.with_label(DiagnosticLabel::secondary(
+                        source_id,
+                        *reference,
+                        "reference created here",
+                    ));
+                }
+
+                diagnostic = diagnostic.with_label(DiagnosticLabel::secondary(
+                    source_id,
+                    *block,
+                    "block returned from",
+                ));
+            }
+            Self::DuplicateObjectKey {
+                existing, object, ..
+            } => {
+                diagnostic = diagnostic
+                    .with_label(DiagnosticLabel::secondary(
+                        source_id,
+                        *existing,
+                        "previously defined here",
+                    ))
+                    .with_label(DiagnosticLabel::secondary(
+                        source_id,
+                        *object,
+                        "object being defined here",
+                    ));
+            }
+            Self::UnsupportedBreakValue { loop_span, .. } => {
+                diagnostic = diagnostic.with_label(DiagnosticLabel::secondary(
+                    source_id,
+                    *loop_span,
+                    "this loop can't produce a value",
+                ));
+            }
+            Self::BranchProducesNothing { value, .. } | Self::IfWithoutElse { value, .. } => {
+                diagnostic = diagnostic.with_label(DiagnosticLabel::secondary(
+                    source_id,
+                    *value,
+                    "this branch produces a value",
+                ));
+            }
+            Self::StackMismatch { from, .. } => {
+                diagnostic = diagnostic.with_label(DiagnosticLabel::secondary(
+                    source_id,
+                    *from,
+                    "reached from here",
+                ));
+            }
+            Self::StrictShadowedVariable { existing_span, .. } => {
+                diagnostic = diagnostic.with_label(DiagnosticLabel::secondary(
+                    source_id,
+                    *existing_span,
+                    "previously declared here",
+                ));
+            }
+            Self::ModAlreadyLoaded { existing, .. } => {
+                let (existing_source_id, existing_span) = *existing;
+
+                diagnostic = diagnostic.with_label(DiagnosticLabel::secondary(
+                    existing_source_id,
+                    existing_span,
+                    "previously loaded here",
+                ));
+            }
+            _ => (),
+        }
+
+        diagnostic.with_label(DiagnosticLabel::primary(source_id, self.span(), ""))
+    }
 }
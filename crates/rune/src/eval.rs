@@ -0,0 +1,189 @@
+//! Evaluation of single expressions with host-supplied bindings.
+
+use crate::collections::HashMap;
+use crate::{LoadError, Options, Sources, Warnings};
+use runestick::{Context, FromValue, Item, ToValue, Tuple, Unit, Value, Vm, VmError};
+use std::cell::RefCell;
+use std::sync::{Arc, Weak};
+use thiserror::Error;
+
+/// The maximum number of compiled expressions cached per thread.
+const CACHE_CAPACITY: usize = 1024;
+
+thread_local! {
+    static CACHE: RefCell<HashMap<CacheKey, CacheEntry>> = RefCell::new(HashMap::new());
+}
+
+/// Compiled expressions are cached by the context they're compiled against,
+/// their source, and the names of their bindings.
+#[derive(PartialEq, Eq, Hash)]
+struct CacheKey {
+    context: usize,
+    expr: String,
+    names: Vec<String>,
+}
+
+struct CacheEntry {
+    /// Used to make sure the context the entry was compiled against is still
+    /// alive, since its address might otherwise be reused.
+    context: Weak<Context>,
+    unit: Arc<Unit>,
+}
+
+/// An error raised when evaluating an expression with [eval].
+#[derive(Debug, Error)]
+pub enum EvalError {
+    /// The expression failed to compile.
+    #[error("failed to compile expression")]
+    LoadError {
+        /// The source error.
+        #[from]
+        error: LoadError,
+    },
+    /// The expression failed to evaluate.
+    #[error("failed to evaluate expression")]
+    VmError {
+        /// The source error.
+        #[from]
+        error: VmError,
+    },
+    /// The name of a binding isn't a valid identifier.
+    #[error("`{name}` is not a valid identifier")]
+    InvalidName {
+        /// The invalid name.
+        name: String,
+    },
+}
+
+/// Evaluate a single expression, where the given bindings are available as
+/// variables.
+///
+/// This is intended for hosts like spreadsheets or templating engines, which
+/// evaluate many small formulas rather than whole scripts. Compiled
+/// expressions are cached per thread by the context, the expression, and the
+/// names of the bindings, so evaluating the same formula with different values
+/// only compiles it once.
+///
+/// The names of the bindings must be valid identifiers. Input which isn't a
+/// single expression, like a sequence of statements, is rejected with a parse
+/// error, and the spans of errors point into `expr` as it was given.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::Context;
+/// use std::sync::Arc;
+///
+/// # fn main() -> Result<(), rune::EvalError> {
+/// let context = Arc::new(Context::with_default_modules().unwrap());
+///
+/// let output: i64 = rune::eval(&context, "a + b * 2", vec![("a", 1), ("b", 2)])?;
+/// assert_eq!(output, 5);
+///
+/// let output: i64 = rune::eval(&context, "a + b * 2", vec![("a", 3), ("b", 4)])?;
+/// assert_eq!(output, 11);
+/// # Ok(())
+/// # }
+/// ```
+pub fn eval<I, N, V, T>(context: &Arc<Context>, expr: &str, bindings: I) -> Result<T, EvalError>
+where
+    I: IntoIterator<Item = (N, V)>,
+    N: AsRef<str>,
+    V: ToValue,
+    T: FromValue,
+{
+    let mut bindings = bindings
+        .into_iter()
+        .map(|(name, value)| Ok((name.as_ref().to_owned(), value.to_value()?)))
+        .collect::<Result<Vec<(String, Value)>, VmError>>()?;
+
+    // NB: bindings are sorted so that the order they're given in doesn't
+    // affect caching.
+    bindings.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let (names, args): (Vec<_>, Vec<_>) = bindings.into_iter().unzip();
+
+    if let Some(name) = names.iter().find(|name| !crate::refactor::is_ident(name)) {
+        return Err(EvalError::InvalidName { name: name.clone() });
+    }
+
+    let unit = compile_cached(context, expr, names)?;
+    let vm = Vm::new(context.clone(), unit);
+
+    let mut execution = if args.is_empty() {
+        vm.call(Item::of(&["eval"]), ())?
+    } else {
+        vm.call(Item::of(&["eval"]), (Tuple::from(args),))?
+    };
+
+    let output = execution.complete()?;
+    Ok(T::from_value(output)?)
+}
+
+/// Compile the given expression, or get it from the cache.
+fn compile_cached(
+    context: &Arc<Context>,
+    expr: &str,
+    names: Vec<String>,
+) -> Result<Arc<Unit>, LoadError> {
+    let key = CacheKey {
+        context: Arc::as_ptr(context) as usize,
+        expr: expr.to_owned(),
+        names,
+    };
+
+    let cached = CACHE.with(|cache| {
+        let cache = cache.borrow();
+        let entry = cache.get(&key)?;
+
+        if entry.context.strong_count() == 0 {
+            return None;
+        }
+
+        Some(entry.unit.clone())
+    });
+
+    if let Some(unit) = cached {
+        return Ok(unit);
+    }
+
+    // NB: bindings are passed as a single tuple which is destructured, unless
+    // there are none in which case the function takes no arguments.
+    let args = if key.names.is_empty() {
+        String::new()
+    } else {
+        format!("({},)", key.names.join(", "))
+    };
+
+    let mut sources = Sources::new();
+    crate::load::insert_expr_fn(&mut sources, "expr", "eval", &args, expr)?;
+
+    let unit = Arc::new(crate::load_sources(
+        context,
+        &Options::default(),
+        &mut sources,
+        &mut Warnings::disabled(),
+    )?);
+
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+
+        if cache.len() >= CACHE_CAPACITY {
+            cache.retain(|_, entry| entry.context.strong_count() > 0);
+
+            if cache.len() >= CACHE_CAPACITY {
+                cache.clear();
+            }
+        }
+
+        cache.insert(
+            key,
+            CacheEntry {
+                context: Arc::downgrade(context),
+                unit: unit.clone(),
+            },
+        );
+    });
+
+    Ok(unit)
+}
@@ -3,12 +3,15 @@ use crate::collections::HashMap;
 use crate::error::{CompileError, CompileResult};
 use crate::index_scopes::IndexScopes;
 use crate::items::Items;
-use crate::query::{Build, BuildEntry, Function, Indexed, IndexedEntry, InstanceFunction, Query};
+use crate::query::{
+    Build, BuildEntry, Function, Indexed, IndexedEntry, InstanceFunction, Query, Symbol,
+    SymbolKind, Symbols,
+};
 use crate::sources::Sources;
 use crate::traits::Resolve as _;
 use crate::warning::Warnings;
 use crate::{SourceId, UnitBuilder};
-use runestick::{Call, CompileMeta, Context, Hash, Item, Source, Span, Type};
+use runestick::{Call, CompileMeta, Context, Item, Source, Span, Type};
 use std::collections::VecDeque;
 use std::sync::Arc;
 
@@ -36,11 +39,13 @@ pub(crate) struct Import {
 }
 
 impl Import {
-    /// Process the import, populating the unit.
+    /// Process the import, populating the unit and recording references to the
+    /// imported items.
     pub(crate) fn process(
         self,
         context: &Context,
         unit: &mut UnitBuilder,
+        symbols: &mut Symbols,
     ) -> Result<(), CompileError> {
         let Self {
             item,
@@ -57,6 +62,7 @@ impl Import {
 
         let mut it = decl_use.rest.iter();
         let last = it.next_back();
+        let mut path = decl_use.first.span();
 
         for (_, c) in it {
             match c {
@@ -65,6 +71,7 @@ impl Import {
                 }
                 ast::DeclUseComponent::Ident(ident) => {
                     name.push(ident.resolve(&*source)?);
+                    path = path.join(ident.span());
                 }
             }
         }
@@ -73,14 +80,15 @@ impl Import {
             match c {
                 ast::DeclUseComponent::Wildcard(..) => {
                     let mut new_names = Vec::new();
+                    let target = unit.map_item(name.clone());
 
-                    if !context.contains_prefix(&name) && !unit.contains_prefix(&name) {
+                    if !context.contains_prefix(&target) && !unit.contains_prefix(&target) {
                         return Err(CompileError::MissingModule { span, item: name });
                     }
 
                     let iter = context
-                        .iter_components(&name)
-                        .chain(unit.iter_components(&name));
+                        .iter_components(&target)
+                        .chain(unit.iter_components(&target));
 
                     for c in iter {
                         let mut name = name.clone();
@@ -91,10 +99,13 @@ impl Import {
                     for name in new_names {
                         unit.new_import(item.clone(), &name, span, source_id)?;
                     }
+
+                    symbols.reference(&name, source_id, path);
                 }
                 ast::DeclUseComponent::Ident(ident) => {
                     name.push(ident.resolve(&*source)?);
                     unit.new_import(item.clone(), &name, span, source_id)?;
+                    symbols.reference(&name, source_id, path.join(ident.span()));
                 }
             }
         }
@@ -138,6 +149,24 @@ impl<'a> Indexer<'a> {
         }
     }
 
+    /// Declare a symbol for the current item, using the source text covered by
+    /// `signature` as its signature.
+    fn declare_symbol(&mut self, kind: SymbolKind, span: Span, signature: Span) {
+        let signature = self
+            .source
+            .source(signature)
+            .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+            .unwrap_or_default();
+
+        self.query.symbols.declare(Symbol {
+            item: self.items.item(),
+            kind,
+            source_id: self.source_id,
+            span,
+            signature,
+        });
+    }
+
     /// Handle a filesystem module.
     pub(crate) fn handle_file_mod(&mut self, decl_mod: &ast::DeclMod) -> CompileResult<()> {
         let span = decl_mod.span();
@@ -203,6 +232,7 @@ impl<'a> Indexer<'a> {
             }
         };
 
+        self.declare_symbol(SymbolKind::Module, decl_mod.name.span(), span);
         self.sources.insert(item, source);
         Ok(())
     }
@@ -251,6 +281,9 @@ impl Index<ast::DeclFn> for Indexer<'_> {
                     let ident = ident.resolve(&*self.source)?;
                     self.scopes.declare(ident, span)?;
                 }
+                ast::FnArg::Pat(pat) => {
+                    self.index(pat)?;
+                }
                 _ => (),
             }
         }
@@ -260,6 +293,18 @@ impl Index<ast::DeclFn> for Indexer<'_> {
         let f = guard.into_function(span)?;
         let call = Self::call(f.generator, f.is_async);
 
+        if matches!(call, Call::Immediate) && !f.returns_value && decl_fn.body.produces_unit() {
+            self.query.mark_unit_function(item.clone());
+        }
+
+        let kind = if decl_fn.is_instance() {
+            SymbolKind::InstanceFunction { call }
+        } else {
+            SymbolKind::Function { call }
+        };
+
+        self.declare_symbol(kind, decl_fn.name.span(), decl_fn.item_span());
+
         let fun = Function {
             ast: decl_fn.clone(),
             call,
@@ -289,11 +334,16 @@ impl Index<ast::DeclFn> for Indexer<'_> {
             });
 
             let meta = CompileMeta::Function {
-                value_type: Type::Hash(Hash::type_hash(&item)),
+                value_type: Type::Hash(item.type_hash()),
                 item: item.clone(),
             };
 
             self.query.unit.borrow_mut().insert_meta(meta)?;
+        } else if let Some(attribute) = decl_fn.attributes.first() {
+            // NB: attributes are only supported on instance functions.
+            return Err(CompileError::UnsupportedAttribute {
+                span: attribute.span(),
+            });
         } else if is_toplevel {
             // NB: immediately compile all toplevel functions.
             self.query.queue.push_back(BuildEntry {
@@ -307,7 +357,7 @@ impl Index<ast::DeclFn> for Indexer<'_> {
                 .unit
                 .borrow_mut()
                 .insert_meta(CompileMeta::Function {
-                    value_type: Type::Hash(Hash::type_hash(&item)),
+                    value_type: Type::Hash(item.type_hash()),
                     item,
                 })?;
         } else {
@@ -413,6 +463,12 @@ impl Index<ast::Pat> for Indexer<'_> {
             ast::Pat::PatString(..) => (),
             ast::Pat::PatUnit(..) => (),
             ast::Pat::PatChar(..) => (),
+            ast::Pat::PatRange(..) => (),
+            ast::Pat::PatOr(pat_or) => {
+                for pat in pat_or.alternatives() {
+                    self.index(pat)?;
+                }
+            }
         }
 
         Ok(())
@@ -515,6 +571,7 @@ impl Index<ast::Expr> for Indexer<'_> {
             ast::Expr::ExprBreak(expr_break) => {
                 self.index(expr_break)?;
             }
+            ast::Expr::ExprContinue(..) => (),
             ast::Expr::ExprYield(expr_yield) => {
                 self.index(expr_yield)?;
             }
@@ -537,6 +594,21 @@ impl Index<ast::Expr> for Indexer<'_> {
             ast::Expr::LitTemplate(lit_template) => {
                 self.index(lit_template)?;
             }
+            ast::Expr::LitObject(lit_object) => {
+                self.index(lit_object)?;
+            }
+            ast::Expr::LitTuple(lit_tuple) => {
+                self.index(lit_tuple)?;
+            }
+            ast::Expr::LitVec(lit_vec) => {
+                self.index(lit_vec)?;
+            }
+            ast::Expr::LitMap(lit_map) => {
+                self.index(lit_map)?;
+            }
+            ast::Expr::LitSet(lit_set) => {
+                self.index(lit_set)?;
+            }
             // NB: literals have nothing to index, they don't export language
             // items.
             ast::Expr::LitUnit(..) => (),
@@ -544,11 +616,8 @@ impl Index<ast::Expr> for Indexer<'_> {
             ast::Expr::LitByte(..) => (),
             ast::Expr::LitChar(..) => (),
             ast::Expr::LitNumber(..) => (),
-            ast::Expr::LitObject(..) => (),
             ast::Expr::LitStr(..) => (),
             ast::Expr::LitByteStr(..) => (),
-            ast::Expr::LitTuple(..) => (),
-            ast::Expr::LitVec(..) => (),
             // NB: macros have nothing to index, they don't export language
             // items.
             ast::Expr::ExprCallMacro(expr_call_macro) => {
@@ -646,6 +715,12 @@ impl Index<ast::Decl> for Indexer<'_> {
                 let span = decl_enum.span();
                 let enum_item = self.items.item();
 
+                self.declare_symbol(
+                    SymbolKind::Enum,
+                    decl_enum.name.span(),
+                    decl_enum.enum_.span().join(decl_enum.name.span()),
+                );
+
                 self.query.index_enum(
                     enum_item.clone(),
                     self.source.clone(),
@@ -658,6 +733,14 @@ impl Index<ast::Decl> for Indexer<'_> {
 
                     let span = variant.span();
 
+                    let signature = match body {
+                        ast::DeclStructBody::EmptyBody(..) => span,
+                        ast::DeclStructBody::TupleBody(body) => span.join(body.span()),
+                        ast::DeclStructBody::StructBody(body) => span.join(body.span()),
+                    };
+
+                    self.declare_symbol(SymbolKind::Variant, span, signature);
+
                     self.query.index_variant(
                         self.items.item(),
                         enum_item.clone(),
@@ -673,6 +756,12 @@ impl Index<ast::Decl> for Indexer<'_> {
                     .items
                     .push_name(decl_struct.ident.resolve(&*self.source)?);
 
+                self.declare_symbol(
+                    SymbolKind::Struct,
+                    decl_struct.ident.span(),
+                    decl_struct.span(),
+                );
+
                 self.query.index_struct(
                     self.items.item(),
                     decl_struct.clone(),
@@ -690,7 +779,13 @@ impl Index<ast::Decl> for Indexer<'_> {
                     guards.push(self.items.push_name(ident.resolve(&*self.source)?));
                 }
 
-                self.impl_items.push(self.items.item());
+                let impl_item = self.items.item();
+
+                self.query
+                    .symbols
+                    .reference(&impl_item, self.source_id, decl_impl.path.span());
+
+                self.impl_items.push(impl_item);
 
                 for decl_fn in &decl_impl.functions {
                     self.index(decl_fn)?;
@@ -702,6 +797,9 @@ impl Index<ast::Decl> for Indexer<'_> {
                 if let Some(body) = &decl_mod.body {
                     let name = decl_mod.name.resolve(&*self.source)?;
                     let _guard = self.items.push_name(name);
+
+                    let signature = decl_mod.mod_.span().join(decl_mod.name.span());
+                    self.declare_symbol(SymbolKind::Module, decl_mod.name.span(), signature);
                     self.index(&*body.file)?;
                 } else {
                     self.handle_file_mod(decl_mod)?;
@@ -769,6 +867,9 @@ impl Index<ast::ExprClosure> for Indexer<'_> {
                     self.scopes.declare(ident, span)?;
                 }
                 ast::FnArg::Ignore(..) => (),
+                ast::FnArg::Pat(pat) => {
+                    self.index(pat)?;
+                }
             }
         }
 
@@ -826,12 +927,7 @@ impl Index<ast::ExprIndexGet> for Indexer<'_> {
 impl Index<ast::ExprBreak> for Indexer<'_> {
     fn index(&mut self, expr_break: &ast::ExprBreak) -> Result<(), CompileError> {
         if let Some(expr) = &expr_break.expr {
-            match expr {
-                ast::ExprBreakValue::Expr(expr) => {
-                    self.index(&**expr)?;
-                }
-                ast::ExprBreakValue::Label(..) => (),
-            }
+            self.index(&**expr)?;
         }
 
         Ok(())
@@ -854,6 +950,7 @@ impl Index<ast::ExprYield> for Indexer<'_> {
 impl Index<ast::ExprReturn> for Indexer<'_> {
     fn index(&mut self, expr_return: &ast::ExprReturn) -> Result<(), CompileError> {
         if let Some(expr) = expr_return.expr.as_deref() {
+            self.scopes.mark_return_value();
             self.index(expr)?;
         }
 
@@ -901,6 +998,23 @@ impl Index<ast::ExprSelect> for Indexer<'_> {
 
 impl Index<ast::ExprCall> for Indexer<'_> {
     fn index(&mut self, expr_call: &ast::ExprCall) -> Result<(), CompileError> {
+        // NB: instance calls compile their target before their arguments, so
+        // they need to be indexed in the same order for closures and async
+        // blocks to be assigned the same items.
+        if let ast::Expr::ExprFieldAccess(ast::ExprFieldAccess {
+            expr_field: ast::ExprField::Ident(..),
+            ..
+        }) = &*expr_call.expr
+        {
+            self.index(&*expr_call.expr)?;
+
+            for (expr, _) in expr_call.args.items.iter() {
+                self.index(expr)?;
+            }
+
+            return Ok(());
+        }
+
         for (expr, _) in expr_call.args.items.iter() {
             self.index(expr)?;
         }
@@ -910,6 +1024,73 @@ impl Index<ast::ExprCall> for Indexer<'_> {
     }
 }
 
+impl Index<ast::LitObject> for Indexer<'_> {
+    fn index(&mut self, lit_object: &ast::LitObject) -> Result<(), CompileError> {
+        for assign in &lit_object.assignments {
+            if let ast::LitObjectFieldKey::Computed(key) = &assign.key {
+                self.index(&*key.expr)?;
+            }
+
+            match &assign.assign {
+                Some((_, expr)) => {
+                    self.index(expr)?;
+                }
+                None => {
+                    if let ast::LitObjectFieldKey::Key(ast::LitObjectKey::Ident(ident)) =
+                        &assign.key
+                    {
+                        let ident = ident.resolve(&*self.source)?;
+                        self.scopes.mark_use(ident);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Index<ast::LitTuple> for Indexer<'_> {
+    fn index(&mut self, lit_tuple: &ast::LitTuple) -> Result<(), CompileError> {
+        for (expr, _) in &lit_tuple.items {
+            self.index(expr)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Index<ast::LitVec> for Indexer<'_> {
+    fn index(&mut self, lit_vec: &ast::LitVec) -> Result<(), CompileError> {
+        for expr in &lit_vec.items {
+            self.index(expr)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Index<ast::LitMap> for Indexer<'_> {
+    fn index(&mut self, lit_map: &ast::LitMap) -> Result<(), CompileError> {
+        for entry in &lit_map.entries {
+            self.index(&entry.key)?;
+            self.index(&entry.value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Index<ast::LitSet> for Indexer<'_> {
+    fn index(&mut self, lit_set: &ast::LitSet) -> Result<(), CompileError> {
+        for expr in &lit_set.items {
+            self.index(expr)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl Index<ast::LitTemplate> for Indexer<'_> {
     fn index(&mut self, lit_template: &ast::LitTemplate) -> Result<(), CompileError> {
         let template = lit_template.resolve(&*self.source)?;
@@ -48,6 +48,7 @@ impl IndexScopeGuard {
                 generator: fun.generator,
                 is_async: fun.is_async,
                 has_await: fun.has_await,
+                returns_value: fun.returns_value,
             }),
             _ => Err(CompileError::internal("expected function", span)),
         }
@@ -106,6 +107,7 @@ pub(crate) struct Function {
     pub(crate) is_async: bool,
     #[allow(dead_code)]
     pub(crate) has_await: bool,
+    pub(crate) returns_value: bool,
 }
 
 pub(crate) struct Closure {
@@ -122,6 +124,8 @@ pub struct IndexFunction {
     scope: IndexScope,
     generator: bool,
     has_await: bool,
+    /// If the function contains a `return` with a value.
+    returns_value: bool,
 }
 
 impl IndexFunction {
@@ -132,6 +136,7 @@ impl IndexFunction {
             scope: IndexScope::new(),
             generator: false,
             has_await: false,
+            returns_value: false,
         }
     }
 }
@@ -264,6 +269,26 @@ impl IndexScopes {
         Err(CompileError::YieldOutsideFunction { span })
     }
 
+    /// Mark that a `return` with a value was used in the encapsulating
+    /// function.
+    ///
+    /// Returns inside of closures don't affect the function they are declared
+    /// in.
+    pub fn mark_return_value(&mut self) {
+        let mut levels = self.levels.borrow_mut();
+
+        for level in levels.iter_mut().rev() {
+            match level {
+                IndexScopeLevel::IndexFunction(fun) => {
+                    fun.returns_value = true;
+                    return;
+                }
+                IndexScopeLevel::IndexClosure(..) => return,
+                IndexScopeLevel::IndexScope(..) => (),
+            }
+        }
+    }
+
     /// Mark that a yield was used, meaning the encapsulating function is a
     /// generator.
     pub fn mark_await(&mut self, span: Span) -> Result<(), CompileError> {
@@ -8,6 +8,8 @@ use runestick::Span;
 pub struct Lexer<'a> {
     cursor: usize,
     source: &'a str,
+    /// Errors for the input which produced error tokens, in order.
+    errors: Vec<ParseError>,
 }
 
 impl<'a> Lexer<'a> {
@@ -37,7 +39,7 @@ impl<'a> Lexer<'a> {
     /// };
     /// ```
     pub fn new(source: &'a str) -> Self {
-        Self { cursor: 0, source }
+        Self::new_with_start(source, 0)
     }
 
     /// Construct a new lexer with the given start.
@@ -45,9 +47,16 @@ impl<'a> Lexer<'a> {
         Self {
             cursor: start,
             source,
+            errors: Vec::new(),
         }
     }
 
+    /// The errors for every [Error][ast::Kind::Error] token produced so far,
+    /// in the order they were produced.
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
+
     /// Access the end span of the input.
     pub fn end(&self) -> Span {
         Span::point(self.source.len())
@@ -101,8 +110,10 @@ impl<'a> Lexer<'a> {
             "true" => ast::Kind::True,
             "false" => ast::Kind::False,
             "is" => ast::Kind::Is,
+            "as" => ast::Kind::As,
             "not" => ast::Kind::Not,
             "break" => ast::Kind::Break,
+            "continue" => ast::Kind::Continue,
             "yield" => ast::Kind::Yield,
             "return" => ast::Kind::Return,
             "await" => ast::Kind::Await,
@@ -129,13 +140,20 @@ impl<'a> Lexer<'a> {
         it: &mut I,
         c: char,
         start: usize,
-        is_negative: bool,
     ) -> Result<Option<ast::Token>, ParseError>
     where
         I: Clone + Iterator<Item = (usize, char)>,
     {
         let mut is_fractional = false;
 
+        // NB: a number immediately following a single dot is a tuple index,
+        // so a dot following it is not a fraction but another field access,
+        // like in `a.0.1`.
+        let is_index = {
+            let mut before = self.source[..start].chars().rev();
+            before.next() == Some('.') && before.next() != Some('.')
+        };
+
         let number = if let ('0', Some((_, m))) = (c, it.clone().next()) {
             // This loop is useful.
             #[allow(clippy::never_loop)]
@@ -163,13 +181,20 @@ impl<'a> Lexer<'a> {
 
             match c {
                 c if char::is_alphanumeric(c) => (),
-                '.' if !is_fractional => {
-                    is_fractional = true;
-
-                    // char immediately following a dot should be numerical.
-                    if !it.next().map(|(_, c)| c.is_numeric()).unwrap_or_default() {
+                '.' if !is_fractional && !is_index => {
+                    // char immediately following a dot should be numerical,
+                    // otherwise the dot is not part of the number, like in
+                    // `1..=2`.
+                    if !it
+                        .clone()
+                        .next()
+                        .map(|(_, c)| c.is_numeric())
+                        .unwrap_or_default()
+                    {
                         break self.cursor + n;
                     }
+
+                    is_fractional = true;
                 }
                 _ => break self.cursor + n,
             }
@@ -178,7 +203,6 @@ impl<'a> Lexer<'a> {
         Ok(Some(ast::Token {
             kind: ast::Kind::LitNumber {
                 is_fractional,
-                is_negative,
                 number,
             },
             span: Span {
@@ -498,8 +522,46 @@ impl<'a> Lexer<'a> {
     }
 
     /// Consume the next token from the lexer.
+    ///
+    /// Input which isn't recognized doesn't stop the lexer. It instead
+    /// produces an [Error][ast::Kind::Error] token covering it, and the error
+    /// describing it is recorded in [errors][Lexer::errors]. Lexing then
+    /// resumes after it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rune::Lexer;
+    /// use rune::ast::Kind;
+    ///
+    /// let mut lexer = Lexer::new("a $ b");
+    /// let mut kinds = Vec::new();
+    ///
+    /// while let Some(token) = lexer.next().unwrap() {
+    ///     kinds.push(token.kind);
+    /// }
+    ///
+    /// assert_eq!(kinds, vec![Kind::Ident, Kind::Error, Kind::Ident]);
+    /// assert_eq!(lexer.errors().len(), 1);
+    /// ```
     #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Result<Option<ast::Token>, ParseError> {
+        match self.next_token() {
+            Ok(token) => Ok(token),
+            Err(error) => {
+                let span = error.span();
+                self.cursor = span.end;
+                self.errors.push(error);
+
+                Ok(Some(ast::Token {
+                    kind: ast::Kind::Error,
+                    span,
+                }))
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<ast::Token>, ParseError> {
         let mut it = self.source[self.cursor..].char_indices();
 
         'outer: while let Some((start, c)) = it.next() {
@@ -581,7 +643,7 @@ impl<'a> Lexer<'a> {
                         ('<', '<') => {
                             it.next();
 
-                            break if matches!(it.next().clone(), Some((_, '='))) {
+                            break if matches!(it.clone().next(), Some((_, '='))) {
                                 it.next();
                                 ast::Kind::LtLtEq
                             } else {
@@ -591,7 +653,7 @@ impl<'a> Lexer<'a> {
                         ('>', '>') => {
                             it.next();
 
-                            break if matches!(it.next().clone(), Some((_, '='))) {
+                            break if matches!(it.clone().next(), Some((_, '='))) {
                                 it.next();
                                 ast::Kind::GtGtEq
                             } else {
@@ -600,23 +662,23 @@ impl<'a> Lexer<'a> {
                         }
                         ('.', '.') => {
                             it.next();
-                            break ast::Kind::DotDot;
+
+                            break if matches!(it.clone().next(), Some((_, '='))) {
+                                it.next();
+                                ast::Kind::DotDotEq
+                            } else {
+                                ast::Kind::DotDot
+                            };
                         }
                         ('=', '>') => {
                             it.next();
                             break ast::Kind::Rocket;
                         }
-                        ('-', c @ '0'..='9') => {
-                            it.next();
-                            return self.next_number_literal(&mut it, c, start, true);
-                        }
                         ('b', '\'') => {
-                            it.next();
                             it.next();
                             return self.next_lit_byte(&mut it, start);
                         }
                         ('b', '"') => {
-                            it.next();
                             it.next();
                             return self.next_lit_byte_str(&mut it, start);
                         }
@@ -654,7 +716,7 @@ impl<'a> Lexer<'a> {
                         return self.next_ident(&mut it, start);
                     }
                     '0'..='9' => {
-                        return self.next_number_literal(&mut it, c, start, false);
+                        return self.next_number_literal(&mut it, c, start);
                     }
                     '"' => {
                         return self.next_lit_str(&mut it, start);
@@ -706,6 +768,53 @@ mod tests {
         }}
     }
 
+    #[test]
+    fn test_error_tokens() {
+        let mut it = Lexer::new("a \u{a4} b \"c");
+
+        assert_eq!(
+            it.next().unwrap(),
+            Some(ast::Token {
+                span: Span::new(0, 1),
+                kind: ast::Kind::Ident,
+            })
+        );
+
+        assert_eq!(
+            it.next().unwrap(),
+            Some(ast::Token {
+                span: Span::new(2, 4),
+                kind: ast::Kind::Error,
+            })
+        );
+
+        assert_eq!(
+            it.next().unwrap(),
+            Some(ast::Token {
+                span: Span::new(5, 6),
+                kind: ast::Kind::Ident,
+            })
+        );
+
+        assert_eq!(
+            it.next().unwrap(),
+            Some(ast::Token {
+                span: Span::new(7, 9),
+                kind: ast::Kind::Error,
+            })
+        );
+
+        assert_eq!(it.next().unwrap(), None);
+
+        assert!(matches!(
+            it.errors(),
+            [
+                crate::ParseError::UnexpectedChar { c: '\u{a4}', .. },
+                crate::ParseError::UnterminatedStrLit { .. },
+            ]
+        ));
+    }
+
     #[test]
     fn test_char_literal() {
         test_lexer! {
@@ -783,6 +892,29 @@ mod tests {
                 kind: ast::Kind::SlashEq,
             }
         };
+        test_lexer! {
+            "a<<b>>=c",
+            ast::Token {
+                span: Span::new(0, 1),
+                kind: ast::Kind::Ident,
+            },
+            ast::Token {
+                span: Span::new(1, 3),
+                kind: ast::Kind::LtLt,
+            },
+            ast::Token {
+                span: Span::new(3, 4),
+                kind: ast::Kind::Ident,
+            },
+            ast::Token {
+                span: Span::new(4, 7),
+                kind: ast::Kind::GtGtEq,
+            },
+            ast::Token {
+                span: Span::new(7, 8),
+                kind: ast::Kind::Ident,
+            },
+        };
     }
 
     #[test]
@@ -809,7 +941,6 @@ mod tests {
                 span: Span::new(14, 16),
                 kind: ast::Kind::LitNumber {
                     is_fractional: false,
-                    is_negative: false,
                     number: ast::NumberKind::Decimal,
                 },
             },
@@ -820,6 +951,108 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_range() {
+        test_lexer! {
+            "1..=2",
+            ast::Token {
+                span: Span::new(0, 1),
+                kind: ast::Kind::LitNumber {
+                    is_fractional: false,
+                    number: ast::NumberKind::Decimal,
+                },
+            },
+            ast::Token {
+                span: Span::new(1, 4),
+                kind: ast::Kind::DotDotEq,
+            },
+            ast::Token {
+                span: Span::new(4, 5),
+                kind: ast::Kind::LitNumber {
+                    is_fractional: false,
+                    number: ast::NumberKind::Decimal,
+                },
+            },
+        };
+    }
+
+    #[test]
+    fn test_subtraction() {
+        test_lexer! {
+            "a-1",
+            ast::Token {
+                span: Span::new(0, 1),
+                kind: ast::Kind::Ident,
+            },
+            ast::Token {
+                span: Span::new(1, 2),
+                kind: ast::Kind::Dash,
+            },
+            ast::Token {
+                span: Span::new(2, 3),
+                kind: ast::Kind::LitNumber {
+                    is_fractional: false,
+                    number: ast::NumberKind::Decimal,
+                },
+            },
+        };
+    }
+
+    #[test]
+    fn test_tuple_index() {
+        test_lexer! {
+            "a.0.1",
+            ast::Token {
+                span: Span::new(0, 1),
+                kind: ast::Kind::Ident,
+            },
+            ast::Token {
+                span: Span::new(1, 2),
+                kind: ast::Kind::Dot,
+            },
+            ast::Token {
+                span: Span::new(2, 3),
+                kind: ast::Kind::LitNumber {
+                    is_fractional: false,
+                    number: ast::NumberKind::Decimal,
+                },
+            },
+            ast::Token {
+                span: Span::new(3, 4),
+                kind: ast::Kind::Dot,
+            },
+            ast::Token {
+                span: Span::new(4, 5),
+                kind: ast::Kind::LitNumber {
+                    is_fractional: false,
+                    number: ast::NumberKind::Decimal,
+                },
+            },
+        };
+
+        test_lexer! {
+            "1..2.5",
+            ast::Token {
+                span: Span::new(0, 1),
+                kind: ast::Kind::LitNumber {
+                    is_fractional: false,
+                    number: ast::NumberKind::Decimal,
+                },
+            },
+            ast::Token {
+                span: Span::new(1, 3),
+                kind: ast::Kind::DotDot,
+            },
+            ast::Token {
+                span: Span::new(3, 6),
+                kind: ast::Kind::LitNumber {
+                    is_fractional: true,
+                    number: ast::NumberKind::Decimal,
+                },
+            },
+        };
+    }
+
     #[test]
     fn test_template_literals() {
         test_lexer! {
@@ -844,9 +1077,9 @@ mod tests {
         };
 
         test_lexer! {
-            "b'\\\\''",
+            "b'\\\\'",
             ast::Token {
-                span: Span::new(0, 6),
+                span: Span::new(0, 5),
                 kind: ast::Kind::LitByte,
             },
         };
@@ -882,5 +1115,21 @@ mod tests {
                 kind: ast::Kind::LitByte,
             },
         };
+
+        test_lexer! {
+            "b'\\''",
+            ast::Token {
+                span: Span::new(0, 5),
+                kind: ast::Kind::LitByte,
+            },
+        };
+
+        test_lexer! {
+            "b\"\\x01\"",
+            ast::Token {
+                span: Span::new(0, 7),
+                kind: ast::Kind::LitByteStr { escaped: true },
+            },
+        };
     }
 }
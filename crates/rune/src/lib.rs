@@ -159,11 +159,15 @@
 
 mod assembly;
 pub mod ast;
+pub mod capabilities;
+mod compatibility;
 mod compile;
 mod compiler;
+mod diagnostic;
 #[cfg(feature = "diagnostics")]
 mod diagnostics;
 mod error;
+mod eval;
 mod index;
 mod index_scopes;
 mod items;
@@ -173,12 +177,16 @@ mod load_error;
 mod loops;
 mod macro_context;
 mod macros;
+mod optimize;
 mod options;
 mod parser;
-mod query;
+pub mod query;
 mod quote;
+pub mod refactor;
 mod scopes;
+mod semantic;
 mod sources;
+mod stack_effect;
 mod token_stream;
 mod traits;
 mod unit_builder;
@@ -194,16 +202,20 @@ mod collections {
 }
 
 pub use crate::assembly::Assembly;
+pub use crate::compatibility::{ArityMismatch, CompatibilityReport, MissingFunction};
+pub use crate::diagnostic::{Diagnostic, DiagnosticLabel, Diagnostics, Severity};
 pub use crate::error::{CompileError, ParseError};
+pub use crate::eval::{eval, EvalError};
 pub use crate::lexer::Lexer;
-pub use crate::load::{load_path, load_sources};
+pub use crate::load::{compile_expressions, load_path, load_sources, load_sources_with_symbols};
 pub use crate::load_error::{LoadError, LoadErrorKind};
 pub use crate::macro_context::MacroContext;
 pub use crate::options::Options;
-pub use crate::parser::Parser;
+pub use crate::parser::{Checkpoint, Parser};
+pub use crate::semantic::{semantic_tokens, SemanticToken, SemanticTokenKind};
 pub use crate::sources::Sources;
 pub use crate::token_stream::{IntoTokens, TokenStream, TokenStreamIter};
-pub use crate::traits::{Parse, Resolve};
+pub use crate::traits::{Parse, Resolve, ResolveOwned};
 pub use crate::warning::{Warning, WarningKind, Warnings};
 pub use compiler::compile;
 pub use unit_builder::{ImportEntry, ImportKey, UnitBuilder};
@@ -230,6 +242,13 @@ pub fn default_context() -> Result<runestick::Context, runestick::ContextError>
         context.install(&rune_modules::process::module()?)?;
         context.install(&rune_modules::fs::module()?)?;
         context.install(&rune_modules::signal::module()?)?;
+        context.install(&rune_modules::csv::module()?)?;
+        context.install(&rune_modules::hash::module()?)?;
+        context.install(&rune_modules::uuid::module()?)?;
+        context.install(&rune_modules::template::module()?)?;
+        context.install(&rune_modules::db::module()?)?;
+        context.install(&rune_modules::msgpack::module()?)?;
+        context.install(&rune_modules::cbor::module()?)?;
     }
 
     Ok(context)
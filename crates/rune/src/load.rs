@@ -1,8 +1,10 @@
+use crate::ast;
 use crate::compiler;
+use crate::query::Symbols;
 use crate::unit_builder::LinkerErrors;
 use crate::unit_builder::UnitBuilder;
-use crate::{LoadError, LoadErrorKind, Options, Sources, Warnings};
-use runestick::{Context, Source, Unit};
+use crate::{LoadError, LoadErrorKind, Options, ParseError, Parser, Sources, Warnings};
+use runestick::{Context, Item, Source, Unit};
 use std::cell::RefCell;
 use std::path::Path;
 use std::rc::Rc;
@@ -129,6 +131,91 @@ pub fn load_sources(
     options: &Options,
     sources: &mut Sources,
     warnings: &mut Warnings,
+) -> Result<Unit, LoadError> {
+    load_sources_with_symbols(
+        context,
+        options,
+        sources,
+        warnings,
+        &mut Symbols::disabled(),
+    )
+}
+
+/// Compile a batch of expressions into a single unit, with an entry point for
+/// each expression.
+///
+/// Each expression is given as a pair of a name and the source of the
+/// expression. The name must be a valid identifier, and is used both as the
+/// name of the source for diagnostics and the name of the function which
+/// evaluates the expression. Since all expressions share a single compilation,
+/// this is much faster than compiling thousands of one-liners like rules or
+/// filters into their own units.
+///
+/// Each expression must be a single expression, anything else like a sequence
+/// of statements is rejected with a parse error. If compilation fails, the
+/// source id of the error is the index of the expression which caused it, and
+/// its spans point into the expression as it was given.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{FromValue as _, Item, Vm};
+/// use std::sync::Arc;
+///
+/// # fn main() -> runestick::Result<()> {
+/// let context = Arc::new(rune::default_context()?);
+///
+/// let unit = rune::compile_expressions(&*context, &[
+///     ("is_small", "1 + 2 < 10"),
+///     ("greeting", r#""Hello World""#),
+/// ])?;
+///
+/// let vm = Vm::new(context, Arc::new(unit));
+/// let output = vm.clone().call(Item::of(&["is_small"]), ())?.complete()?;
+/// assert!(bool::from_value(output)?);
+/// # Ok(())
+/// # }
+/// ```
+pub fn compile_expressions(
+    context: &Context,
+    expressions: &[(&str, &str)],
+) -> Result<Unit, LoadError> {
+    let mut sources = Sources::new();
+
+    for (name, expr) in expressions {
+        if !crate::refactor::is_ident(name) {
+            return Err(LoadError::from(LoadErrorKind::InvalidName {
+                name: (*name).to_owned(),
+            }));
+        }
+
+        insert_expr_fn(&mut sources, name, name, "", expr)?;
+    }
+
+    load_sources(
+        context,
+        &Options::default(),
+        &mut sources,
+        &mut Warnings::disabled(),
+    )
+}
+
+/// Load and compile the given sources, like [load_sources], while collecting
+/// information on the items declared and referenced in them into `symbols`.
+///
+/// Since references are recorded as functions are compiled, this also compiles
+/// functions which are never used and would otherwise be skipped.
+///
+/// See [Symbols] for an example.
+///
+/// [load_sources]: crate::load_sources
+/// [Symbols]: crate::query::Symbols
+pub fn load_sources_with_symbols(
+    context: &Context,
+    options: &Options,
+    sources: &mut Sources,
+    warnings: &mut Warnings,
+    symbols: &mut Symbols,
 ) -> Result<Unit, LoadError> {
     let unit = if context.has_default_modules() {
         UnitBuilder::with_default_prelude()
@@ -137,7 +224,7 @@ pub fn load_sources(
     };
 
     let unit = Rc::new(RefCell::new(unit));
-    compiler::compile_with_options(&*context, sources, &options, &unit, warnings)?;
+    compiler::compile_with_options(&*context, sources, &options, &unit, warnings, symbols)?;
 
     let unit = match Rc::try_unwrap(unit) {
         Ok(unit) => unit.into_inner(),
@@ -148,7 +235,13 @@ pub fn load_sources(
         }
     };
 
-    if options.link_checks {
+    if let Some(target) = options.api_version {
+        let report = unit.check_compatibility(&*context, target);
+
+        if !report.is_compatible() {
+            return Err(LoadError::from(LoadErrorKind::Incompatible { report }));
+        }
+    } else if options.link_checks {
         let mut errors = LinkerErrors::new();
 
         if !unit.link(&*context, &mut errors) {
@@ -158,3 +251,54 @@ pub fn load_sources(
 
     Ok(unit.into_unit())
 }
+
+/// Insert a source declaring a function with the given name and arguments,
+/// which evaluates the single expression `expr`.
+///
+/// The expression is parsed on its own, so it can't end the function early or
+/// declare other items. It's put first in the source, so that spans in
+/// diagnostics point into the expression as it was given, followed by the
+/// declaration of the function on its own line.
+///
+/// The name and the arguments must have been validated by the caller.
+pub(crate) fn insert_expr_fn(
+    sources: &mut Sources,
+    source_name: &str,
+    name: &str,
+    args: &str,
+    expr: &str,
+) -> Result<usize, LoadError> {
+    let source = format!("{}\nfn {}({}) {{}}", expr, name, args);
+
+    let file = match parse_expr_fn(&source, expr.len() + 1) {
+        Ok(file) => file,
+        Err(error) => {
+            let source_id = sources.insert_default(Source::new(source_name, source));
+            return Err(LoadError::from(LoadErrorKind::ParseError {
+                source_id,
+                error,
+            }));
+        }
+    };
+
+    let source = Source::new(source_name, source);
+    Ok(sources.insert_parsed(Item::default(), source, file))
+}
+
+/// Parse the expression at the start of `source` and the function declaration
+/// starting at `decl`, and use the expression as the body of the function.
+fn parse_expr_fn(source: &str, decl: usize) -> Result<ast::DeclFile, ParseError> {
+    let mut parser = Parser::new(&source[..decl - 1]);
+    let expr = parser.parse::<ast::Expr>()?;
+    parser.parse_eof()?;
+
+    let mut parser = Parser::new_with_start(source, decl);
+    let mut decl_fn = parser.parse::<ast::DeclFn>()?;
+    parser.parse_eof()?;
+
+    decl_fn.body.trailing_expr = Some(Box::new(expr));
+
+    Ok(ast::DeclFile {
+        decls: vec![(ast::Decl::DeclFn(decl_fn), None)],
+    })
+}
@@ -1,5 +1,6 @@
-use crate::unit_builder::LinkerErrors;
-use crate::{CompileError, ParseError};
+use crate::compatibility::CompatibilityReport;
+use crate::unit_builder::{LinkerError, LinkerErrors};
+use crate::{CompileError, Diagnostic, DiagnosticLabel, Diagnostics, ParseError};
 use std::io;
 use std::path::PathBuf;
 use thiserror::Error;
@@ -32,6 +33,106 @@ impl LoadError {
     pub fn into_kind(self) -> LoadErrorKind {
         *self.kind
     }
+
+    /// Convert the load error into diagnostics.
+    ///
+    /// Most errors produce a single diagnostic, but a linker error produces
+    /// one for every missing function.
+    pub fn diagnostics(&self) -> Diagnostics {
+        match &*self.kind {
+            LoadErrorKind::ReadFile { .. } => {
+                vec![Diagnostic::error("E0201", self.kind.to_string())].into()
+            }
+            LoadErrorKind::ParseError { error, source_id } => {
+                vec![error.to_diagnostic(*source_id)].into()
+            }
+            LoadErrorKind::CompileError { error, source_id } => {
+                vec![error.to_diagnostic(*source_id)].into()
+            }
+            LoadErrorKind::LinkError { errors } => errors
+                .into_iter()
+                .map(|error| match error {
+                    LinkerError::MissingFunction { hash, spans } => {
+                        let mut diagnostic = Diagnostic::error(
+                            "E0202",
+                            format!("missing function with hash `{}`", hash),
+                        );
+
+                        for (span, source_id) in spans {
+                            diagnostic = diagnostic.with_label(DiagnosticLabel::primary(
+                                *source_id,
+                                *span,
+                                "called here.",
+                            ));
+                        }
+
+                        diagnostic
+                    }
+                })
+                .collect(),
+            LoadErrorKind::Incompatible { report } => {
+                let mut diagnostics = Vec::new();
+
+                if !report.version_supported() {
+                    let message = match report.host() {
+                        Some(host) => format!(
+                            "host API version {} doesn't support target version {}",
+                            host,
+                            report.target()
+                        ),
+                        None => format!(
+                            "host API doesn't declare a version, but version {} is targeted",
+                            report.target()
+                        ),
+                    };
+
+                    diagnostics.push(Diagnostic::error("E0204", message));
+                }
+
+                for missing in report.missing() {
+                    let mut diagnostic = Diagnostic::error(
+                        "E0202",
+                        format!("missing function with hash `{}`", missing.hash),
+                    );
+
+                    for (span, source_id) in &missing.spans {
+                        diagnostic = diagnostic.with_label(DiagnosticLabel::primary(
+                            *source_id,
+                            *span,
+                            "called here.",
+                        ));
+                    }
+
+                    diagnostics.push(diagnostic);
+                }
+
+                for mismatch in report.arity_mismatches() {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            "E0205",
+                            format!(
+                                "`{}` expects {} arguments, but is called with {}",
+                                mismatch.function, mismatch.expected, mismatch.actual
+                            ),
+                        )
+                        .with_label(DiagnosticLabel::primary(
+                            mismatch.source_id,
+                            mismatch.span,
+                            "called here.",
+                        )),
+                    );
+                }
+
+                diagnostics.into()
+            }
+            LoadErrorKind::InvalidName { .. } => {
+                vec![Diagnostic::error("E0206", self.kind.to_string())].into()
+            }
+            LoadErrorKind::Internal { .. } => {
+                vec![Diagnostic::error("E0203", self.kind.to_string())].into()
+            }
+        }
+    }
 }
 
 impl<E> From<E> for LoadError
@@ -81,6 +182,20 @@ pub enum LoadErrorKind {
         /// Errors that happened during linking.
         errors: LinkerErrors,
     },
+    /// The sources aren't compatible with the host API they target.
+    #[error("incompatible with host API")]
+    Incompatible {
+        /// The report of what's incompatible.
+        report: CompatibilityReport,
+    },
+    /// The name of an expression compiled with
+    /// [compile_expressions][crate::compile_expressions] isn't a valid
+    /// identifier.
+    #[error("`{name}` is not a valid identifier")]
+    InvalidName {
+        /// The invalid name.
+        name: String,
+    },
     /// An internal error.
     #[error("internal error: {message}")]
     Internal {
@@ -1,7 +1,7 @@
 use crate::ast;
 use crate::compiler::Needs;
 use crate::error::{CompileError, CompileResult};
-use runestick::{Label, Source};
+use runestick::{Label, Source, Span};
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -16,11 +16,41 @@ impl Drop for LoopGuard {
     }
 }
 
+/// The kind of a loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LoopKind {
+    /// A `loop` expression.
+    Loop,
+    /// A `while` loop.
+    While,
+    /// A `for` loop.
+    For,
+}
+
+impl LoopKind {
+    /// The keyword which introduces the loop.
+    pub(crate) fn keyword(self) -> &'static str {
+        match self {
+            Self::Loop => "loop",
+            Self::While => "while",
+            Self::For => "for",
+        }
+    }
+}
+
 /// Loops we are inside.
 #[derive(Clone, Copy)]
 pub(crate) struct Loop {
+    /// The kind of the loop.
+    pub(crate) kind: LoopKind,
+    /// The span of the loop.
+    pub(crate) span: Span,
     /// The optional label of the loop.
     pub(crate) label: Option<ast::Label>,
+    /// The label to jump to when continuing the loop.
+    pub(crate) continue_label: Label,
+    /// The number of variables expected at the continue label.
+    pub(crate) continue_var_count: usize,
     /// The end label of the loop.
     pub(crate) break_label: Label,
     /// The number of variables observed at the start of the loop.
@@ -29,6 +59,9 @@ pub(crate) struct Loop {
     pub(crate) needs: Needs,
     /// Locals to drop when breaking.
     pub(crate) drop: Option<usize>,
+    /// If anything in the body of the loop exits it, like a `break` or a
+    /// `return`.
+    pub(crate) exits: bool,
 }
 
 pub(crate) struct Loops {
@@ -57,6 +90,37 @@ impl Loops {
         }
     }
 
+    /// Mark the loop with the given break label as being exited, together
+    /// with all loops nested inside of it.
+    pub(crate) fn mark_exit(&self, break_label: Label) {
+        for l in self.loops.borrow_mut().iter_mut().rev() {
+            l.exits = true;
+
+            if l.break_label == break_label {
+                break;
+            }
+        }
+    }
+
+    /// Mark all loops nested inside of the loop with the given break label as
+    /// being exited, like when continuing an outer loop.
+    pub(crate) fn mark_exit_nested(&self, break_label: Label) {
+        for l in self.loops.borrow_mut().iter_mut().rev() {
+            if l.break_label == break_label {
+                break;
+            }
+
+            l.exits = true;
+        }
+    }
+
+    /// Mark all loops as being exited, like when returning from the function.
+    pub(crate) fn mark_exit_all(&self) {
+        for l in self.loops.borrow_mut().iter_mut() {
+            l.exits = true;
+        }
+    }
+
     /// Find the loop with the matching label.
     pub(crate) fn walk_until_label(
         &self,
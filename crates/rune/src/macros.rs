@@ -2,9 +2,10 @@
 
 use crate::error::CompileResult;
 use crate::{
-    ast, CompileError, MacroContext, Options, Parse, ParseError, Parser, TokenStream, UnitBuilder,
+    ast, CompileError, MacroContext, Options, Parse, ParseError, Resolve as _, TokenStream,
+    UnitBuilder,
 };
-use runestick::{Context, Hash, Item, Source, Span};
+use runestick::{Context, Item, Source, Span};
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -19,6 +20,26 @@ pub(crate) struct MacroCompiler<'a> {
 }
 
 impl MacroCompiler<'_> {
+    /// Compile the given macro into an expression.
+    ///
+    /// The built-in `map!{}` and `set!{}` macros are expanded into hash map
+    /// and hash set literals, which doesn't require macros to be enabled.
+    pub(crate) fn eval_expr_macro(
+        &mut self,
+        expr_call_macro: ast::ExprCallMacro,
+    ) -> CompileResult<ast::Expr> {
+        let builtin = match expr_call_macro.path.try_as_ident() {
+            Some(ident) => ident.resolve(&*self.source)?,
+            None => "",
+        };
+
+        match builtin {
+            "map" => Ok(ast::Expr::LitMap(ast::LitMap::from_macro(expr_call_macro)?)),
+            "set" => Ok(ast::Expr::LitSet(ast::LitSet::from_macro(expr_call_macro)?)),
+            _ => self.eval_macro(expr_call_macro),
+        }
+    }
+
     /// Compile the given macro into the given output type.
     pub(crate) fn eval_macro<T>(&mut self, expr_call_macro: ast::ExprCallMacro) -> CompileResult<T>
     where
@@ -37,7 +58,7 @@ impl MacroCompiler<'_> {
             self.unit
                 .borrow()
                 .convert_path(&self.item, &expr_call_macro.path, &*self.source)?;
-        let hash = Hash::type_hash(&item);
+        let hash = item.type_hash();
 
         let handler = match self.context.lookup_macro(hash) {
             Some(handler) => handler,
@@ -80,9 +101,6 @@ impl MacroCompiler<'_> {
             }
         };
 
-        let mut parser = Parser::from_token_stream(&token_stream);
-        let output = parser.parse::<T>()?;
-        parser.parse_eof()?;
-        Ok(output)
+        Ok(token_stream.parse::<T>()?)
     }
 }
@@ -0,0 +1,376 @@
+//! Optimization passes over the assembly of a single function.
+//!
+//! These are enabled through the `optimize` compiler option and only ever
+//! perform rewrites which don't change the observable behavior of a function.
+
+use crate::assembly::{Assembly, AssemblyInst};
+use crate::stack_effect::{stack_depths, Frame};
+use crate::unit_builder::UnitBuilder;
+use runestick::Inst;
+use std::convert::TryFrom as _;
+
+/// Optimize the assembly of a function which is entered with the given frame.
+///
+/// Passes are repeated until none of them can make any more progress.
+pub(crate) fn optimize(asm: &mut Assembly, frame: Frame, unit: &UnitBuilder) {
+    while fold_constants(asm) || reuse_dead_slots(asm, frame, unit) {}
+}
+
+/// Fold computations over constant operands into a single constant.
+///
+/// Constants are folded in place, so a constant computation in a loop body is
+/// replaced by a single instruction pushing its result, but isn't moved out of
+/// the loop. Computations which would raise an error, like integer overflow or
+/// division by zero, are left alone so that the error is still raised at
+/// runtime.
+fn fold_constants(asm: &mut Assembly) -> bool {
+    let mut removed = vec![false; asm.instructions.len()];
+    let mut changed = false;
+    let mut n = 0;
+
+    while n + 1 < asm.instructions.len() {
+        if asm.labels_rev.contains_key(&(n + 1)) {
+            n += 1;
+            continue;
+        }
+
+        let a = match raw(asm, n).and_then(constant) {
+            Some(a) => a,
+            None => {
+                n += 1;
+                continue;
+            }
+        };
+
+        // NB: a constant condition decides if a short-circuiting jump is
+        // taken, in which case it becomes unconditional.
+        let taken = match (&asm.instructions[n + 1].0, a) {
+            (AssemblyInst::PeekAndJumpIf { label }, Constant::Bool(a)) => Some((*label, a)),
+            (AssemblyInst::PeekAndJumpIfNot { label }, Constant::Bool(a)) => Some((*label, !a)),
+            _ => None,
+        };
+
+        if let Some((label, taken)) = taken {
+            if taken {
+                asm.instructions[n + 1].0 = AssemblyInst::Jump { label };
+            } else {
+                removed[n + 1] = true;
+            }
+
+            changed = true;
+            n += 2;
+            continue;
+        }
+
+        if let Some(folded) = raw(asm, n + 1).and_then(|op| fold_unary(op, a)) {
+            asm.instructions[n].0 = AssemblyInst::Raw { raw: folded };
+            removed[n + 1] = true;
+            changed = true;
+            n += 2;
+            continue;
+        }
+
+        if asm.labels_rev.contains_key(&(n + 2)) {
+            n += 1;
+            continue;
+        }
+
+        let b = match raw(asm, n + 1).and_then(constant) {
+            Some(b) => b,
+            None => {
+                n += 1;
+                continue;
+            }
+        };
+
+        match raw(asm, n + 2).and_then(|op| fold_binary(op, a, b)) {
+            Some(folded) => {
+                asm.instructions[n].0 = AssemblyInst::Raw { raw: folded };
+                removed[n + 1] = true;
+                removed[n + 2] = true;
+                changed = true;
+                n += 3;
+            }
+            None => {
+                n += 1;
+            }
+        }
+    }
+
+    if changed {
+        compact(asm, &removed);
+    }
+
+    changed
+}
+
+/// Avoid copying values out of local slots which are dead after the copy.
+///
+/// A local in the topmost slot of the stack which is copied and then
+/// immediately discarded can instead be used in place, which saves both the
+/// copy and a slot on the stack. This commonly happens when the last
+/// expression in a block is the variable that was declared last.
+fn reuse_dead_slots(asm: &mut Assembly, frame: Frame, unit: &UnitBuilder) -> bool {
+    let depths = match stack_depths(asm, frame, unit) {
+        Ok(depths) => depths,
+        Err(..) => return false,
+    };
+
+    let mut removed = vec![false; asm.instructions.len()];
+    let mut changed = false;
+    let mut n = 0;
+
+    while n + 1 < asm.instructions.len() {
+        if asm.labels_rev.contains_key(&(n + 1)) {
+            n += 1;
+            continue;
+        }
+
+        let top = match (depths[n], raw(asm, n)) {
+            (Some(depth), Some(Inst::Copy { offset })) if offset + 1 == depth => offset,
+            _ => {
+                n += 1;
+                continue;
+            }
+        };
+
+        let next = match raw(asm, n + 1) {
+            Some(next) => next,
+            None => {
+                n += 1;
+                continue;
+            }
+        };
+
+        match next {
+            Inst::Clean { count } | Inst::PopN { count } if count > 0 => {
+                removed[n] = true;
+                decrement_count(asm, n + 1, &mut removed);
+                n += 2;
+            }
+            Inst::Pop => {
+                removed[n] = true;
+                removed[n + 1] = true;
+                n += 2;
+            }
+            next if consumes_one(next, top) => {
+                let discards = n + 2 < asm.instructions.len()
+                    && !asm.labels_rev.contains_key(&(n + 2))
+                    && match raw(asm, n + 2) {
+                        Some(Inst::Pop) => true,
+                        Some(Inst::PopN { count }) => count > 0,
+                        _ => false,
+                    };
+
+                if !discards {
+                    n += 1;
+                    continue;
+                }
+
+                removed[n] = true;
+                decrement_count(asm, n + 2, &mut removed);
+                n += 3;
+            }
+            _ => {
+                n += 1;
+                continue;
+            }
+        }
+
+        changed = true;
+    }
+
+    if changed {
+        compact(asm, &removed);
+    }
+
+    changed
+}
+
+/// Decrement the number of values removed by the `Pop`, `PopN`, or `Clean`
+/// instruction at `n`, removing it entirely if it becomes a no-op.
+fn decrement_count(asm: &mut Assembly, n: usize, removed: &mut [bool]) {
+    match &mut asm.instructions[n].0 {
+        AssemblyInst::Raw {
+            raw: Inst::Clean { count },
+        }
+        | AssemblyInst::Raw {
+            raw: Inst::PopN { count },
+        } if *count > 1 => {
+            *count -= 1;
+        }
+        _ => {
+            removed[n] = true;
+        }
+    }
+}
+
+/// Test if the instruction pops exactly one value from the stack without
+/// pushing anything or otherwise accessing the slot at `top`.
+fn consumes_one(inst: Inst, top: usize) -> bool {
+    match inst {
+        Inst::AddAssign { offset }
+        | Inst::SubAssign { offset }
+        | Inst::MulAssign { offset }
+        | Inst::DivAssign { offset }
+        | Inst::RemAssign { offset }
+        | Inst::BitAndAssign { offset }
+        | Inst::BitXorAssign { offset }
+        | Inst::BitOrAssign { offset }
+        | Inst::ShlAssign { offset }
+        | Inst::ShrAssign { offset }
+        | Inst::Replace { offset } => offset != top,
+        _ => false,
+    }
+}
+
+/// A constant operand.
+#[derive(Debug, Clone, Copy)]
+enum Constant {
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+}
+
+/// Get the raw instruction at the given position, if it is one.
+fn raw(asm: &Assembly, n: usize) -> Option<Inst> {
+    match asm.instructions.get(n) {
+        Some((AssemblyInst::Raw { raw }, _)) => Some(*raw),
+        _ => None,
+    }
+}
+
+/// Get the constant pushed by the given instruction, if it pushes one.
+fn constant(inst: Inst) -> Option<Constant> {
+    Some(match inst {
+        Inst::Bool { value } => Constant::Bool(value),
+        Inst::Integer { number } => Constant::Integer(number),
+        Inst::Float { number } => Constant::Float(number),
+        _ => return None,
+    })
+}
+
+/// Fold the given unary operation.
+fn fold_unary(op: Inst, a: Constant) -> Option<Inst> {
+    Some(match (op, a) {
+        (Inst::Not, Constant::Bool(a)) => Inst::Bool { value: !a },
+        (Inst::Not, Constant::Integer(a)) => Inst::Integer { number: !a },
+        (Inst::Neg, Constant::Integer(a)) => Inst::Integer {
+            number: a.checked_neg()?,
+        },
+        (Inst::Neg, Constant::Float(a)) => Inst::Float { number: -a },
+        _ => return None,
+    })
+}
+
+/// Fold the given binary operation.
+fn fold_binary(op: Inst, a: Constant, b: Constant) -> Option<Inst> {
+    Some(match (a, b) {
+        (Constant::Integer(a), Constant::Integer(b)) => match op {
+            Inst::Add => Inst::Integer {
+                number: a.checked_add(b)?,
+            },
+            Inst::Sub => Inst::Integer {
+                number: a.checked_sub(b)?,
+            },
+            Inst::Mul => Inst::Integer {
+                number: a.checked_mul(b)?,
+            },
+            Inst::Div => Inst::Integer {
+                number: a.checked_div(b)?,
+            },
+            Inst::Rem => Inst::Integer {
+                number: a.checked_rem(b)?,
+            },
+            Inst::BitAnd => Inst::Integer { number: a & b },
+            Inst::BitXor => Inst::Integer { number: a ^ b },
+            Inst::BitOr => Inst::Integer { number: a | b },
+            Inst::Shl => Inst::Integer {
+                number: a << shift_amount(b)?,
+            },
+            Inst::Shr => Inst::Integer {
+                number: a >> shift_amount(b)?,
+            },
+            Inst::Lt => Inst::Bool { value: a < b },
+            Inst::Gt => Inst::Bool { value: a > b },
+            Inst::Lte => Inst::Bool { value: a <= b },
+            Inst::Gte => Inst::Bool { value: a >= b },
+            Inst::Eq => Inst::Bool { value: a == b },
+            Inst::Neq => Inst::Bool { value: a != b },
+            _ => return None,
+        },
+        (Constant::Float(a), Constant::Float(b)) => match op {
+            Inst::Add => Inst::Float { number: a + b },
+            Inst::Sub => Inst::Float { number: a - b },
+            Inst::Mul => Inst::Float { number: a * b },
+            Inst::Div => Inst::Float { number: a / b },
+            Inst::Rem => Inst::Float { number: a % b },
+            Inst::Lt => Inst::Bool { value: a < b },
+            Inst::Gt => Inst::Bool { value: a > b },
+            Inst::Lte => Inst::Bool { value: a <= b },
+            Inst::Gte => Inst::Bool { value: a >= b },
+            _ => return None,
+        },
+        (Constant::Bool(a), Constant::Bool(b)) => match op {
+            Inst::And => Inst::Bool { value: a && b },
+            Inst::Or => Inst::Bool { value: a || b },
+            Inst::Eq => Inst::Bool { value: a == b },
+            Inst::Neq => Inst::Bool { value: a != b },
+            _ => return None,
+        },
+        _ => return None,
+    })
+}
+
+/// Convert a constant shift amount, which must be in range for a 64-bit
+/// integer.
+fn shift_amount(amount: i64) -> Option<u32> {
+    u32::try_from(amount).ok().filter(|amount| *amount < 64)
+}
+
+/// Remove the instructions marked as removed, while keeping labels pointing to
+/// the instruction following the removed ones.
+fn compact(asm: &mut Assembly, removed: &[bool]) {
+    let mut positions = Vec::with_capacity(removed.len() + 1);
+    let mut position = 0;
+
+    for removed in removed {
+        positions.push(position);
+
+        if !*removed {
+            position += 1;
+        }
+    }
+
+    positions.push(position);
+
+    let instructions = std::mem::take(&mut asm.instructions);
+
+    asm.instructions = instructions
+        .into_iter()
+        .zip(removed)
+        .filter(|(_, removed)| !**removed)
+        .map(|(inst, _)| inst)
+        .collect();
+
+    for offset in asm.labels.values_mut() {
+        *offset = positions[*offset];
+    }
+
+    // NB: labels are reassigned in order, so that the ones which belong to
+    // instructions that are kept take precedence.
+    let mut labels_rev = std::mem::take(&mut asm.labels_rev)
+        .into_iter()
+        .collect::<Vec<_>>();
+    labels_rev.sort_by_key(|(offset, _)| *offset);
+
+    for (offset, label) in labels_rev {
+        asm.labels_rev.insert(positions[offset], label);
+    }
+
+    asm.comments = std::mem::take(&mut asm.comments)
+        .into_iter()
+        .filter(|(offset, _)| !removed[*offset])
+        .map(|(offset, comments)| (positions[offset], comments))
+        .collect();
+}
@@ -1,4 +1,5 @@
 use crate::error::ConfigurationError;
+use runestick::ApiVersion;
 
 /// Compiler options.
 pub struct Options {
@@ -10,6 +11,18 @@ pub struct Options {
     pub(crate) debug_info: bool,
     /// Support (experimental) macros.
     pub(crate) macros: bool,
+    /// Run optimization passes over the assembly of each function.
+    pub(crate) optimize: bool,
+    /// Warn about loops which obviously never exit.
+    pub(crate) lint_infinite_loops: bool,
+    /// Warn about functions which unconditionally call themselves.
+    pub(crate) lint_unguarded_recursion: bool,
+    /// Emit coverage counters for every statement.
+    pub(crate) coverage: bool,
+    /// The version of the host API targeted by the sources.
+    pub(crate) api_version: Option<ApiVersion>,
+    /// Forbid shadowing variables and builtins, and require explicit returns.
+    pub(crate) strict: bool,
 }
 
 impl Options {
@@ -30,6 +43,33 @@ impl Options {
             Some("macros") => {
                 self.macros = it.next() != Some("false");
             }
+            Some("optimize") => {
+                self.optimize = it.next() != Some("false");
+            }
+            Some("lint-infinite-loops") => {
+                self.lint_infinite_loops = it.next() != Some("false");
+            }
+            Some("lint-unguarded-recursion") => {
+                self.lint_unguarded_recursion = it.next() != Some("false");
+            }
+            Some("coverage") => {
+                self.coverage = it.next() != Some("false");
+            }
+            Some("strict") => {
+                self.strict = it.next() != Some("false");
+            }
+            Some("api-version") => {
+                let version = it.next().unwrap_or_default();
+
+                self.api_version =
+                    Some(
+                        version
+                            .parse()
+                            .map_err(|_| ConfigurationError::InvalidApiVersion {
+                                version: version.to_owned(),
+                            })?,
+                    );
+            }
             _ => {
                 return Err(ConfigurationError::UnsupportedOptimizationOption {
                     option: option.to_owned(),
@@ -48,6 +88,12 @@ impl Default for Options {
             memoize_instance_fn: true,
             debug_info: true,
             macros: false,
+            optimize: false,
+            lint_infinite_loops: false,
+            lint_unguarded_recursion: false,
+            coverage: false,
+            api_version: None,
+            strict: false,
         }
     }
 }
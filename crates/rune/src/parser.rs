@@ -1,9 +1,10 @@
-use crate::ast::Token;
+use crate::ast::{Kind, Token};
 use crate::error::ParseError;
 use crate::lexer::Lexer;
 use crate::token_stream::{TokenStream, TokenStreamIter};
 use crate::traits::{Parse, Peek};
 use runestick::Span;
+use std::collections::VecDeque;
 use std::fmt;
 
 /// Parser for the rune language.
@@ -19,12 +20,34 @@ use std::fmt;
 #[derive(Debug)]
 pub struct Parser<'a> {
     pub(crate) source: Source<'a>,
-    p1: Result<Option<Token>, ParseError>,
-    p2: Result<Option<Token>, ParseError>,
-    p3: Result<Option<Token>, ParseError>,
+    /// Tokens read from the source which are ahead of the parser, or which are
+    /// retained since they might be rolled back to.
+    buffer: VecDeque<Result<Option<Token>, ParseError>>,
+    /// The position of the current token in the buffer.
+    cursor: usize,
+    /// The number of tokens which have been released from the front of the
+    /// buffer.
+    released: usize,
+    /// The number of checkpoints which haven't been committed or rolled back
+    /// yet.
+    checkpoints: usize,
+}
+
+/// A position in the token stream of a [Parser] which it can be rolled back
+/// to.
+///
+/// Constructed through [Parser::checkpoint], and must be passed to either
+/// [Parser::rollback] or [Parser::commit].
+#[derive(Debug)]
+#[must_use = "checkpoints must be rolled back or committed"]
+pub struct Checkpoint {
+    position: usize,
 }
 
 impl<'a> Parser<'a> {
+    /// The number of tokens which are always buffered ahead of the parser.
+    const LOOKAHEAD: usize = 3;
+
     /// Construct a new parser around the given source.
     pub fn new(source: &'a str) -> Self {
         Self::new_with_start(source, 0)
@@ -45,12 +68,17 @@ impl<'a> Parser<'a> {
     }
 
     /// Construct a new parser with a source.
-    fn with_source(mut source: Source<'a>) -> Self {
-        let p1 = source.next();
-        let p2 = source.next();
-        let p3 = source.next();
+    fn with_source(source: Source<'a>) -> Self {
+        let mut parser = Self {
+            source,
+            buffer: VecDeque::new(),
+            cursor: 0,
+            released: 0,
+            checkpoints: 0,
+        };
 
-        Self { source, p1, p2, p3 }
+        parser.fill(Self::LOOKAHEAD);
+        parser
     }
 
     /// Parse a specific item from the parser.
@@ -66,7 +94,7 @@ impl<'a> Parser<'a> {
     where
         T: Peek,
     {
-        Ok(T::peek(self.p1?, self.p2?))
+        Ok(T::peek(self.nth(0)?, self.nth(1)?))
     }
 
     /// Peek for the given token.
@@ -74,27 +102,52 @@ impl<'a> Parser<'a> {
     where
         T: Peek,
     {
-        Ok(T::peek(self.p2?, self.p3?))
+        Ok(T::peek(self.nth(1)?, self.nth(2)?))
+    }
+
+    /// Peek for the given token `n` tokens ahead of the current one, where
+    /// `peek_n::<T>(0)` is the same as `peek::<T>()`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rune::{ast, Parser};
+    ///
+    /// let mut parser = Parser::new("foo(a = 1)");
+    /// assert!(parser.peek_n::<ast::Eq>(3).unwrap());
+    /// ```
+    pub fn peek_n<T>(&mut self, n: usize) -> Result<bool, ParseError>
+    where
+        T: Peek,
+    {
+        Ok(T::peek(self.token_peek_n(n)?, self.token_peek_n(n + 1)?))
+    }
+
+    /// Peek the token `n` tokens ahead of the current one.
+    pub fn token_peek_n(&mut self, n: usize) -> Result<Option<Token>, ParseError> {
+        self.fill(n + 1);
+        self.nth(n)
     }
 
     /// Peek the current token.
     pub fn token_peek(&mut self) -> Result<Option<Token>, ParseError> {
-        self.p1
+        self.nth(0)
     }
 
     /// Peek the next two tokens.
     pub fn token_peek_pair(&mut self) -> Result<Option<(Token, Option<Token>)>, ParseError> {
-        Ok(match self.p1? {
-            Some(p1) => Some((p1, self.p2?)),
+        Ok(match self.nth(0)? {
+            Some(p1) => Some((p1, self.nth(1)?)),
             None => None,
         })
     }
 
     /// Consume the next token from the lexer.
     pub fn token_next(&mut self) -> Result<Token, ParseError> {
-        let token = std::mem::replace(&mut self.p3, self.source.next());
-        let token = std::mem::replace(&mut self.p2, token);
-        let token = std::mem::replace(&mut self.p1, token);
+        let token = self.nth(0);
+        self.cursor += 1;
+        self.release();
+        self.fill(Self::LOOKAHEAD);
 
         match token? {
             Some(token) => Ok(token),
@@ -107,7 +160,7 @@ impl<'a> Parser<'a> {
     /// Peek the current token from the lexer but treat a missing token as an
     /// unexpected end-of-file.
     pub fn token_peek_eof(&mut self) -> Result<Token, ParseError> {
-        match self.p1? {
+        match self.nth(0)? {
             Some(token) => Ok(token),
             None => Err(ParseError::UnexpectedEof {
                 span: self.source.end(),
@@ -117,7 +170,7 @@ impl<'a> Parser<'a> {
 
     /// Assert that the parser has reached its end-of-file.
     pub fn parse_eof(&mut self) -> Result<(), ParseError> {
-        if let Some(token) = self.source.next()? {
+        if let Some(token) = self.nth(0)? {
             return Err(ParseError::ExpectedEof {
                 actual: token.kind,
                 span: token.span,
@@ -126,6 +179,78 @@ impl<'a> Parser<'a> {
 
         Ok(())
     }
+
+    /// Construct a checkpoint at the current position of the parser, which
+    /// it can later be rolled back to.
+    ///
+    /// Every checkpoint must be either rolled back with [Parser::rollback] or
+    /// committed with [Parser::commit]. Checkpoints can be nested, in which
+    /// case the innermost one has to be resolved first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rune::{ast, Parser};
+    ///
+    /// let mut parser = Parser::new("foo + bar");
+    ///
+    /// let checkpoint = parser.checkpoint();
+    ///
+    /// if parser.parse::<ast::ExprCall>().is_err() {
+    ///     parser.rollback(checkpoint);
+    /// } else {
+    ///     parser.commit(checkpoint);
+    /// }
+    ///
+    /// parser.parse::<ast::ExprBinary>().unwrap();
+    /// parser.parse_eof().unwrap();
+    /// ```
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        self.checkpoints += 1;
+
+        Checkpoint {
+            position: self.released + self.cursor,
+        }
+    }
+
+    /// Roll the parser back to the given checkpoint, so that every token
+    /// consumed since it was constructed is parsed again.
+    pub fn rollback(&mut self, checkpoint: Checkpoint) {
+        self.cursor = checkpoint.position - self.released;
+        self.checkpoints -= 1;
+        self.release();
+    }
+
+    /// Commit to everything parsed since the given checkpoint was
+    /// constructed.
+    pub fn commit(&mut self, checkpoint: Checkpoint) {
+        debug_assert!(checkpoint.position <= self.released + self.cursor);
+        self.checkpoints -= 1;
+        self.release();
+    }
+
+    /// Get the token `n` tokens ahead of the current one, which must already
+    /// have been buffered.
+    fn nth(&self, n: usize) -> Result<Option<Token>, ParseError> {
+        self.buffer[self.cursor + n]
+    }
+
+    /// Make sure that at least `n` tokens are buffered ahead of the parser.
+    fn fill(&mut self, n: usize) {
+        while self.buffer.len() < self.cursor + n {
+            self.buffer.push_back(self.source.next());
+        }
+    }
+
+    /// Release all consumed tokens, unless there's a checkpoint which might
+    /// be rolled back to them.
+    fn release(&mut self) {
+        if self.checkpoints == 0 {
+            self.buffer.drain(..self.cursor);
+            self.released += self.cursor;
+            self.cursor = 0;
+        }
+    }
 }
 
 /// A source adapter.
@@ -145,7 +270,22 @@ impl Source<'_> {
     /// Get the next token in the stream.
     pub(crate) fn next(&mut self) -> Result<Option<Token>, ParseError> {
         match &mut self.inner {
-            SourceInner::Lexer(lexer) => lexer.next(),
+            SourceInner::Lexer(lexer) => {
+                let token = lexer.next()?;
+
+                // NB: fail with the error behind an invalid token when the
+                // parser reaches it.
+                if let Some(Token {
+                    kind: Kind::Error, ..
+                }) = token
+                {
+                    if let Some(error) = lexer.errors().last() {
+                        return Err(*error);
+                    }
+                }
+
+                Ok(token)
+            }
             SourceInner::TokenStream(token_stream) => Ok(token_stream.next()),
         }
     }
@@ -162,3 +302,32 @@ enum SourceInner<'a> {
     Lexer(Lexer<'a>),
     TokenStream(TokenStreamIter<'a>),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Parser;
+    use crate::ast;
+
+    #[test]
+    fn test_nested_checkpoints() {
+        let mut parser = Parser::new("a b c d");
+
+        let outer = parser.checkpoint();
+        parser.parse::<ast::Ident>().unwrap();
+
+        let inner = parser.checkpoint();
+        parser.parse::<ast::Ident>().unwrap();
+        parser.parse::<ast::Ident>().unwrap();
+        assert!(parser.peek_n::<ast::Ident>(0).unwrap());
+        assert!(!parser.peek_n::<ast::Ident>(1).unwrap());
+        parser.rollback(inner);
+
+        parser.parse::<ast::Ident>().unwrap();
+        parser.commit(outer);
+
+        parser.parse::<ast::Ident>().unwrap();
+        parser.parse::<ast::Ident>().unwrap();
+        parser.parse_eof().unwrap();
+        assert!(parser.buffer.len() <= Parser::LOOKAHEAD);
+    }
+}
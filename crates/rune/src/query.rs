@@ -1,4 +1,11 @@
 //! Lazy query system, used to compile and build items on demand.
+//!
+//! This also provides [Symbols], which is information on the items declared
+//! and referenced in a compiled source tree. It's collected while compiling
+//! with [load_sources_with_symbols], and is intended for tooling like
+//! language servers and documentation generators.
+//!
+//! [load_sources_with_symbols]: crate::load_sources_with_symbols
 
 use crate::ast;
 use crate::collections::{HashMap, HashSet};
@@ -6,8 +13,8 @@ use crate::error::CompileError;
 use crate::traits::Resolve as _;
 use crate::unit_builder::UnitBuilder;
 use runestick::{
-    Call, CompileMeta, CompileMetaCapture, CompileMetaStruct, CompileMetaTuple, Hash, Item, Source,
-    Span, Type,
+    Call, CompileMeta, CompileMetaCapture, CompileMetaStruct, CompileMetaTuple, Item, Source, Span,
+    Type,
 };
 use std::cell::RefCell;
 use std::collections::VecDeque;
@@ -23,7 +30,7 @@ pub(crate) enum Indexed {
     AsyncBlock(AsyncBlock),
 }
 
-pub struct Struct {
+pub(crate) struct Struct {
     ast: ast::DeclStruct,
 }
 
@@ -34,7 +41,7 @@ impl Struct {
     }
 }
 
-pub struct Variant {
+pub(crate) struct Variant {
     /// Item of the enum type.
     enum_item: Item,
     /// Ast for declaration.
@@ -107,16 +114,26 @@ pub(crate) struct IndexedEntry {
 pub(crate) struct Query {
     pub(crate) queue: VecDeque<BuildEntry>,
     indexed: HashMap<Item, IndexedEntry>,
+    /// The variants of each indexed enum, in declaration order.
+    enum_variants: HashMap<Item, Vec<Item>>,
+    /// Functions which never produce a value, since they neither have a
+    /// trailing expression nor return a value.
+    unit_functions: HashSet<Item>,
     pub(crate) unit: Rc<RefCell<UnitBuilder>>,
+    /// Symbols collected while indexing and compiling.
+    pub(crate) symbols: Symbols,
 }
 
 impl Query {
     /// Construct a new compilation context.
-    pub fn new(unit: Rc<RefCell<UnitBuilder>>) -> Self {
+    pub fn new(unit: Rc<RefCell<UnitBuilder>>, symbols: Symbols) -> Self {
         Self {
             queue: VecDeque::new(),
             indexed: HashMap::new(),
+            enum_variants: HashMap::new(),
+            unit_functions: HashSet::new(),
             unit,
+            symbols,
         }
     }
 
@@ -174,6 +191,12 @@ impl Query {
         span: Span,
     ) -> Result<(), CompileError> {
         log::trace!("new variant: {}", item);
+
+        self.enum_variants
+            .entry(enum_item.clone())
+            .or_default()
+            .push(item.clone());
+
         self.index(
             item,
             IndexedEntry {
@@ -186,6 +209,21 @@ impl Query {
         Ok(())
     }
 
+    /// Get the variants of an indexed enum, in declaration order.
+    pub fn enum_variants(&self, enum_item: &Item) -> Option<&[Item]> {
+        self.enum_variants.get(enum_item).map(Vec::as_slice)
+    }
+
+    /// Mark the given function as never producing a value.
+    pub fn mark_unit_function(&mut self, item: Item) {
+        self.unit_functions.insert(item);
+    }
+
+    /// Test if the given function never produces a value.
+    pub fn is_unit_function(&self, item: &Item) -> bool {
+        self.unit_functions.contains(item)
+    }
+
     /// Add a new function that can be queried for.
     pub fn index_closure(
         &mut self,
@@ -267,6 +305,23 @@ impl Query {
         Ok(())
     }
 
+    /// Get all indexed functions which haven't been built because nothing has
+    /// referenced them yet, together with their span and the id of the source
+    /// they're declared in.
+    pub(crate) fn unused_functions(&self) -> Vec<(Item, Span, usize)> {
+        let mut unused = self
+            .indexed
+            .iter()
+            .filter_map(|(item, entry)| match &entry.indexed {
+                Indexed::Function(f) => Some((item.clone(), f.ast.span(), entry.source_id)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        unused.sort();
+        unused
+    }
+
     /// Query for the given meta item.
     pub fn query_meta(
         &mut self,
@@ -289,9 +344,11 @@ impl Query {
             None => return Ok(None),
         };
 
+        let is_enum = matches!(indexed, Indexed::Enum);
+
         let meta = match indexed {
             Indexed::Enum => CompileMeta::Enum {
-                value_type: Type::Hash(Hash::type_hash(&item)),
+                value_type: Type::Hash(item.type_hash()),
                 item: item.clone(),
             },
             Indexed::Variant(variant) => {
@@ -309,7 +366,7 @@ impl Query {
                 });
 
                 CompileMeta::Function {
-                    value_type: Type::Hash(Hash::type_hash(&item)),
+                    value_type: Type::Hash(item.type_hash()),
                     item: item.clone(),
                 }
             }
@@ -323,7 +380,7 @@ impl Query {
                 });
 
                 CompileMeta::Closure {
-                    value_type: Type::Hash(Hash::type_hash(&item)),
+                    value_type: Type::Hash(item.type_hash()),
                     item: item.clone(),
                     captures,
                 }
@@ -338,7 +395,7 @@ impl Query {
                 });
 
                 CompileMeta::AsyncBlock {
-                    value_type: Type::Hash(Hash::type_hash(&item)),
+                    value_type: Type::Hash(item.type_hash()),
                     item: item.clone(),
                     captures,
                 }
@@ -347,6 +404,20 @@ impl Query {
 
         self.unit.borrow_mut().insert_meta(meta)?;
 
+        // NB: all variants of an enum are built together with it, so that
+        // they can be looked up by name at runtime.
+        if is_enum {
+            if let Some(variants) = self.enum_variants.get(&item).cloned() {
+                for variant in &variants {
+                    self.query_meta(variant, span)?;
+                }
+
+                self.unit
+                    .borrow_mut()
+                    .insert_enum_variants(&item, &variants);
+            }
+        }
+
         match self.unit.borrow().lookup_meta(&item) {
             Some(meta) => Ok(Some(meta)),
             None => Err(CompileError::MissingType { span, item }),
@@ -361,14 +432,14 @@ impl Query {
         enum_item: Option<Item>,
         source: Arc<Source>,
     ) -> Result<CompileMeta, CompileError> {
-        let value_type = Type::Hash(Hash::type_hash(item));
+        let value_type = Type::Hash(item.type_hash());
 
         Ok(match body {
             ast::DeclStructBody::EmptyBody(..) => {
                 let tuple = CompileMetaTuple {
                     item: item.clone(),
                     args: 0,
-                    hash: Hash::type_hash(item),
+                    hash: item.type_hash(),
                 };
 
                 match enum_item {
@@ -384,7 +455,7 @@ impl Query {
                 let tuple = CompileMetaTuple {
                     item: item.clone(),
                     args: tuple.fields.len(),
-                    hash: Hash::type_hash(item),
+                    hash: item.type_hash(),
                 };
 
                 match enum_item {
@@ -398,12 +469,18 @@ impl Query {
             }
             ast::DeclStructBody::StructBody(st) => {
                 let mut fields = HashSet::new();
+                let mut ordered = Vec::new();
 
                 for (ident, _) in &st.fields {
                     let ident = ident.resolve(&*source)?;
                     fields.insert(ident.to_owned());
+                    ordered.push(ident.to_owned());
                 }
 
+                self.unit
+                    .borrow_mut()
+                    .insert_struct_fields(item, ordered.into());
+
                 let object = CompileMetaStruct {
                     item: item.clone(),
                     fields: Some(fields),
@@ -421,3 +498,199 @@ impl Query {
         })
     }
 }
+
+/// Information on the items declared and referenced in a compiled source tree.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::query::{Symbols, SymbolKind};
+/// use runestick::{Item, Source};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let context = rune::default_context()?;
+/// let mut sources = rune::Sources::new();
+/// let mut warnings = rune::Warnings::new();
+/// let mut symbols = Symbols::new();
+///
+/// sources.insert_default(Source::new("entry", r#"
+/// fn add(a, b) { a + b }
+/// fn main() { add(1, 2) }
+/// "#));
+///
+/// rune::load_sources_with_symbols(
+///     &context,
+///     &Default::default(),
+///     &mut sources,
+///     &mut warnings,
+///     &mut symbols,
+/// )?;
+///
+/// let add = symbols.lookup(&Item::of(&["add"])).expect("missing `add`");
+/// assert!(matches!(add.kind, SymbolKind::Function { .. }));
+/// assert_eq!(add.signature, "fn add(a, b)");
+/// assert_eq!(symbols.references(&add.item).count(), 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Symbols {
+    /// If collecting symbols is enabled.
+    enabled: bool,
+    /// Declared symbols, in the order they were declared.
+    symbols: Vec<Symbol>,
+    /// Index of symbols by item.
+    by_item: HashMap<Item, usize>,
+    /// References to items.
+    references: Vec<Reference>,
+}
+
+impl Symbols {
+    /// Construct a new, empty collection of symbols.
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            ..Self::default()
+        }
+    }
+
+    /// Construct a collection of symbols that is disabled, i.e. nothing will
+    /// be collected into it.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Test if collecting symbols is enabled.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Test if no symbols have been collected.
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Iterate over all declared symbols, in the order they were declared.
+    pub fn iter(&self) -> impl Iterator<Item = &'_ Symbol> {
+        self.symbols.iter()
+    }
+
+    /// Look up the symbol declared with the given item.
+    pub fn lookup(&self, item: &Item) -> Option<&Symbol> {
+        Some(&self.symbols[*self.by_item.get(item)?])
+    }
+
+    /// Iterate over all references to the given item, in the order they were
+    /// encountered.
+    pub fn references<'a>(&'a self, item: &'a Item) -> impl Iterator<Item = &'a Reference> + 'a {
+        self.references.iter().filter(move |r| r.item == *item)
+    }
+
+    /// Iterate over all references, in the order they were encountered.
+    pub fn iter_references(&self) -> impl Iterator<Item = &'_ Reference> {
+        self.references.iter()
+    }
+
+    /// Find the item which is declared or referenced at the given byte offset
+    /// in the given source.
+    ///
+    /// If multiple spans cover the offset, the item of the narrowest one is
+    /// returned.
+    pub fn item_at(&self, source_id: usize, offset: usize) -> Option<&Item> {
+        let declarations = self.symbols.iter().map(|s| (&s.item, s.source_id, s.span));
+        let references = self
+            .references
+            .iter()
+            .map(|r| (&r.item, r.source_id, r.span));
+
+        declarations
+            .chain(references)
+            .filter(|(_, id, span)| *id == source_id && span.start <= offset && offset < span.end)
+            .min_by_key(|(_, _, span)| span.end - span.start)
+            .map(|(item, _, _)| item)
+    }
+
+    /// Declare a new symbol.
+    pub(crate) fn declare(&mut self, symbol: Symbol) {
+        if !self.enabled {
+            return;
+        }
+
+        let index = self.symbols.len();
+        self.by_item.insert(symbol.item.clone(), index);
+        self.symbols.push(symbol);
+    }
+
+    /// Add a reference to the given item.
+    ///
+    /// The same location is only recorded once, even if it's resolved multiple
+    /// times while compiling.
+    pub(crate) fn reference(&mut self, item: &Item, source_id: usize, span: Span) {
+        if !self.enabled {
+            return;
+        }
+
+        let exists = self
+            .references
+            .iter()
+            .any(|r| r.source_id == source_id && r.span == span && r.item == *item);
+
+        if !exists {
+            self.references.push(Reference {
+                item: item.clone(),
+                source_id,
+                span,
+            });
+        }
+    }
+}
+
+/// A symbol declared in a source.
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    /// The item of the symbol.
+    pub item: Item,
+    /// The kind of the symbol.
+    pub kind: SymbolKind,
+    /// The id of the source the symbol is declared in.
+    pub source_id: usize,
+    /// The span of the name of the symbol.
+    pub span: Span,
+    /// The signature of the symbol as it's written in the source, like
+    /// `fn add(a, b)`.
+    pub signature: String,
+}
+
+/// The kind of a declared symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    /// A module.
+    Module,
+    /// A struct.
+    Struct,
+    /// An enum.
+    Enum,
+    /// A variant of an enum.
+    Variant,
+    /// A function.
+    Function {
+        /// How the function is called.
+        call: Call,
+    },
+    /// An instance function declared in an `impl` block.
+    InstanceFunction {
+        /// How the function is called.
+        call: Call,
+    },
+}
+
+/// A reference to an item in a source.
+#[derive(Debug, Clone)]
+pub struct Reference {
+    /// The item being referenced.
+    pub item: Item,
+    /// The id of the source containing the reference.
+    pub source_id: usize,
+    /// The span of the reference.
+    pub span: Span,
+}
@@ -0,0 +1,231 @@
+//! Refactorings built on top of the [query API][crate::query].
+//!
+//! Refactorings don't modify sources directly, instead they produce a
+//! collection of [Edit]s which can be applied with [apply_edits] or forwarded
+//! to an editor.
+
+use crate::ast;
+use crate::query::{SymbolKind, Symbols};
+use crate::{LoadError, Options, Sources, Warnings};
+use runestick::{Context, Item, Span};
+use thiserror::Error;
+
+/// A single textual edit to a source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    /// The id of the source being edited.
+    pub source_id: usize,
+    /// The span being replaced.
+    pub span: Span,
+    /// The text to replace the span with.
+    pub replacement: String,
+}
+
+/// An error raised when renaming an item.
+#[derive(Debug, Error)]
+pub enum RenameError {
+    /// The sources failed to compile.
+    #[error("failed to compile sources")]
+    LoadError {
+        /// The source error.
+        #[from]
+        error: LoadError,
+    },
+    /// The item to rename isn't declared in the sources.
+    #[error("missing item `{item}`")]
+    MissingItem {
+        /// The item that was missing.
+        item: Item,
+    },
+    /// The new name isn't a valid identifier.
+    #[error("`{name}` is not a valid identifier")]
+    InvalidName {
+        /// The invalid name.
+        name: String,
+    },
+    /// An item with the new name already exists.
+    #[error("an item named `{item}` already exists")]
+    Conflict {
+        /// The existing item.
+        item: Item,
+    },
+    /// Instance functions are called through values, so calls to them can't
+    /// be found statically.
+    #[error("instance function `{item}` can't be renamed")]
+    InstanceFunction {
+        /// The instance function.
+        item: Item,
+    },
+}
+
+/// Rename the item at `old_path` to `new_name`, producing the edits needed to
+/// update its declaration and every reference to it.
+///
+/// Renaming an item also updates references to any items declared inside of
+/// it, like `Shape::Circle` when renaming the enum `Shape`.
+///
+/// Note that renaming a module which is loaded from a file only updates the
+/// `mod` declaration, moving the file is up to the caller.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Item, Source};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let context = rune::default_context()?;
+/// let mut sources = rune::Sources::new();
+///
+/// let id = sources.insert_default(Source::new("entry", r#"
+/// fn add(a, b) { a + b }
+/// fn main() { add(1, 2) }
+/// "#));
+///
+/// let edits = rune::refactor::rename(&context, &mut sources, &Item::of(&["add"]), "sum")?;
+/// assert_eq!(edits.len(), 2);
+///
+/// let source = sources.get(id).unwrap().as_str();
+/// let source = rune::refactor::apply_edits(source, &edits);
+/// assert!(source.contains("fn sum(a, b)"));
+/// assert!(source.contains("sum(1, 2)"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn rename(
+    context: &Context,
+    sources: &mut Sources,
+    old_path: &Item,
+    new_name: &str,
+) -> Result<Vec<Edit>, RenameError> {
+    if !is_ident(new_name) {
+        return Err(RenameError::InvalidName {
+            name: new_name.to_owned(),
+        });
+    }
+
+    let mut symbols = Symbols::new();
+
+    crate::load_sources_with_symbols(
+        context,
+        &Options::default(),
+        sources,
+        &mut Warnings::disabled(),
+        &mut symbols,
+    )?;
+
+    let symbol = match symbols.lookup(old_path) {
+        Some(symbol) => symbol,
+        None => {
+            return Err(RenameError::MissingItem {
+                item: old_path.clone(),
+            })
+        }
+    };
+
+    if let SymbolKind::InstanceFunction { .. } = symbol.kind {
+        return Err(RenameError::InstanceFunction {
+            item: old_path.clone(),
+        });
+    }
+
+    let mut new_path = old_path.clone();
+    new_path.pop();
+    new_path.push(new_name);
+
+    if new_path != *old_path && symbols.lookup(&new_path).is_some() {
+        return Err(RenameError::Conflict { item: new_path });
+    }
+
+    let mut edits = vec![Edit {
+        source_id: symbol.source_id,
+        span: symbol.span,
+        replacement: new_name.to_owned(),
+    }];
+
+    let old = old_path.as_vec();
+
+    for reference in symbols.iter_references() {
+        let item = reference.item.as_vec();
+
+        if item.len() < old.len() || item[..old.len()] != old[..] {
+            continue;
+        }
+
+        let text = match sources
+            .get(reference.source_id)
+            .and_then(|s| s.source(reference.span))
+        {
+            Some(text) => text,
+            None => continue,
+        };
+
+        // NB: paths are written relative to where they're used, so the written
+        // components line up with the end of the referenced item.
+        let components = path_components(text, reference.span.start);
+        let index = match (components.len() + old.len()).checked_sub(item.len() + 1) {
+            Some(index) => index,
+            None => continue,
+        };
+
+        edits.push(Edit {
+            source_id: reference.source_id,
+            span: components[index],
+            replacement: new_name.to_owned(),
+        });
+    }
+
+    edits.sort_by_key(|e| (e.source_id, e.span.start));
+    edits.dedup();
+    Ok(edits)
+}
+
+/// Apply the given edits to a source, returning the edited source.
+///
+/// All edits are expected to belong to the same source and to not overlap.
+pub fn apply_edits<'a, I>(source: &str, edits: I) -> String
+where
+    I: IntoIterator<Item = &'a Edit>,
+{
+    let mut edits = edits.into_iter().collect::<Vec<_>>();
+    edits.sort_by_key(|e| e.span.start);
+
+    let mut output = String::with_capacity(source.len());
+    let mut offset = 0;
+
+    for edit in edits {
+        output.push_str(&source[offset..edit.span.start]);
+        output.push_str(&edit.replacement);
+        offset = edit.span.end;
+    }
+
+    output.push_str(&source[offset..]);
+    output
+}
+
+/// Test if the given name is a plain identifier, and not a keyword.
+pub(crate) fn is_ident(name: &str) -> bool {
+    let mut lexer = crate::Lexer::new(name);
+
+    match lexer.next() {
+        Ok(Some(token)) if token.kind == ast::Kind::Ident => {
+            token.span == Span::new(0, name.len()) && matches!(lexer.next(), Ok(None))
+        }
+        _ => false,
+    }
+}
+
+/// Get the span of each component in the path `text`, which starts at the
+/// given offset.
+fn path_components(text: &str, start: usize) -> Vec<Span> {
+    let mut components = Vec::new();
+    let mut offset = start;
+
+    for part in text.split("::") {
+        let leading = part.len() - part.trim_start().len();
+        let start = offset + leading;
+        components.push(Span::new(start, start + part.trim().len()));
+        offset += part.len() + 2;
+    }
+
+    components
+}
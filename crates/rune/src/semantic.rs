@@ -0,0 +1,515 @@
+//! Classification of the tokens of a source for semantic highlighting in
+//! editors.
+
+use crate::ast::{Delimiter, Kind, Token};
+use crate::Lexer;
+use runestick::Span;
+
+/// A classified span of a source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SemanticToken {
+    /// The span of the token.
+    pub span: Span,
+    /// The kind of the token.
+    pub kind: SemanticTokenKind,
+}
+
+/// The kind of a [SemanticToken].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SemanticTokenKind {
+    /// A keyword, like `fn` or `self`.
+    Keyword,
+    /// A module, like `std` in `std::iter::range`.
+    Module,
+    /// A struct or an enum.
+    Type,
+    /// A variant of an enum.
+    Variant,
+    /// A function, either where it's declared or where it's called.
+    Function,
+    /// An instance function called through a value, like `len` in
+    /// `values.len()`.
+    Method,
+    /// A macro, like `assert` in `assert!(true)`.
+    Macro,
+    /// A parameter of a function or a closure, and uses of it.
+    Parameter,
+    /// A local variable, and uses of it.
+    Variable,
+    /// A field of a struct or an object.
+    Field,
+    /// A label, like `'outer`.
+    Label,
+    /// A number literal.
+    Number,
+    /// A character literal.
+    Char,
+    /// A byte literal.
+    Byte,
+    /// A string literal.
+    String,
+    /// A byte string literal.
+    ByteString,
+    /// A template literal.
+    Template,
+    /// A boolean literal.
+    Bool,
+}
+
+impl SemanticTokenKind {
+    /// The name of the standard token type in the language server protocol
+    /// which corresponds to this kind.
+    pub fn lsp_type(self) -> &'static str {
+        match self {
+            Self::Keyword | Self::Bool => "keyword",
+            Self::Module => "namespace",
+            Self::Type => "type",
+            Self::Variant => "enumMember",
+            Self::Function => "function",
+            Self::Method => "method",
+            Self::Macro => "macro",
+            Self::Parameter => "parameter",
+            Self::Variable => "variable",
+            Self::Field => "property",
+            Self::Label => "label",
+            Self::Number | Self::Byte => "number",
+            Self::Char | Self::String | Self::ByteString | Self::Template => "string",
+        }
+    }
+}
+
+/// Classify every keyword, identifier and literal in the given source, in
+/// the order they appear.
+///
+/// Classification is done from the syntax of the source alone, so it's
+/// available for sources which don't compile yet as long as they parse.
+/// Parameters and variables are tracked through the blocks they're declared
+/// in, so uses of them are classified the same way as their declarations.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::SemanticTokenKind;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let source = "fn add(a, b) { let c = a + b; c }";
+/// let tokens = rune::semantic_tokens(source)?;
+///
+/// let kinds = tokens
+///     .iter()
+///     .map(|token| (&source[token.span.start..token.span.end], token.kind))
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(kinds[1], ("add", SemanticTokenKind::Function));
+/// assert_eq!(kinds[2], ("a", SemanticTokenKind::Parameter));
+/// assert_eq!(kinds[5], ("c", SemanticTokenKind::Variable));
+/// assert_eq!(kinds[6], ("a", SemanticTokenKind::Parameter));
+/// # Ok(())
+/// # }
+/// ```
+pub fn semantic_tokens(source: &str) -> Result<Vec<SemanticToken>, crate::ParseError> {
+    crate::parse_all::<crate::ast::DeclFile>(source)?;
+
+    let mut tokens = Vec::new();
+    let mut lexer = Lexer::new(source);
+
+    while let Some(token) = lexer.next()? {
+        tokens.push(token);
+    }
+
+    let mut classifier = Classifier {
+        source,
+        tokens: &tokens,
+        frames: vec![Frame::new(FrameKind::Block)],
+        binding: None,
+        fn_params: None,
+        expects_fn_params: false,
+    };
+
+    let mut output = Vec::new();
+
+    for (index, token) in tokens.iter().enumerate() {
+        if let Some(kind) = classifier.step(index) {
+            output.push(SemanticToken {
+                span: token.span,
+                kind,
+            });
+        }
+    }
+
+    Ok(output)
+}
+
+/// What the identifiers inside of a delimiter are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FrameKind {
+    /// A block or an expression, where locals can be declared.
+    Block,
+    /// The body of a struct or a struct variant, containing fields.
+    Struct,
+    /// The body of an enum, containing variants.
+    Enum,
+}
+
+/// An open delimiter, or the root of the source.
+struct Frame {
+    kind: FrameKind,
+    /// Locals declared in the frame, with the kind they were declared as.
+    locals: Vec<(String, SemanticTokenKind)>,
+}
+
+impl Frame {
+    fn new(kind: FrameKind) -> Self {
+        Self {
+            kind,
+            locals: Vec::new(),
+        }
+    }
+}
+
+/// A pattern or a list which declares bindings, and the number of frames
+/// which were open when it started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Binding {
+    /// The parameters of a function.
+    FnParams(usize),
+    /// The parameters of a closure.
+    ClosureParams(usize),
+    /// The pattern of a `let` binding.
+    Let(usize),
+    /// The pattern of a `for` loop.
+    For(usize),
+    /// A `use` declaration.
+    Use(usize),
+}
+
+struct Classifier<'a> {
+    source: &'a str,
+    tokens: &'a [Token],
+    frames: Vec<Frame>,
+    /// The binding currently being declared, if any.
+    binding: Option<Binding>,
+    /// Parameters of a function which are declared in its body once it's
+    /// opened.
+    fn_params: Option<Vec<(String, SemanticTokenKind)>>,
+    /// If the next open parenthesis starts the parameters of a function.
+    expects_fn_params: bool,
+}
+
+impl<'a> Classifier<'a> {
+    /// Process the token at the given index, returning its classification if
+    /// it's classified.
+    fn step(&mut self, index: usize) -> Option<SemanticTokenKind> {
+        Some(match self.tokens[index].kind {
+            Kind::Ident => self.ident(index),
+            Kind::Label => SemanticTokenKind::Label,
+            Kind::LitNumber { .. } => SemanticTokenKind::Number,
+            Kind::LitChar => SemanticTokenKind::Char,
+            Kind::LitByte => SemanticTokenKind::Byte,
+            Kind::LitStr { .. } => SemanticTokenKind::String,
+            Kind::LitByteStr { .. } => SemanticTokenKind::ByteString,
+            Kind::LitTemplate { .. } => SemanticTokenKind::Template,
+            Kind::True | Kind::False => SemanticTokenKind::Bool,
+            Kind::Fn => {
+                self.expects_fn_params = true;
+                SemanticTokenKind::Keyword
+            }
+            Kind::Let => {
+                self.binding = Some(Binding::Let(self.frames.len()));
+                SemanticTokenKind::Keyword
+            }
+            Kind::For => {
+                self.binding = Some(Binding::For(self.frames.len()));
+                SemanticTokenKind::Keyword
+            }
+            Kind::Use => {
+                self.binding = Some(Binding::Use(self.frames.len()));
+                SemanticTokenKind::Keyword
+            }
+            Kind::In => {
+                if let Some(Binding::For(..)) = self.binding {
+                    self.binding = None;
+                }
+
+                SemanticTokenKind::Keyword
+            }
+            Kind::Self_
+            | Kind::Macro
+            | Kind::Enum
+            | Kind::Struct
+            | Kind::Is
+            | Kind::As
+            | Kind::Not
+            | Kind::If
+            | Kind::Match
+            | Kind::Else
+            | Kind::While
+            | Kind::Loop
+            | Kind::Break
+            | Kind::Continue
+            | Kind::Yield
+            | Kind::Return
+            | Kind::Await
+            | Kind::Async
+            | Kind::Select
+            | Kind::Default
+            | Kind::Impl
+            | Kind::Mod => SemanticTokenKind::Keyword,
+            Kind::Open(delimiter) => {
+                self.open(index, delimiter);
+                return None;
+            }
+            Kind::Close(..) => {
+                self.close();
+                return None;
+            }
+            Kind::Eq | Kind::SemiColon => {
+                if let Some(Binding::Let(depth)) | Some(Binding::Use(depth)) = self.binding {
+                    if depth == self.frames.len() {
+                        self.binding = None;
+                    }
+                }
+
+                return None;
+            }
+            Kind::Pipe => {
+                match self.binding {
+                    Some(Binding::ClosureParams(depth)) if depth == self.frames.len() => {
+                        self.binding = None;
+                    }
+                    None if self.starts_expression(index) => {
+                        self.binding = Some(Binding::ClosureParams(self.frames.len()));
+                    }
+                    _ => (),
+                }
+
+                return None;
+            }
+            _ => return None,
+        })
+    }
+
+    fn ident(&mut self, index: usize) -> SemanticTokenKind {
+        let name = self.text(index);
+        let prev = self.kind_at(index.checked_sub(1));
+        let next = self.kind_at(Some(index + 1));
+        let is_type = name.chars().next().map_or(false, char::is_uppercase);
+
+        match prev {
+            Some(Kind::Fn) => return SemanticTokenKind::Function,
+            Some(Kind::Struct) | Some(Kind::Enum) => return SemanticTokenKind::Type,
+            Some(Kind::Mod) => return SemanticTokenKind::Module,
+            _ => (),
+        }
+
+        if let Some(Kind::Bang) = next {
+            return SemanticTokenKind::Macro;
+        }
+
+        match self.binding {
+            Some(Binding::FnParams(..)) => {
+                return self.declare_param(name);
+            }
+            Some(Binding::ClosureParams(depth)) => {
+                let kind = SemanticTokenKind::Parameter;
+                self.declare(depth, name, kind);
+                return kind;
+            }
+            Some(Binding::Let(depth)) | Some(Binding::For(depth))
+                if !is_type && !is_path(prev, next) =>
+            {
+                let kind = SemanticTokenKind::Variable;
+                self.declare(depth, name, kind);
+                return kind;
+            }
+            Some(Binding::Use(..)) => {
+                return match next {
+                    Some(Kind::ColonColon) => SemanticTokenKind::Module,
+                    _ if is_type => SemanticTokenKind::Type,
+                    _ => SemanticTokenKind::Function,
+                };
+            }
+            _ => (),
+        }
+
+        let frame = self.frames.last().map(|frame| frame.kind);
+
+        match frame {
+            Some(FrameKind::Struct) => return SemanticTokenKind::Field,
+            Some(FrameKind::Enum) if matches!(prev, Some(Kind::Open(..)) | Some(Kind::Comma)) => {
+                return SemanticTokenKind::Variant;
+            }
+            _ => (),
+        }
+
+        if let Some(Kind::Dot) = prev {
+            return match next {
+                Some(Kind::Open(Delimiter::Parenthesis)) => SemanticTokenKind::Method,
+                _ => SemanticTokenKind::Field,
+            };
+        }
+
+        if let Some(Kind::ColonColon) = next {
+            return if is_type {
+                SemanticTokenKind::Type
+            } else {
+                SemanticTokenKind::Module
+            };
+        }
+
+        if let Some(Kind::ColonColon) = prev {
+            let after_type = match self.kind_at(index.checked_sub(2)) {
+                Some(Kind::Ident) => self
+                    .text(index - 2)
+                    .chars()
+                    .next()
+                    .map_or(false, char::is_uppercase),
+                _ => false,
+            };
+
+            return match (is_type, after_type) {
+                (true, true) => SemanticTokenKind::Variant,
+                (true, false) => SemanticTokenKind::Type,
+                (false, _) => SemanticTokenKind::Function,
+            };
+        }
+
+        if let Some(Kind::Colon) = next {
+            return SemanticTokenKind::Field;
+        }
+
+        if let Some(kind) = self.lookup(name) {
+            return kind;
+        }
+
+        if is_type {
+            return SemanticTokenKind::Type;
+        }
+
+        match next {
+            Some(Kind::Open(Delimiter::Parenthesis)) => SemanticTokenKind::Function,
+            _ => SemanticTokenKind::Variable,
+        }
+    }
+
+    fn open(&mut self, index: usize, delimiter: Delimiter) {
+        let prev = self.kind_at(index.checked_sub(1));
+        let before_prev = self.kind_at(index.checked_sub(2));
+
+        let in_enum = self.frames.last().map(|frame| frame.kind) == Some(FrameKind::Enum);
+
+        // NB: the bodies of structs and variants contain the names of their
+        // fields, whether they're declared with braces or parentheses.
+        let kind = match (before_prev, prev, delimiter) {
+            (Some(Kind::Struct), Some(Kind::Ident), Delimiter::Brace)
+            | (Some(Kind::Struct), Some(Kind::Ident), Delimiter::Parenthesis) => FrameKind::Struct,
+            (Some(Kind::Enum), Some(Kind::Ident), Delimiter::Brace) => FrameKind::Enum,
+            (_, Some(Kind::Ident), Delimiter::Brace)
+            | (_, Some(Kind::Ident), Delimiter::Parenthesis)
+                if in_enum =>
+            {
+                FrameKind::Struct
+            }
+            _ => FrameKind::Block,
+        };
+
+        let mut frame = Frame::new(kind);
+
+        match delimiter {
+            Delimiter::Parenthesis if self.expects_fn_params => {
+                self.expects_fn_params = false;
+                self.fn_params = Some(Vec::new());
+                self.binding = Some(Binding::FnParams(self.frames.len() + 1));
+            }
+            Delimiter::Brace => {
+                if let Some(params) = self.fn_params.take() {
+                    frame.locals = params;
+                }
+            }
+            _ => (),
+        }
+
+        self.frames.push(frame);
+    }
+
+    fn close(&mut self) {
+        if let Some(Binding::FnParams(depth)) = self.binding {
+            if depth == self.frames.len() {
+                self.binding = None;
+            }
+        }
+
+        // NB: the root frame is never closed, since the source parses.
+        if self.frames.len() > 1 {
+            self.frames.pop();
+        }
+    }
+
+    /// Declare a local in the frame at the given depth, which is where the
+    /// pattern declaring it started.
+    fn declare(&mut self, depth: usize, name: &str, kind: SemanticTokenKind) {
+        let index = depth.saturating_sub(1).min(self.frames.len() - 1);
+        self.frames[index].locals.push((name.to_owned(), kind));
+    }
+
+    fn declare_param(&mut self, name: &str) -> SemanticTokenKind {
+        let kind = SemanticTokenKind::Parameter;
+
+        if let Some(params) = &mut self.fn_params {
+            params.push((name.to_owned(), kind));
+        }
+
+        kind
+    }
+
+    /// Look up the local with the given name, starting with the innermost
+    /// frame.
+    fn lookup(&self, name: &str) -> Option<SemanticTokenKind> {
+        self.frames
+            .iter()
+            .rev()
+            .flat_map(|frame| frame.locals.iter().rev())
+            .find(|(local, _)| local == name)
+            .map(|(_, kind)| *kind)
+    }
+
+    /// Test if the token at the given index is in a position where an
+    /// expression starts, like after an assignment or an open delimiter.
+    fn starts_expression(&self, index: usize) -> bool {
+        match self.kind_at(index.checked_sub(1)) {
+            None => true,
+            Some(kind) => matches!(
+                kind,
+                Kind::Open(..)
+                    | Kind::Comma
+                    | Kind::Eq
+                    | Kind::SemiColon
+                    | Kind::Rocket
+                    | Kind::Return
+                    | Kind::Yield
+                    | Kind::Break
+            ),
+        }
+    }
+
+    fn kind_at(&self, index: Option<usize>) -> Option<Kind> {
+        Some(self.tokens.get(index?)?.kind)
+    }
+
+    fn text(&self, index: usize) -> &'a str {
+        let span = self.tokens[index].span;
+        &self.source[span.start..span.end]
+    }
+}
+
+/// Test if an identifier is part of a path or a constructor in a pattern,
+/// rather than a binding, like `Some` in `Some(value)`.
+fn is_path(prev: Option<Kind>, next: Option<Kind>) -> bool {
+    matches!(prev, Some(Kind::ColonColon))
+        || matches!(
+            next,
+            Some(Kind::ColonColon)
+                | Some(Kind::Open(Delimiter::Parenthesis))
+                | Some(Kind::Open(Delimiter::Brace))
+        )
+}
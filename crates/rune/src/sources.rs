@@ -1,3 +1,5 @@
+use crate::ast;
+use crate::collections::HashMap;
 use runestick::{Item, Source};
 use std::collections::VecDeque;
 use std::sync::Arc;
@@ -6,6 +8,8 @@ use std::sync::Arc;
 pub struct Sources {
     sources: Vec<Arc<Source>>,
     queue: VecDeque<(Item, usize)>,
+    /// Sources which have already been parsed, by source id.
+    parsed: HashMap<usize, ast::DeclFile>,
 }
 
 impl Sources {
@@ -14,6 +18,7 @@ impl Sources {
         Self {
             sources: Vec::new(),
             queue: VecDeque::new(),
+            parsed: HashMap::new(),
         }
     }
 
@@ -35,6 +40,27 @@ impl Sources {
         self.insert(Item::default(), source)
     }
 
+    /// Insert a new source which has already been parsed into the given file,
+    /// and return its associated id.
+    ///
+    /// The spans in the file must refer to the given source.
+    pub(crate) fn insert_parsed(
+        &mut self,
+        item: Item,
+        source: Source,
+        file: ast::DeclFile,
+    ) -> usize {
+        let source_id = self.insert(item, source);
+        self.parsed.insert(source_id, file);
+        source_id
+    }
+
+    /// Take the file which the source with the given id was parsed into, if
+    /// it was inserted with [insert_parsed][Self::insert_parsed].
+    pub(crate) fn take_parsed(&mut self, source_id: usize) -> Option<ast::DeclFile> {
+        self.parsed.remove(&source_id)
+    }
+
     /// Get the source matching the given source id.
     pub fn get(&self, source_id: usize) -> Option<&Arc<Source>> {
         self.sources.get(source_id)
@@ -46,7 +72,7 @@ impl Sources {
     }
 
     /// Iterate over all sources in order by index.
-    pub(crate) fn iter(&self) -> impl Iterator<Item = &Source> {
+    pub fn iter(&self) -> impl Iterator<Item = &Source> {
         self.sources.iter().map(|s| &**s)
     }
 }
@@ -0,0 +1,371 @@
+//! Simulation of the stack of a single function.
+//!
+//! The compiler uses this to verify that the instructions of every function in
+//! the unit keep the stack balanced and to record its maximum depth, and the
+//! optimizer uses it to find out which slots are on top of the stack.
+
+use crate::assembly::{Assembly, AssemblyInst};
+use crate::unit_builder::UnitBuilder;
+use runestick::Inst;
+
+/// The stack of a function when it's entered.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Frame {
+    /// The number of values on the stack, including the environment of a
+    /// closure.
+    pub(crate) args: usize,
+    /// The number of values in the environment of a closure, which are
+    /// unpacked onto the stack by [Inst::PushTuple].
+    pub(crate) environment: usize,
+}
+
+impl Frame {
+    /// The frame of a function which takes `args` arguments.
+    pub(crate) fn new(args: usize) -> Self {
+        Self {
+            args,
+            environment: 0,
+        }
+    }
+
+    /// The frame of a closure which takes `args` arguments and captures
+    /// `environment` values.
+    ///
+    /// Captured values are passed in as a single environment tuple.
+    pub(crate) fn closure(args: usize, environment: usize) -> Self {
+        Self {
+            args: args + usize::from(environment > 0),
+            environment,
+        }
+    }
+}
+
+/// Error raised when the stack of a function can't be simulated, which means
+/// that the compiler emitted instructions that would corrupt the stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StackError {
+    /// The instruction at `at` pops more values than there are on the stack.
+    Underflow {
+        /// The offset of the instruction.
+        at: usize,
+        /// The depth of the stack before the instruction.
+        depth: usize,
+        /// The number of values popped by the instruction.
+        pops: usize,
+    },
+    /// The instruction at `at` is reached with different depths of the stack.
+    Mismatch {
+        /// The offset of the instruction.
+        at: usize,
+        /// The offset of the instruction that reached it with `actual`.
+        from: usize,
+        /// The depth it was first reached with.
+        expected: usize,
+        /// The depth it was reached with from `from`.
+        actual: usize,
+    },
+    /// The instruction at `at` jumps to a label which was never applied.
+    MissingLabel {
+        /// The offset of the jump.
+        at: usize,
+    },
+    /// The stack effect of the instruction at `at` is not known.
+    Unknown {
+        /// The offset of the instruction.
+        at: usize,
+    },
+}
+
+/// Calculate the maximum depth of the stack of a function, including its
+/// arguments.
+pub(crate) fn max_depth(
+    asm: &Assembly,
+    frame: Frame,
+    unit: &UnitBuilder,
+) -> Result<usize, StackError> {
+    let depths = stack_depths(asm, frame, unit)?;
+    let mut max = frame.args;
+
+    for (n, depth) in depths.iter().enumerate() {
+        let depth = match depth {
+            Some(depth) => *depth,
+            None => continue,
+        };
+
+        let peak = match asm.instructions.get(n) {
+            // NB: the branch which completed is on top of the selected value
+            // until it's popped by a jump-if-branch.
+            Some((
+                AssemblyInst::Raw {
+                    raw: Inst::Select { len, .. },
+                },
+                _,
+            )) => depth - *len + 2,
+            _ => depth,
+        };
+
+        max = usize::max(max, peak);
+    }
+
+    Ok(max)
+}
+
+/// Simulate the depth of the stack before every instruction.
+///
+/// Instructions which are unreachable have an unknown depth. If the depth
+/// can't be determined, like when an instruction pops more values than are on
+/// the stack or when jumps disagree over the depth at their target, the
+/// offending instruction is reported in the returned error.
+pub(crate) fn stack_depths(
+    asm: &Assembly,
+    frame: Frame,
+    unit: &UnitBuilder,
+) -> Result<Vec<Option<usize>>, StackError> {
+    let mut depths = vec![None; asm.instructions.len() + 1];
+    let mut queue = vec![(0, frame.args, 0)];
+
+    while let Some((n, depth, from)) = queue.pop() {
+        match depths[n] {
+            Some(existing) if existing == depth => continue,
+            Some(expected) => {
+                return Err(StackError::Mismatch {
+                    at: n,
+                    from,
+                    expected,
+                    actual: depth,
+                });
+            }
+            None => depths[n] = Some(depth),
+        }
+
+        let inst = match asm.instructions.get(n) {
+            Some((inst, _)) => inst,
+            None => continue,
+        };
+
+        let target = |label| {
+            asm.labels
+                .get(&label)
+                .copied()
+                .ok_or(StackError::MissingLabel { at: n })
+        };
+
+        let pop = |pops: usize| {
+            depth
+                .checked_sub(pops)
+                .ok_or(StackError::Underflow { at: n, depth, pops })
+        };
+
+        match *inst {
+            AssemblyInst::Jump { label } => {
+                queue.push((target(label)?, depth, n));
+            }
+            AssemblyInst::JumpIf { label } | AssemblyInst::JumpIfNot { label } => {
+                let depth = pop(1)?;
+                queue.push((target(label)?, depth, n));
+                queue.push((n + 1, depth, n));
+            }
+            AssemblyInst::PopAndJumpIfNot { count, label } => {
+                let jumped = pop(count.checked_add(1).ok_or(StackError::Unknown { at: n })?)?;
+                queue.push((target(label)?, jumped, n));
+                queue.push((n + 1, pop(1)?, n));
+            }
+            // NB: the branch which completed is only on the stack until the
+            // jump to it is taken, so it isn't counted.
+            AssemblyInst::JumpIfBranch { label, .. }
+            | AssemblyInst::PeekAndJumpIf { label }
+            | AssemblyInst::PeekAndJumpIfNot { label } => {
+                queue.push((target(label)?, depth, n));
+                queue.push((n + 1, depth, n));
+            }
+            AssemblyInst::Raw { raw } => match raw {
+                Inst::Return => {
+                    pop(1)?;
+                }
+                Inst::ReturnUnit | Inst::Panic { .. } => (),
+                raw => {
+                    let (pops, pushes) =
+                        stack_effect(raw, frame, unit).ok_or(StackError::Unknown { at: n })?;
+                    queue.push((n + 1, pop(pops)? + pushes, n));
+                }
+            },
+        }
+    }
+
+    Ok(depths)
+}
+
+/// The number of values popped and pushed by a non-branching instruction.
+fn stack_effect(inst: Inst, frame: Frame, unit: &UnitBuilder) -> Option<(usize, usize)> {
+    Some(match inst {
+        Inst::Fn { .. }
+        | Inst::TupleIndexGetAt { .. }
+        | Inst::ObjectSlotIndexGetAt { .. }
+        | Inst::Integer { .. }
+        | Inst::Float { .. }
+        | Inst::Copy { .. }
+        | Inst::RangeNext { .. }
+        | Inst::Dup
+        | Inst::Unit
+        | Inst::Bool { .. }
+        | Inst::Char { .. }
+        | Inst::Byte { .. }
+        | Inst::String { .. }
+        | Inst::Bytes { .. }
+        | Inst::Static { .. }
+        | Inst::Type { .. }
+        | Inst::YieldUnit => (0, 1),
+        Inst::Not
+        | Inst::Neg
+        | Inst::LoadInstanceFn { .. }
+        | Inst::TupleIndexGet { .. }
+        | Inst::ObjectSlotIndexGet { .. }
+        | Inst::Await
+        | Inst::IsUnit
+        | Inst::IsValue
+        | Inst::Unwrap
+        | Inst::EqByte { .. }
+        | Inst::EqCharacter { .. }
+        | Inst::EqInteger { .. }
+        | Inst::MatchByteRange { .. }
+        | Inst::MatchCharacterRange { .. }
+        | Inst::MatchIntegerRange { .. }
+        | Inst::EqStaticString { .. }
+        | Inst::MatchSequence { .. }
+        | Inst::MatchObject { .. }
+        | Inst::Yield => (1, 1),
+        Inst::Add
+        | Inst::Sub
+        | Inst::Mul
+        | Inst::Div
+        | Inst::Rem
+        | Inst::IndexGet
+        | Inst::Lt
+        | Inst::Gt
+        | Inst::Lte
+        | Inst::Gte
+        | Inst::Eq
+        | Inst::Neq
+        | Inst::Is
+        | Inst::IsNot
+        | Inst::As
+        | Inst::And
+        | Inst::Or
+        | Inst::BitAnd
+        | Inst::BitXor
+        | Inst::BitOr
+        | Inst::Shl
+        | Inst::Shr => (2, 1),
+        Inst::AddAssign { .. }
+        | Inst::SubAssign { .. }
+        | Inst::MulAssign { .. }
+        | Inst::DivAssign { .. }
+        | Inst::RemAssign { .. }
+        | Inst::BitAndAssign { .. }
+        | Inst::BitXorAssign { .. }
+        | Inst::BitOrAssign { .. }
+        | Inst::ShlAssign { .. }
+        | Inst::ShrAssign { .. }
+        | Inst::Replace { .. }
+        | Inst::Pop => (1, 0),
+        Inst::Drop { .. } | Inst::Coverage { .. } => (0, 0),
+        Inst::TupleIndexSet { .. } => (2, 0),
+        Inst::IndexSet => (3, 0),
+        Inst::PopN { count } => (count, 0),
+        Inst::Clean { count } => (count.checked_add(1)?, 1),
+        Inst::Closure { count, .. } | Inst::Vec { count } | Inst::Tuple { count } => (count, 1),
+        Inst::HashMap { count } | Inst::DynamicObject { count } => (count * 2, 1),
+        Inst::HashSet { count } => (count, 1),
+        Inst::StringConcat { len, .. } | Inst::Select { len, .. } => (len, 1),
+        Inst::PushTuple => (1, frame.environment),
+        Inst::Call { args, .. } => (args, 1),
+        Inst::CallInstance { args, .. } | Inst::CallFn { args } => (args.checked_add(1)?, 1),
+        Inst::Object { slot }
+        | Inst::TypedObject { slot, .. }
+        | Inst::VariantObject { slot, .. } => (unit.lookup_object_keys(slot)?.len(), 1),
+        Inst::Jump { .. }
+        | Inst::JumpIf { .. }
+        | Inst::JumpIfNot { .. }
+        | Inst::JumpIfBranch { .. }
+        | Inst::PeekAndJumpIf { .. }
+        | Inst::PeekAndJumpIfNot { .. }
+        | Inst::PopAndJumpIfNot { .. }
+        | Inst::Return
+        | Inst::ReturnUnit
+        | Inst::Panic { .. } => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{max_depth, Frame, StackError};
+    use crate::assembly::Assembly;
+    use crate::unit_builder::UnitBuilder;
+    use runestick::{Inst, Span};
+
+    #[test]
+    fn test_balanced_branches() {
+        let unit = UnitBuilder::new();
+        let mut asm = Assembly::new(0, 0);
+        let span = Span::empty();
+
+        let else_label = asm.new_label("else");
+        let end_label = asm.new_label("end");
+
+        asm.push(Inst::Copy { offset: 0 }, span);
+        asm.jump_if_not(else_label, span);
+        asm.push(Inst::Integer { number: 1 }, span);
+        asm.jump(end_label, span);
+        asm.label(else_label).unwrap();
+        asm.push(Inst::Integer { number: 2 }, span);
+        asm.label(end_label).unwrap();
+        asm.push(Inst::Return, span);
+
+        assert_eq!(max_depth(&asm, Frame::new(1), &unit), Ok(2));
+    }
+
+    #[test]
+    fn test_mismatched_branches() {
+        let unit = UnitBuilder::new();
+        let mut asm = Assembly::new(0, 0);
+        let span = Span::empty();
+
+        let end_label = asm.new_label("end");
+
+        asm.push(Inst::Copy { offset: 0 }, span);
+        asm.jump_if_not(end_label, span);
+        asm.push(Inst::Integer { number: 1 }, span);
+        asm.label(end_label).unwrap();
+        asm.push(Inst::Return, span);
+
+        assert_eq!(
+            max_depth(&asm, Frame::new(1), &unit),
+            Err(StackError::Mismatch {
+                at: 3,
+                from: 1,
+                expected: 2,
+                actual: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_underflow() {
+        let unit = UnitBuilder::new();
+        let mut asm = Assembly::new(0, 0);
+        let span = Span::empty();
+
+        asm.push(Inst::Integer { number: 1 }, span);
+        asm.push(Inst::Add, span);
+        asm.push(Inst::Return, span);
+
+        assert_eq!(
+            max_depth(&asm, Frame::new(0), &unit),
+            Err(StackError::Underflow {
+                at: 1,
+                depth: 1,
+                pops: 2,
+            })
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use crate::ast::Token;
-use crate::MacroContext;
+use crate::{MacroContext, Parse, ParseError, Parser};
 use runestick::Span;
 use std::slice;
 
@@ -35,6 +35,40 @@ impl TokenStream {
         self.end
     }
 
+    /// Parse the whole token stream as the given type.
+    ///
+    /// This is typically used by macros to parse the token streams they
+    /// receive into typed AST.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rune::{ast, Lexer, TokenStream};
+    /// use runestick::Span;
+    ///
+    /// # fn main() -> Result<(), rune::ParseError> {
+    /// let mut lexer = Lexer::new("foo(1, 2)");
+    /// let mut stream = TokenStream::new(Vec::new(), lexer.end());
+    ///
+    /// while let Some(token) = lexer.next()? {
+    ///     stream.push(token);
+    /// }
+    ///
+    /// let call = stream.parse::<ast::ExprCall>()?;
+    /// assert_eq!(call.args.items.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse<T>(&self) -> Result<T, ParseError>
+    where
+        T: Parse,
+    {
+        let mut parser = Parser::from_token_stream(self);
+        let output = parser.parse::<T>()?;
+        parser.parse_eof()?;
+        Ok(output)
+    }
+
     /// Create an iterator over the token stream.
     pub(crate) fn iter(&self) -> TokenStreamIter<'_> {
         TokenStreamIter {
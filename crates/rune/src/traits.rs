@@ -99,6 +99,33 @@ pub trait Resolve<'a> {
     fn resolve(&self, source: &'a Source) -> Result<Self::Output, ParseError>;
 }
 
+/// A type that can be resolved to an owned value based on a source.
+///
+/// Unlike [Resolve], the resolved value doesn't borrow from the source, which
+/// is useful for tooling that keeps resolved values around independently of
+/// the sources they came from.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{ast, parse_all, ResolveOwned as _};
+/// use runestick::Source;
+///
+/// let source = Source::new("main", "foo::bar");
+/// let path = parse_all::<ast::Path>(source.as_str()).unwrap();
+/// let path = path.resolve_owned(&source).unwrap();
+/// drop(source);
+///
+/// assert_eq!(path, vec![String::from("foo"), String::from("bar")]);
+/// ```
+pub trait ResolveOwned {
+    /// The owned type being resolved.
+    type Owned;
+
+    /// Resolve the owned value from parsed AST.
+    fn resolve_owned(&self, source: &Source) -> Result<Self::Owned, ParseError>;
+}
+
 pub(crate) trait Compile<T> {
     /// Walk the current type with the given item.
     fn compile(&mut self, item: T) -> CompileResult<()>;
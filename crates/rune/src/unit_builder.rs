@@ -5,13 +5,15 @@
 
 use crate::assembly::{Assembly, AssemblyInst};
 use crate::ast;
-use crate::collections::HashMap;
+use crate::collections::{HashMap, HashSet};
+use crate::compatibility::{ArityMismatch, CompatibilityReport, MissingFunction};
 use crate::error::CompileResult;
 use crate::Resolve as _;
 use runestick::debug::{DebugArgs, DebugSignature};
 use runestick::{
-    Call, CompileMeta, Component, Context, DebugInfo, DebugInst, Hash, Inst, Item, Label, Names,
-    Source, Span, StaticString, Type, Unit, UnitFn, UnitTypeInfo,
+    ApiVersion, Call, CompileMeta, Component, Context, ContextSignature, Coverage, DebugInfo,
+    DebugInst, Hash, Inst, Item, Label, Names, Source, Span, StaticString, StaticValue, Type, Unit,
+    UnitFn, UnitTypeInfo,
 };
 use std::sync::Arc;
 use thiserror::Error;
@@ -195,8 +197,18 @@ pub struct UnitBuilder {
     meta: HashMap<Item, CompileMeta>,
     /// Where functions are located in the collection of instructions.
     functions: HashMap<Hash, UnitFn>,
+    /// The function table, indexed by function id.
+    function_offsets: Vec<usize>,
+    /// The maximum depth of the stack of every function, indexed by function
+    /// id.
+    function_stacks: Vec<usize>,
     /// Declared types.
     types: HashMap<Hash, UnitTypeInfo>,
+    /// The variants of declared enums, in declaration order.
+    enums: HashMap<Hash, Vec<Hash>>,
+    /// The fields of declared structs and struct variants, in declaration
+    /// order.
+    struct_fields: HashMap<Hash, Box<[String]>>,
     /// Function by address.
     functions_rev: HashMap<usize, Hash>,
     /// A static string.
@@ -216,14 +228,24 @@ pub struct UnitBuilder {
     static_object_keys: Vec<Box<[String]>>,
     /// Used to detect duplicates in the collection of static object keys.
     static_object_keys_rev: HashMap<Hash, usize>,
+    /// The static data section, containing fully constant values.
+    static_values: Vec<StaticValue>,
     /// The current label count.
     label_count: usize,
-    /// A collection of required function hashes.
-    required_functions: HashMap<Hash, Vec<(Span, usize)>>,
+    /// A collection of required function hashes, with the span, source id,
+    /// and number of arguments of every call.
+    required_functions: HashMap<Hash, Vec<(Span, usize, usize)>>,
     /// All available names in the context.
     names: Names,
     /// Debug info if available for unit.
     debug: Option<Box<DebugInfo>>,
+    /// Coverage counters emitted while compiling.
+    coverage: Coverage,
+    /// Modules remapped by the host, from the path used in scripts to the path
+    /// of the module backing it.
+    module_map: HashMap<Item, Item>,
+    /// Names imported by the prelude.
+    prelude: HashSet<String>,
 }
 
 impl UnitBuilder {
@@ -325,9 +347,63 @@ impl UnitBuilder {
             ImportEntry::of(&["std", "option", "Option", "None"]),
         );
 
+        this.prelude = this
+            .imports
+            .keys()
+            .map(|key| key.component.to_string())
+            .collect();
+
         this
     }
 
+    /// Test if the given name is imported by the prelude.
+    pub(crate) fn is_prelude(&self, name: &str) -> bool {
+        self.prelude.contains(name)
+    }
+
+    /// Map the module at `from`, as used in scripts, to the module at `to`.
+    ///
+    /// Paths in scripts which start with `from`, either directly or through a
+    /// `use` declaration, are resolved as if they started with `to` instead.
+    /// This lets the host decide which module backs a path without changing
+    /// the scripts, like to swap in a test double or a per-tenant API. If
+    /// several mappings apply to a path, the longest one is used.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut unit = rune::UnitBuilder::with_default_prelude();
+    /// unit.map_module(&["app", "api"], &["tenants", "acme", "api"]);
+    /// ```
+    pub fn map_module<A, B>(&mut self, from: A, to: B)
+    where
+        A: IntoIterator,
+        A::Item: Into<Component>,
+        B: IntoIterator,
+        B::Item: Into<Component>,
+    {
+        self.module_map.insert(Item::of(from), Item::of(to));
+    }
+
+    /// Apply the module mappings to the given item.
+    pub(crate) fn map_item(&self, item: Item) -> Item {
+        if self.module_map.is_empty() {
+            return item;
+        }
+
+        let components = item.as_vec();
+
+        for n in (1..=components.len()).rev() {
+            let prefix = Item::of(components[..n].iter().cloned());
+
+            if let Some(to) = self.module_map.get(&prefix) {
+                return to.join(components[n..].iter().cloned());
+            }
+        }
+
+        item
+    }
+
     /// Convert into a runtime unit, shedding our build metadata in the process.
     pub fn into_unit(mut self) -> Unit {
         if let Some(debug) = &mut self.debug {
@@ -337,14 +413,26 @@ impl UnitBuilder {
         Unit::new(
             self.instructions,
             self.functions,
+            self.function_offsets,
+            self.function_stacks,
             self.types,
+            self.enums,
+            self.struct_fields,
             self.static_strings,
             self.static_bytes,
             self.static_object_keys,
+            self.static_values,
             self.debug,
+            self.coverage,
         )
     }
 
+    /// Add a new coverage counter covering the given span, returning its
+    /// index.
+    pub(crate) fn new_coverage_counter(&mut self, source_id: usize, span: Span) -> usize {
+        self.coverage.insert(source_id, span)
+    }
+
     /// Insert and access debug information.
     pub(crate) fn debug_info_mut(&mut self) -> &mut DebugInfo {
         self.debug.get_or_insert_with(Default::default)
@@ -409,6 +497,25 @@ impl UnitBuilder {
         Ok(new_slot)
     }
 
+    /// Intern a static string, returning the string shared with the static
+    /// string slot it's stored in.
+    pub(crate) fn intern_static_string(
+        &mut self,
+        current: &str,
+    ) -> Result<Arc<StaticString>, UnitBuilderError> {
+        let slot = self.new_static_string(current)?;
+
+        let string =
+            self.static_strings
+                .get(slot)
+                .ok_or_else(|| UnitBuilderError::StaticStringMissing {
+                    hash: Hash::of(current),
+                    slot,
+                })?;
+
+        Ok(string.clone())
+    }
+
     /// Insert a static byte string and return its associated slot that can
     /// later be looked up through [lookup_bytes][Self::lookup_bytes].
     ///
@@ -475,6 +582,13 @@ impl UnitBuilder {
         Ok(new_slot)
     }
 
+    /// Insert a value into the static data section, returning its slot.
+    pub(crate) fn new_static_value(&mut self, value: StaticValue) -> usize {
+        let slot = self.static_values.len();
+        self.static_values.push(value);
+        slot
+    }
+
     fn lookup_import_by_name(&self, base: &Item, local: &Component) -> Option<Item> {
         let mut base = base.clone();
 
@@ -493,6 +607,11 @@ impl UnitBuilder {
         None
     }
 
+    /// Lookup the static object keys in the given slot.
+    pub(crate) fn lookup_object_keys(&self, slot: usize) -> Option<&[String]> {
+        self.static_object_keys.get(slot).map(|keys| &keys[..])
+    }
+
     /// Perform a path lookup on the current state of the unit.
     pub(crate) fn convert_path(
         &self,
@@ -502,19 +621,17 @@ impl UnitBuilder {
     ) -> CompileResult<Item> {
         let local = Component::from(path.first.resolve(source)?);
 
-        let imported = match self.lookup_import_by_name(base, &local) {
-            Some(path) => path,
-            None => Item::of(&[local]),
-        };
-
         let mut rest = Vec::new();
 
         for (_, part) in &path.rest {
             rest.push(Component::String(part.resolve(source)?.to_owned()));
         }
 
-        let it = imported.into_iter().chain(rest.into_iter());
-        Ok(Item::of(it))
+        // NB: imports are mapped when they're declared.
+        match self.lookup_import_by_name(base, &local) {
+            Some(imported) => Ok(imported.join(rest)),
+            None => Ok(self.map_item(Item::of(Some(local).into_iter().chain(rest)))),
+        }
     }
 
     /// Look up an use by name.
@@ -534,7 +651,7 @@ impl UnitBuilder {
         I: Copy + IntoIterator,
         I::Item: Into<Component>,
     {
-        let path = Item::of(path);
+        let path = self.map_item(Item::of(path));
 
         if let Some(last) = path.last() {
             let entry = ImportEntry {
@@ -577,6 +694,7 @@ impl UnitBuilder {
                 let info = UnitTypeInfo {
                     hash: tuple.hash,
                     value_type: Type::Hash(tuple.hash),
+                    name: tuple.item.clone(),
                 };
 
                 if self.types.insert(tuple.hash, info).is_some() {
@@ -594,7 +712,7 @@ impl UnitBuilder {
             CompileMeta::TupleVariant {
                 enum_item, tuple, ..
             } => {
-                let enum_hash = Hash::type_hash(enum_item);
+                let enum_hash = enum_item.type_hash();
 
                 let info = UnitFn::TupleVariant {
                     enum_hash,
@@ -616,6 +734,7 @@ impl UnitBuilder {
                 let info = UnitTypeInfo {
                     hash: tuple.hash,
                     value_type: Type::Hash(enum_hash),
+                    name: tuple.item.clone(),
                 };
 
                 if self.types.insert(tuple.hash, info).is_some() {
@@ -631,11 +750,12 @@ impl UnitBuilder {
                 tuple.item.clone()
             }
             CompileMeta::Struct { object, .. } => {
-                let hash = Hash::type_hash(&object.item);
+                let hash = object.item.type_hash();
 
                 let info = UnitTypeInfo {
                     hash,
                     value_type: Type::Hash(hash),
+                    name: object.item.clone(),
                 };
 
                 if self.types.insert(hash, info).is_some() {
@@ -649,12 +769,13 @@ impl UnitBuilder {
             CompileMeta::StructVariant {
                 enum_item, object, ..
             } => {
-                let hash = Hash::type_hash(&object.item);
-                let enum_hash = Hash::type_hash(enum_item);
+                let hash = object.item.type_hash();
+                let enum_hash = enum_item.type_hash();
 
                 let info = UnitTypeInfo {
                     hash,
                     value_type: Type::Hash(enum_hash),
+                    name: object.item.clone(),
                 };
 
                 if self.types.insert(hash, info).is_some() {
@@ -666,11 +787,12 @@ impl UnitBuilder {
                 object.item.clone()
             }
             CompileMeta::Enum { item, .. } => {
-                let hash = Hash::type_hash(item);
+                let hash = item.type_hash();
 
                 let info = UnitTypeInfo {
                     hash,
                     value_type: Type::Hash(hash),
+                    name: item.clone(),
                 };
 
                 if self.types.insert(hash, info).is_some() {
@@ -685,6 +807,7 @@ impl UnitBuilder {
             CompileMeta::Closure { item, .. } => item.clone(),
             CompileMeta::AsyncBlock { item, .. } => item.clone(),
             CompileMeta::Macro { item, .. } => item.clone(),
+            CompileMeta::Interface { item, .. } => item.clone(),
         };
 
         if let Some(existing) = self.meta.insert(item, meta.clone()) {
@@ -697,26 +820,52 @@ impl UnitBuilder {
         Ok(())
     }
 
+    /// Record the variants of the given enum, in declaration order.
+    pub(crate) fn insert_enum_variants(&mut self, enum_item: &Item, variants: &[Item]) {
+        let variants = variants.iter().map(Item::type_hash).collect();
+        self.enums.insert(enum_item.type_hash(), variants);
+    }
+
+    /// Record the fields of the given struct or struct variant, in
+    /// declaration order.
+    pub(crate) fn insert_struct_fields(&mut self, item: &Item, fields: Box<[String]>) {
+        self.struct_fields.insert(item.type_hash(), fields);
+    }
+
     /// Construct a new empty assembly associated with the current unit.
     pub(crate) fn new_assembly(&self, source_id: usize) -> Assembly {
         Assembly::new(source_id, self.label_count)
     }
 
+    /// Allocate an entry in the function table for a function starting at the
+    /// given offset, whose stack is at most `stack` values deep.
+    fn new_function_id(&mut self, offset: usize, stack: usize) -> usize {
+        let id = self.function_offsets.len();
+        self.function_offsets.push(offset);
+        self.function_stacks.push(stack);
+        id
+    }
+
     /// Declare a new function at the current instruction pointer.
     pub(crate) fn new_function(
         &mut self,
         source_id: usize,
         path: Item,
         args: usize,
+        stack: usize,
         assembly: Assembly,
         call: Call,
         debug_args: Vec<String>,
     ) -> Result<(), UnitBuilderError> {
         let offset = self.instructions.len();
-        let hash = Hash::type_hash(&path);
+        let hash = path.type_hash();
 
         self.functions_rev.insert(offset, hash);
-        let info = UnitFn::Offset { offset, call, args };
+        let info = UnitFn::Offset {
+            id: self.new_function_id(offset, stack),
+            call,
+            args,
+        };
         let signature = DebugSignature::new(path, debug_args);
 
         if self.functions.insert(hash, info).is_some() {
@@ -731,13 +880,16 @@ impl UnitBuilder {
     }
 
     /// Declare a new instance function at the current instruction pointer.
+    ///
+    /// The `name` is the hash of the name the function is called through.
     pub(crate) fn new_instance_function(
         &mut self,
         source_id: usize,
         path: Item,
         value_type: Type,
-        name: &str,
+        name: Hash,
         args: usize,
+        stack: usize,
         assembly: Assembly,
         call: Call,
         debug_args: Vec<String>,
@@ -745,11 +897,14 @@ impl UnitBuilder {
         log::trace!("instance fn: {}", path);
 
         let offset = self.instructions.len();
-        let instance_fn = Hash::of(name);
-        let instance_fn = Hash::instance_function(value_type, instance_fn);
-        let hash = Hash::type_hash(&path);
+        let instance_fn = Hash::instance_function(value_type, name);
+        let hash = path.type_hash();
 
-        let info = UnitFn::Offset { offset, call, args };
+        let info = UnitFn::Offset {
+            id: self.new_function_id(offset, stack),
+            call,
+            args,
+        };
         let signature = DebugSignature::new(path, debug_args);
 
         if self.functions.insert(instance_fn, info.clone()).is_some() {
@@ -780,7 +935,12 @@ impl UnitBuilder {
     ) -> Result<(), UnitBuilderError> {
         self.label_count = assembly.label_count;
 
-        self.required_functions.extend(assembly.required_functions);
+        for (hash, calls) in assembly.required_functions {
+            self.required_functions
+                .entry(hash)
+                .or_default()
+                .extend(calls);
+        }
 
         for (pos, (inst, span)) in assembly.instructions.into_iter().enumerate() {
             let mut comment = None;
@@ -808,6 +968,16 @@ impl UnitBuilder {
                     self.instructions
                         .push(Inst::JumpIfBranch { branch, offset });
                 }
+                AssemblyInst::PeekAndJumpIf { label } => {
+                    comment = Some(format!("label:{}", label));
+                    let offset = translate_offset(pos, label, &assembly.labels)?;
+                    self.instructions.push(Inst::PeekAndJumpIf { offset });
+                }
+                AssemblyInst::PeekAndJumpIfNot { label } => {
+                    comment = Some(format!("label:{}", label));
+                    let offset = translate_offset(pos, label, &assembly.labels)?;
+                    self.instructions.push(Inst::PeekAndJumpIfNot { offset });
+                }
                 AssemblyInst::PopAndJumpIfNot { count, label } => {
                     comment = Some(format!("label:{}", label));
                     let offset = translate_offset(pos, label, &assembly.labels)?;
@@ -867,17 +1037,76 @@ impl UnitBuilder {
     ///
     /// This can prevent a number of runtime errors, like missing functions.
     pub(crate) fn link(&self, context: &Context, errors: &mut LinkerErrors) -> bool {
-        for (hash, spans) in &self.required_functions {
+        for (hash, calls) in &self.required_functions {
             if self.functions.get(hash).is_none() && context.lookup(*hash).is_none() {
                 errors.errors.push(LinkerError::MissingFunction {
                     hash: *hash,
-                    spans: spans.clone(),
+                    spans: calls.iter().map(|c| (c.0, c.1)).collect(),
                 });
             }
         }
 
         errors.errors.is_empty()
     }
+
+    /// Check the unit against the host API provided by the context, for a
+    /// unit which targets the given version of it.
+    ///
+    /// In addition to the checks performed when linking, calls to native
+    /// functions are checked to have the number of arguments they expect.
+    pub(crate) fn check_compatibility(
+        &self,
+        context: &Context,
+        target: ApiVersion,
+    ) -> CompatibilityReport {
+        let mut missing = Vec::new();
+        let mut arity_mismatches = Vec::new();
+
+        for (hash, calls) in &self.required_functions {
+            if self.functions.get(hash).is_some() {
+                continue;
+            }
+
+            if context.lookup(*hash).is_none() {
+                missing.push(MissingFunction {
+                    hash: *hash,
+                    spans: calls.iter().map(|c| (c.0, c.1)).collect(),
+                });
+
+                continue;
+            }
+
+            let (function, expected) = match context.lookup_signature(*hash) {
+                Some(ContextSignature::Function {
+                    path,
+                    args: Some(args),
+                }) => (path.to_string(), *args),
+                _ => continue,
+            };
+
+            for (span, source_id, args) in calls {
+                if *args != expected {
+                    arity_mismatches.push(ArityMismatch {
+                        function: function.clone(),
+                        expected,
+                        actual: *args,
+                        span: *span,
+                        source_id: *source_id,
+                    });
+                }
+            }
+        }
+
+        missing.sort_by_key(|m| m.spans.first().map(|s| (s.1, s.0.start)));
+        arity_mismatches.sort_by_key(|m| (m.source_id, m.span.start));
+
+        CompatibilityReport {
+            target,
+            host: context.api_version(),
+            missing,
+            arity_mismatches,
+        }
+    }
 }
 
 /// An error raised during linking.
@@ -1,7 +1,8 @@
-use runestick::Span;
+use crate::{Diagnostic, DiagnosticLabel, Diagnostics, Sources};
+use runestick::{Item, Span};
 
 /// Compilation warning.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Warning {
     /// The id of the source where the id happened.
     pub source_id: usize,
@@ -10,7 +11,7 @@ pub struct Warning {
 }
 
 /// Compilation warning kind.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum WarningKind {
     /// Item identified by the span is not used.
     NotUsed {
@@ -48,7 +49,256 @@ pub enum WarningKind {
         /// Span where the semi-colon is.
         span: Span,
     },
+    /// A match arm can never be reached, because the values it matches are
+    /// already covered by earlier arms.
+    UnreachableMatchArm {
+        /// The span of the unreachable pattern.
+        span: Span,
+        /// The context in which it is used.
+        context: Option<Span>,
+    },
+    /// Code which can never be reached, because it follows an expression
+    /// which unconditionally diverges, like `return` or `break`.
+    UnreachableCode {
+        /// The span of the unreachable code.
+        span: Span,
+        /// The span of the expression causing the code to be unreachable.
+        cause: Span,
+        /// The context in which it is used.
+        context: Option<Span>,
+    },
+    /// The value of a call to a function which never produces a value is
+    /// used.
+    UnitFunctionUsedAsValue {
+        /// The span of the call.
+        span: Span,
+        /// The function being called.
+        item: Item,
+        /// The context in which it is used.
+        context: Option<Span>,
+    },
+    /// A loop which obviously never exits, since nothing in its body breaks
+    /// out of it or returns.
+    InfiniteLoop {
+        /// The span of the loop.
+        span: Span,
+        /// The context in which it is used.
+        context: Option<Span>,
+    },
+    /// A function unconditionally calls itself, so the recursion can never
+    /// terminate.
+    UnguardedRecursion {
+        /// The span of the recursive call.
+        span: Span,
+        /// The context in which it is used.
+        context: Option<Span>,
+    },
+    /// A value is used to break out of a loop whose value isn't used.
+    BreakValueNotUsed {
+        /// The span of the break value.
+        span: Span,
+        /// The context in which it is used.
+        context: Option<Span>,
+    },
+    /// A match over an enum doesn't cover all of its variants.
+    MatchMissingVariants {
+        /// The span of the match.
+        span: Span,
+        /// The variants which are not covered.
+        missing: Vec<Item>,
+        /// The context in which it is used.
+        context: Option<Span>,
+    },
+}
+
+impl Warning {
+    /// The stable code of the warning, like `W0001`.
+    pub fn code(&self) -> &'static str {
+        match self.kind {
+            WarningKind::NotUsed { .. } => "W0001",
+            WarningKind::LetPatternMightPanic { .. } => "W0002",
+            WarningKind::TemplateWithoutExpansions { .. } => "W0003",
+            WarningKind::RemoveTupleCallParams { .. } => "W0004",
+            WarningKind::UnecessarySemiColon { .. } => "W0005",
+            WarningKind::UnreachableMatchArm { .. } => "W0006",
+            WarningKind::UnreachableCode { .. } => "W0007",
+            WarningKind::UnitFunctionUsedAsValue { .. } => "W0008",
+            WarningKind::InfiniteLoop { .. } => "W0009",
+            WarningKind::UnguardedRecursion { .. } => "W0010",
+            WarningKind::MatchMissingVariants { .. } => "W0011",
+            WarningKind::BreakValueNotUsed { .. } => "W0012",
+        }
+    }
+
+    /// Convert the warning into a diagnostic.
+    ///
+    /// The sources are used to suggest rewrites of the offending code, and
+    /// the suggestions are left out if the source is missing.
+    pub fn to_diagnostic(&self, sources: &Sources) -> Diagnostic {
+        let source_id = self.source_id;
+        let text = |span: Span| sources.source_at(source_id).and_then(|s| s.source(span));
+
+        let (diagnostic, context) = match &self.kind {
+            WarningKind::NotUsed { span, context } => (
+                Diagnostic::warning(self.code(), "value not used")
+                    .with_label(DiagnosticLabel::primary(source_id, *span, "value not used")),
+                *context,
+            ),
+            WarningKind::LetPatternMightPanic { span, context } => {
+                let mut diagnostic = Diagnostic::warning(self.code(), "let binding might panic")
+                    .with_label(DiagnosticLabel::primary(
+                        source_id,
+                        *span,
+                        "let binding might panic",
+                    ));
+
+                if let Some(binding) = text(*span) {
+                    diagnostic = diagnostic.with_note(format!(
+                        "Consider rewriting to:\nif {} {{\n    // ..\n}}\n",
+                        binding
+                    ));
+                }
+
+                (diagnostic, *context)
+            }
+            WarningKind::TemplateWithoutExpansions { span, context } => (
+                Diagnostic::warning(self.code(), "template string without expansions").with_label(
+                    DiagnosticLabel::primary(
+                        source_id,
+                        *span,
+                        "template string without expansions like `{1 + 2}`",
+                    ),
+                ),
+                *context,
+            ),
+            WarningKind::RemoveTupleCallParams {
+                span,
+                variant,
+                context,
+            } => {
+                let mut diagnostic =
+                    Diagnostic::warning(self.code(), "unnecessary parentheses").with_label(
+                        DiagnosticLabel::secondary(
+                            source_id,
+                            *span,
+                            "constructing this variant could be done without parentheses",
+                        ),
+                    );
+
+                if let Some(variant) = text(*variant) {
+                    diagnostic =
+                        diagnostic.with_note(format!("Consider rewriting to `{}`", variant));
+                }
+
+                (diagnostic, *context)
+            }
+            WarningKind::UnecessarySemiColon { span } => (
+                Diagnostic::warning(self.code(), "unnecessary semicolon").with_label(
+                    DiagnosticLabel::primary(source_id, *span, "unnecessary semicolon"),
+                ),
+                None,
+            ),
+            WarningKind::UnreachableMatchArm { span, context } => (
+                Diagnostic::warning(self.code(), "unreachable match arm").with_label(
+                    DiagnosticLabel::primary(source_id, *span, "unreachable pattern"),
+                ),
+                *context,
+            ),
+            WarningKind::UnreachableCode {
+                span,
+                cause,
+                context,
+            } => (
+                Diagnostic::warning(self.code(), "unreachable code")
+                    .with_label(DiagnosticLabel::primary(source_id, *span, "unreachable code"))
+                    .with_label(DiagnosticLabel::secondary(
+                        source_id,
+                        *cause,
+                        "any code following this expression is unreachable",
+                    )),
+                *context,
+            ),
+            WarningKind::UnitFunctionUsedAsValue {
+                span,
+                item,
+                context,
+            } => (
+                Diagnostic::warning(self.code(), "function without a value used as a value")
+                    .with_label(DiagnosticLabel::primary(
+                        source_id,
+                        *span,
+                        "this call always evaluates to `()`",
+                    ))
+                    .with_note(format!(
+                        "The function `{}` doesn't have a trailing expression or return a value",
+                        item
+                    )),
+                *context,
+            ),
+            WarningKind::InfiniteLoop { span, context } => (
+                Diagnostic::warning(self.code(), "infinite loop")
+                    .with_label(DiagnosticLabel::primary(
+                        source_id,
+                        *span,
+                        "this loop never exits",
+                    ))
+                    .with_note("Nothing in the loop breaks out of it or returns"),
+                *context,
+            ),
+            WarningKind::UnguardedRecursion { span, context } => (
+                Diagnostic::warning(self.code(), "unguarded recursion")
+                    .with_label(DiagnosticLabel::primary(
+                        source_id,
+                        *span,
+                        "function unconditionally calls itself",
+                    ))
+                    .with_note("The recursion can never terminate, since nothing before this call returns or branches"),
+                *context,
+            ),
+            WarningKind::BreakValueNotUsed { span, context } => (
+                Diagnostic::warning(self.code(), "break value not used")
+                    .with_label(DiagnosticLabel::primary(
+                        source_id,
+                        *span,
+                        "the value of the loop is not used",
+                    )),
+                *context,
+            ),
+            WarningKind::MatchMissingVariants {
+                span,
+                missing,
+                context,
+            } => {
+                let mut note = String::from("Missing variants:");
+
+                for item in missing {
+                    note.push_str(&format!(" `{}`", item));
+                }
+
+                (
+                    Diagnostic::warning(self.code(), "non-exhaustive match")
+                        .with_label(DiagnosticLabel::primary(
+                            source_id,
+                            *span,
+                            "match does not cover all variants",
+                        ))
+                        .with_note(note),
+                    *context,
+                )
+            }
+        };
+
+        match context {
+            Some(context) => diagnostic.with_label(DiagnosticLabel::secondary(
+                source_id,
+                context,
+                "in this context",
+            )),
+            None => diagnostic,
+        }
+    }
 }
+
 /// Compilation warnings.
 #[derive(Debug, Clone, Default)]
 pub struct Warnings {
@@ -104,6 +354,13 @@ impl Warnings {
         self.into_iter()
     }
 
+    /// Take an immutable snapshot of the warnings as diagnostics.
+    ///
+    /// See [Warning::to_diagnostic] for how the sources are used.
+    pub fn diagnostics(&self, sources: &Sources) -> Diagnostics {
+        self.iter().map(|w| w.to_diagnostic(sources)).collect()
+    }
+
     /// Indicate that a value is produced but never used.
     pub fn not_used(&mut self, source_id: usize, span: Span, context: Option<Span>) {
         if let Some(w) = &mut self.warnings {
@@ -167,6 +424,117 @@ impl Warnings {
         }
     }
 
+    /// Indicate that a match arm can never be reached.
+    pub fn unreachable_match_arm(&mut self, source_id: usize, span: Span, context: Option<Span>) {
+        if let Some(w) = &mut self.warnings {
+            w.push(Warning {
+                source_id,
+                kind: WarningKind::UnreachableMatchArm { span, context },
+            });
+        }
+    }
+
+    /// Indicate that the given code can never be reached, because of the
+    /// diverging expression at `cause`.
+    pub fn unreachable_code(
+        &mut self,
+        source_id: usize,
+        span: Span,
+        cause: Span,
+        context: Option<Span>,
+    ) {
+        if let Some(w) = &mut self.warnings {
+            w.push(Warning {
+                source_id,
+                kind: WarningKind::UnreachableCode {
+                    span,
+                    cause,
+                    context,
+                },
+            });
+        }
+    }
+
+    /// Indicate that the value of a call to a function which never produces
+    /// a value is used.
+    ///
+    /// Like `let n = f();` where `fn f() { g(); }`.
+    pub fn unit_function_used_as_value(
+        &mut self,
+        source_id: usize,
+        span: Span,
+        item: Item,
+        context: Option<Span>,
+    ) {
+        if let Some(w) = &mut self.warnings {
+            w.push(Warning {
+                source_id,
+                kind: WarningKind::UnitFunctionUsedAsValue {
+                    span,
+                    item,
+                    context,
+                },
+            });
+        }
+    }
+
+    /// Indicate that a loop never exits.
+    ///
+    /// Like `loop { n += 1; }`.
+    pub fn infinite_loop(&mut self, source_id: usize, span: Span, context: Option<Span>) {
+        if let Some(w) = &mut self.warnings {
+            w.push(Warning {
+                source_id,
+                kind: WarningKind::InfiniteLoop { span, context },
+            });
+        }
+    }
+
+    /// Indicate that a function unconditionally calls itself.
+    ///
+    /// Like `fn f(n) { f(n - 1) }`.
+    pub fn unguarded_recursion(&mut self, source_id: usize, span: Span, context: Option<Span>) {
+        if let Some(w) = &mut self.warnings {
+            w.push(Warning {
+                source_id,
+                kind: WarningKind::UnguardedRecursion { span, context },
+            });
+        }
+    }
+
+    /// Indicate that a loop is broken out of with a value, but the value of
+    /// the loop isn't used.
+    ///
+    /// Like `loop { break 42; }` used as a statement.
+    pub fn break_value_not_used(&mut self, source_id: usize, span: Span, context: Option<Span>) {
+        if let Some(w) = &mut self.warnings {
+            w.push(Warning {
+                source_id,
+                kind: WarningKind::BreakValueNotUsed { span, context },
+            });
+        }
+    }
+
+    /// Indicate that a match over an enum doesn't cover the given variants.
+    pub fn match_missing_variants(
+        &mut self,
+        source_id: usize,
+        span: Span,
+        missing: Vec<Item>,
+        context: Option<Span>,
+    ) {
+        if let Some(w) = &mut self.warnings {
+            w.push(Warning {
+                source_id,
+                kind: WarningKind::MatchMissingVariants {
+                    span,
+                    missing,
+                    context,
+                },
+            });
+        }
+    }
+
     /// Add a warning about an unecessary semi-colon.
     pub fn uneccessary_semi_colon(&mut self, source_id: usize, span: Span) {
         if let Some(w) = &mut self.warnings {
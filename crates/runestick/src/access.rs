@@ -47,32 +47,125 @@ pub enum AccessError {
 /// Error raised when tried to access for shared access but it was not
 /// accessible.
 #[derive(Debug, Error)]
-#[error("cannot read, value is {0}")]
+#[error("cannot read `{}`, value is {}", short_type_name(.0.type_name), .0)]
 pub struct NotAccessibleRef(Snapshot);
 
+impl NotAccessibleRef {
+    /// The snapshot of the access at the time of the error.
+    pub fn snapshot(&self) -> &Snapshot {
+        &self.0
+    }
+}
+
 /// Error raised when tried to access for exclusive access but it was not
 /// accessible.
 #[derive(Debug, Error)]
-#[error("cannot write, value is {0}")]
+#[error("cannot write `{}`, value is {}", short_type_name(.0.type_name), .0)]
 pub struct NotAccessibleMut(Snapshot);
 
+impl NotAccessibleMut {
+    /// The snapshot of the access at the time of the error.
+    pub fn snapshot(&self) -> &Snapshot {
+        &self.0
+    }
+}
+
 /// Error raised when tried to access the guarded data for taking.
 ///
 /// This requires exclusive access, but it's a scenario we structure separately
 /// for diagnostics purposes.
 #[derive(Debug, Error)]
-#[error("cannot take, value is {0}")]
+#[error("cannot take `{}`, value is {}", short_type_name(.0.type_name), .0)]
 pub struct NotAccessibleTake(Snapshot);
 
+impl NotAccessibleTake {
+    /// The snapshot of the access at the time of the error.
+    pub fn snapshot(&self) -> &Snapshot {
+        &self.0
+    }
+}
+
+impl AccessError {
+    /// Get the snapshot of the access at the time of the error, unless the
+    /// error is about an unexpected type.
+    pub fn snapshot(&self) -> Option<&Snapshot> {
+        match self {
+            Self::UnexpectedType { .. } => None,
+            Self::NotAccessibleRef { error } => Some(error.snapshot()),
+            Self::NotAccessibleMut { error } => Some(error.snapshot()),
+            Self::NotAccessibleTake { error } => Some(error.snapshot()),
+        }
+    }
+
+    /// A hint on how to resolve the error.
+    pub fn hint(&self) -> Option<&'static str> {
+        let snapshot = self.snapshot()?;
+
+        Some(match snapshot.state {
+            TAKEN => "the value has been moved, like into a function taking ownership of it, and can no longer be used",
+            1 => "the value is being modified, like by the function being called or an assignment to it, so it can't be accessed until that's done",
+            n if n < 0 => "the value is being read, like by an iterator over it or a function reading it, so it can't be modified until that's done; consider collecting or cloning it first",
+            _ => return None,
+        })
+    }
+}
+
+/// Where a value was borrowed, which is tracked while a virtual machine with
+/// debug info is running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BorrowSite {
+    /// The address of the unit which was running, used to identify it.
+    pub unit: usize,
+    /// The instruction pointer in the unit.
+    pub ip: usize,
+}
+
+thread_local! {
+    /// The site of the instruction currently being executed.
+    static BORROW_SITE: Cell<Option<BorrowSite>> = Cell::new(None);
+}
+
+/// Set the site to use for borrows performed from now on, returning the
+/// previous site.
+pub(crate) fn set_borrow_site(site: Option<BorrowSite>) -> Option<BorrowSite> {
+    BORROW_SITE.with(|s| s.replace(site))
+}
+
+/// Get the site of the instruction currently being executed, if tracked.
+#[cfg(feature = "census")]
+pub(crate) fn current_site() -> Option<BorrowSite> {
+    BORROW_SITE.with(Cell::get)
+}
+
 /// Snapshot that can be used to indicate how the value was being accessed at
 /// the time of an error.
 #[derive(Debug)]
-#[repr(transparent)]
-pub struct Snapshot(isize);
+pub struct Snapshot {
+    state: isize,
+    type_name: &'static str,
+    borrowed_at: Option<BorrowSite>,
+}
+
+impl Snapshot {
+    /// The name of the type of the value which was accessed.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Test if the value had been moved.
+    pub fn is_moved(&self) -> bool {
+        self.state == TAKEN
+    }
+
+    /// Where the value was borrowed or moved, if it was tracked.
+    pub fn borrowed_at(&self) -> Option<BorrowSite> {
+        self.borrowed_at
+    }
+}
 
 impl fmt::Display for Snapshot {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.0 {
+        match self.state {
             0 => write!(f, "fully accessible"),
             1 => write!(f, "exclusively accessed"),
             TAKEN => write!(f, "moved"),
@@ -82,58 +175,98 @@ impl fmt::Display for Snapshot {
     }
 }
 
+/// Strip the module paths from a type name, like `alloc::vec::Vec<T>` to
+/// `Vec<T>`.
+fn short_type_name(name: &str) -> String {
+    let mut out = String::new();
+    let mut segment = String::new();
+    let mut chars = name.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ':' if chars.peek() == Some(&':') => {
+                chars.next();
+                segment.clear();
+            }
+            c if c.is_alphanumeric() || c == '_' => segment.push(c),
+            c => {
+                out.push_str(&segment);
+                segment.clear();
+                out.push(c);
+            }
+        }
+    }
+
+    out.push_str(&segment);
+    out
+}
+
 #[derive(Clone)]
-pub(crate) struct Access(Cell<isize>);
+pub(crate) struct Access {
+    state: Cell<isize>,
+    /// Where the value was most recently borrowed from being fully
+    /// accessible.
+    borrowed_at: Cell<Option<BorrowSite>>,
+}
 
 impl Access {
     /// Construct a new default access.
     pub(crate) const fn new() -> Self {
-        Self(Cell::new(0))
+        Self {
+            state: Cell::new(0),
+            borrowed_at: Cell::new(None),
+        }
     }
 
     /// Test if we have shared access without modifying the internal count.
     #[inline]
     pub(crate) fn is_shared(&self) -> bool {
-        self.0.get().wrapping_sub(1) < 0
+        self.state.get().wrapping_sub(1) < 0
     }
 
     /// Test if we have exclusive access without modifying the internal count.
     #[inline]
     pub(crate) fn is_exclusive(&self) -> bool {
-        self.0.get() == 0
+        self.state.get() == 0
     }
 
     /// Test if the data has been taken.
     #[inline]
     pub(crate) fn is_taken(&self) -> bool {
-        self.0.get() == isize::max_value()
+        self.state.get() == isize::max_value()
     }
 
     /// Mark that we want shared access to the given access token.
     #[inline]
-    pub(crate) fn shared(&self) -> Result<RawBorrowedRef, NotAccessibleRef> {
-        let state = self.0.get();
+    pub(crate) fn shared(
+        &self,
+        type_name: &'static str,
+    ) -> Result<RawBorrowedRef, NotAccessibleRef> {
+        let state = self.state.get();
         let n = state.wrapping_sub(1);
 
         if n >= 0 {
-            return Err(NotAccessibleRef(Snapshot(state)));
+            return Err(NotAccessibleRef(self.snapshot(state, type_name)));
         }
 
-        self.0.set(n);
+        self.mark(state, n);
         Ok(RawBorrowedRef { access: self })
     }
 
     /// Mark that we want exclusive access to the given access token.
     #[inline]
-    pub(crate) fn exclusive(&self) -> Result<RawBorrowedMut, NotAccessibleMut> {
-        let state = self.0.get();
+    pub(crate) fn exclusive(
+        &self,
+        type_name: &'static str,
+    ) -> Result<RawBorrowedMut, NotAccessibleMut> {
+        let state = self.state.get();
         let n = state.wrapping_add(1);
 
         if n != 1 {
-            return Err(NotAccessibleMut(Snapshot(state)));
+            return Err(NotAccessibleMut(self.snapshot(state, type_name)));
         }
 
-        self.0.set(n);
+        self.mark(state, n);
         Ok(RawBorrowedMut { access: self })
     }
 
@@ -141,45 +274,65 @@ impl Access {
     ///
     /// I.e. whatever guarded data is no longer available.
     #[inline]
-    pub(crate) fn take(&self) -> Result<RawTakeGuard, NotAccessibleTake> {
-        let state = self.0.get();
+    pub(crate) fn take(&self, type_name: &'static str) -> Result<RawTakeGuard, NotAccessibleTake> {
+        let state = self.state.get();
 
         if state != 0 {
-            return Err(NotAccessibleTake(Snapshot(state)));
+            return Err(NotAccessibleTake(self.snapshot(state, type_name)));
         }
 
-        self.0.set(isize::max_value());
+        self.mark(state, isize::max_value());
         Ok(RawTakeGuard { access: self })
     }
 
+    /// Update the state, recording where the value was borrowed if it was
+    /// previously fully accessible.
+    #[inline]
+    fn mark(&self, state: isize, n: isize) {
+        if state == 0 {
+            self.borrowed_at.set(BORROW_SITE.with(Cell::get));
+        }
+
+        self.state.set(n);
+    }
+
+    /// Construct a snapshot of the current access.
+    fn snapshot(&self, state: isize, type_name: &'static str) -> Snapshot {
+        Snapshot {
+            state,
+            type_name,
+            borrowed_at: self.borrowed_at.get(),
+        }
+    }
+
     /// Unshare the current access.
     #[inline]
     fn release_shared(&self) {
-        let b = self.0.get().wrapping_add(1);
+        let b = self.state.get().wrapping_add(1);
         debug_assert!(b <= 0);
-        self.0.set(b);
+        self.state.set(b);
     }
 
     /// Unshare the current access.
     #[inline]
     fn release_exclusive(&self) {
-        let b = self.0.get().wrapping_sub(1);
+        let b = self.state.get().wrapping_sub(1);
         debug_assert!(b == 0);
-        self.0.set(b);
+        self.state.set(b);
     }
 
     /// Unshare the current access.
     #[inline]
     fn release_take(&self) {
-        let b = self.0.get();
+        let b = self.state.get();
         debug_assert!(b == isize::max_value());
-        self.0.set(0);
+        self.state.set(0);
     }
 }
 
 impl fmt::Debug for Access {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", Snapshot(self.0.get()))
+        write!(f, "{}", self.snapshot(self.state.get(), ""))
     }
 }
 
@@ -1,5 +1,6 @@
 //! Helper types for a holder of data.
 
+use crate::finalizer::Installed;
 use crate::Hash;
 use std::any;
 use std::fmt;
@@ -29,13 +30,21 @@ impl Any {
     {
         let data = Box::into_raw(Box::new(data));
 
-        Self {
-            vtable: &AnyVtable {
+        // NB: values constructed while a virtual machine is running use the
+        // vtable with the finalizer installed for their type, if any.
+        let vtable = match crate::finalizer::vtable(Hash::from_any::<T>) {
+            Some(vtable) => vtable,
+            None => &AnyVtable {
                 drop: drop_impl::<T>,
                 as_ptr: as_ptr_impl::<T>,
                 type_name: any::type_name::<T>,
                 type_hash: Hash::from_any::<T>,
+                finalizer: None,
             },
+        };
+
+        Self {
+            vtable,
             data: data as *mut (),
         }
     }
@@ -156,9 +165,13 @@ impl Any {
 impl Drop for Any {
     fn drop(&mut self) {
         // Safety: The safety of the called implementation is guaranteed at
-        // compile time.
+        // compile time. Finalizers are only set in vtables constructed for the
+        // stored type by `Any::new`, which owns a boxed value.
         unsafe {
-            (self.vtable.drop)(self.data);
+            match self.vtable.finalizer {
+                Some(finalizer) => finalizer.finalize(self.data),
+                None => (self.vtable.drop)(self.data),
+            }
         }
     }
 }
@@ -190,10 +203,27 @@ pub struct AnyVtable {
     type_name: TypeNameFn,
     /// Get the type hash of the stored type.
     type_hash: TypeHashFn,
+    /// The finalizer which takes ownership of the stored value when it's
+    /// dropped, if any.
+    finalizer: Option<&'static Installed>,
+}
+
+/// Construct the vtable of a type with an installed finalizer.
+pub(crate) fn finalizer_vtable<T>(finalizer: &'static Installed) -> AnyVtable
+where
+    T: any::Any,
+{
+    AnyVtable {
+        drop: drop_impl::<T>,
+        as_ptr: as_ptr_impl::<T>,
+        type_name: any::type_name::<T>,
+        type_hash: Hash::from_any::<T>,
+        finalizer: Some(finalizer),
+    }
 }
 
 unsafe fn drop_impl<T>(this: *const ()) {
-    Box::from_raw(this as *mut () as *mut T);
+    drop(Box::from_raw(this as *mut () as *mut T));
 }
 
 fn as_ptr_impl<T>(this: *const (), expected: Hash) -> Option<*const ()>
@@ -0,0 +1,80 @@
+use std::fmt;
+use std::str;
+
+/// The version of the host API, which is the set of native functions a
+/// [Context][crate::Context] provides to scripts.
+///
+/// Versions are compared like semantic versions: a host providing version
+/// `1.3` is compatible with scripts targeting `1.0` through `1.3`, but not
+/// with scripts targeting `1.4` or `2.0`.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::ApiVersion;
+///
+/// let host = ApiVersion::new(1, 3);
+///
+/// assert_eq!("1.2".parse::<ApiVersion>(), Ok(ApiVersion::new(1, 2)));
+/// assert!(host.supports(ApiVersion::new(1, 2)));
+/// assert!(!host.supports(ApiVersion::new(1, 4)));
+/// assert!(!host.supports(ApiVersion::new(2, 0)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ApiVersion {
+    /// The major version, which is bumped on incompatible changes.
+    pub major: u32,
+    /// The minor version, which is bumped when functions are added.
+    pub minor: u32,
+}
+
+impl ApiVersion {
+    /// Construct a new API version.
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+
+    /// Test if a host providing this version supports scripts targeting the
+    /// given version.
+    pub fn supports(self, target: Self) -> bool {
+        self.major == target.major && self.minor >= target.minor
+    }
+}
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl str::FromStr for ApiVersion {
+    type Err = ParseApiVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut it = s.splitn(2, '.');
+
+        let major = it
+            .next()
+            .and_then(|n| n.parse().ok())
+            .ok_or(ParseApiVersionError)?;
+
+        let minor = match it.next() {
+            Some(n) => n.parse().map_err(|_| ParseApiVersionError)?,
+            None => 0,
+        };
+
+        Ok(Self { major, minor })
+    }
+}
+
+/// Error raised when parsing a malformed [ApiVersion].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseApiVersionError;
+
+impl fmt::Display for ParseApiVersionError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "expected a version like `1.2`")
+    }
+}
+
+impl std::error::Error for ParseApiVersionError {}
@@ -1,3 +1,6 @@
+use crate::{FromValue, Stack, VmError, VmErrorKind};
+use std::any::type_name;
+
 /// Trait for converting arguments into values unsafely.
 ///
 /// This has the ability to encode references.
@@ -18,6 +21,35 @@ pub trait Args {
     fn count() -> usize;
 }
 
+/// Trait for extracting a fixed number of typed arguments from the stack.
+///
+/// This is implemented for tuples of types implementing [FromValue], and is
+/// used through [Stack::take_args] when writing raw functions.
+pub trait FromArgs: Sized {
+    /// Pop and convert the given number of arguments from the stack.
+    fn from_args(stack: &mut Stack, args: usize) -> Result<Self, VmError>;
+}
+
+/// Construct an error for an argument which failed to convert, keeping type
+/// information from the underlying error where it's available.
+fn bad_argument<T>(arg: usize, error: VmError) -> Result<VmError, VmError> {
+    let error = error.unpack_critical()?;
+
+    if let VmErrorKind::Expected { expected, actual } = error.kind() {
+        return Ok(VmError::from(VmErrorKind::BadArgumentType {
+            arg,
+            expected: *expected,
+            actual: *actual,
+        }));
+    }
+
+    Ok(VmError::from(VmErrorKind::BadArgument {
+        error,
+        arg,
+        to: type_name::<T>(),
+    }))
+}
+
 macro_rules! impl_into_args {
     () => {
         impl_into_args!{@impl 0,}
@@ -51,6 +83,34 @@ macro_rules! impl_into_args {
                 $count
             }
         }
+
+        impl<$($ty,)*> FromArgs for ($($ty,)*)
+        where
+            $($ty: FromValue,)*
+        {
+            fn from_args(stack: &mut Stack, args: usize) -> Result<Self, VmError> {
+                if args != $count {
+                    return Err(VmError::from(VmErrorKind::BadArgumentCount {
+                        actual: args,
+                        expected: $count,
+                    }));
+                }
+
+                #[allow(unused_mut, unused_variables)]
+                let mut it = stack.drain_stack_top($count)?;
+                $(let $value = it.next().unwrap();)*
+                drop(it);
+
+                $(
+                    let $value = match <$ty>::from_value($value) {
+                        Ok(value) => value,
+                        Err(e) => return Err(bad_argument::<$ty>($count - $ignore_count, e)?),
+                    };
+                )*
+
+                Ok(($($value,)*))
+            }
+        }
     };
 }
 
@@ -11,7 +11,10 @@ pub enum Awaited {
 
 impl Awaited {
     /// Wait for the given awaited into the specified virtual machine.
-    pub(crate) async fn into_vm(self, vm: &mut Vm) -> Result<(), VmError> {
+    ///
+    /// If the returned future is dropped before it completes, waiting can be
+    /// resumed by calling this again.
+    pub(crate) async fn wait_into_vm(&mut self, vm: &mut Vm) -> Result<(), VmError> {
         match self {
             Self::Future(future) => {
                 let value = future.borrow_mut()?.await?;
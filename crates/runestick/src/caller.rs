@@ -0,0 +1,164 @@
+//! Information about the script function calling into a native handler.
+
+use crate::debug::DebugSignature;
+use crate::{Context, DebugInst, Hash, Item, Span, Unit, Value, VmError};
+use std::any::TypeId;
+use std::cell::{Cell, RefCell};
+use std::sync::Arc;
+
+thread_local! {
+    static CALLER: RefCell<Option<Caller>> = const { RefCell::new(None) };
+    /// If the context of the current caller has coercion hooks, so that
+    /// arguments can skip looking them up otherwise.
+    static COERCIONS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Information about the location in a unit which is currently calling into a
+/// native function.
+///
+/// This is made available to native handlers through [Caller::current], which
+/// allows host functions to attribute work, logging, or permissions to the
+/// specific call site in a script.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Caller, Module};
+///
+/// # fn main() -> runestick::Result<()> {
+/// let mut module = Module::new(&["log"]);
+///
+/// module.function(&["info"], |message: &str| {
+///     match Caller::current().and_then(|caller| caller.item().cloned()) {
+///         Some(item) => println!("{}: {}", item, message),
+///         None => println!("{}", message),
+///     }
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Caller {
+    /// The context of the virtual machine performing the call.
+    context: Arc<Context>,
+    /// The unit of the caller.
+    unit: Arc<Unit>,
+    /// The instruction pointer of the call instruction.
+    ip: usize,
+}
+
+impl Caller {
+    /// Get information about the caller of the native function currently
+    /// being executed.
+    ///
+    /// Returns `None` if we're not called from within a virtual machine.
+    ///
+    /// Note that for async functions this is only available while the future
+    /// is being constructed, not while it is being polled.
+    pub fn current() -> Option<Caller> {
+        CALLER.with(|caller| caller.borrow().clone())
+    }
+
+    /// Access the context of the virtual machine performing the call.
+    pub fn context(&self) -> &Arc<Context> {
+        &self.context
+    }
+
+    /// Access the unit of the caller.
+    pub fn unit(&self) -> &Arc<Unit> {
+        &self.unit
+    }
+
+    /// The instruction pointer of the instruction performing the call.
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    /// Debug information for the calling instruction, if available.
+    pub fn debug_inst(&self) -> Option<&DebugInst> {
+        self.unit.debug_info()?.instruction_at(self.ip)
+    }
+
+    /// The source id of the call site, if debug information is available.
+    pub fn source_id(&self) -> Option<usize> {
+        Some(self.debug_inst()?.source_id)
+    }
+
+    /// The span of the call site, if debug information is available.
+    pub fn span(&self) -> Option<Span> {
+        Some(self.debug_inst()?.span)
+    }
+
+    /// The hash and signature of the script function performing the call, if
+    /// debug information is available.
+    pub fn function(&self) -> Option<(Hash, &DebugSignature)> {
+        self.unit.debug_info()?.function_enclosing(self.ip)
+    }
+
+    /// The item of the script function performing the call, if debug
+    /// information is available.
+    pub fn item(&self) -> Option<&Item> {
+        let (_, signature) = self.function()?;
+        Some(&signature.path)
+    }
+}
+
+/// Run the given closure with the specified caller installed, restoring the
+/// previous caller once it returns.
+pub(crate) fn with_caller<F, T>(context: &Arc<Context>, unit: &Arc<Unit>, ip: usize, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let caller = Caller {
+        context: context.clone(),
+        unit: unit.clone(),
+        ip,
+    };
+
+    let coercions = COERCIONS.with(|current| current.replace(context.has_coercions()));
+
+    let _guard = CallerGuard {
+        previous: CALLER.with(|current| current.replace(Some(caller))),
+        coercions,
+    };
+
+    f()
+}
+
+/// Apply the coercion hook registered for the native type with the given type
+/// id in the context of the current caller.
+///
+/// The value is returned unchanged if there is no caller, or if no hook is
+/// registered for the type.
+pub(crate) fn coerce(value: Value, to: TypeId) -> Result<Value, VmError> {
+    if !COERCIONS.with(Cell::get) {
+        return Ok(value);
+    }
+
+    // NB: the hook is cloned out so that the caller isn't borrowed while it
+    // runs, since it might call back into the virtual machine.
+    let hook = CALLER.with(|caller| {
+        let caller = caller.borrow();
+        caller.as_ref()?.context.lookup_coercion(to).cloned()
+    });
+
+    match hook {
+        Some(hook) => hook(value),
+        None => Ok(value),
+    }
+}
+
+/// Guard which restores the previous caller when dropped, even if the handler
+/// panics.
+struct CallerGuard {
+    previous: Option<Caller>,
+    coercions: bool,
+}
+
+impl Drop for CallerGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CALLER.with(|current| *current.borrow_mut() = previous);
+        COERCIONS.with(|current| current.set(self.coercions));
+    }
+}
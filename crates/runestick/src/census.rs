@@ -0,0 +1,206 @@
+//! A census of the shared values which are alive on the current thread.
+
+use crate::access::BorrowSite;
+use crate::collections::HashMap;
+use crate::{
+    Any, Bytes, Function, Future, Generator, GeneratorState, Object, Shared, Stream, Tuple,
+    TupleVariant, TypeNames, TypedObject, TypedTuple, Value, VariantObject,
+};
+use std::any::{self, TypeId};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+
+thread_local! {
+    /// The shared values which are alive, keyed by the address of their box.
+    static LIVE: RefCell<HashMap<*const (), Live>> = RefCell::new(HashMap::new());
+}
+
+/// A shared value which is alive.
+#[derive(Clone, Copy)]
+struct Live {
+    type_id: TypeId,
+    type_name: fn() -> &'static str,
+    site: Option<BorrowSite>,
+}
+
+/// Record the allocation of a shared value.
+pub(crate) fn allocated<T>(ptr: *const ())
+where
+    T: any::Any,
+{
+    let live = Live {
+        type_id: TypeId::of::<T>(),
+        type_name: any::type_name::<T>,
+        site: crate::access::current_site(),
+    };
+
+    // NB: values might be allocated while thread locals are being destroyed.
+    let _ = LIVE.try_with(|values| values.borrow_mut().insert(ptr, live));
+}
+
+/// Record that the shared value with the given address has been freed.
+pub(crate) fn freed(ptr: *const ()) {
+    let _ = LIVE.try_with(|values| {
+        let mut values = values.borrow_mut();
+        values.remove(&ptr);
+
+        // NB: release the memory of the table once no values are alive, so
+        // that it isn't reported as leaked.
+        if values.is_empty() {
+            *values = HashMap::new();
+        }
+    });
+}
+
+/// Counts of the shared values which are alive on the current thread, grouped
+/// by type. Constructed through [Vm::census][crate::Vm::census].
+///
+/// Values are counted as they are allocated and freed, so every live value is
+/// included regardless of whether it's reachable or not, like values which are
+/// kept alive by reference cycles. Every shared value is counted once,
+/// regardless of how many references to it exist. Values which are borrowed
+/// exclusively while the census is taken are counted by the name of their
+/// native type.
+///
+/// The sites at which values were allocated are tracked for values allocated
+/// while running a unit with debug info.
+#[derive(Debug, Default, Clone)]
+pub struct Census {
+    entries: BTreeMap<String, CensusEntry>,
+}
+
+impl Census {
+    /// Take a census of the shared values which are alive on the current
+    /// thread.
+    pub(crate) fn take(type_names: &TypeNames) -> Self {
+        // NB: the live values are copied out, since constructing the names of
+        // their types might access the values.
+        let live = LIVE.with(|values| {
+            values
+                .borrow()
+                .iter()
+                .map(|(ptr, live)| (*ptr, *live))
+                .collect::<Vec<_>>()
+        });
+
+        let mut census = Self::default();
+
+        for (ptr, live) in live {
+            // Safety: the value is alive since it hasn't been freed, and the
+            // type id identifies the type it was allocated with.
+            let value = unsafe { to_value(ptr, live.type_id) };
+
+            let name = match value.map(|value| value.type_info()) {
+                Some(Ok(type_info)) => type_names.display(&type_info).to_string(),
+                _ => String::from((live.type_name)()),
+            };
+
+            census.entries.entry(name).or_default().record(live.site);
+        }
+
+        census
+    }
+
+    /// The total number of live shared values.
+    pub fn total(&self) -> usize {
+        self.entries.values().map(|e| e.count).sum()
+    }
+
+    /// The number of live shared values of the type with the given name.
+    pub fn count(&self, type_name: &str) -> usize {
+        self.entries
+            .get(type_name)
+            .map(|e| e.count)
+            .unwrap_or_default()
+    }
+
+    /// Iterate over the entries of the census, ordered by type name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &CensusEntry)> {
+        self.entries.iter().map(|(name, e)| (name.as_str(), e))
+    }
+}
+
+impl fmt::Display for Census {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, entry) in &self.entries {
+            writeln!(fmt, "{}: {}", name, entry.count)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The live shared values of a single type.
+#[derive(Debug, Default, Clone)]
+pub struct CensusEntry {
+    /// The number of live values.
+    count: usize,
+    /// The number of live values allocated at each site.
+    sites: HashMap<BorrowSite, usize>,
+}
+
+impl CensusEntry {
+    /// The number of live values.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The sites at which the live values were allocated, together with the
+    /// number of values allocated at each.
+    ///
+    /// Values allocated outside of a unit with debug info have no site and
+    /// aren't included.
+    pub fn sites(&self) -> impl Iterator<Item = (BorrowSite, usize)> + '_ {
+        self.sites.iter().map(|(site, count)| (*site, *count))
+    }
+
+    fn record(&mut self, site: Option<BorrowSite>) {
+        self.count += 1;
+
+        if let Some(site) = site {
+            *self.sites.entry(site).or_default() += 1;
+        }
+    }
+}
+
+/// Construct a value referencing the live shared value with the given address,
+/// if it's of a type which can be stored in a value.
+///
+/// # Safety
+///
+/// The address must be of a live shared value allocated with the type
+/// identified by the given type id.
+unsafe fn to_value(ptr: *const (), type_id: TypeId) -> Option<Value> {
+    macro_rules! to_value {
+        ($($variant:ident => $ty:ty),* $(,)?) => {
+            $(
+                if type_id == TypeId::of::<$ty>() {
+                    return Some(Value::$variant(Shared::<$ty>::from_live(ptr)));
+                }
+            )*
+        };
+    }
+
+    to_value! {
+        String => String,
+        Bytes => Bytes,
+        Vec => Vec<Value>,
+        Tuple => Tuple,
+        Object => Object<Value>,
+        Future => Future,
+        Stream => Stream,
+        Generator => Generator,
+        GeneratorState => GeneratorState,
+        Option => Option<Value>,
+        Result => Result<Value, Value>,
+        TypedTuple => TypedTuple,
+        TupleVariant => TupleVariant,
+        TypedObject => TypedObject,
+        VariantObject => VariantObject,
+        Function => Function,
+        Any => Any,
+    }
+
+    None
+}
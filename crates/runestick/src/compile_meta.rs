@@ -82,6 +82,14 @@ pub enum CompileMeta {
         /// The item of the macro.
         item: Item,
     },
+    /// An interface, which values implementing all of its protocols are
+    /// considered to be instances of when tested with `is`.
+    Interface {
+        /// The value type associated with this meta item.
+        value_type: Type,
+        /// The item of the interface.
+        item: Item,
+    },
 }
 
 impl CompileMeta {
@@ -97,6 +105,7 @@ impl CompileMeta {
             CompileMeta::Closure { item, .. } => item,
             CompileMeta::AsyncBlock { item, .. } => item,
             CompileMeta::Macro { item, .. } => item,
+            CompileMeta::Interface { item, .. } => item,
         }
     }
 
@@ -112,6 +121,7 @@ impl CompileMeta {
             Self::Closure { value_type, .. } => Some(*value_type),
             Self::AsyncBlock { value_type, .. } => Some(*value_type),
             Self::Macro { .. } => None,
+            Self::Interface { value_type, .. } => Some(*value_type),
         }
     }
 }
@@ -146,6 +156,9 @@ impl fmt::Display for CompileMeta {
             Self::Macro { item, .. } => {
                 write!(fmt, "macro {}", item)?;
             }
+            Self::Interface { item, .. } => {
+                write!(fmt, "interface {}", item)?;
+            }
         }
 
         Ok(())
@@ -1,10 +1,13 @@
 use crate::collections::{HashMap, HashSet};
+use crate::finalizer::{ExecutorSlot, FinalizerFuture};
 use crate::module::{
-    ModuleAssociatedFn, ModuleFn, ModuleInternalEnum, ModuleMacro, ModuleType, ModuleUnitType,
+    ModuleAssociatedFn, ModuleAssociatedKind, ModuleFn, ModuleInternalEnum, ModuleMacro,
+    ModuleType, ModuleUnitType,
 };
 use crate::{
-    CompileMeta, CompileMetaStruct, CompileMetaTuple, Component, Hash, Item, Module, Names, Stack,
-    StaticType, Type, TypeCheck, TypeInfo, ValueType, VmError,
+    AnyVtable, ApiVersion, CompileMeta, CompileMetaStruct, CompileMetaTuple, Component,
+    FloatDisplay, Function, Hash, Item, Module, Names, Protocol, Stack, StaticType, Type,
+    TypeCheck, TypeInfo, UnsafeFromValue, Value, ValueType, VmError,
 };
 use std::any;
 use std::fmt;
@@ -81,12 +84,38 @@ pub enum ContextError {
         /// The type we're trying to insert.
         value_type: Type,
     },
+    /// Error raised when attempting to register a conflicting protocol.
+    #[error("protocol with name `{name}` already exists")]
+    ConflictingProtocol {
+        /// The name of the conflicting protocol.
+        name: &'static str,
+    },
+    /// Error raised when a custom protocol has the same name as a native
+    /// instance function, which calls to it would be resolved to instead.
+    #[error("protocol `{name}` conflicts with an instance function with the same name")]
+    ConflictingProtocolFunction {
+        /// The name of the protocol.
+        name: &'static str,
+    },
+    /// Error raised when attempting to register a conflicting interface.
+    #[error("interface with name `{name}` already exists")]
+    ConflictingInterface {
+        /// The name of the conflicting interface.
+        name: Item,
+    },
     /// Error raised when attempting to register a conflicting function.
     #[error("variant with name `{name}` already exists")]
     ConflictingVariant {
         /// The name of the conflicting variant.
         name: Item,
     },
+    /// Error raised when attempting to decorate a function which does not
+    /// exist.
+    #[error("function `{name}` does not exist")]
+    MissingFunction {
+        /// The name of the missing function.
+        name: Item,
+    },
     /// Error raised when attempting to register an instance function on an
     /// instance which does not exist.
     #[error("instance `{instance_type}` does not exist in module")]
@@ -104,7 +133,10 @@ pub enum ContextError {
 }
 
 /// A function handler.
-pub(crate) type Handler = dyn Fn(&mut Stack, usize) -> Result<(), VmError> + Sync;
+pub(crate) type Handler = dyn Fn(&mut Stack, usize) -> Result<(), VmError> + Send + Sync;
+
+/// A hook coercing values before they are converted into a native type.
+pub(crate) type Coercion = dyn Fn(Value) -> Result<Value, VmError> + Send + Sync;
 
 /// A (type erased) macro handler.
 pub(crate) type Macro =
@@ -132,15 +164,45 @@ impl fmt::Display for ContextTypeInfo {
     }
 }
 
+/// Information on an interface.
+#[derive(Debug, Clone)]
+pub struct ContextInterface {
+    /// The name of the interface.
+    pub name: Item,
+    /// The protocols a type must implement to implement the interface.
+    pub protocols: Vec<Protocol>,
+}
+
+impl fmt::Display for ContextInterface {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{} => ", self.name)?;
+
+        let mut it = self.protocols.iter();
+        let last = it.next_back();
+
+        for protocol in it {
+            write!(fmt, "{} + ", protocol)?;
+        }
+
+        if let Some(protocol) = last {
+            write!(fmt, "{}", protocol)?;
+        }
+
+        Ok(())
+    }
+}
+
 /// A description of a function signature.
 #[derive(Debug, Clone)]
 pub enum ContextSignature {
+    /// A free function.
     Function {
         /// Path to the function.
         path: Item,
         /// Arguments.
         args: Option<usize>,
     },
+    /// An instance function.
     Instance {
         /// Path to the instance function.
         path: Item,
@@ -228,6 +290,30 @@ pub struct Context {
     internal_enums: HashSet<&'static StaticType>,
     /// All available names in the context.
     names: Names,
+    /// Custom protocols declared by installed modules.
+    protocols: HashMap<&'static str, Protocol>,
+    /// Hashes of the names of native instance functions, used to detect
+    /// custom protocols which would take over calls to them.
+    instance_fn_names: HashSet<Hash>,
+    /// Interfaces declared by installed modules.
+    interfaces: HashMap<Hash, ContextInterface>,
+    /// Let panics in native functions unwind through the virtual machine
+    /// instead of converting them into errors.
+    propagate_panics: bool,
+    /// How floats are displayed in template strings.
+    float_display: FloatDisplay,
+    /// The version of the host API provided by the context.
+    api_version: Option<ApiVersion>,
+    /// Coercion hooks, keyed by the type id of the native type they convert
+    /// to.
+    coercions: HashMap<any::TypeId, Arc<Coercion>>,
+    /// Vtables of native types with finalizers, keyed by the hash of the type.
+    finalizers: HashMap<Hash, &'static AnyVtable>,
+    /// Executor which futures produced by async finalizers are spawned on.
+    finalizer_executor: ExecutorSlot,
+    /// Recorder receiving instrumentation events.
+    #[cfg(feature = "metrics")]
+    recorder: Option<Arc<dyn crate::Recorder>>,
 }
 
 impl Context {
@@ -251,16 +337,26 @@ impl Context {
         this.install(&crate::modules::string::module()?)?;
         this.install(&crate::modules::int::module()?)?;
         this.install(&crate::modules::float::module()?)?;
+        this.install(&crate::modules::char::module()?)?;
         this.install(&crate::modules::test::module()?)?;
         this.install(&crate::modules::iter::module()?)?;
         this.install(&crate::modules::vec::module()?)?;
+        this.install(&crate::modules::tuple::module()?)?;
         this.install(&crate::modules::object::module()?)?;
+        this.install(&crate::modules::collections::module()?)?;
         this.install(&crate::modules::result::module()?)?;
         this.install(&crate::modules::option::module()?)?;
         this.install(&crate::modules::future::module()?)?;
         this.install(&crate::modules::stream::module()?)?;
+        this.install(&crate::modules::channel::module()?)?;
         this.install(&crate::modules::io::module()?)?;
         this.install(&crate::modules::fmt::module()?)?;
+        this.install(&crate::modules::encoding::module()?)?;
+        this.install(&crate::modules::cache::module()?)?;
+        this.install(&crate::modules::schema::module()?)?;
+        this.install(&crate::modules::enums::module()?)?;
+        this.install(&crate::modules::diff::module()?)?;
+        this.install(&crate::modules::mem::module()?)?;
         this.has_default_modules = true;
         Ok(this)
     }
@@ -302,6 +398,216 @@ impl Context {
         self.functions.get(&hash)
     }
 
+    /// Lookup the signature of the given native function.
+    pub fn lookup_signature(&self, hash: Hash) -> Option<&ContextSignature> {
+        self.functions_info.get(&hash)
+    }
+
+    /// Configure whether panics in native functions should propagate through
+    /// the virtual machine.
+    ///
+    /// By default, a panic in a native function is caught where the virtual
+    /// machine calls it, and is converted into a
+    /// [NativePanic][crate::VmErrorKind::NativePanic] error. The execution
+    /// which called the function is poisoned, but the host thread and any
+    /// other virtual machines using this context are unaffected.
+    ///
+    /// Setting this to `true` lets the panic unwind into the host instead.
+    pub fn set_propagate_panics(&mut self, propagate_panics: bool) {
+        self.propagate_panics = propagate_panics;
+    }
+
+    /// Test if panics in native functions propagate through the virtual
+    /// machine. See [set_propagate_panics][Self::set_propagate_panics].
+    pub fn propagate_panics(&self) -> bool {
+        self.propagate_panics
+    }
+
+    /// Set how floats are displayed in template strings.
+    ///
+    /// By default floats use [FloatDisplay::Shortest], which displays whole
+    /// numbers like `1.0`. Use [FloatDisplay::Plain] to display them like
+    /// integers, or [FloatDisplay::Fixed] for a fixed number of decimals.
+    pub fn set_float_display(&mut self, float_display: FloatDisplay) {
+        self.float_display = float_display;
+    }
+
+    /// Get how floats are displayed in template strings. See
+    /// [set_float_display][Self::set_float_display].
+    pub fn float_display(&self) -> FloatDisplay {
+        self.float_display
+    }
+
+    /// Declare the version of the host API provided by the context.
+    ///
+    /// Scripts which declare the version they target through the
+    /// `api-version` compiler option are checked against it when loaded.
+    pub fn set_api_version(&mut self, api_version: ApiVersion) {
+        self.api_version = Some(api_version);
+    }
+
+    /// Get the version of the host API provided by the context, if declared.
+    /// See [set_api_version][Self::set_api_version].
+    pub fn api_version(&self) -> Option<ApiVersion> {
+        self.api_version
+    }
+
+    /// Register a hook which coerces values passed from scripts into native
+    /// functions expecting an argument of type `T`.
+    ///
+    /// The hook is called with every value that is about to be converted into
+    /// `T`, before the conversion happens. It should return values it doesn't
+    /// know how to handle unchanged, so that the regular conversion can
+    /// produce an error for them. Registering a hook for a type replaces any
+    /// hook previously registered for it.
+    ///
+    /// Hooks are matched on the exact argument type, so a hook for `String`
+    /// doesn't apply to arguments of type `&str`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Context, Value};
+    ///
+    /// let mut context = Context::new();
+    ///
+    /// // Allow integers to be passed where floats are expected.
+    /// context.coerce::<f64, _>(|value| match value {
+    ///     Value::Integer(n) => Ok(Value::Float(n as f64)),
+    ///     value => Ok(value),
+    /// });
+    /// ```
+    pub fn coerce<T, F>(&mut self, hook: F)
+    where
+        T: UnsafeFromValue,
+        T::Output: 'static,
+        F: 'static + Send + Sync + Fn(Value) -> Result<Value, VmError>,
+    {
+        self.coercions
+            .insert(any::TypeId::of::<T::Output>(), Arc::new(hook));
+    }
+
+    /// Test if any coercion hooks are registered.
+    pub(crate) fn has_coercions(&self) -> bool {
+        !self.coercions.is_empty()
+    }
+
+    /// Lookup the coercion hook registered for the native type with the given
+    /// type id of its [UnsafeFromValue::Output].
+    pub(crate) fn lookup_coercion(&self, to: any::TypeId) -> Option<&Arc<Coercion>> {
+        self.coercions.get(&to)
+    }
+
+    /// Wrap the native function at the given path with a decorator.
+    ///
+    /// The decorator is called instead of the function, with a pointer to the
+    /// function it replaces and the arguments it was called with. It can
+    /// inspect or change the arguments, refuse the call by returning an
+    /// error, or post-process the value returned by the original function.
+    /// Decorating a function more than once wraps the previous decorator.
+    ///
+    /// Only functions installed from modules can be decorated, and the
+    /// decorator doesn't apply to pointers to the function created before it
+    /// was installed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Context, Value, VmError};
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let mut context = Context::with_default_modules()?;
+    ///
+    /// // Only allow scripts to print strings.
+    /// context.decorate(&["std", "println"], |original, args| {
+    ///     if !args.iter().all(|arg| matches!(arg, Value::String(..) | Value::StaticString(..))) {
+    ///         return Err(VmError::panic("can only print strings"));
+    ///     }
+    ///
+    ///     original.call_values(args)
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn decorate<N, F>(&mut self, name: N, decorator: F) -> Result<(), ContextError>
+    where
+        N: IntoIterator,
+        N::Item: Into<Component>,
+        F: 'static + Send + Sync + Fn(&Function, Vec<Value>) -> Result<Value, VmError>,
+    {
+        let name = Item::of(name);
+        let hash = Hash::type_hash(&name);
+
+        let original = match self.functions.get(&hash) {
+            Some(original) => original.clone(),
+            None => return Err(ContextError::MissingFunction { name }),
+        };
+
+        let handler: Arc<Handler> = Arc::new(move |stack, args| {
+            let original = Function::from_handler(hash, original.clone());
+            let args = stack.pop_sequence(args)?;
+            let value = decorator(&original, args)?;
+            stack.push(value);
+            Ok(())
+        });
+
+        self.functions.insert(hash, handler);
+        Ok(())
+    }
+
+    /// Set the executor which futures produced by async finalizers are spawned
+    /// on.
+    ///
+    /// Async finalizers are registered with
+    /// [TypeBuilder::build_with_async_finalizer][crate::module::TypeBuilder::build_with_async_finalizer].
+    /// If no executor is set, the futures they produce are dropped without
+    /// being polled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::Context;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let queue = Arc::new(Mutex::new(Vec::new()));
+    /// let mut context = Context::new();
+    ///
+    /// let q = queue.clone();
+    /// context.set_finalizer_executor(move |future| q.lock().unwrap().push(future));
+    /// ```
+    pub fn set_finalizer_executor<F>(&mut self, executor: F)
+    where
+        F: 'static + Send + Sync + Fn(FinalizerFuture),
+    {
+        if let Ok(mut slot) = self.finalizer_executor.write() {
+            *slot = Some(Arc::new(executor));
+        }
+    }
+
+    /// Test if any native type in the context has a finalizer.
+    pub(crate) fn has_finalizers(&self) -> bool {
+        !self.finalizers.is_empty()
+    }
+
+    /// Lookup the vtable with the finalizer of the native type with the given
+    /// hash.
+    pub(crate) fn lookup_finalizer(&self, hash: Hash) -> Option<&'static AnyVtable> {
+        self.finalizers.get(&hash).copied()
+    }
+
+    /// Install a recorder which will receive instrumentation events from all
+    /// virtual machines using this context.
+    #[cfg(feature = "metrics")]
+    pub fn set_recorder(&mut self, recorder: Arc<dyn crate::Recorder>) {
+        self.recorder = Some(recorder);
+    }
+
+    /// Access the installed recorder, if any.
+    #[cfg(feature = "metrics")]
+    pub fn recorder(&self) -> Option<&Arc<dyn crate::Recorder>> {
+        self.recorder.as_ref()
+    }
+
     /// Lookup the given macro handler.
     pub fn lookup_macro(&self, hash: Hash) -> Option<&Arc<Macro>> {
         self.macros.get(&hash)
@@ -354,7 +660,19 @@ impl Context {
             self.install_internal_enum(module, internal_enum)?;
         }
 
+        for protocol in module.protocols.values() {
+            self.install_protocol(*protocol)?;
+        }
+
+        for (name, protocols) in &module.interfaces {
+            self.install_interface(module, name, protocols)?;
+        }
+
         for (key, inst) in &module.associated_functions {
+            if key.kind == ModuleAssociatedKind::Instance {
+                self.install_instance_fn_name(key.hash, &inst.name)?;
+            }
+
             self.install_associated_function(
                 key.value_type,
                 key.hash,
@@ -366,6 +684,128 @@ impl Context {
         Ok(())
     }
 
+    /// Look up the name of a type by its value type.
+    pub fn lookup_type_name(&self, value_type: Type) -> Option<&Item> {
+        let hash = self.types_rev.get(&value_type)?;
+        Some(&self.types.get(hash)?.name)
+    }
+
+    /// Look up a custom protocol declared by an installed module.
+    pub fn lookup_protocol(&self, name: &str) -> Option<Protocol> {
+        self.protocols.get(name).copied()
+    }
+
+    /// Iterate over all custom protocols declared by installed modules.
+    pub fn iter_protocols(&self) -> impl Iterator<Item = Protocol> + '_ {
+        self.protocols.values().copied()
+    }
+
+    /// Look up an interface by the type hash of its name.
+    pub fn lookup_interface(&self, hash: Hash) -> Option<&ContextInterface> {
+        self.interfaces.get(&hash)
+    }
+
+    /// Iterate over all interfaces declared by installed modules.
+    pub fn iter_interfaces(&self) -> impl Iterator<Item = (Hash, &ContextInterface)> {
+        self.interfaces
+            .iter()
+            .map(|(hash, interface)| (*hash, interface))
+    }
+
+    /// Test if the given type implements the given protocol through a native
+    /// instance function.
+    pub fn implements(&self, value_type: Type, protocol: Protocol) -> bool {
+        let hash = Hash::instance_function(value_type, protocol.hash);
+        self.functions.contains_key(&hash)
+    }
+
+    /// Iterate over the protocols, built-in or custom, which the given type
+    /// implements through native instance functions.
+    pub fn type_protocols(&self, value_type: Type) -> impl Iterator<Item = Protocol> + '_ {
+        crate::protocol::BUILTIN_PROTOCOLS
+            .iter()
+            .copied()
+            .chain(self.protocols.values().copied())
+            .filter(move |protocol| self.implements(value_type, *protocol))
+    }
+
+    /// Get the hash used to call the instance function with the given name,
+    /// which resolves to a protocol if one has been declared with that name.
+    pub fn instance_fn_hash(&self, name: &str) -> Hash {
+        match self.protocols.get(name) {
+            Some(protocol) => protocol.hash,
+            None => Hash::of(name),
+        }
+    }
+
+    /// Install a custom protocol.
+    fn install_protocol(&mut self, protocol: Protocol) -> Result<(), ContextError> {
+        if let Some(existing) = self.protocols.get(protocol.name) {
+            if existing.hash != protocol.hash {
+                return Err(ContextError::ConflictingProtocol {
+                    name: protocol.name,
+                });
+            }
+        }
+
+        let name = Hash::of(protocol.name);
+
+        if protocol.hash != name && self.instance_fn_names.contains(&name) {
+            return Err(ContextError::ConflictingProtocolFunction {
+                name: protocol.name,
+            });
+        }
+
+        self.protocols.insert(protocol.name, protocol);
+        Ok(())
+    }
+
+    /// Install the name of a native instance function, which is called through
+    /// the given hash.
+    fn install_instance_fn_name(&mut self, hash: Hash, name: &str) -> Result<(), ContextError> {
+        if let Some(protocol) = self.protocols.get(name) {
+            if protocol.hash != hash {
+                return Err(ContextError::ConflictingProtocolFunction {
+                    name: protocol.name,
+                });
+            }
+        }
+
+        self.instance_fn_names.insert(hash);
+        Ok(())
+    }
+
+    /// Install an interface.
+    fn install_interface(
+        &mut self,
+        module: &Module,
+        name: &Item,
+        protocols: &[Protocol],
+    ) -> Result<(), ContextError> {
+        let name = module.path.join(name);
+        let hash = Hash::type_hash(&name);
+
+        self.install_meta(
+            name.clone(),
+            CompileMeta::Interface {
+                value_type: Type::Hash(hash),
+                item: name.clone(),
+            },
+        )?;
+
+        self.names.insert(&name);
+
+        self.interfaces.insert(
+            hash,
+            ContextInterface {
+                name,
+                protocols: protocols.to_vec(),
+            },
+        );
+
+        Ok(())
+    }
+
     /// Install the given meta.
     fn install_meta(&mut self, item: Item, meta: CompileMeta) -> Result<(), ContextError> {
         if let Some(existing) = self.meta.insert(item.clone(), meta.clone()) {
@@ -399,6 +839,11 @@ impl Context {
             },
         )?;
 
+        if let Some(finalizer) = &ty.finalizer {
+            let vtable = crate::finalizer::install(finalizer, &self.finalizer_executor);
+            self.finalizers.insert(value_type.as_type_hash(), vtable);
+        }
+
         self.install_meta(
             name.clone(),
             CompileMeta::Struct {
@@ -0,0 +1,80 @@
+//! Coverage counters of a unit.
+
+use crate::Span;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The coverage counters of a unit.
+///
+/// Counters are emitted by the compiler when coverage is enabled, and are
+/// incremented every time the code they cover is executed. Since they are
+/// stored in the unit, they collect hits from every virtual machine running
+/// it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Coverage {
+    counters: Vec<CoverageCounter>,
+}
+
+impl Coverage {
+    /// Construct a new, empty collection of counters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a counter covering the given span, returning its index.
+    pub fn insert(&mut self, source_id: usize, span: Span) -> usize {
+        let counter = self.counters.len();
+
+        self.counters.push(CoverageCounter {
+            source_id,
+            span,
+            hits: AtomicU64::new(0),
+        });
+
+        counter
+    }
+
+    /// Test if there are no counters, which is the case if the unit was
+    /// compiled without coverage.
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty()
+    }
+
+    /// Iterate over all counters.
+    pub fn iter(&self) -> impl Iterator<Item = &'_ CoverageCounter> {
+        self.counters.iter()
+    }
+
+    /// Reset the hits of all counters to zero.
+    pub fn reset(&self) {
+        for counter in &self.counters {
+            counter.hits.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Increment the given counter.
+    pub(crate) fn hit(&self, counter: usize) {
+        if let Some(counter) = self.counters.get(counter) {
+            counter.hits.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// A single coverage counter.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CoverageCounter {
+    /// The id of the source the covered code is in.
+    pub source_id: usize,
+    /// The span of the covered code.
+    pub span: Span,
+    /// The number of times the covered code has been executed.
+    #[serde(skip)]
+    hits: AtomicU64,
+}
+
+impl CoverageCounter {
+    /// The number of times the covered code has been executed.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+}
@@ -2,10 +2,11 @@
 
 use crate::collections::HashMap;
 use crate::{Hash, Item, Label, Span};
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Debug information about a unit.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct DebugInfo {
     /// Debug information on each instruction.
     pub instructions: Vec<DebugInst>,
@@ -27,10 +28,26 @@ impl DebugInfo {
         let signature = self.functions.get(&hash)?;
         Some((hash, signature))
     }
+
+    /// Get the function which contains the instruction at the given
+    /// instruction pointer.
+    ///
+    /// Unlike [function_at][Self::function_at], this doesn't require `ip` to
+    /// point to the first instruction of the function.
+    pub fn function_enclosing(&self, ip: usize) -> Option<(Hash, &DebugSignature)> {
+        let (_, hash) = self
+            .functions_rev
+            .iter()
+            .filter(|(offset, _)| **offset <= ip)
+            .max_by_key(|(offset, _)| **offset)?;
+
+        let signature = self.functions.get(hash)?;
+        Some((*hash, signature))
+    }
 }
 
 /// Debug information for every instruction.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct DebugInst {
     /// The file by id the instruction belongs to.
     pub source_id: usize,
@@ -39,11 +56,15 @@ pub struct DebugInst {
     /// The comment for the line.
     pub comment: Option<String>,
     /// Label associated with the location.
+    ///
+    /// Labels are only used when dumping instructions, and are not preserved
+    /// when the debug info is serialized.
+    #[serde(skip)]
     pub label: Option<Label>,
 }
 
 /// Debug information on function arguments.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum DebugArgs {
     /// A tuple, with the given number of arguments.
     TupleArgs(usize),
@@ -52,7 +73,7 @@ pub enum DebugArgs {
 }
 
 /// A description of a function signature.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct DebugSignature {
     /// The path of the function.
     pub path: Item,
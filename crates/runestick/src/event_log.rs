@@ -0,0 +1,193 @@
+//! An event log of what an execution did, for audit trails.
+
+use crate::Value;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::rc::Rc;
+
+thread_local! {
+    static EVENT_LOG: RefCell<Option<EventLog>> = RefCell::new(None);
+}
+
+/// The maximum number of characters of a string argument which are rendered
+/// in an event.
+const MAX_STRING: usize = 64;
+
+/// An event recorded in an [EventLog].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// A script function was called.
+    Call {
+        /// The name of the function, if known.
+        function: String,
+        /// The arguments of the call, shallow-rendered.
+        args: Vec<String>,
+    },
+    /// A native function was called.
+    NativeCall {
+        /// The name of the function, if known.
+        function: String,
+        /// The arguments of the call, shallow-rendered.
+        args: Vec<String>,
+    },
+    /// The execution errored.
+    Error {
+        /// The message of the error.
+        message: String,
+    },
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Call { function, args } => write!(fmt, "call {}({})", function, args.join(", ")),
+            Self::NativeCall { function, args } => {
+                write!(fmt, "native {}({})", function, args.join(", "))
+            }
+            Self::Error { message } => write!(fmt, "error: {}", message),
+        }
+    }
+}
+
+/// A bounded log of the events of an execution, enabled through
+/// [VmExecution::with_event_log][crate::VmExecution::with_event_log].
+///
+/// The log is a ring buffer, so once it's full the oldest events are dropped
+/// to make room for new ones. Arguments are rendered shallowly: primitives and
+/// strings are rendered as their values, while other values are only rendered
+/// as their type.
+#[derive(Debug, Clone)]
+pub struct EventLog {
+    inner: Rc<RefCell<Inner>>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    capacity: usize,
+    events: VecDeque<Event>,
+    dropped: usize,
+}
+
+impl EventLog {
+    /// Construct a new event log holding at most `capacity` events.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(Inner {
+                capacity,
+                events: VecDeque::with_capacity(capacity),
+                dropped: 0,
+            })),
+        }
+    }
+
+    /// The events in the log, from oldest to newest.
+    pub fn events(&self) -> Vec<Event> {
+        self.inner.borrow().events.iter().cloned().collect()
+    }
+
+    /// The number of events which have been dropped because the log was full.
+    pub fn dropped(&self) -> usize {
+        self.inner.borrow().dropped
+    }
+
+    /// Push an event onto the log, dropping the oldest event if it's full.
+    pub(crate) fn push(&self, event: Event) {
+        let mut inner = self.inner.borrow_mut();
+
+        if inner.capacity == 0 {
+            inner.dropped += 1;
+            return;
+        }
+
+        if inner.events.len() == inner.capacity {
+            inner.events.pop_front();
+            inner.dropped += 1;
+        }
+
+        inner.events.push_back(event);
+    }
+}
+
+/// Run the given closure with the given event log installed, so that calls
+/// performed while it runs are recorded.
+pub(crate) fn with_event_log<F, T>(event_log: Option<&EventLog>, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let event_log = match event_log {
+        Some(event_log) => event_log,
+        None => return f(),
+    };
+
+    let _guard = EventLogGuard {
+        previous: EVENT_LOG.with(|current| current.replace(Some(event_log.clone()))),
+    };
+
+    f()
+}
+
+/// Record a call of a function with the given arguments in the installed
+/// event log, if any.
+///
+/// The name of the function is only computed if the call is recorded.
+pub(crate) fn record_call<F>(native: bool, function: F, args: &[Value])
+where
+    F: FnOnce() -> String,
+{
+    let event_log = match EVENT_LOG.with(|current| current.borrow().clone()) {
+        Some(event_log) => event_log,
+        None => return,
+    };
+
+    let function = function();
+    let args = args.iter().map(render).collect();
+
+    event_log.push(if native {
+        Event::NativeCall { function, args }
+    } else {
+        Event::Call { function, args }
+    });
+}
+
+/// Render the given value without looking into other values it contains.
+fn render(value: &Value) -> String {
+    match value {
+        Value::Unit => String::from("()"),
+        Value::Bool(b) => b.to_string(),
+        Value::Byte(b) => format!("{:?}", b),
+        Value::Char(c) => format!("{:?}", c),
+        Value::Integer(n) => n.to_string(),
+        Value::Float(n) => n.to_string(),
+        Value::StaticString(s) => render_str(s.as_str()),
+        Value::String(s) => match s.borrow_ref() {
+            Ok(s) => render_str(&s),
+            Err(..) => String::from("<String>"),
+        },
+        value => match value.type_info() {
+            Ok(type_info) => format!("<{}>", type_info),
+            Err(..) => String::from("<inaccessible>"),
+        },
+    }
+}
+
+/// Render a string, truncating it if it's long.
+fn render_str(s: &str) -> String {
+    match s.char_indices().nth(MAX_STRING) {
+        Some((n, _)) => format!("{:?}...", &s[..n]),
+        None => format!("{:?}", s),
+    }
+}
+
+/// Guard which restores the previous event log when dropped, even if the
+/// closure panics.
+struct EventLogGuard {
+    previous: Option<EventLog>,
+}
+
+impl Drop for EventLogGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        EVENT_LOG.with(|current| *current.borrow_mut() = previous);
+    }
+}
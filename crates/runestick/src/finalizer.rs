@@ -0,0 +1,137 @@
+//! Finalizers for native values constructed by the virtual machine.
+//!
+//! Values constructed through [Any::new][crate::Any::new] while a virtual
+//! machine is running pick up a vtable with the finalizer installed for their
+//! type in its context. The finalizer is then called when the value is dropped,
+//! regardless of where that happens.
+
+use crate::{AnyVtable, Context, Hash};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+
+thread_local! {
+    static CONTEXT: RefCell<Option<Arc<Context>>> = const { RefCell::new(None) };
+}
+
+/// A future produced by an async finalizer, which is handed to the executor
+/// configured with [Context::set_finalizer_executor].
+pub type FinalizerFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// The executor which async finalizers are spawned on.
+pub(crate) type FinalizerExecutor = dyn Fn(FinalizerFuture) + Send + Sync;
+
+/// The executor of a context, which is shared with the finalizers installed in
+/// it so that it can be set after they are installed.
+pub(crate) type ExecutorSlot = Arc<RwLock<Option<Arc<FinalizerExecutor>>>>;
+
+/// A type-erased finalizer for a native type.
+pub(crate) struct Finalizer {
+    /// Construct the vtable of the finalized type.
+    vtable: fn(&'static Installed) -> AnyVtable,
+    /// Take ownership of the boxed value pointed to and finalize it.
+    finalize: Box<dyn Fn(*const ()) -> Option<FinalizerFuture> + Send + Sync>,
+}
+
+/// Construct a type-erased finalizer for the given type.
+pub(crate) fn finalizer<T, F>(f: F) -> Arc<Finalizer>
+where
+    T: std::any::Any,
+    F: 'static + Send + Sync + Fn(T) -> Option<FinalizerFuture>,
+{
+    Arc::new(Finalizer {
+        vtable: crate::any::finalizer_vtable::<T>,
+        // Safety: this is only called through the vtable constructed above,
+        // which is only used by values constructed through `Any::new::<T>`.
+        finalize: Box::new(move |data| f(*unsafe { Box::from_raw(data as *mut T) })),
+    })
+}
+
+/// A finalizer installed in a context, which is referenced by the vtables of
+/// the values it finalizes.
+pub(crate) struct Installed {
+    finalizer: Arc<Finalizer>,
+    executor: ExecutorSlot,
+}
+
+impl Installed {
+    /// Finalize the given value.
+    ///
+    /// # Safety
+    ///
+    /// The caller must hand over ownership of a boxed value of the type the
+    /// finalizer was constructed for.
+    pub(crate) unsafe fn finalize(&self, data: *const ()) {
+        let future = match (self.finalizer.finalize)(data) {
+            Some(future) => future,
+            None => return,
+        };
+
+        let executor = match self.executor.read() {
+            Ok(executor) => executor.clone(),
+            Err(..) => None,
+        };
+
+        match executor {
+            Some(executor) => executor(future),
+            None => log::warn!("no executor configured to run async finalizer"),
+        }
+    }
+}
+
+/// Install the given finalizer, constructing the vtable used by values of its
+/// type.
+///
+/// NB: the vtable is leaked, since values constructed with it might outlive the
+/// context it's installed in.
+pub(crate) fn install(finalizer: &Arc<Finalizer>, executor: &ExecutorSlot) -> &'static AnyVtable {
+    let installed = Box::leak(Box::new(Installed {
+        finalizer: finalizer.clone(),
+        executor: executor.clone(),
+    }));
+
+    Box::leak(Box::new((finalizer.vtable)(installed)))
+}
+
+/// Run the given closure with the finalizers of the given context installed,
+/// so that native values constructed while it runs are finalized.
+pub(crate) fn with_context<F, T>(context: &Arc<Context>, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    if !context.has_finalizers() {
+        return f();
+    }
+
+    let _guard = ContextGuard {
+        previous: CONTEXT.with(|current| current.replace(Some(context.clone()))),
+    };
+
+    f()
+}
+
+/// Lookup the vtable with the finalizer for the type with the given hash in
+/// the installed context, if any.
+///
+/// The hash is only computed if a context with finalizers is installed.
+pub(crate) fn vtable(type_hash: fn() -> Hash) -> Option<&'static AnyVtable> {
+    // NB: values might be constructed while thread locals are being destroyed.
+    CONTEXT
+        .try_with(|context| context.borrow().as_ref()?.lookup_finalizer(type_hash()))
+        .ok()
+        .flatten()
+}
+
+/// Guard which restores the previous context when dropped, even if the
+/// closure panics.
+struct ContextGuard {
+    previous: Option<Arc<Context>>,
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CONTEXT.with(|current| *current.borrow_mut() = previous);
+    }
+}
@@ -0,0 +1,55 @@
+use std::fmt::Write as _;
+
+/// How floats are displayed in template strings, configured with
+/// [Context::set_float_display][crate::Context::set_float_display].
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::FloatDisplay;
+///
+/// let mut out = String::new();
+/// FloatDisplay::Shortest.format(1.0, &mut out);
+/// out.push(' ');
+/// FloatDisplay::Plain.format(1.0, &mut out);
+/// out.push(' ');
+/// FloatDisplay::Fixed(2).format(1.0, &mut out);
+/// assert_eq!(out, "1.0 1 1.00");
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FloatDisplay {
+    /// The shortest representation which parses back into the same float,
+    /// which always includes a fraction or an exponent. Like `1.0`, `0.1` or
+    /// `1e16`.
+    ///
+    /// This is the default.
+    #[default]
+    Shortest,
+    /// Like Rust's [Display][std::fmt::Display] for floats, which never uses an
+    /// exponent and leaves out the fraction of whole numbers, so that they're
+    /// displayed like integers. Like `1`, `0.1` or `10000000000000000`.
+    Plain,
+    /// Always display the given number of digits in the fraction, rounding
+    /// if necessary. Like `1.00` or `0.10` for two digits.
+    Fixed(usize),
+}
+
+impl FloatDisplay {
+    /// Format the given float according to the policy, appending it to the
+    /// given string.
+    pub fn format(self, value: f64, out: &mut String) {
+        match self {
+            Self::Shortest => {
+                let mut buffer = ryu::Buffer::new();
+                out.push_str(buffer.format(value));
+            }
+            Self::Plain => {
+                // NB: writing to a string can't fail.
+                let _ = write!(out, "{}", value);
+            }
+            Self::Fixed(precision) => {
+                let _ = write!(out, "{:.*}", precision, value);
+            }
+        }
+    }
+}
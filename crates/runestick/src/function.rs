@@ -1,4 +1,5 @@
 use crate::context::Handler;
+use crate::memoize::Memoized;
 use crate::VmErrorKind;
 use crate::{
     Args, Call, Context, FromValue, Future, Generator, Hash, OwnedRef, RawOwnedRef, Shared, Stack,
@@ -23,7 +24,7 @@ impl Function {
             Inner::FnHandler(handler) => {
                 let mut stack = Stack::with_capacity(A::count());
                 args.into_stack(&mut stack)?;
-                (handler.handler)(&mut stack, A::count())?;
+                (handler.resolve())(&mut stack, A::count())?;
                 stack.pop()?
             }
             Inner::FnOffset(fn_offset) => fn_offset.call(args, ())?,
@@ -38,6 +39,93 @@ impl Function {
                 Self::check_args(A::count(), tuple.args)?;
                 Value::variant_tuple(tuple.enum_hash, tuple.hash, args.into_vec()?)
             }
+            Inner::FnMemoized(memoized) => memoized.call(args.into_vec()?)?,
+        };
+
+        Ok(T::from_value(value)?)
+    }
+
+    /// Perform a call with a dynamic number of arguments.
+    pub fn call_values(&self, args: Vec<Value>) -> Result<Value, VmError> {
+        Ok(match &self.inner {
+            Inner::FnHandler(handler) => {
+                let count = args.len();
+                let mut stack = args.into_iter().collect::<Stack>();
+                (handler.resolve())(&mut stack, count)?;
+                stack.pop()?
+            }
+            Inner::FnOffset(fn_offset) => {
+                Self::check_args(args.len(), fn_offset.args)?;
+                fn_offset.call_with(|stack| {
+                    stack.extend(args);
+                    Ok(())
+                })?
+            }
+            Inner::FnClosureOffset(closure) => {
+                Self::check_args(args.len(), closure.fn_offset.args)?;
+                closure.fn_offset.call_with(|stack| {
+                    stack.extend(args);
+                    stack.push(closure.environment.clone());
+                    Ok(())
+                })?
+            }
+            Inner::FnTuple(tuple) => {
+                Self::check_args(args.len(), tuple.args)?;
+                Value::typed_tuple(tuple.hash, args)
+            }
+            Inner::FnVariantTuple(tuple) => {
+                Self::check_args(args.len(), tuple.args)?;
+                Value::variant_tuple(tuple.enum_hash, tuple.hash, args)
+            }
+            Inner::FnMemoized(memoized) => memoized.call(args)?,
+        })
+    }
+
+    /// Perform an asynchronous call over the function represented by this
+    /// function pointer.
+    ///
+    /// This is intended to be used by native functions which want to call back
+    /// into the virtual machine, like a function passed in as an argument.
+    /// Each call runs on a fresh virtual machine with its own stack, so the
+    /// calling virtual machine is unaffected by it.
+    ///
+    /// Unlike [call][Self::call], this can drive functions which await to
+    /// completion. If the function produces a future, like an `async fn` or an
+    /// async native function does, it will be awaited as well.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Function, Module, VmError};
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let mut module = Module::new(&["test"]);
+    ///
+    /// module.async_function(&["apply"], |f: Function, value: i64| async move {
+    ///     f.async_call::<_, i64>((value,)).await
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn async_call<A, T>(&self, args: A) -> Result<T, VmError>
+    where
+        A: Args,
+        T: FromValue,
+    {
+        let value = match &self.inner {
+            Inner::FnOffset(fn_offset) => fn_offset.async_call(args, ()).await?,
+            Inner::FnClosureOffset(closure) => {
+                closure
+                    .fn_offset
+                    .async_call(args, (closure.environment.clone(),))
+                    .await?
+            }
+            _ => self.call::<A, Value>(args)?,
+        };
+
+        let value = match value {
+            Value::Future(future) => future.take()?.await?,
+            value => value,
         };
 
         Ok(T::from_value(value)?)
@@ -52,7 +140,7 @@ impl Function {
     pub(crate) fn call_with_vm(&self, vm: &mut Vm, args: usize) -> Result<Option<VmHalt>, VmError> {
         let reason = match &self.inner {
             Inner::FnHandler(handler) => {
-                (handler.handler)(vm.stack_mut(), args)?;
+                vm.call_native(handler.hash, &*handler.handler, args)?;
                 None
             }
             Inner::FnOffset(fn_offset) => {
@@ -92,23 +180,29 @@ impl Function {
                 vm.stack_mut().push(value);
                 None
             }
+            Inner::FnMemoized(memoized) => {
+                let args = vm.stack_mut().pop_sequence(args)?;
+                let value = memoized.call(args)?;
+                vm.stack_mut().push(value);
+                None
+            }
         };
 
         Ok(reason)
     }
 
     /// Create a function pointer from a handler.
-    pub(crate) fn from_handler(handler: Arc<Handler>) -> Self {
+    pub(crate) fn from_handler(hash: Hash, handler: Arc<Handler>) -> Self {
         Self {
-            inner: Inner::FnHandler(FnHandler { handler }),
+            inner: Inner::FnHandler(FnHandler { hash, handler }),
         }
     }
 
-    /// Create a function pointer from an offset.
+    /// Create a function pointer from a function declared in the unit.
     pub(crate) fn from_offset(
         context: Arc<Context>,
         unit: Arc<Unit>,
-        offset: usize,
+        id: usize,
         call: Call,
         args: usize,
     ) -> Self {
@@ -116,7 +210,7 @@ impl Function {
             inner: Inner::FnOffset(FnOffset {
                 context,
                 unit,
-                offset,
+                id,
                 call,
                 args,
             }),
@@ -127,7 +221,7 @@ impl Function {
     pub(crate) fn from_closure(
         context: Arc<Context>,
         unit: Arc<Unit>,
-        offset: usize,
+        id: usize,
         call: Call,
         args: usize,
         environment: Shared<Tuple>,
@@ -137,7 +231,7 @@ impl Function {
                 fn_offset: FnOffset {
                     context,
                     unit,
-                    offset,
+                    id,
                     call,
                     args,
                 },
@@ -164,6 +258,14 @@ impl Function {
         }
     }
 
+    /// Create a function pointer which caches the results of calling the given
+    /// function.
+    pub(crate) fn from_memoized(memoized: Memoized) -> Self {
+        Self {
+            inner: Inner::FnMemoized(Box::new(memoized)),
+        }
+    }
+
     #[inline]
     fn check_args(actual: usize, expected: usize) -> Result<(), VmError> {
         if actual != expected {
@@ -184,13 +286,14 @@ impl fmt::Debug for Function {
                 write!(f, "native function ({:p})", handler.handler.as_ref())?;
             }
             Inner::FnOffset(offset) => {
-                write!(f, "dynamic function (at: 0x{:x})", offset.offset)?;
+                write!(f, "dynamic function (at: {})", FnAt(offset))?;
             }
             Inner::FnClosureOffset(closure) => {
                 write!(
                     f,
-                    "closure (at: 0x{:x}, env:{:?})",
-                    closure.fn_offset.offset, closure.environment
+                    "closure (at: {}, env:{:?})",
+                    FnAt(&closure.fn_offset),
+                    closure.environment
                 )?;
             }
             Inner::FnTuple(tuple) => {
@@ -203,6 +306,9 @@ impl fmt::Debug for Function {
                     tuple.enum_hash, tuple.hash
                 )?;
             }
+            Inner::FnMemoized(memoized) => {
+                write!(f, "memoized ({:?})", memoized)?;
+            }
         }
 
         Ok(())
@@ -228,13 +334,28 @@ enum Inner {
     FnTuple(FnTuple),
     /// Constructor for a tuple variant.
     FnVariantTuple(FnVariantTuple),
+    /// A function whose results are cached.
+    FnMemoized(Box<Memoized>),
 }
 
 struct FnHandler {
+    /// The hash of the function.
+    hash: Hash,
     /// The function handler.
     handler: Arc<Handler>,
 }
 
+impl FnHandler {
+    /// Get the handler to call, which is replaced if the function is
+    /// overridden in the current execution.
+    fn resolve(&self) -> Arc<Handler> {
+        match crate::overrides::lookup(self.hash) {
+            Some(handler) => handler,
+            None => self.handler.clone(),
+        }
+    }
+}
+
 impl fmt::Debug for FnHandler {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "FnHandler")
@@ -245,8 +366,8 @@ struct FnOffset {
     context: Arc<Context>,
     /// The unit where the function resides.
     unit: Arc<Unit>,
-    /// The offset of the function.
-    offset: usize,
+    /// The id of the function in the function table of the unit.
+    id: usize,
     /// The calling convention.
     call: Call,
     /// The number of arguments the function takes.
@@ -262,11 +383,25 @@ impl FnOffset {
     {
         Function::check_args(A::count(), self.args)?;
 
+        self.call_with(|stack| {
+            args.into_stack(stack)?;
+            extra.into_stack(stack)
+        })
+    }
+
+    /// Perform a call into the specified offset, with arguments pushed onto
+    /// the stack by the given closure.
+    fn call_with<F>(&self, push: F) -> Result<Value, VmError>
+    where
+        F: FnOnce(&mut Stack) -> Result<(), VmError>,
+    {
+        let offset = self.unit.function_offset(self.id)?;
+        let stack = self.unit.function_stack(self.id)?;
         let mut vm = Vm::new(self.context.clone(), self.unit.clone());
 
-        vm.set_ip(self.offset);
-        args.into_stack(vm.stack_mut())?;
-        extra.into_stack(vm.stack_mut())?;
+        vm.set_ip(offset);
+        vm.stack_mut().reserve_frame(stack)?;
+        push(vm.stack_mut())?;
 
         Ok(match self.call {
             Call::Stream => Value::from(Stream::new(vm)),
@@ -276,6 +411,33 @@ impl FnOffset {
         })
     }
 
+    /// Perform an asynchronous call into the specified offset and return the
+    /// produced value.
+    ///
+    /// Immediate and async functions are both run to completion.
+    async fn async_call<A, E>(&self, args: A, extra: E) -> Result<Value, VmError>
+    where
+        A: Args,
+        E: Args,
+    {
+        Function::check_args(A::count(), self.args)?;
+
+        let offset = self.unit.function_offset(self.id)?;
+        let stack = self.unit.function_stack(self.id)?;
+        let mut vm = Vm::new(self.context.clone(), self.unit.clone());
+
+        vm.set_ip(offset);
+        vm.stack_mut().reserve_frame(stack)?;
+        args.into_stack(vm.stack_mut())?;
+        extra.into_stack(vm.stack_mut())?;
+
+        Ok(match self.call {
+            Call::Stream => Value::from(Stream::new(vm)),
+            Call::Generator => Value::from(Generator::new(vm)),
+            Call::Immediate | Call::Async => vm.async_complete().await?,
+        })
+    }
+
     /// Perform a potentially optimized call into the specified vm.
     ///
     /// This will cause a halt in case the vm being called into isn't the same
@@ -285,20 +447,26 @@ impl FnOffset {
         E: Args,
     {
         Function::check_args(args, self.args)?;
+        let offset = self.unit.function_offset(self.id)?;
+        let stack = self.unit.function_stack(self.id)?;
 
         // Fast past, just allocate a call frame and keep running.
         if let Call::Immediate = self.call {
             if vm.is_same(&self.context, &self.unit) {
-                vm.push_call_frame(self.offset, args)?;
+                vm.push_call_frame(offset, args, stack)?;
                 extra.into_stack(vm.stack_mut())?;
                 return Ok(None);
             }
         }
 
         let mut new_stack = vm.stack_mut().drain_stack_top(args)?.collect::<Stack>();
+        new_stack.set_limit(vm.stack().limit());
+        new_stack.reserve_frame(stack)?;
         extra.into_stack(&mut new_stack)?;
+        let integer_overflow = vm.integer_overflow();
         let mut vm = Vm::new_with_stack(self.context.clone(), self.unit.clone(), new_stack);
-        vm.set_ip(self.offset);
+        vm.set_ip(offset);
+        vm.set_integer_overflow(integer_overflow);
         Ok(Some(VmCall::new(self.call, vm)))
     }
 }
@@ -308,13 +476,25 @@ impl fmt::Debug for FnOffset {
         f.debug_struct("FnOffset")
             .field("context", &(&self.context as *const _))
             .field("unit", &(&self.unit as *const _))
-            .field("offset", &self.offset)
+            .field("id", &self.id)
             .field("call", &self.call)
             .field("args", &self.args)
             .finish()
     }
 }
 
+/// Display the offset a function currently starts at.
+struct FnAt<'a>(&'a FnOffset);
+
+impl fmt::Display for FnAt<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.unit.function_offset(self.0.id) {
+            Ok(offset) => write!(f, "0x{:x}", offset),
+            Err(..) => write!(f, "missing function {}", self.0.id),
+        }
+    }
+}
+
 #[derive(Debug)]
 struct FnClosureOffset {
     /// Function offset.
@@ -9,6 +9,7 @@ use std::mem;
 pub struct Generator {
     execution: Option<VmExecution>,
     first: bool,
+    completion: Option<Value>,
 }
 
 impl Generator {
@@ -17,6 +18,7 @@ impl Generator {
         Self {
             execution: Some(VmExecution::new(vm)),
             first: true,
+            completion: None,
         }
     }
 
@@ -43,12 +45,33 @@ impl Generator {
 
         let state = execution.resume()?;
 
-        if state.is_complete() {
+        if let GeneratorState::Complete(value) = &state {
             self.execution = None;
+            self.completion = Some(value.clone());
         }
 
         Ok(state)
     }
+
+    /// Test if the generator has completed.
+    pub fn is_complete(&self) -> bool {
+        self.execution.is_none()
+    }
+
+    /// Get the value the generator completed with, if it has completed.
+    pub fn completion(&self) -> Option<Value> {
+        self.completion.clone()
+    }
+
+    /// Get the virtual machine the generator runs on.
+    ///
+    /// Errors if the generator has completed.
+    pub fn vm(&self) -> Result<&Vm, VmError> {
+        match &self.execution {
+            Some(execution) => execution.vm(),
+            None => Err(VmError::from(VmErrorKind::GeneratorComplete)),
+        }
+    }
 }
 
 impl fmt::Debug for Generator {
@@ -1,9 +1,10 @@
-use crate::{Component, Type};
+use crate::{Component, Item, Type};
+use serde::{Deserialize, Serialize};
 use std::any;
 use std::fmt;
 use std::hash;
 use std::hash::{BuildHasher as _, BuildHasherDefault, Hash as _, Hasher as _};
-use std::mem;
+
 use twox_hash::XxHash64;
 
 const SEP: usize = 0x7f;
@@ -11,10 +12,14 @@ const TYPE: usize = 1;
 const INSTANCE_FUNCTION: usize = 2;
 const GETTER: usize = 3;
 const OBJECT_KEYS: usize = 4;
+const PROTOCOL: usize = 5;
+/// The discriminant of [Component::String].
+const STRING_COMPONENT: isize = 0;
 
 /// The hash of a primitive thing.
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[repr(transparent)]
+#[serde(transparent)]
 pub struct Hash(u64);
 
 impl Hash {
@@ -23,6 +28,11 @@ impl Hash {
         Self(hash)
     }
 
+    /// Get the little-endian byte representation of the hash.
+    pub(crate) fn to_le_bytes(self) -> [u8; 8] {
+        self.0.to_le_bytes()
+    }
+
     /// Construct a hash from the given type id.
     pub fn from_any<T>() -> Self
     where
@@ -33,9 +43,7 @@ impl Hash {
 
     /// Construct a hash from a type id.
     pub fn from_type_id(type_id: any::TypeId) -> Self {
-        // Safety: a type id is exactly a 64-bit unsigned integer.
-        // And has an identical bit pattern to `Hash`.
-        unsafe { mem::transmute(type_id) }
+        Self::of(type_id)
     }
 
     /// Construct a hash to an instance function, where the instance is a
@@ -57,6 +65,14 @@ impl Hash {
         Self(Hash::of((GETTER, value_type, SEP, name)).0)
     }
 
+    /// Construct a hash for a custom protocol with the given name.
+    ///
+    /// This is distinct from the hash of a regular instance function with the
+    /// same name.
+    pub fn protocol(name: &str) -> Self {
+        Self(Hash::of((PROTOCOL, SEP, name)).0)
+    }
+
     /// Construct a simple hash from something that is hashable.
     pub fn of<T: hash::Hash>(thing: T) -> Self {
         let mut hasher = Self::new_hasher();
@@ -89,6 +105,51 @@ impl Hash {
         path.into_hash()
     }
 
+    /// Get the hash of a path of string components, like
+    /// `Hash::type_hash(&["std", "vec", "Vec"])`, in a constant context.
+    ///
+    /// This is what the [hash!][crate::hash!] macro uses.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Hash, Item};
+    ///
+    /// const VEC: Hash = Hash::of_path(&["std", "vec", "Vec"]);
+    /// assert_eq!(VEC, Hash::type_hash(Item::of(&["std", "vec", "Vec"])));
+    /// ```
+    pub const fn of_path(path: &[&str]) -> Self {
+        let mut hasher = ConstHasher::new().write(&TYPE.to_ne_bytes());
+        let mut n = 0;
+
+        while n < path.len() {
+            // NB: this is how the derived implementation of `Hash` for
+            // `Component` hashes its `String` variant.
+            hasher = hasher
+                .write(&STRING_COMPONENT.to_ne_bytes())
+                .write(path[n].as_bytes())
+                .write(&[0xff]);
+            n += 1;
+        }
+
+        Self(hasher.finish())
+    }
+
+    /// Get the hash of an item, like [Hash::type_hash] does.
+    ///
+    /// This hashes the components of the item in place, instead of cloning
+    /// them like going through [IntoHash] does.
+    pub(crate) fn item_hash(item: &Item) -> Self {
+        let mut hasher = Self::new_hasher();
+        TYPE.hash(&mut hasher);
+
+        for part in item {
+            part.hash(&mut hasher);
+        }
+
+        Self(hasher.finish())
+    }
+
     /// Construct a new hasher.
     fn new_hasher() -> impl hash::Hasher {
         BuildHasherDefault::<XxHash64>::default().build_hasher()
@@ -144,3 +205,186 @@ where
         Hash::path_hash(TYPE, self)
     }
 }
+
+const PRIME64_1: u64 = 0x9E37_79B1_85EB_CA87;
+const PRIME64_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const PRIME64_3: u64 = 0x1656_67B1_9E37_79F9;
+const PRIME64_4: u64 = 0x85EB_CA77_C2B2_AE63;
+const PRIME64_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+/// An implementation of [XxHash64] with a zero seed which can be used in
+/// constant contexts.
+///
+/// Constant functions can't take mutable references, so every operation
+/// consumes the hasher and returns the updated one.
+struct ConstHasher {
+    lanes: [u64; 4],
+    buffer: [u8; 32],
+    buffered: usize,
+    total: u64,
+}
+
+impl ConstHasher {
+    const fn new() -> Self {
+        Self {
+            lanes: [
+                PRIME64_1.wrapping_add(PRIME64_2),
+                PRIME64_2,
+                0,
+                0u64.wrapping_sub(PRIME64_1),
+            ],
+            buffer: [0; 32],
+            buffered: 0,
+            total: 0,
+        }
+    }
+
+    const fn write(mut self, bytes: &[u8]) -> Self {
+        let mut n = 0;
+
+        while n < bytes.len() {
+            self.buffer[self.buffered] = bytes[n];
+            self.buffered += 1;
+
+            if self.buffered == 32 {
+                let mut lane = 0;
+
+                while lane < 4 {
+                    let value = read_u64(&self.buffer, lane * 8);
+                    self.lanes[lane] = round(self.lanes[lane], value);
+                    lane += 1;
+                }
+
+                self.buffered = 0;
+            }
+
+            n += 1;
+        }
+
+        self.total += bytes.len() as u64;
+        self
+    }
+
+    const fn finish(self) -> u64 {
+        let mut hash = if self.total >= 32 {
+            let [v1, v2, v3, v4] = self.lanes;
+
+            let mut hash = v1
+                .rotate_left(1)
+                .wrapping_add(v2.rotate_left(7))
+                .wrapping_add(v3.rotate_left(12))
+                .wrapping_add(v4.rotate_left(18));
+
+            hash = merge_round(hash, v1);
+            hash = merge_round(hash, v2);
+            hash = merge_round(hash, v3);
+            merge_round(hash, v4)
+        } else {
+            PRIME64_5
+        };
+
+        hash = hash.wrapping_add(self.total);
+
+        let mut n = 0;
+
+        while n + 8 <= self.buffered {
+            hash ^= round(0, read_u64(&self.buffer, n));
+            hash = hash
+                .rotate_left(27)
+                .wrapping_mul(PRIME64_1)
+                .wrapping_add(PRIME64_4);
+            n += 8;
+        }
+
+        if n + 4 <= self.buffered {
+            let value = u32::from_le_bytes([
+                self.buffer[n],
+                self.buffer[n + 1],
+                self.buffer[n + 2],
+                self.buffer[n + 3],
+            ]);
+
+            hash ^= (value as u64).wrapping_mul(PRIME64_1);
+            hash = hash
+                .rotate_left(23)
+                .wrapping_mul(PRIME64_2)
+                .wrapping_add(PRIME64_3);
+            n += 4;
+        }
+
+        while n < self.buffered {
+            hash ^= (self.buffer[n] as u64).wrapping_mul(PRIME64_5);
+            hash = hash.rotate_left(11).wrapping_mul(PRIME64_1);
+            n += 1;
+        }
+
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(PRIME64_2);
+        hash ^= hash >> 29;
+        hash = hash.wrapping_mul(PRIME64_3);
+        hash ^ (hash >> 32)
+    }
+}
+
+const fn round(acc: u64, value: u64) -> u64 {
+    acc.wrapping_add(value.wrapping_mul(PRIME64_2))
+        .rotate_left(31)
+        .wrapping_mul(PRIME64_1)
+}
+
+const fn merge_round(acc: u64, value: u64) -> u64 {
+    (acc ^ round(0, value))
+        .wrapping_mul(PRIME64_1)
+        .wrapping_add(PRIME64_4)
+}
+
+const fn read_u64(buffer: &[u8; 32], at: usize) -> u64 {
+    u64::from_le_bytes([
+        buffer[at],
+        buffer[at + 1],
+        buffer[at + 2],
+        buffer[at + 3],
+        buffer[at + 4],
+        buffer[at + 5],
+        buffer[at + 6],
+        buffer[at + 7],
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConstHasher, Hash};
+    use crate::Item;
+    use std::hash::Hasher as _;
+
+    #[test]
+    fn test_const_hasher() {
+        let data = (0..=255u8).collect::<Vec<_>>();
+
+        for len in 0..data.len() {
+            let mut expected = twox_hash::XxHash64::default();
+            expected.write(&data[..len]);
+
+            // NB: split the input to make sure that writes are buffered.
+            let (a, b) = data[..len].split_at(len / 3);
+            let actual = ConstHasher::new().write(a).write(b).finish();
+            assert_eq!(actual, expected.finish(), "length {}", len);
+        }
+    }
+
+    #[test]
+    fn test_of_path() {
+        let paths: &[&[&str]] = &[
+            &[],
+            &["main"],
+            &["std", "vec", "Vec"],
+            &["a_fairly_long_module_name", "with_a_long_item_name_inside"],
+        ];
+
+        for path in paths {
+            let item = Item::of(*path);
+            assert_eq!(Hash::of_path(path), Hash::type_hash(&item));
+            assert_eq!(item.type_hash(), Hash::type_hash(&item));
+        }
+    }
+}
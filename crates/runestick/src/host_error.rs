@@ -0,0 +1,106 @@
+use crate::{Panic, ToValue, Value, VmError, VmErrorKind};
+use std::error;
+use std::fmt;
+
+/// An error raised by a native function registered with
+/// [Module::fallible_function][crate::Module::fallible_function], as seen by
+/// scripts.
+///
+/// It preserves the message and the name of the type of the original error,
+/// which scripts can access through `message()` and `type_name()`. It's also
+/// displayed as its message in template strings.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::HostError;
+///
+/// let error = "abc".parse::<i64>().unwrap_err();
+/// let error = HostError::new(&error);
+///
+/// assert!(error.type_name().ends_with("ParseIntError"));
+/// assert_eq!(error.message(), "invalid digit found in string");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostError {
+    /// The name of the type of the original error.
+    type_name: &'static str,
+    /// The message of the original error.
+    message: String,
+}
+
+impl HostError {
+    /// Capture the message and type name of the given error.
+    pub fn new<E>(error: &E) -> Self
+    where
+        E: error::Error,
+    {
+        Self {
+            type_name: std::any::type_name::<E>(),
+            message: error.to_string(),
+        }
+    }
+
+    /// The name of the type of the original error.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// The message of the original error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for HostError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(&self.message)
+    }
+}
+
+/// How errors returned by a native function registered with
+/// [Module::fallible_function][crate::Module::fallible_function] surface in
+/// scripts.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HostErrorMode {
+    /// The function returns `Err` with a [HostError], which preserves both the
+    /// message and the type name of the error.
+    ///
+    /// This is the default.
+    #[default]
+    HostError,
+    /// The function returns `Err` with the message of the error as a string.
+    Message,
+    /// The error aborts the virtual machine as a panic with the message of the
+    /// error, so scripts can't handle it.
+    Panic,
+}
+
+impl HostErrorMode {
+    /// Convert the result of a native function into a script value according
+    /// to the mode.
+    pub(crate) fn convert<T, E>(self, result: Result<T, E>) -> Result<Value, VmError>
+    where
+        T: ToValue,
+        E: error::Error,
+    {
+        let error = match result {
+            Ok(value) => return Ok::<_, Value>(value.to_value()?).to_value(),
+            Err(error) => error,
+        };
+
+        let error = match self {
+            Self::HostError => HostError::new(&error).to_value()?,
+            Self::Message => error.to_string().to_value()?,
+            Self::Panic => {
+                return Err(VmError::from(VmErrorKind::Panic {
+                    reason: Panic::custom(error.to_string()).at_caller(),
+                }));
+            }
+        };
+
+        Err::<Value, _>(error).to_value()
+    }
+}
+
+impl_external!(HostError);
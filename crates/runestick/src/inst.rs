@@ -1,10 +1,11 @@
 use crate::Hash;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Pre-canned panic reasons.
 ///
 /// To formulate a custom reason, use [crate::Panic::custom].
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum PanicReason {
     /// Not implemented.
     NotImplemented,
@@ -40,7 +41,7 @@ impl fmt::Display for PanicReason {
 }
 
 /// An encoded type check.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum TypeCheck {
     /// Matches a unit type.
     Unit,
@@ -79,7 +80,7 @@ impl fmt::Display for TypeCheck {
 }
 
 /// An operation in the stack-based virtual machine.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum Inst {
     /// Not operator. Takes a boolean from the top of the stack  and inverts its
     /// logical value.
@@ -91,6 +92,18 @@ pub enum Inst {
     /// => <bool>
     /// ```
     Not,
+    /// Negation operator. Takes a number from the top of the stack and
+    /// negates it.
+    ///
+    /// This is the result of an `-<a>` expression.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// => <value>
+    /// ```
+    Neg,
     /// Add two things together.
     ///
     /// This is the result of an `<a> + <b>` expression.
@@ -126,7 +139,9 @@ pub enum Inst {
     },
     /// Divide two things.
     ///
-    /// This is the result of an `<a> / <b>` expression.
+    /// This is the result of an `<a> / <b>` expression. Integer division
+    /// rounds towards zero, so `-7 / 2` is `-3`. Use `div_euclid` for
+    /// division which rounds towards negative infinity for positive divisors.
     Div,
     /// Divide a value to the given frame offset.
     ///
@@ -137,7 +152,9 @@ pub enum Inst {
     },
     /// Remainder operation.
     ///
-    /// This is the result of an `<a> % <b>` expression.
+    /// This is the result of an `<a> % <b>` expression. The remainder has the
+    /// same sign as the dividend, so `-7 % 2` is `-1`. Use `rem_euclid` for a
+    /// remainder which is never negative.
     ///
     /// # Operation
     ///
@@ -351,7 +368,12 @@ pub enum Inst {
     /// stack.
     ///
     /// This operation will block the VM until at least one of the underlying
-    /// futures complete.
+    /// futures complete. If `default` is set, the futures are only polled once,
+    /// and if none of them is ready the branch register isn't set and a unit
+    /// is pushed on the stack instead.
+    ///
+    /// Streams and channel receivers can also be selected over, in which case
+    /// the value is the next value they produce.
     ///
     /// # Operation
     ///
@@ -362,6 +384,9 @@ pub enum Inst {
     Select {
         /// The number of futures to poll.
         len: usize,
+        /// If the select has a default branch, which is used if no future is
+        /// immediately ready.
+        default: bool,
     },
     /// Pop the value on the stack, discarding its result.
     ///
@@ -421,6 +446,27 @@ pub enum Inst {
         /// Offset to copy value from.
         offset: usize,
     },
+    /// Advance an integer range stored in the given frame offsets.
+    ///
+    /// If the integer at `counter` is smaller than the integer at `end`, it's
+    /// stored in `binding`, the counter is incremented, and `true` is pushed
+    /// on the stack. Otherwise `false` is pushed on the stack.
+    ///
+    /// This is used to iterate over ranges without allocating an iterator.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// => <boolean>
+    /// ```
+    RangeNext {
+        /// Offset of the counter.
+        counter: usize,
+        /// Offset of the exclusive end of the range.
+        end: usize,
+        /// Offset to store the current value in.
+        binding: usize,
+    },
     /// Drop the value in the given frame offset, cleaning out it's slot in
     /// memory.
     ///
@@ -545,6 +591,38 @@ pub enum Inst {
         /// The offset to jump.
         offset: isize,
     },
+    /// Jump to `offset` relative to the current instruction pointer if the
+    /// top of the stack is `true`, without popping it.
+    ///
+    /// This is used to short-circuit `||` expressions. Values which are not
+    /// booleans never cause a jump.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// => <value>
+    /// ```
+    PeekAndJumpIf {
+        /// Offset to jump to.
+        offset: isize,
+    },
+    /// Jump to `offset` relative to the current instruction pointer if the
+    /// top of the stack is `false`, without popping it.
+    ///
+    /// This is used to short-circuit `&&` expressions. Values which are not
+    /// booleans never cause a jump.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// => <value>
+    /// ```
+    PeekAndJumpIfNot {
+        /// Offset to jump to.
+        offset: isize,
+    },
     /// Push a unit value onto the stack.
     ///
     /// # Operation
@@ -590,6 +668,35 @@ pub enum Inst {
         /// The size of the tuple.
         count: usize,
     },
+    /// Construct a push a hash map onto the stack. The number of entries in the
+    /// map are determined by `count`, and a key followed by its value is popped
+    /// from the stack for each of them.
+    ///
+    /// Later entries replace earlier ones with the same key.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <key, value..>
+    /// => <map>
+    /// ```
+    HashMap {
+        /// The number of entries in the map.
+        count: usize,
+    },
+    /// Construct a push a hash set onto the stack. The number of keys in the
+    /// set are determined by `count` and are popped from the stack.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <key..>
+    /// => <set>
+    /// ```
+    HashSet {
+        /// The number of keys in the set.
+        count: usize,
+    },
     /// Take the tuple that is on top of the stack and push its content onto the
     /// stack.
     ///
@@ -619,6 +726,23 @@ pub enum Inst {
         /// The static slot of the object keys.
         slot: usize,
     },
+    /// Construct a push an object onto the stack, where the keys are only known
+    /// at runtime. The number of entries in the object are determined by
+    /// `count`, and a string key followed by its value is popped from the stack
+    /// for each of them.
+    ///
+    /// Later entries replace earlier ones with the same key.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <key, value..>
+    /// => <object>
+    /// ```
+    DynamicObject {
+        /// The number of entries in the object.
+        count: usize,
+    },
     /// Construct a push an object of the given type onto the stack. The number
     /// of elements in the object are determined the slot of the object keys
     /// `slot` and are popped from the stack.
@@ -701,6 +825,21 @@ pub enum Inst {
         /// The static byte string slot to load the string from.
         slot: usize,
     },
+    /// Load a fully constant value, like a vector or an object literal, from
+    /// the static data section of the unit.
+    ///
+    /// The loaded value is only constructed again if the previously loaded one
+    /// has been modified or is still referenced, so it can be freely mutated.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// => <value>
+    /// ```
+    Static {
+        /// The static value slot to load the value from.
+        slot: usize,
+    },
     /// Pop the given number of values from the stack, and concatenate a string
     /// from them.
     ///
@@ -740,6 +879,17 @@ pub enum Inst {
     /// => <boolean>
     /// ```
     IsNot,
+    /// Convert the second item on the stack into the type on top of the
+    /// stack, like `value as int`.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <type>
+    /// <value>
+    /// => <value>
+    /// ```
+    As,
     /// Pop two values from the stack and test if they are both boolean true.
     ///
     /// # Operation
@@ -948,6 +1098,51 @@ pub enum Inst {
         /// The integer to test against.
         integer: i64,
     },
+    /// Test if the top of the stack is a byte in the inclusive range
+    /// `start..=end`.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// => <boolean>
+    /// ```
+    MatchByteRange {
+        /// The start of the range.
+        start: u8,
+        /// The inclusive end of the range.
+        end: u8,
+    },
+    /// Test if the top of the stack is a character in the inclusive range
+    /// `start..=end`.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// => <boolean>
+    /// ```
+    MatchCharacterRange {
+        /// The start of the range.
+        start: char,
+        /// The inclusive end of the range.
+        end: char,
+    },
+    /// Test if the top of the stack is an integer in the inclusive range
+    /// `start..=end`.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// => <boolean>
+    /// ```
+    MatchIntegerRange {
+        /// The start of the range.
+        start: i64,
+        /// The inclusive end of the range.
+        end: i64,
+    },
     /// Compare the top of the stack against a static string slot.
     ///
     /// # Operation
@@ -1029,6 +1224,19 @@ pub enum Inst {
     /// => <unit>
     /// ```
     YieldUnit,
+    /// Increment the given coverage counter of the unit.
+    ///
+    /// This is only emitted when compiling with coverage enabled.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// =>
+    /// ```
+    Coverage {
+        /// The index of the counter to increment.
+        counter: usize,
+    },
     /// Cause the VM to panic and error out without a reason.
     ///
     /// This should only be used during testing or extreme scenarios that are
@@ -1042,12 +1250,22 @@ pub enum Inst {
 impl fmt::Display for Inst {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Self::RangeNext {
+                counter,
+                end,
+                binding,
+            } => {
+                write!(fmt, "range-next {} {} (binding: {})", counter, end, binding)?;
+            }
             Self::Drop { offset } => {
                 write!(fmt, "drop {}", offset)?;
             }
             Self::Not => {
                 write!(fmt, "not")?;
             }
+            Self::Neg => {
+                write!(fmt, "neg")?;
+            }
             Self::Add => {
                 write!(fmt, "add")?;
             }
@@ -1126,8 +1344,12 @@ impl fmt::Display for Inst {
             Self::Await => {
                 write!(fmt, "await")?;
             }
-            Self::Select { len } => {
+            Self::Select { len, default } => {
                 write!(fmt, "select {}", len)?;
+
+                if *default {
+                    write!(fmt, " (default)")?;
+                }
             }
             Self::Pop => {
                 write!(fmt, "pop")?;
@@ -1186,6 +1408,12 @@ impl fmt::Display for Inst {
             Self::JumpIfBranch { branch, offset } => {
                 write!(fmt, "jump-if-branch {}, {}", branch, offset)?;
             }
+            Self::PeekAndJumpIf { offset } => {
+                write!(fmt, "peek-and-jump-if {}", offset)?;
+            }
+            Self::PeekAndJumpIfNot { offset } => {
+                write!(fmt, "peek-and-jump-if-not {}", offset)?;
+            }
             Self::Unit => {
                 write!(fmt, "unit")?;
             }
@@ -1198,6 +1426,12 @@ impl fmt::Display for Inst {
             Self::Tuple { count } => {
                 write!(fmt, "tuple {}", count)?;
             }
+            Self::HashMap { count } => {
+                write!(fmt, "hash-map {}", count)?;
+            }
+            Self::HashSet { count } => {
+                write!(fmt, "hash-set {}", count)?;
+            }
             Self::PushTuple => {
                 write!(fmt, "push-tuple")?;
             }
@@ -1214,12 +1448,18 @@ impl fmt::Display for Inst {
             Self::Object { slot } => {
                 write!(fmt, "object {}", slot)?;
             }
+            Self::DynamicObject { count } => {
+                write!(fmt, "dynamic-object {}", count)?;
+            }
             Self::String { slot } => {
                 write!(fmt, "string {}", slot)?;
             }
             Self::Bytes { slot } => {
                 write!(fmt, "bytes {}", slot)?;
             }
+            Self::Static { slot } => {
+                write!(fmt, "static {}", slot)?;
+            }
             Self::StringConcat { len, size_hint } => {
                 write!(fmt, "string-concat {}, {}", len, size_hint)?;
             }
@@ -1235,6 +1475,9 @@ impl fmt::Display for Inst {
             Self::IsNot => {
                 write!(fmt, "is-not")?;
             }
+            Self::As => {
+                write!(fmt, "as")?;
+            }
             Self::And => {
                 write!(fmt, "and")?;
             }
@@ -1289,6 +1532,15 @@ impl fmt::Display for Inst {
             Self::EqInteger { integer } => {
                 write!(fmt, "eq-integer {}", integer)?;
             }
+            Self::MatchByteRange { start, end } => {
+                write!(fmt, "match-byte-range {:?}..={:?}", start, end)?;
+            }
+            Self::MatchCharacterRange { start, end } => {
+                write!(fmt, "match-character-range {:?}..={:?}", start, end)?;
+            }
+            Self::MatchIntegerRange { start, end } => {
+                write!(fmt, "match-integer-range {}..={}", start, end)?;
+            }
             Self::EqStaticString { slot } => {
                 write!(fmt, "eq-static-string {}", slot)?;
             }
@@ -1315,6 +1567,9 @@ impl fmt::Display for Inst {
             Self::YieldUnit => {
                 write!(fmt, "yield-unit")?;
             }
+            Self::Coverage { counter } => {
+                write!(fmt, "coverage {}", counter)?;
+            }
             Self::Panic { reason } => {
                 write!(fmt, "panic {}", reason.ident())?;
             }
@@ -0,0 +1,115 @@
+use crate::{Args, Context, IntegerOverflow, IntoHash, Stack, Unit, Vm, VmError, VmExecution};
+use std::sync::Arc;
+
+/// An instance of a compiled unit, which virtual machines are constructed
+/// from.
+///
+/// A unit is compiled once and can then be instantiated any number of times.
+/// Instances share the unit and the context they were created with, so they
+/// are cheap to construct and clone, but every call through an instance runs
+/// on a fresh virtual machine with a stack of its own. This means that many
+/// independent copies of a single script can be run side by side, and that any
+/// function in the unit can be used as an entry point.
+///
+/// Settings applied to an instance, like its stack limit, are inherited by all
+/// the virtual machines it constructs.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Context, Instance, IntegerOverflow, Item, Unit};
+/// use std::sync::Arc;
+///
+/// # fn main() -> runestick::Result<()> {
+/// let context = Arc::new(Context::with_default_modules()?);
+/// let unit = Arc::new(Unit::default());
+///
+/// let first = Instance::new(context.clone(), unit.clone());
+/// let mut second = Instance::new(context, unit);
+/// second.set_integer_overflow(IntegerOverflow::Wrapping);
+///
+/// assert_eq!(first.integer_overflow(), IntegerOverflow::Error);
+/// assert_eq!(second.integer_overflow(), IntegerOverflow::Wrapping);
+/// assert!(Arc::ptr_eq(first.unit(), second.unit()));
+///
+/// // NB: the empty unit doesn't contain any functions.
+/// assert!(first.call(Item::of(&["main"]), ()).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Instance {
+    /// Context of the instance.
+    context: Arc<Context>,
+    /// Unit being instantiated.
+    unit: Arc<Unit>,
+    /// How integer arithmetic which overflows is handled.
+    integer_overflow: IntegerOverflow,
+    /// The maximum number of values on the stack of every virtual machine.
+    stack_limit: Option<usize>,
+}
+
+impl Instance {
+    /// Construct a new instance of the given unit.
+    pub fn new(context: Arc<Context>, unit: Arc<Unit>) -> Self {
+        Self {
+            context,
+            unit,
+            integer_overflow: IntegerOverflow::Error,
+            stack_limit: None,
+        }
+    }
+
+    /// Access the context of the instance.
+    pub fn context(&self) -> &Arc<Context> {
+        &self.context
+    }
+
+    /// Access the unit of the instance.
+    pub fn unit(&self) -> &Arc<Unit> {
+        &self.unit
+    }
+
+    /// Get how integer arithmetic which overflows is handled.
+    pub fn integer_overflow(&self) -> IntegerOverflow {
+        self.integer_overflow
+    }
+
+    /// Set how integer arithmetic which overflows is handled by the virtual
+    /// machines constructed from this instance.
+    pub fn set_integer_overflow(&mut self, integer_overflow: IntegerOverflow) {
+        self.integer_overflow = integer_overflow;
+    }
+
+    /// Get the maximum number of values on the stack, if limited.
+    pub fn stack_limit(&self) -> Option<usize> {
+        self.stack_limit
+    }
+
+    /// Limit the number of values on the stack of the virtual machines
+    /// constructed from this instance.
+    pub fn set_stack_limit(&mut self, stack_limit: Option<usize>) {
+        self.stack_limit = stack_limit;
+    }
+
+    /// Construct a new virtual machine for the instance.
+    pub fn vm(&self) -> Vm {
+        let mut stack = Stack::new();
+        stack.set_limit(self.stack_limit);
+
+        let mut vm = Vm::new_with_stack(self.context.clone(), self.unit.clone(), stack);
+        vm.set_integer_overflow(self.integer_overflow);
+        vm
+    }
+
+    /// Call the given function in the unit on a new virtual machine.
+    ///
+    /// See [Vm::call].
+    pub fn call<A, N>(&self, name: N, args: A) -> Result<VmExecution, VmError>
+    where
+        N: IntoHash,
+        A: Args,
+    {
+        self.vm().call(name, args)
+    }
+}
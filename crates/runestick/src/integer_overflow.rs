@@ -0,0 +1,125 @@
+use std::fmt;
+use std::str;
+
+/// How integer arithmetic which overflows is handled by a virtual machine.
+///
+/// This applies to the `+`, `-`, `*`, and `/` operators, their assign
+/// variants, and negation. Dividing by zero is always an error.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::IntegerOverflow;
+///
+/// assert_eq!(IntegerOverflow::default(), IntegerOverflow::Error);
+/// assert_eq!("wrapping".parse::<IntegerOverflow>(), Ok(IntegerOverflow::Wrapping));
+/// assert!("clamp".parse::<IntegerOverflow>().is_err());
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IntegerOverflow {
+    /// Overflow raises an error. This is the default.
+    #[default]
+    Error,
+    /// Overflow wraps around at the boundary of the integer.
+    Wrapping,
+    /// Overflow saturates at the minimum or maximum value of the integer.
+    Saturating,
+}
+
+impl IntegerOverflow {
+    /// Add two integers.
+    pub(crate) fn add(self, a: i64, b: i64) -> Option<i64> {
+        match self {
+            Self::Error => a.checked_add(b),
+            Self::Wrapping => Some(a.wrapping_add(b)),
+            Self::Saturating => Some(a.saturating_add(b)),
+        }
+    }
+
+    /// Subtract two integers.
+    pub(crate) fn sub(self, a: i64, b: i64) -> Option<i64> {
+        match self {
+            Self::Error => a.checked_sub(b),
+            Self::Wrapping => Some(a.wrapping_sub(b)),
+            Self::Saturating => Some(a.saturating_sub(b)),
+        }
+    }
+
+    /// Multiply two integers.
+    pub(crate) fn mul(self, a: i64, b: i64) -> Option<i64> {
+        match self {
+            Self::Error => a.checked_mul(b),
+            Self::Wrapping => Some(a.wrapping_mul(b)),
+            Self::Saturating => Some(a.saturating_mul(b)),
+        }
+    }
+
+    /// Divide two integers, which fails regardless of mode if dividing by
+    /// zero.
+    pub(crate) fn div(self, a: i64, b: i64) -> Option<i64> {
+        if b == 0 {
+            return None;
+        }
+
+        match self {
+            Self::Error => a.checked_div(b),
+            Self::Wrapping => Some(a.wrapping_div(b)),
+            Self::Saturating => Some(a.checked_div(b).unwrap_or(i64::MAX)),
+        }
+    }
+
+    /// The remainder of dividing two integers, which has the same sign as the
+    /// dividend. This only fails if dividing by zero, since the remainder of
+    /// `i64::MIN % -1` is `0`.
+    pub(crate) fn rem(self, a: i64, b: i64) -> Option<i64> {
+        if b == 0 {
+            return None;
+        }
+
+        Some(a.wrapping_rem(b))
+    }
+
+    /// Negate an integer.
+    pub(crate) fn neg(self, a: i64) -> Option<i64> {
+        match self {
+            Self::Error => a.checked_neg(),
+            Self::Wrapping => Some(a.wrapping_neg()),
+            Self::Saturating => Some(a.saturating_neg()),
+        }
+    }
+}
+
+impl fmt::Display for IntegerOverflow {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(fmt, "error"),
+            Self::Wrapping => write!(fmt, "wrapping"),
+            Self::Saturating => write!(fmt, "saturating"),
+        }
+    }
+}
+
+impl str::FromStr for IntegerOverflow {
+    type Err = ParseIntegerOverflowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(Self::Error),
+            "wrapping" => Ok(Self::Wrapping),
+            "saturating" => Ok(Self::Saturating),
+            _ => Err(ParseIntegerOverflowError),
+        }
+    }
+}
+
+/// Error raised when parsing an unknown [IntegerOverflow] mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseIntegerOverflowError;
+
+impl fmt::Display for ParseIntegerOverflowError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "expected one of `error`, `wrapping`, or `saturating`")
+    }
+}
+
+impl std::error::Error for ParseIntegerOverflowError {}
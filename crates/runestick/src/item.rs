@@ -1,3 +1,5 @@
+use crate::Hash;
+use serde::{Deserialize, Serialize};
 use std::convert;
 use std::fmt;
 
@@ -5,7 +7,7 @@ use std::fmt;
 ///
 /// This is made up of a collection of strings, like `["foo", "bar"]`.
 /// This is indicated in rune as `foo::bar`.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
 pub struct Item {
     path: Vec<Component>,
 }
@@ -97,6 +99,14 @@ impl Item {
     pub fn last(&self) -> Option<&Component> {
         self.path.last()
     }
+
+    /// Get the hash of the item.
+    ///
+    /// This is the same as `Hash::type_hash(&item)`, but doesn't clone the
+    /// components of the item to hash them.
+    pub fn type_hash(&self) -> Hash {
+        Hash::item_hash(self)
+    }
 }
 
 impl fmt::Display for Item {
@@ -134,7 +144,7 @@ impl<'a> IntoIterator for &'a Item {
 }
 
 /// The component of an item.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Component {
     /// A regular string component.
     String(String),
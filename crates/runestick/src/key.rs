@@ -0,0 +1,105 @@
+use crate::{Bytes, FromValue, Shared, ToValue, Tuple, Value, VmError, VmErrorKind};
+
+/// A value which can be used as a key in a hash map or a hash set.
+///
+/// Only values which can be compared for equality and hashed are supported,
+/// which are units, booleans, bytes, chars, integers, strings, byte strings
+/// and tuples of keys. Keys are copied out of the value they're constructed
+/// from, so modifying a string after it's been used as a key doesn't affect
+/// the key.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Key, Value};
+///
+/// let key = Key::from_value(&Value::Integer(42)).unwrap();
+/// assert_eq!(key, Key::Integer(42));
+/// assert!(Key::from_value(&Value::Float(4.2)).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Key {
+    /// A unit key.
+    Unit,
+    /// A boolean key.
+    Bool(bool),
+    /// A byte key.
+    Byte(u8),
+    /// A char key.
+    Char(char),
+    /// An integer key.
+    Integer(i64),
+    /// A string key.
+    String(String),
+    /// A byte string key.
+    Bytes(Vec<u8>),
+    /// A tuple of keys.
+    Tuple(Box<[Key]>),
+}
+
+impl Key {
+    /// Construct a key from the given value.
+    ///
+    /// Errors with [VmErrorKind::UnsupportedKey] if the value can't be used
+    /// as a key.
+    pub fn from_value(value: &Value) -> Result<Self, VmError> {
+        Ok(match value {
+            Value::Unit => Self::Unit,
+            Value::Bool(b) => Self::Bool(*b),
+            Value::Byte(b) => Self::Byte(*b),
+            Value::Char(c) => Self::Char(*c),
+            Value::Integer(n) => Self::Integer(*n),
+            Value::StaticString(string) => Self::String((***string).to_owned()),
+            Value::String(string) => Self::String(string.borrow_ref()?.clone()),
+            Value::Bytes(bytes) => Self::Bytes(bytes.borrow_ref()?.bytes.clone()),
+            Value::Tuple(tuple) => {
+                let tuple = tuple.borrow_ref()?;
+                let mut keys = Vec::with_capacity(tuple.len());
+
+                for value in tuple.iter() {
+                    keys.push(Self::from_value(value)?);
+                }
+
+                Self::Tuple(keys.into_boxed_slice())
+            }
+            actual => {
+                return Err(VmError::from(VmErrorKind::UnsupportedKey {
+                    actual: actual.type_info()?,
+                }))
+            }
+        })
+    }
+
+    /// Convert the key back into a value.
+    pub fn into_value(self) -> Value {
+        match self {
+            Self::Unit => Value::Unit,
+            Self::Bool(b) => Value::Bool(b),
+            Self::Byte(b) => Value::Byte(b),
+            Self::Char(c) => Value::Char(c),
+            Self::Integer(n) => Value::Integer(n),
+            Self::String(string) => Value::String(Shared::new(string)),
+            Self::Bytes(bytes) => Value::Bytes(Shared::new(Bytes::from_vec(bytes))),
+            Self::Tuple(keys) => {
+                let values = Vec::from(keys)
+                    .into_iter()
+                    .map(Self::into_value)
+                    .collect::<Vec<_>>();
+
+                Value::Tuple(Shared::new(Tuple::from(values)))
+            }
+        }
+    }
+}
+
+impl FromValue for Key {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Self::from_value(&value)
+    }
+}
+
+impl ToValue for Key {
+    fn to_value(self) -> Result<Value, VmError> {
+        Ok(self.into_value())
+    }
+}
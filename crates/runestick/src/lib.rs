@@ -38,6 +38,7 @@
 #![deny(missing_docs)]
 
 mod any;
+mod api_version;
 mod context;
 mod value;
 mod vm;
@@ -48,34 +49,56 @@ mod args;
 mod awaited;
 mod bytes;
 mod call;
+mod caller;
+#[cfg(feature = "census")]
+mod census;
 mod compile_meta;
+mod coverage;
 pub mod debug;
+mod event_log;
+mod finalizer;
+mod float_display;
 mod function;
 mod future;
 mod generator;
 mod generator_state;
 mod hash;
+mod host_error;
 mod inst;
+mod instance;
+mod integer_overflow;
 mod item;
+mod key;
 mod label;
+mod memoize;
+#[cfg(feature = "metrics")]
+mod metrics;
 pub mod module;
 pub mod modules;
 mod names;
+mod object_key;
+mod overrides;
 mod panic;
 mod protocol;
+mod recording;
 mod reflection;
 mod select;
 mod serde;
 mod shared;
 mod source;
 mod span;
+#[cfg(feature = "tracing")]
+mod spans;
 mod stack;
 mod static_string;
 mod static_type;
+mod static_value;
 mod stream;
+mod transfer;
 mod tuple;
 mod type_;
 mod type_info;
+mod type_names;
 mod unit;
 mod vec_tuple;
 mod vm_call;
@@ -91,7 +114,7 @@ pub type Result<T, E = anyhow::Error> = std::result::Result<T, E>;
 /// Exported boxed error type for convenience.
 pub type Error = anyhow::Error;
 
-pub use self::args::Args;
+pub use self::args::{Args, FromArgs};
 pub use self::compile_meta::{
     CompileMeta, CompileMetaCapture, CompileMetaStruct, CompileMetaTuple,
 };
@@ -108,36 +131,56 @@ pub use self::static_type::{
     FUTURE_TYPE, GENERATOR_STATE_TYPE, GENERATOR_TYPE, INTEGER_TYPE, OBJECT_TYPE, OPTION_TYPE,
     RESULT_TYPE, STREAM_TYPE, STRING_TYPE, TUPLE_TYPE, UNIT_TYPE, VEC_TYPE,
 };
+pub use self::static_value::StaticValue;
 pub use self::stream::Stream;
 pub use self::tuple::Tuple;
 pub use self::type_::Type;
 pub use self::type_info::TypeInfo;
+pub use self::type_names::TypeNames;
 pub use crate::access::{
-    AccessError, BorrowMut, BorrowRef, NotAccessibleMut, NotAccessibleRef, RawBorrowedMut,
-    RawBorrowedRef,
+    AccessError, BorrowMut, BorrowRef, BorrowSite, NotAccessibleMut, NotAccessibleRef,
+    NotAccessibleTake, RawBorrowedMut, RawBorrowedRef, Snapshot,
 };
 pub use crate::any::{Any, AnyVtable};
+pub use crate::api_version::{ApiVersion, ParseApiVersionError};
 pub use crate::awaited::Awaited;
 pub use crate::bytes::Bytes;
 pub use crate::call::Call;
-pub use crate::context::{Context, ContextError};
+pub use crate::caller::Caller;
+#[cfg(feature = "census")]
+pub use crate::census::{Census, CensusEntry};
+pub use crate::context::{Context, ContextError, ContextInterface, ContextSignature};
+pub use crate::coverage::{Coverage, CoverageCounter};
 pub use crate::debug::{DebugInfo, DebugInst};
+pub use crate::event_log::{Event, EventLog};
+pub use crate::finalizer::FinalizerFuture;
+pub use crate::float_display::FloatDisplay;
 pub use crate::function::Function;
 pub use crate::future::Future;
 pub use crate::hash::{Hash, IntoHash};
+pub use crate::host_error::{HostError, HostErrorMode};
 pub use crate::inst::{Inst, PanicReason, TypeCheck};
+pub use crate::instance::Instance;
+pub use crate::integer_overflow::{IntegerOverflow, ParseIntegerOverflowError};
 pub use crate::item::{Component, Item};
+pub use crate::key::Key;
+#[cfg(feature = "metrics")]
+pub use crate::metrics::Recorder;
 pub use crate::names::Names;
+pub use crate::overrides::Overrides;
 pub use crate::panic::Panic;
 pub use crate::protocol::{
     Protocol, ADD, ADD_ASSIGN, BIT_AND, BIT_AND_ASSIGN, BIT_OR, BIT_OR_ASSIGN, BIT_XOR,
-    BIT_XOR_ASSIGN, DIV, DIV_ASSIGN, INDEX_GET, INDEX_SET, INTO_FUTURE, INTO_ITER, MUL, MUL_ASSIGN,
-    NEXT, REM, REM_ASSIGN, SHL, SHL_ASSIGN, SHR, SHR_ASSIGN, STRING_DISPLAY, SUB, SUB_ASSIGN,
+    BIT_XOR_ASSIGN, DIV, DIV_ASSIGN, EQ, HASH, INDEX_GET, INDEX_SET, INTO_FUTURE, INTO_ITER, MUL,
+    MUL_ASSIGN, NEG, NEXT, REM, REM_ASSIGN, SHL, SHL_ASSIGN, SHR, SHR_ASSIGN, STRING_DEBUG,
+    STRING_DISPLAY, SUB, SUB_ASSIGN,
 };
+pub use crate::recording::{RecordedCall, Recording};
 pub use crate::reflection::{FromValue, ToValue, UnsafeFromValue, ValueType};
 pub use crate::shared::{OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef, Shared};
-pub use crate::stack::{Stack, StackError};
-pub use crate::unit::{Unit, UnitFn, UnitTypeInfo};
+pub use crate::stack::{Stack, StackError, StackFrameGuard};
+pub use crate::transfer::Transfer;
+pub use crate::unit::{Unit, UnitFn, UnitStats, UnitTypeInfo};
 pub use crate::value::{
     Integer, Object, TupleVariant, TypedObject, TypedTuple, Value, VariantObject,
 };
@@ -69,6 +69,29 @@ macro_rules! impl_external {
     };
 }
 
+/// Calculate the hash of an item path at compile time.
+///
+/// This produces the same hash as [Hash::type_hash][crate::Hash::type_hash]
+/// would for the same path, and can be used to call functions or look up types
+/// without hashing their names at runtime.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Hash, Item};
+///
+/// const VEC: Hash = runestick::hash!(std::vec::Vec);
+/// assert_eq!(VEC, Hash::type_hash(Item::of(&["std", "vec", "Vec"])));
+/// assert_eq!(runestick::hash!(main), Hash::type_hash(&["main"]));
+/// ```
+#[macro_export]
+macro_rules! hash {
+    ($($component:ident)::+) => {{
+        const HASH: $crate::Hash = $crate::Hash::of_path(&[$(stringify!($component)),+]);
+        HASH
+    }};
+}
+
 /// Build an implementation of `ValueType` basic of a static type.
 macro_rules! impl_static_type {
     (impl <$($p:ident),*> $ty:ty => $static_type:expr) => {
@@ -0,0 +1,167 @@
+//! Caching of calls to pure functions, used by `std::cache::memoize`.
+
+use crate::collections::HashMap;
+use crate::{Context, FromValue as _, Function, Hash, Key, Stack, Type, Value, VmError};
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Limits on how many results a memoized function caches, and for how long.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct CacheLimits {
+    /// The maximum number of cached results. Once reached, the least recently
+    /// used result is evicted.
+    pub(crate) capacity: Option<usize>,
+    /// How long a result is cached for.
+    pub(crate) ttl: Option<Duration>,
+}
+
+/// A function whose results are cached by the arguments it's called with.
+pub(crate) struct Memoized {
+    /// The function being memoized.
+    function: Function,
+    /// The context used to look up the [HASH][crate::HASH] protocol of
+    /// external arguments.
+    context: Option<Arc<Context>>,
+    /// Limits on the cache.
+    limits: CacheLimits,
+    /// The cached results.
+    cache: RefCell<Cache>,
+}
+
+impl Memoized {
+    /// Memoize the given function.
+    pub(crate) fn new(
+        function: Function,
+        context: Option<Arc<Context>>,
+        limits: CacheLimits,
+    ) -> Self {
+        Self {
+            function,
+            context,
+            limits,
+            cache: RefCell::new(Cache::default()),
+        }
+    }
+
+    /// Call the function with the given arguments, or return the result of an
+    /// earlier call with equal arguments.
+    pub(crate) fn call(&self, args: Vec<Value>) -> Result<Value, VmError> {
+        let key = args
+            .iter()
+            .map(|value| MemoKey::from_value(self.context.as_deref(), value))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some(value) = self.cache.borrow_mut().get(&key, self.limits.ttl) {
+            return Ok(value);
+        }
+
+        // NB: the cache isn't borrowed while calling the function, since it
+        // might call the memoized function again.
+        let value = self.function.call_values(args)?;
+
+        self.cache
+            .borrow_mut()
+            .insert(key, value.clone(), self.limits.capacity);
+
+        Ok(value)
+    }
+}
+
+impl fmt::Debug for Memoized {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Memoized")
+            .field("function", &self.function)
+            .field("limits", &self.limits)
+            .finish()
+    }
+}
+
+/// The key of a single argument.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum MemoKey {
+    /// A value which can be used as a key as-is.
+    Key(Key),
+    /// An external value, hashed through the [HASH][crate::HASH] protocol.
+    Hashed(Type, i64),
+}
+
+impl MemoKey {
+    fn from_value(context: Option<&Context>, value: &Value) -> Result<Self, VmError> {
+        if let Value::Any(..) = value {
+            let value_type = value.value_type()?;
+            let hash = Hash::instance_function(value_type, crate::HASH);
+
+            if let Some(handler) = context.and_then(|context| context.lookup(hash)) {
+                let mut stack = Stack::new();
+                stack.push(value.clone());
+                handler(&mut stack, 1)?;
+                return Ok(Self::Hashed(value_type, i64::from_value(stack.pop()?)?));
+            }
+        }
+
+        Ok(Self::Key(Key::from_value(value)?))
+    }
+}
+
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<Vec<MemoKey>, Entry>,
+    /// Counter used to track how recently entries were used.
+    tick: u64,
+}
+
+struct Entry {
+    value: Value,
+    inserted: Instant,
+    used: u64,
+}
+
+impl Cache {
+    fn get(&mut self, key: &[MemoKey], ttl: Option<Duration>) -> Option<Value> {
+        let entry = self.entries.get_mut(key)?;
+
+        if let Some(ttl) = ttl {
+            if entry.inserted.elapsed() >= ttl {
+                self.entries.remove(key);
+                return None;
+            }
+        }
+
+        self.tick += 1;
+        entry.used = self.tick;
+        Some(entry.value.clone())
+    }
+
+    fn insert(&mut self, key: Vec<MemoKey>, value: Value, capacity: Option<usize>) {
+        if let Some(capacity) = capacity {
+            if capacity == 0 {
+                return;
+            }
+
+            if self.entries.len() >= capacity && !self.entries.contains_key(&key) {
+                let oldest = self
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.used)
+                    .map(|(key, _)| key.clone());
+
+                if let Some(oldest) = oldest {
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+
+        self.tick += 1;
+
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                inserted: Instant::now(),
+                used: self.tick,
+            },
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Instrumentation of the virtual machine.
+//!
+//! This is only available with the `metrics` feature. Embedders install a
+//! [Recorder] in the [Context][crate::Context] with
+//! [Context::set_recorder][crate::Context::set_recorder], and forward what
+//! it observes to their metrics system of choice, like prometheus.
+
+use crate::{Vm, VmError, VmErrorKind, VmHalt};
+use std::sync::Arc;
+
+/// Receives instrumentation events from virtual machines.
+///
+/// Every method has a default implementation which does nothing, so a
+/// recorder only needs to implement the events it cares about.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Context, Recorder};
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use std::sync::Arc;
+///
+/// #[derive(Default)]
+/// struct Instructions(AtomicU64);
+///
+/// impl Recorder for Instructions {
+///     fn instructions_executed(&self, count: u64) {
+///         self.0.fetch_add(count, Ordering::Relaxed);
+///     }
+/// }
+///
+/// let mut context = Context::new();
+/// context.set_recorder(Arc::new(Instructions::default()));
+/// ```
+pub trait Recorder: 'static + Send + Sync {
+    /// An execution was started, like through [Vm::call].
+    fn execution_started(&self) {}
+
+    /// The given number of instructions were executed.
+    ///
+    /// This is reported every time the virtual machine stops running, rather
+    /// than once per instruction.
+    fn instructions_executed(&self, count: u64) {
+        let _ = count;
+    }
+
+    /// Running an instruction resulted in the given error.
+    ///
+    /// Errors are only reported by the execution which raised them, and not
+    /// again by any execution they propagate through.
+    fn error(&self, kind: &VmErrorKind) {
+        let _ = kind;
+    }
+
+    /// The virtual machine suspended to await a future.
+    fn awaited(&self) {}
+}
+
+/// Get the recorder installed in the context of the virtual machine.
+fn recorder(vm: &Vm) -> Option<&Arc<dyn Recorder>> {
+    vm.context().recorder()
+}
+
+/// Record that an execution was started with the given virtual machine.
+pub(crate) fn execution_started(vm: &Vm) {
+    if let Some(recorder) = recorder(vm) {
+        recorder.execution_started();
+    }
+}
+
+/// Record the outcome of running the given virtual machine.
+pub(crate) fn record_run(vm: &mut Vm, result: &Result<VmHalt, VmError>) {
+    let executed = vm.take_executed();
+
+    let recorder = match recorder(vm) {
+        Some(recorder) => recorder,
+        None => return,
+    };
+
+    recorder.instructions_executed(executed);
+
+    match result {
+        Ok(VmHalt::Awaited(..)) => recorder.awaited(),
+        Err(error) => {
+            if let VmErrorKind::Unwound { .. } = error.kind() {
+                return;
+            }
+
+            recorder.error(error.kind());
+        }
+        _ => (),
+    }
+}
@@ -5,15 +5,16 @@
 
 use crate::collections::HashMap;
 use crate::{
-    Component, Future, Hash, Stack, ToValue, Type, TypeInfo, UnsafeFromValue, ValueType, VmError,
-    VmErrorKind,
+    Component, Future, Hash, Protocol, Stack, ToValue, Type, TypeInfo, UnsafeFromValue, ValueType,
+    VmError, VmErrorKind,
 };
 use std::any::type_name;
 use std::future;
 use std::sync::Arc;
 
 use crate::context::{ContextError, Handler, Macro};
-use crate::{GeneratorState, Item, StaticType, TypeCheck, Value};
+use crate::finalizer::{Finalizer, FinalizerFuture};
+use crate::{GeneratorState, HostErrorMode, Item, StaticType, TypeCheck, Value};
 
 /// Specialized information on `Option` types.
 pub(crate) struct ModuleUnitType {
@@ -87,6 +88,9 @@ pub(crate) struct ModuleType {
     pub(crate) name: Item,
     /// Type information for the installed type.
     pub(crate) type_info: TypeInfo,
+    /// Finalizer called when a value of the type is dropped by the virtual
+    /// machine.
+    pub(crate) finalizer: Option<Arc<Finalizer>>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -145,6 +149,10 @@ pub struct Module {
     pub(crate) unit_type: Option<ModuleUnitType>,
     /// Registered generator state type.
     pub(crate) internal_enums: Vec<ModuleInternalEnum>,
+    /// Custom protocols declared by the module.
+    pub(crate) protocols: HashMap<&'static str, Protocol>,
+    /// Interfaces declared by the module.
+    pub(crate) interfaces: HashMap<Item, Vec<Protocol>>,
 }
 
 impl Module {
@@ -162,7 +170,63 @@ impl Module {
             types: Default::default(),
             unit_type: None,
             internal_enums: Vec::new(),
+            protocols: Default::default(),
+            interfaces: Default::default(),
+        }
+    }
+
+    /// Declare a custom protocol, constructed with [Protocol::custom].
+    ///
+    /// Once the module is installed, calls in scripts to an instance function
+    /// with the same name as the protocol resolve to the protocol, for both
+    /// native types and types declared in scripts. Installing a protocol with
+    /// the same name as a native instance function is an error, since calls
+    /// to the function would resolve to the protocol instead.
+    pub fn protocol(&mut self, protocol: Protocol) -> Result<(), ContextError> {
+        if let Some(existing) = self.protocols.get(protocol.name) {
+            if existing.hash != protocol.hash {
+                return Err(ContextError::ConflictingProtocol {
+                    name: protocol.name,
+                });
+            }
         }
+
+        self.protocols.insert(protocol.name, protocol);
+        Ok(())
+    }
+
+    /// Declare an interface, which bundles a collection of protocols.
+    ///
+    /// Once the module is installed, `value is Interface` in scripts tests if
+    /// the type of the value implements every protocol in the interface,
+    /// through either native or script instance functions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Module, Protocol};
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let matches = Protocol::custom("matches");
+    ///
+    /// let mut module = Module::new(&["dsl"]);
+    /// module.protocol(matches)?;
+    /// module.interface(&["Matcher"], &[matches, runestick::STRING_DISPLAY])?;
+    /// # Ok(()) }
+    /// ```
+    pub fn interface<N>(&mut self, name: N, protocols: &[Protocol]) -> Result<(), ContextError>
+    where
+        N: IntoIterator,
+        N::Item: Into<Component>,
+    {
+        let name = Item::of(name);
+
+        if self.interfaces.contains_key(&name) {
+            return Err(ContextError::ConflictingInterface { name });
+        }
+
+        self.interfaces.insert(name, protocols.to_vec());
+        Ok(())
     }
 
     /// Register a type. Registering a type is mandatory in order to register
@@ -392,6 +456,61 @@ impl Module {
         Ok(())
     }
 
+    /// Register a function returning a [Result] whose errors scripts can
+    /// handle.
+    ///
+    /// Instead of aborting the virtual machine, errors returned by the
+    /// function surface in scripts as `Err` values, converted according to the
+    /// given [HostErrorMode]. The default mode wraps them in a
+    /// [HostError][crate::HostError], which preserves the message and the type
+    /// name of the error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::HostErrorMode;
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let mut module = runestick::Module::default();
+    ///
+    /// module.fallible_function(&["parse"], HostErrorMode::default(), |s: &str| {
+    ///     s.parse::<i64>()
+    /// })?;
+    ///
+    /// module.fallible_function(&["read"], HostErrorMode::Message, |path: &str| {
+    ///     std::fs::read_to_string(path)
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fallible_function<Func, Args, N>(
+        &mut self,
+        name: N,
+        mode: HostErrorMode,
+        f: Func,
+    ) -> Result<(), ContextError>
+    where
+        Func: FallibleFunction<Args>,
+        N: IntoIterator,
+        N::Item: Into<Component>,
+    {
+        let name = Item::of(name);
+
+        if self.functions.contains_key(&name) {
+            return Err(ContextError::ConflictingFunctionName { name });
+        }
+
+        self.functions.insert(
+            name,
+            ModuleFn {
+                handler: Arc::new(move |stack, args| f.fn_call(stack, args, mode)),
+                args: Some(Func::args()),
+            },
+        );
+
+        Ok(())
+    }
+
     /// Register a native macro handler.
     pub fn macro_<N, M, A, B, O>(&mut self, name: N, f: M) -> Result<(), ContextError>
     where
@@ -668,6 +787,72 @@ where
 {
     /// Construct a new type, specifying which type it is with the parameter.
     pub fn build<T>(self) -> Result<(), ContextError>
+    where
+        T: ValueType,
+    {
+        self.build_type::<T>(None)
+    }
+
+    /// Construct a new type with a finalizer, which takes ownership of values
+    /// of the type as they are dropped.
+    ///
+    /// This allows resources owned by scripts, like sockets or files, to be
+    /// closed deterministically. Only values constructed while a virtual
+    /// machine is running are finalized, and values which the host takes out
+    /// of a virtual machine are dropped as usual.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// #[derive(Debug)]
+    /// struct Socket;
+    ///
+    /// impl Socket {
+    ///     fn close(self) {}
+    /// }
+    ///
+    /// runestick::impl_external!(Socket);
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let mut module = runestick::Module::new(&["net"]);
+    /// module.ty(&["Socket"]).build_with_finalizer(Socket::close)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn build_with_finalizer<T, F>(self, f: F) -> Result<(), ContextError>
+    where
+        T: ValueType + std::any::Any,
+        F: 'static + Send + Sync + Fn(T),
+    {
+        let finalizer = crate::finalizer::finalizer(move |value: T| {
+            f(value);
+            None
+        });
+
+        self.build_type::<T>(Some(finalizer))
+    }
+
+    /// Construct a new type with an async finalizer, which takes ownership of
+    /// values of the type as they are dropped.
+    ///
+    /// The future produced by the finalizer is spawned on the executor
+    /// configured with
+    /// [Context::set_finalizer_executor][crate::Context::set_finalizer_executor].
+    pub fn build_with_async_finalizer<T, F, O>(self, f: F) -> Result<(), ContextError>
+    where
+        T: ValueType + std::any::Any,
+        F: 'static + Send + Sync + Fn(T) -> O,
+        O: 'static + Send + future::Future<Output = ()>,
+    {
+        let finalizer = crate::finalizer::finalizer(move |value: T| {
+            let future: FinalizerFuture = Box::pin(f(value));
+            Some(future)
+        });
+
+        self.build_type::<T>(Some(finalizer))
+    }
+
+    fn build_type<T>(self, finalizer: Option<Arc<Finalizer>>) -> Result<(), ContextError>
     where
         T: ValueType,
     {
@@ -678,6 +863,7 @@ where
         let ty = ModuleType {
             name: name.clone(),
             type_info,
+            finalizer,
         };
 
         if let Some(old) = self.types.insert(value_type, ty) {
@@ -711,7 +897,7 @@ impl<'a> IntoInstFnHash for &'a str {
 }
 
 /// Trait used to provide the [function][Module::function] function.
-pub trait Function<Args>: 'static + Copy + Send + Sync {
+pub trait Function<Args>: 'static + Send + Sync {
     /// The return type of the function.
     type Return;
 
@@ -719,11 +905,24 @@ pub trait Function<Args>: 'static + Copy + Send + Sync {
     fn args() -> usize;
 
     /// Perform the vm call.
-    fn fn_call(self, stack: &mut Stack, args: usize) -> Result<(), VmError>;
+    fn fn_call(&self, stack: &mut Stack, args: usize) -> Result<(), VmError>;
+}
+
+/// Trait used to provide the [fallible_function][Module::fallible_function]
+/// function.
+pub trait FallibleFunction<Args>: 'static + Send + Sync {
+    /// The type of the error returned by the function.
+    type Error;
+
+    /// Get the number of arguments.
+    fn args() -> usize;
+
+    /// Perform the vm call, converting errors according to the given mode.
+    fn fn_call(&self, stack: &mut Stack, args: usize, mode: HostErrorMode) -> Result<(), VmError>;
 }
 
 /// Trait used to provide the [async_function][Module::async_function] function.
-pub trait AsyncFunction<Args>: 'static + Copy + Send + Sync {
+pub trait AsyncFunction<Args>: 'static + Send + Sync {
     /// The return type of the function.
     type Return;
 
@@ -731,11 +930,11 @@ pub trait AsyncFunction<Args>: 'static + Copy + Send + Sync {
     fn args() -> usize;
 
     /// Perform the vm call.
-    fn fn_call(self, stack: &mut Stack, args: usize) -> Result<(), VmError>;
+    fn fn_call(&self, stack: &mut Stack, args: usize) -> Result<(), VmError>;
 }
 
 /// Trait used to provide the [inst_fn][Module::inst_fn] function.
-pub trait InstFn<Args>: 'static + Copy + Send + Sync {
+pub trait InstFn<Args>: 'static + Send + Sync {
     /// The type of the instance.
     type Instance;
     /// The return type of the function.
@@ -751,11 +950,11 @@ pub trait InstFn<Args>: 'static + Copy + Send + Sync {
     fn instance_value_type_info() -> TypeInfo;
 
     /// Perform the vm call.
-    fn fn_call(self, stack: &mut Stack, args: usize) -> Result<(), VmError>;
+    fn fn_call(&self, stack: &mut Stack, args: usize) -> Result<(), VmError>;
 }
 
 /// Trait used to provide the [async_inst_fn][Module::async_inst_fn] function.
-pub trait AsyncInstFn<Args>: 'static + Copy + Send + Sync {
+pub trait AsyncInstFn<Args>: 'static + Send + Sync {
     /// The type of the instance.
     type Instance;
     /// The return type of the function.
@@ -771,7 +970,7 @@ pub trait AsyncInstFn<Args>: 'static + Copy + Send + Sync {
     fn instance_value_type_info() -> TypeInfo;
 
     /// Perform the vm call.
-    fn fn_call(self, stack: &mut Stack, args: usize) -> Result<(), VmError>;
+    fn fn_call(&self, stack: &mut Stack, args: usize) -> Result<(), VmError>;
 }
 
 macro_rules! impl_register {
@@ -787,7 +986,7 @@ macro_rules! impl_register {
     (@impl $count:expr, $({$ty:ident, $var:ident, $num:expr},)*) => {
         impl<Func, Return, $($ty,)*> Function<($($ty,)*)> for Func
         where
-            Func: 'static + Copy + Send + Sync + Fn($($ty,)*) -> Return,
+            Func: 'static + Send + Sync + Fn($($ty,)*) -> Return,
             Return: ToValue,
             $($ty: UnsafeFromValue,)*
         {
@@ -798,7 +997,7 @@ macro_rules! impl_register {
             }
 
             fn fn_call(
-                self,
+                &self,
                 stack: &mut Stack,
                 args: usize
             ) -> Result<(), VmError> {
@@ -826,10 +1025,50 @@ macro_rules! impl_register {
             }
         }
 
+        impl<Func, T, Error, $($ty,)*> FallibleFunction<($($ty,)*)> for Func
+        where
+            Func: 'static + Send + Sync + Fn($($ty,)*) -> Result<T, Error>,
+            T: ToValue,
+            Error: std::error::Error,
+            $($ty: UnsafeFromValue,)*
+        {
+            type Error = Error;
+
+            fn args() -> usize {
+                $count
+            }
+
+            fn fn_call(
+                &self,
+                stack: &mut Stack,
+                args: usize,
+                mode: HostErrorMode,
+            ) -> Result<(), VmError> {
+                impl_register!{@check-args $count, args}
+
+                #[allow(unused_mut)]
+                let mut it = stack.drain_stack_top($count)?;
+                $(let $var = it.next().unwrap();)*
+                drop(it);
+
+                // Safety: We hold a reference to the stack, so we can
+                // guarantee that it won't be modified.
+                #[allow(unused)]
+                let ret = unsafe {
+                    impl_register!{@unsafe-vars $count, $($ty, $var, $num,)*}
+
+                    self($(<$ty>::to_arg($var.0),)*)
+                };
+
+                stack.push(mode.convert(ret)?);
+                Ok(())
+            }
+        }
+
         impl<Func, Return, $($ty,)*> AsyncFunction<($($ty,)*)> for Func
         where
-            Func: 'static + Copy + Send + Sync + Fn($($ty,)*) -> Return,
-            Return: future::Future,
+            Func: 'static + Send + Sync + Fn($($ty,)*) -> Return,
+            Return: 'static + future::Future,
             Return::Output: ToValue,
             $($ty: 'static + UnsafeFromValue,)*
         {
@@ -840,7 +1079,7 @@ macro_rules! impl_register {
             }
 
             fn fn_call(
-                self,
+                &self,
                 stack: &mut Stack,
                 args: usize
             ) -> Result<(), VmError> {
@@ -859,8 +1098,10 @@ macro_rules! impl_register {
                 let ret = unsafe {
                     impl_register!{@unsafe-vars $count, $($ty, $var, $num,)*}
 
+                    let future = self($(<$ty>::to_arg($var.0),)*);
+
                     Future::new(async move {
-                        let output = self($(<$ty>::to_arg($var.0),)*).await;
+                        let output = future.await;
                         let value = output.to_value()?;
                         Ok(value)
                     })
@@ -873,7 +1114,7 @@ macro_rules! impl_register {
 
         impl<Func, Return, Instance, $($ty,)*> InstFn<(Instance, $($ty,)*)> for Func
         where
-            Func: 'static + Copy + Send + Sync + Fn(Instance $(, $ty)*) -> Return,
+            Func: 'static + Send + Sync + Fn(Instance $(, $ty)*) -> Return,
             Return: ToValue,
             Instance: UnsafeFromValue + ValueType,
             $($ty: UnsafeFromValue,)*
@@ -893,7 +1134,7 @@ macro_rules! impl_register {
                 Instance::type_info()
             }
 
-            fn fn_call(self, stack: &mut Stack, args: usize) -> Result<(), VmError> {
+            fn fn_call(&self, stack: &mut Stack, args: usize) -> Result<(), VmError> {
                 impl_register!{@check-args ($count + 1), args}
 
                 #[allow(unused_mut)]
@@ -920,8 +1161,8 @@ macro_rules! impl_register {
 
         impl<Func, Return, Instance, $($ty,)*> AsyncInstFn<(Instance, $($ty,)*)> for Func
         where
-            Func: 'static + Copy + Send + Sync + Fn(Instance $(, $ty)*) -> Return,
-            Return: future::Future,
+            Func: 'static + Send + Sync + Fn(Instance $(, $ty)*) -> Return,
+            Return: 'static + future::Future,
             Return::Output: ToValue,
             Instance: UnsafeFromValue + ValueType,
             $($ty: UnsafeFromValue,)*
@@ -941,7 +1182,7 @@ macro_rules! impl_register {
                 Instance::type_info()
             }
 
-            fn fn_call(self, stack: &mut Stack, args: usize) -> Result<(), VmError> {
+            fn fn_call(&self, stack: &mut Stack, args: usize) -> Result<(), VmError> {
                 impl_register!{@check-args ($count + 1), args}
 
                 #[allow(unused_mut)]
@@ -958,8 +1199,10 @@ macro_rules! impl_register {
                 let ret = unsafe {
                     impl_register!{@unsafe-inst-vars inst, $count, $($ty, $var, $num,)*}
 
+                    let future = self(Instance::to_arg(inst.0), $(<$ty>::to_arg($var.0),)*);
+
                     Future::new(async move {
-                        let output = self(Instance::to_arg(inst.0), $(<$ty>::to_arg($var.0),)*).await;
+                        let output = future.await;
                         let value = output.to_value()?;
                         Ok(value)
                     })
@@ -986,37 +1229,16 @@ macro_rules! impl_register {
     // Expand to function variable bindings.
     (@unsafe-vars $count:expr, $($ty:ty, $var:ident, $num:expr,)*) => {
         $(
-            let $var = match <$ty>::unsafe_from_value($var) {
-                Ok(v) => v,
-                Err(e) => return Err(VmError::from(VmErrorKind::BadArgument {
-                    error: e.unpack_critical()?,
-                    arg: $count - $num,
-                    to: type_name::<$ty>(),
-                })),
-            };
+            let $var = from_arg::<$ty>($var, $count - $num)?;
         )*
     };
 
     // Expand to instance variable bindings.
     (@unsafe-inst-vars $inst:ident, $count:expr, $($ty:ty, $var:ident, $num:expr,)*) => {
-        let $inst = match Instance::unsafe_from_value($inst) {
-            Ok(v) => v,
-            Err(e) => return Err(VmError::from(VmErrorKind::BadArgument {
-                error: e.unpack_critical()?,
-                arg: 0,
-                to: type_name::<Instance>()
-            })),
-        };
+        let $inst = from_arg::<Instance>($inst, 0)?;
 
         $(
-            let $var = match <$ty>::unsafe_from_value($var) {
-                Ok(v) => v,
-                Err(e) => return Err(VmError::from(VmErrorKind::BadArgument {
-                    error: e.unpack_critical()?,
-                    arg: 1 + $count - $num,
-                    to: type_name::<$ty>()
-                })),
-            };
+            let $var = from_arg::<$ty>($var, 1 + $count - $num)?;
         )*
     };
 
@@ -1031,3 +1253,27 @@ macro_rules! impl_register {
 }
 
 repeat_macro!(impl_register);
+
+/// Convert an argument passed to a native function, after applying the
+/// coercion hook registered for its type in the context of the calling virtual
+/// machine, if any.
+///
+/// # Safety
+///
+/// Has the same requirements as [UnsafeFromValue::unsafe_from_value].
+unsafe fn from_arg<T>(value: Value, arg: usize) -> Result<(T::Output, T::Guard), VmError>
+where
+    T: UnsafeFromValue,
+    T::Output: 'static,
+{
+    let value = crate::caller::coerce(value, std::any::TypeId::of::<T::Output>())?;
+
+    match T::unsafe_from_value(value) {
+        Ok(output) => Ok(output),
+        Err(e) => Err(VmError::from(VmErrorKind::BadArgument {
+            error: e.unpack_critical()?,
+            arg,
+            to: type_name::<T>(),
+        })),
+    }
+}
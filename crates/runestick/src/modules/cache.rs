@@ -0,0 +1,82 @@
+//! The `std::cache` module.
+//!
+//! Provides `memoize`, which wraps a pure function so that its results are
+//! cached by the arguments it's called with:
+//!
+//! ```text
+//! use std::cache;
+//!
+//! fn score(rule) {
+//!     // expensive computation
+//! }
+//!
+//! fn main() {
+//!     let score = cache::memoize(score);
+//!     let score = cache::memoize_with(score, #{capacity: 128, ttl: 60});
+//! }
+//! ```
+//!
+//! Arguments must be usable as keys: units, booleans, bytes, chars, integers,
+//! strings, byte strings, tuples of keys, or external values implementing the
+//! [HASH][crate::HASH] protocol.
+
+use crate::memoize::{CacheLimits, Memoized};
+use crate::{Caller, ContextError, FromValue as _, Function, Module, Object, Value, VmError};
+use std::time::Duration;
+
+/// Construct the `std::cache` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "cache"]);
+    module.function(&["memoize"], memoize)?;
+    module.function(&["memoize_with"], memoize_with)?;
+    Ok(module)
+}
+
+/// Memoize the given function without any limits on the cache.
+fn memoize(function: Function) -> Function {
+    memoize_limited(function, CacheLimits::default())
+}
+
+/// Memoize the given function, with limits given as an object with the
+/// optional keys `capacity`, the maximum number of cached results, and `ttl`,
+/// the number of seconds a result is cached for.
+fn memoize_with(function: Function, options: Object<Value>) -> Result<Function, VmError> {
+    let mut limits = CacheLimits::default();
+
+    for (key, value) in options {
+        match key.as_str() {
+            "capacity" => {
+                limits.capacity = Some(usize::from_value(value)?);
+            }
+            "ttl" => {
+                let seconds = match value {
+                    Value::Integer(n) => n as f64,
+                    value => f64::from_value(value)?,
+                };
+
+                // NB: rejects negative, NaN, and out of range durations.
+                let ttl = match Duration::try_from_secs_f64(seconds) {
+                    Ok(ttl) => ttl,
+                    Err(..) => {
+                        return Err(VmError::panic(format!("invalid cache ttl `{}`", seconds)));
+                    }
+                };
+
+                limits.ttl = Some(ttl);
+            }
+            key => {
+                return Err(VmError::panic(format!(
+                    "unsupported cache option `{}`",
+                    key
+                )));
+            }
+        }
+    }
+
+    Ok(memoize_limited(function, limits))
+}
+
+fn memoize_limited(function: Function, limits: CacheLimits) -> Function {
+    let context = Caller::current().map(|caller| caller.context().clone());
+    Function::from_memoized(Memoized::new(function, context, limits))
+}
@@ -0,0 +1,83 @@
+//! The `std::channel` module.
+
+use crate::{ContextError, Future, Module, Shared, Value, VmError};
+use futures::channel::mpsc;
+use futures::{FutureExt as _, StreamExt as _};
+
+/// Construct the `std::channel` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "channel"]);
+    module.ty(&["Sender"]).build::<Sender>()?;
+    module.ty(&["Receiver"]).build::<Receiver>()?;
+
+    module.function(&["channel"], channel)?;
+    module.inst_fn("send", Sender::send)?;
+    module.inst_fn("clone", Sender::clone)?;
+    module.async_inst_fn("recv", Receiver::recv)?;
+    module.inst_fn("try_recv", Receiver::try_recv)?;
+    module.inst_fn(crate::INTO_FUTURE, Receiver::recv_future)?;
+    Ok(module)
+}
+
+/// Construct a new unbounded channel.
+///
+/// The channel is closed once every sender has been dropped.
+fn channel() -> (Sender, Receiver) {
+    let (sender, receiver) = mpsc::unbounded();
+
+    let receiver = Receiver {
+        inner: Shared::new(receiver),
+    };
+
+    (Sender { inner: sender }, receiver)
+}
+
+/// The sending half of a channel.
+#[derive(Debug, Clone)]
+pub struct Sender {
+    inner: mpsc::UnboundedSender<Value>,
+}
+
+impl Sender {
+    /// Send a value over the channel, returning it back as an error if the
+    /// receiver has been dropped.
+    fn send(&self, value: Value) -> Result<(), Value> {
+        self.inner
+            .unbounded_send(value)
+            .map_err(|error| error.into_inner())
+    }
+}
+
+/// The receiving half of a channel.
+///
+/// Receiving can be awaited directly, or used as a branch in a `select`
+/// expression. Either of which produces `None` once the channel is closed.
+#[derive(Debug)]
+pub struct Receiver {
+    inner: Shared<mpsc::UnboundedReceiver<Value>>,
+}
+
+impl Receiver {
+    /// Receive the next value from the channel.
+    async fn recv(&self) -> Result<Option<Value>, VmError> {
+        Ok(self.inner.borrow_mut()?.next().await)
+    }
+
+    /// Receive the next value from the channel if one is immediately
+    /// available.
+    fn try_recv(&self) -> Result<Option<Value>, VmError> {
+        Ok(self.inner.borrow_mut()?.next().now_or_never().flatten())
+    }
+
+    /// Construct a future receiving the next value from the channel.
+    ///
+    /// Receiving doesn't lose any values if the future is dropped, so it can
+    /// lose a select without consequences.
+    fn recv_future(&self) -> Future {
+        let inner = self.inner.clone();
+        Future::new(async move { Ok(inner.borrow_mut()?.next().await) })
+    }
+}
+
+impl_external!(Sender);
+impl_external!(Receiver);
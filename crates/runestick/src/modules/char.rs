@@ -0,0 +1,119 @@
+//! The `std::char` module.
+
+use crate::{ContextError, Module, Panic};
+
+/// Construct the `std::char` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std"]);
+
+    module.function(&["char", "from_int"], from_int)?;
+    module.function(&["char", "from_digit"], from_digit)?;
+    module.function(&["char", "from_string"], from_string)?;
+
+    module.inst_fn("is_alphabetic", char::is_alphabetic)?;
+    module.inst_fn("is_alphanumeric", char::is_alphanumeric)?;
+    module.inst_fn("is_numeric", char::is_numeric)?;
+    module.inst_fn("is_whitespace", char::is_whitespace)?;
+    module.inst_fn("is_control", char::is_control)?;
+    module.inst_fn("is_uppercase", char::is_uppercase)?;
+    module.inst_fn("is_lowercase", char::is_lowercase)?;
+    module.inst_fn("is_ascii", is_ascii)?;
+    module.inst_fn("is_digit", is_digit)?;
+    module.inst_fn("to_digit", to_digit)?;
+    module.inst_fn("to_int", to_int)?;
+    module.inst_fn("to_uppercase", to_uppercase)?;
+    module.inst_fn("to_lowercase", to_lowercase)?;
+    module.inst_fn("to_string", to_string)?;
+    Ok(module)
+}
+
+/// Convert a unicode scalar value into a char, if it's valid.
+fn from_int(value: i64) -> Option<char> {
+    if value < 0 || value > u32::MAX as i64 {
+        return None;
+    }
+
+    std::char::from_u32(value as u32)
+}
+
+/// Convert a digit in the given radix into a char.
+fn from_digit(digit: i64, radix: i64) -> Result<Option<char>, Panic> {
+    let radix = radix_arg(radix)?;
+
+    if digit < 0 || digit >= radix as i64 {
+        return Ok(None);
+    }
+
+    Ok(std::char::from_digit(digit as u32, radix))
+}
+
+/// Convert a string consisting of exactly one char into that char.
+fn from_string(s: &str) -> Option<char> {
+    let mut it = s.chars();
+    let c = it.next()?;
+
+    if it.next().is_some() {
+        return None;
+    }
+
+    Some(c)
+}
+
+fn is_ascii(c: char) -> bool {
+    c.is_ascii()
+}
+
+/// Test if the char is a digit in the given radix.
+fn is_digit(c: char, radix: i64) -> Result<bool, Panic> {
+    Ok(c.is_digit(radix_arg(radix)?))
+}
+
+/// Convert the char into the digit it represents in the given radix.
+fn to_digit(c: char, radix: i64) -> Result<Option<i64>, Panic> {
+    Ok(c.to_digit(radix_arg(radix)?).map(i64::from))
+}
+
+/// Convert the char into its unicode scalar value.
+fn to_int(c: char) -> i64 {
+    c as i64
+}
+
+/// Convert the char to uppercase.
+///
+/// Chars which don't have a single char uppercase form, like `ß`, are left
+/// as-is. Use `to_uppercase` on a string to get their full uppercase form.
+fn to_uppercase(c: char) -> char {
+    single(c, c.to_uppercase())
+}
+
+/// Convert the char to lowercase.
+///
+/// Chars which don't have a single char lowercase form are left as-is.
+fn to_lowercase(c: char) -> char {
+    single(c, c.to_lowercase())
+}
+
+fn to_string(c: char) -> String {
+    c.to_string()
+}
+
+/// Get the only char of the given case mapping, falling back to the original
+/// char.
+fn single(c: char, mut it: impl Iterator<Item = char>) -> char {
+    match (it.next(), it.next()) {
+        (Some(mapped), None) => mapped,
+        _ => c,
+    }
+}
+
+/// Check that the given radix is supported.
+fn radix_arg(radix: i64) -> Result<u32, Panic> {
+    if !(2..=36).contains(&radix) {
+        return Err(Panic::custom(format!(
+            "radix must be in the range 2 to 36, but was {}",
+            radix
+        )));
+    }
+
+    Ok(radix as u32)
+}
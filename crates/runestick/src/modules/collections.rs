@@ -0,0 +1,215 @@
+//! The `std::collections` module.
+
+use crate::{ContextError, Key, Module, Panic, Value, VmError};
+use std::iter::FromIterator;
+
+/// Construct the `std::collections` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "collections"]);
+
+    module.ty(&["HashMap"]).build::<HashMap>()?;
+    module.ty(&["HashSet"]).build::<HashSet>()?;
+    module.ty(&["Iter"]).build::<Iter>()?;
+
+    module.function(&["HashMap", "new"], HashMap::new)?;
+    module.inst_fn("len", HashMap::len)?;
+    module.inst_fn("is_empty", HashMap::is_empty)?;
+    module.inst_fn("insert", HashMap::insert)?;
+    module.inst_fn("get", HashMap::get)?;
+    module.inst_fn("contains_key", HashMap::contains_key)?;
+    module.inst_fn("remove", HashMap::remove)?;
+    module.inst_fn("clear", HashMap::clear)?;
+    module.inst_fn("keys", HashMap::keys)?;
+    module.inst_fn("values", HashMap::values)?;
+    module.inst_fn(crate::INTO_ITER, HashMap::iter)?;
+    module.inst_fn(crate::INDEX_GET, HashMap::index_get)?;
+    module.inst_fn(crate::INDEX_SET, HashMap::index_set)?;
+
+    module.function(&["HashSet", "new"], HashSet::new)?;
+    module.inst_fn("len", HashSet::len)?;
+    module.inst_fn("is_empty", HashSet::is_empty)?;
+    module.inst_fn("insert", HashSet::insert)?;
+    module.inst_fn("contains", HashSet::contains)?;
+    module.inst_fn("remove", HashSet::remove)?;
+    module.inst_fn("clear", HashSet::clear)?;
+    module.inst_fn(crate::INTO_ITER, HashSet::iter)?;
+
+    module.inst_fn("next", Iter::next)?;
+    module.inst_fn(crate::NEXT, Iter::next)?;
+    module.inst_fn(crate::INTO_ITER, Iter::into_iter)?;
+    Ok(module)
+}
+
+/// A hash map, mapping keys to values.
+///
+/// See [Key] for which values can be used as keys.
+#[derive(Debug, Clone, Default)]
+pub struct HashMap {
+    map: crate::collections::HashMap<Key, Value>,
+}
+
+impl HashMap {
+    /// Construct a new empty hash map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Test if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Insert a value into the map, returning the value previously stored
+    /// under the same key.
+    pub fn insert(&mut self, key: Key, value: Value) -> Option<Value> {
+        self.map.insert(key, value)
+    }
+
+    /// Get the value stored under the given key.
+    pub fn get(&self, key: Key) -> Option<Value> {
+        self.map.get(&key).cloned()
+    }
+
+    /// Test if the map contains the given key.
+    pub fn contains_key(&self, key: Key) -> bool {
+        self.map.contains_key(&key)
+    }
+
+    /// Remove the value stored under the given key, returning it.
+    pub fn remove(&mut self, key: Key) -> Option<Value> {
+        self.map.remove(&key)
+    }
+
+    /// Remove all entries from the map.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    fn keys(&self) -> Iter {
+        Iter::new(self.map.keys().cloned().map(Key::into_value))
+    }
+
+    fn values(&self) -> Iter {
+        Iter::new(self.map.values().cloned())
+    }
+
+    /// Iterate over the entries of the map as `(key, value)` tuples.
+    fn iter(&self) -> Result<Iter, VmError> {
+        let mut entries = Vec::with_capacity(self.map.len());
+
+        for (key, value) in &self.map {
+            entries.push(crate::ToValue::to_value((key.clone(), value.clone()))?);
+        }
+
+        Ok(Iter {
+            iter: entries.into_iter(),
+        })
+    }
+
+    fn index_get(&self, key: Key) -> Result<Value, Panic> {
+        match self.map.get(&key) {
+            Some(value) => Ok(value.clone()),
+            None => Err(Panic::custom(format!("missing key `{:?}`", key))),
+        }
+    }
+
+    fn index_set(&mut self, key: Key, value: Value) {
+        self.map.insert(key, value);
+    }
+}
+
+impl FromIterator<(Key, Value)> for HashMap {
+    fn from_iter<I: IntoIterator<Item = (Key, Value)>>(iter: I) -> Self {
+        Self {
+            map: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// A hash set of keys.
+///
+/// See [Key] for which values can be stored in a set.
+#[derive(Debug, Clone, Default)]
+pub struct HashSet {
+    set: crate::collections::HashSet<Key>,
+}
+
+impl HashSet {
+    /// Construct a new empty hash set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of keys in the set.
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    /// Test if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Insert a key into the set, returning `true` if it wasn't already
+    /// present.
+    pub fn insert(&mut self, key: Key) -> bool {
+        self.set.insert(key)
+    }
+
+    /// Test if the set contains the given key.
+    pub fn contains(&self, key: Key) -> bool {
+        self.set.contains(&key)
+    }
+
+    /// Remove the given key from the set, returning `true` if it was present.
+    pub fn remove(&mut self, key: Key) -> bool {
+        self.set.remove(&key)
+    }
+
+    /// Remove all keys from the set.
+    pub fn clear(&mut self) {
+        self.set.clear();
+    }
+
+    fn iter(&self) -> Iter {
+        Iter::new(self.set.iter().cloned().map(Key::into_value))
+    }
+}
+
+impl FromIterator<Key> for HashSet {
+    fn from_iter<I: IntoIterator<Item = Key>>(iter: I) -> Self {
+        Self {
+            set: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// An iterator over the entries of a hash map or a hash set.
+pub struct Iter {
+    iter: std::vec::IntoIter<Value>,
+}
+
+impl Iter {
+    fn new(iter: impl Iterator<Item = Value>) -> Self {
+        Self {
+            iter: iter.collect::<Vec<_>>().into_iter(),
+        }
+    }
+}
+
+impl Iterator for Iter {
+    type Item = Value;
+
+    fn next(&mut self) -> Option<Value> {
+        self.iter.next()
+    }
+}
+
+impl_external!(HashMap);
+impl_external!(HashSet);
+impl_external!(Iter);
@@ -93,8 +93,8 @@ fn println_impl(m: &str) -> Result<(), Panic> {
     writeln!(stdout, "{}", m).map_err(Panic::custom)
 }
 
-fn panic_impl(m: &str) -> Result<(), Panic> {
-    Err(Panic::custom(m.to_owned()))
+fn panic_impl(value: Value) -> Result<(), Panic> {
+    Err(Panic::value(value))
 }
 
 fn is_readable(value: Value) -> bool {
@@ -0,0 +1,505 @@
+//! The `std::diff` module.
+//!
+//! Computes structural patches between values, and applies them:
+//!
+//! ```text
+//! use std::diff;
+//!
+//! fn main() {
+//!     let before = #{name: "rune", tags: ["vm"]};
+//!     let after = #{name: "rune", tags: ["vm", "script"], stable: true};
+//!
+//!     let patch = diff::diff(before, after);
+//!     // => [#{op: "add", path: ["stable"], value: true},
+//!     //     #{op: "add", path: ["tags", 1], value: "script"}]
+//!
+//!     let patched = diff::apply(before, patch);
+//! }
+//! ```
+//!
+//! A patch is a vector of operations, each of which is an object with the
+//! following keys:
+//! * `op` - one of `"add"`, `"remove"` or `"replace"`.
+//! * `path` - a vector of object keys and vector indexes leading to the value
+//!   the operation applies to, where an empty path is the value itself.
+//! * `value` - the value which is added or replaced, which is missing for
+//!   `"remove"`.
+//!
+//! Objects, vectors, tuples, structs and variants are compared field by field
+//! and element by element, and everything else is replaced as a whole if it
+//! differs. Applying a patch never modifies the value it's applied to.
+
+use crate::{
+    ContextError, Module, Object, Shared, Tuple, TupleVariant, TypedObject, TypedTuple, Value,
+    VariantObject, VmError,
+};
+
+/// Construct the `std::diff` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "diff"]);
+    module.function(&["diff"], diff)?;
+    module.function(&["apply"], apply)?;
+    Ok(module)
+}
+
+/// Compute the patch which turns `a` into `b`.
+///
+/// The fields of objects are compared in the order of their keys, and
+/// elements removed from the end of a vector are removed last to first, so
+/// that applying the operations in order is always valid.
+fn diff(a: Value, b: Value) -> Result<Vec<Value>, VmError> {
+    let mut patch = Vec::new();
+    diff_into(&a, &b, &mut Vec::new(), &mut patch)?;
+    Ok(patch)
+}
+
+/// Apply the given patch to a copy of the given value.
+///
+/// Errors if the patch is malformed or an operation refers to a value which
+/// doesn't exist.
+fn apply(mut value: Value, patch: Vec<Value>) -> Result<Value, VmError> {
+    for operation in &patch {
+        let operation = Operation::parse(operation)?;
+        value = apply_at(&value, &operation.path, &operation)?;
+    }
+
+    Ok(value)
+}
+
+fn diff_into(
+    lhs: &Value,
+    rhs: &Value,
+    path: &mut Vec<Value>,
+    patch: &mut Vec<Value>,
+) -> Result<(), VmError> {
+    match (lhs, rhs) {
+        (Value::Object(a), Value::Object(b)) => {
+            let a = a.borrow_ref()?;
+            let b = b.borrow_ref()?;
+            diff_objects(&a, &b, path, patch)
+        }
+        (Value::TypedObject(a), Value::TypedObject(b)) => {
+            let a = a.borrow_ref()?;
+            let b = b.borrow_ref()?;
+
+            if a.hash != b.hash {
+                push_operation(patch, "replace", path, Some(rhs.clone()));
+                return Ok(());
+            }
+
+            diff_objects(&a.object, &b.object, path, patch)
+        }
+        (Value::VariantObject(a), Value::VariantObject(b)) => {
+            let a = a.borrow_ref()?;
+            let b = b.borrow_ref()?;
+
+            if a.hash != b.hash {
+                push_operation(patch, "replace", path, Some(rhs.clone()));
+                return Ok(());
+            }
+
+            diff_objects(&a.object, &b.object, path, patch)
+        }
+        (Value::Vec(a), Value::Vec(b)) => {
+            let a = a.borrow_ref()?;
+            let b = b.borrow_ref()?;
+
+            for (index, (a, b)) in a.iter().zip(b.iter()).enumerate() {
+                path.push(Value::Integer(index as i64));
+                diff_into(a, b, path, patch)?;
+                path.pop();
+            }
+
+            for (index, value) in b.iter().enumerate().skip(a.len()) {
+                path.push(Value::Integer(index as i64));
+                push_operation(patch, "add", path, Some(value.clone()));
+                path.pop();
+            }
+
+            for index in (b.len()..a.len()).rev() {
+                path.push(Value::Integer(index as i64));
+                push_operation(patch, "remove", path, None);
+                path.pop();
+            }
+
+            Ok(())
+        }
+        (Value::Tuple(a), Value::Tuple(b)) => {
+            let a = a.borrow_ref()?;
+            let b = b.borrow_ref()?;
+            diff_tuples(&a, &b, rhs, path, patch)
+        }
+        (Value::TypedTuple(a), Value::TypedTuple(b)) => {
+            let a = a.borrow_ref()?;
+            let b = b.borrow_ref()?;
+
+            if a.hash != b.hash {
+                push_operation(patch, "replace", path, Some(rhs.clone()));
+                return Ok(());
+            }
+
+            diff_tuples(&a.tuple, &b.tuple, rhs, path, patch)
+        }
+        (Value::TupleVariant(a), Value::TupleVariant(b)) => {
+            let a = a.borrow_ref()?;
+            let b = b.borrow_ref()?;
+
+            if a.hash != b.hash {
+                push_operation(patch, "replace", path, Some(rhs.clone()));
+                return Ok(());
+            }
+
+            diff_tuples(&a.tuple, &b.tuple, rhs, path, patch)
+        }
+        (a, b) => {
+            if !equal(a, b)? {
+                push_operation(patch, "replace", path, Some(rhs.clone()));
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn diff_objects(
+    a: &Object<Value>,
+    b: &Object<Value>,
+    path: &mut Vec<Value>,
+    patch: &mut Vec<Value>,
+) -> Result<(), VmError> {
+    let mut keys = a.keys().chain(b.keys()).collect::<Vec<_>>();
+    keys.sort();
+    keys.dedup();
+
+    for key in keys {
+        path.push(Value::from(key.clone()));
+
+        match (a.get(key), b.get(key)) {
+            (Some(a), Some(b)) => diff_into(a, b, path, patch)?,
+            (None, Some(b)) => push_operation(patch, "add", path, Some(b.clone())),
+            (Some(..), None) => push_operation(patch, "remove", path, None),
+            (None, None) => (),
+        }
+
+        path.pop();
+    }
+
+    Ok(())
+}
+
+/// Diff two tuples element by element, or replace the tuple with `rhs` if
+/// they have a different number of elements.
+fn diff_tuples(
+    a: &[Value],
+    b: &[Value],
+    rhs: &Value,
+    path: &mut Vec<Value>,
+    patch: &mut Vec<Value>,
+) -> Result<(), VmError> {
+    if a.len() != b.len() {
+        push_operation(patch, "replace", path, Some(rhs.clone()));
+        return Ok(());
+    }
+
+    for (index, (a, b)) in a.iter().zip(b.iter()).enumerate() {
+        path.push(Value::Integer(index as i64));
+        diff_into(a, b, path, patch)?;
+        path.pop();
+    }
+
+    Ok(())
+}
+
+/// Test if two values which aren't diffed structurally are equal.
+fn equal(a: &Value, b: &Value) -> Result<bool, VmError> {
+    if Value::is_same(a, b) {
+        return Ok(true);
+    }
+
+    Ok(match (a, b) {
+        (Value::Byte(a), Value::Byte(b)) => a == b,
+        (Value::Type(a), Value::Type(b)) => a == b,
+        (Value::Bytes(a), Value::Bytes(b)) => *a.borrow_ref()? == *b.borrow_ref()?,
+        (Value::Option(a), Value::Option(b)) => match (&*a.borrow_ref()?, &*b.borrow_ref()?) {
+            (Some(a), Some(b)) => equal(a, b)?,
+            (None, None) => true,
+            _ => false,
+        },
+        (Value::Result(a), Value::Result(b)) => match (&*a.borrow_ref()?, &*b.borrow_ref()?) {
+            (Ok(a), Ok(b)) | (Err(a), Err(b)) => equal(a, b)?,
+            _ => false,
+        },
+        (a, b) => Value::value_ptr_eq(a, b)?,
+    })
+}
+
+fn push_operation(patch: &mut Vec<Value>, op: &str, path: &[Value], value: Option<Value>) {
+    let mut operation = Object::new();
+    operation.insert(String::from("op"), Value::from(String::from(op)));
+    operation.insert(String::from("path"), Value::vec(path.to_vec()));
+
+    if let Some(value) = value {
+        operation.insert(String::from("value"), value);
+    }
+
+    patch.push(Value::Object(Shared::new(operation)));
+}
+
+/// The kind of a patch operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Remove,
+    Replace,
+}
+
+/// A parsed patch operation.
+struct Operation {
+    op: Op,
+    path: Vec<Value>,
+    value: Value,
+}
+
+impl Operation {
+    fn parse(operation: &Value) -> Result<Self, VmError> {
+        let object = match operation {
+            Value::Object(object) => object.borrow_ref()?,
+            value => {
+                return Err(VmError::panic(format!(
+                    "invalid patch: expected an operation object, but found `{}`",
+                    value.type_info()?
+                )))
+            }
+        };
+
+        let op = match object.get("op") {
+            Some(op) => match string(op)?.as_str() {
+                "add" => Op::Add,
+                "remove" => Op::Remove,
+                "replace" => Op::Replace,
+                op => {
+                    return Err(VmError::panic(format!(
+                        "invalid patch: unsupported operation `{}`",
+                        op
+                    )))
+                }
+            },
+            None => return Err(VmError::panic("invalid patch: missing `op`")),
+        };
+
+        let path = match object.get("path") {
+            Some(Value::Vec(path)) => path.borrow_ref()?.clone(),
+            Some(..) => return Err(VmError::panic("invalid patch: `path` must be a vector")),
+            None => return Err(VmError::panic("invalid patch: missing `path`")),
+        };
+
+        let value = match (op, object.get("value")) {
+            (Op::Remove, _) => Value::Unit,
+            (_, Some(value)) => value.clone(),
+            (_, None) => return Err(VmError::panic("invalid patch: missing `value`")),
+        };
+
+        Ok(Self { op, path, value })
+    }
+
+    /// Construct the error raised when the operation doesn't apply to the
+    /// value at its path.
+    fn error(&self, message: &str) -> Result<VmError, VmError> {
+        Ok(VmError::panic(format!(
+            "can't apply patch at `{}`: {}",
+            format_path(&self.path)?,
+            message
+        )))
+    }
+}
+
+/// Apply the given operation at the given remaining path of `value`, copying
+/// every value on the way.
+fn apply_at(value: &Value, path: &[Value], operation: &Operation) -> Result<Value, VmError> {
+    let (segment, rest) = match path.split_first() {
+        Some(split) => split,
+        None => {
+            return match operation.op {
+                Op::Remove => Err(operation.error("can't remove the value itself")?),
+                Op::Add | Op::Replace => Ok(operation.value.clone()),
+            }
+        }
+    };
+
+    Ok(match value {
+        Value::Object(object) => {
+            let mut object = object.borrow_ref()?.clone();
+            apply_field(&mut object, segment, rest, operation)?;
+            Value::Object(Shared::new(object))
+        }
+        Value::TypedObject(typed) => {
+            let typed = typed.borrow_ref()?;
+            let mut object = typed.object.clone();
+            apply_field(&mut object, segment, rest, operation)?;
+
+            Value::from(TypedObject {
+                hash: typed.hash,
+                object,
+            })
+        }
+        Value::VariantObject(variant) => {
+            let variant = variant.borrow_ref()?;
+            let mut object = variant.object.clone();
+            apply_field(&mut object, segment, rest, operation)?;
+
+            Value::from(VariantObject {
+                enum_hash: variant.enum_hash,
+                hash: variant.hash,
+                object,
+            })
+        }
+        Value::Vec(vec) => {
+            let mut vec = vec.borrow_ref()?.clone();
+            let index = index(segment, operation)?;
+
+            if rest.is_empty() && operation.op == Op::Add {
+                if index > vec.len() {
+                    return Err(operation.error("index out of bounds")?);
+                }
+
+                vec.insert(index, operation.value.clone());
+            } else if rest.is_empty() && operation.op == Op::Remove {
+                if index >= vec.len() {
+                    return Err(operation.error("index out of bounds")?);
+                }
+
+                vec.remove(index);
+            } else {
+                apply_element(&mut vec, index, rest, operation)?;
+            }
+
+            Value::vec(vec)
+        }
+        Value::Tuple(tuple) => {
+            let mut tuple = tuple.borrow_ref()?.to_vec();
+            apply_element(&mut tuple, index(segment, operation)?, rest, operation)?;
+            Value::from(Tuple::from(tuple))
+        }
+        Value::TypedTuple(typed) => {
+            let typed = typed.borrow_ref()?;
+            let mut tuple = typed.tuple.to_vec();
+            apply_element(&mut tuple, index(segment, operation)?, rest, operation)?;
+
+            Value::from(TypedTuple {
+                hash: typed.hash,
+                tuple: tuple.into(),
+            })
+        }
+        Value::TupleVariant(variant) => {
+            let variant = variant.borrow_ref()?;
+            let mut tuple = variant.tuple.to_vec();
+            apply_element(&mut tuple, index(segment, operation)?, rest, operation)?;
+
+            Value::from(TupleVariant {
+                enum_hash: variant.enum_hash,
+                hash: variant.hash,
+                tuple: tuple.into(),
+            })
+        }
+        value => {
+            return Err(operation.error(&format!(
+                "`{}` doesn't have fields or elements",
+                value.type_info()?
+            ))?)
+        }
+    })
+}
+
+fn apply_field(
+    object: &mut Object<Value>,
+    segment: &Value,
+    rest: &[Value],
+    operation: &Operation,
+) -> Result<(), VmError> {
+    let key = match segment {
+        Value::StaticString(..) | Value::String(..) => string(segment)?,
+        _ => return Err(operation.error("expected an object key")?),
+    };
+
+    if rest.is_empty() {
+        match operation.op {
+            Op::Add => {
+                object.insert(key, operation.value.clone());
+            }
+            Op::Remove => {
+                if object.remove(&key).is_none() {
+                    return Err(operation.error("missing field")?);
+                }
+            }
+            Op::Replace => match object.get_mut(&key) {
+                Some(value) => *value = operation.value.clone(),
+                None => return Err(operation.error("missing field")?),
+            },
+        }
+
+        return Ok(());
+    }
+
+    let value = match object.get(&key) {
+        Some(value) => apply_at(value, rest, operation)?,
+        None => return Err(operation.error("missing field")?),
+    };
+
+    object.insert(key, value);
+    Ok(())
+}
+
+/// Apply the operation to the element at the given index, which can only be
+/// replaced since the number of elements is fixed.
+fn apply_element(
+    elements: &mut [Value],
+    index: usize,
+    rest: &[Value],
+    operation: &Operation,
+) -> Result<(), VmError> {
+    let element = match elements.get_mut(index) {
+        Some(element) => element,
+        None => return Err(operation.error("index out of bounds")?),
+    };
+
+    if rest.is_empty() && operation.op != Op::Replace {
+        return Err(operation.error("elements can only be replaced")?);
+    }
+
+    *element = apply_at(element, rest, operation)?;
+    Ok(())
+}
+
+fn index(segment: &Value, operation: &Operation) -> Result<usize, VmError> {
+    match segment {
+        Value::Integer(index) if *index >= 0 => Ok(*index as usize),
+        _ => Err(operation.error("expected a non-negative index")?),
+    }
+}
+
+/// Format the given path in the same way as paths of schema validation
+/// errors, like `$.users[0].name`.
+fn format_path(path: &[Value]) -> Result<String, VmError> {
+    let mut output = String::from("$");
+
+    for segment in path {
+        match segment {
+            Value::Integer(index) => output.push_str(&format!("[{}]", index)),
+            Value::StaticString(..) | Value::String(..) => {
+                output.push('.');
+                output.push_str(&string(segment)?);
+            }
+            value => output.push_str(&format!("[<{}>]", value.type_info()?)),
+        }
+    }
+
+    Ok(output)
+}
+
+fn string(value: &Value) -> Result<String, VmError> {
+    Ok(match value {
+        Value::StaticString(string) => string.as_str().to_owned(),
+        Value::String(string) => string.borrow_ref()?.clone(),
+        value => return Err(VmError::expected::<String>(value.type_info()?)),
+    })
+}
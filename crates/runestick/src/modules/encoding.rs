@@ -0,0 +1,273 @@
+//! The `std::encoding` module.
+
+use crate::{Bytes, ContextError, Module, Value, VmError};
+use std::fmt;
+
+/// Construct the `std::encoding` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "encoding"]);
+    module.ty(&["DecodeError"]).build::<DecodeError>()?;
+    module.function(&["base64", "encode"], base64_encode)?;
+    module.function(&["base64", "decode"], base64_decode)?;
+    module.function(&["base64_url", "encode"], base64_url_encode)?;
+    module.function(&["base64_url", "decode"], base64_url_decode)?;
+    module.function(&["hex", "encode"], hex_encode)?;
+    module.function(&["hex", "decode"], hex_decode)?;
+    module.function(&["percent", "encode"], percent_encode)?;
+    module.function(&["percent", "decode"], percent_decode)?;
+    module.inst_fn(crate::STRING_DISPLAY, DecodeError::display)?;
+    Ok(module)
+}
+
+const BASE64_STANDARD: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const HEX: &[u8; 16] = b"0123456789abcdef";
+
+/// Error raised when decoding invalid input.
+#[derive(Debug)]
+pub struct DecodeError {
+    kind: &'static str,
+    position: usize,
+}
+
+impl DecodeError {
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        use std::fmt::Write as _;
+        write!(buf, "{}", self)
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "invalid {} input at byte {}", self.kind, self.position)
+    }
+}
+
+/// Encode the input as standard base64, with padding.
+fn base64_encode(input: Value) -> Result<String, VmError> {
+    with_input(&input, |input| encode_base64(input, BASE64_STANDARD, true))
+}
+
+/// Decode standard base64, where padding is optional.
+fn base64_decode(input: Value) -> Result<Result<Bytes, DecodeError>, VmError> {
+    with_input(&input, |input| decode_base64(input, BASE64_STANDARD))
+}
+
+/// Encode the input as url-safe base64, without padding.
+fn base64_url_encode(input: Value) -> Result<String, VmError> {
+    with_input(&input, |input| encode_base64(input, BASE64_URL, false))
+}
+
+/// Decode url-safe base64, where padding is optional.
+fn base64_url_decode(input: Value) -> Result<Result<Bytes, DecodeError>, VmError> {
+    with_input(&input, |input| decode_base64(input, BASE64_URL))
+}
+
+/// Encode the input as lowercase hex.
+fn hex_encode(input: Value) -> Result<String, VmError> {
+    with_input(&input, encode_hex)
+}
+
+/// Encode the given bytes as a lowercase hex string.
+pub fn encode_hex(input: &[u8]) -> String {
+    let mut output = String::with_capacity(input.len() * 2);
+
+    for b in input {
+        output.push(HEX[(b >> 4) as usize] as char);
+        output.push(HEX[(b & 0xf) as usize] as char);
+    }
+
+    output
+}
+
+/// Decode hex, in either case.
+fn hex_decode(input: Value) -> Result<Result<Bytes, DecodeError>, VmError> {
+    with_input(&input, |input| {
+        let error = |position| DecodeError {
+            kind: "hex",
+            position,
+        };
+
+        if input.len() % 2 != 0 {
+            return Err(error(input.len()));
+        }
+
+        let mut output = Vec::with_capacity(input.len() / 2);
+
+        for (n, pair) in input.chunks(2).enumerate() {
+            let hi = hex_digit(pair[0]).ok_or_else(|| error(n * 2))?;
+            let lo = hex_digit(pair[1]).ok_or_else(|| error(n * 2 + 1))?;
+            output.push(hi << 4 | lo);
+        }
+
+        Ok(Bytes::from_vec(output))
+    })
+}
+
+/// Percent-encode the input, leaving only unreserved characters as-is.
+fn percent_encode(input: Value) -> Result<String, VmError> {
+    with_input(&input, |input| {
+        let mut output = String::with_capacity(input.len());
+
+        for &b in input {
+            match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    output.push(b as char);
+                }
+                b => {
+                    output.push('%');
+                    output.push(HEX[(b >> 4) as usize].to_ascii_uppercase() as char);
+                    output.push(HEX[(b & 0xf) as usize].to_ascii_uppercase() as char);
+                }
+            }
+        }
+
+        output
+    })
+}
+
+/// Decode percent-encoded input into a string.
+fn percent_decode(input: Value) -> Result<Result<String, DecodeError>, VmError> {
+    with_input(&input, |input| {
+        let mut output = Vec::with_capacity(input.len());
+        let mut n = 0;
+
+        while n < input.len() {
+            match input[n] {
+                b'%' => {
+                    let error = DecodeError {
+                        kind: "percent",
+                        position: n,
+                    };
+
+                    let hi = input.get(n + 1).copied().and_then(hex_digit);
+                    let lo = input.get(n + 2).copied().and_then(hex_digit);
+
+                    match (hi, lo) {
+                        (Some(hi), Some(lo)) => output.push(hi << 4 | lo),
+                        _ => return Err(error),
+                    }
+
+                    n += 3;
+                }
+                b => {
+                    output.push(b);
+                    n += 1;
+                }
+            }
+        }
+
+        String::from_utf8(output).map_err(|e| DecodeError {
+            kind: "utf-8",
+            position: e.utf8_error().valid_up_to(),
+        })
+    })
+}
+
+fn encode_base64(input: &[u8], alphabet: &[u8; 64], pad: bool) -> String {
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+
+    for chunk in input.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or_default(),
+            chunk.get(2).copied().unwrap_or_default(),
+        ];
+
+        let indexes = [
+            b[0] >> 2,
+            (b[0] & 0x03) << 4 | b[1] >> 4,
+            (b[1] & 0x0f) << 2 | b[2] >> 6,
+            b[2] & 0x3f,
+        ];
+
+        for (n, index) in indexes.iter().enumerate() {
+            if n <= chunk.len() {
+                output.push(alphabet[*index as usize] as char);
+            } else if pad {
+                output.push('=');
+            }
+        }
+    }
+
+    output
+}
+
+fn decode_base64(input: &[u8], alphabet: &[u8; 64]) -> Result<Bytes, DecodeError> {
+    let error = |position| DecodeError {
+        kind: "base64",
+        position,
+    };
+
+    let data = match input.iter().position(|b| *b == b'=') {
+        Some(n) => {
+            // NB: padding must only fill out the last group of four.
+            let padding = (4 - n % 4) % 4;
+
+            if input[n..].iter().any(|b| *b != b'=') || input.len() - n != padding {
+                return Err(error(n));
+            }
+
+            &input[..n]
+        }
+        None => input,
+    };
+
+    if data.len() % 4 == 1 {
+        return Err(error(data.len()));
+    }
+
+    let mut output = Vec::with_capacity(data.len() * 3 / 4);
+    let mut acc = 0u32;
+    let mut bits = 0;
+
+    for (n, b) in data.iter().enumerate() {
+        let value = alphabet
+            .iter()
+            .position(|c| c == b)
+            .ok_or_else(|| error(n))?;
+
+        acc = acc << 6 | value as u32;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            output.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+
+    // NB: the bits left over in the last character must be zero, so that
+    // every encoding decodes to distinct bytes.
+    if acc != 0 {
+        return Err(error(data.len() - 1));
+    }
+
+    Ok(Bytes::from_vec(output))
+}
+
+fn hex_digit(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Access the bytes of the input to encode or decode, which is either a string
+/// or bytes.
+pub fn with_input<F, T>(value: &Value, f: F) -> Result<T, VmError>
+where
+    F: FnOnce(&[u8]) -> T,
+{
+    Ok(match value {
+        Value::StaticString(string) => f(string.as_ref().as_bytes()),
+        Value::String(string) => f(string.borrow_ref()?.as_bytes()),
+        Value::Bytes(bytes) => f(&bytes.borrow_ref()?),
+        actual => return Err(VmError::expected::<String>(actual.type_info()?)),
+    })
+}
+
+impl_external!(DecodeError);
@@ -0,0 +1,132 @@
+//! The `std::enums` module.
+//!
+//! Converts between the variants of enums declared in scripts and their names,
+//! so that enums can be serialized or listed without hand-written match
+//! tables:
+//!
+//! ```text
+//! use std::enums;
+//!
+//! enum Color { Red, Green, Blue }
+//!
+//! fn main() {
+//!     let name = enums::variant_name(Color::Green); // => Some("Green")
+//!     let color = enums::from_name(Color, "Blue"); // => Some(Color::Blue)
+//!
+//!     for name in enums::variants(Color) {
+//!         println(name);
+//!     }
+//! }
+//! ```
+//!
+//! Enums are looked up in the unit of the calling script.
+
+use crate::{Caller, ContextError, Hash, Item, Module, Unit, UnitFn, Value, VmError};
+use std::sync::Arc;
+
+/// Construct the `std::enums` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "enums"]);
+    module.function(&["variant_name"], variant_name)?;
+    module.function(&["from_name"], from_name)?;
+    module.function(&["variants"], variants)?;
+    Ok(module)
+}
+
+/// Get the name of the variant of the given value, if it's an enum variant.
+fn variant_name(value: Value) -> Result<Option<String>, VmError> {
+    let hash = match &value {
+        Value::TupleVariant(variant) => variant.borrow_ref()?.hash,
+        Value::VariantObject(variant) => variant.borrow_ref()?.hash,
+        Value::Option(option) => {
+            let name = if option.borrow_ref()?.is_some() {
+                "Some"
+            } else {
+                "None"
+            };
+
+            return Ok(Some(String::from(name)));
+        }
+        Value::Result(result) => {
+            let name = if result.borrow_ref()?.is_ok() {
+                "Ok"
+            } else {
+                "Err"
+            };
+
+            return Ok(Some(String::from(name)));
+        }
+        _ => return Ok(None),
+    };
+
+    let unit = caller_unit()?;
+    Ok(unit.lookup_type_name(hash).and_then(last_component))
+}
+
+/// Construct the variant of the given enum with the given name.
+///
+/// Only variants without fields can be constructed by name.
+fn from_name(ty: Value, name: &str) -> Result<Option<Value>, VmError> {
+    let unit = caller_unit()?;
+    let enum_hash = enum_hash(&ty)?;
+
+    for hash in unit.lookup_variants(enum_hash).unwrap_or_default() {
+        let item = match unit.lookup_type_name(*hash) {
+            Some(item) => item,
+            None => continue,
+        };
+
+        if last_component(item).as_deref() != Some(name) {
+            continue;
+        }
+
+        return match unit.lookup(*hash) {
+            Some(UnitFn::TupleVariant {
+                enum_hash,
+                hash,
+                args: 0,
+            }) => Ok(Some(Value::variant_tuple(enum_hash, hash, Vec::new()))),
+            _ => Err(VmError::panic(format!(
+                "variant `{}` has fields and can't be constructed by name",
+                item
+            ))),
+        };
+    }
+
+    Ok(None)
+}
+
+/// Get the names of the variants of the given enum, in declaration order.
+fn variants(ty: Value) -> Result<Vec<String>, VmError> {
+    let unit = caller_unit()?;
+    let enum_hash = enum_hash(&ty)?;
+
+    Ok(unit
+        .lookup_variants(enum_hash)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|hash| unit.lookup_type_name(*hash).and_then(last_component))
+        .collect())
+}
+
+/// Get the hash of the enum the given type value refers to.
+fn enum_hash(ty: &Value) -> Result<Hash, VmError> {
+    match ty {
+        Value::Type(hash) => Ok(*hash),
+        value => Err(VmError::panic(format!(
+            "expected an enum, but found `{}`",
+            value.type_info()?
+        ))),
+    }
+}
+
+fn caller_unit() -> Result<Arc<Unit>, VmError> {
+    match Caller::current() {
+        Some(caller) => Ok(caller.unit().clone()),
+        None => Err(VmError::panic("enums can only be inspected from a script")),
+    }
+}
+
+fn last_component(item: &Item) -> Option<String> {
+    Some(item.last()?.to_string())
+}
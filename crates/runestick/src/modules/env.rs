@@ -0,0 +1,36 @@
+//! The `std::env` module.
+//!
+//! Gives scripts access to the command-line arguments they were started with.
+//!
+//! This module isn't part of the default modules, since the arguments are
+//! provided by the embedder.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use runestick::modules::env;
+//!
+//! # fn main() -> runestick::Result<()> {
+//! let mut context = runestick::Context::with_default_modules()?;
+//! context.install(&env::module(vec![String::from("script.rn")])?)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{ContextError, Module};
+use std::sync::Arc;
+
+/// Construct the `std::env` module, where `std::env::args()` returns the
+/// given arguments.
+///
+/// Like [std::env::args], the first argument is by convention the name of the
+/// script being run.
+pub fn module<I>(args: I) -> Result<Module, ContextError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let args = args.into_iter().collect::<Arc<[String]>>();
+    let mut module = Module::new(&["std", "env"]);
+    module.function(&["args"], move || args.to_vec())?;
+    Ok(module)
+}
@@ -13,6 +13,17 @@ fn to_integer(value: f64) -> i64 {
     value as i64
 }
 
+/// Round a float to the given number of decimals.
+fn round(value: f64, decimals: usize) -> f64 {
+    let factor = 10f64.powi(decimals as i32);
+    (value * factor).round() / factor
+}
+
+/// Format a float with exactly the given number of decimals.
+fn to_fixed(value: f64, decimals: usize) -> String {
+    format!("{:.*}", decimals, value)
+}
+
 impl_external!(ParseFloatError);
 
 /// Install the core package into the given functions namespace.
@@ -25,6 +36,10 @@ pub fn module() -> Result<Module, ContextError> {
         .build::<ParseFloatError>()?;
     module.function(&["float", "parse"], parse)?;
     module.inst_fn("to_integer", to_integer)?;
+    module.inst_fn("round", round)?;
+    module.inst_fn("to_fixed", to_fixed)?;
+    module.inst_fn("div_euclid", f64::div_euclid)?;
+    module.inst_fn("rem_euclid", f64::rem_euclid)?;
 
     Ok(module)
 }
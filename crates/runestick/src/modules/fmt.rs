@@ -1,6 +1,6 @@
 //! The `std::fmt` module.
 
-use crate::{ContextError, Module};
+use crate::{ContextError, FromValue as _, Module, Object, Value, VmError};
 use std::fmt;
 use std::fmt::Write as _;
 
@@ -9,9 +9,174 @@ pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::new(&["std", "fmt"]);
     module.ty(&["Error"]).build::<std::fmt::Error>()?;
     module.inst_fn(crate::STRING_DISPLAY, format_fmt_error)?;
+    module.function(&["format_int"], format_int)?;
+    module.function(&["format_float"], format_float)?;
     Ok(module)
 }
 
 fn format_fmt_error(error: &std::fmt::Error, buf: &mut String) -> fmt::Result {
     write!(buf, "{}", error)
 }
+
+/// Format an integer according to the given options.
+///
+/// See [NumberFormat] for the supported options.
+fn format_int(value: i64, options: &Object<Value>) -> Result<String, VmError> {
+    let format = NumberFormat::from_options(options)?;
+    let digits = value.unsigned_abs().to_string();
+    Ok(format.finish(value < 0, &digits, None))
+}
+
+/// Format a float according to the given options.
+///
+/// See [NumberFormat] for the supported options.
+fn format_float(value: f64, options: &Object<Value>) -> Result<String, VmError> {
+    let format = NumberFormat::from_options(options)?;
+
+    // NB: NaN has no sign, and neither it nor infinities are grouped or
+    // padded with zeros.
+    if value.is_nan() {
+        return Ok(format.pad("", "NaN", false));
+    }
+
+    if value.is_infinite() {
+        let sign = format.sign(value.is_sign_negative());
+        return Ok(format.pad(sign, "inf", false));
+    }
+
+    let digits = match format.precision {
+        Some(precision) => format!("{:.*}", precision, value.abs()),
+        None => value.abs().to_string(),
+    };
+
+    let (whole, fraction) = match digits.find('.') {
+        Some(n) => (&digits[..n], Some(&digits[n + 1..])),
+        None => (&digits[..], None),
+    };
+
+    Ok(format.finish(value.is_sign_negative(), whole, fraction))
+}
+
+/// Options used when formatting numbers.
+///
+/// Formatting is locale-independent, everything which differs between locales
+/// has to be specified explicitly:
+/// * `separator` - the string inserted between groups of digits in the whole
+///   part of the number. Defaults to no separator.
+/// * `group` - the number of digits in each group. Defaults to `3`.
+/// * `decimal` - the string separating the whole part of a float from its
+///   fraction. Defaults to `"."`.
+/// * `precision` - the number of digits in the fraction of a float.
+/// * `sign` - always include the sign of the number, even if it's positive.
+/// * `width` - the minimum width of the formatted number, which is
+///   right-aligned.
+/// * `fill` - the character used to pad the number to `width`. Defaults to a
+///   space. If this is `'0'`, padding is inserted after the sign.
+struct NumberFormat {
+    separator: String,
+    group: usize,
+    decimal: String,
+    precision: Option<usize>,
+    sign: bool,
+    width: usize,
+    fill: char,
+}
+
+impl NumberFormat {
+    /// Parse number format options from an object.
+    fn from_options(options: &Object<Value>) -> Result<Self, VmError> {
+        let mut format = Self {
+            separator: String::new(),
+            group: 3,
+            decimal: String::from("."),
+            precision: None,
+            sign: false,
+            width: 0,
+            fill: ' ',
+        };
+
+        for (key, value) in options {
+            let value = value.clone();
+
+            match key.as_str() {
+                "separator" => format.separator = String::from_value(value)?,
+                "group" => format.group = usize::from_value(value)?,
+                "decimal" => format.decimal = String::from_value(value)?,
+                "precision" => format.precision = Some(usize::from_value(value)?),
+                "sign" => format.sign = bool::from_value(value)?,
+                "width" => format.width = usize::from_value(value)?,
+                "fill" => format.fill = char::from_value(value)?,
+                key => {
+                    return Err(VmError::panic(format!(
+                        "unsupported number format option `{}`",
+                        key
+                    )));
+                }
+            }
+        }
+
+        if format.group == 0 {
+            return Err(VmError::panic(
+                "number format option `group` must be non-zero",
+            ));
+        }
+
+        Ok(format)
+    }
+
+    /// Finish formatting a number, given its sign and the digits of its whole
+    /// part and its fraction.
+    fn finish(&self, negative: bool, whole: &str, fraction: Option<&str>) -> String {
+        let mut number = String::new();
+
+        for (n, c) in whole.chars().enumerate() {
+            if n > 0 && (whole.len() - n).is_multiple_of(self.group) {
+                number.push_str(&self.separator);
+            }
+
+            number.push(c);
+        }
+
+        if let Some(fraction) = fraction {
+            number.push_str(&self.decimal);
+            number.push_str(fraction);
+        }
+
+        self.pad(self.sign(negative), &number, true)
+    }
+
+    /// Get the sign to display for a number.
+    fn sign(&self, negative: bool) -> &'static str {
+        match (negative, self.sign) {
+            (true, _) => "-",
+            (false, true) => "+",
+            (false, false) => "",
+        }
+    }
+
+    /// Pad the given sign and number to the configured width.
+    ///
+    /// If `zeros` is `false`, a fill of `'0'` pads with spaces instead.
+    fn pad(&self, sign: &str, number: &str, zeros: bool) -> String {
+        let fill = match self.fill {
+            '0' if !zeros => ' ',
+            fill => fill,
+        };
+
+        let len = sign.chars().count() + number.chars().count();
+        let padding = std::iter::repeat_n(fill, self.width.saturating_sub(len));
+
+        let mut output = String::new();
+
+        if fill == '0' {
+            output.push_str(sign);
+            output.extend(padding);
+        } else {
+            output.extend(padding);
+            output.push_str(sign);
+        }
+
+        output.push_str(number);
+        output
+    }
+}
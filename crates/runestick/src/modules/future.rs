@@ -1,7 +1,7 @@
 //! The `std::future` module.
 
 use crate::future::SelectFuture;
-use crate::{ContextError, Future, Module, Shared, Stack, Value, VmError, VmErrorKind};
+use crate::{ContextError, Future, Module, Stack, Value, VmError};
 
 /// Construct the `std::future` module.
 pub fn module() -> Result<Module, ContextError> {
@@ -55,15 +55,6 @@ async fn join(value: Value) -> Result<Value, VmError> {
 
 /// The join implementation.
 fn raw_join(stack: &mut Stack, args: usize) -> Result<(), VmError> {
-    if args != 1 {
-        return Err(VmError::from(VmErrorKind::BadArgumentCount {
-            actual: args,
-            expected: 1,
-        }));
-    }
-
-    let value = stack.pop()?;
-    let value = Value::Future(Shared::new(Future::new(join(value))));
-    stack.push(value);
-    Ok(())
+    let (value,) = stack.take_args(args)?;
+    stack.push_value(Future::new(join(value)))
 }
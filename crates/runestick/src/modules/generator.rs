@@ -10,5 +10,7 @@ pub fn module() -> Result<Module, ContextError> {
 
     module.inst_fn("next", Generator::next)?;
     module.inst_fn("resume", Generator::resume)?;
+    module.inst_fn("is_complete", Generator::is_complete)?;
+    module.inst_fn("completion", Generator::completion)?;
     Ok(module)
 }
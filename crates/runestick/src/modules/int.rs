@@ -1,6 +1,6 @@
 //! The `std::int` module.
 
-use crate::{ContextError, Module};
+use crate::{ContextError, Module, VmError, VmErrorKind};
 use std::num::ParseIntError;
 
 /// Construct the `std::int` module.
@@ -20,6 +20,8 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("checked_div", i64::checked_div)?;
     module.inst_fn("checked_mul", i64::checked_mul)?;
     module.inst_fn("checked_rem", i64::checked_rem)?;
+    module.inst_fn("checked_div_euclid", i64::checked_div_euclid)?;
+    module.inst_fn("checked_rem_euclid", i64::checked_rem_euclid)?;
 
     module.inst_fn("wrapping_add", i64::wrapping_add)?;
     module.inst_fn("wrapping_sub", i64::wrapping_sub)?;
@@ -33,7 +35,19 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("saturating_abs", i64::saturating_abs)?;
     module.inst_fn("saturating_pow", i64::saturating_pow)?;
 
+    module.inst_fn("div_euclid", div_euclid)?;
+    module.inst_fn("rem_euclid", rem_euclid)?;
+
     module.inst_fn("pow", i64::pow)?;
+
+    module.inst_fn("rotate_left", i64::rotate_left)?;
+    module.inst_fn("rotate_right", i64::rotate_right)?;
+    module.inst_fn("count_ones", i64::count_ones)?;
+    module.inst_fn("count_zeros", i64::count_zeros)?;
+    module.inst_fn("leading_zeros", i64::leading_zeros)?;
+    module.inst_fn("trailing_zeros", i64::trailing_zeros)?;
+    module.inst_fn("swap_bytes", i64::swap_bytes)?;
+    module.inst_fn("reverse_bits", i64::reverse_bits)?;
     Ok(module)
 }
 
@@ -42,6 +56,26 @@ fn parse(s: &str) -> Result<i64, ParseIntError> {
     Ok(str::parse::<i64>(s)?)
 }
 
+/// Euclidean division, which rounds the quotient so that the remainder is
+/// never negative.
+fn div_euclid(lhs: i64, rhs: i64) -> Result<i64, VmError> {
+    if rhs == 0 {
+        return Err(VmError::from(VmErrorKind::DivideByZero));
+    }
+
+    lhs.checked_div_euclid(rhs)
+        .ok_or_else(|| VmError::from(VmErrorKind::Overflow))
+}
+
+/// Euclidean remainder, which is never negative.
+fn rem_euclid(lhs: i64, rhs: i64) -> Result<i64, VmError> {
+    if rhs == 0 {
+        return Err(VmError::from(VmErrorKind::DivideByZero));
+    }
+
+    Ok(lhs.wrapping_rem_euclid(rhs))
+}
+
 /// Convert a whole number to float.
 fn to_float(value: i64) -> f64 {
     value as f64
@@ -13,6 +13,8 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("rev", Range::rev)?;
     module.inst_fn(crate::INTO_ITER, Rev::into_iter)?;
     module.inst_fn(crate::NEXT, Rev::next)?;
+    module.interface(&["Iterator"], &[crate::NEXT])?;
+    module.interface(&["IntoIterator"], &[crate::INTO_ITER])?;
     Ok(module)
 }
 
@@ -35,8 +37,9 @@ impl Iterator for Rev {
     }
 }
 
+/// A range of integers, as constructed by `std::iter::range`.
 #[derive(Debug)]
-struct Range {
+pub(crate) struct Range {
     current: i64,
     end: i64,
 }
@@ -49,6 +52,11 @@ impl Range {
         }
     }
 
+    /// The remaining bounds of the range, as `(start, end)`.
+    pub(crate) fn bounds(&self) -> (i64, i64) {
+        (self.current, self.end)
+    }
+
     fn rev(self) -> Rev {
         Rev {
             current: self.end,
@@ -0,0 +1,286 @@
+//! The `std::mem` module.
+//!
+//! Provides `content_hash`, which computes a hash of the structure and content
+//! of a value, suitable as a cache key or to detect changes to data between
+//! runs:
+//!
+//! ```text
+//! use std::mem;
+//!
+//! fn main() {
+//!     let key = mem::content_hash(#{rules: rules, input: input});
+//!
+//!     if key != previous_key {
+//!         // recompute
+//!     }
+//! }
+//! ```
+//!
+//! Equal values have equal hashes, and the hash of an object doesn't depend on
+//! the order in which its fields were inserted. The hash is stable between
+//! runs, processes and platforms, so it can be persisted. External values are
+//! hashed through the [HASH][crate::HASH] protocol.
+
+use crate::{Caller, Context, ContextError, FromValue as _, Hash, Module, Stack, Value, VmError};
+use std::hash::Hasher;
+use twox_hash::XxHash64;
+
+/// Construct the `std::mem` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "mem"]);
+    module.function(&["content_hash"], content_hash)?;
+    Ok(module)
+}
+
+// Tags distinguishing the kinds of values, so that values of different kinds
+// with the same content hash differently.
+const UNIT: u8 = 0;
+const BOOL: u8 = 1;
+const BYTE: u8 = 2;
+const CHAR: u8 = 3;
+const INTEGER: u8 = 4;
+const FLOAT: u8 = 5;
+const STRING: u8 = 6;
+const BYTES: u8 = 7;
+const VEC: u8 = 8;
+const TUPLE: u8 = 9;
+const OBJECT: u8 = 10;
+const TYPED_TUPLE: u8 = 11;
+const TYPED_OBJECT: u8 = 12;
+const OPTION: u8 = 13;
+const RESULT: u8 = 14;
+const TYPE: u8 = 15;
+const EXTERNAL: u8 = 16;
+
+/// Compute the content hash of the given value.
+///
+/// The whole value is borrowed while it's being hashed, so the hash reflects a
+/// single consistent state of it. Errors if the value contains itself, is
+/// being modified, or contains a value which can't be hashed, like a function
+/// or a future.
+fn content_hash(value: Value) -> Result<i64, VmError> {
+    let caller = Caller::current();
+
+    let mut state = State {
+        hasher: XxHash64::with_seed(0),
+        context: caller.as_ref().map(|caller| &**caller.context()),
+        visiting: Vec::new(),
+    };
+
+    state.hash(&value)?;
+    Ok(state.hasher.finish() as i64)
+}
+
+struct State<'a> {
+    hasher: XxHash64,
+    /// The context used to look up the [HASH][crate::HASH] protocol of
+    /// external values.
+    context: Option<&'a Context>,
+    /// Containers which are currently being hashed, used to detect values
+    /// which contain themselves.
+    visiting: Vec<*const ()>,
+}
+
+impl State<'_> {
+    fn hash(&mut self, value: &Value) -> Result<(), VmError> {
+        match value {
+            Value::Unit => self.tag(UNIT),
+            Value::Bool(b) => {
+                self.tag(BOOL);
+                self.hasher.write_u8(*b as u8);
+            }
+            Value::Byte(b) => {
+                self.tag(BYTE);
+                self.hasher.write_u8(*b);
+            }
+            Value::Char(c) => {
+                self.tag(CHAR);
+                self.hasher.write(&(*c as u32).to_le_bytes());
+            }
+            Value::Integer(n) => {
+                self.tag(INTEGER);
+                self.hasher.write(&n.to_le_bytes());
+            }
+            Value::Float(n) => {
+                self.tag(FLOAT);
+                // NB: `0.0` and `-0.0` are equal, so they must hash the same.
+                let n = if *n == 0.0 { 0.0 } else { *n };
+                self.hasher.write(&n.to_bits().to_le_bytes());
+            }
+            Value::StaticString(string) => self.string(string.as_str()),
+            Value::String(string) => self.string(&*string.borrow_ref()?),
+            Value::Bytes(bytes) => {
+                let bytes = bytes.borrow_ref()?;
+                self.tag(BYTES);
+                self.hasher.write(&(bytes.len() as u64).to_le_bytes());
+                self.hasher.write(&*bytes);
+            }
+            Value::Vec(vec) => {
+                self.enter(vec.as_ptr())?;
+                self.sequence(VEC, &*vec.borrow_ref()?)?;
+                self.visiting.pop();
+            }
+            Value::Tuple(tuple) => {
+                self.enter(tuple.as_ptr())?;
+                self.sequence(TUPLE, &*tuple.borrow_ref()?)?;
+                self.visiting.pop();
+            }
+            Value::Object(object) => {
+                self.enter(object.as_ptr())?;
+                self.tag(OBJECT);
+                self.object(&*object.borrow_ref()?)?;
+                self.visiting.pop();
+            }
+            Value::TypedTuple(typed) => {
+                self.enter(typed.as_ptr())?;
+                let typed = typed.borrow_ref()?;
+                self.tag(TYPED_TUPLE);
+                self.hasher.write(&typed.hash.to_le_bytes());
+                self.sequence(TUPLE, &typed.tuple)?;
+                self.visiting.pop();
+            }
+            Value::TupleVariant(variant) => {
+                self.enter(variant.as_ptr())?;
+                let variant = variant.borrow_ref()?;
+                self.tag(TYPED_TUPLE);
+                self.hasher.write(&variant.hash.to_le_bytes());
+                self.sequence(TUPLE, &variant.tuple)?;
+                self.visiting.pop();
+            }
+            Value::TypedObject(typed) => {
+                self.enter(typed.as_ptr())?;
+                let typed = typed.borrow_ref()?;
+                self.tag(TYPED_OBJECT);
+                self.hasher.write(&typed.hash.to_le_bytes());
+                self.object(&typed.object)?;
+                self.visiting.pop();
+            }
+            Value::VariantObject(variant) => {
+                self.enter(variant.as_ptr())?;
+                let variant = variant.borrow_ref()?;
+                self.tag(TYPED_OBJECT);
+                self.hasher.write(&variant.hash.to_le_bytes());
+                self.object(&variant.object)?;
+                self.visiting.pop();
+            }
+            Value::Option(option) => {
+                self.enter(option.as_ptr())?;
+                self.tag(OPTION);
+
+                match &*option.borrow_ref()? {
+                    Some(value) => {
+                        self.hasher.write_u8(1);
+                        self.hash(value)?;
+                    }
+                    None => self.hasher.write_u8(0),
+                }
+
+                self.visiting.pop();
+            }
+            Value::Result(result) => {
+                self.enter(result.as_ptr())?;
+                self.tag(RESULT);
+
+                match &*result.borrow_ref()? {
+                    Ok(value) => {
+                        self.hasher.write_u8(1);
+                        self.hash(value)?;
+                    }
+                    Err(value) => {
+                        self.hasher.write_u8(0);
+                        self.hash(value)?;
+                    }
+                }
+
+                self.visiting.pop();
+            }
+            Value::Type(hash) => {
+                self.tag(TYPE);
+                self.hasher.write(&hash.to_le_bytes());
+            }
+            Value::Any(..) => self.external(value)?,
+            value => {
+                return Err(VmError::panic(format!(
+                    "`{}` can't be content hashed",
+                    value.type_info()?
+                )))
+            }
+        }
+
+        Ok(())
+    }
+
+    fn tag(&mut self, tag: u8) {
+        self.hasher.write_u8(tag);
+    }
+
+    fn string(&mut self, string: &str) {
+        self.tag(STRING);
+        self.hasher.write(&(string.len() as u64).to_le_bytes());
+        self.hasher.write(string.as_bytes());
+    }
+
+    fn sequence(&mut self, tag: u8, values: &[Value]) -> Result<(), VmError> {
+        self.tag(tag);
+        self.hasher.write(&(values.len() as u64).to_le_bytes());
+
+        for value in values {
+            self.hash(value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Hash the fields of an object in the order of their keys.
+    fn object(&mut self, object: &crate::Object<Value>) -> Result<(), VmError> {
+        let mut entries = object.iter().collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+
+        self.hasher.write(&(entries.len() as u64).to_le_bytes());
+
+        for (key, value) in entries {
+            self.string(key);
+            self.hash(value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Hash an external value through the [HASH][crate::HASH] protocol.
+    fn external(&mut self, value: &Value) -> Result<(), VmError> {
+        let value_type = value.value_type()?;
+        let hash = Hash::instance_function(value_type, crate::HASH);
+
+        let handler = match self.context.and_then(|context| context.lookup(hash)) {
+            Some(handler) => handler,
+            None => {
+                return Err(VmError::panic(format!(
+                    "`{}` can't be content hashed, since it doesn't implement the hash protocol",
+                    value.type_info()?
+                )))
+            }
+        };
+
+        let mut stack = Stack::new();
+        stack.push(value.clone());
+        handler(&mut stack, 1)?;
+
+        self.tag(EXTERNAL);
+        self.hasher.write(&value_type.as_type_hash().to_le_bytes());
+        self.hasher
+            .write(&i64::from_value(stack.pop()?)?.to_le_bytes());
+        Ok(())
+    }
+
+    /// Mark the container with the given pointer as being hashed.
+    fn enter(&mut self, ptr: *const ()) -> Result<(), VmError> {
+        if self.visiting.contains(&ptr) {
+            return Err(VmError::panic(
+                "can't content hash a value which contains itself",
+            ));
+        }
+
+        self.visiting.push(ptr);
+        Ok(())
+    }
+}
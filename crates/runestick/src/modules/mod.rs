@@ -2,7 +2,15 @@
 //! machines.
 
 pub mod bytes;
+pub mod cache;
+pub mod channel;
+pub mod char;
+pub mod collections;
 pub mod core;
+pub mod diff;
+pub mod encoding;
+pub mod enums;
+pub mod env;
 pub mod float;
 pub mod fmt;
 pub mod future;
@@ -10,10 +18,14 @@ pub mod generator;
 pub mod int;
 pub mod io;
 pub mod iter;
+pub mod mem;
 pub mod object;
 pub mod option;
 pub mod result;
+pub mod schema;
+pub mod store;
 pub mod stream;
 pub mod string;
 pub mod test;
+pub mod tuple;
 pub mod vec;
@@ -1,6 +1,9 @@
 //! The `std::object` module.
 
-use crate::{ContextError, Module, Object, Value};
+use crate::{
+    Caller, ContextError, Function, Hash, Module, Object, Shared, Stack, TypeInfo, Value, VmError,
+    VmErrorKind,
+};
 use std::iter::Rev;
 
 /// Construct the `std::object` module.
@@ -16,6 +19,8 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("clear", Object::<Value>::clear)?;
     module.inst_fn("contains_key", contains_key)?;
     module.inst_fn("get", get)?;
+    module.inst_fn("retain", retain)?;
+    module.raw_fn(&["fields"], raw_fields)?;
 
     module.inst_fn(crate::INTO_ITER, object_iter)?;
     module.inst_fn("next", Iter::next)?;
@@ -68,5 +73,89 @@ fn get(object: &Object<Value>, key: &str) -> Option<Value> {
     object.get(key).cloned()
 }
 
+/// Only retain the entries for which the given predicate returns `true`.
+///
+/// The predicate is called with the key and the value of each entry. It can
+/// access the object while it's being called, but it's an error to modify it.
+fn retain(object: Shared<Object<Value>>, predicate: &Function) -> Result<(), VmError> {
+    let snapshot = object.borrow_ref()?.clone();
+    let mut removed = Vec::new();
+
+    for (key, value) in &snapshot {
+        if !predicate.call::<_, bool>((key.clone(), value.clone()))? {
+            removed.push(key);
+        }
+    }
+
+    let mut object = object.borrow_mut()?;
+
+    let unmodified = object.len() == snapshot.len()
+        && snapshot.iter().all(|(key, a)| match object.get(key) {
+            Some(b) => Value::is_same(a, b),
+            None => false,
+        });
+
+    if !unmodified {
+        return Err(VmError::from(VmErrorKind::ModifiedDuringIteration {
+            target: TypeInfo::StaticType(crate::OBJECT_TYPE),
+        }));
+    }
+
+    for key in removed {
+        object.remove(key);
+    }
+
+    Ok(())
+}
+
+/// Get the fields of the given object, struct or struct variant as key/value
+/// pairs.
+///
+/// Fields of structs and struct variants declared in the calling script are
+/// returned in declaration order, while the fields of anonymous objects are
+/// sorted by key.
+fn fields(value: Value) -> Result<Vec<(String, Value)>, VmError> {
+    let (hash, object) = match &value {
+        Value::Object(object) => (None, object.borrow_ref()?.clone()),
+        Value::TypedObject(object) => {
+            let object = object.borrow_ref()?;
+            (Some(object.hash), object.object.clone())
+        }
+        Value::VariantObject(object) => {
+            let object = object.borrow_ref()?;
+            (Some(object.hash), object.object.clone())
+        }
+        value => return Err(VmError::expected::<Object<Value>>(value.type_info()?)),
+    };
+
+    Ok(ordered_fields(hash, object))
+}
+
+/// The fields implementation, which raises errors as they are instead of as
+/// bad return values.
+fn raw_fields(stack: &mut Stack, args: usize) -> Result<(), VmError> {
+    let (value,) = stack.take_args(args)?;
+    stack.push_value(fields(value)?)
+}
+
+/// Order the fields of an object by the declaration of the type with the
+/// given hash, with any remaining fields sorted by key.
+fn ordered_fields(hash: Option<Hash>, mut object: Object<Value>) -> Vec<(String, Value)> {
+    let mut output = Vec::with_capacity(object.len());
+
+    if let (Some(hash), Some(caller)) = (hash, Caller::current()) {
+        for key in caller.unit().lookup_fields(hash).unwrap_or_default() {
+            if let Some(value) = object.remove(key) {
+                output.push((key.clone(), value));
+            }
+        }
+    }
+
+    let mut rest = object.into_iter().collect::<Vec<_>>();
+    rest.sort_by(|a, b| a.0.cmp(&b.0));
+    output.extend(rest);
+    output
+}
+
 impl_external!(Iter);
 impl_external!(Rev<Iter>);
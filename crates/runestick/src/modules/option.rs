@@ -9,10 +9,15 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("is_some", Option::<Value>::is_some)?;
     module.inst_fn("unwrap_or_else", unwrap_or_else_impl)?;
     module.inst_fn("transpose", transpose_impl)?;
+    module.inst_fn("map", map_impl)?;
+    module.inst_fn("and_then", and_then_impl)?;
+    module.inst_fn("ok_or", ok_or_impl)?;
+    module.inst_fn("unwrap", unwrap_impl)?;
+    module.inst_fn("expect", expect_impl)?;
     Ok(module)
 }
 
-use crate::{ContextError, Function, Module, Shared, Value, VmError};
+use crate::{ContextError, Function, Module, Panic, Shared, Value, VmError};
 
 fn unwrap_or_else_impl(this: &Option<Value>, default: Function) -> Result<Value, VmError> {
     if let Some(this) = this {
@@ -32,3 +37,44 @@ fn transpose_impl(this: &Option<Value>) -> Result<Value, VmError> {
         None => Ok(Value::from(Shared::new(None::<Value>))),
     })))
 }
+
+/// Maps the contained value of `Some` using the given function.
+fn map_impl(this: &Option<Value>, f: &Function) -> Result<Option<Value>, VmError> {
+    match this {
+        Some(value) => Ok(Some(f.call::<_, Value>((value.clone(),))?)),
+        None => Ok(None),
+    }
+}
+
+/// Calls the given function with the contained value of `Some`, which is
+/// expected to return another option.
+fn and_then_impl(this: &Option<Value>, f: &Function) -> Result<Option<Value>, VmError> {
+    match this {
+        Some(value) => Ok(f.call::<_, Option<Value>>((value.clone(),))?),
+        None => Ok(None),
+    }
+}
+
+/// Converts into a `Result`, mapping `None` to `Err(err)`.
+fn ok_or_impl(this: &Option<Value>, err: Value) -> Result<Value, Value> {
+    match this {
+        Some(value) => Ok(value.clone()),
+        None => Err(err),
+    }
+}
+
+/// Unwraps the contained value, or panics.
+fn unwrap_impl(this: &Option<Value>) -> Result<Value, Panic> {
+    match this {
+        Some(value) => Ok(value.clone()),
+        None => Err(Panic::custom("called `Option::unwrap()` on a `None` value").at_caller()),
+    }
+}
+
+/// Unwraps the contained value, or panics with the given message.
+fn expect_impl(this: &Option<Value>, message: &str) -> Result<Value, Panic> {
+    match this {
+        Some(value) => Ok(value.clone()),
+        None => Err(Panic::custom(message.to_owned()).at_caller()),
+    }
+}
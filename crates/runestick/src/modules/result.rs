@@ -1,13 +1,27 @@
 //! The `std::result` module.
 
-use crate::{ContextError, Module, Value};
+use crate::{
+    Caller, ContextError, Function, HostError, Module, Panic, Value, VmError, VmErrorKind,
+};
+use std::fmt;
+use std::fmt::Write as _;
 
 /// Construct the `std::result` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::new(&["std", "result"]);
     module.result(&["Result"])?;
+    module.ty(&["HostError"]).build::<HostError>()?;
     module.inst_fn("is_ok", is_ok)?;
     module.inst_fn("is_err", is_err)?;
+    module.inst_fn("map", map)?;
+    module.inst_fn("and_then", and_then)?;
+    module.inst_fn("unwrap_or", unwrap_or)?;
+    module.inst_fn("unwrap_or_else", unwrap_or_else)?;
+    module.inst_fn("unwrap", unwrap)?;
+    module.inst_fn("expect", expect)?;
+    module.inst_fn("message", host_error_message)?;
+    module.inst_fn("type_name", host_error_type_name)?;
+    module.inst_fn(crate::STRING_DISPLAY, host_error_display)?;
     Ok(module)
 }
 
@@ -18,3 +32,86 @@ fn is_ok(result: &Result<Value, Value>) -> bool {
 fn is_err(result: &Result<Value, Value>) -> bool {
     result.is_err()
 }
+
+/// Maps the contained `Ok` value using the given function.
+fn map(result: &Result<Value, Value>, f: &Function) -> Result<Result<Value, Value>, VmError> {
+    match result {
+        Ok(value) => Ok(Ok(f.call::<_, Value>((value.clone(),))?)),
+        Err(err) => Ok(Err(err.clone())),
+    }
+}
+
+/// Calls the given function with the contained `Ok` value, which is expected
+/// to return another result.
+fn and_then(result: &Result<Value, Value>, f: &Function) -> Result<Result<Value, Value>, VmError> {
+    match result {
+        Ok(value) => Ok(f.call::<_, Result<Value, Value>>((value.clone(),))?),
+        Err(err) => Ok(Err(err.clone())),
+    }
+}
+
+/// Unwraps the contained `Ok` value, or returns the given default.
+fn unwrap_or(result: &Result<Value, Value>, default: Value) -> Value {
+    match result {
+        Ok(value) => value.clone(),
+        Err(..) => default,
+    }
+}
+
+/// Unwraps the contained `Ok` value, or computes it by calling the given
+/// function with the `Err` value.
+fn unwrap_or_else(result: &Result<Value, Value>, f: &Function) -> Result<Value, VmError> {
+    match result {
+        Ok(value) => Ok(value.clone()),
+        Err(err) => Ok(f.call::<_, Value>((err.clone(),))?),
+    }
+}
+
+/// Unwraps the contained `Ok` value, or panics.
+fn unwrap(result: &Result<Value, Value>) -> Result<Value, VmError> {
+    match result {
+        Ok(value) => Ok(value.clone()),
+        Err(err) => Err(err_panic(
+            "called `Result::unwrap()` on an `Err` value",
+            err,
+        )?),
+    }
+}
+
+/// Unwraps the contained `Ok` value, or panics with the given message.
+fn expect(result: &Result<Value, Value>, message: &str) -> Result<Value, VmError> {
+    match result {
+        Ok(value) => Ok(value.clone()),
+        Err(err) => Err(err_panic(message, err)?),
+    }
+}
+
+/// The message of an error raised by a native function.
+fn host_error_message(error: &HostError) -> String {
+    error.message().to_owned()
+}
+
+/// The name of the type of an error raised by a native function.
+fn host_error_type_name(error: &HostError) -> String {
+    error.type_name().to_owned()
+}
+
+fn host_error_display(error: &HostError, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", error)
+}
+
+/// Construct a panic for the given message and `Err` value, debug formatting
+/// the value with the context of the caller if available.
+fn err_panic(message: &str, err: &Value) -> Result<VmError, VmError> {
+    let mut buf = String::from(message);
+    buf.push_str(": ");
+
+    match Caller::current() {
+        Some(caller) => err.string_debug(caller.context(), &mut buf)?,
+        None => write!(buf, "{:?}", err).map_err(|_| VmErrorKind::FormatError)?,
+    }
+
+    Ok(VmError::from(VmErrorKind::Panic {
+        reason: Panic::custom(buf).at_caller(),
+    }))
+}
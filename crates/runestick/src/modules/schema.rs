@@ -0,0 +1,456 @@
+//! The `std::schema` module.
+//!
+//! Validates values, like decoded JSON payloads, against a schema declared as
+//! a value:
+//!
+//! ```text
+//! use std::schema::Schema;
+//!
+//! fn main() {
+//!     let schema = Schema::new(#{
+//!         type: "object",
+//!         fields: #{
+//!             name: #{type: "string", min_len: 1},
+//!             age: #{type: "int", min: 0, optional: true},
+//!             tags: #{type: "vec", items: "string"},
+//!         },
+//!     });
+//!
+//!     if let Err(errors) = schema.validate(payload) {
+//!         for error in errors {
+//!             println(`{error}`);
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! A schema is either the name of a type, or an object with a `type` key and
+//! the following optional keys:
+//! * `optional` - if the field the schema describes may be missing.
+//! * `min` and `max` - inclusive bounds of `int`, `float` and `number` values.
+//! * `min_len` and `max_len` - inclusive bounds on the length of `string`,
+//!   `bytes` and `vec` values.
+//! * `items` - the schema of the items of a `vec`.
+//! * `fields` - the schemas of the fields of an `object`.
+//! * `extra` - if an `object` may have fields which aren't declared in
+//!   `fields`, which defaults to `true`.
+//!
+//! The available types are `any`, `unit`, `bool`, `int`, `float`, `number`
+//! (an `int` or a `float`), `string`, `bytes`, `vec` and `object`.
+
+use crate::{ContextError, Module, Value, VmError};
+use std::fmt;
+
+/// Construct the `std::schema` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "schema"]);
+    module.ty(&["Schema"]).build::<Schema>()?;
+    module.ty(&["ValidationError"]).build::<ValidationError>()?;
+    module.function(&["Schema", "new"], Schema::new)?;
+    module.inst_fn("validate", Schema::validate)?;
+    module.inst_fn("path", ValidationError::path)?;
+    module.inst_fn("message", ValidationError::message)?;
+    module.inst_fn(crate::STRING_DISPLAY, ValidationError::display)?;
+    Ok(module)
+}
+
+/// A schema which values can be validated against.
+#[derive(Debug, Clone)]
+pub struct Schema {
+    root: Node,
+}
+
+impl Schema {
+    /// Construct a schema from its declaration.
+    ///
+    /// Errors if the declaration isn't a valid schema.
+    fn new(declaration: Value) -> Result<Self, VmError> {
+        Ok(Self {
+            root: Node::parse(&declaration, "$")?,
+        })
+    }
+
+    /// Validate the given value, returning all the ways in which it doesn't
+    /// match the schema.
+    fn validate(&self, value: Value) -> Result<Result<(), Vec<ValidationError>>, VmError> {
+        let mut errors = Vec::new();
+        self.root
+            .validate(&value, &mut String::from("$"), &mut errors)?;
+
+        if errors.is_empty() {
+            Ok(Ok(()))
+        } else {
+            Ok(Err(errors))
+        }
+    }
+}
+
+/// A value which doesn't match a schema.
+#[derive(Debug, Clone)]
+pub struct ValidationError {
+    /// The path to the value, like `$.user.tags[2]`.
+    path: String,
+    /// What's wrong with the value.
+    message: String,
+}
+
+impl ValidationError {
+    fn path(&self) -> String {
+        self.path.clone()
+    }
+
+    fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        use std::fmt::Write as _;
+        write!(buf, "{}", self)
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}: {}", self.path, self.message)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Kind {
+    Any,
+    Unit,
+    Bool,
+    Int,
+    Float,
+    Number,
+    String,
+    Bytes,
+    Vec(Option<Box<Node>>),
+    Object {
+        /// Declared fields, sorted by name.
+        fields: Vec<(String, Node)>,
+        extra: bool,
+    },
+}
+
+impl Kind {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Any => "any",
+            Self::Unit => "unit",
+            Self::Bool => "bool",
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::Number => "number",
+            Self::String => "string",
+            Self::Bytes => "bytes",
+            Self::Vec(..) => "vec",
+            Self::Object { .. } => "object",
+        }
+    }
+}
+
+/// An inclusive bound on a number.
+#[derive(Debug, Clone, Copy)]
+enum Bound {
+    Integer(i64),
+    Float(f64),
+}
+
+impl Bound {
+    fn as_f64(self) -> f64 {
+        match self {
+            Self::Integer(n) => n as f64,
+            Self::Float(n) => n,
+        }
+    }
+}
+
+impl fmt::Display for Bound {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(n) => write!(fmt, "{}", n),
+            Self::Float(n) => write!(fmt, "{}", n),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    kind: Kind,
+    optional: bool,
+    min: Option<Bound>,
+    max: Option<Bound>,
+    min_len: Option<usize>,
+    max_len: Option<usize>,
+}
+
+impl Node {
+    fn new(kind: Kind) -> Self {
+        Self {
+            kind,
+            optional: false,
+            min: None,
+            max: None,
+            min_len: None,
+            max_len: None,
+        }
+    }
+
+    /// Parse the declaration of a schema at the given path.
+    fn parse(declaration: &Value, path: &str) -> Result<Self, VmError> {
+        let object = match declaration {
+            Value::StaticString(..) | Value::String(..) => {
+                let name = string(declaration)?;
+                return Ok(Self::new(parse_kind(&name, path)?));
+            }
+            Value::Object(object) => object.borrow_ref()?,
+            _ => return Err(invalid(path, "expected a type name or an object")),
+        };
+
+        let name = match object.get("type") {
+            Some(name @ Value::StaticString(..)) | Some(name @ Value::String(..)) => string(name)?,
+            Some(..) => return Err(invalid(path, "`type` must be a string")),
+            None => return Err(invalid(path, "missing `type`")),
+        };
+
+        let mut node = Self::new(parse_kind(&name, path)?);
+
+        let mut entries = object.iter().collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (key, value) in entries {
+            match (key.as_str(), &mut node.kind) {
+                ("type", _) => (),
+                ("optional", _) => {
+                    node.optional = match value {
+                        Value::Bool(b) => *b,
+                        _ => return Err(invalid(path, "`optional` must be a bool")),
+                    };
+                }
+                ("min", Kind::Int) | ("min", Kind::Float) | ("min", Kind::Number) => {
+                    node.min = Some(bound(value, path, key)?);
+                }
+                ("max", Kind::Int) | ("max", Kind::Float) | ("max", Kind::Number) => {
+                    node.max = Some(bound(value, path, key)?);
+                }
+                ("min_len", Kind::String)
+                | ("min_len", Kind::Bytes)
+                | ("min_len", Kind::Vec(..)) => {
+                    node.min_len = Some(length(value, path, key)?);
+                }
+                ("max_len", Kind::String)
+                | ("max_len", Kind::Bytes)
+                | ("max_len", Kind::Vec(..)) => {
+                    node.max_len = Some(length(value, path, key)?);
+                }
+                ("items", Kind::Vec(items)) => {
+                    *items = Some(Box::new(Self::parse(value, &format!("{}[]", path))?));
+                }
+                ("fields", Kind::Object { fields, .. }) => {
+                    let declared = match value {
+                        Value::Object(declared) => declared.borrow_ref()?,
+                        _ => return Err(invalid(path, "`fields` must be an object")),
+                    };
+
+                    for (name, value) in declared.iter() {
+                        let node = Self::parse(value, &format!("{}.{}", path, name))?;
+                        fields.push((name.clone(), node));
+                    }
+
+                    fields.sort_by(|a, b| a.0.cmp(&b.0));
+                }
+                ("extra", Kind::Object { extra, .. }) => {
+                    *extra = match value {
+                        Value::Bool(b) => *b,
+                        _ => return Err(invalid(path, "`extra` must be a bool")),
+                    };
+                }
+                (key, kind) => {
+                    return Err(invalid(
+                        path,
+                        &format!("`{}` isn't supported for `{}`", key, kind.name()),
+                    ));
+                }
+            }
+        }
+
+        Ok(node)
+    }
+
+    /// Validate the given value at the given path, pushing any errors onto
+    /// `errors`.
+    fn validate(
+        &self,
+        value: &Value,
+        path: &mut String,
+        errors: &mut Vec<ValidationError>,
+    ) -> Result<(), VmError> {
+        let len = match (&self.kind, value) {
+            (Kind::Any, _) | (Kind::Unit, Value::Unit) | (Kind::Bool, Value::Bool(..)) => None,
+            (Kind::Int, Value::Integer(n)) | (Kind::Number, Value::Integer(n)) => {
+                self.check_bounds(*n as f64, path, errors);
+                None
+            }
+            (Kind::Float, Value::Float(n)) | (Kind::Number, Value::Float(n)) => {
+                self.check_bounds(*n, path, errors);
+                None
+            }
+            (Kind::String, Value::StaticString(..)) | (Kind::String, Value::String(..)) => {
+                Some(string(value)?.chars().count())
+            }
+            (Kind::Bytes, Value::Bytes(bytes)) => Some(bytes.borrow_ref()?.len()),
+            (Kind::Vec(items), Value::Vec(vec)) => {
+                let vec = vec.borrow_ref()?;
+
+                if let Some(items) = items {
+                    for (index, value) in vec.iter().enumerate() {
+                        let len = path.len();
+                        path.push_str(&format!("[{}]", index));
+                        items.validate(value, path, errors)?;
+                        path.truncate(len);
+                    }
+                }
+
+                Some(vec.len())
+            }
+            (Kind::Object { fields, extra }, Value::Object(object)) => {
+                let object = object.borrow_ref()?;
+
+                for (name, node) in fields {
+                    let len = path.len();
+                    path.push('.');
+                    path.push_str(name);
+
+                    match object.get(name) {
+                        Some(value) => node.validate(value, path, errors)?,
+                        None if node.optional => (),
+                        None => push_error(errors, path, String::from("missing field")),
+                    }
+
+                    path.truncate(len);
+                }
+
+                if !*extra {
+                    let mut unexpected = object
+                        .keys()
+                        .filter(|key| fields.iter().all(|(name, _)| name != *key))
+                        .collect::<Vec<_>>();
+
+                    unexpected.sort();
+
+                    for key in unexpected {
+                        let path = format!("{}.{}", path, key);
+                        push_error(errors, &path, String::from("unexpected field"));
+                    }
+                }
+
+                None
+            }
+            (kind, value) => {
+                let message = format!("expected {}, found {}", kind.name(), type_name(value)?);
+                push_error(errors, path, message);
+                None
+            }
+        };
+
+        if let Some(len) = len {
+            if let Some(min_len) = self.min_len.filter(|min_len| len < *min_len) {
+                let message = format!("length must be at least {}", min_len);
+                push_error(errors, path, message);
+            }
+
+            if let Some(max_len) = self.max_len.filter(|max_len| len > *max_len) {
+                let message = format!("length must be at most {}", max_len);
+                push_error(errors, path, message);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_bounds(&self, n: f64, path: &str, errors: &mut Vec<ValidationError>) {
+        if let Some(min) = self.min.filter(|min| n < min.as_f64()) {
+            push_error(errors, path, format!("must be at least {}", min));
+        }
+
+        if let Some(max) = self.max.filter(|max| n > max.as_f64()) {
+            push_error(errors, path, format!("must be at most {}", max));
+        }
+    }
+}
+
+fn parse_kind(name: &str, path: &str) -> Result<Kind, VmError> {
+    Ok(match name {
+        "any" => Kind::Any,
+        "unit" => Kind::Unit,
+        "bool" => Kind::Bool,
+        "int" => Kind::Int,
+        "float" => Kind::Float,
+        "number" => Kind::Number,
+        "string" => Kind::String,
+        "bytes" => Kind::Bytes,
+        "vec" => Kind::Vec(None),
+        "object" => Kind::Object {
+            fields: Vec::new(),
+            extra: true,
+        },
+        name => return Err(invalid(path, &format!("unknown type `{}`", name))),
+    })
+}
+
+fn bound(value: &Value, path: &str, key: &str) -> Result<Bound, VmError> {
+    match value {
+        Value::Integer(n) => Ok(Bound::Integer(*n)),
+        Value::Float(n) => Ok(Bound::Float(*n)),
+        _ => Err(invalid(path, &format!("`{}` must be a number", key))),
+    }
+}
+
+fn length(value: &Value, path: &str, key: &str) -> Result<usize, VmError> {
+    match value {
+        Value::Integer(n) if *n >= 0 => Ok(*n as usize),
+        _ => Err(invalid(
+            path,
+            &format!("`{}` must be a non-negative integer", key),
+        )),
+    }
+}
+
+fn string(value: &Value) -> Result<String, VmError> {
+    Ok(match value {
+        Value::StaticString(string) => string.as_str().to_owned(),
+        Value::String(string) => string.borrow_ref()?.clone(),
+        value => return Err(VmError::expected::<String>(value.type_info()?)),
+    })
+}
+
+/// The name of the type of the given value, as it would be declared in a
+/// schema if it can be.
+fn type_name(value: &Value) -> Result<String, VmError> {
+    Ok(match value {
+        Value::Unit => String::from("unit"),
+        Value::Bool(..) => String::from("bool"),
+        Value::Integer(..) => String::from("int"),
+        Value::Float(..) => String::from("float"),
+        Value::StaticString(..) | Value::String(..) => String::from("string"),
+        Value::Bytes(..) => String::from("bytes"),
+        Value::Vec(..) => String::from("vec"),
+        Value::Object(..) => String::from("object"),
+        value => value.type_info()?.to_string(),
+    })
+}
+
+fn push_error(errors: &mut Vec<ValidationError>, path: &str, message: String) {
+    errors.push(ValidationError {
+        path: path.to_owned(),
+        message,
+    });
+}
+
+fn invalid(path: &str, message: &str) -> VmError {
+    VmError::panic(format!("invalid schema at `{}`: {}", path, message))
+}
+
+impl_external!(Schema);
+impl_external!(ValidationError);
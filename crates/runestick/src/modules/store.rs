@@ -0,0 +1,156 @@
+//! The `std::store` module.
+//!
+//! Gives scripts access to a key-value store provided by the embedder through
+//! the [Store] trait, so that scripts can persist data without being given
+//! raw file or database access.
+//!
+//! This module isn't part of the default modules, since it requires a store.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use runestick::modules::store::{self, MemoryStore};
+//!
+//! # fn main() -> runestick::Result<()> {
+//! let mut context = runestick::Context::with_default_modules()?;
+//! context.install(&store::module(MemoryStore::new())?)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Bytes, ContextError, Module, Value, VmError};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+/// A key-value store which backs the `std::store` module.
+///
+/// Implement this to back script persistence with something like redis or
+/// sled.
+pub trait Store: 'static + Send + Sync {
+    /// Get the value stored under the given key.
+    fn get(&self, key: &str) -> crate::Result<Option<Vec<u8>>>;
+
+    /// Store a value under the given key, replacing any existing value.
+    fn set(&self, key: &str, value: Vec<u8>) -> crate::Result<()>;
+
+    /// Delete the value stored under the given key, returning `true` if there
+    /// was one.
+    fn delete(&self, key: &str) -> crate::Result<bool>;
+
+    /// List all keys which start with the given prefix, in sorted order.
+    fn list(&self, prefix: &str) -> crate::Result<Vec<String>>;
+}
+
+/// Stores can be shared, allowing the embedder to keep a handle to the store
+/// it gave to the module.
+impl<S> Store for Arc<S>
+where
+    S: ?Sized + Store,
+{
+    fn get(&self, key: &str) -> crate::Result<Option<Vec<u8>>> {
+        (**self).get(key)
+    }
+
+    fn set(&self, key: &str, value: Vec<u8>) -> crate::Result<()> {
+        (**self).set(key, value)
+    }
+
+    fn delete(&self, key: &str) -> crate::Result<bool> {
+        (**self).delete(key)
+    }
+
+    fn list(&self, prefix: &str) -> crate::Result<Vec<String>> {
+        (**self).list(prefix)
+    }
+}
+
+/// A [Store] which keeps all values in memory.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    values: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+impl MemoryStore {
+    /// Construct a new empty in-memory store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn values(&self) -> crate::Result<std::sync::MutexGuard<'_, BTreeMap<String, Vec<u8>>>> {
+        self.values
+            .lock()
+            .map_err(|_| anyhow::anyhow!("store is poisoned"))
+    }
+}
+
+impl Store for MemoryStore {
+    fn get(&self, key: &str) -> crate::Result<Option<Vec<u8>>> {
+        Ok(self.values()?.get(key).cloned())
+    }
+
+    fn set(&self, key: &str, value: Vec<u8>) -> crate::Result<()> {
+        self.values()?.insert(key.to_owned(), value);
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> crate::Result<bool> {
+        Ok(self.values()?.remove(key).is_some())
+    }
+
+    fn list(&self, prefix: &str) -> crate::Result<Vec<String>> {
+        Ok(self
+            .values()?
+            .range(prefix.to_owned()..)
+            .map(|(key, _)| key)
+            .take_while(|key| key.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+}
+
+/// Construct the `std::store` module, backed by the given store.
+pub fn module<S>(store: S) -> Result<Module, ContextError>
+where
+    S: Store,
+{
+    let store: Arc<dyn Store> = Arc::new(store);
+    let mut module = Module::new(&["std", "store"]);
+
+    let s = store.clone();
+    module.function(&["get"], move |key: &str| -> crate::Result<Option<Bytes>> {
+        Ok(s.get(key)?.map(Bytes::from_vec))
+    })?;
+
+    let s = store.clone();
+    module.function(
+        &["get_string"],
+        move |key: &str| -> crate::Result<Option<String>> {
+            match s.get(key)? {
+                Some(value) => Ok(Some(String::from_utf8(value)?)),
+                None => Ok(None),
+            }
+        },
+    )?;
+
+    let s = store.clone();
+    module.function(
+        &["set"],
+        move |key: &str, value: Value| -> Result<crate::Result<()>, VmError> {
+            let value = match value {
+                Value::StaticString(string) => string.as_bytes().to_vec(),
+                Value::String(string) => string.borrow_ref()?.as_bytes().to_vec(),
+                Value::Bytes(bytes) => bytes.borrow_ref()?.to_vec(),
+                actual => return Err(VmError::expected::<String>(actual.type_info()?)),
+            };
+
+            Ok(s.set(key, value))
+        },
+    )?;
+
+    let s = store.clone();
+    module.function(&["delete"], move |key: &str| s.delete(key))?;
+
+    let s = store;
+    module.function(&["list"], move |prefix: &str| s.list(prefix))?;
+    Ok(module)
+}
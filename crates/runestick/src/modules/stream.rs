@@ -9,5 +9,7 @@ pub fn module() -> Result<Module, ContextError> {
 
     module.async_inst_fn("next", Stream::next)?;
     module.async_inst_fn("resume", Stream::resume)?;
+    module.inst_fn("is_complete", Stream::is_complete)?;
+    module.inst_fn("completion", Stream::completion)?;
     Ok(module)
 }
@@ -1,18 +1,25 @@
 //! The `std::string` module.
 
 use crate::{Bytes, ContextError, Module};
+use std::string::FromUtf8Error;
 
 /// Construct the `std::string` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::new(&["std", "string"]);
 
     module.ty(&["String"]).build::<String>()?;
+    module
+        .ty(&["String", "FromUtf8Error"])
+        .build::<FromUtf8Error>()?;
 
     module.function(&["String", "from_str"], <String as From<&str>>::from)?;
     module.function(&["String", "new"], String::new)?;
     module.function(&["String", "with_capacity"], String::with_capacity)?;
+    module.function(&["String", "from_utf8"], from_utf8)?;
 
     module.inst_fn("len", String::len)?;
+    module.inst_fn("len_bytes", String::len)?;
+    module.inst_fn("len_chars", len_chars)?;
     module.inst_fn("capacity", String::capacity)?;
     module.inst_fn("clear", String::clear)?;
     module.inst_fn("push", String::push)?;
@@ -23,6 +30,12 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("clone", String::clone)?;
     module.inst_fn("shrink_to_fit", String::shrink_to_fit)?;
     module.inst_fn("char_at", char_at)?;
+    module.inst_fn("chars", chars)?;
+    #[cfg(feature = "unicode-segmentation")]
+    module.inst_fn("graphemes", graphemes)?;
+    module.inst_fn("to_lowercase", str::to_lowercase)?;
+    module.inst_fn("to_uppercase", str::to_uppercase)?;
+    module.inst_fn("eq_ignore_case", eq_ignore_case)?;
     module.inst_fn(crate::ADD, add)?;
     module.inst_fn(crate::ADD_ASSIGN, String::push_str)?;
     Ok(module)
@@ -36,6 +49,11 @@ fn into_bytes(s: String) -> Bytes {
     Bytes::from_vec(s.into_bytes())
 }
 
+/// Convert bytes into a string, failing if they are not valid UTF-8.
+fn from_utf8(bytes: Bytes) -> Result<String, FromUtf8Error> {
+    String::from_utf8(bytes.into_vec())
+}
+
 fn char_at(s: &str, index: usize) -> Result<Option<char>, NotCharBoundary> {
     if !s.is_char_boundary(index) {
         return Err(NotCharBoundary(()));
@@ -44,6 +62,42 @@ fn char_at(s: &str, index: usize) -> Result<Option<char>, NotCharBoundary> {
     Ok(s[index..].chars().next())
 }
 
+/// The number of unicode scalar values in the string.
+fn len_chars(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// Collect the unicode scalar values in the string.
+fn chars(s: &str) -> Vec<char> {
+    s.chars().collect()
+}
+
+/// Collect the extended grapheme clusters in the string.
+#[cfg(feature = "unicode-segmentation")]
+fn graphemes(s: &str) -> Vec<String> {
+    use unicode_segmentation::UnicodeSegmentation as _;
+    s.graphemes(true).map(String::from).collect()
+}
+
+/// Compare two strings for equality, ignoring case.
+///
+/// This compares the full unicode lowercase mapping of both strings, so that
+/// for example `"ΣΑΣ"` and `"σας"` compare equal.
+fn eq_ignore_case(a: &str, b: &str) -> bool {
+    fold_case(a).eq(fold_case(b))
+}
+
+/// Case fold a string by mapping every character to its lowercase form.
+///
+/// The final form of sigma is folded into its regular form, since lowercase
+/// mapping alone is context-sensitive for it.
+fn fold_case(s: &str) -> impl Iterator<Item = char> + '_ {
+    s.chars().flat_map(char::to_lowercase).map(|c| match c {
+        'ς' => 'σ',
+        c => c,
+    })
+}
+
 /// The add operation for strings.
 fn add(a: &str, b: &str) -> String {
     let mut string = String::with_capacity(a.len() + b.len());
@@ -53,3 +107,4 @@ fn add(a: &str, b: &str) -> String {
 }
 
 impl_external!(NotCharBoundary);
+impl_external!(FromUtf8Error);
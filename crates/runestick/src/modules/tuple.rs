@@ -0,0 +1,24 @@
+//! The `std::tuple` module.
+
+use crate::{ContextError, Module, Tuple, Value};
+
+/// Construct the `std::tuple` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "tuple"]);
+    module.ty(&["Tuple"]).build::<Tuple>()?;
+    module.inst_fn("len", len)?;
+    module.inst_fn("get", get)?;
+    Ok(module)
+}
+
+fn len(tuple: &Tuple) -> usize {
+    tuple.len()
+}
+
+/// Get the element at the given index, or `None` if it's out of bounds.
+///
+/// Negative indexes are relative to the end, like when indexing with `tuple[-1]`.
+fn get(tuple: &Tuple, index: i64) -> Option<Value> {
+    let index = crate::vm::resolve_index(tuple.len(), index)?;
+    tuple.get(index).cloned()
+}
@@ -1,6 +1,7 @@
 //! The `std::vec` module.
 
-use crate::{ContextError, Module, Value};
+use crate::{ContextError, Function, Module, Shared, TypeInfo, Value, VmError, VmErrorKind};
+use std::cmp::Ordering;
 use std::iter::Rev;
 
 /// Construct the `std::vec` module.
@@ -17,6 +18,10 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("push", Vec::<Value>::push)?;
     module.inst_fn("clear", Vec::<Value>::clear)?;
     module.inst_fn("pop", Vec::<Value>::pop)?;
+    module.inst_fn("get", get)?;
+    module.inst_fn("sort_by", sort_by)?;
+    module.inst_fn("map", map)?;
+    module.inst_fn("filter", filter)?;
 
     module.inst_fn(crate::INTO_ITER, vec_iter)?;
     module.inst_fn("next", Iter::next)?;
@@ -56,5 +61,95 @@ fn vec_iter(vec: &[Value]) -> Iter {
     }
 }
 
+/// Get the element at the given index, or `None` if it's out of bounds.
+///
+/// Negative indexes are relative to the end, like when indexing with `vec[-1]`.
+fn get(vec: &[Value], index: i64) -> Option<Value> {
+    let index = crate::vm::resolve_index(vec.len(), index)?;
+    vec.get(index).cloned()
+}
+
+/// Sort the vector using the given comparator function.
+///
+/// The comparator is called with two elements and is expected to return an
+/// integer which is negative, zero, or positive if the first element is less
+/// than, equal to, or greater than the second one.
+///
+/// The comparator can access the vector while it's being sorted, but it's an
+/// error to modify it.
+fn sort_by(vec: Shared<Vec<Value>>, comparator: &Function) -> Result<(), VmError> {
+    let snapshot = vec.borrow_ref()?.clone();
+    let mut sorted = snapshot.clone();
+    let mut error = None;
+
+    sorted.sort_by(|a, b| {
+        if error.is_some() {
+            return Ordering::Equal;
+        }
+
+        match comparator.call::<_, i64>((a.clone(), b.clone())) {
+            Ok(ordering) => ordering.cmp(&0),
+            Err(e) => {
+                error = Some(e);
+                Ordering::Equal
+            }
+        }
+    });
+
+    if let Some(error) = error {
+        return Err(error);
+    }
+
+    let mut vec = vec.borrow_mut()?;
+
+    if !is_unmodified(&vec, &snapshot) {
+        return Err(VmError::from(VmErrorKind::ModifiedDuringIteration {
+            target: TypeInfo::StaticType(crate::VEC_TYPE),
+        }));
+    }
+
+    *vec = sorted;
+    Ok(())
+}
+
+/// Construct a new vector by calling the given function on every element.
+///
+/// This iterates over a snapshot of the vector, so modifying the vector in the
+/// function doesn't affect which elements it's called on.
+fn map(vec: Shared<Vec<Value>>, f: &Function) -> Result<Vec<Value>, VmError> {
+    let snapshot = vec.borrow_ref()?.clone();
+
+    snapshot
+        .into_iter()
+        .map(|value| f.call::<_, Value>((value,)))
+        .collect()
+}
+
+/// Construct a new vector out of the elements for which the given predicate
+/// returns `true`.
+///
+/// Like [map], this iterates over a snapshot of the vector.
+fn filter(vec: Shared<Vec<Value>>, predicate: &Function) -> Result<Vec<Value>, VmError> {
+    let snapshot = vec.borrow_ref()?.clone();
+    let mut output = Vec::new();
+
+    for value in snapshot {
+        if predicate.call::<_, bool>((value.clone(),))? {
+            output.push(value);
+        }
+    }
+
+    Ok(output)
+}
+
+/// Test if the vector still holds the same values as the given snapshot.
+fn is_unmodified(vec: &[Value], snapshot: &[Value]) -> bool {
+    vec.len() == snapshot.len()
+        && vec
+            .iter()
+            .zip(snapshot.iter())
+            .all(|(a, b)| Value::is_same(a, b))
+}
+
 impl_external!(Iter);
 impl_external!(Rev<Iter>);
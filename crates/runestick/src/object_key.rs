@@ -0,0 +1,75 @@
+//! Fast lookups of static keys in objects.
+//!
+//! Objects constructed by the virtual machine share a single hasher, so the
+//! hash of every static string in a unit can be computed once when the unit
+//! is loaded. Looking up a static key in such an object then doesn't need to
+//! hash the key.
+//!
+//! Objects constructed elsewhere use a hasher of their own, so lookups in them
+//! fall back to hashing the key.
+
+use crate::{Object, StaticString, Value};
+use ahash::RandomState;
+use hashbrown::hash_map::RawEntryMut;
+use std::hash::{BuildHasher as _, Hash as _, Hasher as _};
+
+/// Get the hasher shared by objects constructed by the virtual machine.
+fn hasher() -> RandomState {
+    // NB: the address of a static is randomized by ASLR, which makes the keys
+    // differ between processes while staying the same within one.
+    static ANCHOR: u8 = 0;
+    let seed = &ANCHOR as *const u8 as u64;
+    RandomState::with_seeds(seed, seed.rotate_left(32))
+}
+
+/// Compute the hash of the given key, as used by objects constructed with
+/// [with_capacity].
+pub(crate) fn hash(key: &str) -> u64 {
+    let mut hasher = hasher().build_hasher();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Construct an object using the shared hasher.
+pub(crate) fn with_capacity(capacity: usize) -> Object<Value> {
+    Object::with_capacity_and_hasher(capacity, hasher())
+}
+
+/// Get the value of the given static key in an object.
+pub(crate) fn get<'a>(object: &'a Object<Value>, key: &StaticString) -> Option<&'a Value> {
+    // NB: a hit is always correct since the key is compared, even if the
+    // object uses a different hasher.
+    match object
+        .raw_entry()
+        .from_key_hashed_nocheck(key.key_hash(), key.as_str())
+    {
+        Some((_, value)) => Some(value),
+        None => object.get(key.as_str()),
+    }
+}
+
+/// Get the value of the given static key in an object mutably.
+pub(crate) fn get_mut<'a>(
+    object: &'a mut Object<Value>,
+    key: &StaticString,
+) -> Option<&'a mut Value> {
+    let hash = match object
+        .raw_entry()
+        .from_key_hashed_nocheck(key.key_hash(), key.as_str())
+    {
+        Some(..) => key.key_hash(),
+        None => {
+            let mut hasher = object.hasher().build_hasher();
+            key.as_str().hash(&mut hasher);
+            hasher.finish()
+        }
+    };
+
+    match object
+        .raw_entry_mut()
+        .from_key_hashed_nocheck(hash, key.as_str())
+    {
+        RawEntryMut::Occupied(entry) => Some(entry.into_mut()),
+        RawEntryMut::Vacant(..) => None,
+    }
+}
@@ -0,0 +1,107 @@
+//! Native functions replaced for the duration of a single execution.
+
+use crate::collections::HashMap;
+use crate::context::Handler;
+use crate::module::Function;
+use crate::{Hash, IntoHash};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+thread_local! {
+    static OVERRIDES: RefCell<Option<Overrides>> = RefCell::new(None);
+}
+
+/// A set of mock handlers replacing native functions for a single execution,
+/// installed through
+/// [VmExecution::with_overrides][crate::VmExecution::with_overrides].
+///
+/// Overrides are looked up by the hash of the function they replace, and only
+/// apply to functions which exist in the context. This is intended for tests
+/// of scripts, which can stub out native functions performing network or
+/// filesystem access without building a separate context.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Hash, Overrides};
+///
+/// let mut overrides = Overrides::new();
+/// overrides.insert(&["http", "get"], |url: &str| format!("mocked {}", url));
+/// overrides.insert(Hash::type_hash(&["fs", "exists"]), |_: &str| true);
+/// ```
+#[derive(Default, Clone)]
+pub struct Overrides {
+    handlers: Rc<HashMap<Hash, Arc<Handler>>>,
+}
+
+impl Overrides {
+    /// Construct a new empty set of overrides.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the native function with the given hash, or path, with the
+    /// given handler.
+    ///
+    /// Inserting an override for a function which is already overridden
+    /// replaces the previous override.
+    pub fn insert<H, Func, Args>(&mut self, hash: H, f: Func)
+    where
+        H: IntoHash,
+        Func: Function<Args>,
+    {
+        let handler: Arc<Handler> = Arc::new(move |stack, args| f.fn_call(stack, args));
+        Rc::make_mut(&mut self.handlers).insert(hash.into_hash(), handler);
+    }
+
+    /// Test if there are no overrides.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// The number of overridden functions.
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+}
+
+/// Run the given closure with the given overrides installed, so that native
+/// calls performed while it runs use them.
+pub(crate) fn with_overrides<F, T>(overrides: Option<&Overrides>, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let overrides = match overrides {
+        Some(overrides) => overrides,
+        None => return f(),
+    };
+
+    let _guard = OverridesGuard {
+        previous: OVERRIDES.with(|current| current.replace(Some(overrides.clone()))),
+    };
+
+    f()
+}
+
+/// Look up the override of the native function with the given hash in the
+/// installed overrides, if any.
+pub(crate) fn lookup(hash: Hash) -> Option<Arc<Handler>> {
+    OVERRIDES.with(|current| {
+        let current = current.borrow();
+        current.as_ref()?.handlers.get(&hash).cloned()
+    })
+}
+
+/// Guard which restores the previous overrides when dropped, even if the
+/// closure panics.
+struct OverridesGuard {
+    previous: Option<Overrides>,
+}
+
+impl Drop for OverridesGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        OVERRIDES.with(|current| *current.borrow_mut() = previous);
+    }
+}
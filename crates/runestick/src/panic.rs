@@ -1,4 +1,4 @@
-use crate::PanicReason;
+use crate::{Caller, FromValue, PanicReason, Span, Transfer, Value, VmError};
 use std::fmt;
 
 pub trait BoxedPanic: 'static + fmt::Display + fmt::Debug + Send + Sync {}
@@ -11,6 +11,11 @@ impl<T> BoxedPanic for T where T: 'static + fmt::Display + fmt::Debug + Send + S
 #[derive(Debug)]
 pub struct Panic {
     inner: Box<dyn BoxedPanic>,
+    /// The source id and span of where the panic was raised, if known.
+    location: Option<(usize, Span)>,
+    /// The value the panic was raised with, if any, or the error raised when
+    /// detaching it.
+    payload: Option<Result<Transfer, VmError>>,
 }
 
 impl Panic {
@@ -21,8 +26,117 @@ impl Panic {
     {
         Self {
             inner: Box::new(message),
+            location: None,
+            payload: None,
         }
     }
+
+    /// A panic raised with an arbitrary value, like `panic(#{code: 404})`.
+    ///
+    /// The value is detached from the virtual machine that raised it, so that
+    /// it can be accessed by the host through [Panic::payload]. Values which
+    /// can't be detached, like functions or external types, are only kept as
+    /// the message of the panic, and the reason they couldn't be detached is
+    /// available through [Panic::payload_error].
+    pub fn value(value: Value) -> Self {
+        let message = match &value {
+            Value::String(string) => match string.borrow_ref() {
+                Ok(string) => string.clone(),
+                Err(..) => format!("{:?}", value),
+            },
+            Value::StaticString(string) => string.as_str().to_owned(),
+            value => format!("{:?}", value),
+        };
+
+        Self {
+            inner: Box::new(message),
+            location: None,
+            payload: Some(value.deep_copy_for_transfer()),
+        }
+    }
+
+    /// Access the value the panic was raised with.
+    ///
+    /// Returns `None` if the panic wasn't raised with a value, or if the value
+    /// couldn't be detached from the virtual machine that raised it.
+    pub fn payload(&self) -> Option<&Transfer> {
+        self.payload.as_ref()?.as_ref().ok()
+    }
+
+    /// The error raised when detaching the value the panic was raised with,
+    /// if it couldn't be detached.
+    pub fn payload_error(&self) -> Option<&VmError> {
+        self.payload.as_ref()?.as_ref().err()
+    }
+
+    /// Convert into the value the panic was raised with.
+    ///
+    /// Returns `None` if the panic wasn't raised with a value, and errors if
+    /// the value couldn't be detached from the virtual machine that raised it.
+    pub fn into_payload(self) -> Result<Option<Value>, VmError> {
+        match self.payload {
+            Some(payload) => Ok(Some(payload?.into_value())),
+            None => Ok(None),
+        }
+    }
+
+    /// Copy the value the panic was raised with and convert it into the given
+    /// type.
+    ///
+    /// Returns `None` if the panic doesn't have a payload, see
+    /// [Panic::payload].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Panic, Value};
+    ///
+    /// let panic = Panic::value(Value::from(42i64));
+    /// assert_eq!(panic.to_string(), "42");
+    /// assert_eq!(panic.downcast_payload::<i64>().unwrap(), Some(42));
+    /// assert_eq!(panic.downcast_payload::<i64>().unwrap(), Some(42));
+    /// ```
+    pub fn downcast_payload<T>(&self) -> Result<Option<T>, VmError>
+    where
+        T: FromValue,
+    {
+        match self.payload() {
+            Some(payload) => Ok(Some(T::from_value(payload.to_value()?)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Associate the panic with the given source location.
+    pub fn with_span(self, source_id: usize, span: Span) -> Self {
+        Self {
+            location: Some((source_id, span)),
+            ..self
+        }
+    }
+
+    /// Associate the panic with the call site of the current [Caller], if
+    /// it's available and has debug information.
+    pub fn at_caller(self) -> Self {
+        let caller = match Caller::current() {
+            Some(caller) => caller,
+            None => return self,
+        };
+
+        match caller.debug_inst() {
+            Some(inst) => self.with_span(inst.source_id, inst.span),
+            None => self,
+        }
+    }
+
+    /// The source id where the panic was raised, if known.
+    pub fn source_id(&self) -> Option<usize> {
+        Some(self.location?.0)
+    }
+
+    /// The span where the panic was raised, if known.
+    pub fn span(&self) -> Option<Span> {
+        Some(self.location?.1)
+    }
 }
 
 impl fmt::Display for Panic {
@@ -35,6 +149,8 @@ impl From<PanicReason> for Panic {
     fn from(value: PanicReason) -> Self {
         Self {
             inner: Box::new(value),
+            location: None,
+            payload: None,
         }
     }
 }
@@ -10,6 +10,37 @@ pub struct Protocol {
     pub hash: Hash,
 }
 
+impl Protocol {
+    /// Construct a custom protocol with the given name.
+    ///
+    /// Custom protocols are registered with [Module::protocol], after which
+    /// calls to an instance function with the same name in scripts resolve to
+    /// the protocol. Native types implement the protocol by registering an
+    /// instance function using it, like they would for a built-in protocol.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Module, Protocol};
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let matches = Protocol::custom("matches");
+    ///
+    /// let mut module = Module::new(&["dsl"]);
+    /// module.protocol(matches)?;
+    /// module.inst_fn(matches, |s: &str, pattern: &str| s.contains(pattern))?;
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [Module::protocol]: crate::Module::protocol
+    pub fn custom(name: &'static str) -> Self {
+        Self {
+            name,
+            hash: Hash::protocol(name),
+        }
+    }
+}
+
 impl IntoInstFnHash for Protocol {
     fn into_inst_fn_hash(self) -> Hash {
         self.hash
@@ -40,6 +71,39 @@ impl fmt::Display for Protocol {
     }
 }
 
+/// All built-in protocols.
+pub(crate) const BUILTIN_PROTOCOLS: &[Protocol] = &[
+    INDEX_GET,
+    INDEX_SET,
+    ADD,
+    ADD_ASSIGN,
+    SUB,
+    SUB_ASSIGN,
+    MUL,
+    MUL_ASSIGN,
+    DIV,
+    DIV_ASSIGN,
+    REM,
+    REM_ASSIGN,
+    BIT_AND,
+    BIT_AND_ASSIGN,
+    BIT_XOR,
+    BIT_XOR_ASSIGN,
+    BIT_OR,
+    BIT_OR_ASSIGN,
+    SHL,
+    SHL_ASSIGN,
+    SHR,
+    SHR_ASSIGN,
+    STRING_DISPLAY,
+    STRING_DEBUG,
+    EQ,
+    HASH,
+    INTO_ITER,
+    NEXT,
+    INTO_FUTURE,
+];
+
 /// The function to access an index.
 pub const INDEX_GET: Protocol = Protocol {
     name: "index_get",
@@ -172,12 +236,39 @@ pub const SHR_ASSIGN: Protocol = Protocol {
     hash: Hash::new(0x61ff7c46ff00e74a),
 };
 
+/// The function to implement for the negation operation.
+pub const NEG: Protocol = Protocol {
+    name: "neg",
+    hash: Hash::new(0xc7bf562f58de1116),
+};
+
 /// Protocol function used by template strings.
 pub const STRING_DISPLAY: Protocol = Protocol {
     name: "string_display",
     hash: Hash::new(0x811b62957ea9d9f9),
 };
 
+/// Protocol function used when formatting a value for debugging.
+pub const STRING_DEBUG: Protocol = Protocol {
+    name: "string_debug",
+    hash: Hash::new(0x319574a4373e39c2),
+};
+
+/// Protocol function used to compare external values for equality with `==`
+/// and `!=`.
+pub const EQ: Protocol = Protocol {
+    name: "eq",
+    hash: Hash::new(0xbf4be1143ab24af9),
+};
+
+/// Protocol function used to hash external values which are used as keys, like
+/// the arguments of a memoized function. It returns an integer, which must be
+/// the same for values which are equal.
+pub const HASH: Protocol = Protocol {
+    name: "hash",
+    hash: Hash::new(0x6c1d9a8e2f47b035),
+};
+
 /// Function used to convert an argument into an iterator.
 pub const INTO_ITER: Protocol = Protocol {
     name: "into_iter",
@@ -0,0 +1,316 @@
+//! Recording of native function calls, and replaying them without calling
+//! out to the host.
+
+use crate::{Hash, Stack, Value, VmError, VmErrorKind};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+thread_local! {
+    static SESSION: RefCell<Option<Session>> = RefCell::new(None);
+}
+
+/// A native function call captured in a [Recording].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedCall {
+    /// The hash of the called function.
+    pub hash: Hash,
+    /// The arguments the function was called with.
+    pub args: Vec<Value>,
+    /// The value returned by the function.
+    pub result: Value,
+}
+
+/// The native function calls performed by an execution, captured through
+/// [VmExecution::with_recording][crate::VmExecution::with_recording].
+///
+/// A recording can be serialized, and replayed through
+/// [VmExecution::with_replay][crate::VmExecution::with_replay] to reproduce
+/// the behavior of a script without calling out to the host, like in a bug
+/// report or a test.
+///
+/// Only values which can be serialized can be stored in a recording which is
+/// serialized, and serialization is lossy in the same way as for any other
+/// value: tuples are deserialized as vectors, for example. Async native
+/// functions are recorded as returning the future they produce, which can't
+/// be serialized.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Recording {
+    calls: Vec<RecordedCall>,
+}
+
+impl Recording {
+    /// Construct a recording from the given calls, in the order they were
+    /// performed.
+    pub fn new(calls: Vec<RecordedCall>) -> Self {
+        Self { calls }
+    }
+
+    /// The recorded calls, in the order they were performed.
+    pub fn calls(&self) -> &[RecordedCall] {
+        &self.calls
+    }
+
+    /// Convert into the recorded calls.
+    pub fn into_calls(self) -> Vec<RecordedCall> {
+        self.calls
+    }
+}
+
+/// A recording or replay session of an execution.
+#[derive(Debug, Clone)]
+pub(crate) enum Session {
+    /// Native calls are recorded.
+    Record(Rc<RefCell<Recording>>),
+    /// Native calls are served from a recording.
+    Replay(Rc<RefCell<VecDeque<RecordedCall>>>),
+}
+
+impl Session {
+    /// Start recording native calls.
+    pub(crate) fn record() -> Self {
+        Self::Record(Rc::new(RefCell::new(Recording::default())))
+    }
+
+    /// Replay the given recording.
+    pub(crate) fn replay(recording: Recording) -> Self {
+        Self::Replay(Rc::new(RefCell::new(recording.calls.into())))
+    }
+
+    /// Get the calls recorded so far, if this is a recording session.
+    pub(crate) fn recording(&self) -> Option<Recording> {
+        match self {
+            Self::Record(recording) => Some(recording.borrow().clone()),
+            Self::Replay(..) => None,
+        }
+    }
+}
+
+/// Run the given closure with the given session installed, so that native
+/// calls performed while it runs are recorded or replayed.
+pub(crate) fn with_session<F, T>(session: Option<&Session>, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let session = match session {
+        Some(session) => session,
+        None => return f(),
+    };
+
+    let _guard = SessionGuard {
+        previous: SESSION.with(|current| current.replace(Some(session.clone()))),
+    };
+
+    f()
+}
+
+/// Perform a call of the native function with the given hash and the given
+/// number of arguments on the stack through `call`, unless it's replayed.
+pub(crate) fn call_native<F>(
+    stack: &mut Stack,
+    hash: Hash,
+    args: usize,
+    call: F,
+) -> Result<(), VmError>
+where
+    F: FnOnce(&mut Stack) -> Result<(), VmError>,
+{
+    let session = match SESSION.with(|current| current.borrow().clone()) {
+        Some(session) => session,
+        None => return call(stack),
+    };
+
+    match session {
+        Session::Record(recording) => {
+            // NB: arguments are copied before the call, since the function
+            // might take them by value and leave the originals moved.
+            let recorded_args = stack
+                .get(stack.len().saturating_sub(args)..)
+                .unwrap_or_default()
+                .iter()
+                .map(snapshot)
+                .collect();
+
+            call(stack)?;
+
+            recording.borrow_mut().calls.push(RecordedCall {
+                hash,
+                args: recorded_args,
+                result: snapshot(stack.last()?),
+            });
+        }
+        Session::Replay(calls) => {
+            let recorded = match calls.borrow_mut().pop_front() {
+                Some(recorded) => recorded,
+                None => return Err(VmError::from(VmErrorKind::ReplayExhausted { hash })),
+            };
+
+            if recorded.hash != hash {
+                return Err(VmError::from(VmErrorKind::ReplayMismatch {
+                    expected: recorded.hash,
+                    actual: hash,
+                }));
+            }
+
+            let actual = stack
+                .get(stack.len().saturating_sub(args)..)
+                .unwrap_or_default();
+
+            if !args_match(&recorded.args, actual)? {
+                return Err(VmError::from(VmErrorKind::ReplayArgumentsMismatch { hash }));
+            }
+
+            stack.popn(args)?;
+            stack.push(recorded.result);
+        }
+    }
+
+    Ok(())
+}
+
+/// Copy a value so that it's unaffected by what happens to the original after
+/// it's been recorded.
+///
+/// Values which can't be copied, like functions and futures, are recorded as
+/// references to the originals.
+fn snapshot(value: &Value) -> Value {
+    match value.deep_copy_for_transfer() {
+        Ok(transfer) => transfer.into_value(),
+        Err(..) => value.clone(),
+    }
+}
+
+/// Test if the arguments of a replayed call match the recorded ones.
+fn args_match(recorded: &[Value], actual: &[Value]) -> Result<bool, VmError> {
+    if recorded.len() != actual.len() {
+        return Ok(false);
+    }
+
+    for (recorded, actual) in recorded.iter().zip(actual) {
+        if !value_matches(recorded, actual)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Test if an actual value matches a recorded one.
+///
+/// Since a recording might have been serialized, values are compared the same
+/// way they are serialized: tuples match vectors, characters match strings, and
+/// options match their contents, for example. Values which can't be serialized
+/// always match.
+fn value_matches(recorded: &Value, actual: &Value) -> Result<bool, VmError> {
+    let recorded = unwrap_option(recorded)?;
+    let actual = unwrap_option(actual)?;
+
+    if !is_serializable(&recorded) || !is_serializable(&actual) {
+        return Ok(true);
+    }
+
+    if let (Some(a), Some(b)) = (integer(&recorded), integer(&actual)) {
+        return Ok(a == b);
+    }
+
+    if let (Some(a), Some(b)) = (string(&recorded)?, string(&actual)?) {
+        return Ok(a == b);
+    }
+
+    if let (Some(a), Some(b)) = (sequence(&recorded)?, sequence(&actual)?) {
+        return args_match(&a, &b);
+    }
+
+    Ok(match (&recorded, &actual) {
+        (Value::Unit, Value::Unit) => true,
+        (Value::Bool(a), Value::Bool(b)) => a == b,
+        (Value::Float(a), Value::Float(b)) => a.to_bits() == b.to_bits(),
+        (Value::Bytes(a), Value::Bytes(b)) => *a.borrow_ref()? == *b.borrow_ref()?,
+        (Value::Object(a), Value::Object(b)) => {
+            let a = a.borrow_ref()?;
+            let b = b.borrow_ref()?;
+
+            if a.len() != b.len() {
+                return Ok(false);
+            }
+
+            for (key, a) in a.iter() {
+                match b.get(key) {
+                    Some(b) if value_matches(a, b)? => (),
+                    _ => return Ok(false),
+                }
+            }
+
+            true
+        }
+        _ => false,
+    })
+}
+
+/// Options are serialized as their contents, or unit if they're empty.
+fn unwrap_option(value: &Value) -> Result<Value, VmError> {
+    Ok(match value {
+        Value::Option(option) => match &*option.borrow_ref()? {
+            Some(value) => unwrap_option(value)?,
+            None => Value::Unit,
+        },
+        value => value.clone(),
+    })
+}
+
+fn is_serializable(value: &Value) -> bool {
+    matches!(
+        value,
+        Value::Unit
+            | Value::Bool(..)
+            | Value::Byte(..)
+            | Value::Char(..)
+            | Value::Integer(..)
+            | Value::Float(..)
+            | Value::StaticString(..)
+            | Value::String(..)
+            | Value::Bytes(..)
+            | Value::Vec(..)
+            | Value::Tuple(..)
+            | Value::Object(..)
+    )
+}
+
+fn integer(value: &Value) -> Option<i64> {
+    match value {
+        Value::Byte(b) => Some(*b as i64),
+        Value::Integer(n) => Some(*n),
+        _ => None,
+    }
+}
+
+fn string(value: &Value) -> Result<Option<String>, VmError> {
+    Ok(match value {
+        Value::Char(c) => Some(c.to_string()),
+        Value::StaticString(string) => Some(string.as_str().to_owned()),
+        Value::String(string) => Some(string.borrow_ref()?.clone()),
+        _ => None,
+    })
+}
+
+fn sequence(value: &Value) -> Result<Option<Vec<Value>>, VmError> {
+    Ok(match value {
+        Value::Vec(vec) => Some(vec.borrow_ref()?.clone()),
+        Value::Tuple(tuple) => Some(tuple.borrow_ref()?.to_vec()),
+        _ => None,
+    })
+}
+
+/// Guard which restores the previous session when dropped, even if the
+/// closure panics.
+struct SessionGuard {
+    previous: Option<Session>,
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        SESSION.with(|current| *current.borrow_mut() = previous);
+    }
+}
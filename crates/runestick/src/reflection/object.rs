@@ -20,6 +20,12 @@ where
     }
 }
 
+impl FromValue for Shared<Object<Value>> {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        value.into_object()
+    }
+}
+
 impl UnsafeFromValue for &Object<Value> {
     type Output = *const Object<Value>;
     type Guard = RawOwnedRef;
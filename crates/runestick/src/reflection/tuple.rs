@@ -1,5 +1,9 @@
 //! Trait implementation for decoding tuples.
 
+use crate::{
+    FromValue, OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef, Tuple, UnsafeFromValue, Value, VmError,
+};
+
 macro_rules! impl_from_value_tuple {
     () => {};
 
@@ -55,3 +59,37 @@ macro_rules! impl_from_value_tuple {
 }
 
 repeat_macro!(impl_from_value_tuple);
+
+impl FromValue for Tuple {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Ok(value.into_tuple()?.take()?)
+    }
+}
+
+impl<'a> UnsafeFromValue for &'a Tuple {
+    type Output = *const Tuple;
+    type Guard = RawOwnedRef;
+
+    unsafe fn unsafe_from_value(value: Value) -> Result<(Self::Output, Self::Guard), VmError> {
+        let tuple = value.into_tuple()?;
+        Ok(OwnedRef::into_raw(tuple.owned_ref()?))
+    }
+
+    unsafe fn to_arg(output: Self::Output) -> Self {
+        &*output
+    }
+}
+
+impl<'a> UnsafeFromValue for &'a mut Tuple {
+    type Output = *mut Tuple;
+    type Guard = RawOwnedMut;
+
+    unsafe fn unsafe_from_value(value: Value) -> Result<(Self::Output, Self::Guard), VmError> {
+        let tuple = value.into_tuple()?;
+        Ok(OwnedMut::into_raw(tuple.owned_mut()?))
+    }
+
+    unsafe fn to_arg(output: Self::Output) -> Self {
+        &mut *output
+    }
+}
@@ -21,6 +21,12 @@ where
     }
 }
 
+impl FromValue for Shared<Vec<Value>> {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        value.into_vec()
+    }
+}
+
 impl<'a> UnsafeFromValue for &'a [Value] {
     type Output = *const [Value];
     type Guard = RawOwnedRef;
@@ -221,6 +221,30 @@ impl<'de> de::Visitor<'de> for VmVisitor {
         Ok(Value::Bool(v))
     }
 
+    #[inline]
+    fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::Float(v as f64))
+    }
+
+    #[inline]
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::Float(v))
+    }
+
+    #[inline]
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(VmVisitor)
+    }
+
     #[inline]
     fn visit_none<E>(self) -> Result<Self::Value, E>
     where
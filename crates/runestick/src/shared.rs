@@ -19,16 +19,37 @@ pub struct Shared<T: ?Sized> {
 
 impl<T> Shared<T> {
     /// Construct a new shared value.
-    pub fn new(data: T) -> Self {
+    pub fn new(data: T) -> Self
+    where
+        T: any::Any,
+    {
         let inner = Box::leak(Box::new(SharedBox {
             access: Access::new(),
             count: Cell::new(1),
             data: data.into(),
         }));
 
-        Self {
+        let shared = Self {
             inner: inner.into(),
-        }
+        };
+
+        #[cfg(feature = "census")]
+        crate::census::allocated::<T>(shared.as_ptr());
+
+        shared
+    }
+
+    /// Construct a new reference to the live shared value with the given
+    /// address.
+    ///
+    /// # Safety
+    ///
+    /// The address must be of a live shared value of type `T`.
+    #[cfg(feature = "census")]
+    pub(crate) unsafe fn from_live(ptr: *const ()) -> Self {
+        let inner = ptr::NonNull::new_unchecked(ptr as *mut SharedBox<T>);
+        SharedBox::inc(inner.as_ptr());
+        Self { inner }
     }
 
     /// Return a debug formatter, that when printed will display detailed
@@ -98,6 +119,14 @@ impl<T> Shared<T> {
         unsafe { self.inner.as_ref().access.is_exclusive() }
     }
 
+    /// Test if this is the only reference to the value, and that it isn't
+    /// currently being accessed.
+    pub(crate) fn is_unique(&self) -> bool {
+        // Safety: Since we have a reference to this shared, we know that the
+        // inner is available.
+        unsafe { self.inner.as_ref().count.get() == 1 && self.is_writable() }
+    }
+
     /// Take the interior value, if we have exlusive access to it and there
     /// are no other live exlusive or shared references.
     ///
@@ -137,7 +166,7 @@ impl<T> Shared<T> {
             // NB: don't drop guard to avoid yielding access back.
             // This will prevent the value from being dropped in the shared
             // destructor and future illegal access of any kind.
-            let _ = ManuallyDrop::new(inner.access.take()?);
+            let _ = ManuallyDrop::new(inner.access.take(any::type_name::<T>())?);
 
             // Read the pointer out without dropping the inner structure.
             // The data field will be invalid at this point, which should be
@@ -187,7 +216,7 @@ impl<T> Shared<T> {
         //
         // Appropriate access is checked when constructing the guards.
         unsafe {
-            let guard = self.inner.as_ref().access.shared()?;
+            let guard = self.inner.as_ref().access.shared(any::type_name::<T>())?;
 
             // NB: we need to prevent the Drop impl for Shared from being called,
             // since we are deconstructing its internals.
@@ -237,7 +266,11 @@ impl<T> Shared<T> {
         //
         // Appropriate access is checked when constructing the guards.
         unsafe {
-            let guard = self.inner.as_ref().access.exclusive()?;
+            let guard = self
+                .inner
+                .as_ref()
+                .access
+                .exclusive(any::type_name::<T>())?;
 
             // NB: we need to prevent the Drop impl for Shared from being called,
             // since we are deconstructing its internals.
@@ -254,6 +287,12 @@ impl<T> Shared<T> {
 }
 
 impl<T: ?Sized> Shared<T> {
+    /// Get the address of the shared box, which identifies the shared value
+    /// regardless of how many handles to it exist.
+    pub(crate) fn as_ptr(&self) -> *const () {
+        self.inner.as_ptr() as *const ()
+    }
+
     /// Get a reference to the interior value while checking for shared access.
     ///
     /// This prevents other exclusive accesses from being performed while the
@@ -291,7 +330,7 @@ impl<T: ?Sized> Shared<T> {
         // Appropriate access is checked when constructing the guards.
         unsafe {
             let inner = self.inner.as_ref();
-            let guard = inner.access.shared()?;
+            let guard = inner.access.shared(any::type_name::<T>())?;
             Ok(BorrowRef::from_raw(inner.data.get(), guard))
         }
     }
@@ -330,7 +369,7 @@ impl<T: ?Sized> Shared<T> {
         // Appropriate access is checked when constructing the guards.
         unsafe {
             let inner = self.inner.as_ref();
-            let guard = inner.access.exclusive()?;
+            let guard = inner.access.exclusive(any::type_name::<T>())?;
             Ok(BorrowMut::from_raw(inner.data.get(), guard))
         }
     }
@@ -353,7 +392,7 @@ impl Shared<Any> {
             // NB: don't drop guard to avoid yielding access back.
             // This will prevent the value from being dropped in the shared
             // destructor and future illegal access of any kind.
-            let guard = ManuallyDrop::new(inner.access.take()?);
+            let guard = ManuallyDrop::new(inner.access.take(any::type_name::<T>())?);
 
             // Read the pointer out without dropping the inner structure.
             // Note that the data field will after this point be invalid.
@@ -396,7 +435,7 @@ impl Shared<Any> {
     {
         unsafe {
             let inner = self.inner.as_ref();
-            let guard = inner.access.shared()?;
+            let guard = inner.access.shared(any::type_name::<T>())?;
             let expected = Hash::from_type_id(any::TypeId::of::<T>());
 
             let data = match (*inner.data.get()).as_ptr(expected) {
@@ -421,7 +460,7 @@ impl Shared<Any> {
         unsafe {
             let (data, guard) = {
                 let inner = self.inner.as_ref();
-                let guard = inner.access.shared()?;
+                let guard = inner.access.shared(any::type_name::<T>())?;
                 let expected = Hash::from_type_id(any::TypeId::of::<T>());
 
                 match (*inner.data.get()).as_ptr(expected) {
@@ -455,7 +494,7 @@ impl Shared<Any> {
     {
         unsafe {
             let inner = self.inner.as_ref();
-            let guard = inner.access.exclusive()?;
+            let guard = inner.access.exclusive(any::type_name::<T>())?;
             let expected = Hash::from_type_id(any::TypeId::of::<T>());
 
             let data = match (*inner.data.get()).as_mut_ptr(expected) {
@@ -480,7 +519,7 @@ impl Shared<Any> {
         unsafe {
             let (data, guard) = {
                 let inner = self.inner.as_ref();
-                let guard = inner.access.exclusive()?;
+                let guard = inner.access.exclusive(any::type_name::<T>())?;
                 let expected = Hash::from_type_id(any::TypeId::of::<T>());
 
                 match (*inner.data.get()).as_mut_ptr(expected) {
@@ -622,6 +661,9 @@ impl<T: ?Sized> SharedBox<T> {
             return;
         }
 
+        #[cfg(feature = "census")]
+        crate::census::freed(this as *const ());
+
         if (*this).access.is_taken() {
             // NB: This prevents the inner `T` from being dropped in case it
             // has already been taken (as indicated by `is_taken`).
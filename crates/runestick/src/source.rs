@@ -2,14 +2,33 @@ use crate::Span;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// A single source file.
+///
+/// The contents of a source are reference counted, so it's cheap to clone and
+/// can be kept around for as long as it's needed, like by tooling which holds
+/// on to sources between compilations.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::Source;
+///
+/// let source = Source::new("main", "fn main() {\n    42\n}\n");
+/// let copy = source.clone();
+///
+/// assert_eq!(copy.as_str(), source.as_str());
+/// assert_eq!(source.line_count(), 3);
+/// ```
 #[derive(Debug, Clone)]
 pub struct Source {
     /// The name of the source.
     name: String,
     /// The source string.
-    source: String,
+    source: Arc<str>,
+    /// The byte offsets at which each line of the source starts.
+    line_starts: Arc<[usize]>,
     /// The (optional) path of a source file.
     path: Option<PathBuf>,
 }
@@ -21,22 +40,50 @@ impl Source {
         N: AsRef<str>,
         S: AsRef<str>,
     {
-        Self {
-            name: name.as_ref().to_owned(),
-            source: source.as_ref().to_owned(),
-            path: None,
-        }
+        Self::with_path(name.as_ref().to_owned(), source.as_ref().into(), None)
+    }
+
+    /// Construct a new source with the given name from contents which are
+    /// already shared, without copying them.
+    pub fn from_shared<N>(name: N, source: Arc<str>) -> Self
+    where
+        N: AsRef<str>,
+    {
+        Self::with_path(name.as_ref().to_owned(), source, None)
     }
 
     /// Load a source from a path.
     pub fn from_path(path: &Path) -> io::Result<Self> {
         let source = fs::read_to_string(path)?;
 
-        Ok(Self {
-            name: path.display().to_string(),
+        Ok(Self::with_path(
+            path.display().to_string(),
+            source.into(),
+            Some(path.to_owned()),
+        ))
+    }
+
+    /// Read a source with the given name until the end of the given reader,
+    /// like standard input.
+    pub fn from_reader<N, R>(name: N, mut reader: R) -> io::Result<Self>
+    where
+        N: AsRef<str>,
+        R: io::Read,
+    {
+        let mut source = String::new();
+        reader.read_to_string(&mut source)?;
+        Ok(Self::from_shared(name, source.into()))
+    }
+
+    fn with_path(name: String, source: Arc<str>, path: Option<PathBuf>) -> Self {
+        let line_starts = line_starts(&source);
+
+        Self {
+            name,
             source,
-            path: Some(path.to_owned()),
-        })
+            line_starts,
+            path,
+        }
     }
 
     /// Get the name of the source.
@@ -59,8 +106,209 @@ impl Source {
         &self.source
     }
 
+    /// Access the shared string for the source.
+    pub fn shared(&self) -> &Arc<str> {
+        &self.source
+    }
+
+    /// Get the number of lines in the source.
+    ///
+    /// A trailing newline doesn't start a new line.
+    pub fn line_count(&self) -> usize {
+        if self.source.ends_with('\n') {
+            self.line_starts.len() - 1
+        } else {
+            self.line_starts.len()
+        }
+    }
+
+    /// Get the zero-based line and column at which the given span starts.
+    ///
+    /// The column is counted in characters. Offsets past the end of the source
+    /// are clamped to it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Source, Span};
+    ///
+    /// let source = Source::new("main", "fn main() {\n    42\n}\n");
+    /// assert_eq!(source.position(Span::new(16, 18)), (1, 4));
+    /// ```
+    pub fn position(&self, span: Span) -> (usize, usize) {
+        self.offset_position(span.start)
+    }
+
+    /// Get the zero-based line and column of the start and the end of the
+    /// given span.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Source, Span};
+    ///
+    /// let source = Source::new("main", "fn main() {\n    42\n}\n");
+    /// assert_eq!(source.range(Span::new(10, 20)), ((0, 10), (2, 1)));
+    /// ```
+    pub fn range(&self, span: Span) -> ((usize, usize), (usize, usize)) {
+        (
+            self.offset_position(span.start),
+            self.offset_position(span.end),
+        )
+    }
+
+    /// Get the zero-based line and column of the given byte offset.
+    pub fn offset_position(&self, offset: usize) -> (usize, usize) {
+        let offset = usize::min(offset, self.source.len());
+        let line = self.line_index(offset);
+        let start = self.line_starts[line];
+
+        let column = match self.source.get(start..offset) {
+            Some(prefix) => prefix.chars().count(),
+            None => offset - start,
+        };
+
+        (line, column)
+    }
+
+    /// Get the byte offset of the given zero-based line and column, where the
+    /// column is counted in characters.
+    ///
+    /// Returns `None` if the position is outside of the source.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::Source;
+    ///
+    /// let source = Source::new("main", "fn main() {\n    42\n}\n");
+    /// assert_eq!(source.offset_at(1, 4), Some(16));
+    /// assert_eq!(source.offset_at(1, 10), None);
+    /// ```
+    pub fn offset_at(&self, line: usize, column: usize) -> Option<usize> {
+        let span = self.line_span(line)?;
+        let text = self.source.get(span.start..span.end)?;
+
+        let mut chars = text.char_indices().map(|(n, _)| n).chain(Some(text.len()));
+        chars.nth(column).map(|n| span.start + n)
+    }
+
+    /// Get the zero-based index of the line containing the given byte offset.
+    pub fn line_index(&self, offset: usize) -> usize {
+        match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        }
+    }
+
+    /// Get the span of the given zero-based line, excluding its line ending.
+    pub fn line_span(&self, line: usize) -> Option<Span> {
+        if line >= self.line_count() {
+            return None;
+        }
+
+        let start = self.line_starts[line];
+
+        let end = match self.line_starts.get(line + 1) {
+            Some(next) => next - 1,
+            None => self.source.len(),
+        };
+
+        let end = if self.source[..end].ends_with('\r') {
+            end - 1
+        } else {
+            end
+        };
+
+        Some(Span::new(start, usize::max(start, end)))
+    }
+
+    /// Get the text of the given zero-based line, excluding its line ending.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::Source;
+    ///
+    /// let source = Source::new("main", "fn main() {\r\n    42\r\n}");
+    /// assert_eq!(source.line_text(1), Some("    42"));
+    /// assert_eq!(source.line_text(2), Some("}"));
+    /// assert_eq!(source.line_text(3), None);
+    /// ```
+    pub fn line_text(&self, line: usize) -> Option<&str> {
+        self.source(self.line_span(line)?)
+    }
+
     /// Get the (optional) path of the source.
     pub fn path(&self) -> Option<&Path> {
         self.path.as_deref()
     }
 }
+
+/// Build the index of where each line in the given source starts.
+fn line_starts(source: &str) -> Arc<[usize]> {
+    std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(n, _)| n + 1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Source;
+    use crate::Span;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_constructors() {
+        let contents = String::from("fn main() {}\n");
+
+        let source = Source::new("main", &contents);
+        assert_eq!(source.name(), "main");
+        assert_eq!(source.as_str(), contents);
+
+        let shared = Arc::<str>::from(contents.as_str());
+        let source = Source::from_shared("main", shared.clone());
+        assert!(Arc::ptr_eq(source.shared(), &shared));
+
+        let source = Source::from_reader("stdin", contents.as_bytes()).unwrap();
+        assert_eq!(source.name(), "stdin");
+        assert_eq!(source.as_str(), contents);
+        assert_eq!(source.path(), None);
+
+        let invalid: &[u8] = &[b'a', 0xff];
+        assert!(Source::from_reader("stdin", invalid).is_err());
+    }
+
+    #[test]
+    fn test_line_count() {
+        assert_eq!(Source::new("main", "").line_count(), 1);
+        assert_eq!(Source::new("main", "a").line_count(), 1);
+        assert_eq!(Source::new("main", "a\n").line_count(), 1);
+        assert_eq!(Source::new("main", "a\nb").line_count(), 2);
+        assert_eq!(Source::new("main", "a\n\n").line_count(), 2);
+        assert_eq!(Source::new("main", "a\r\nb\r\n").line_count(), 2);
+    }
+
+    #[test]
+    fn test_positions() {
+        let source = Source::new("main", "a\n\u{e5}\u{e4}b\n\nc");
+
+        assert_eq!(source.line_count(), 4);
+        assert_eq!(source.offset_position(0), (0, 0));
+        assert_eq!(source.offset_position(1), (0, 1));
+        assert_eq!(source.offset_position(2), (1, 0));
+        assert_eq!(source.offset_position(6), (1, 2));
+        assert_eq!(source.offset_position(8), (2, 0));
+        assert_eq!(source.offset_position(9), (3, 0));
+        assert_eq!(source.offset_position(100), (3, 1));
+
+        assert_eq!(source.line_text(1), Some("\u{e5}\u{e4}b"));
+        assert_eq!(source.line_text(2), Some(""));
+        assert_eq!(source.line_span(3), Some(Span::new(9, 10)));
+
+        for offset in &[0, 1, 2, 4, 6, 8, 9, 10] {
+            let (line, column) = source.offset_position(*offset);
+            assert_eq!(source.offset_at(line, column), Some(*offset));
+        }
+    }
+}
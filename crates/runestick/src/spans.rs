@@ -0,0 +1,117 @@
+//! Tracing spans for script functions.
+//!
+//! This is only available with the `tracing` feature.
+
+use crate::Unit;
+use tracing::span::{EnteredSpan, Id};
+use tracing::Span;
+
+/// The spans of the script functions currently being called by a virtual
+/// machine, from the outermost function to the innermost.
+///
+/// Spans are only entered while the virtual machine is running, so that they
+/// don't cover unrelated work when it's suspended, like when it's awaiting a
+/// future.
+#[derive(Debug)]
+pub(crate) struct Spans {
+    /// Spans while the virtual machine is suspended.
+    suspended: Vec<Span>,
+    /// Spans while the virtual machine is running.
+    entered: Vec<EnteredSpan>,
+}
+
+impl Spans {
+    /// Construct an empty collection of spans.
+    pub(crate) const fn new() -> Self {
+        Self {
+            suspended: Vec::new(),
+            entered: Vec::new(),
+        }
+    }
+
+    /// Enter all spans before the virtual machine starts running.
+    ///
+    /// If no function has been entered yet, a span is created for the function
+    /// at `ip`, which is the function the virtual machine was started with.
+    pub(crate) fn enter(&mut self, unit: &Unit, ip: usize) {
+        if self.suspended.is_empty() && self.entered.is_empty() {
+            self.entered.push(function_span(unit, ip, None).entered());
+            return;
+        }
+
+        for span in self.suspended.drain(..) {
+            self.entered.push(span.entered());
+        }
+    }
+
+    /// Exit all spans once the virtual machine stops running.
+    pub(crate) fn exit(&mut self) {
+        while let Some(span) = self.entered.pop() {
+            self.suspended.push(span.exit());
+        }
+
+        self.suspended.reverse();
+    }
+
+    /// Enter the span of a function called at the given offset.
+    pub(crate) fn push(&mut self, unit: &Unit, offset: usize) {
+        let parent = self.entered.last().and_then(|span| span.id());
+        self.entered
+            .push(function_span(unit, offset, parent).entered());
+    }
+
+    /// Exit and close the span of the function returned from.
+    pub(crate) fn pop(&mut self) {
+        self.entered.pop();
+    }
+
+    /// Construct a span for awaiting a future in the current function.
+    pub(crate) fn await_span(&self) -> Span {
+        let parent = match self.suspended.last() {
+            Some(span) => span.id(),
+            None => self.entered.last().and_then(|span| span.id()),
+        };
+
+        match parent {
+            Some(parent) => tracing::trace_span!(parent: parent, "await"),
+            None => tracing::trace_span!("await"),
+        }
+    }
+
+    /// Close all spans.
+    pub(crate) fn clear(&mut self) {
+        self.entered.clear();
+        self.suspended.clear();
+    }
+}
+
+impl Clone for Spans {
+    fn clone(&self) -> Self {
+        let mut suspended = self.suspended.clone();
+        suspended.extend(self.entered.iter().map(|span| Span::clone(span)));
+
+        Self {
+            suspended,
+            entered: Vec::new(),
+        }
+    }
+}
+
+/// Construct a span for the function at the given offset, named by its item
+/// from the debug info of the unit.
+///
+/// Without an explicit parent, the span is a child of the current span of the
+/// host.
+fn function_span(unit: &Unit, offset: usize, parent: Option<Id>) -> Span {
+    let item = unit
+        .debug_info()
+        .and_then(|debug| debug.function_at(offset))
+        .map(|(_, signature)| signature.path.to_string());
+
+    let item = item.as_deref().unwrap_or("?");
+
+    match parent {
+        Some(parent) => tracing::trace_span!(parent: parent, "call", item),
+        None => tracing::trace_span!("call", item),
+    }
+}
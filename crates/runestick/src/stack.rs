@@ -1,6 +1,7 @@
-use crate::Value;
+use crate::{FromArgs, FromValue, ToValue, Value, VmError, VmErrorKind};
 use std::iter;
 use std::mem;
+use std::ops;
 use std::slice;
 use thiserror::Error;
 
@@ -18,6 +19,8 @@ pub struct Stack {
     ///
     /// It is not possible to interact with values below this stack frame.
     stack_bottom: usize,
+    /// The maximum number of values on the stack, if limited.
+    limit: Option<usize>,
 }
 
 impl Stack {
@@ -26,6 +29,7 @@ impl Stack {
         Self {
             stack: Vec::new(),
             stack_bottom: 0,
+            limit: None,
         }
     }
 
@@ -47,9 +51,35 @@ impl Stack {
         Self {
             stack: Vec::with_capacity(capacity),
             stack_bottom: 0,
+            limit: None,
         }
     }
 
+    /// Limit the number of values on the stack.
+    ///
+    /// The limit is checked when a function is called, using the maximum depth
+    /// of its stack as recorded in the unit. So a call raises an error if the
+    /// function could possibly exceed the limit, before it starts running.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::Stack;
+    ///
+    /// let mut stack = Stack::new();
+    /// assert_eq!(stack.limit(), None);
+    /// stack.set_limit(Some(1024));
+    /// assert_eq!(stack.limit(), Some(1024));
+    /// ```
+    pub fn set_limit(&mut self, limit: Option<usize>) {
+        self.limit = limit;
+    }
+
+    /// Get the maximum number of values on the stack, if limited.
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
     /// Clear the current stack.
     pub fn clear(&mut self) {
         self.stack.clear();
@@ -119,6 +149,27 @@ impl Stack {
         self.stack.push(Value::from(value));
     }
 
+    /// Convert and push a value onto the stack.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::Stack;
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let mut stack = Stack::new();
+    /// stack.push_value(Some(42i64))?;
+    /// assert_eq!(stack.pop_as::<Option<i64>>()?, Some(42));
+    /// # Ok(()) }
+    /// ```
+    pub fn push_value<T>(&mut self, value: T) -> Result<(), VmError>
+    where
+        T: ToValue,
+    {
+        self.stack.push(value.to_value()?);
+        Ok(())
+    }
+
     /// Pop a reference to a value from the stack.
     pub fn pop(&mut self) -> Result<Value, StackError> {
         if self.stack.len() == self.stack_bottom {
@@ -128,6 +179,50 @@ impl Stack {
         self.stack.pop().ok_or_else(|| StackError(()))
     }
 
+    /// Pop a value from the stack and convert it into the given type.
+    pub fn pop_as<T>(&mut self) -> Result<T, VmError>
+    where
+        T: FromValue,
+    {
+        T::from_value(self.pop()?)
+    }
+
+    /// Pop the arguments passed to a raw function and convert them into the
+    /// given tuple of types.
+    ///
+    /// Errors if the number of arguments doesn't match, or if an argument
+    /// can't be converted, indicating which argument failed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Stack, VmError};
+    ///
+    /// fn repeat(stack: &mut Stack, args: usize) -> Result<(), VmError> {
+    ///     let (string, n): (String, usize) = stack.take_args(args)?;
+    ///     stack.push_value(string.repeat(n))
+    /// }
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let mut stack = Stack::new();
+    /// stack.push_value(String::from("ab"))?;
+    /// stack.push_value(2i64)?;
+    /// repeat(&mut stack, 2)?;
+    /// assert_eq!(stack.pop_as::<String>()?, "abab");
+    ///
+    /// stack.push_value(2i64)?;
+    /// stack.push_value(2i64)?;
+    /// let error = repeat(&mut stack, 2).unwrap_err();
+    /// assert_eq!(error.to_string(), "bad argument #0, expected `String` but got `integer`");
+    /// # Ok(()) }
+    /// ```
+    pub fn take_args<T>(&mut self, args: usize) -> Result<T, VmError>
+    where
+        T: FromArgs,
+    {
+        T::from_args(self, args)
+    }
+
     /// Pop the given number of elements from the stack.
     pub fn popn(&mut self, count: usize) -> Result<(), StackError> {
         drop(self.drain_stack_top(count)?);
@@ -154,6 +249,48 @@ impl Stack {
         Ok(self.drain_stack_top(count)?.collect::<Vec<_>>())
     }
 
+    /// Enter a stack frame covering the given number of values at the top of
+    /// the stack, like the arguments passed to a raw function.
+    ///
+    /// Values below the frame cannot be accessed or popped through the
+    /// returned guard, and the previous frame is restored once the guard is
+    /// dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::Stack;
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let mut stack = Stack::new();
+    /// stack.push_value(1i64)?;
+    /// stack.push_value(2i64)?;
+    ///
+    /// {
+    ///     let mut frame = stack.frame(1)?;
+    ///     assert_eq!(frame.pop_as::<i64>()?, 2);
+    ///     assert!(frame.pop().is_err());
+    ///     frame.push_value(3i64)?;
+    /// }
+    ///
+    /// assert_eq!(stack.pop_as::<i64>()?, 3);
+    /// assert_eq!(stack.pop_as::<i64>()?, 1);
+    /// # Ok(()) }
+    /// ```
+    pub fn frame(&mut self, count: usize) -> Result<StackFrameGuard<'_>, StackError> {
+        let stack_bottom = self.swap_stack_bottom(count)?;
+
+        if self.stack_bottom < stack_bottom {
+            self.stack_bottom = stack_bottom;
+            return Err(StackError(()));
+        }
+
+        Ok(StackFrameGuard {
+            stack: self,
+            stack_bottom,
+        })
+    }
+
     /// Pop a sub stack of the given size.
     pub(crate) fn drain_stack_top(
         &mut self,
@@ -188,6 +325,23 @@ impl Stack {
         Err(StackError(()))
     }
 
+    /// Reserve capacity for a stack frame which is at most `depth` values deep,
+    /// starting at the current stack bottom.
+    ///
+    /// Raises an error if the frame could exceed the limit of the stack.
+    pub(crate) fn reserve_frame(&mut self, depth: usize) -> Result<(), VmError> {
+        let end = self.stack_bottom.saturating_add(depth);
+
+        if let Some(limit) = self.limit {
+            if end > limit {
+                return Err(VmError::from(VmErrorKind::StackOverflow { limit }));
+            }
+        }
+
+        self.stack.reserve(end.saturating_sub(self.stack.len()));
+        Ok(())
+    }
+
     /// Pop the current stack top and modify it to a different one.
     ///
     /// This asserts that the size of the current stack frame is exactly zero
@@ -199,11 +353,41 @@ impl Stack {
     }
 }
 
+/// A guard for a scoped stack frame, constructed through [Stack::frame].
+///
+/// Dereferences to the [Stack] it was constructed from.
+pub struct StackFrameGuard<'a> {
+    stack: &'a mut Stack,
+    /// The stack bottom to restore when the guard is dropped.
+    stack_bottom: usize,
+}
+
+impl ops::Deref for StackFrameGuard<'_> {
+    type Target = Stack;
+
+    fn deref(&self) -> &Self::Target {
+        self.stack
+    }
+}
+
+impl ops::DerefMut for StackFrameGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.stack
+    }
+}
+
+impl Drop for StackFrameGuard<'_> {
+    fn drop(&mut self) {
+        self.stack.stack_bottom = self.stack_bottom;
+    }
+}
+
 impl iter::FromIterator<Value> for Stack {
     fn from_iter<T: IntoIterator<Item = Value>>(iter: T) -> Self {
         Self {
             stack: iter.into_iter().collect(),
             stack_bottom: 0,
+            limit: None,
         }
     }
 }
@@ -213,6 +397,7 @@ impl From<Vec<Value>> for Stack {
         Self {
             stack,
             stack_bottom: 0,
+            limit: None,
         }
     }
 }
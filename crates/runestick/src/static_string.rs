@@ -1,4 +1,5 @@
 use crate::Hash;
+use serde::{de, ser};
 use std::fmt;
 use std::ops;
 
@@ -7,6 +8,7 @@ use std::ops;
 pub struct StaticString {
     inner: String,
     hash: Hash,
+    key_hash: u64,
 }
 
 impl StaticString {
@@ -15,16 +17,19 @@ impl StaticString {
     where
         S: AsRef<str>,
     {
-        let inner = s.as_ref().to_owned();
-        let hash = Hash::of(&inner);
-
-        Self { inner, hash }
+        Self::from(s.as_ref().to_owned())
     }
 
     /// Get the hash of the string.
     pub fn hash(&self) -> Hash {
         self.hash
     }
+
+    /// Get the hash of the string when it's used as a key in an object
+    /// constructed by the virtual machine.
+    pub(crate) fn key_hash(&self) -> u64 {
+        self.key_hash
+    }
 }
 
 impl AsRef<String> for StaticString {
@@ -48,9 +53,33 @@ impl ops::Deref for StaticString {
     }
 }
 
+impl ser::Serialize for StaticString {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_str(&self.inner)
+    }
+}
+
+impl<'de> de::Deserialize<'de> for StaticString {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        Ok(Self::from(String::deserialize(deserializer)?))
+    }
+}
+
 impl From<String> for StaticString {
     fn from(inner: String) -> Self {
         let hash = Hash::of(inner.as_str());
-        Self { inner, hash }
+        let key_hash = crate::object_key::hash(&inner);
+
+        Self {
+            inner,
+            hash,
+            key_hash,
+        }
     }
 }
@@ -108,6 +108,7 @@ pub static VEC_TYPE: &StaticType = &StaticType {
 
 impl_static_type!(impl<T> Vec<T> => VEC_TYPE);
 impl_static_type!([crate::Value] => VEC_TYPE);
+impl_static_type!(crate::Shared<Vec<crate::Value>> => VEC_TYPE);
 impl_static_type!(impl<T> crate::VecTuple<T> => VEC_TYPE);
 
 /// The specialized type information for an anonymous tuple type.
@@ -125,6 +126,7 @@ pub static OBJECT_TYPE: &StaticType = &StaticType {
 };
 
 impl_static_type!(impl<T> crate::Object<T> => OBJECT_TYPE);
+impl_static_type!(crate::Shared<crate::Object<crate::Value>> => OBJECT_TYPE);
 
 /// The specialized type information for a future type.
 pub static FUTURE_TYPE: &StaticType = &StaticType {
@@ -0,0 +1,116 @@
+use crate::{Bytes, Shared, StaticString, Tuple, Value};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// A fully constant value stored in the static data section of a unit.
+///
+/// Static values are immutable. The value constructed when one is loaded is
+/// kept by the virtual machine and loaded again as long as it hasn't been
+/// modified and nothing else references it. Otherwise a fresh copy is
+/// constructed, so mutating a loaded value never affects the unit or any other
+/// load.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum StaticValue {
+    /// The unit value.
+    Unit,
+    /// A boolean.
+    Bool(bool),
+    /// A single byte.
+    Byte(u8),
+    /// A character.
+    Char(char),
+    /// An integer.
+    Integer(i64),
+    /// A float.
+    Float(f64),
+    /// A string.
+    String(Arc<StaticString>),
+    /// A byte string.
+    Bytes(Vec<u8>),
+    /// A vector.
+    Vec(Vec<StaticValue>),
+    /// A tuple.
+    Tuple(Vec<StaticValue>),
+    /// An anonymous object.
+    Object(Vec<(String, StaticValue)>),
+}
+
+impl StaticValue {
+    /// Construct a new value from the static value.
+    pub fn to_value(&self) -> Value {
+        match self {
+            Self::Unit => Value::Unit,
+            Self::Bool(b) => Value::Bool(*b),
+            Self::Byte(b) => Value::Byte(*b),
+            Self::Char(c) => Value::Char(*c),
+            Self::Integer(n) => Value::Integer(*n),
+            Self::Float(n) => Value::Float(*n),
+            Self::String(s) => Value::StaticString(s.clone()),
+            Self::Bytes(b) => Value::from(Bytes::from_vec(b.clone())),
+            Self::Vec(values) => {
+                let vec = values.iter().map(Self::to_value).collect::<Vec<_>>();
+                Value::Vec(Shared::new(vec))
+            }
+            Self::Tuple(values) => {
+                let tuple = values.iter().map(Self::to_value).collect::<Vec<_>>();
+                Value::from(Tuple::from(tuple))
+            }
+            Self::Object(values) => {
+                let mut object = crate::object_key::with_capacity(values.len());
+
+                for (key, value) in values {
+                    object.insert(key.clone(), value.to_value());
+                }
+
+                Value::Object(Shared::new(object))
+            }
+        }
+    }
+
+    /// Test if the given value is an unmodified copy of the static value which
+    /// nothing else references, so that it can be loaded again without being
+    /// observable.
+    pub(crate) fn is_unmodified(&self, value: &Value) -> bool {
+        match (self, value) {
+            (Self::Unit, Value::Unit) => true,
+            (Self::Bool(a), Value::Bool(b)) => a == b,
+            (Self::Byte(a), Value::Byte(b)) => a == b,
+            (Self::Char(a), Value::Char(b)) => a == b,
+            (Self::Integer(a), Value::Integer(b)) => a == b,
+            (Self::Float(a), Value::Float(b)) => a.to_bits() == b.to_bits(),
+            (Self::String(a), Value::StaticString(b)) => Arc::ptr_eq(a, b),
+            (Self::Bytes(a), Value::Bytes(b)) => unique(b, |b| a[..] == b[..]),
+            (Self::Vec(a), Value::Vec(b)) => unique(b, |b| all_unmodified(a, b)),
+            (Self::Tuple(a), Value::Tuple(b)) => unique(b, |b| all_unmodified(a, b)),
+            (Self::Object(a), Value::Object(b)) => unique(b, |b| {
+                a.len() == b.len()
+                    && a.iter().all(|(key, a)| match b.get(key) {
+                        Some(b) => a.is_unmodified(b),
+                        None => false,
+                    })
+            }),
+            _ => false,
+        }
+    }
+}
+
+/// Test if the given shared value is only referenced by the given handle, and
+/// that its contents satisfy the given predicate.
+fn unique<T, F>(shared: &Shared<T>, f: F) -> bool
+where
+    F: FnOnce(&T) -> bool,
+{
+    if !shared.is_unique() {
+        return false;
+    }
+
+    match shared.borrow_ref() {
+        Ok(value) => f(&*value),
+        Err(..) => false,
+    }
+}
+
+/// Test if all the given values are unmodified copies of the static values.
+fn all_unmodified(statics: &[StaticValue], values: &[Value]) -> bool {
+    statics.len() == values.len() && statics.iter().zip(values).all(|(a, b)| a.is_unmodified(b))
+}
@@ -9,6 +9,7 @@ use std::mem;
 pub struct Stream {
     execution: Option<VmExecution>,
     first: bool,
+    completion: Option<Value>,
 }
 
 impl Stream {
@@ -17,6 +18,7 @@ impl Stream {
         Self {
             execution: Some(VmExecution::new(vm)),
             first: true,
+            completion: None,
         }
     }
 
@@ -37,18 +39,31 @@ impl Stream {
             }
         };
 
-        if !mem::take(&mut self.first) {
+        // NB: if an earlier resume was interrupted while waiting, it's picked
+        // up again and the value isn't used.
+        if !mem::take(&mut self.first) && !execution.is_awaiting() {
             execution.vm_mut()?.stack_mut().push(value);
         }
 
         let state = execution.async_resume().await?;
 
-        if state.is_complete() {
+        if let GeneratorState::Complete(value) = &state {
             self.execution = None;
+            self.completion = Some(value.clone());
         }
 
         Ok(state)
     }
+
+    /// Test if the stream has completed.
+    pub fn is_complete(&self) -> bool {
+        self.execution.is_none()
+    }
+
+    /// Get the value the stream completed with, if it has completed.
+    pub fn completion(&self) -> Option<Value> {
+        self.completion.clone()
+    }
 }
 
 impl fmt::Debug for Stream {
@@ -0,0 +1,200 @@
+use crate::collections::{HashMap, HashSet};
+use crate::{
+    Bytes, Object, Shared, Tuple, TupleVariant, TypedObject, TypedTuple, Value, VariantObject,
+    VmError, VmErrorKind,
+};
+use std::sync::Mutex;
+
+/// A detached value graph which can be sent across threads and used as an
+/// argument in a different virtual machine.
+///
+/// Constructed through [Value::deep_copy_for_transfer].
+#[derive(Debug)]
+pub struct Transfer {
+    value: Mutex<Value>,
+}
+
+impl Transfer {
+    /// Convert into the detached value.
+    pub fn into_value(self) -> Value {
+        match self.value.into_inner() {
+            Ok(value) => value,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Deep copy the detached value, leaving this container intact.
+    pub fn to_value(&self) -> Result<Value, VmError> {
+        let value = match self.value.lock() {
+            Ok(value) => value,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        Copier::default().copy(&*value)
+    }
+}
+
+// Safety: the contained value graph is freshly allocated by the deep copy and
+// no handles to any part of it exist outside of this container. It's only
+// accessed under the lock, and only fresh copies of it are handed out through
+// a shared reference. The only shared data it might reference are static
+// strings, which are reference counted atomically.
+unsafe impl Send for Transfer {}
+unsafe impl Sync for Transfer {}
+
+/// Deep copy the given value into a detached value graph.
+pub(crate) fn deep_copy(value: &Value) -> Result<Transfer, VmError> {
+    let mut copier = Copier::default();
+    let value = copier.copy(value)?;
+
+    Ok(Transfer {
+        value: Mutex::new(value),
+    })
+}
+
+/// State used while copying a value graph.
+///
+/// Values which are referenced in multiple places are only copied once, so
+/// that the copied graph preserves the same sharing as the original.
+#[derive(Default)]
+struct Copier {
+    /// Values which have already been copied.
+    copied: HashMap<*const (), Value>,
+    /// Values which are currently being copied, used to detect cycles.
+    visiting: HashSet<*const ()>,
+}
+
+impl Copier {
+    fn copy(&mut self, value: &Value) -> Result<Value, VmError> {
+        Ok(match value {
+            Value::Unit => Value::Unit,
+            Value::Bool(b) => Value::Bool(*b),
+            Value::Byte(b) => Value::Byte(*b),
+            Value::Char(c) => Value::Char(*c),
+            Value::Integer(n) => Value::Integer(*n),
+            Value::Float(n) => Value::Float(*n),
+            Value::Type(hash) => Value::Type(*hash),
+            Value::StaticString(string) => Value::StaticString(string.clone()),
+            Value::String(string) => self.shared(value, string, |_, string| {
+                Ok(Value::String(Shared::new(string.clone())))
+            })?,
+            Value::Bytes(bytes) => self.shared(value, bytes, |_, bytes| {
+                Ok(Value::Bytes(Shared::new(Bytes::from_vec(bytes.to_vec()))))
+            })?,
+            Value::Vec(vec) => self.shared(value, vec, |this, vec| {
+                Ok(Value::vec(this.copy_all(vec.iter())?))
+            })?,
+            Value::Tuple(tuple) => self.shared(value, tuple, |this, tuple| {
+                Ok(Value::Tuple(Shared::new(Tuple::from(
+                    this.copy_all(tuple.iter())?,
+                ))))
+            })?,
+            Value::Object(object) => self.shared(value, object, |this, object| {
+                Ok(Value::Object(Shared::new(this.copy_object(object)?)))
+            })?,
+            Value::Option(option) => self.shared(value, option, |this, option| {
+                let option = match option {
+                    Some(value) => Some(this.copy(value)?),
+                    None => None,
+                };
+
+                Ok(Value::Option(Shared::new(option)))
+            })?,
+            Value::Result(result) => self.shared(value, result, |this, result| {
+                let result = match result {
+                    Ok(value) => Ok(this.copy(value)?),
+                    Err(value) => Err(this.copy(value)?),
+                };
+
+                Ok(Value::Result(Shared::new(result)))
+            })?,
+            Value::TypedTuple(tuple) => self.shared(value, tuple, |this, tuple| {
+                Ok(Value::TypedTuple(Shared::new(TypedTuple {
+                    hash: tuple.hash,
+                    tuple: this.copy_all(tuple.tuple.iter())?.into_boxed_slice(),
+                })))
+            })?,
+            Value::TupleVariant(tuple) => self.shared(value, tuple, |this, tuple| {
+                Ok(Value::TupleVariant(Shared::new(TupleVariant {
+                    enum_hash: tuple.enum_hash,
+                    hash: tuple.hash,
+                    tuple: this.copy_all(tuple.tuple.iter())?.into_boxed_slice(),
+                })))
+            })?,
+            Value::TypedObject(object) => self.shared(value, object, |this, object| {
+                Ok(Value::TypedObject(Shared::new(TypedObject {
+                    hash: object.hash,
+                    object: this.copy_object(&object.object)?,
+                })))
+            })?,
+            Value::VariantObject(object) => self.shared(value, object, |this, object| {
+                Ok(Value::VariantObject(Shared::new(VariantObject {
+                    enum_hash: object.enum_hash,
+                    hash: object.hash,
+                    object: this.copy_object(&object.object)?,
+                })))
+            })?,
+            Value::Future(..)
+            | Value::Stream(..)
+            | Value::Generator(..)
+            | Value::GeneratorState(..)
+            | Value::Function(..)
+            | Value::Any(..) => {
+                return Err(VmError::from(VmErrorKind::UnsupportedTransfer {
+                    actual: value.type_info()?,
+                }));
+            }
+        })
+    }
+
+    /// Copy a shared value, reusing an existing copy if it has already been
+    /// copied and erroring if the value contains itself.
+    fn shared<T, F>(&mut self, value: &Value, shared: &Shared<T>, f: F) -> Result<Value, VmError>
+    where
+        F: FnOnce(&mut Self, &T) -> Result<Value, VmError>,
+    {
+        let ptr = shared.as_ptr();
+
+        if let Some(copied) = self.copied.get(&ptr) {
+            return Ok(copied.clone());
+        }
+
+        if !self.visiting.insert(ptr) {
+            return Err(VmError::from(VmErrorKind::TransferCycle {
+                target: value.type_info()?,
+            }));
+        }
+
+        let copied = {
+            let inner = shared.borrow_ref()?;
+            f(self, &*inner)?
+        };
+
+        self.visiting.remove(&ptr);
+        self.copied.insert(ptr, copied.clone());
+        Ok(copied)
+    }
+
+    fn copy_all<'a, I>(&mut self, values: I) -> Result<Vec<Value>, VmError>
+    where
+        I: ExactSizeIterator<Item = &'a Value>,
+    {
+        let mut output = Vec::with_capacity(values.len());
+
+        for value in values {
+            output.push(self.copy(value)?);
+        }
+
+        Ok(output)
+    }
+
+    fn copy_object(&mut self, object: &Object<Value>) -> Result<Object<Value>, VmError> {
+        let mut output = Object::with_capacity(object.len());
+
+        for (key, value) in object {
+            output.insert(key.clone(), self.copy(value)?);
+        }
+
+        Ok(output)
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{Hash, StaticType};
+use serde::{de, ser};
 use std::cmp;
 use std::fmt;
 use std::hash;
@@ -57,6 +58,26 @@ impl hash::Hash for Type {
     }
 }
 
+/// Types are serialized as their type hash, since static types can't be
+/// deserialized.
+impl ser::Serialize for Type {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        self.as_type_hash().serialize(serializer)
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Type {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        Ok(Self::Hash(Hash::deserialize(deserializer)?))
+    }
+}
+
 impl fmt::Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -21,7 +21,10 @@ impl fmt::Display for TypeInfo {
                 write!(fmt, "{}", ty.name)?;
             }
             Self::Hash(ty) => {
-                write!(fmt, "Type({})", ty)?;
+                crate::TypeNames::with_current(ty, |name| match name {
+                    Some(name) => write!(fmt, "{}", name),
+                    None => write!(fmt, "Type({})", ty),
+                })?;
             }
             Self::Any(type_name) => {
                 write!(fmt, "{}", type_name)?;
@@ -0,0 +1,100 @@
+use crate::{Context, Hash, Item, Type, Unit};
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::Arc;
+
+thread_local! {
+    /// The type names in scope while displaying a value through
+    /// [TypeNames::display].
+    static TYPE_NAMES: RefCell<Option<TypeNames>> = RefCell::new(None);
+}
+
+/// Resolves the names of types from the context and the unit they are
+/// declared in.
+///
+/// Type information like [TypeInfo::Hash][crate::TypeInfo::Hash] only knows
+/// the hash of a type, so it's displayed as `Type(0x...)` by default. Anything
+/// displayed through [display][TypeNames::display], like a [VmError], instead
+/// shows the name of the type if it's known, like `Customer` or
+/// `shop::Customer`.
+///
+/// [VmError]: crate::VmError
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Hash, Item, TypeInfo, TypeNames};
+///
+/// let info = TypeInfo::Hash(Hash::type_hash(&Item::of(&["Customer"])));
+/// let names = TypeNames::new(None, None);
+///
+/// assert!(info.to_string().starts_with("Type("));
+/// assert_eq!(names.display(&info).to_string(), info.to_string());
+/// ```
+#[derive(Default, Clone)]
+pub struct TypeNames {
+    context: Option<Arc<Context>>,
+    unit: Option<Arc<Unit>>,
+}
+
+impl TypeNames {
+    /// Construct a resolver for the types in the given context and unit.
+    pub fn new(context: Option<Arc<Context>>, unit: Option<Arc<Unit>>) -> Self {
+        Self { context, unit }
+    }
+
+    /// Look up the name of the type with the given hash.
+    pub fn lookup(&self, hash: Hash) -> Option<&Item> {
+        if let Some(name) = self.unit.as_ref().and_then(|u| u.lookup_type_name(hash)) {
+            return Some(name);
+        }
+
+        self.context.as_ref()?.lookup_type_name(Type::Hash(hash))
+    }
+
+    /// Display the given value with the names of types resolved.
+    pub fn display<'a, T>(&'a self, value: &'a T) -> impl fmt::Display + 'a
+    where
+        T: ?Sized + fmt::Display,
+    {
+        Display { names: self, value }
+    }
+
+    /// Look up the name of the type with the given hash in the type names
+    /// currently in scope, if any.
+    pub(crate) fn with_current<F, O>(hash: Hash, f: F) -> O
+    where
+        F: FnOnce(Option<&Item>) -> O,
+    {
+        TYPE_NAMES.with(|names| {
+            let names = names.borrow();
+            f(names.as_ref().and_then(|names| names.lookup(hash)))
+        })
+    }
+}
+
+struct Display<'a, T: ?Sized> {
+    names: &'a TypeNames,
+    value: &'a T,
+}
+
+impl<T> fmt::Display for Display<'_, T>
+where
+    T: ?Sized + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let previous = TYPE_NAMES.with(|names| names.replace(Some(self.names.clone())));
+        let _guard = Restore(previous);
+        self.value.fmt(f)
+    }
+}
+
+/// Restores the previous type names in scope when dropped.
+struct Restore(Option<TypeNames>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        TYPE_NAMES.with(|names| *names.borrow_mut() = previous);
+    }
+}
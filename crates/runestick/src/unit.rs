@@ -4,19 +4,39 @@
 //! metadata like function locations.
 
 use crate::collections::HashMap;
-use crate::{Call, DebugInfo, Hash, Inst, StaticString, Type, VmError, VmErrorKind};
+use crate::{
+    Call, Coverage, DebugInfo, Hash, Inst, Item, StaticString, StaticValue, Type, VmError,
+    VmErrorKind,
+};
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::sync::Arc;
 
 /// Instructions from a single source file.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Unit {
     /// The instructions contained in the source file.
     instructions: Vec<Inst>,
     /// Where functions are located in the collection of instructions.
     functions: HashMap<Hash, UnitFn>,
+    /// The function table, mapping the id of every function declared in the
+    /// unit to the offset of its first instruction.
+    ///
+    /// Calls only refer to functions by id, so a function can be relocated by
+    /// updating its entry in this table.
+    function_offsets: Vec<usize>,
+    /// The maximum depth of the stack of every function in the function
+    /// table, including its arguments.
+    ///
+    /// This is reserved when the function is called.
+    function_stacks: Vec<usize>,
     /// Declared types.
     types: HashMap<Hash, UnitTypeInfo>,
+    /// The variants of declared enums, in declaration order.
+    enums: HashMap<Hash, Vec<Hash>>,
+    /// The fields of declared structs and struct variants, in declaration
+    /// order.
+    struct_fields: HashMap<Hash, Box<[String]>>,
     /// A static string.
     static_strings: Vec<Arc<StaticString>>,
     /// A static byte string.
@@ -28,29 +48,46 @@ pub struct Unit {
     ///
     /// All keys are sorted with the default string sort.
     static_object_keys: Vec<Box<[String]>>,
+    /// The static data section, containing fully constant values.
+    static_values: Vec<StaticValue>,
     /// Debug info if available for unit.
     debug: Option<Box<DebugInfo>>,
+    /// Coverage counters, if the unit was compiled with coverage.
+    coverage: Coverage,
 }
 
 impl Unit {
     /// Construct a new unit with the given content.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         instructions: Vec<Inst>,
         functions: HashMap<Hash, UnitFn>,
+        function_offsets: Vec<usize>,
+        function_stacks: Vec<usize>,
         types: HashMap<Hash, UnitTypeInfo>,
+        enums: HashMap<Hash, Vec<Hash>>,
+        struct_fields: HashMap<Hash, Box<[String]>>,
         static_strings: Vec<Arc<StaticString>>,
         static_bytes: Vec<Vec<u8>>,
         static_object_keys: Vec<Box<[String]>>,
+        static_values: Vec<StaticValue>,
         debug: Option<Box<DebugInfo>>,
+        coverage: Coverage,
     ) -> Self {
         Self {
             instructions,
             functions,
+            function_offsets,
+            function_stacks,
             types,
+            enums,
+            struct_fields,
             static_strings,
             static_bytes,
             static_object_keys,
+            static_values,
             debug,
+            coverage,
         }
     }
 
@@ -59,12 +96,42 @@ impl Unit {
         self.types.get(&hash)
     }
 
+    /// Look up the name of a type declared in the unit by its hash.
+    pub fn lookup_type_name(&self, hash: Hash) -> Option<&Item> {
+        Some(&self.types.get(&hash)?.name)
+    }
+
+    /// Look up the hashes of the variants of the enum with the given hash, in
+    /// declaration order.
+    pub fn lookup_variants(&self, enum_hash: Hash) -> Option<&[Hash]> {
+        Some(self.enums.get(&enum_hash)?.as_slice())
+    }
+
+    /// Look up the fields of the struct or struct variant with the given hash,
+    /// in declaration order.
+    pub fn lookup_fields(&self, hash: Hash) -> Option<&[String]> {
+        Some(&**self.struct_fields.get(&hash)?)
+    }
+
     /// Access debug information for the given location if it is available.
     pub fn debug_info(&self) -> Option<&DebugInfo> {
         let debug = self.debug.as_ref()?;
         Some(&**debug)
     }
 
+    /// Remove the debug information from the unit, like when it's being
+    /// prepared to be shipped without its sources.
+    pub fn strip_debug_info(&mut self) {
+        self.debug = None;
+    }
+
+    /// Access the coverage counters of the unit.
+    ///
+    /// These are empty unless the unit was compiled with coverage enabled.
+    pub fn coverage(&self) -> &Coverage {
+        &self.coverage
+    }
+
     /// Get the instruction at the given instruction pointer.
     pub fn instruction_at(&self, ip: usize) -> Option<&Inst> {
         self.instructions.get(ip)
@@ -85,6 +152,11 @@ impl Unit {
         })
     }
 
+    /// Iterate over all values in the static data section of the unit.
+    pub fn iter_static_values(&self) -> impl Iterator<Item = &StaticValue> + '_ {
+        self.static_values.iter()
+    }
+
     /// Iterate over all instructions in order.
     pub fn iter_instructions(&self) -> impl Iterator<Item = Inst> + '_ {
         self.instructions.iter().copied()
@@ -117,24 +189,147 @@ impl Unit {
             .as_ref())
     }
 
+    /// Lookup the static value by slot, if it exists.
+    pub fn lookup_static_value(&self, slot: usize) -> Result<&StaticValue, VmError> {
+        self.static_values
+            .get(slot)
+            .ok_or_else(|| VmError::from(VmErrorKind::MissingStaticValue { slot }))
+    }
+
     /// Lookup the static object keys by slot, if it exists.
     pub fn lookup_object_keys(&self, slot: usize) -> Option<&[String]> {
         self.static_object_keys.get(slot).map(|keys| &keys[..])
     }
 
+    /// Collect statistics on the size of the unit.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::Unit;
+    ///
+    /// let stats = Unit::default().stats();
+    /// assert_eq!(stats.instructions, 0);
+    /// assert_eq!(stats.static_strings, 0);
+    /// ```
+    pub fn stats(&self) -> UnitStats {
+        UnitStats {
+            instructions: self.instructions.len(),
+            functions: self.function_offsets.len(),
+            types: self.types.len(),
+            static_strings: self.static_strings.len(),
+            static_string_bytes: self.static_strings.iter().map(|s| s.as_ref().len()).sum(),
+            static_bytes: self.static_bytes.len(),
+            static_bytes_bytes: self.static_bytes.iter().map(Vec::len).sum(),
+            static_object_keys: self.static_object_keys.len(),
+            static_values: self.static_values.len(),
+        }
+    }
+
     /// Lookup information of a function.
     pub fn lookup(&self, hash: Hash) -> Option<UnitFn> {
         self.functions.get(&hash).copied()
     }
+
+    /// Lookup the offset of the first instruction of the function with the
+    /// given id, if it exists.
+    pub fn function_offset(&self, id: usize) -> Result<usize, VmError> {
+        self.function_offsets
+            .get(id)
+            .copied()
+            .ok_or_else(|| VmError::from(VmErrorKind::MissingFunctionId { id }))
+    }
+
+    /// Lookup the maximum depth of the stack of the function with the given
+    /// id, including its arguments.
+    pub fn function_stack(&self, id: usize) -> Result<usize, VmError> {
+        self.function_stacks
+            .get(id)
+            .copied()
+            .ok_or_else(|| VmError::from(VmErrorKind::MissingFunctionId { id }))
+    }
+
+    /// Iterate over the function table, in order of function id.
+    pub fn iter_function_offsets(&self) -> impl Iterator<Item = usize> + '_ {
+        self.function_offsets.iter().copied()
+    }
+
+    /// Relocate the function with the given id so that it starts at the given
+    /// instruction offset, returning the offset it previously started at.
+    ///
+    /// Call sites don't have to be rewritten, since they refer to the function
+    /// by id. Only the entry point of the function changes: instructions aren't
+    /// moved, so the debug information of each instruction keeps describing the
+    /// instruction at its offset. The debug information of the function itself
+    /// follows it to its new entry point.
+    pub fn relocate_function(&mut self, id: usize, offset: usize) -> Option<usize> {
+        let slot = self.function_offsets.get_mut(id)?;
+        let previous = std::mem::replace(slot, offset);
+
+        if let Some(debug) = &mut self.debug {
+            if let Some(hash) = debug.functions_rev.remove(&previous) {
+                debug.functions_rev.insert(offset, hash);
+            }
+        }
+
+        Some(previous)
+    }
+}
+
+/// Statistics on the size of a unit, as returned by [Unit::stats].
+///
+/// Static strings, byte strings and object keys are deduplicated when the
+/// unit is assembled, so each of them is only counted once.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct UnitStats {
+    /// The number of instructions.
+    pub instructions: usize,
+    /// The number of functions declared in the unit.
+    pub functions: usize,
+    /// The number of types declared in the unit.
+    pub types: usize,
+    /// The number of static strings.
+    pub static_strings: usize,
+    /// The combined length in bytes of all static strings.
+    pub static_string_bytes: usize,
+    /// The number of static byte strings.
+    pub static_bytes: usize,
+    /// The combined length in bytes of all static byte strings.
+    pub static_bytes_bytes: usize,
+    /// The number of static object key sets.
+    pub static_object_keys: usize,
+    /// The number of values in the static data section.
+    pub static_values: usize,
+}
+
+impl fmt::Display for UnitStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "instructions: {}", self.instructions)?;
+        writeln!(f, "functions: {}", self.functions)?;
+        writeln!(f, "types: {}", self.types)?;
+        writeln!(
+            f,
+            "static strings: {} ({} bytes)",
+            self.static_strings, self.static_string_bytes
+        )?;
+        writeln!(
+            f,
+            "static byte strings: {} ({} bytes)",
+            self.static_bytes, self.static_bytes_bytes
+        )?;
+        writeln!(f, "static object keys: {}", self.static_object_keys)?;
+        write!(f, "static values: {}", self.static_values)
+    }
 }
 
 /// The kind and necessary information on registered functions.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum UnitFn {
-    /// Offset to call a "real" function.
+    /// A "real" function, declared in the unit.
     Offset {
-        /// Offset of the registered function.
-        offset: usize,
+        /// The id of the function, which is resolved to the offset of its
+        /// first instruction through the function table of the unit.
+        id: usize,
         /// The way the function is called.
         call: Call,
         /// The number of arguments the function takes.
@@ -161,8 +356,8 @@ pub enum UnitFn {
 impl fmt::Display for UnitFn {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Offset { offset, call, args } => {
-                write!(f, "offset {}, {}, {}", offset, call, args)?;
+            Self::Offset { id, call, args } => {
+                write!(f, "fn {}, {}, {}", id, call, args)?;
             }
             Self::Tuple { hash, args } => {
                 write!(f, "tuple {}, {}", hash, args)?;
@@ -181,10 +376,12 @@ impl fmt::Display for UnitFn {
 }
 
 /// Type information on a unit.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct UnitTypeInfo {
     /// A type declared in a unit.
     pub hash: Hash,
     /// value type of the given type.
     pub value_type: Type,
+    /// The name of the type.
+    pub name: Item,
 }
@@ -1,9 +1,13 @@
+use crate::context::Handler;
 use crate::{
-    Any, Bytes, Function, Future, Generator, GeneratorState, Hash, OwnedMut, OwnedRef, RawOwnedMut,
-    RawOwnedRef, Shared, StaticString, Stream, Tuple, Type, TypeInfo, VmError,
+    Any, Bytes, Context, FromValue as _, Function, Future, Generator, GeneratorState, Hash,
+    OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef, Shared, Stack, StaticString, Stream, Transfer,
+    Tuple, Type, TypeInfo, VmError, VmErrorKind,
 };
 use std::any;
+use std::cmp::Ordering;
 use std::fmt;
+use std::fmt::Write as _;
 use std::sync::Arc;
 
 /// The type of an object.
@@ -167,6 +171,17 @@ impl Value {
         }))
     }
 
+    /// Deep copy the value into a detached value graph which can be moved to
+    /// another virtual machine or across threads.
+    ///
+    /// Values which are referenced multiple times are only copied once.
+    /// Errors if the value contains itself or contains a value which is tied
+    /// to the virtual machine that produced it, like functions, futures,
+    /// generators, or external types.
+    pub fn deep_copy_for_transfer(&self) -> Result<Transfer, VmError> {
+        crate::transfer::deep_copy(self)
+    }
+
     /// Try to coerce value into a unit.
     #[inline]
     pub fn into_unit(self) -> Result<(), VmError> {
@@ -425,6 +440,163 @@ impl Value {
         })
     }
 
+    /// Format the value for debugging into the given buffer.
+    ///
+    /// External types are formatted using the [STRING_DEBUG][crate::STRING_DEBUG]
+    /// protocol if it's implemented in the given context. Anything else falls
+    /// back to the [Debug][fmt::Debug] implementation of the value. Values
+    /// which contain themselves are formatted as `[...]` where they recur.
+    pub fn string_debug(&self, context: &Context, buf: &mut String) -> Result<(), VmError> {
+        self.string_debug_with(context, buf, &mut Vec::new())
+    }
+
+    /// Format the value for debugging, where `visiting` are the containers
+    /// which are currently being formatted.
+    fn string_debug_with(
+        &self,
+        context: &Context,
+        buf: &mut String,
+        visiting: &mut Vec<*const ()>,
+    ) -> Result<(), VmError> {
+        let ptr = match self {
+            Self::Vec(vec) => Some(vec.as_ptr()),
+            Self::Tuple(tuple) => Some(tuple.as_ptr()),
+            Self::Object(object) => Some(object.as_ptr()),
+            Self::Option(option) => Some(option.as_ptr()),
+            Self::Result(result) => Some(result.as_ptr()),
+            _ => None,
+        };
+
+        if let Some(ptr) = ptr {
+            if visiting.contains(&ptr) {
+                buf.push_str("[...]");
+                return Ok(());
+            }
+
+            visiting.push(ptr);
+        }
+
+        match self {
+            Self::Vec(vec) => {
+                let vec = vec.borrow_ref()?;
+                Self::string_debug_seq(context, buf, visiting, "[", "]", vec.iter())?;
+            }
+            Self::Tuple(tuple) => {
+                let tuple = tuple.borrow_ref()?;
+                Self::string_debug_seq(context, buf, visiting, "(", ")", tuple.iter())?;
+            }
+            Self::Object(object) => {
+                let object = object.borrow_ref()?;
+                buf.push_str("#{");
+
+                let mut it = object.iter().peekable();
+
+                while let Some((key, value)) = it.next() {
+                    write!(buf, "{:?}: ", key).map_err(|_| VmErrorKind::FormatError)?;
+                    value.string_debug_with(context, buf, visiting)?;
+
+                    if it.peek().is_some() {
+                        buf.push_str(", ");
+                    }
+                }
+
+                buf.push('}');
+            }
+            Self::Option(option) => match &*option.borrow_ref()? {
+                Some(value) => {
+                    buf.push_str("Some(");
+                    value.string_debug_with(context, buf, visiting)?;
+                    buf.push(')');
+                }
+                None => {
+                    buf.push_str("None");
+                }
+            },
+            Self::Result(result) => match &*result.borrow_ref()? {
+                Ok(value) => {
+                    buf.push_str("Ok(");
+                    value.string_debug_with(context, buf, visiting)?;
+                    buf.push(')');
+                }
+                Err(value) => {
+                    buf.push_str("Err(");
+                    value.string_debug_with(context, buf, visiting)?;
+                    buf.push(')');
+                }
+            },
+            Self::Any(..) => {
+                let hash = Hash::instance_function(self.value_type()?, crate::STRING_DEBUG);
+
+                let handler = match context.lookup(hash) {
+                    Some(handler) => handler,
+                    None => {
+                        write!(buf, "{:?}", self).map_err(|_| VmErrorKind::FormatError)?;
+                        return Ok(());
+                    }
+                };
+
+                let b = Shared::new(std::mem::take(buf));
+                let result = self.call_string_debug(&**handler, &b);
+
+                // NB: the buffer is handed back to the caller even if the
+                // protocol failed.
+                *buf = b.take()?;
+                result?;
+            }
+            value => {
+                write!(buf, "{:?}", value).map_err(|_| VmErrorKind::FormatError)?;
+            }
+        }
+
+        if ptr.is_some() {
+            visiting.pop();
+        }
+
+        Ok(())
+    }
+
+    /// Call the given `STRING_DEBUG` protocol handler to format the value
+    /// into the given buffer.
+    fn call_string_debug(&self, handler: &Handler, buf: &Shared<String>) -> Result<(), VmError> {
+        let mut stack = Stack::with_capacity(2);
+        stack.push(self.clone());
+        stack.push(Value::String(buf.clone()));
+        handler(&mut stack, 2)?;
+
+        if let Err(fmt::Error) = fmt::Result::from_value(stack.pop()?)? {
+            return Err(VmError::from(VmErrorKind::FormatError));
+        }
+
+        Ok(())
+    }
+
+    fn string_debug_seq<'a, I>(
+        context: &Context,
+        buf: &mut String,
+        visiting: &mut Vec<*const ()>,
+        open: &str,
+        close: &str,
+        values: I,
+    ) -> Result<(), VmError>
+    where
+        I: IntoIterator<Item = &'a Value>,
+    {
+        buf.push_str(open);
+
+        let mut it = values.into_iter().peekable();
+
+        while let Some(value) = it.next() {
+            value.string_debug_with(context, buf, visiting)?;
+
+            if it.peek().is_some() {
+                buf.push_str(", ");
+            }
+        }
+
+        buf.push_str(close);
+        Ok(())
+    }
+
     /// Get the type information for the current value.
     pub fn type_info(&self) -> Result<TypeInfo, VmError> {
         Ok(match self {
@@ -525,6 +697,107 @@ impl Value {
             _ => false,
         })
     }
+
+    /// Optimized function to compare two value pointers with each other.
+    ///
+    /// Integers and floats can be compared with each other, in which case the
+    /// integer is promoted to a float. Strings, characters, and byte strings
+    /// are compared lexicographically, and tuples are compared element-wise.
+    /// Returns `None` if the values are unordered, like when comparing with a
+    /// float which is NaN.
+    ///
+    /// This is the basis for the comparison operations (`<`, `>`, `<=`, and
+    /// `>=`), and `op` is the operation reported if the values can't be
+    /// compared.
+    pub(crate) fn value_ptr_cmp(
+        a: &Value,
+        b: &Value,
+        op: &'static str,
+    ) -> Result<Option<Ordering>, VmError> {
+        Ok(match (a, b) {
+            (Self::Integer(a), Self::Integer(b)) => Some(a.cmp(b)),
+            (Self::Float(a), Self::Float(b)) => a.partial_cmp(b),
+            (Self::Integer(a), Self::Float(b)) => (*a as f64).partial_cmp(b),
+            (Self::Float(a), Self::Integer(b)) => a.partial_cmp(&(*b as f64)),
+            (Self::Byte(a), Self::Byte(b)) => Some(a.cmp(b)),
+            (Self::Char(a), Self::Char(b)) => Some(a.cmp(b)),
+            (Self::String(a), Self::String(b)) => {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+                Some(a.as_str().cmp(b.as_str()))
+            }
+            (Self::StaticString(a), Self::String(b)) => {
+                let b = b.borrow_ref()?;
+                Some(a.as_str().cmp(b.as_str()))
+            }
+            (Self::String(a), Self::StaticString(b)) => {
+                let a = a.borrow_ref()?;
+                Some(a.as_str().cmp(b.as_str()))
+            }
+            (Self::StaticString(a), Self::StaticString(b)) => Some(a.as_str().cmp(b.as_str())),
+            (Self::Bytes(a), Self::Bytes(b)) => {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+                Some((**a).cmp(&**b))
+            }
+            (Self::Tuple(a), Self::Tuple(b)) => {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+
+                for (a, b) in a.iter().zip(b.iter()) {
+                    match Self::value_ptr_cmp(a, b, op)? {
+                        Some(Ordering::Equal) => (),
+                        ordering => return Ok(ordering),
+                    }
+                }
+
+                Some(a.len().cmp(&b.len()))
+            }
+            (a, b) => {
+                return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
+                    op,
+                    lhs: a.type_info()?,
+                    rhs: b.type_info()?,
+                }))
+            }
+        })
+    }
+
+    /// Test if two values are the same value, as opposed to being equal.
+    ///
+    /// Primitives are compared by value, and everything else by which shared
+    /// value it refers to. This never accesses the values, so it's safe to use
+    /// to check if a collection has been modified.
+    pub(crate) fn is_same(a: &Value, b: &Value) -> bool {
+        match (a, b) {
+            (Self::Unit, Self::Unit) => true,
+            (Self::Bool(a), Self::Bool(b)) => a == b,
+            (Self::Byte(a), Self::Byte(b)) => a == b,
+            (Self::Char(a), Self::Char(b)) => a == b,
+            (Self::Integer(a), Self::Integer(b)) => a == b,
+            (Self::Float(a), Self::Float(b)) => a.to_bits() == b.to_bits(),
+            (Self::Type(a), Self::Type(b)) => a == b,
+            (Self::StaticString(a), Self::StaticString(b)) => Arc::ptr_eq(a, b),
+            (Self::String(a), Self::String(b)) => a.as_ptr() == b.as_ptr(),
+            (Self::Bytes(a), Self::Bytes(b)) => a.as_ptr() == b.as_ptr(),
+            (Self::Vec(a), Self::Vec(b)) => a.as_ptr() == b.as_ptr(),
+            (Self::Tuple(a), Self::Tuple(b)) => a.as_ptr() == b.as_ptr(),
+            (Self::Object(a), Self::Object(b)) => a.as_ptr() == b.as_ptr(),
+            (Self::Future(a), Self::Future(b)) => a.as_ptr() == b.as_ptr(),
+            (Self::Stream(a), Self::Stream(b)) => a.as_ptr() == b.as_ptr(),
+            (Self::Generator(a), Self::Generator(b)) => a.as_ptr() == b.as_ptr(),
+            (Self::GeneratorState(a), Self::GeneratorState(b)) => a.as_ptr() == b.as_ptr(),
+            (Self::Option(a), Self::Option(b)) => a.as_ptr() == b.as_ptr(),
+            (Self::Result(a), Self::Result(b)) => a.as_ptr() == b.as_ptr(),
+            (Self::TypedTuple(a), Self::TypedTuple(b)) => a.as_ptr() == b.as_ptr(),
+            (Self::TupleVariant(a), Self::TupleVariant(b)) => a.as_ptr() == b.as_ptr(),
+            (Self::TypedObject(a), Self::TypedObject(b)) => a.as_ptr() == b.as_ptr(),
+            (Self::VariantObject(a), Self::VariantObject(b)) => a.as_ptr() == b.as_ptr(),
+            (Self::Function(a), Self::Function(b)) => a.as_ptr() == b.as_ptr(),
+            (Self::Any(a), Self::Any(b)) => a.as_ptr() == b.as_ptr(),
+            _ => false,
+        }
+    }
 }
 
 impl fmt::Debug for Value {
@@ -549,7 +822,7 @@ impl fmt::Debug for Value {
                 write!(f, "{:?}", value)?;
             }
             Value::Type(value) => {
-                write!(f, "Type({})", value)?;
+                write!(f, "{}", TypeInfo::Hash(*value))?;
             }
             Value::StaticString(value) => {
                 write!(f, "{:?}", value)?;
@@ -1,12 +1,20 @@
+use crate::access::{self, BorrowSite};
+use crate::context::{ContextSignature, Handler};
 use crate::future::SelectFuture;
+use crate::modules::collections::{HashMap, HashSet};
+use crate::object_key;
 use crate::unit::UnitFn;
 use crate::{
     Args, Awaited, Bytes, Call, Context, FromValue, Function, Future, Generator, Hash, Inst,
-    Integer, IntoHash, Object, Panic, Select, Shared, Stack, Stream, Tuple, TypeCheck, TypedObject,
-    Unit, Value, VariantObject, VmError, VmErrorKind, VmExecution, VmHalt,
+    Integer, IntegerOverflow, IntoHash, Key, Object, Panic, Protocol, Select, Shared, Stack,
+    Stream, ToValue, Tuple, Type, TypeCheck, TypeInfo, TypeNames, TypedObject, Unit, Value,
+    VariantObject, VmError, VmErrorKind, VmExecution, VmHalt,
 };
+use std::any::Any;
+use std::cmp::Ordering;
 use std::fmt;
 use std::mem;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::Arc;
 
 /// A stack which references variables indirectly from a slab.
@@ -22,6 +30,20 @@ pub struct Vm {
     stack: Stack,
     /// Frames relative to the stack.
     call_frames: Vec<CallFrame>,
+    /// The values most recently loaded from each static value slot, which are
+    /// loaded again as long as they're unmodified and unreferenced.
+    statics: Vec<Option<Value>>,
+    /// Set if a native function panicked while being called, since the
+    /// state of the virtual machine can no longer be trusted.
+    poisoned: bool,
+    /// How integer arithmetic which overflows is handled.
+    integer_overflow: IntegerOverflow,
+    /// The number of instructions executed since it was last reported.
+    #[cfg(feature = "metrics")]
+    executed: u64,
+    /// Tracing spans of the functions being called.
+    #[cfg(feature = "tracing")]
+    spans: crate::spans::Spans,
 }
 
 impl Vm {
@@ -38,6 +60,13 @@ impl Vm {
             ip: 0,
             stack,
             call_frames: Vec::new(),
+            statics: Vec::new(),
+            poisoned: false,
+            integer_overflow: IntegerOverflow::Error,
+            #[cfg(feature = "metrics")]
+            executed: 0,
+            #[cfg(feature = "tracing")]
+            spans: crate::spans::Spans::new(),
         }
     }
 
@@ -84,6 +113,35 @@ impl Vm {
         &mut self.stack
     }
 
+    /// Get how integer arithmetic which overflows is handled.
+    #[inline]
+    pub fn integer_overflow(&self) -> IntegerOverflow {
+        self.integer_overflow
+    }
+
+    /// Set how integer arithmetic which overflows is handled.
+    ///
+    /// This is inherited by the virtual machines which are used to run async
+    /// functions, generators, and streams called from this one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Context, IntegerOverflow, Unit, Vm};
+    /// use std::sync::Arc;
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let context = Arc::new(Context::with_default_modules()?);
+    /// let mut vm = Vm::new(context, Arc::new(Unit::default()));
+    /// vm.set_integer_overflow(IntegerOverflow::Wrapping);
+    /// assert_eq!(vm.integer_overflow(), IntegerOverflow::Wrapping);
+    /// # Ok(()) }
+    /// ```
+    #[inline]
+    pub fn set_integer_overflow(&mut self, integer_overflow: IntegerOverflow) {
+        self.integer_overflow = integer_overflow;
+    }
+
     /// Access the context related to the virtual machine.
     pub fn context(&self) -> &Arc<Context> {
         &self.context
@@ -94,11 +152,93 @@ impl Vm {
         &self.unit
     }
 
+    /// Construct a resolver for the names of types in the context and unit of
+    /// the virtual machine, used to display errors raised by it.
+    pub fn type_names(&self) -> TypeNames {
+        TypeNames::new(Some(self.context.clone()), Some(self.unit.clone()))
+    }
+
+    /// Take a census of the shared values which are alive on the current
+    /// thread, grouped by type and named through the virtual machine.
+    ///
+    /// Values are counted as they are allocated and freed, so this includes
+    /// values which aren't reachable from the stack, like ones kept alive by
+    /// reference cycles. This can be used to find values that pile up in
+    /// long-running scripts.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Context, Unit, Value, Vm};
+    /// use std::sync::Arc;
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let context = Arc::new(Context::with_default_modules()?);
+    /// let mut vm = Vm::new(context, Arc::new(Unit::default()));
+    ///
+    /// let shared = Value::vec(vec![Value::from(1i64)]);
+    /// vm.stack_mut().push(Value::vec(vec![shared.clone(), shared]));
+    ///
+    /// let census = vm.census();
+    /// assert_eq!(census.count("Vec"), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "census")]
+    pub fn census(&self) -> crate::Census {
+        crate::Census::take(&self.type_names())
+    }
+
     /// Reset this virtual machine, freeing all memory used.
+    ///
+    /// This also makes a virtual machine which has been poisoned by a panic in
+    /// a native function usable again.
     pub fn clear(&mut self) {
         self.ip = 0;
         self.stack.clear();
         self.call_frames.clear();
+        self.statics.clear();
+        self.poisoned = false;
+
+        #[cfg(feature = "tracing")]
+        self.spans.clear();
+    }
+
+    /// Test if the virtual machine has been poisoned by a panic in a native
+    /// function.
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+
+    /// Take the number of instructions executed since this was last called.
+    #[cfg(feature = "metrics")]
+    pub(crate) fn take_executed(&mut self) -> u64 {
+        mem::take(&mut self.executed)
+    }
+
+    /// Enter the tracing spans of the functions being called, before running
+    /// the virtual machine.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn enter_spans(&mut self) {
+        self.spans.enter(&self.unit, self.ip);
+    }
+
+    /// Exit the tracing spans of the functions being called, after running
+    /// the virtual machine.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn exit_spans(&mut self) {
+        self.spans.exit();
+    }
+
+    /// Construct a tracing span for awaiting a future in the current function.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn await_span(&self) -> tracing::Span {
+        self.spans.await_span()
+    }
+
+    /// Mark the virtual machine as poisoned.
+    pub(crate) fn poison(&mut self) {
+        self.poisoned = true;
     }
 
     /// Access the current instruction pointer.
@@ -161,16 +301,17 @@ impl Vm {
             .lookup(hash)
             .ok_or_else(|| VmError::from(VmErrorKind::MissingFunction { hash }))?;
 
-        let offset = match info {
+        let (offset, stack) = match info {
             // NB: we ignore the calling convention.
             // everything is just async when called externally.
             UnitFn::Offset {
-                offset,
-                args: expected,
-                ..
+                id, args: expected, ..
             } => {
                 Self::check_args(A::count(), expected)?;
-                offset
+                (
+                    self.unit.function_offset(id)?,
+                    self.unit.function_stack(id)?,
+                )
             }
             _ => {
                 return Err(VmError::from(VmErrorKind::MissingFunction { hash }));
@@ -179,6 +320,7 @@ impl Vm {
 
         self.ip = offset;
         self.stack.clear();
+        self.stack.reserve_frame(stack)?;
 
         // Safety: we bind the lifetime of the arguments to the outgoing task,
         // ensuring that the task won't outlive any references passed in.
@@ -197,12 +339,24 @@ impl Vm {
         }
     }
 
-    fn op_select(&mut self, len: usize) -> Result<Option<Select>, VmError> {
+    fn op_select(&mut self, len: usize, default: bool) -> Result<Option<Select>, VmError> {
         let futures = futures::stream::FuturesUnordered::new();
 
         let arguments = self.stack.drain_stack_top(len)?.collect::<Vec<_>>();
 
         for (branch, value) in arguments.into_iter().enumerate() {
+            // NB: selecting over a stream waits for its next value, and a
+            // stream which has completed can't be selected.
+            if let Value::Stream(stream) = value {
+                if stream.borrow_ref()?.is_complete() {
+                    continue;
+                }
+
+                let future = Future::new(async move { stream.borrow_mut()?.next().await });
+                futures.push(SelectFuture::new(branch, Shared::new(future).owned_mut()?));
+                continue;
+            }
+
             let future = match self.try_into_future(value)? {
                 Ok(future) => future.owned_mut()?,
                 Err(value) => {
@@ -223,7 +377,29 @@ impl Vm {
             return Ok(None);
         }
 
-        Ok(Some(Select::new(futures)))
+        let mut select = Select::new(futures);
+
+        if default {
+            let mut cx = std::task::Context::from_waker(futures::task::noop_waker_ref());
+
+            // NB: if nothing is ready, the select is dropped. This is fine,
+            // since the futures keep their progress and can be selected over
+            // again.
+            match std::future::Future::poll(std::pin::Pin::new(&mut select), &mut cx) {
+                std::task::Poll::Ready(result) => {
+                    let (branch, value) = result?;
+                    self.stack.push(value);
+                    self.stack.push(ToValue::to_value(branch)?);
+                }
+                std::task::Poll::Pending => {
+                    self.stack.push(());
+                }
+            }
+
+            return Ok(None);
+        }
+
+        Ok(Some(select))
     }
 
     /// Helper function to call an instance function.
@@ -236,7 +412,7 @@ impl Vm {
         let hash = Hash::instance_function(target.value_type()?, hash.into_hash());
 
         if let Some(UnitFn::Offset {
-            offset,
+            id,
             call,
             args: expected,
         }) = self.unit.lookup(hash)
@@ -244,7 +420,7 @@ impl Vm {
             Self::check_args(count, expected)?;
             self.stack.push(target.clone());
             args.into_stack(&mut self.stack)?;
-            self.call_offset_fn(offset, call, count)?;
+            self.call_offset_fn(id, call, count, Return::Push)?;
             return Ok(true);
         }
 
@@ -256,10 +432,155 @@ impl Vm {
         self.stack.push(target.clone());
         args.into_stack(&mut self.stack)?;
 
-        handler(&mut self.stack, count)?;
+        Self::call_handler(
+            &self.context,
+            &self.unit,
+            self.ip,
+            &mut self.stack,
+            hash,
+            &**handler,
+            count,
+        )?;
+        Ok(true)
+    }
+
+    /// Call an instance function implementing an assign protocol like
+    /// [ADD_ASSIGN][crate::ADD_ASSIGN], discarding its return value.
+    fn call_assign_fn<H>(&mut self, lhs: &Value, hash: H, rhs: &Value) -> Result<bool, VmError>
+    where
+        H: IntoHash,
+    {
+        let hash = hash.into_hash();
+
+        if self.call_unit_fn(lhs, hash, rhs, Return::Discard)? {
+            return Ok(true);
+        }
+
+        if !self.call_instance_fn(lhs, hash, (rhs,))? {
+            return Ok(false);
+        }
+
+        self.stack.pop()?;
+        Ok(true)
+    }
+
+    /// Call an instance function which takes one argument and is declared in
+    /// the unit, returning `false` if it doesn't exist.
+    ///
+    /// The function is called in a new call frame of this virtual machine, and
+    /// its return value is handled as specified by `ret` once it returns.
+    fn call_unit_fn(
+        &mut self,
+        target: &Value,
+        hash: Hash,
+        arg: &Value,
+        ret: Return,
+    ) -> Result<bool, VmError> {
+        let hash = Hash::instance_function(target.value_type()?, hash);
+
+        let (id, call, expected) = match self.unit.lookup(hash) {
+            Some(UnitFn::Offset { id, call, args }) => (id, call, args),
+            _ => return Ok(false),
+        };
+
+        Self::check_args(2, expected)?;
+        self.stack.push(target.clone());
+        self.stack.push(arg.clone());
+        self.call_offset_fn(id, call, 2, ret)?;
         Ok(true)
     }
 
+    /// Call the given native handler, making the current location available to
+    /// it through [Caller::current][crate::Caller::current].
+    pub(crate) fn call_native(
+        &mut self,
+        hash: Hash,
+        handler: &Handler,
+        args: usize,
+    ) -> Result<(), VmError> {
+        Self::call_handler(
+            &self.context,
+            &self.unit,
+            self.ip,
+            &mut self.stack,
+            hash,
+            handler,
+            args,
+        )
+    }
+
+    /// Call the native handler identified by `hash`.
+    ///
+    /// Unless the context is configured to propagate them, panics in the
+    /// handler are caught here and converted into a
+    /// [NativePanic][VmErrorKind::NativePanic] error.
+    #[inline]
+    fn call_handler(
+        context: &Arc<Context>,
+        unit: &Arc<Unit>,
+        ip: usize,
+        stack: &mut Stack,
+        hash: Hash,
+        handler: &Handler,
+        args: usize,
+    ) -> Result<(), VmError> {
+        let overridden = crate::overrides::lookup(hash);
+        let handler = overridden.as_deref().unwrap_or(handler);
+
+        crate::event_log::record_call(
+            true,
+            || Self::native_function_name(context, hash),
+            Self::top_args(stack, args),
+        );
+
+        crate::recording::call_native(stack, hash, args, |stack| {
+            if context.propagate_panics() {
+                return crate::caller::with_caller(context, unit, ip, || handler(stack, args));
+            }
+
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                crate::caller::with_caller(context, unit, ip, || handler(stack, args))
+            }));
+
+            match result {
+                Ok(result) => result,
+                Err(payload) => Err(VmError::from(VmErrorKind::NativePanic {
+                    function: Self::native_function_name(context, hash),
+                    message: Self::panic_message(&*payload),
+                })),
+            }
+        })
+    }
+
+    /// Get the arguments of a call at the top of the stack.
+    fn top_args(stack: &Stack, args: usize) -> &[Value] {
+        stack
+            .get(stack.len().saturating_sub(args)..)
+            .unwrap_or_default()
+    }
+
+    /// Get a human readable name of the native function identified by `hash`.
+    fn native_function_name(context: &Context, hash: Hash) -> String {
+        match context.lookup_signature(hash) {
+            Some(ContextSignature::Function { path, .. }) => path.to_string(),
+            Some(ContextSignature::Instance { path, name, .. }) => format!("{}::{}", path, name),
+            None => hash.to_string(),
+        }
+    }
+
+    /// Extract the message from the payload of a caught panic.
+    fn panic_message(payload: &(dyn Any + Send)) -> String {
+        if let Some(message) = payload.downcast_ref::<&'static str>() {
+            return (*message).to_owned();
+        }
+
+        if let Some(message) = payload.downcast_ref::<String>() {
+            return message.clone();
+        }
+
+        String::from("Box<Any>")
+    }
+
     /// Helper function to call an external getter.
     fn call_getter<H, A>(&mut self, target: &Value, hash: H, args: A) -> Result<bool, VmError>
     where
@@ -277,7 +598,15 @@ impl Vm {
         args.into_stack(&mut self.stack)?;
 
         self.stack.push(target.clone());
-        handler(&mut self.stack, count)?;
+        Self::call_handler(
+            &self.context,
+            &self.unit,
+            self.ip,
+            &mut self.stack,
+            hash,
+            &**handler,
+            count,
+        )?;
         Ok(true)
     }
 
@@ -337,88 +666,127 @@ impl Vm {
         Ok(())
     }
 
+    /// Internal impl of a comparison operation.
     fn internal_boolean_ops(
         &mut self,
-        int_op: impl FnOnce(i64, i64) -> bool,
-        float_op: impl FnOnce(f64, f64) -> bool,
+        cmp_op: impl FnOnce(Ordering) -> bool,
         op: &'static str,
     ) -> Result<(), VmError> {
         let rhs = self.stack.pop()?;
         let lhs = self.stack.pop()?;
-
-        let out = match (lhs, rhs) {
-            (Value::Integer(lhs), Value::Integer(rhs)) => int_op(lhs, rhs),
-            (Value::Float(lhs), Value::Float(rhs)) => float_op(lhs, rhs),
-            (lhs, rhs) => {
-                return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
-                    op,
-                    lhs: lhs.type_info()?,
-                    rhs: rhs.type_info()?,
-                }))
-            }
-        };
-
+        let out = Value::value_ptr_cmp(&lhs, &rhs, op)?.is_some_and(cmp_op);
         self.stack.push(out);
         Ok(())
     }
 
     fn op_gt(&mut self) -> Result<(), VmError> {
-        self.internal_boolean_ops(|a, b| a > b, |a, b| a > b, ">")?;
+        self.internal_boolean_ops(Ordering::is_gt, ">")?;
         Ok(())
     }
 
     fn op_gte(&mut self) -> Result<(), VmError> {
-        self.internal_boolean_ops(|a, b| a >= b, |a, b| a >= b, ">=")?;
+        self.internal_boolean_ops(Ordering::is_ge, ">=")?;
         Ok(())
     }
 
     fn op_lt(&mut self) -> Result<(), VmError> {
-        self.internal_boolean_ops(|a, b| a < b, |a, b| a < b, "<")?;
+        self.internal_boolean_ops(Ordering::is_lt, "<")?;
         Ok(())
     }
 
     fn op_lte(&mut self) -> Result<(), VmError> {
-        self.internal_boolean_ops(|a, b| a <= b, |a, b| a <= b, "<=")?;
+        self.internal_boolean_ops(Ordering::is_le, "<=")?;
         Ok(())
     }
 
     /// Push a new call frame.
     ///
     /// This will cause the `args` number of elements on the stack to be
-    /// associated and accessible to the new call frame.
-    pub(crate) fn push_call_frame(&mut self, ip: usize, args: usize) -> Result<(), VmError> {
+    /// associated and accessible to the new call frame, and reserves space for
+    /// the frame to grow to `stack` values.
+    pub(crate) fn push_call_frame(
+        &mut self,
+        ip: usize,
+        args: usize,
+        stack: usize,
+    ) -> Result<(), VmError> {
+        self.push_call_frame_with(ip, args, stack, Return::Push)
+    }
+
+    /// Push a new call frame, whose return value is handled as specified by
+    /// `ret` once it returns.
+    fn push_call_frame_with(
+        &mut self,
+        ip: usize,
+        args: usize,
+        stack: usize,
+        ret: Return,
+    ) -> Result<(), VmError> {
         let stack_top = self.stack.swap_stack_bottom(args)?;
+        self.stack.reserve_frame(stack)?;
 
         self.call_frames.push(CallFrame {
             ip: self.ip,
             stack_bottom: stack_top,
+            ret,
         });
 
+        #[cfg(feature = "tracing")]
+        self.spans.push(&self.unit, ip);
+
         self.ip = ip.overflowing_sub(1).0;
         Ok(())
     }
 
-    /// Pop a call frame and return it.
-    fn pop_call_frame(&mut self) -> Result<bool, VmError> {
+    /// Pop a call frame and push the value it returned onto the stack of the
+    /// caller.
+    ///
+    /// Returns `true` if the popped frame was the last one, in which case the
+    /// virtual machine should exit.
+    fn pop_call_frame(&mut self, return_value: Value) -> Result<bool, VmError> {
+        #[cfg(feature = "tracing")]
+        self.spans.pop();
+
         let frame = match self.call_frames.pop() {
             Some(frame) => frame,
             None => {
                 self.stack.check_stack_top()?;
+                self.stack.push(return_value);
                 return Ok(true);
             }
         };
 
         self.stack.pop_stack_top(frame.stack_bottom)?;
         self.ip = frame.ip;
+        self.push_return(return_value, frame.ret)?;
         Ok(false)
     }
 
+    /// Push the value returned by a function onto the stack, as specified by
+    /// `ret`.
+    fn push_return(&mut self, return_value: Value, ret: Return) -> Result<(), VmError> {
+        match ret {
+            Return::Push => self.stack.push(return_value),
+            Return::Discard => (),
+            Return::Bool => self.stack.push(return_value.into_bool()?),
+            Return::NotBool => self.stack.push(!return_value.into_bool()?),
+        }
+
+        Ok(())
+    }
+
     /// Optimized equality implementation.
     #[inline]
     fn op_eq(&mut self) -> Result<(), VmError> {
         let b = self.stack.pop()?;
         let a = self.stack.pop()?;
-        self.stack.push(Value::value_ptr_eq(&a, &b)?);
+
+        if self.call_unit_eq_fn(&a, &b, Return::Bool)? {
+            return Ok(());
+        }
+
+        let eq = self.value_eq(&a, &b)?;
+        self.stack.push(eq);
         Ok(())
     }
 
@@ -427,10 +795,97 @@ impl Vm {
     fn op_neq(&mut self) -> Result<(), VmError> {
         let b = self.stack.pop()?;
         let a = self.stack.pop()?;
-        self.stack.push(!Value::value_ptr_eq(&a, &b)?);
+
+        if self.call_unit_eq_fn(&a, &b, Return::NotBool)? {
+            return Ok(());
+        }
+
+        let eq = self.value_eq(&a, &b)?;
+        self.stack.push(!eq);
         Ok(())
     }
 
+    /// Call the [EQ][crate::EQ] protocol of a type declared in the unit on
+    /// either side of a comparison, returning `false` if neither implements
+    /// it.
+    fn call_unit_eq_fn(&mut self, a: &Value, b: &Value, ret: Return) -> Result<bool, VmError> {
+        for (target, other) in [(a, b), (b, a)] {
+            if let Value::TypedObject(..)
+            | Value::TypedTuple(..)
+            | Value::VariantObject(..)
+            | Value::TupleVariant(..) = target
+            {
+                if self.call_unit_fn(target, crate::EQ.hash, other, ret)? {
+                    return Ok(true);
+                }
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Test two values for equality, using the [EQ][crate::EQ] protocol for
+    /// external values which implement it.
+    ///
+    /// Integers compared with floats are promoted to floats.
+    fn value_eq(&mut self, a: &Value, b: &Value) -> Result<bool, VmError> {
+        match (a, b) {
+            (Value::Integer(a), Value::Float(b)) => return Ok(*a as f64 == *b),
+            (Value::Float(a), Value::Integer(b)) => return Ok(*a == *b as f64),
+            _ => (),
+        }
+
+        // NB: the protocol is tried on both sides, so that it doesn't matter
+        // which side of the comparison a value implementing it is on.
+        if let Some(eq) = self.call_eq_protocol(a, b)? {
+            return Ok(eq);
+        }
+
+        if let Some(eq) = self.call_eq_protocol(b, a)? {
+            return Ok(eq);
+        }
+
+        Value::value_ptr_eq(a, b)
+    }
+
+    /// Call the `EQ` protocol of the given target to compare it with the other
+    /// value.
+    ///
+    /// Returns `None` if the target doesn't implement the protocol, or if its
+    /// native implementation doesn't accept the other value, like when
+    /// comparing values of unrelated types.
+    fn call_eq_protocol(&mut self, target: &Value, other: &Value) -> Result<Option<bool>, VmError> {
+        if let Value::Any(..) = target {
+            let hash = Hash::instance_function(target.value_type()?, crate::EQ);
+
+            if let Some(handler) = self.context.lookup(hash) {
+                self.stack.push(target.clone());
+                self.stack.push(other.clone());
+
+                let result = Self::call_handler(
+                    &self.context,
+                    &self.unit,
+                    self.ip,
+                    &mut self.stack,
+                    hash,
+                    &**handler,
+                    2,
+                );
+
+                match result {
+                    Ok(()) => return Ok(Some(self.stack.pop()?.into_bool()?)),
+                    Err(error) => match error.kind() {
+                        VmErrorKind::BadArgument { arg: 1, .. }
+                        | VmErrorKind::BadArgumentType { arg: 1, .. } => return Ok(None),
+                        _ => return Err(error),
+                    },
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
     /// Perform a jump operation.
     #[inline]
     fn op_jump(&mut self, offset: isize) -> Result<(), VmError> {
@@ -458,6 +913,19 @@ impl Vm {
         Ok(())
     }
 
+    /// Perform a conditional jump operation which leaves the condition on the
+    /// stack. Only jumps if the top of the stack is the expected boolean.
+    #[inline]
+    fn op_peek_and_jump_if(&mut self, expected: bool, offset: isize) -> Result<(), VmError> {
+        if let Some(Value::Bool(value)) = self.stack.peek() {
+            if *value == expected {
+                self.modify_ip(offset)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Perform a branch-conditional jump operation.
     #[inline]
     fn op_jump_if_branch(&mut self, branch: i64, offset: isize) -> Result<(), VmError> {
@@ -487,6 +955,34 @@ impl Vm {
         Ok(())
     }
 
+    /// Construct a new hash map out of pairs of keys and values.
+    #[inline]
+    fn op_hash_map(&mut self, count: usize) -> Result<(), VmError> {
+        let mut map = HashMap::new();
+        let mut it = self.stack.drain_stack_top(count * 2)?;
+
+        while let (Some(key), Some(value)) = (it.next(), it.next()) {
+            map.insert(Key::from_value(&key)?, value);
+        }
+
+        drop(it);
+        self.stack.push(map.to_value()?);
+        Ok(())
+    }
+
+    /// Construct a new hash set.
+    #[inline]
+    fn op_hash_set(&mut self, count: usize) -> Result<(), VmError> {
+        let mut set = HashSet::new();
+
+        for key in self.stack.drain_stack_top(count)? {
+            set.insert(Key::from_value(&key)?);
+        }
+
+        self.stack.push(set.to_value()?);
+        Ok(())
+    }
+
     /// Push the tuple that is on top of the stack.
     #[inline]
     fn op_push_tuple(&mut self) -> Result<(), VmError> {
@@ -515,12 +1011,39 @@ impl Vm {
         Ok(())
     }
 
+    #[inline]
+    fn op_neg(&mut self) -> Result<(), VmError> {
+        let value = self.stack.pop()?;
+
+        let value = match value {
+            Value::Integer(value) => Value::from(
+                self.integer_overflow
+                    .neg(value)
+                    .ok_or_else(|| VmError::from(VmErrorKind::Overflow))?,
+            ),
+            Value::Float(value) => Value::from(-value),
+            other => {
+                if !self.call_instance_fn(&other, crate::NEG, ())? {
+                    return Err(VmError::from(VmErrorKind::UnsupportedUnaryOperation {
+                        op: "-",
+                        operand: other.type_info()?,
+                    }));
+                }
+
+                return Ok(());
+            }
+        };
+
+        self.stack.push(value);
+        Ok(())
+    }
+
     #[inline]
     fn op_add(&mut self) -> Result<(), VmError> {
         self.internal_num(
             crate::ADD,
-            || VmError::from(VmErrorKind::Overflow),
-            i64::checked_add,
+            |_, _| VmError::from(VmErrorKind::Overflow),
+            IntegerOverflow::add,
             std::ops::Add::add,
             "+",
         )?;
@@ -531,8 +1054,8 @@ impl Vm {
     fn op_sub(&mut self) -> Result<(), VmError> {
         self.internal_num(
             crate::SUB,
-            || VmError::from(VmErrorKind::Underflow),
-            i64::checked_sub,
+            |_, _| VmError::from(VmErrorKind::Underflow),
+            IntegerOverflow::sub,
             std::ops::Sub::sub,
             "-",
         )?;
@@ -542,9 +1065,9 @@ impl Vm {
     #[inline]
     fn op_mul(&mut self) -> Result<(), VmError> {
         self.internal_num(
-            crate::ADD,
-            || VmError::from(VmErrorKind::Overflow),
-            i64::checked_mul,
+            crate::MUL,
+            |_, _| VmError::from(VmErrorKind::Overflow),
+            IntegerOverflow::mul,
             std::ops::Mul::mul,
             "*",
         )?;
@@ -554,11 +1077,11 @@ impl Vm {
     #[inline]
     fn op_div(&mut self) -> Result<(), VmError> {
         self.internal_num(
-            crate::ADD,
-            || VmError::from(VmErrorKind::DivideByZero),
-            i64::checked_div,
+            crate::DIV,
+            Self::division_error,
+            IntegerOverflow::div,
             std::ops::Div::div,
-            "+",
+            "/",
         )?;
         Ok(())
     }
@@ -567,8 +1090,8 @@ impl Vm {
     fn op_rem(&mut self) -> Result<(), VmError> {
         self.internal_num(
             crate::REM,
-            || VmError::from(VmErrorKind::DivideByZero),
-            i64::checked_rem,
+            Self::division_error,
+            IntegerOverflow::rem,
             std::ops::Rem::rem,
             "%",
         )?;
@@ -577,19 +1100,34 @@ impl Vm {
 
     #[inline]
     fn op_bit_and(&mut self) -> Result<(), VmError> {
-        self.internal_infallible_bitwise(crate::BIT_AND, std::ops::BitAnd::bitand, "&")?;
+        self.internal_infallible_bitwise(
+            crate::BIT_AND,
+            std::ops::BitAnd::bitand,
+            std::ops::BitAnd::bitand,
+            "&",
+        )?;
         Ok(())
     }
 
     #[inline]
     fn op_bit_xor(&mut self) -> Result<(), VmError> {
-        self.internal_infallible_bitwise(crate::BIT_XOR, std::ops::BitXor::bitxor, "^")?;
+        self.internal_infallible_bitwise(
+            crate::BIT_XOR,
+            std::ops::BitXor::bitxor,
+            std::ops::BitXor::bitxor,
+            "^",
+        )?;
         Ok(())
     }
 
     #[inline]
     fn op_bit_or(&mut self) -> Result<(), VmError> {
-        self.internal_infallible_bitwise(crate::BIT_OR, std::ops::BitOr::bitor, "|")?;
+        self.internal_infallible_bitwise(
+            crate::BIT_OR,
+            std::ops::BitOr::bitor,
+            std::ops::BitOr::bitor,
+            "|",
+        )?;
         Ok(())
     }
 
@@ -599,6 +1137,7 @@ impl Vm {
             offset,
             crate::BIT_AND_ASSIGN,
             std::ops::BitAndAssign::bitand_assign,
+            std::ops::BitAndAssign::bitand_assign,
             "&=",
         )?;
         Ok(())
@@ -610,6 +1149,7 @@ impl Vm {
             offset,
             crate::BIT_XOR_ASSIGN,
             std::ops::BitXorAssign::bitxor_assign,
+            std::ops::BitXorAssign::bitxor_assign,
             "^=",
         )?;
         Ok(())
@@ -621,6 +1161,7 @@ impl Vm {
             offset,
             crate::BIT_OR_ASSIGN,
             std::ops::BitOrAssign::bitor_assign,
+            std::ops::BitOrAssign::bitor_assign,
             "|=",
         )?;
         Ok(())
@@ -628,45 +1169,35 @@ impl Vm {
 
     #[inline]
     fn op_shl(&mut self) -> Result<(), VmError> {
-        use std::convert::TryFrom as _;
-
-        self.internal_bitwise(
-            crate::SHL,
-            || VmError::from(VmErrorKind::Overflow),
-            |a, b| a.checked_shl(u32::try_from(b).ok()?),
-            "<<",
-        )?;
-
+        self.internal_shift(crate::SHL, |a, b| a << b, |a, b| a << b, "<<")?;
         Ok(())
     }
 
     #[inline]
     fn op_shr(&mut self) -> Result<(), VmError> {
-        self.internal_infallible_bitwise(crate::SHR, std::ops::Shr::shr, ">>")?;
+        self.internal_shift(crate::SHR, |a, b| a >> b, |a, b| a >> b, ">>")?;
         Ok(())
     }
 
     #[inline]
     fn op_shl_assign(&mut self, offset: usize) -> Result<(), VmError> {
-        use std::convert::TryFrom as _;
-
-        self.internal_bitwise_assign(
+        self.internal_shift_assign(
             offset,
             crate::SHL_ASSIGN,
-            || VmError::from(VmErrorKind::Overflow),
-            |a, b| a.checked_shl(u32::try_from(b).ok()?),
+            |a, b| a << b,
+            |a, b| a << b,
             "<<=",
         )?;
-
         Ok(())
     }
 
     #[inline]
     fn op_shr_assign(&mut self, offset: usize) -> Result<(), VmError> {
-        self.internal_infallible_bitwise_assign(
+        self.internal_shift_assign(
             offset,
             crate::SHR_ASSIGN,
-            std::ops::ShrAssign::shr_assign,
+            |a, b| a >> b,
+            |a, b| a >> b,
             ">>=",
         )?;
         Ok(())
@@ -677,8 +1208,8 @@ impl Vm {
         self.internal_num_assign(
             offset,
             crate::ADD_ASSIGN,
-            || VmError::from(VmErrorKind::Overflow),
-            i64::checked_add,
+            |_, _| VmError::from(VmErrorKind::Overflow),
+            IntegerOverflow::add,
             std::ops::Add::add,
             "+=",
         )?;
@@ -690,8 +1221,8 @@ impl Vm {
         self.internal_num_assign(
             offset,
             crate::SUB_ASSIGN,
-            || VmError::from(VmErrorKind::Underflow),
-            i64::checked_sub,
+            |_, _| VmError::from(VmErrorKind::Underflow),
+            IntegerOverflow::sub,
             std::ops::Sub::sub,
             "-=",
         )?;
@@ -703,8 +1234,8 @@ impl Vm {
         self.internal_num_assign(
             offset,
             crate::MUL_ASSIGN,
-            || VmError::from(VmErrorKind::Overflow),
-            i64::checked_mul,
+            |_, _| VmError::from(VmErrorKind::Overflow),
+            IntegerOverflow::mul,
             std::ops::Mul::mul,
             "*=",
         )?;
@@ -716,8 +1247,8 @@ impl Vm {
         self.internal_num_assign(
             offset,
             crate::DIV_ASSIGN,
-            || VmError::from(VmErrorKind::DivideByZero),
-            i64::checked_div,
+            Self::division_error,
+            IntegerOverflow::div,
             std::ops::Div::div,
             "/=",
         )?;
@@ -729,8 +1260,8 @@ impl Vm {
         self.internal_num_assign(
             offset,
             crate::REM_ASSIGN,
-            || VmError::from(VmErrorKind::DivideByZero),
-            i64::checked_rem,
+            Self::division_error,
+            IntegerOverflow::rem,
             std::ops::Rem::rem,
             "%=",
         )?;
@@ -744,18 +1275,36 @@ impl Vm {
         let index = self.stack.pop()?;
         let value = self.stack.pop()?;
 
+        match &index {
+            Value::Integer(integer) => {
+                if Self::try_integer_index_set(&target, *integer, &value)? {
+                    return Ok(());
+                }
+            }
+            Value::Any(..) => {
+                if Self::try_range_index_set(&target, &index, &value)? {
+                    return Ok(());
+                }
+            }
+            _ => (),
+        }
+
         // This is a useful pattern.
         #[allow(clippy::never_loop)]
         loop {
             // NB: local storage for string.
             let local_field;
+            let mut static_field = None;
 
             let field = match &index {
                 Value::String(string) => {
                     local_field = string.borrow_ref()?;
                     local_field.as_str()
                 }
-                Value::StaticString(string) => string.as_ref(),
+                Value::StaticString(string) => {
+                    static_field = Some(&**string);
+                    string.as_ref()
+                }
                 _ => break,
             };
 
@@ -768,7 +1317,12 @@ impl Vm {
                 Value::TypedObject(typed_object) => {
                     let mut typed_object = typed_object.borrow_mut()?;
 
-                    if let Some(v) = typed_object.object.get_mut(field) {
+                    let v = match static_field {
+                        Some(key) => object_key::get_mut(&mut typed_object.object, key),
+                        None => typed_object.object.get_mut(field),
+                    };
+
+                    if let Some(v) = v {
                         *v = value;
                         return Ok(());
                     }
@@ -781,7 +1335,12 @@ impl Vm {
                 Value::VariantObject(variant_object) => {
                     let mut variant_object = variant_object.borrow_mut()?;
 
-                    if let Some(v) = variant_object.object.get_mut(field) {
+                    let v = match static_field {
+                        Some(key) => object_key::get_mut(&mut variant_object.object, key),
+                        None => variant_object.object.get_mut(field),
+                    };
+
+                    if let Some(v) = v {
                         *v = value;
                         return Ok(());
                     }
@@ -803,22 +1362,21 @@ impl Vm {
             }));
         }
 
+        // NB: an index set doesn't produce a value, so discard whatever the
+        // protocol function returned.
+        self.stack.pop()?;
         Ok(())
     }
 
     #[inline]
     fn op_return(&mut self) -> Result<bool, VmError> {
         let return_value = self.stack.pop()?;
-        let exit = self.pop_call_frame()?;
-        self.stack.push(return_value);
-        Ok(exit)
+        self.pop_call_frame(return_value)
     }
 
     #[inline]
     fn op_return_unit(&mut self) -> Result<bool, VmError> {
-        let exit = self.pop_call_frame()?;
-        self.stack.push(());
-        Ok(exit)
+        self.pop_call_frame(Value::Unit)
     }
 
     #[inline]
@@ -918,17 +1476,139 @@ impl Vm {
             _ => return Ok(None),
         };
 
-        let value = match value {
-            Some(value) => value,
-            None => {
-                return Err(VmError::from(VmErrorKind::MissingIndex {
+        let value = match value {
+            Some(value) => value,
+            None => {
+                return Err(VmError::from(VmErrorKind::MissingIndex {
+                    target: target.type_info()?,
+                    index: Integer::Usize(index),
+                }));
+            }
+        };
+
+        Ok(Some(value))
+    }
+
+    /// Resolve an integer index on the given target.
+    ///
+    /// Negative indexes on vectors and tuples address elements relative to
+    /// their end, so that `-1` is the last element. Returns `None` if the index
+    /// can't be resolved.
+    fn resolve_index(target: &Value, index: i64) -> Result<Option<usize>, VmError> {
+        use std::convert::TryFrom as _;
+
+        if index >= 0 {
+            return Ok(usize::try_from(index).ok());
+        }
+
+        let len = match target {
+            Value::Vec(vec) => vec.borrow_ref()?.len(),
+            Value::Tuple(tuple) => tuple.borrow_ref()?.len(),
+            _ => return Ok(None),
+        };
+
+        Ok(resolve_index(len, index))
+    }
+
+    /// Resolve the bounds of a slice into a sequence with the given length.
+    ///
+    /// Negative bounds are relative to the end of the sequence.
+    fn resolve_slice(
+        target: &Value,
+        len: usize,
+        start: i64,
+        end: i64,
+    ) -> Result<std::ops::Range<usize>, VmError> {
+        match (resolve_index(len, start), resolve_index(len, end)) {
+            (Some(s), Some(e)) if s <= e && e <= len => Ok(s..e),
+            _ => Err(VmError::from(VmErrorKind::SliceOutOfBounds {
+                target: target.type_info()?,
+                start,
+                end,
+                len,
+            })),
+        }
+    }
+
+    /// Get the bounds of a range used as an index.
+    fn range_bounds(index: &Value) -> Result<Option<(i64, i64)>, VmError> {
+        let any = match index {
+            Value::Any(any) => any.borrow_ref()?,
+            _ => return Ok(None),
+        };
+
+        Ok(any
+            .downcast_borrow_ref::<crate::modules::iter::Range>()
+            .map(|range| range.bounds()))
+    }
+
+    /// Implementation of getting a slice of a vector, using a range as an
+    /// index.
+    fn try_range_index_get(target: &Value, index: &Value) -> Result<Option<Value>, VmError> {
+        let vec = match target {
+            Value::Vec(vec) => vec.borrow_ref()?,
+            _ => return Ok(None),
+        };
+
+        let (start, end) = match Self::range_bounds(index)? {
+            Some(bounds) => bounds,
+            None => return Ok(None),
+        };
+
+        let range = Self::resolve_slice(target, vec.len(), start, end)?;
+        Ok(Some(Value::vec(vec[range].to_vec())))
+    }
+
+    /// Implementation of setting an integer index on vectors and tuples.
+    fn try_integer_index_set(target: &Value, index: i64, value: &Value) -> Result<bool, VmError> {
+        match target {
+            Value::Vec(..) | Value::Tuple(..) => (),
+            _ => return Ok(false),
+        }
+
+        if let Some(resolved) = Self::resolve_index(target, index)? {
+            if Self::try_tuple_like_index_set(target, resolved, value.clone())? {
+                return Ok(true);
+            }
+        }
+
+        Err(VmError::from(VmErrorKind::MissingIndex {
+            target: target.type_info()?,
+            index: Integer::I64(index),
+        }))
+    }
+
+    /// Implementation of slice assignment, which replaces the elements of a
+    /// vector in the given range with the elements of another sequence.
+    fn try_range_index_set(target: &Value, index: &Value, value: &Value) -> Result<bool, VmError> {
+        let vec = match target {
+            Value::Vec(vec) => vec,
+            _ => return Ok(false),
+        };
+
+        let (start, end) = match Self::range_bounds(index)? {
+            Some(bounds) => bounds,
+            None => return Ok(false),
+        };
+
+        // NB: copy the values first, in case we are assigning a vector to a
+        // slice of itself.
+        let values = match value {
+            Value::Vec(values) => values.borrow_ref()?.clone(),
+            Value::Tuple(values) => values.borrow_ref()?.to_vec(),
+            actual => {
+                return Err(VmError::from(VmErrorKind::UnsupportedIndexSet {
                     target: target.type_info()?,
-                    index: Integer::Usize(index),
+                    index: index.type_info()?,
+                    value: actual.type_info()?,
                 }));
             }
         };
 
-        Ok(Some(value))
+        let mut vec = vec.borrow_mut()?;
+        let range = Self::resolve_slice(target, vec.len(), start, end)?;
+        vec.splice(range, values);
+        Ok(true)
     }
 
     /// Implementation of getting a string index on an object-like type.
@@ -1028,15 +1708,13 @@ impl Vm {
                         return Ok(());
                     }
                 }
-                Value::Integer(index) => {
-                    use std::convert::TryInto as _;
-
-                    let index = match (*index).try_into() {
-                        Ok(index) => index,
-                        Err(..) => {
+                Value::Integer(integer) if !matches!(target, Value::Any(..)) => {
+                    let index = match Self::resolve_index(&target, *integer)? {
+                        Some(index) => index,
+                        None => {
                             return Err(VmError::from(VmErrorKind::MissingIndex {
                                 target: target.type_info()?,
-                                index: Integer::I64(*index),
+                                index: Integer::I64(*integer),
                             }));
                         }
                     };
@@ -1046,8 +1724,16 @@ impl Vm {
                         return Ok(());
                     }
                 }
-                _ => break,
+                Value::Any(..) => {
+                    if let Some(value) = Self::try_range_index_get(&target, &index)? {
+                        self.stack.push(value);
+                        return Ok(());
+                    }
+                }
+                _ => (),
             };
+
+            break;
         }
 
         if !self.call_instance_fn(&target, crate::INDEX_GET, (&index,))? {
@@ -1117,11 +1803,12 @@ impl Vm {
             Value::Object(object) => {
                 let object = object.borrow_ref()?;
 
-                match object.get(&***index).cloned() {
+                match object_key::get(&object, index).cloned() {
                     Some(value) => Some(value),
                     None => {
-                        return Err(VmError::from(VmErrorKind::ObjectIndexMissing {
-                            slot: string_slot,
+                        return Err(VmError::from(VmErrorKind::MissingField {
+                            target: target.type_info()?,
+                            field: index.as_str().to_owned(),
                         }));
                     }
                 }
@@ -1129,11 +1816,12 @@ impl Vm {
             Value::TypedObject(typed_object) => {
                 let typed_object = typed_object.borrow_ref()?;
 
-                match typed_object.object.get(&***index).cloned() {
+                match object_key::get(&typed_object.object, index).cloned() {
                     Some(value) => Some(value),
                     None => {
-                        return Err(VmError::from(VmErrorKind::ObjectIndexMissing {
-                            slot: string_slot,
+                        return Err(VmError::from(VmErrorKind::MissingField {
+                            target: target.type_info()?,
+                            field: index.as_str().to_owned(),
                         }));
                     }
                 }
@@ -1141,11 +1829,12 @@ impl Vm {
             Value::VariantObject(variant_object) => {
                 let variant_object = variant_object.borrow_ref()?;
 
-                match variant_object.object.get(&***index).cloned() {
+                match object_key::get(&variant_object.object, index).cloned() {
                     Some(value) => Some(value),
                     None => {
-                        return Err(VmError::from(VmErrorKind::ObjectIndexMissing {
-                            slot: string_slot,
+                        return Err(VmError::from(VmErrorKind::MissingField {
+                            target: target.type_info()?,
+                            field: index.as_str().to_owned(),
                         }));
                     }
                 }
@@ -1206,7 +1895,7 @@ impl Vm {
             .lookup_object_keys(slot)
             .ok_or_else(|| VmError::from(VmErrorKind::MissingStaticObjectKeys { slot }))?;
 
-        let mut object = Object::with_capacity(keys.len());
+        let mut object = object_key::with_capacity(keys.len());
         let values = self.stack.drain_stack_top(keys.len())?;
 
         for (key, value) in keys.iter().zip(values) {
@@ -1217,6 +1906,21 @@ impl Vm {
         Ok(())
     }
 
+    /// Operation to allocate an object out of pairs of keys and values.
+    #[inline]
+    fn op_dynamic_object(&mut self, count: usize) -> Result<(), VmError> {
+        let mut object = object_key::with_capacity(count);
+        let mut it = self.stack.drain_stack_top(count * 2)?;
+
+        while let (Some(key), Some(value)) = (it.next(), it.next()) {
+            object.insert(String::from_value(key)?, value);
+        }
+
+        drop(it);
+        self.stack.push(Shared::new(object));
+        Ok(())
+    }
+
     /// Operation to allocate an object.
     #[inline]
     fn op_typed_object(&mut self, hash: Hash, slot: usize) -> Result<(), VmError> {
@@ -1225,7 +1929,7 @@ impl Vm {
             .lookup_object_keys(slot)
             .ok_or_else(|| VmError::from(VmErrorKind::MissingStaticObjectKeys { slot }))?;
 
-        let mut object = Object::with_capacity(keys.len());
+        let mut object = object_key::with_capacity(keys.len());
 
         let values = self.stack.drain_stack_top(keys.len())?;
 
@@ -1250,7 +1954,7 @@ impl Vm {
             .lookup_object_keys(slot)
             .ok_or_else(|| VmError::from(VmErrorKind::MissingStaticObjectKeys { slot }))?;
 
-        let mut object = Object::with_capacity(keys.len());
+        let mut object = object_key::with_capacity(keys.len());
         let values = self.stack.drain_stack_top(keys.len())?;
 
         for (key, value) in keys.iter().zip(values) {
@@ -1280,6 +1984,28 @@ impl Vm {
         Ok(())
     }
 
+    #[inline]
+    fn op_static(&mut self, slot: usize) -> Result<(), VmError> {
+        let value = self.unit.lookup_static_value(slot)?;
+
+        if let Some(Some(loaded)) = self.statics.get(slot) {
+            if value.is_unmodified(loaded) {
+                self.stack.push(loaded.clone());
+                return Ok(());
+            }
+        }
+
+        let loaded = value.to_value();
+
+        if self.statics.len() <= slot {
+            self.statics.resize(slot + 1, None);
+        }
+
+        self.statics[slot] = Some(loaded.clone());
+        self.stack.push(loaded);
+        Ok(())
+    }
+
     /// Optimize operation to perform string concatenation.
     #[inline]
     fn op_string_concat(&mut self, len: usize, size_hint: usize) -> Result<(), VmError> {
@@ -1299,8 +2025,7 @@ impl Vm {
                     buf.push_str(buffer.format(integer));
                 }
                 Value::Float(float) => {
-                    let mut buffer = ryu::Buffer::new();
-                    buf.push_str(buffer.format(float));
+                    self.context.float_display().format(float, &mut buf);
                 }
                 actual => {
                     let b = Shared::new(std::mem::take(&mut buf));
@@ -1376,7 +2101,28 @@ impl Vm {
             }
         };
 
-        Ok(a.value_type()? == hash)
+        let value_type = a.value_type()?;
+
+        if value_type == hash {
+            return Ok(true);
+        }
+
+        let interface = match self.context.lookup_interface(hash) {
+            Some(interface) => interface,
+            None => return Ok(false),
+        };
+
+        Ok(interface
+            .protocols
+            .iter()
+            .all(|protocol| self.implements(value_type, *protocol)))
+    }
+
+    /// Test if the given type implements the given protocol, through an
+    /// instance function declared in either the unit or the context.
+    fn implements(&self, value_type: Type, protocol: Protocol) -> bool {
+        let hash = Hash::instance_function(value_type, protocol.hash);
+        self.unit.lookup(hash).is_some() || self.context.lookup(hash).is_some()
     }
 
     #[inline]
@@ -1393,6 +2139,86 @@ impl Vm {
         Ok(())
     }
 
+    /// Convert a primitive value into another primitive type, following the
+    /// semantics of `as` casts in Rust.
+    fn op_as(&mut self) -> Result<(), VmError> {
+        let b = self.stack.pop()?;
+        let a = self.stack.pop()?;
+
+        let hash = match b {
+            Value::Type(hash) => hash,
+            b => {
+                return Err(VmError::from(VmErrorKind::UnsupportedAs {
+                    value: a.type_info()?,
+                    target: b.type_info()?,
+                }));
+            }
+        };
+
+        let value = if hash == crate::INTEGER_TYPE.hash {
+            match a {
+                Value::Integer(n) => Some(Value::Integer(n)),
+                Value::Float(n) => Some(Value::Integer(n as i64)),
+                Value::Byte(b) => Some(Value::Integer(b as i64)),
+                Value::Char(c) => Some(Value::Integer(c as i64)),
+                Value::Bool(b) => Some(Value::Integer(b as i64)),
+                _ => None,
+            }
+        } else if hash == crate::FLOAT_TYPE.hash {
+            match a {
+                Value::Integer(n) => Some(Value::Float(n as f64)),
+                Value::Float(n) => Some(Value::Float(n)),
+                Value::Byte(b) => Some(Value::Float(b as f64)),
+                _ => None,
+            }
+        } else if hash == crate::BYTE_TYPE.hash {
+            match a {
+                Value::Integer(n) => Some(Value::Byte(n as u8)),
+                Value::Float(n) => Some(Value::Byte(n as u8)),
+                Value::Byte(b) => Some(Value::Byte(b)),
+                Value::Char(c) => Some(Value::Byte(c as u8)),
+                Value::Bool(b) => Some(Value::Byte(b as u8)),
+                _ => None,
+            }
+        } else if hash == crate::CHAR_TYPE.hash {
+            match a {
+                Value::Byte(b) => Some(Value::Char(b as char)),
+                Value::Char(c) => Some(Value::Char(c)),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        let value = match value {
+            Some(value) => value,
+            None => {
+                return Err(VmError::from(VmErrorKind::UnsupportedAs {
+                    value: a.type_info()?,
+                    target: Self::cast_target_type_info(hash),
+                }));
+            }
+        };
+
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// Get the type information of the target of an `as` cast.
+    fn cast_target_type_info(hash: Hash) -> TypeInfo {
+        let types = [
+            crate::INTEGER_TYPE,
+            crate::FLOAT_TYPE,
+            crate::BYTE_TYPE,
+            crate::CHAR_TYPE,
+        ];
+
+        match types.iter().find(|ty| ty.hash == hash) {
+            Some(ty) => TypeInfo::StaticType(ty),
+            None => TypeInfo::Hash(hash),
+        }
+    }
+
     #[inline]
     fn op_is_unit(&mut self) -> Result<(), VmError> {
         let value = self.stack.pop()?;
@@ -1492,6 +2318,70 @@ impl Vm {
         Ok(())
     }
 
+    #[inline]
+    fn op_range_next(&mut self, counter: usize, end: usize, binding: usize) -> Result<(), VmError> {
+        let current = match (self.stack.at_offset(counter)?, self.stack.at_offset(end)?) {
+            (Value::Integer(current), Value::Integer(end)) => {
+                if current < end {
+                    Some(*current)
+                } else {
+                    None
+                }
+            }
+            (start, end) => {
+                return Err(VmError::from(VmErrorKind::UnsupportedRangeBounds {
+                    start: start.type_info()?,
+                    end: end.type_info()?,
+                }));
+            }
+        };
+
+        if let Some(current) = current {
+            // NB: can't overflow since the counter is smaller than the end.
+            *self.stack.at_offset_mut(counter)? = Value::Integer(current + 1);
+            *self.stack.at_offset_mut(binding)? = Value::Integer(current);
+        }
+
+        self.stack.push(current.is_some());
+        Ok(())
+    }
+
+    #[inline]
+    fn op_match_byte_range(&mut self, start: u8, end: u8) -> Result<(), VmError> {
+        let value = self.stack.pop()?;
+
+        self.stack.push(match value {
+            Value::Byte(actual) => start <= actual && actual <= end,
+            _ => false,
+        });
+
+        Ok(())
+    }
+
+    #[inline]
+    fn op_match_character_range(&mut self, start: char, end: char) -> Result<(), VmError> {
+        let value = self.stack.pop()?;
+
+        self.stack.push(match value {
+            Value::Char(actual) => start <= actual && actual <= end,
+            _ => false,
+        });
+
+        Ok(())
+    }
+
+    #[inline]
+    fn op_match_integer_range(&mut self, start: i64, end: i64) -> Result<(), VmError> {
+        let value = self.stack.pop()?;
+
+        self.stack.push(match value {
+            Value::Integer(actual) => start <= actual && actual <= end,
+            _ => false,
+        });
+
+        Ok(())
+    }
+
     /// Test if the top of stack is equal to the string at the given static
     /// string location.
     #[inline]
@@ -1667,46 +2557,57 @@ impl Vm {
         Ok(None)
     }
 
-    /// Construct a future from calling an async function.
-    fn call_generator_fn(&mut self, offset: usize, args: usize) -> Result<(), VmError> {
-        let stack = self.stack.drain_stack_top(args)?.collect::<Stack>();
-        let mut vm = Self::new_with_stack(self.context.clone(), self.unit.clone(), stack);
-        vm.ip = offset;
-        self.stack.push(Generator::new(vm));
-        Ok(())
-    }
-
-    /// Construct a stream from calling a function.
-    fn call_stream_fn(&mut self, offset: usize, args: usize) -> Result<(), VmError> {
-        let stack = self.stack.drain_stack_top(args)?.collect::<Stack>();
-        let mut vm = Self::new_with_stack(self.context.clone(), self.unit.clone(), stack);
+    /// Construct a virtual machine which runs the function at the given offset
+    /// on a stack of its own, taking the `args` values on top of the current
+    /// stack as arguments.
+    ///
+    /// The new stack has the same limit as the current one.
+    fn new_fn_vm(&mut self, offset: usize, args: usize, stack: usize) -> Result<Self, VmError> {
+        let mut new_stack = self.stack.drain_stack_top(args)?.collect::<Stack>();
+        new_stack.set_limit(self.stack.limit());
+        new_stack.reserve_frame(stack)?;
+        let mut vm = Self::new_with_stack(self.context.clone(), self.unit.clone(), new_stack);
         vm.ip = offset;
-        self.stack.push(Stream::new(vm));
-        Ok(())
+        vm.integer_overflow = self.integer_overflow;
+        Ok(vm)
     }
 
-    /// Construct a future from calling a function.
-    fn call_async_fn(&mut self, offset: usize, args: usize) -> Result<(), VmError> {
-        let stack = self.stack.drain_stack_top(args)?.collect::<Stack>();
-        let mut vm = Self::new_with_stack(self.context.clone(), self.unit.clone(), stack);
-        vm.ip = offset;
-        self.stack.push(Future::new(vm.async_complete()));
-        Ok(())
-    }
+    /// Call the function with the given id in the function table of the unit,
+    /// handling its return value as specified by `ret`.
+    fn call_offset_fn(
+        &mut self,
+        id: usize,
+        call: Call,
+        args: usize,
+        ret: Return,
+    ) -> Result<(), VmError> {
+        let offset = self.unit.function_offset(id)?;
+        let stack = self.unit.function_stack(id)?;
+
+        crate::event_log::record_call(
+            false,
+            || match self.unit.debug_info().and_then(|d| d.function_at(offset)) {
+                Some((_, signature)) => signature.path.to_string(),
+                None => format!("<function at {}>", offset),
+            },
+            Self::top_args(&self.stack, args),
+        );
 
-    fn call_offset_fn(&mut self, offset: usize, call: Call, args: usize) -> Result<(), VmError> {
         match call {
             Call::Async => {
-                self.call_async_fn(offset, args)?;
+                let vm = self.new_fn_vm(offset, args, stack)?;
+                self.push_return(Value::from(Future::new(vm.async_complete())), ret)?;
             }
             Call::Stream => {
-                self.call_stream_fn(offset, args)?;
+                let vm = self.new_fn_vm(offset, args, stack)?;
+                self.push_return(Value::from(Stream::new(vm)), ret)?;
             }
             Call::Generator => {
-                self.call_generator_fn(offset, args)?;
+                let vm = self.new_fn_vm(offset, args, stack)?;
+                self.push_return(Value::from(Generator::new(vm)), ret)?;
             }
             Call::Immediate => {
-                self.push_call_frame(offset, args)?;
+                self.push_call_frame_with(offset, args, stack, ret)?;
             }
         }
 
@@ -1716,13 +2617,9 @@ impl Vm {
     fn op_fn(&mut self, hash: Hash) -> Result<(), VmError> {
         let function = match self.unit.lookup(hash) {
             Some(info) => match info {
-                UnitFn::Offset { offset, call, args } => Function::from_offset(
-                    self.context.clone(),
-                    self.unit.clone(),
-                    offset,
-                    call,
-                    args,
-                ),
+                UnitFn::Offset { id, call, args } => {
+                    Function::from_offset(self.context.clone(), self.unit.clone(), id, call, args)
+                }
                 UnitFn::Tuple { hash, args } => Function::from_tuple(hash, args),
                 UnitFn::TupleVariant {
                     enum_hash,
@@ -1736,7 +2633,7 @@ impl Vm {
                     .lookup(hash)
                     .ok_or_else(|| VmError::from(VmErrorKind::MissingFunction { hash }))?;
 
-                Function::from_handler(handler.clone())
+                Function::from_handler(hash, handler.clone())
             }
         };
 
@@ -1751,8 +2648,8 @@ impl Vm {
             .lookup(hash)
             .ok_or_else(|| VmError::from(VmErrorKind::MissingFunction { hash }))?;
 
-        let (offset, call, args) = match info {
-            UnitFn::Offset { offset, call, args } => (offset, call, args),
+        let (id, call, args) = match info {
+            UnitFn::Offset { id, call, args } => (id, call, args),
             _ => return Err(VmError::from(VmErrorKind::MissingFunction { hash })),
         };
 
@@ -1762,7 +2659,7 @@ impl Vm {
         let function = Function::from_closure(
             self.context.clone(),
             self.unit.clone(),
-            offset,
+            id,
             call,
             args,
             environment,
@@ -1777,12 +2674,12 @@ impl Vm {
         match self.unit.lookup(hash) {
             Some(info) => match info {
                 UnitFn::Offset {
-                    offset,
+                    id,
                     call,
                     args: expected,
                 } => {
                     Self::check_args(args, expected)?;
-                    self.call_offset_fn(offset, call, args)?;
+                    self.call_offset_fn(id, call, args, Return::Push)?;
                 }
                 UnitFn::Tuple {
                     hash,
@@ -1810,7 +2707,15 @@ impl Vm {
                     .lookup(hash)
                     .ok_or_else(|| VmError::from(VmErrorKind::MissingFunction { hash }))?;
 
-                handler(&mut self.stack, args)?;
+                Self::call_handler(
+                    &self.context,
+                    &self.unit,
+                    self.ip,
+                    &mut self.stack,
+                    hash,
+                    &**handler,
+                    args,
+                )?;
             }
         }
 
@@ -1831,12 +2736,12 @@ impl Vm {
         match self.unit.lookup(hash) {
             Some(info) => match info {
                 UnitFn::Offset {
-                    offset,
+                    id,
                     call,
                     args: expected,
                 } => {
                     Self::check_args(args, expected)?;
-                    self.call_offset_fn(offset, call, args)?;
+                    self.call_offset_fn(id, call, args, Return::Push)?;
                 }
                 _ => {
                     return Err(VmError::from(VmErrorKind::MissingInstanceFunction {
@@ -1856,7 +2761,15 @@ impl Vm {
                     }
                 };
 
-                handler(&mut self.stack, args)?;
+                Self::call_handler(
+                    &self.context,
+                    &self.unit,
+                    self.ip,
+                    &mut self.stack,
+                    hash,
+                    &**handler,
+                    args,
+                )?;
             }
         }
 
@@ -1890,19 +2803,59 @@ impl Vm {
     }
 
     /// Evaluate a single instruction.
-    pub(crate) fn run_for(&mut self, mut limit: Option<usize>) -> Result<VmHalt, VmError> {
+    pub(crate) fn run_for(&mut self, limit: Option<usize>) -> Result<VmHalt, VmError> {
+        if self.poisoned {
+            return Err(VmError::from(VmErrorKind::Poisoned));
+        }
+
+        let context = self.context.clone();
+
+        crate::finalizer::with_context(&context, || {
+            // NB: borrows are only tracked if there's debug info to make sense
+            // of where they happened.
+            if self.unit.debug_info().is_none() {
+                return self.run_instructions(limit, false);
+            }
+
+            let previous = access::set_borrow_site(None);
+            let result = self.run_instructions(limit, true);
+            access::set_borrow_site(previous);
+            result
+        })
+    }
+
+    fn run_instructions(
+        &mut self,
+        mut limit: Option<usize>,
+        track_borrows: bool,
+    ) -> Result<VmHalt, VmError> {
         loop {
             let inst = *self
                 .unit
                 .instruction_at(self.ip)
                 .ok_or_else(|| VmError::from(VmErrorKind::IpOutOfBounds))?;
 
+            if track_borrows {
+                access::set_borrow_site(Some(BorrowSite {
+                    unit: Arc::as_ptr(&self.unit) as usize,
+                    ip: self.ip,
+                }));
+            }
+
             log::trace!("{}: {}", self.ip, inst);
 
+            #[cfg(feature = "metrics")]
+            {
+                self.executed += 1;
+            }
+
             match inst {
                 Inst::Not => {
                     self.op_not()?;
                 }
+                Inst::Neg => {
+                    self.op_neg()?;
+                }
                 Inst::Add => {
                     self.op_add()?;
                 }
@@ -1991,8 +2944,8 @@ impl Vm {
                     // NB: the future itself will advance the virtual machine.
                     return Ok(VmHalt::Awaited(Awaited::Future(future)));
                 }
-                Inst::Select { len } => {
-                    if let Some(select) = self.op_select(len)? {
+                Inst::Select { len, default } => {
+                    if let Some(select) = self.op_select(len, default)? {
                         // NB: the future itself will advance the virtual machine.
                         return Ok(VmHalt::Awaited(Awaited::Select(select)));
                     }
@@ -2018,6 +2971,13 @@ impl Vm {
                 Inst::Copy { offset } => {
                     self.op_copy(offset)?;
                 }
+                Inst::RangeNext {
+                    counter,
+                    end,
+                    binding,
+                } => {
+                    self.op_range_next(counter, end, binding)?;
+                }
                 Inst::Drop { offset } => {
                     self.op_drop(offset)?;
                 }
@@ -2057,6 +3017,12 @@ impl Vm {
                 Inst::JumpIfBranch { branch, offset } => {
                     self.op_jump_if_branch(branch, offset)?;
                 }
+                Inst::PeekAndJumpIf { offset } => {
+                    self.op_peek_and_jump_if(true, offset)?;
+                }
+                Inst::PeekAndJumpIfNot { offset } => {
+                    self.op_peek_and_jump_if(false, offset)?;
+                }
                 Inst::Unit => {
                     self.stack.push(Value::Unit);
                 }
@@ -2069,12 +3035,21 @@ impl Vm {
                 Inst::Tuple { count } => {
                     self.op_tuple(count)?;
                 }
+                Inst::HashMap { count } => {
+                    self.op_hash_map(count)?;
+                }
+                Inst::HashSet { count } => {
+                    self.op_hash_set(count)?;
+                }
                 Inst::PushTuple => {
                     self.op_push_tuple()?;
                 }
                 Inst::Object { slot } => {
                     self.op_object(slot)?;
                 }
+                Inst::DynamicObject { count } => {
+                    self.op_dynamic_object(count)?;
+                }
                 Inst::TypedObject { hash, slot } => {
                     self.op_typed_object(hash, slot)?;
                 }
@@ -2100,6 +3075,9 @@ impl Vm {
                 Inst::Bytes { slot } => {
                     self.op_bytes(slot)?;
                 }
+                Inst::Static { slot } => {
+                    self.op_static(slot)?;
+                }
                 Inst::StringConcat { len, size_hint } => {
                     self.op_string_concat(len, size_hint)?;
                 }
@@ -2109,6 +3087,9 @@ impl Vm {
                 Inst::IsNot => {
                     self.op_is_not()?;
                 }
+                Inst::As => {
+                    self.op_as()?;
+                }
                 Inst::IsUnit => {
                     self.op_is_unit()?;
                 }
@@ -2163,6 +3144,15 @@ impl Vm {
                 Inst::EqInteger { integer } => {
                     self.op_eq_integer(integer)?;
                 }
+                Inst::MatchByteRange { start, end } => {
+                    self.op_match_byte_range(start, end)?;
+                }
+                Inst::MatchCharacterRange { start, end } => {
+                    self.op_match_character_range(start, end)?;
+                }
+                Inst::MatchIntegerRange { start, end } => {
+                    self.op_match_integer_range(start, end)?;
+                }
                 Inst::EqStaticString { slot } => {
                     self.op_eq_static_string(slot)?;
                 }
@@ -2189,6 +3179,9 @@ impl Vm {
                     self.stack.push(Value::Unit);
                     return Ok(VmHalt::Yielded);
                 }
+                Inst::Coverage { counter } => {
+                    self.unit.coverage().hit(counter);
+                }
                 Inst::Panic { reason } => {
                     return Err(VmError::from(VmErrorKind::Panic {
                         reason: Panic::from(reason),
@@ -2219,16 +3212,26 @@ impl Vm {
     ) -> Result<(), VmError>
     where
         H: IntoHash,
-        E: Copy + FnOnce() -> VmError,
-        I: FnOnce(i64, i64) -> Option<i64>,
+        E: Copy + FnOnce(i64, i64) -> VmError,
+        I: FnOnce(IntegerOverflow, i64, i64) -> Option<i64>,
         F: FnOnce(f64, f64) -> f64,
     {
+        let integer_overflow = self.integer_overflow;
         let rhs = self.stack.pop()?;
         let lhs = self.stack.at_offset_mut(offset)?;
 
+        // NB: an integer operand is promoted to float if the other is a float.
+        if let (Value::Integer(n), Value::Float(rhs)) = (&*lhs, &rhs) {
+            *lhs = Value::Float(float_op(*n as f64, *rhs));
+            return Ok(());
+        }
+
         let (lhs, rhs) = match (lhs, rhs) {
             (Value::Integer(lhs), Value::Integer(rhs)) => {
-                let out = integer_op(*lhs, rhs).ok_or_else(error)?;
+                let out = match integer_op(integer_overflow, *lhs, rhs) {
+                    Some(out) => out,
+                    None => return Err(error(*lhs, rhs)),
+                };
                 *lhs = out;
                 return Ok(());
             }
@@ -2237,10 +3240,15 @@ impl Vm {
                 *lhs = out;
                 return Ok(());
             }
+            (Value::Float(lhs), Value::Integer(rhs)) => {
+                let out = float_op(*lhs, rhs as f64);
+                *lhs = out;
+                return Ok(());
+            }
             (lhs, rhs) => (lhs.clone(), rhs),
         };
 
-        if !self.call_instance_fn(&lhs, hash, (&rhs,))? {
+        if !self.call_assign_fn(&lhs, hash, &rhs)? {
             return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
                 op,
                 lhs: lhs.type_info()?,
@@ -2248,7 +3256,6 @@ impl Vm {
             }));
         }
 
-        self.stack.pop()?;
         Ok(())
     }
 
@@ -2263,8 +3270,8 @@ impl Vm {
     ) -> Result<(), VmError>
     where
         H: IntoHash,
-        E: Copy + FnOnce() -> VmError,
-        I: FnOnce(i64, i64) -> Option<i64>,
+        E: Copy + FnOnce(i64, i64) -> VmError,
+        I: FnOnce(IntegerOverflow, i64, i64) -> Option<i64>,
         F: FnOnce(f64, f64) -> f64,
     {
         let rhs = self.stack.pop()?;
@@ -2272,13 +3279,26 @@ impl Vm {
 
         let (lhs, rhs) = match (lhs, rhs) {
             (Value::Integer(lhs), Value::Integer(rhs)) => {
-                self.stack.push(integer_op(lhs, rhs).ok_or_else(error)?);
+                let out = match integer_op(self.integer_overflow, lhs, rhs) {
+                    Some(out) => out,
+                    None => return Err(error(lhs, rhs)),
+                };
+
+                self.stack.push(out);
                 return Ok(());
             }
             (Value::Float(lhs), Value::Float(rhs)) => {
                 self.stack.push(float_op(lhs, rhs));
                 return Ok(());
             }
+            (Value::Integer(lhs), Value::Float(rhs)) => {
+                self.stack.push(float_op(lhs as f64, rhs));
+                return Ok(());
+            }
+            (Value::Float(lhs), Value::Integer(rhs)) => {
+                self.stack.push(float_op(lhs, rhs as f64));
+                return Ok(());
+            }
             (lhs, rhs) => (lhs.clone(), rhs),
         };
 
@@ -2293,11 +3313,15 @@ impl Vm {
         Ok(())
     }
 
-    /// Internal impl of a numeric operation.
+    /// Internal impl of a bitwise operation.
+    ///
+    /// Bitwise operations on byte strings are performed element-wise, and
+    /// require both operands to have the same length.
     fn internal_infallible_bitwise<H, I>(
         &mut self,
         hash: H,
         integer_op: I,
+        byte_op: fn(u8, u8) -> u8,
         op: &'static str,
     ) -> Result<(), VmError>
     where
@@ -2312,6 +3336,24 @@ impl Vm {
                 self.stack.push(integer_op(lhs, rhs));
                 return Ok(());
             }
+            (Value::Byte(lhs), Value::Byte(rhs)) => {
+                self.stack.push(byte_op(lhs, rhs));
+                return Ok(());
+            }
+            (Value::Bytes(lhs), Value::Bytes(rhs)) => {
+                let lhs = lhs.borrow_ref()?;
+                let rhs = rhs.borrow_ref()?;
+                Self::check_bytes_len(op, &lhs, &rhs)?;
+
+                let bytes = lhs
+                    .iter()
+                    .zip(rhs.iter())
+                    .map(|(a, b)| byte_op(*a, *b))
+                    .collect::<Vec<_>>();
+
+                self.stack.push(Bytes::from_vec(bytes));
+                return Ok(());
+            }
             (lhs, rhs) => (lhs.clone(), rhs),
         };
 
@@ -2331,6 +3373,7 @@ impl Vm {
         offset: usize,
         hash: H,
         integer_op: I,
+        byte_op: fn(&mut u8, u8),
         op: &'static str,
     ) -> Result<(), VmError>
     where
@@ -2345,10 +3388,27 @@ impl Vm {
                 integer_op(lhs, rhs);
                 return Ok(());
             }
+            (Value::Byte(lhs), Value::Byte(rhs)) => {
+                byte_op(lhs, rhs);
+                return Ok(());
+            }
+            (Value::Bytes(lhs), Value::Bytes(rhs)) => {
+                // NB: copy the right-hand side first, since it might be the
+                // same byte string as the left-hand side.
+                let rhs = rhs.borrow_ref()?.to_vec();
+                let mut lhs = lhs.borrow_mut()?;
+                Self::check_bytes_len(op, &lhs, &rhs)?;
+
+                for (a, b) in lhs.iter_mut().zip(rhs) {
+                    byte_op(a, b);
+                }
+
+                return Ok(());
+            }
             (lhs, rhs) => (lhs.clone(), rhs),
         };
 
-        if !self.call_instance_fn(&lhs, hash, (&rhs,))? {
+        if !self.call_assign_fn(&lhs, hash, &rhs)? {
             return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
                 op,
                 lhs: lhs.type_info()?,
@@ -2356,28 +3416,35 @@ impl Vm {
             }));
         }
 
-        self.stack.pop()?;
         Ok(())
     }
 
-    fn internal_bitwise<H, E, I>(
+    /// Internal impl of a shift operation.
+    ///
+    /// The shift amount must be in the range `0..bits`, where `bits` is the
+    /// number of bits in the shifted value. Bits shifted out are discarded.
+    fn internal_shift<H>(
         &mut self,
         hash: H,
-        error: E,
-        integer_op: I,
+        integer_op: fn(i64, u32) -> i64,
+        byte_op: fn(u8, u32) -> u8,
         op: &'static str,
     ) -> Result<(), VmError>
     where
         H: IntoHash,
-        E: FnOnce() -> VmError,
-        I: FnOnce(i64, i64) -> Option<i64>,
     {
         let rhs = self.stack.pop()?;
         let lhs = self.stack.pop()?;
 
         let (lhs, rhs) = match (lhs, rhs) {
             (Value::Integer(lhs), Value::Integer(rhs)) => {
-                self.stack.push(integer_op(lhs, rhs).ok_or_else(error)?);
+                let shift = Self::shift_amount(op, rhs, 64)?;
+                self.stack.push(integer_op(lhs, shift));
+                return Ok(());
+            }
+            (Value::Byte(lhs), Value::Integer(rhs)) => {
+                let shift = Self::shift_amount(op, rhs, 8)?;
+                self.stack.push(byte_op(lhs, shift));
                 return Ok(());
             }
             (lhs, rhs) => (lhs.clone(), rhs),
@@ -2394,32 +3461,35 @@ impl Vm {
         Ok(())
     }
 
-    fn internal_bitwise_assign<H, E, I>(
+    fn internal_shift_assign<H>(
         &mut self,
         offset: usize,
         hash: H,
-        error: E,
-        integer_op: I,
+        integer_op: fn(i64, u32) -> i64,
+        byte_op: fn(u8, u32) -> u8,
         op: &'static str,
     ) -> Result<(), VmError>
     where
         H: IntoHash,
-        E: FnOnce() -> VmError,
-        I: FnOnce(i64, i64) -> Option<i64>,
     {
         let rhs = self.stack.pop()?;
         let lhs = self.stack.at_offset_mut(offset)?;
 
         let (lhs, rhs) = match (lhs, rhs) {
             (Value::Integer(lhs), Value::Integer(rhs)) => {
-                let out = integer_op(*lhs, rhs).ok_or_else(error)?;
-                *lhs = out;
+                let shift = Self::shift_amount(op, rhs, 64)?;
+                *lhs = integer_op(*lhs, shift);
+                return Ok(());
+            }
+            (Value::Byte(lhs), Value::Integer(rhs)) => {
+                let shift = Self::shift_amount(op, rhs, 8)?;
+                *lhs = byte_op(*lhs, shift);
                 return Ok(());
             }
             (lhs, rhs) => (lhs.clone(), rhs),
         };
 
-        if !self.call_instance_fn(&lhs, hash, (&rhs,))? {
+        if !self.call_assign_fn(&lhs, hash, &rhs)? {
             return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
                 op,
                 lhs: lhs.type_info()?,
@@ -2430,6 +3500,43 @@ impl Vm {
         Ok(())
     }
 
+    /// Check that a shift amount is in range for a value with the given
+    /// number of bits.
+    fn shift_amount(op: &'static str, amount: i64, bits: u32) -> Result<u32, VmError> {
+        if amount < 0 || amount >= i64::from(bits) {
+            return Err(VmError::from(VmErrorKind::ShiftOutOfRange {
+                op,
+                amount,
+                bits,
+            }));
+        }
+
+        Ok(amount as u32)
+    }
+
+    /// Check that two byte strings have the same length for a bitwise
+    /// operation.
+    fn check_bytes_len(op: &'static str, lhs: &[u8], rhs: &[u8]) -> Result<(), VmError> {
+        if lhs.len() != rhs.len() {
+            return Err(VmError::from(VmErrorKind::BytesLengthMismatch {
+                op,
+                lhs: lhs.len(),
+                rhs: rhs.len(),
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// The error raised when an integer division or remainder fails.
+    fn division_error(_: i64, rhs: i64) -> VmError {
+        if rhs == 0 {
+            VmError::from(VmErrorKind::DivideByZero)
+        } else {
+            VmError::from(VmErrorKind::Overflow)
+        }
+    }
+
     /// Check that arguments matches expected or raise the appropriate error.
     fn check_args(args: usize, expected: usize) -> Result<(), VmError> {
         if args != args {
@@ -2456,6 +3563,21 @@ pub struct CallFrame {
     /// I.e. a function should not be able to manipulate the size of any other
     /// stack than its own.
     stack_bottom: usize,
+    /// How the value returned by the call is handled.
+    ret: Return,
+}
+
+/// How the value returned by a call frame is handled by its caller.
+#[derive(Debug, Clone, Copy)]
+enum Return {
+    /// Push the value onto the stack.
+    Push,
+    /// Discard the value, like for the assign protocols.
+    Discard,
+    /// Push the value as a boolean, like for the [EQ][crate::EQ] protocol.
+    Bool,
+    /// Push the negation of the value as a boolean.
+    NotBool,
 }
 
 impl CallFrame {
@@ -2469,3 +3591,17 @@ impl CallFrame {
         self.stack_bottom
     }
 }
+
+/// Resolve an integer index into a sequence with the given length.
+///
+/// Negative indexes are relative to the end of the sequence, so that `-1` is
+/// the last element. Non-negative indexes are not bounds checked.
+pub(crate) fn resolve_index(len: usize, index: i64) -> Option<usize> {
+    use std::convert::TryFrom as _;
+
+    if index >= 0 {
+        usize::try_from(index).ok()
+    } else {
+        len.checked_sub(index.unsigned_abs() as usize)
+    }
+}
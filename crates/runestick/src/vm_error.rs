@@ -57,6 +57,42 @@ impl VmError {
         &*self.kind
     }
 
+    /// Access the panic that caused this error, if the virtual machine
+    /// panicked.
+    ///
+    /// This is how hosts access the payload of a script panicking with a
+    /// value, like `panic(#{code: 404})`. See [Panic::payload].
+    pub fn as_panic(&self) -> Option<&Panic> {
+        match self.kind.into_unwound_ref() {
+            (VmErrorKind::Panic { reason }, _) => Some(reason),
+            _ => None,
+        }
+    }
+
+    /// Convert into the panic that caused this error, if the virtual machine
+    /// panicked.
+    ///
+    /// See [Panic::into_payload].
+    pub fn into_panic(self) -> Option<Panic> {
+        match *self.into_unwound().0.kind {
+            VmErrorKind::Panic { reason } => Some(reason),
+            _ => None,
+        }
+    }
+
+    /// Access the error raised when a shared value couldn't be accessed, if
+    /// that's what caused this error.
+    ///
+    /// This looks through errors raised when converting arguments, since
+    /// that's where native functions borrow their arguments.
+    pub fn as_access_error(&self) -> Option<&AccessError> {
+        match self.kind.into_unwound_ref() {
+            (VmErrorKind::AccessError { error }, _) => Some(error),
+            (VmErrorKind::BadArgument { error, .. }, _) => error.as_access_error(),
+            _ => None,
+        }
+    }
+
     /// Convert into an unwinded vm error.
     pub fn into_unwinded(self, unit: &Arc<Unit>, ip: usize) -> Self {
         if let VmErrorKind::Unwound { .. } = &*self.kind {
@@ -98,6 +134,7 @@ impl VmError {
         match &*self.kind {
             VmErrorKind::Panic { .. } => true,
             VmErrorKind::Unwound { .. } => true,
+            VmErrorKind::ModifiedDuringIteration { .. } => true,
             _ => false,
         }
     }
@@ -136,6 +173,41 @@ pub enum VmErrorKind {
         /// The reason for the panic.
         reason: Panic,
     },
+    /// A native function panicked while it was being called by the virtual
+    /// machine.
+    #[error("native function `{function}` panicked: {message}")]
+    NativePanic {
+        /// The name of the function which panicked.
+        function: String,
+        /// The message of the panic.
+        message: String,
+    },
+    /// The virtual machine was poisoned by a panic in a native function, and
+    /// must be cleared before it can be used again.
+    #[error("virtual machine is poisoned by a panic in a native function")]
+    Poisoned,
+    /// A replayed execution performed a native call which differs from the
+    /// next call in the recording.
+    #[error("replayed call of native function `{actual}` doesn't match the recorded call of `{expected}`")]
+    ReplayMismatch {
+        /// The hash of the function in the recording.
+        expected: Hash,
+        /// The hash of the function which was called.
+        actual: Hash,
+    },
+    /// A replayed execution called a native function with different arguments
+    /// than the recorded call.
+    #[error("replayed call of native function `{hash}` has different arguments than recorded")]
+    ReplayArgumentsMismatch {
+        /// The hash of the function which was called.
+        hash: Hash,
+    },
+    /// A replayed execution performed more native calls than were recorded.
+    #[error("replayed call of native function `{hash}` was not recorded")]
+    ReplayExhausted {
+        /// The hash of the function which was called.
+        hash: Hash,
+    },
     /// Raised when we try to access an empty execution.
     #[error("no running virtual machines")]
     NoRunningVm,
@@ -155,6 +227,12 @@ pub enum VmErrorKind {
         #[from]
         error: StackError,
     },
+    /// Calling a function would exceed the limit on the size of the stack.
+    #[error("stack overflow, the stack is limited to {limit} values")]
+    StackOverflow {
+        /// The maximum number of values on the stack.
+        limit: usize,
+    },
     /// The virtual machine encountered a numerical overflow.
     #[error("numerical overflow")]
     Overflow,
@@ -164,6 +242,35 @@ pub enum VmErrorKind {
     /// The virtual machine encountered a divide-by-zero.
     #[error("division by zero")]
     DivideByZero,
+    /// A shift amount was negative or not smaller than the number of bits in
+    /// the shifted value.
+    #[error("shift amount `{amount}` is out of range for `{op}` on a {bits}-bit value")]
+    ShiftOutOfRange {
+        /// The shift operation.
+        op: &'static str,
+        /// The shift amount.
+        amount: i64,
+        /// The number of bits in the shifted value.
+        bits: u32,
+    },
+    /// Tried to iterate over a range with bounds which are not integers.
+    #[error("range bounds `{start}` and `{end}` are not integers")]
+    UnsupportedRangeBounds {
+        /// The type of the start of the range.
+        start: TypeInfo,
+        /// The type of the end of the range.
+        end: TypeInfo,
+    },
+    /// A bitwise operation was performed on byte strings of different length.
+    #[error("bitwise `{op}` on byte strings of different length `{lhs}` and `{rhs}`")]
+    BytesLengthMismatch {
+        /// The bitwise operation.
+        op: &'static str,
+        /// The length of the left-hand side.
+        lhs: usize,
+        /// The length of the right-hand side.
+        rhs: usize,
+    },
     /// Failure to lookup function.
     #[error("missing function with hash `{hash}`")]
     MissingFunction {
@@ -213,18 +320,38 @@ pub enum VmErrorKind {
         /// The encountered argument.
         actual: TypeInfo,
     },
+    /// Indicates that the function table of the unit is missing the given
+    /// function.
+    #[error("function with id `{id}` does not exist")]
+    MissingFunctionId {
+        /// The id of the missing function.
+        id: usize,
+    },
     /// Indicates that a static string is missing for the given slot.
     #[error("static string slot `{slot}` does not exist")]
     MissingStaticString {
         /// Slot which is missing a static string.
         slot: usize,
     },
+    /// Indicates that a static value is missing for the given slot.
+    #[error("static value slot `{slot}` does not exist")]
+    MissingStaticValue {
+        /// Slot which is missing a static value.
+        slot: usize,
+    },
     /// Indicates that a static object keys is missing for the given slot.
     #[error("static object keys slot `{slot}` does not exist")]
     MissingStaticObjectKeys {
         /// Slot which is missing a static object keys.
         slot: usize,
     },
+    /// Indicates that a value of the given type was used as a key in a hash
+    /// map or a hash set, which is not supported.
+    #[error("type `{actual}` can't be used as a key")]
+    UnsupportedKey {
+        /// The type of the value used as a key.
+        actual: TypeInfo,
+    },
     /// Wrong number of arguments provided in call.
     #[error("wrong number of arguments `{actual}`, expected `{expected}`")]
     BadArgumentCount {
@@ -307,26 +434,53 @@ pub enum VmErrorKind {
         /// The type that is not supported.
         test_type: TypeInfo,
     },
+    /// A cast with `as` is not supported.
+    #[error("`{value} as {target}` is not supported")]
+    UnsupportedAs {
+        /// The type of the value being cast.
+        value: TypeInfo,
+        /// The type being cast to.
+        target: TypeInfo,
+    },
     /// Encountered a value that could not be called as a function
     #[error("`{actual_type}` cannot be called since it's not a function")]
     UnsupportedCallFn {
         /// The type that could not be called.
         actual_type: TypeInfo,
     },
-    /// Tried to fetch an index in an object that doesn't exist.
-    #[error("missing index by static string slot `{slot}` in object")]
-    ObjectIndexMissing {
-        /// The static string slot corresponding to the index that is missing.
-        slot: usize,
-    },
     /// Tried to access an index that was missing on a type.
-    #[error("missing index `{}` on `{target}`")]
+    #[error("missing index `{}` on `{target}`", display_index(.index))]
     MissingIndex {
         /// Type where field did not exist.
         target: TypeInfo,
         /// Index that we tried to access.
         index: Integer,
     },
+    /// Tried to access a slice which is out of bounds.
+    #[error("slice `{start}..{end}` is out of bounds for `{target}` of length {len}")]
+    SliceOutOfBounds {
+        /// Type which was sliced.
+        target: TypeInfo,
+        /// The start of the slice.
+        start: i64,
+        /// The end of the slice.
+        end: i64,
+        /// The length of the sliced value.
+        len: usize,
+    },
+    /// Tried to transfer a value which contains itself.
+    #[error("cannot transfer `{target}` since it contains itself")]
+    TransferCycle {
+        /// The type of the value which contains itself.
+        target: TypeInfo,
+    },
+    /// Tried to transfer a value which can't be detached from the virtual
+    /// machine it belongs to.
+    #[error("cannot transfer value of type `{actual}`")]
+    UnsupportedTransfer {
+        /// The type which could not be transferred.
+        actual: TypeInfo,
+    },
     /// Missing a struct field.
     #[error("missing field `{field}` on `{target}`")]
     MissingField {
@@ -408,6 +562,13 @@ pub enum VmErrorKind {
     /// Internal error that happens when we run out of items in a list.
     #[error("unexpectedly ran out of items to iterate over")]
     IterationError,
+    /// Error raised when a collection was modified by the function called on
+    /// each of its elements.
+    #[error("`{target}` was modified during iteration")]
+    ModifiedDuringIteration {
+        /// The type of the collection being iterated over.
+        target: TypeInfo,
+    },
 }
 
 impl VmErrorKind {
@@ -419,3 +580,22 @@ impl VmErrorKind {
         }
     }
 }
+
+/// Display an index without the type suffix that [Integer] is normally
+/// displayed with.
+fn display_index(index: &Integer) -> String {
+    match *index {
+        Integer::U8(n) => n.to_string(),
+        Integer::U16(n) => n.to_string(),
+        Integer::U32(n) => n.to_string(),
+        Integer::U64(n) => n.to_string(),
+        Integer::U128(n) => n.to_string(),
+        Integer::I8(n) => n.to_string(),
+        Integer::I16(n) => n.to_string(),
+        Integer::I32(n) => n.to_string(),
+        Integer::I64(n) => n.to_string(),
+        Integer::I128(n) => n.to_string(),
+        Integer::Isize(n) => n.to_string(),
+        Integer::Usize(n) => n.to_string(),
+    }
+}
@@ -1,14 +1,98 @@
-use crate::{GeneratorState, Value, Vm, VmError, VmErrorKind, VmHalt, VmHaltInfo};
+use crate::event_log::{Event, EventLog};
+use crate::recording::{Recording, Session};
+use crate::{
+    Awaited, GeneratorState, Overrides, Value, Vm, VmError, VmErrorKind, VmHalt, VmHaltInfo,
+};
 
 /// The execution environment for a virtual machine.
 pub struct VmExecution {
     vms: Vec<Vm>,
+    /// An await which is in progress.
+    ///
+    /// It's stored in the execution so that an async resume which is dropped
+    /// while waiting, like a stream losing a select, can be picked up again.
+    awaited: Option<Awaited>,
+    /// Hooks installed while the execution runs.
+    hooks: Hooks,
+}
+
+/// Hooks installed while an execution runs.
+#[derive(Default)]
+struct Hooks {
+    /// Log of the events of the execution, if enabled.
+    event_log: Option<EventLog>,
+    /// Native functions replaced for the execution, if any.
+    overrides: Option<Overrides>,
+    /// Recording or replay of native calls, if enabled.
+    session: Option<Session>,
 }
 
 impl VmExecution {
     /// Construct an execution from a virtual machine.
     pub(crate) fn new(vm: Vm) -> Self {
-        Self { vms: vec![vm] }
+        #[cfg(feature = "metrics")]
+        crate::metrics::execution_started(&vm);
+
+        Self {
+            vms: vec![vm],
+            awaited: None,
+            hooks: Hooks::default(),
+        }
+    }
+
+    /// Enable an event log for the execution, which keeps the last `capacity`
+    /// function calls and errors performed by it.
+    ///
+    /// The log can be retrieved through [event_log][Self::event_log] once the
+    /// execution has completed.
+    pub fn with_event_log(mut self, capacity: usize) -> Self {
+        self.hooks.event_log = Some(EventLog::new(capacity));
+        self
+    }
+
+    /// Access the event log of the execution, if enabled through
+    /// [with_event_log][Self::with_event_log].
+    pub fn event_log(&self) -> Option<&EventLog> {
+        self.hooks.event_log.as_ref()
+    }
+
+    /// Replace native functions with the given overrides for the duration
+    /// of the execution.
+    ///
+    /// This is intended for tests of scripts, to stub out native functions
+    /// which would otherwise access the network or the filesystem. See
+    /// [Overrides] for how overrides are matched.
+    pub fn with_overrides(mut self, overrides: Overrides) -> Self {
+        self.hooks.overrides = Some(overrides);
+        self
+    }
+
+    /// Record the native function calls performed by the execution.
+    ///
+    /// The recording can be retrieved through [recording][Self::recording],
+    /// and replayed through [with_replay][Self::with_replay].
+    pub fn with_recording(mut self) -> Self {
+        self.hooks.session = Some(Session::record());
+        self
+    }
+
+    /// Get the native function calls recorded so far, if enabled through
+    /// [with_recording][Self::with_recording].
+    pub fn recording(&self) -> Option<Recording> {
+        self.hooks.session.as_ref()?.recording()
+    }
+
+    /// Serve native function calls from the given recording instead of
+    /// calling the native functions.
+    ///
+    /// Calls are served in the order they were recorded. If the execution
+    /// calls a different function than the next one in the recording, or
+    /// performs more calls than were recorded, it errors with
+    /// [ReplayMismatch][VmErrorKind::ReplayMismatch] or
+    /// [ReplayExhausted][VmErrorKind::ReplayExhausted].
+    pub fn with_replay(mut self, recording: Recording) -> Self {
+        self.hooks.session = Some(Session::replay(recording));
+        self
     }
 
     /// Get the current virtual machine.
@@ -58,14 +142,17 @@ impl VmExecution {
 
     /// Resume the current execution with support for async instructions.
     pub async fn async_resume(&mut self) -> Result<GeneratorState, VmError> {
+        self.complete_awaited().await?;
+
         loop {
             let len = self.vms.len();
-            let vm = self.vm_mut()?;
+            let (vm, hooks) = self.vm_with_hooks()?;
 
-            match Self::run_for(vm, None)? {
+            match Self::run_for(vm, hooks, None)? {
                 VmHalt::Exited => (),
                 VmHalt::Awaited(awaited) => {
-                    awaited.into_vm(vm).await?;
+                    self.awaited = Some(awaited);
+                    self.complete_awaited().await?;
                     continue;
                 }
                 VmHalt::VmCall(vm_call) => {
@@ -97,9 +184,9 @@ impl VmExecution {
     pub fn resume(&mut self) -> Result<GeneratorState, VmError> {
         loop {
             let len = self.vms.len();
-            let vm = self.vm_mut()?;
+            let (vm, hooks) = self.vm_with_hooks()?;
 
-            match Self::run_for(vm, None)? {
+            match Self::run_for(vm, hooks, None)? {
                 VmHalt::Exited => (),
                 VmHalt::VmCall(vm_call) => {
                     vm_call.into_execution(self)?;
@@ -130,9 +217,9 @@ impl VmExecution {
     /// If any async instructions are encountered, this will error.
     pub fn step(&mut self) -> Result<Option<Value>, VmError> {
         let len = self.vms.len();
-        let vm = self.vm_mut()?;
+        let (vm, hooks) = self.vm_with_hooks()?;
 
-        match Self::run_for(vm, Some(1))? {
+        match Self::run_for(vm, hooks, Some(1))? {
             VmHalt::Exited => (),
             VmHalt::VmCall(vm_call) => {
                 vm_call.into_execution(self)?;
@@ -159,13 +246,19 @@ impl VmExecution {
     /// Step the single execution for one step with support for async
     /// instructions.
     pub async fn async_step(&mut self) -> Result<Option<Value>, VmError> {
+        if self.awaited.is_some() {
+            self.complete_awaited().await?;
+            return Ok(None);
+        }
+
         let len = self.vms.len();
-        let vm = self.vm_mut()?;
+        let (vm, hooks) = self.vm_with_hooks()?;
 
-        match Self::run_for(vm, Some(1))? {
+        match Self::run_for(vm, hooks, Some(1))? {
             VmHalt::Exited => (),
             VmHalt::Awaited(awaited) => {
-                awaited.into_vm(vm).await?;
+                self.awaited = Some(awaited);
+                self.complete_awaited().await?;
                 return Ok(None);
             }
             VmHalt::VmCall(vm_call) => {
@@ -190,6 +283,15 @@ impl VmExecution {
         Ok(None)
     }
 
+    /// Get the current virtual machine mutably, together with the hooks of
+    /// the execution.
+    fn vm_with_hooks(&mut self) -> Result<(&mut Vm, &Hooks), VmError> {
+        match self.vms.last_mut() {
+            Some(vm) => Ok((vm, &self.hooks)),
+            None => Err(VmError::from(VmErrorKind::NoRunningVm)),
+        }
+    }
+
     /// Push a virtual machine state onto the execution.
     pub(crate) fn push_vm(&mut self, vm: Vm) {
         self.vms.push(vm);
@@ -213,11 +315,76 @@ impl VmExecution {
         Ok(())
     }
 
+    /// Test if the execution was interrupted while waiting for an await,
+    /// which will be completed when it's resumed.
+    pub(crate) fn is_awaiting(&self) -> bool {
+        self.awaited.is_some()
+    }
+
+    /// Complete the stored await, if there is one.
+    async fn complete_awaited(&mut self) -> Result<(), VmError> {
+        let awaited = match &mut self.awaited {
+            Some(awaited) => awaited,
+            None => return Ok(()),
+        };
+
+        let vm = match self.vms.last_mut() {
+            Some(vm) => vm,
+            None => return Err(VmError::from(VmErrorKind::NoRunningVm)),
+        };
+
+        let result = Self::await_into_vm(awaited, vm).await;
+        self.awaited = None;
+        result
+    }
+
+    /// Wait for the given awaited into the specified virtual machine.
+    async fn await_into_vm(awaited: &mut Awaited, vm: &mut Vm) -> Result<(), VmError> {
+        #[cfg(feature = "tracing")]
+        {
+            use tracing::Instrument as _;
+            let span = vm.await_span();
+            awaited.wait_into_vm(vm).instrument(span).await
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        awaited.wait_into_vm(vm).await
+    }
+
     #[inline]
-    fn run_for(vm: &mut Vm, limit: Option<usize>) -> Result<VmHalt, VmError> {
-        match vm.run_for(limit) {
+    fn run_for(vm: &mut Vm, hooks: &Hooks, limit: Option<usize>) -> Result<VmHalt, VmError> {
+        #[cfg(feature = "tracing")]
+        vm.enter_spans();
+
+        let result = crate::recording::with_session(hooks.session.as_ref(), || {
+            crate::overrides::with_overrides(hooks.overrides.as_ref(), || {
+                crate::event_log::with_event_log(hooks.event_log.as_ref(), || vm.run_for(limit))
+            })
+        });
+
+        #[cfg(feature = "tracing")]
+        vm.exit_spans();
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_run(vm, &result);
+
+        match result {
             Ok(reason) => Ok(reason),
-            Err(error) => Err(error.into_unwinded(vm.unit(), vm.ip())),
+            Err(error) => {
+                if let (VmErrorKind::NativePanic { .. }, _) = error.kind().into_unwound_ref() {
+                    vm.poison();
+                }
+
+                let error = error.into_unwinded(vm.unit(), vm.ip());
+
+                if let Some(event_log) = &hooks.event_log {
+                    event_log.push(Event::Error {
+                        message: error.to_string(),
+                    });
+                }
+
+                Err(error)
+            }
         }
     }
 }